@@ -2,31 +2,55 @@
 
 use anyhow::Result;
 use colored::Colorize;
+use serde::Serialize;
 
-use crate::config::{load_keys, format_meta_address};
+use crate::config::{load_keys, format_meta_address, StoredKeys};
 
-pub fn run() -> Result<()> {
-    let stored_keys = load_keys()?;
+/// `address --json` output; public keys only
+#[derive(Serialize, Debug)]
+pub struct AddressOutput {
+    pub meta_address: String,
+    /// Scan public key (hex)
+    pub scan_pubkey: String,
+    /// Spend public key (hex)
+    pub spend_pubkey: String,
+}
+
+impl AddressOutput {
+    pub fn from_stored(stored_keys: &StoredKeys) -> Result<Self> {
+        let scan_pubkey = hex::decode(&stored_keys.scan_pubkey)?;
+        let spend_pubkey = hex::decode(&stored_keys.spend_pubkey)?;
+
+        let mut scan_pubkey_arr = [0u8; 32];
+        let mut spend_pubkey_arr = [0u8; 32];
+        scan_pubkey_arr.copy_from_slice(&scan_pubkey);
+        spend_pubkey_arr.copy_from_slice(&spend_pubkey);
+
+        Ok(Self {
+            meta_address: format_meta_address(&scan_pubkey_arr, &spend_pubkey_arr),
+            scan_pubkey: hex::encode(scan_pubkey_arr),
+            spend_pubkey: hex::encode(spend_pubkey_arr),
+        })
+    }
+}
 
-    let scan_pubkey = hex::decode(&stored_keys.scan_pubkey)?;
-    let spend_pubkey = hex::decode(&stored_keys.spend_pubkey)?;
+pub fn run(json: bool) -> Result<()> {
+    let stored_keys = load_keys()?;
+    let address = AddressOutput::from_stored(&stored_keys)?;
 
-    let mut scan_pubkey_arr = [0u8; 32];
-    let mut spend_pubkey_arr = [0u8; 32];
-    scan_pubkey_arr.copy_from_slice(&scan_pubkey);
-    spend_pubkey_arr.copy_from_slice(&spend_pubkey);
+    if json {
+        println!("{}", serde_json::to_string_pretty(&address)?);
+        return Ok(());
+    }
 
     println!();
     println!("{}", "Your Stealth Meta-Address".yellow().bold());
     println!();
-    println!(
-        "{}",
-        format_meta_address(&scan_pubkey_arr, &spend_pubkey_arr)
-    );
+    println!("{}", address.meta_address);
     println!();
     println!("{}:", "Components".dimmed());
-    println!("  Scan pubkey:  {}", hex::encode(&scan_pubkey_arr));
-    println!("  Spend pubkey: {}", hex::encode(&spend_pubkey_arr));
+    println!("  Scan pubkey:  {}", address.scan_pubkey);
+    println!("  Spend pubkey: {}", address.spend_pubkey);
     println!();
     println!(
         "{}",
@@ -1,35 +1,34 @@
-//! Show total balance across all stealth addresses
+//! Show total balance across all stealth addresses and pool notes
+//!
+//! "Received" counts everything ever sent to us; "available" leaves out
+//! stealth addresses that were emptied and pool notes whose nullifier is
+//! already on-chain.
 
 use anyhow::Result;
 use colored::Colorize;
+use serde::Serialize;
 use solana_client::rpc_client::RpcClient;
-use solana_sdk::{
-    commitment_config::CommitmentConfig,
-    pubkey::Pubkey,
-};
-use borsh::BorshDeserialize;
-
-use crate::config::load_keys;
-use crate::crypto::{StealthKeys, scan_payment};
-
-// Program ID (update after deployment)
-const PROGRAM_ID: &str = "6CiqeSFEmghXeS4pnhDpR4j5VieDi81jDhfzaanaqpv8";
-
-/// On-chain announcement structure
-#[derive(BorshDeserialize, Debug)]
-#[allow(dead_code)]
-struct Announcement {
-    pub ephemeral_pubkey: [u8; 32],
-    pub stealth_address: Pubkey,
-    pub amount: u64,
-    pub token_mint: Pubkey,
-    pub slot: u64,
-    pub timestamp: i64,
-    pub bump: u8,
+use solana_sdk::commitment_config::CommitmentConfig;
+
+use crate::commands::scan::{fetch_announcements, fetch_balances, fetch_notes};
+use crate::config::{format_sol, load_keys};
+use crate::crypto::{StealthKeys, scan_batch};
+use crate::pool::{fetch_spent_nullifiers, note_nullifier_pda};
+
+/// `balance --json` output; amounts in lamports
+#[derive(Serialize, Debug, PartialEq)]
+pub struct BalanceOutput {
+    pub available: u64,
+    pub total_received: u64,
+    pub addresses_with_balance: usize,
+    pub unspent_notes: usize,
+    pub total_notes: usize,
 }
 
-pub async fn run(rpc_url: &str) -> Result<()> {
-    println!("{}", "Calculating total stealth balance...".cyan());
+pub async fn run(rpc_url: &str, commitment_config: CommitmentConfig, json: bool) -> Result<()> {
+    if !json {
+        println!("{}", "Calculating total stealth balance...".cyan());
+    }
 
     // Load stealth keys
     let stored_keys = load_keys()?;
@@ -45,50 +44,82 @@ pub async fn run(rpc_url: &str) -> Result<()> {
     let keys = StealthKeys::from_secrets(&scan_secret_arr, &spend_secret_arr);
 
     // Connect to RPC
-    let client = RpcClient::new_with_commitment(rpc_url, CommitmentConfig::confirmed());
-
-    let program_id: Pubkey = PROGRAM_ID.parse()?;
-    let accounts = client.get_program_accounts(&program_id)?;
-
-    let mut total_balance: u64 = 0;
+    let client = RpcClient::new_with_commitment(rpc_url, commitment_config);
+
+    // Direct stealth payments
+    let announcements = fetch_announcements(&client, None)?;
+    let candidates: Vec<([u8; 32], [u8; 32])> = announcements
+        .iter()
+        .map(|a| (a.ephemeral_pubkey, a.stealth_address.to_bytes()))
+        .collect();
+    let mut results = scan_batch(&keys, &candidates).into_iter().peekable();
+
+    let mut address_received: u64 = 0;
+    let mut address_available: u64 = 0;
     let mut address_count = 0;
+    let mut owned_addresses = Vec::new();
 
-    for (_pubkey, account) in accounts {
-        if account.data.len() < 8 + 32 + 32 + 8 + 32 + 8 + 8 + 1 {
+    for announcement in &announcements {
+        let payment_address_bytes = announcement.stealth_address.to_bytes();
+        if results.next_if(|r| r.stealth_address == payment_address_bytes).is_none() {
             continue;
         }
 
-        if let Ok(announcement) = Announcement::try_from_slice(&account.data[8..]) {
-            let payment_address_bytes = announcement.stealth_address.to_bytes();
+        address_received += announcement.amount;
+        owned_addresses.push(announcement.stealth_address);
+    }
 
-            if scan_payment(&keys, &announcement.ephemeral_pubkey, &payment_address_bytes).is_some()
-            {
-                let balance = client
-                    .get_balance(&announcement.stealth_address)
-                    .unwrap_or(0);
+    for balance in fetch_balances(&client, &owned_addresses)? {
+        if balance > 0 {
+            address_available += balance;
+            address_count += 1;
+        }
+    }
 
-                if balance > 0 {
-                    total_balance += balance;
-                    address_count += 1;
-                }
-            }
+    // Pool notes: spent once their nullifier record exists
+    let notes = fetch_notes(&client, &keys)?;
+    let nullifier_pdas: Vec<_> = notes.iter().map(|(_, _, note)| note_nullifier_pda(note)).collect();
+    let spent = fetch_spent_nullifiers(&client, &nullifier_pdas)?;
+
+    let mut note_received: u64 = 0;
+    let mut note_available: u64 = 0;
+    let mut unspent_count = 0;
+
+    for ((_, _, note), is_spent) in notes.iter().zip(&spent) {
+        note_received += note.amount;
+        if !is_spent {
+            note_available += note.amount;
+            unspent_count += 1;
         }
     }
 
+    let summary = BalanceOutput {
+        available: address_available + note_available,
+        total_received: address_received + note_received,
+        addresses_with_balance: address_count,
+        unspent_notes: unspent_count,
+        total_notes: notes.len(),
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&summary)?);
+        return Ok(());
+    }
+
     println!();
     println!("{}", "Stealth Balance Summary".yellow().bold());
     println!();
-    println!(
-        "Total:     {} SOL",
-        format!("{:.9}", total_balance as f64 / 1_000_000_000.0).green()
-    );
-    println!("Addresses: {} with balance", address_count);
+    println!("Available:      {}", format_sol(summary.available).green());
+    println!("Total received: {}", format_sol(summary.total_received));
+    println!();
+    println!("Addresses:      {} with balance", summary.addresses_with_balance);
+    println!("Pool notes:     {} unspent of {}", summary.unspent_notes, summary.total_notes);
     println!();
 
-    if address_count > 0 {
+    if address_count > 0 || unspent_count > 0 {
         println!(
             "{}",
-            "Use 'stealth scan' to see individual addresses.".dimmed()
+            "Use 'stealthsol scan' to see individual addresses and notes.".dimmed()
         );
     }
 
@@ -0,0 +1,80 @@
+//! Compute a one-time stealth address offline
+//!
+//! Runs the sender side of DKSAP for a meta-address and prints the result
+//! without loading a keypair or touching the network, e.g. for a merchant
+//! generating a fresh address per invoice. With `--seed` the derivation is
+//! deterministic, so the same seed always yields the same address.
+
+use anyhow::{Result, Context};
+use colored::Colorize;
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::config::{decode_hex_32, parse_meta_address_with};
+use crate::crypto::{compute_commitment, try_compute_stealth_address, try_compute_stealth_address_deterministic};
+
+/// `derive-address` result; `--json` prints it as is
+#[derive(Serialize, Debug)]
+pub struct DeriveAddressOutput {
+    /// One-time stealth address (base58)
+    pub stealth_address: String,
+    /// Ephemeral public key to publish in the announcement (hex)
+    pub ephemeral_pubkey: String,
+    /// Announcement commitment binding the two to the meta-address (hex)
+    pub commitment: String,
+}
+
+/// Derive a stealth address for `recipient`, deterministically if `seed_hex` is given
+pub fn derive(recipient: &str, seed_hex: Option<&str>, legacy: bool) -> Result<DeriveAddressOutput> {
+    let (scan_pubkey, spend_pubkey) = parse_meta_address_with(recipient, legacy)
+        .context("Invalid meta-address format")?;
+
+    let computation = match seed_hex {
+        Some(seed_hex) => {
+            let seed = decode_hex_32(seed_hex).context("Seed must be 32 bytes of hex")?;
+            try_compute_stealth_address_deterministic(&scan_pubkey, &spend_pubkey, &seed)
+        }
+        None => try_compute_stealth_address(&scan_pubkey, &spend_pubkey),
+    }
+    .context("Failed to compute stealth address")?;
+
+    let commitment = compute_commitment(
+        &computation.ephemeral_pubkey,
+        &scan_pubkey,
+        &spend_pubkey,
+        &computation.stealth_pubkey,
+    );
+
+    Ok(DeriveAddressOutput {
+        stealth_address: Pubkey::new_from_array(computation.stealth_pubkey).to_string(),
+        ephemeral_pubkey: hex::encode(computation.ephemeral_pubkey),
+        commitment: hex::encode(commitment),
+    })
+}
+
+pub fn run(recipient: &str, seed: Option<&str>, legacy: bool, json: bool) -> Result<()> {
+    let output = derive(recipient, seed, legacy)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    println!();
+    println!("Stealth address: {}", output.stealth_address);
+    println!("Ephemeral key:   {}", output.ephemeral_pubkey);
+    println!("Commitment:      {}", output.commitment);
+    println!();
+    if seed.is_none() {
+        println!(
+            "{}",
+            "The ephemeral key is random; pass --seed to derive the same address again.".dimmed()
+        );
+    }
+    println!(
+        "{}",
+        "Publish the ephemeral key with the payment, or the recipient can't find it.".dimmed()
+    );
+
+    Ok(())
+}
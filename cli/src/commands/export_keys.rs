@@ -0,0 +1,59 @@
+//! Export the full key set as an encrypted, portable backup
+//!
+//! Unlike `export-view-key`, the backup holds the spend secret too, so it
+//! restores full control on another machine via `import-keys`.
+
+use std::path::Path;
+
+use anyhow::{Result, Context, bail};
+use colored::Colorize;
+use zeroize::Zeroize;
+
+use crate::secure_storage::{KeyBackup, SecureKeyStorage, prompt_new_password, prompt_password, validate_password_strength};
+
+pub fn run(out: &str, password: Option<String>) -> Result<()> {
+    let storage = SecureKeyStorage::new(SecureKeyStorage::default_path());
+
+    if !storage.exists() {
+        bail!("No stealth keys found. Run 'stealthsol keygen' first.");
+    }
+    let out = Path::new(out);
+    if out.exists() {
+        bail!("{:?} already exists; choose another path or remove it first", out);
+    }
+
+    let mut store_password = prompt_password("Enter password to decrypt keys: ")?;
+    let key_data = storage.load(&store_password)
+        .context("Failed to decrypt keys. Wrong password?");
+    store_password.zeroize();
+    let key_data = key_data?;
+
+    let mut backup_password = match password {
+        Some(password) => {
+            validate_password_strength(&password)?;
+            password
+        }
+        None => prompt_new_password("Enter a password for the backup: ")?,
+    };
+    let backup = KeyBackup::seal(&key_data, &backup_password);
+    backup_password.zeroize();
+    drop(key_data);
+
+    backup?.write(out)?;
+
+    println!();
+    println!("{}", "Key backup written".green().bold());
+    println!();
+    println!("File: {}", out.display());
+    println!(
+        "{}",
+        format!("Restore with: stealthsol import-keys --in {}", out.display()).dimmed()
+    );
+    println!();
+    println!(
+        "{}",
+        "WARNING: This backup holds your spend key. Anyone with it and its password can spend your funds!".red()
+    );
+
+    Ok(())
+}
@@ -1,12 +1,33 @@
 //! Export view key (scan-only capability)
 
-use anyhow::Result;
+use std::path::Path;
+
+use anyhow::{Result, Context};
 use colored::Colorize;
 
-use crate::config::load_keys;
+use crate::config::{save_view_key, ViewKeyFile};
+use crate::secure_storage::{SecureKeyStorage, prompt_password};
+
+pub fn run(output: Option<&str>) -> Result<()> {
+    let storage = SecureKeyStorage::new(SecureKeyStorage::default_path());
+
+    if !storage.exists() {
+        anyhow::bail!(
+            "No stealth keys found. Run 'stealthsol keygen' first."
+        );
+    }
 
-pub fn run() -> Result<()> {
-    let stored_keys = load_keys()?;
+    let password = prompt_password("Enter password to decrypt keys: ")?;
+    let key_data = storage.load(&password)
+        .context("Failed to decrypt keys. Wrong password?")?;
+
+    // View key = scan secret + spend public key
+    // This allows detecting payments but not spending
+    let view_key = ViewKeyFile {
+        scan_secret: hex::encode(key_data.scan_secret),
+        spend_pubkey: hex::encode(key_data.spend_pubkey),
+        created_at: chrono::Utc::now().to_rfc3339(),
+    };
 
     println!();
     println!("{}", "View Key Export".yellow().bold());
@@ -20,13 +41,20 @@ pub fn run() -> Result<()> {
         "Share this with accountants/auditors who need to see your transactions.".dimmed()
     );
     println!();
-    println!("{}:", "View Key (scan secret + spend pubkey)".yellow());
-    println!();
 
-    // View key = scan secret + spend public key
-    // This allows detecting payments but not spending
-    let view_key = format!("{}:{}", stored_keys.scan_secret, stored_keys.spend_pubkey);
-    println!("  {}", view_key);
+    if let Some(path) = output {
+        save_view_key(Path::new(path), &view_key)?;
+        println!("{}: {}", "View key written to".yellow(), path);
+        println!();
+        println!(
+            "{}",
+            format!("Scan with: stealthsol scan-view --view-key {}", path).dimmed()
+        );
+    } else {
+        println!("{}:", "View Key (scan secret + spend pubkey)".yellow());
+        println!();
+        println!("  {}:{}", view_key.scan_secret, view_key.spend_pubkey);
+    }
     println!();
 
     println!(
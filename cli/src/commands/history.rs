@@ -0,0 +1,62 @@
+//! List past sends, withdrawals and recovered payments from the local history file
+//!
+//! Only operations made from this machine (or recovered by `rescan`) appear
+//! here; nothing is fetched from the network.
+
+use anyhow::Result;
+use chrono::{TimeZone, Utc};
+use colored::Colorize;
+
+use crate::config::format_sol;
+use crate::history::{filter_entries, history_file, load_entries_from, parse_since, HistoryEvent};
+
+pub fn run(sent: bool, withdrawn: bool, received: bool, since: Option<&str>) -> Result<()> {
+    let since = since.map(parse_since).transpose()?;
+    let entries = filter_entries(load_entries_from(&history_file())?, sent, withdrawn, received, since);
+
+    if entries.is_empty() {
+        println!("{}", "No matching history entries.".yellow());
+        return Ok(());
+    }
+
+    println!();
+    println!("{}", "Transaction history".yellow().bold());
+
+    for entry in &entries {
+        let when = Utc
+            .timestamp_opt(entry.timestamp, 0)
+            .single()
+            .map(|t| t.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+            .unwrap_or_else(|| entry.timestamp.to_string());
+
+        println!();
+        match &entry.event {
+            HistoryEvent::Sent { recipient_hash, amount, ephemeral_pubkey, stealth_address, signature } => {
+                println!("{}  {}  {}", when.dimmed(), "SENT".red().bold(), format_sol(*amount));
+                println!("  Recipient:     {}", recipient_hash);
+                println!("  Stealth addr:  {}", stealth_address);
+                println!("  Ephemeral key: {}", ephemeral_pubkey);
+                println!("  Transaction:   {}", signature);
+            }
+            HistoryEvent::Withdrawn { from, to, amount, signature } => {
+                println!("{}  {}  {}", when.dimmed(), "WITHDRAWN".green().bold(), format_sol(*amount));
+                println!("  From:          {}", from);
+                println!("  To:            {}", to);
+                println!("  Transaction:   {}", signature);
+            }
+            HistoryEvent::Received { address, amount, pool_note } => {
+                println!("{}  {}  {}", when.dimmed(), "RECEIVED".cyan().bold(), format_sol(*amount));
+                if *pool_note {
+                    println!("  Pool note:     {}", address);
+                } else {
+                    println!("  Stealth addr:  {}", address);
+                }
+            }
+        }
+    }
+
+    println!();
+    println!("{}", format!("{} entr{}", entries.len(), if entries.len() == 1 { "y" } else { "ies" }).dimmed());
+
+    Ok(())
+}
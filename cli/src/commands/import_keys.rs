@@ -0,0 +1,61 @@
+//! Import a full key set from a backup written by `export-keys`
+
+use std::path::Path;
+
+use anyhow::{Result, bail};
+use colored::Colorize;
+use zeroize::Zeroize;
+
+use crate::config::format_meta_address;
+use crate::crypto::StealthKeys;
+use crate::secure_storage::{KeyBackup, SecureKeyStorage, prompt_password};
+
+pub fn run(input: &str, password: Option<String>, force: bool) -> Result<()> {
+    let storage = SecureKeyStorage::new(SecureKeyStorage::default_path());
+
+    if storage.exists() && !force {
+        bail!(
+            "Stealth keys already exist. Use --force to overwrite.\n\
+             Warning: Overwriting keys will make any existing stealth payments unrecoverable!"
+        );
+    }
+
+    let backup = KeyBackup::read(Path::new(input))?;
+
+    let mut password = match password {
+        Some(password) => password,
+        None => prompt_password("Enter backup password: ")?,
+    };
+
+    // The local store is encrypted with the backup's password
+    let result = backup.open(&password).and_then(|key_data| {
+        // Catch a backup whose public keys don't belong to its secrets
+        let keys = StealthKeys::from_secrets(&key_data.scan_secret, &key_data.spend_secret);
+        let meta_address = keys.meta_address();
+        if meta_address != (key_data.scan_pubkey, key_data.spend_pubkey) {
+            bail!("Backup is corrupted: its public keys don't match its secrets");
+        }
+
+        storage.save(&key_data, &password)?;
+        Ok(meta_address)
+    });
+    password.zeroize();
+    let (scan_pubkey, spend_pubkey) = result?;
+
+    println!();
+    println!("{}", "Keys imported".green().bold());
+    println!();
+    println!("{}:", "Meta-Address".yellow());
+    println!("  {}", format_meta_address(&scan_pubkey, &spend_pubkey));
+    println!();
+    println!(
+        "{}",
+        format!("Encrypted keys saved to: {:?}", SecureKeyStorage::default_path()).dimmed()
+    );
+    println!(
+        "{}",
+        "The keys use the backup's password; change it with 'stealthsol rotate-password'.".dimmed()
+    );
+
+    Ok(())
+}
@@ -2,24 +2,52 @@
 
 use anyhow::Result;
 use colored::Colorize;
+use serde::Serialize;
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::{commitment_config::CommitmentConfig, signer::Signer};
 
 use crate::config::{load_keys, keys_file, stealth_dir, load_solana_keypair, format_meta_address};
 
-pub fn run(rpc_url: &str) -> Result<()> {
-    println!();
-    println!("{}", "StealthSol Configuration".yellow().bold());
-    println!();
+const PROGRAM_ID: &str = "StLthNnJdCYvVPDV8bwvJmMHhxjbj17PHT6gUiDCfYU";
 
-    // Keys info
-    println!("{}:", "Keys Directory".cyan());
-    println!("  {}", stealth_dir().display());
-    println!();
+/// `info --json` output; public data only, never secret keys
+#[derive(Serialize, Debug)]
+pub struct InfoOutput {
+    pub keys_dir: String,
+    pub keys_configured: bool,
+    pub keys_created_at: Option<String>,
+    pub meta_address: Option<String>,
+    pub wallet: Option<String>,
+    /// Wallet balance in lamports (absent if the RPC call failed)
+    pub wallet_balance: Option<u64>,
+    pub rpc_url: String,
+    /// Commitment level used for reads and confirmations
+    pub commitment: String,
+    pub program_id: String,
+    pub keys_file: String,
+    pub wallet_file: String,
+}
 
-    if let Ok(stored_keys) = load_keys() {
-        println!("{}", "Stealth Keys: CONFIGURED".green());
+pub fn run(rpc_url: &str, commitment_config: CommitmentConfig, json: bool) -> Result<()> {
+    let mut info = InfoOutput {
+        keys_dir: stealth_dir().display().to_string(),
+        keys_configured: false,
+        keys_created_at: None,
+        meta_address: None,
+        wallet: None,
+        wallet_balance: None,
+        rpc_url: rpc_url.to_string(),
+        commitment: commitment_config.commitment.to_string(),
+        program_id: PROGRAM_ID.to_string(),
+        keys_file: keys_file().display().to_string(),
+        wallet_file: dirs::home_dir()
+            .unwrap()
+            .join(".config/solana/id.json")
+            .display()
+            .to_string(),
+    };
 
+    if let Ok(stored_keys) = load_keys() {
         let scan_pubkey = hex::decode(&stored_keys.scan_pubkey)?;
         let spend_pubkey = hex::decode(&stored_keys.spend_pubkey)?;
 
@@ -28,25 +56,51 @@ pub fn run(rpc_url: &str) -> Result<()> {
         scan_pubkey_arr.copy_from_slice(&scan_pubkey);
         spend_pubkey_arr.copy_from_slice(&spend_pubkey);
 
-        println!("  Created: {}", stored_keys.created_at);
-        println!(
-            "  Meta-address: {}",
-            format_meta_address(&scan_pubkey_arr, &spend_pubkey_arr)
-        );
-    } else {
-        println!("{}", "Stealth Keys: NOT CONFIGURED".red());
-        println!("  Run 'stealth keygen' to generate keys");
+        info.keys_configured = true;
+        info.keys_created_at = Some(stored_keys.created_at.clone());
+        info.meta_address = Some(format_meta_address(&scan_pubkey_arr, &spend_pubkey_arr));
     }
-    println!();
 
-    // Solana keypair
-    println!("{}:", "Solana Wallet".cyan());
     if let Ok(keypair) = load_solana_keypair(None) {
-        println!("  Address: {}", keypair.pubkey());
+        info.wallet = Some(keypair.pubkey().to_string());
 
         // Try to get balance
-        let client = RpcClient::new_with_commitment(rpc_url, CommitmentConfig::confirmed());
-        if let Ok(balance) = client.get_balance(&keypair.pubkey()) {
+        let client = RpcClient::new_with_commitment(rpc_url, commitment_config);
+        info.wallet_balance = client.get_balance(&keypair.pubkey()).ok();
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&info)?);
+        return Ok(());
+    }
+
+    println!();
+    println!("{}", "StealthSol Configuration".yellow().bold());
+    println!();
+
+    // Keys info
+    println!("{}:", "Keys Directory".cyan());
+    println!("  {}", info.keys_dir);
+    println!();
+
+    match (&info.keys_created_at, &info.meta_address) {
+        (Some(created_at), Some(meta_address)) => {
+            println!("{}", "Stealth Keys: CONFIGURED".green());
+            println!("  Created: {}", created_at);
+            println!("  Meta-address: {}", meta_address);
+        }
+        _ => {
+            println!("{}", "Stealth Keys: NOT CONFIGURED".red());
+            println!("  Run 'stealth keygen' to generate keys");
+        }
+    }
+    println!();
+
+    // Solana keypair
+    println!("{}:", "Solana Wallet".cyan());
+    if let Some(wallet) = &info.wallet {
+        println!("  Address: {}", wallet);
+        if let Some(balance) = info.wallet_balance {
             println!("  Balance: {} SOL", balance as f64 / 1_000_000_000.0);
         }
     } else {
@@ -57,25 +111,20 @@ pub fn run(rpc_url: &str) -> Result<()> {
 
     // RPC info
     println!("{}:", "RPC Endpoint".cyan());
-    println!("  {}", rpc_url);
+    println!("  {}", info.rpc_url);
+    println!("  Commitment: {}", info.commitment);
     println!();
 
     // Program ID
     println!("{}:", "Program ID".cyan());
-    println!("  StLthNnJdCYvVPDV8bwvJmMHhxjbj17PHT6gUiDCfYU");
+    println!("  {}", info.program_id);
     println!("  {}", "(Update after deployment)".dimmed());
     println!();
 
     // File locations
     println!("{}:", "File Locations".cyan());
-    println!("  Keys:   {}", keys_file().display());
-    println!(
-        "  Wallet: {}",
-        dirs::home_dir()
-            .unwrap()
-            .join(".config/solana/id.json")
-            .display()
-    );
+    println!("  Keys:   {}", info.keys_file);
+    println!("  Wallet: {}", info.wallet_file);
 
     Ok(())
 }
@@ -13,6 +13,8 @@ pub struct KeygenOptions {
     pub force: bool,
     /// Use mnemonic for key generation (allows recovery)
     pub with_mnemonic: bool,
+    /// Number of words in a generated mnemonic (12/15/18/21/24)
+    pub words: usize,
     /// Import from existing mnemonic
     pub import_mnemonic: Option<String>,
     /// Passphrase for mnemonic (optional extra security)
@@ -42,7 +44,7 @@ pub fn run(options: KeygenOptions) -> Result<()> {
     } else if options.with_mnemonic {
         // Generate new mnemonic
         println!("{}", "Generating keys with recovery phrase...".cyan());
-        let (keys, phrase) = StealthKeys::generate_with_mnemonic()?;
+        let (keys, phrase) = StealthKeys::generate_with_mnemonic_words(options.words)?;
         (keys, Some(phrase))
     } else {
         // Generate random keys (no recovery possible)
@@ -145,6 +147,7 @@ pub fn run_simple(force: bool) -> Result<()> {
     run(KeygenOptions {
         force,
         with_mnemonic: true, // Default to mnemonic for safety
+        words: 24,
         import_mnemonic: None,
         passphrase: None,
     })
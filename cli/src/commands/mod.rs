@@ -6,4 +6,16 @@ pub mod scan;
 pub mod withdraw;
 pub mod balance;
 pub mod export_view_key;
+pub mod export_keys;
+pub mod import_keys;
+pub mod rotate_password;
+pub mod sweep;
 pub mod info;
+pub mod pool_stats;
+pub mod pool_roots;
+pub mod history;
+pub mod verify_note;
+pub mod rescan;
+pub mod relayers;
+pub mod derive_address;
+pub mod relabel;
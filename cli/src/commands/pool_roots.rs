@@ -0,0 +1,49 @@
+//! List the Merkle roots a privacy pool currently accepts
+//!
+//! Proofs must reference one of these roots. Each deposit pushes the oldest
+//! out of the history, so wallets proving offline should pick a recent one.
+
+use anyhow::Result;
+use colored::Colorize;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use stealth::state::ROOT_HISTORY_SIZE;
+
+use crate::commands::pool_stats::parse_denomination;
+use crate::config::format_sol;
+use crate::pool::fetch_pool;
+
+pub fn run(rpc_url: &str, commitment_config: CommitmentConfig, denomination_sol: &str) -> Result<()> {
+    let denomination = parse_denomination(denomination_sol)?;
+
+    let client = RpcClient::new_with_commitment(rpc_url, commitment_config);
+    let pool = fetch_pool(&client, denomination)?;
+    let roots = pool.valid_roots();
+
+    println!();
+    println!(
+        "{}",
+        format!("Valid roots: {} pool", format_sol(denomination))
+            .yellow()
+            .bold()
+    );
+    println!();
+
+    for (i, root) in roots.iter().enumerate() {
+        let label = if i == 0 { "current".green() } else { format!("-{}", i).dimmed() };
+        println!("  {:>8}  {}", label, hex::encode(root));
+    }
+
+    println!();
+    println!(
+        "{}",
+        format!(
+            "{} root(s); the pool keeps the current root plus the last {}. Older entries rotate out first.",
+            roots.len(),
+            ROOT_HISTORY_SIZE
+        )
+        .dimmed()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,120 @@
+//! Show privacy pool statistics (anonymity set size)
+
+use anyhow::{Result, bail};
+use colored::Colorize;
+use serde::Serialize;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use stealth::state::{PrivacyPool, DEFAULT_DENOMINATIONS};
+
+use crate::config::{format_sol, parse_sol};
+use crate::pool::{fetch_pool, pool_pda};
+
+/// `pool-stats --json` output; amounts in lamports
+#[derive(Serialize, Debug)]
+pub struct PoolStatsOutput {
+    pub denomination: u64,
+    pub address: String,
+    pub is_active: bool,
+    pub deposit_count: u64,
+    pub withdrawal_count: u64,
+    pub total_deposited: u64,
+    pub total_withdrawn: u64,
+    pub next_leaf_index: u64,
+    /// Current Merkle root (hex)
+    pub merkle_root: String,
+    /// Every deposit in the tree
+    pub anonymity_set: u64,
+    /// Deposits not yet withdrawn
+    pub unspent_notes: u64,
+    /// `CommitmentScheme` id deposits must use
+    pub commitment_scheme: u8,
+}
+
+impl PoolStatsOutput {
+    pub fn from_pool(denomination: u64, pool: &PrivacyPool) -> Self {
+        Self {
+            denomination,
+            address: pool_pda(denomination).to_string(),
+            is_active: pool.is_active,
+            deposit_count: pool.deposit_count,
+            withdrawal_count: pool.withdrawal_count,
+            total_deposited: pool.total_deposited,
+            total_withdrawn: pool.total_withdrawn,
+            next_leaf_index: pool.next_leaf_index,
+            merkle_root: hex::encode(pool.merkle_root),
+            anonymity_set: pool.next_leaf_index,
+            unspent_notes: pool.deposit_count.saturating_sub(pool.withdrawal_count),
+            commitment_scheme: pool.commitment_scheme,
+        }
+    }
+}
+
+pub fn run(rpc_url: &str, commitment_config: CommitmentConfig, denomination_sol: &str, json: bool) -> Result<()> {
+    let denomination = parse_denomination(denomination_sol)?;
+
+    let client = RpcClient::new_with_commitment(rpc_url, commitment_config);
+    let pool = fetch_pool(&client, denomination)?;
+
+    if json {
+        let stats = PoolStatsOutput::from_pool(denomination, &pool);
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+        return Ok(());
+    }
+
+    let active_notes = pool.deposit_count.saturating_sub(pool.withdrawal_count);
+
+    println!();
+    println!(
+        "{}",
+        format!("Privacy Pool: {}", format_sol(denomination))
+            .yellow()
+            .bold()
+    );
+    println!();
+    println!("  Address:          {}", pool_pda(denomination));
+    println!("  Active:           {}", pool.is_active);
+    println!("  Deposits:         {}", pool.deposit_count);
+    println!("  Withdrawals:      {}", pool.withdrawal_count);
+    println!("  Total deposited:  {}", format_sol(pool.total_deposited));
+    println!("  Total withdrawn:  {}", format_sol(pool.total_withdrawn));
+    println!("  Next leaf index:  {}", pool.next_leaf_index);
+    println!("  Merkle root:      {}", hex::encode(pool.merkle_root));
+    match pool.scheme() {
+        Some(scheme) => println!("  Commitments:      {:?}", scheme),
+        None => println!("  Commitments:      unknown scheme {}", pool.commitment_scheme),
+    }
+    println!();
+
+    // Anonymity set = every deposit in the tree; unspent notes are what a
+    // withdrawal can plausibly be confused with right now
+    println!("  Anonymity set:    {} deposit(s), {} unspent", pool.next_leaf_index, active_notes);
+    if pool.next_leaf_index < 10 {
+        println!();
+        println!(
+            "{}",
+            "Small anonymity set - consider waiting for more deposits before withdrawing.".red()
+        );
+    }
+
+    Ok(())
+}
+
+/// Convert a SOL amount to lamports and check it is a supported pool denomination
+pub(crate) fn parse_denomination(denomination_sol: &str) -> Result<u64> {
+    let lamports = parse_sol(denomination_sol)?;
+
+    if !PrivacyPool::is_valid_denomination(lamports) {
+        let valid: Vec<String> = DEFAULT_DENOMINATIONS
+            .iter()
+            .map(|d| format_sol(*d))
+            .collect();
+        bail!(
+            "Invalid denomination: {} SOL. Valid denominations: {}",
+            denomination_sol,
+            valid.join(", ")
+        );
+    }
+
+    Ok(lamports)
+}
@@ -13,11 +13,12 @@ use solana_sdk::{
 };
 
 use crate::config::{load_keys, load_solana_keypair, format_meta_address};
+use crate::rpc::{send_and_confirm, with_retry};
 
 // Program ID (update after deployment)
 const PROGRAM_ID: &str = "6CiqeSFEmghXeS4pnhDpR4j5VieDi81jDhfzaanaqpv8";
 
-pub async fn run(rpc_url: &str, keypair_path: Option<&str>, label: &str) -> Result<()> {
+pub async fn run(rpc_url: &str, commitment_config: CommitmentConfig, keypair_path: Option<&str>, label: &str) -> Result<()> {
     println!("{}", "Registering stealth meta-address on-chain...".cyan());
 
     // Load stealth keys
@@ -34,10 +35,10 @@ pub async fn run(rpc_url: &str, keypair_path: Option<&str>, label: &str) -> Resu
     let payer = load_solana_keypair(keypair_path)?;
 
     // Connect to RPC
-    let client = RpcClient::new_with_commitment(rpc_url, CommitmentConfig::confirmed());
+    let client = RpcClient::new_with_commitment(rpc_url, commitment_config);
 
     // Check balance
-    let balance = client.get_balance(&payer.pubkey())?;
+    let balance = with_retry(|| client.get_balance(&payer.pubkey()))?;
     println!("Wallet balance: {} SOL", balance as f64 / 1_000_000_000.0);
 
     if balance < 10_000_000 {
@@ -80,7 +81,7 @@ pub async fn run(rpc_url: &str, keypair_path: Option<&str>, label: &str) -> Resu
     };
 
     // Build and send transaction
-    let recent_blockhash = client.get_latest_blockhash()?;
+    let recent_blockhash = with_retry(|| client.get_latest_blockhash())?;
     let transaction = Transaction::new_signed_with_payer(
         &[instruction],
         Some(&payer.pubkey()),
@@ -89,8 +90,7 @@ pub async fn run(rpc_url: &str, keypair_path: Option<&str>, label: &str) -> Resu
     );
 
     println!("Sending transaction...");
-    let signature = client
-        .send_and_confirm_transaction(&transaction)
+    let signature = send_and_confirm(&client, &transaction)
         .context("Failed to send transaction. Make sure the program is deployed.")?;
 
     println!();
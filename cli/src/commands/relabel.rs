@@ -0,0 +1,89 @@
+//! Change the label on a registered meta-address
+//!
+//! Sends `update_label`, leaving the registered keys as they are.
+
+use anyhow::{bail, Context, Result};
+use anchor_lang::{InstructionData, ToAccountMetas};
+use colored::Colorize;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signer::Signer,
+    transaction::Transaction,
+};
+use stealth::state::StealthRegistry;
+
+use crate::config::load_solana_keypair;
+use crate::rpc::{send_and_confirm, with_retry};
+
+/// Reject labels the program would refuse, before paying for a transaction
+pub fn validate_label(label: &str) -> Result<()> {
+    if label.len() > StealthRegistry::MAX_LABEL_LEN {
+        bail!(
+            "Label is {} bytes; the maximum is {}",
+            label.len(),
+            StealthRegistry::MAX_LABEL_LEN
+        );
+    }
+    if label.contains('\0') {
+        bail!("Label can't contain NUL characters");
+    }
+    Ok(())
+}
+
+pub async fn run(rpc_url: &str, commitment_config: CommitmentConfig, keypair_path: Option<&str>, label: &str) -> Result<()> {
+    validate_label(label)?;
+
+    let owner = load_solana_keypair(keypair_path)?;
+    let client = RpcClient::new_with_commitment(rpc_url, commitment_config);
+
+    let (registry, _) = Pubkey::find_program_address(
+        &[StealthRegistry::SEED, owner.pubkey().as_ref()],
+        &stealth::ID,
+    );
+    if with_retry(|| client.get_account_with_commitment(&registry, client.commitment()))?
+        .value
+        .is_none()
+    {
+        bail!("No meta-address registered for {}. Run 'stealthsol register' first.", owner.pubkey());
+    }
+
+    let instruction = Instruction {
+        program_id: stealth::ID,
+        accounts: stealth::accounts::UpdateRegistry {
+            owner: owner.pubkey(),
+            registry,
+        }
+        .to_account_metas(None),
+        data: stealth::instruction::UpdateLabel {
+            label: label.as_bytes().to_vec(),
+        }
+        .data(),
+    };
+
+    let recent_blockhash = with_retry(|| client.get_latest_blockhash())?;
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&owner.pubkey()),
+        &[&owner],
+        recent_blockhash,
+    );
+
+    println!("Updating label...");
+    let signature = send_and_confirm(&client, &transaction)
+        .context("Failed to send label update")?;
+
+    println!();
+    println!("{}", "Label updated".green().bold());
+    println!();
+    println!("Transaction: {}", signature);
+    if label.is_empty() {
+        println!("Label:       {}", "(cleared)".dimmed());
+    } else {
+        println!("Label:       {}", label);
+    }
+
+    Ok(())
+}
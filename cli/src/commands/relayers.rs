@@ -0,0 +1,112 @@
+//! List registered relayers, most reliable first
+//!
+//! Reliability comes from each relayer's on-chain counters: withdrawals it
+//! signed and submitted versus relay requests users marked failed after they
+//! expired. Relayers with no history sort after those with a record.
+
+use anyhow::Result;
+use anchor_lang::AccountDeserialize;
+use colored::Colorize;
+use serde::Serialize;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use stealth::state::Relayer;
+
+use crate::config::format_sol;
+use crate::pool::fetch_accounts_by_size;
+
+/// `relayers --json` output; fees in lamports
+#[derive(Serialize, Debug)]
+pub struct RelayerOutput {
+    pub pubkey: String,
+    pub fee_bps: u16,
+    pub min_fee: u64,
+    pub max_fee: u64,
+    pub min_denomination: u64,
+    /// Success rate in basis points (absent with no history)
+    pub success_rate_bps: Option<u16>,
+    pub total_relayed: u64,
+    pub total_failed: u64,
+    pub last_active_slot: u64,
+    pub reputation: u8,
+    pub endpoint_url: String,
+}
+
+impl RelayerOutput {
+    pub fn from_relayer(relayer: &Relayer) -> Self {
+        Self {
+            pubkey: relayer.pubkey.to_string(),
+            fee_bps: relayer.fee_bps,
+            min_fee: relayer.min_fee,
+            max_fee: relayer.max_fee,
+            min_denomination: relayer.min_denomination,
+            success_rate_bps: relayer.success_rate_bps(),
+            total_relayed: relayer.total_relayed,
+            total_failed: relayer.total_failed,
+            last_active_slot: relayer.last_active_slot,
+            reputation: relayer.reputation,
+            endpoint_url: endpoint_url(relayer),
+        }
+    }
+}
+
+/// Active relayers ordered by success rate, then by volume relayed
+pub fn rank_relayers(relayers: Vec<Relayer>) -> Vec<Relayer> {
+    let mut active: Vec<Relayer> = relayers.into_iter().filter(|r| r.is_active).collect();
+    active.sort_by(|a, b| {
+        b.success_rate_bps()
+            .cmp(&a.success_rate_bps())
+            .then(b.total_relayed.cmp(&a.total_relayed))
+    });
+    active
+}
+
+/// Advertised endpoint, with the zero padding stripped
+pub fn endpoint_url(relayer: &Relayer) -> String {
+    let len = relayer.endpoint_url.iter().position(|b| *b == 0).unwrap_or(relayer.endpoint_url.len());
+    String::from_utf8_lossy(&relayer.endpoint_url[..len]).into_owned()
+}
+
+pub fn run(rpc_url: &str, commitment_config: CommitmentConfig, json: bool) -> Result<()> {
+    let client = RpcClient::new_with_commitment(rpc_url, commitment_config);
+
+    let relayers: Vec<Relayer> = fetch_accounts_by_size(&client, Relayer::SIZE)?
+        .into_iter()
+        .filter_map(|(_, account)| Relayer::try_deserialize(&mut account.data.as_slice()).ok())
+        .collect();
+    let ranked = rank_relayers(relayers);
+
+    if json {
+        let output: Vec<RelayerOutput> = ranked.iter().map(RelayerOutput::from_relayer).collect();
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    println!();
+    println!("{}", format!("Active relayers: {}", ranked.len()).yellow().bold());
+
+    for relayer in &ranked {
+        let success = match relayer.success_rate_bps() {
+            Some(bps) => format!("{}.{:02}%", bps / 100, bps % 100),
+            None => "no history".to_string(),
+        };
+
+        println!();
+        println!("  {}", relayer.pubkey.to_string().cyan());
+        println!("    Success rate:   {} ({} relayed, {} failed)", success, relayer.total_relayed, relayer.total_failed);
+        println!("    Fee:            {} bps (min {})", relayer.fee_bps, format_sol(relayer.min_fee));
+        println!("    Min pool:       {}", format_sol(relayer.min_denomination));
+        println!("    Last active:    slot {}", relayer.last_active_slot);
+        let endpoint = endpoint_url(relayer);
+        if !endpoint.is_empty() {
+            println!("    Endpoint:       {}", endpoint);
+        }
+    }
+
+    if ranked.is_empty() {
+        println!();
+        println!("{}", "No active relayers registered.".dimmed());
+    }
+
+    Ok(())
+}
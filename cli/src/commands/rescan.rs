@@ -0,0 +1,106 @@
+//! Rebuild local state from chain using only the recovery phrase
+//!
+//! Re-derives the stealth keys from the mnemonic, walks every announcement
+//! and pool note, and records each payment and note found as a `received`
+//! history entry. Entries are deduplicated by address, so `rescan` can be
+//! repeated or run against an existing history file. Sends and withdrawals
+//! can't be recovered this way: nothing on chain links them to our keys.
+
+use anyhow::{Result, Context};
+use chrono::Utc;
+use colored::Colorize;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use zeroize::Zeroize;
+
+use crate::commands::scan::{fetch_announcements, fetch_notes};
+use crate::config::{format_meta_address, format_sol};
+use crate::crypto::{StealthKeys, scan_batch};
+use crate::history::{history_file, merge_received, HistoryEntry};
+use crate::pool::{fetch_spent_nullifiers, note_nullifier_pda};
+use crate::rpc::with_retry;
+use crate::secure_storage::prompt_password;
+
+pub async fn run(rpc_url: &str, commitment_config: CommitmentConfig, from_slot: Option<u64>, passphrase: Option<&str>) -> Result<()> {
+    println!("{}", "Recovering stealth payments from chain...".cyan());
+    println!();
+
+    let mut phrase = prompt_password("Enter recovery phrase: ")?;
+    let keys = StealthKeys::from_mnemonic(phrase.trim(), passphrase.unwrap_or(""));
+    phrase.zeroize();
+    let keys = keys.context("Could not derive keys from the recovery phrase")?;
+
+    let (scan_pubkey, spend_pubkey) = keys.meta_address();
+    println!("Meta-address: {}", format_meta_address(&scan_pubkey, &spend_pubkey));
+
+    let client = RpcClient::new_with_commitment(rpc_url, commitment_config);
+
+    // Direct stealth payments
+    let announcements = fetch_announcements(&client, from_slot)?;
+    let candidates: Vec<([u8; 32], [u8; 32])> = announcements
+        .iter()
+        .map(|a| (a.ephemeral_pubkey, a.stealth_address.to_bytes()))
+        .collect();
+    let mut results = scan_batch(&keys, &candidates).into_iter().peekable();
+
+    let mut entries = Vec::new();
+    let mut payment_count = 0;
+    let mut address_available: u64 = 0;
+
+    for announcement in &announcements {
+        let payment_address_bytes = announcement.stealth_address.to_bytes();
+        if results.next_if(|r| r.stealth_address == payment_address_bytes).is_none() {
+            continue;
+        }
+
+        payment_count += 1;
+        address_available += with_retry(|| client.get_balance(&announcement.stealth_address))
+            .unwrap_or(0);
+        entries.push(HistoryEntry::received(
+            announcement.stealth_address,
+            announcement.amount,
+            false,
+            announcement.timestamp,
+        ));
+    }
+
+    // Pool notes encrypted to our scan key (always scanned in full)
+    let notes = fetch_notes(&client, &keys)?;
+    let nullifier_pdas: Vec<_> = notes.iter().map(|(_, _, note)| note_nullifier_pda(note)).collect();
+    let spent = fetch_spent_nullifiers(&client, &nullifier_pdas)?;
+
+    let now = Utc::now().timestamp();
+    let mut note_available: u64 = 0;
+    for ((leaf, _, note), is_spent) in notes.iter().zip(&spent) {
+        if !is_spent {
+            note_available += note.amount;
+        }
+        entries.push(HistoryEntry::received(leaf, note.amount, true, now));
+    }
+
+    let found = entries.len();
+    let added = merge_received(&history_file(), entries)?;
+
+    println!();
+    println!("{}", "Recovery complete".green().bold());
+    println!();
+    println!("Payments found:   {}", payment_count);
+    println!("Pool notes found: {} ({} unspent)", notes.len(), spent.iter().filter(|s| !**s).count());
+    println!("History:          {} new, {} already recorded", added, found - added);
+    println!();
+    println!("Available:        {}", format_sol(address_available + note_available).green());
+    println!();
+
+    if from_slot.is_some() {
+        println!(
+            "{}",
+            "Only announcements from --from-slot on were scanned; run without it for a full recovery.".dimmed()
+        );
+    }
+    println!(
+        "{}",
+        "To restore the encrypted key store too, run 'stealthsol keygen --import-mnemonic'.".dimmed()
+    );
+
+    Ok(())
+}
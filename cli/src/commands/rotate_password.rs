@@ -0,0 +1,30 @@
+//! Rotate the password protecting the encrypted key file
+
+use anyhow::Result;
+use colored::Colorize;
+
+use crate::secure_storage::{SecureKeyStorage, prompt_password, prompt_new_password};
+
+pub fn run() -> Result<()> {
+    let storage = SecureKeyStorage::new(SecureKeyStorage::default_path());
+
+    if !storage.exists() {
+        anyhow::bail!(
+            "No stealth keys found. Run 'stealthsol keygen' first."
+        );
+    }
+
+    let old_password = prompt_password("Enter current password: ")?;
+    let new_password = prompt_new_password("Enter new password: ")?;
+
+    storage.reencrypt(&old_password, &new_password)?;
+
+    println!();
+    println!("{}", "Password changed.".green().bold());
+    println!(
+        "{}",
+        format!("Keys re-encrypted at: {:?}", SecureKeyStorage::default_path()).dimmed()
+    );
+
+    Ok(())
+}
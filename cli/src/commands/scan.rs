@@ -4,16 +4,24 @@ use anyhow::{Result, Context};
 use colored::Colorize;
 use solana_client::rpc_client::RpcClient;
 use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
-use solana_client::rpc_filter::RpcFilterType;
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
 use solana_sdk::{
     commitment_config::CommitmentConfig,
     pubkey::Pubkey,
     account::Account,
 };
 use solana_account_decoder::UiAccountEncoding;
+use anchor_lang::{AccountDeserialize, Discriminator};
 use borsh::BorshDeserialize;
+use stealth::state::{announcement_buckets, index_bucket_for_slot, AnnouncementIndex, AnnouncementLog};
+use zeroize::Zeroize;
 
-use crate::crypto::{StealthKeys, scan_payment};
+use crate::config::{load_view_key, view_key_file};
+use crate::crypto::{
+    StealthKeys, ViewKey, Note, scan_batch, decrypt_note, note_ephemeral_pubkey,
+    ENCRYPTED_NOTE_SIZE,
+};
+use crate::rpc::with_retry;
 use crate::secure_storage::{SecureKeyStorage, prompt_password};
 
 // Program ID (update after deployment)
@@ -27,10 +35,17 @@ const ANNOUNCEMENT_SIZE: usize = 161;
 // sha256("account:StealthAnnouncement")[..8]
 const ANNOUNCEMENT_DISCRIMINATOR: [u8; 8] = [0x9a, 0x47, 0x72, 0x8e, 0x36, 0x7c, 0x5f, 0x2a];
 
+// CommitmentLeaf account size (must match on-chain)
+// 8 (discriminator) + 32 (commitment) + 8 (leaf_index) + 8 (timestamp) + 128 (encrypted_note) + 33 (amount_commitment) + 32 (range_proof_hash) + 8 (slot) + 1 (bump)
+const COMMITMENT_LEAF_SIZE: usize = 258;
+
+// Offset of encrypted_note within a CommitmentLeaf account
+const COMMITMENT_LEAF_NOTE_OFFSET: usize = 8 + 32 + 8 + 8;
+
 /// On-chain announcement structure (must match program)
 #[derive(BorshDeserialize, Debug)]
 #[allow(dead_code)]
-struct Announcement {
+pub(crate) struct Announcement {
     pub ephemeral_pubkey: [u8; 32],
     pub stealth_address: Pubkey,
     pub commitment: [u8; 32],
@@ -49,7 +64,7 @@ struct PaymentInfo {
     spending_key: [u8; 32],
 }
 
-pub async fn run(rpc_url: &str, from_slot: Option<u64>) -> Result<()> {
+pub async fn run(rpc_url: &str, commitment_config: CommitmentConfig, from_slot: Option<u64>, use_log: bool, use_index: bool) -> Result<()> {
     println!("{}", "Scanning for incoming stealth payments...".cyan());
 
     // Load encrypted stealth keys
@@ -68,10 +83,255 @@ pub async fn run(rpc_url: &str, from_slot: Option<u64>) -> Result<()> {
     let keys = StealthKeys::from_secrets(&key_data.scan_secret, &key_data.spend_secret);
 
     // Connect to RPC
-    let client = RpcClient::new_with_commitment(rpc_url, CommitmentConfig::confirmed());
+    let client = RpcClient::new_with_commitment(rpc_url, commitment_config);
+    let announcements = load_announcements(&client, from_slot, use_log, use_index)?;
+
+    // Check every announcement in parallel; matches come back in order
+    let candidates: Vec<([u8; 32], [u8; 32])> = announcements
+        .iter()
+        .map(|a| (a.ephemeral_pubkey, a.stealth_address.to_bytes()))
+        .collect();
+    let mut results = scan_batch(&keys, &candidates).into_iter().peekable();
+
+    let mut found_payments: Vec<PaymentInfo> = Vec::new();
+
+    for announcement in announcements {
+        let payment_address_bytes = announcement.stealth_address.to_bytes();
+        let Some(result) = results.next_if(|r| r.stealth_address == payment_address_bytes) else {
+            continue;
+        };
+
+        // Check actual balance
+        let balance = with_retry(|| client.get_balance(&announcement.stealth_address))
+            .unwrap_or(0);
+
+        found_payments.push(PaymentInfo {
+            announcement,
+            balance,
+            spending_key: result.spending_key_bytes(),
+        });
+    }
+
+    if found_payments.is_empty() {
+        println!("{}", "No incoming payments found.".yellow());
+        if from_slot.is_some() {
+            println!(
+                "{}",
+                "Try without --from-slot to scan all announcements.".dimmed()
+            );
+        }
+    } else {
+        println!(
+            "{}",
+            format!("Found {} payment(s):", found_payments.len())
+                .green()
+                .bold()
+        );
+        println!();
+
+        // Sort by slot (newest first)
+        found_payments.sort_by(|a, b| b.announcement.slot.cmp(&a.announcement.slot));
+
+        for (i, payment) in found_payments.iter().enumerate() {
+            print_payment(i, &payment.announcement, payment.balance);
+        }
+
+        // Calculate totals
+        let total_balance: u64 = found_payments.iter().map(|p| p.balance).sum();
+        let available_count = found_payments.iter().filter(|p| p.balance > 0).count();
+
+        println!(
+            "{}",
+            format!(
+                "Total available: {} SOL ({} payment(s))",
+                total_balance as f64 / 1_000_000_000.0,
+                available_count
+            )
+            .green()
+            .bold()
+        );
+        println!();
+        println!(
+            "{}",
+            "Use 'stealthsol withdraw --from <address>' to withdraw funds.".dimmed()
+        );
+    }
+
+    // Recover deposit notes encrypted to our scan key
+    match fetch_notes(&client, &keys) {
+        Ok(notes) if !notes.is_empty() => {
+            println!();
+            println!(
+                "{}",
+                format!("Recovered {} pool note(s):", notes.len()).green().bold()
+            );
+            println!();
+
+            for (i, (leaf, leaf_index, note)) in notes.iter().enumerate() {
+                println!("{}. {}", i + 1, "Note".yellow());
+                println!("   Leaf:      {} (index {})", leaf, leaf_index);
+                println!("   Amount:    {} SOL", note.amount as f64 / 1_000_000_000.0);
+                println!("   Nullifier: {}", hex::encode(note.nullifier));
+                println!("   Secret:    {}", hex::encode(note.secret));
+                println!();
+            }
+
+            println!(
+                "{}",
+                "Keep these secrets private - they authorize pool withdrawals.".red()
+            );
+            println!(
+                "{}",
+                "Use 'stealthsol withdraw --from <leaf>' to withdraw a note.".dimmed()
+            );
+        }
+        Ok(_) => {}
+        Err(e) => {
+            println!("{}", format!("Could not scan pool notes: {}", e).dimmed());
+        }
+    }
+
+    Ok(())
+}
+
+/// Lamport balances of `addresses`, 0 for accounts that don't exist
+///
+/// Batched like `fetch_spent_nullifiers`: one `get_multiple_accounts` call
+/// per 100 addresses instead of a `get_balance` per address.
+pub(crate) fn fetch_balances(client: &RpcClient, addresses: &[Pubkey]) -> Result<Vec<u64>> {
+    let mut balances = Vec::with_capacity(addresses.len());
+    for chunk in addresses.chunks(100) {
+        let accounts = with_retry(|| client.get_multiple_accounts(chunk))?;
+        balances.extend(accounts.iter().map(|account| account.as_ref().map_or(0, |a| a.lamports)));
+    }
+    Ok(balances)
+}
+
+/// Find pool commitment leaves whose encrypted note decrypts with our scan key
+pub(crate) fn fetch_notes(client: &RpcClient, keys: &StealthKeys) -> Result<Vec<(Pubkey, u64, Note)>> {
     let program_id: Pubkey = PROGRAM_ID.parse()?;
 
-    println!("Fetching announcements from program {}...", program_id);
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![
+            RpcFilterType::DataSize(COMMITMENT_LEAF_SIZE as u64),
+        ]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(client.commitment()),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let accounts = with_retry(|| client.get_program_accounts_with_config(&program_id, config.clone()))?;
+    let scan_secret = keys.scan_secret();
+
+    let mut notes = Vec::new();
+    for (pubkey, account) in accounts {
+        if account.data.len() < COMMITMENT_LEAF_SIZE {
+            continue;
+        }
+
+        let mut blob = [0u8; ENCRYPTED_NOTE_SIZE];
+        blob.copy_from_slice(
+            &account.data[COMMITMENT_LEAF_NOTE_OFFSET..COMMITMENT_LEAF_NOTE_OFFSET + ENCRYPTED_NOTE_SIZE],
+        );
+
+        // Deposits without a note leave the field zeroed
+        if blob.iter().all(|&b| b == 0) {
+            continue;
+        }
+
+        let ephemeral = note_ephemeral_pubkey(&blob);
+        if let Some(note) = decrypt_note(&scan_secret, &ephemeral, &blob) {
+            let mut leaf_index = [0u8; 8];
+            leaf_index.copy_from_slice(&account.data[8 + 32..8 + 32 + 8]);
+            notes.push((pubkey, u64::from_le_bytes(leaf_index), note));
+        }
+    }
+
+    Ok(notes)
+}
+
+/// Scan with an exported view key only (watch-only)
+///
+/// Uses `check_payment`, so spending keys are never derived and the spend
+/// secret is never loaded.
+pub async fn run_view_only(
+    rpc_url: &str,
+    commitment_config: CommitmentConfig,
+    view_key_path: Option<&str>,
+    from_slot: Option<u64>,
+    use_log: bool,
+    use_index: bool,
+) -> Result<()> {
+    println!("{}", "Scanning for incoming stealth payments (view-only)...".cyan());
+
+    let path = view_key_path
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(view_key_file);
+
+    let (mut scan_secret, spend_pubkey) = load_view_key(&path)?;
+    let view_key = ViewKey::from_parts(&scan_secret, &spend_pubkey);
+    scan_secret.zeroize();
+
+    // Connect to RPC
+    let client = RpcClient::new_with_commitment(rpc_url, commitment_config);
+    let announcements = load_announcements(&client, from_slot, use_log, use_index)?;
+
+    let mut found: Vec<(Announcement, u64)> = Vec::new();
+
+    for announcement in announcements {
+        let payment_address_bytes = announcement.stealth_address.to_bytes();
+
+        if view_key.check_payment(&announcement.ephemeral_pubkey, &payment_address_bytes) {
+            let balance = with_retry(|| client.get_balance(&announcement.stealth_address))
+                .unwrap_or(0);
+            found.push((announcement, balance));
+        }
+    }
+
+    if found.is_empty() {
+        println!("{}", "No incoming payments found.".yellow());
+        return Ok(());
+    }
+
+    println!(
+        "{}",
+        format!("Found {} payment(s):", found.len()).green().bold()
+    );
+    println!();
+
+    // Sort by slot (newest first)
+    found.sort_by_key(|(announcement, _)| std::cmp::Reverse(announcement.slot));
+
+    for (i, (announcement, balance)) in found.iter().enumerate() {
+        print_payment(i, announcement, *balance);
+    }
+
+    let total_balance: u64 = found.iter().map(|(_, balance)| balance).sum();
+    println!(
+        "{}",
+        format!("Total balance: {} SOL", total_balance as f64 / 1_000_000_000.0)
+            .green()
+            .bold()
+    );
+    println!();
+    println!(
+        "{}",
+        "View-only scan: these funds cannot be withdrawn with a view key.".dimmed()
+    );
+
+    Ok(())
+}
+
+/// Fetch and decode all announcements, applying the optional slot filter
+///
+/// Progress goes to stderr so `--json` output on stdout stays parseable.
+pub(crate) fn fetch_announcements(client: &RpcClient, from_slot: Option<u64>) -> Result<Vec<Announcement>> {
+    let program_id: Pubkey = PROGRAM_ID.parse()?;
+
+    eprintln!("Fetching announcements from program {}...", program_id);
 
     // Use filters for efficient querying
     let config = RpcProgramAccountsConfig {
@@ -83,18 +343,18 @@ pub async fn run(rpc_url: &str, from_slot: Option<u64>) -> Result<()> {
         ]),
         account_config: RpcAccountInfoConfig {
             encoding: Some(UiAccountEncoding::Base64),
-            commitment: Some(CommitmentConfig::confirmed()),
+            commitment: Some(client.commitment()),
             ..Default::default()
         },
         ..Default::default()
     };
 
     // Fetch with pagination support
-    let accounts = fetch_accounts_paginated(&client, &program_id, config, from_slot)?;
+    let accounts = fetch_accounts_paginated(client, &program_id, config, from_slot)?;
 
-    println!("Found {} announcement accounts, scanning...", accounts.len());
+    eprintln!("Found {} announcement accounts, scanning...", accounts.len());
 
-    let mut found_payments: Vec<PaymentInfo> = Vec::new();
+    let mut announcements = Vec::new();
     let mut scanned = 0;
     let mut errors = 0;
 
@@ -103,7 +363,7 @@ pub async fn run(rpc_url: &str, from_slot: Option<u64>) -> Result<()> {
 
         // Progress indicator every 100 accounts
         if scanned % 100 == 0 {
-            print!("\rScanned {} accounts...", scanned);
+            eprint!("\rScanned {} accounts...", scanned);
         }
 
         // Skip accounts that are too small
@@ -120,26 +380,7 @@ pub async fn run(rpc_url: &str, from_slot: Option<u64>) -> Result<()> {
                         continue;
                     }
                 }
-
-                // Check if this payment is for us
-                let payment_address_bytes = announcement.stealth_address.to_bytes();
-
-                if let Some(result) = scan_payment(
-                    &keys,
-                    &announcement.ephemeral_pubkey,
-                    &payment_address_bytes,
-                ) {
-                    // Check actual balance
-                    let balance = client
-                        .get_balance(&announcement.stealth_address)
-                        .unwrap_or(0);
-
-                    found_payments.push(PaymentInfo {
-                        announcement,
-                        balance,
-                        spending_key: result.spending_key_bytes(),
-                    });
-                }
+                announcements.push(announcement);
             }
             Err(_) => {
                 errors += 1;
@@ -147,83 +388,274 @@ pub async fn run(rpc_url: &str, from_slot: Option<u64>) -> Result<()> {
         }
     }
 
-    println!("\r"); // Clear progress line
+    eprintln!("\r"); // Clear progress line
 
     if errors > 0 {
-        println!(
+        eprintln!(
             "{}",
             format!("Skipped {} malformed accounts", errors).dimmed()
         );
     }
 
-    println!();
+    eprintln!();
 
-    if found_payments.is_empty() {
-        println!("{}", "No incoming payments found.".yellow());
-        if from_slot.is_some() {
+    Ok(announcements)
+}
+
+/// Fetch announcements, from the announcement logs when `use_log` is set
+/// or the slot-bucket indexes when `use_index` is set
+///
+/// Falls back to the full account scan if a log no longer retains every
+/// entry since `from_slot`, or an index for one of the buckets is missing
+/// or incomplete.
+fn load_announcements(
+    client: &RpcClient,
+    from_slot: Option<u64>,
+    use_log: bool,
+    use_index: bool,
+) -> Result<Vec<Announcement>> {
+    let Some(from_slot) = from_slot else {
+        return fetch_announcements(client, None);
+    };
+
+    if use_index {
+        return match fetch_indexed_announcements(client, from_slot)? {
+            Some(announcements) => Ok(announcements),
+            None => {
+                println!(
+                    "{}",
+                    format!(
+                        "Announcement indexes don't cover every bucket since slot {}, falling back to a full scan",
+                        from_slot
+                    )
+                    .yellow()
+                );
+                fetch_announcements(client, Some(from_slot))
+            }
+        };
+    }
+
+    if !use_log {
+        return fetch_announcements(client, Some(from_slot));
+    }
+
+    match fetch_logged_announcements(client, from_slot)? {
+        Some(announcements) => Ok(announcements),
+        None => {
             println!(
                 "{}",
-                "Try without --from-slot to scan all announcements.".dimmed()
+                format!(
+                    "Announcement log no longer covers slot {}, falling back to a full scan",
+                    from_slot
+                )
+                .yellow()
             );
+            fetch_announcements(client, Some(from_slot))
         }
-    } else {
-        println!(
-            "{}",
-            format!("Found {} payment(s):", found_payments.len())
-                .green()
-                .bold()
-        );
-        println!();
+    }
+}
 
-        // Sort by slot (newest first)
-        found_payments.sort_by(|a, b| b.announcement.slot.cmp(&a.announcement.slot));
+/// Read announcements recorded since `from_slot` from the announcement logs
+///
+/// Only instructions given an announcement log (pool withdrawals, logged
+/// blended transfers) are indexed. Each entry is resolved to its announcement
+/// account when one exists; otherwise the entry itself is used, without an
+/// amount. Returns `None` if any log has wrapped past `from_slot`.
+pub(crate) fn fetch_logged_announcements(client: &RpcClient, from_slot: u64) -> Result<Option<Vec<Announcement>>> {
+    let program_id: Pubkey = PROGRAM_ID.parse()?;
 
-        for (i, payment) in found_payments.iter().enumerate() {
-            let sol_amount = payment.balance as f64 / 1_000_000_000.0;
-            let recorded_amount = payment.announcement.amount as f64 / 1_000_000_000.0;
+    println!("Reading announcement logs since slot {}...", from_slot);
 
-            let status = if payment.balance > 0 {
-                "AVAILABLE".green()
-            } else {
-                "WITHDRAWN".dimmed()
-            };
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+            0,
+            AnnouncementLog::DISCRIMINATOR.to_vec(),
+        ))]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(client.commitment()),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let mut entries = Vec::new();
+    for (_pubkey, account) in with_retry(|| client.get_program_accounts_with_config(&program_id, config.clone()))? {
+        let Ok(log) = AnnouncementLog::try_deserialize(&mut account.data.as_slice()) else {
+            continue;
+        };
+        if !log.covers_slot(from_slot) {
+            return Ok(None);
+        }
+        entries.extend(log.entries_in_slot_range(from_slot, u64::MAX).into_iter().cloned());
+    }
+    entries.sort_by_key(|entry| entry.slot);
+
+    // Withdrawals create their announcement under one of these two PDAs
+    let pdas: Vec<Pubkey> = entries
+        .iter()
+        .flat_map(|entry| {
+            [
+                Pubkey::find_program_address(&[b"announcement", &entry.ephemeral_pubkey], &program_id).0,
+                Pubkey::find_program_address(
+                    &[b"unified_announcement", entry.stealth_address.as_ref()],
+                    &program_id,
+                )
+                .0,
+            ]
+        })
+        .collect();
 
-            println!("{}. {} [{}]", i + 1, "Payment".yellow(), status);
-            println!("   Address:  {}", payment.announcement.stealth_address);
-            println!("   Balance:  {} SOL", sol_amount);
-            println!("   Recorded: {} SOL", recorded_amount);
-            println!("   Slot:     {}", payment.announcement.slot);
+    let mut accounts = Vec::with_capacity(pdas.len());
+    for chunk in pdas.chunks(100) {
+        accounts.extend(with_retry(|| client.get_multiple_accounts(chunk))?);
+    }
 
-            if payment.announcement.token_mint == Pubkey::default() {
-                println!("   Token:    Native SOL");
-            } else {
-                println!("   Token:    {}", payment.announcement.token_mint);
+    let announcements: Vec<Announcement> = entries
+        .iter()
+        .zip(accounts.chunks(2))
+        .map(|(entry, pair)| {
+            let stored = pair
+                .iter()
+                .flatten()
+                .filter(|account| account.data.len() >= ANNOUNCEMENT_SIZE)
+                .find_map(|account| Announcement::try_from_slice(&account.data[8..ANNOUNCEMENT_SIZE]).ok());
+
+            match stored {
+                Some(announcement) => Announcement { slot: entry.slot, ..announcement },
+                None => Announcement {
+                    ephemeral_pubkey: entry.ephemeral_pubkey,
+                    stealth_address: entry.stealth_address,
+                    commitment: entry.commitment,
+                    amount: 0,
+                    token_mint: Pubkey::default(),
+                    slot: entry.slot,
+                    timestamp: 0,
+                    bump: 0,
+                },
             }
-            println!();
+        })
+        .collect();
+
+    println!("Found {} logged announcements, scanning...", announcements.len());
+    println!();
+
+    Ok(Some(announcements))
+}
+
+/// Read announcements made since `from_slot` through the slot-bucket indexes
+///
+/// Fetches the `AnnouncementIndex` of every bucket from `from_slot` to the
+/// current slot, then the announcement accounts they list. Only
+/// announcements whose instruction was given the index are listed. Returns
+/// `None` if any bucket has no index or one that doesn't cover `from_slot`.
+///
+/// Near the end of a bucket senders already pass the next bucket's index,
+/// so that one is read too; it may not exist yet, in which case nothing was
+/// sent to it.
+pub(crate) fn fetch_indexed_announcements(client: &RpcClient, from_slot: u64) -> Result<Option<Vec<Announcement>>> {
+    let program_id: Pubkey = PROGRAM_ID.parse()?;
+    let current_slot = with_retry(|| client.get_slot())?;
+    let buckets = announcement_buckets(from_slot, current_slot);
+    let lookahead = index_bucket_for_slot(current_slot);
+
+    println!(
+        "Reading announcement indexes for slots {}..={} ({} buckets)...",
+        from_slot,
+        current_slot,
+        buckets.clone().count()
+    );
+
+    let index_pdas: Vec<Pubkey> = buckets.clone().map(|bucket| announcement_index_pda(&program_id, bucket)).collect();
+
+    let mut pdas = Vec::new();
+    for chunk in index_pdas.chunks(100) {
+        for account in with_retry(|| client.get_multiple_accounts(chunk))? {
+            let Some(index) = account.and_then(|a| AnnouncementIndex::try_deserialize(&mut a.data.as_slice()).ok())
+            else {
+                return Ok(None);
+            };
+            if !index.covers_slot(from_slot) {
+                return Ok(None);
+            }
+            pdas.extend(index.announcements);
         }
+    }
 
-        // Calculate totals
-        let total_balance: u64 = found_payments.iter().map(|p| p.balance).sum();
-        let available_count = found_payments.iter().filter(|p| p.balance > 0).count();
+    if lookahead > *buckets.end() {
+        let pda = announcement_index_pda(&program_id, lookahead);
+        if let Some(account) = with_retry(|| client.get_account_with_commitment(&pda, client.commitment()))?.value {
+            let Ok(index) = AnnouncementIndex::try_deserialize(&mut account.data.as_slice()) else {
+                return Ok(None);
+            };
+            if !index.covers_slot(from_slot) {
+                return Ok(None);
+            }
+            pdas.extend(index.announcements);
+        }
+    }
 
-        println!(
-            "{}",
-            format!(
-                "Total available: {} SOL ({} payment(s))",
-                total_balance as f64 / 1_000_000_000.0,
-                available_count
-            )
-            .green()
-            .bold()
-        );
-        println!();
-        println!(
-            "{}",
-            "Use 'stealthsol withdraw --from <address>' to withdraw funds.".dimmed()
-        );
+    let mut announcements = Vec::with_capacity(pdas.len());
+    for chunk in pdas.chunks(100) {
+        for account in with_retry(|| client.get_multiple_accounts(chunk))?.into_iter().flatten() {
+            if account.data.len() < ANNOUNCEMENT_SIZE {
+                continue;
+            }
+            if let Ok(announcement) = Announcement::try_from_slice(&account.data[8..ANNOUNCEMENT_SIZE]) {
+                if announcement.slot >= from_slot {
+                    announcements.push(announcement);
+                }
+            }
+        }
     }
 
-    Ok(())
+    println!("Found {} indexed announcements, scanning...", announcements.len());
+    println!();
+
+    Ok(Some(announcements))
+}
+
+/// Index PDA for `bucket` under `program_id`
+pub(crate) fn announcement_index_pda(program_id: &Pubkey, bucket: u64) -> Pubkey {
+    Pubkey::find_program_address(&[AnnouncementIndex::SEED, &bucket.to_le_bytes()], program_id).0
+}
+
+/// The announcement index a transaction sent now should pass, if it exists
+///
+/// Near the end of a bucket this is the next bucket's index, which accepts
+/// the announcement whichever side of the edge the transaction lands on.
+pub(crate) fn current_announcement_index(client: &RpcClient, program_id: &Pubkey) -> Result<Option<Pubkey>> {
+    let slot = with_retry(|| client.get_slot())?;
+    let index = announcement_index_pda(program_id, index_bucket_for_slot(slot));
+    let exists = with_retry(|| client.get_account_with_commitment(&index, client.commitment()))?
+        .value
+        .is_some();
+    Ok(exists.then_some(index))
+}
+
+/// Print a single detected payment
+fn print_payment(i: usize, announcement: &Announcement, balance: u64) {
+    let sol_amount = balance as f64 / 1_000_000_000.0;
+    let recorded_amount = announcement.amount as f64 / 1_000_000_000.0;
+
+    let status = if balance > 0 {
+        "AVAILABLE".green()
+    } else {
+        "WITHDRAWN".dimmed()
+    };
+
+    println!("{}. {} [{}]", i + 1, "Payment".yellow(), status);
+    println!("   Address:  {}", announcement.stealth_address);
+    println!("   Balance:  {} SOL", sol_amount);
+    println!("   Recorded: {} SOL", recorded_amount);
+    println!("   Slot:     {}", announcement.slot);
+
+    if announcement.token_mint == Pubkey::default() {
+        println!("   Token:    Native SOL");
+    } else {
+        println!("   Token:    {}", announcement.token_mint);
+    }
+    println!();
 }
 
 /// Fetch accounts with pagination to handle large datasets
@@ -268,7 +700,7 @@ async fn fetch_accounts_efficient(
         ..Default::default()
     };
 
-    let initial_accounts = client.get_program_accounts_with_config(program_id, slice_config)?;
+    let initial_accounts = with_retry(|| client.get_program_accounts_with_config(program_id, slice_config.clone()))?;
 
     // Step 2: Filter to only announcement accounts and batch fetch full data
     let announcement_pubkeys: Vec<Pubkey> = initial_accounts
@@ -282,7 +714,7 @@ async fn fetch_accounts_efficient(
     // Step 3: Batch fetch full account data (max 100 per request)
     let mut full_accounts = Vec::new();
     for chunk in announcement_pubkeys.chunks(100) {
-        let accounts = client.get_multiple_accounts(chunk)?;
+        let accounts = with_retry(|| client.get_multiple_accounts(chunk))?;
         for (i, maybe_account) in accounts.into_iter().enumerate() {
             if let Some(account) = maybe_account {
                 full_accounts.push((chunk[i], account));
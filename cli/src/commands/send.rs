@@ -1,4 +1,12 @@
 //! Send SOL to a stealth address
+//!
+//! With `dry_run`, everything up to signing is computed and printed
+//! (stealth address, ephemeral key, commitment, announcement PDA, fees)
+//! without loading the keypair or touching the network.
+//!
+//! `run_private` deposits into the privacy pools instead: the amount is
+//! split into pool denominations and each deposit's note is encrypted to
+//! the recipient, who finds it with `scan` and withdraws it.
 
 use anyhow::{Result, Context, bail};
 use colored::Colorize;
@@ -7,44 +15,70 @@ use solana_sdk::{
     commitment_config::CommitmentConfig,
     instruction::{AccountMeta, Instruction},
     pubkey::Pubkey,
+    rent::Rent,
     transaction::Transaction,
     system_program,
     signer::Signer,
 };
+use stealth::state::{CommitmentLeaf, StealthAnnouncement};
 
-use crate::config::{load_solana_keypair, parse_meta_address};
-use crate::crypto::{compute_stealth_address, compute_commitment, MIN_PAYMENT_LAMPORTS};
+use crate::commands::scan::current_announcement_index;
+use crate::config::{
+    decode_hex_32, decompose_into_denominations, format_sol, load_solana_keypair,
+    parse_meta_address_with, parse_sol,
+};
+use crate::crypto::{
+    try_compute_stealth_address, try_compute_stealth_address_deterministic, compute_commitment,
+    Note, MIN_PAYMENT_LAMPORTS,
+};
+use crate::history::{self, HistoryEntry};
+use crate::pool::{ensure_note_scheme, fetch_pool, fetch_pool_config, private_deposit_instruction};
+use crate::rpc::{send_and_confirm, with_retry};
 
 // Program ID (update after deployment)
 const PROGRAM_ID: &str = "6CiqeSFEmghXeS4pnhDpR4j5VieDi81jDhfzaanaqpv8";
 
+/// Fee for a single-signature transaction (0.000005 SOL)
+const TX_FEE_ESTIMATE: u64 = 5_000;
+
+#[allow(clippy::too_many_arguments)]
 pub async fn run(
     rpc_url: &str,
+    commitment_config: CommitmentConfig,
     keypair_path: Option<&str>,
     recipient: &str,
-    amount_sol: f64,
+    amount_sol: &str,
+    seed: Option<&str>,
+    dry_run: bool,
+    legacy: bool,
 ) -> Result<()> {
     println!("{}", "Preparing stealth payment...".cyan());
 
     // Convert SOL to lamports
-    let amount_lamports = (amount_sol * 1_000_000_000.0) as u64;
+    let amount_lamports = parse_sol(amount_sol)?;
 
     // Check minimum amount
     if amount_lamports < MIN_PAYMENT_LAMPORTS {
         bail!(
-            "Payment amount too small. Minimum is {} SOL ({} lamports)",
-            MIN_PAYMENT_LAMPORTS as f64 / 1_000_000_000.0,
+            "Payment amount too small. Minimum is {} ({} lamports)",
+            format_sol(MIN_PAYMENT_LAMPORTS),
             MIN_PAYMENT_LAMPORTS
         );
     }
 
     // Parse recipient meta-address
-    let (scan_pubkey, spend_pubkey) = parse_meta_address(recipient)
+    let (scan_pubkey, spend_pubkey) = parse_meta_address_with(recipient, legacy)
         .context("Invalid meta-address format")?;
 
-    // Compute stealth address
-    let computation = compute_stealth_address(&scan_pubkey, &spend_pubkey)
-        .context("Failed to compute stealth address")?;
+    // Compute stealth address (a seed makes retries reuse the same ephemeral key)
+    let computation = match seed {
+        Some(seed_hex) => {
+            let seed = decode_hex_32(seed_hex).context("Seed must be 32 bytes of hex")?;
+            try_compute_stealth_address_deterministic(&scan_pubkey, &spend_pubkey, &seed)
+        }
+        None => try_compute_stealth_address(&scan_pubkey, &spend_pubkey),
+    }
+    .context("Failed to compute stealth address")?;
 
     let stealth_address = Pubkey::new_from_array(computation.stealth_pubkey);
 
@@ -56,6 +90,41 @@ pub async fn run(
         &computation.stealth_pubkey,
     );
 
+    // Derive announcement PDA
+    let program_id: Pubkey = PROGRAM_ID.parse()?;
+    let (announcement_pda, _bump) = Pubkey::find_program_address(
+        &[b"announcement", &computation.ephemeral_pubkey],
+        &program_id,
+    );
+
+    if dry_run {
+        let announcement_rent = Rent::default().minimum_balance(StealthAnnouncement::SIZE);
+
+        println!();
+        println!("{}", "Dry run: nothing will be signed or sent".yellow().bold());
+        println!();
+        println!("Recipient:       {}", recipient);
+        println!("Amount:          {} ({} lamports)", format_sol(amount_lamports), amount_lamports);
+        println!("Stealth address: {}", stealth_address);
+        println!("Ephemeral key:   {}", hex::encode(computation.ephemeral_pubkey));
+        println!("Commitment:      {}", hex::encode(commitment));
+        println!("Announcement:    {}", announcement_pda);
+        println!("Network fee:     ~{}", format_sol(TX_FEE_ESTIMATE));
+        println!("Rent:            {} (announcement account)", format_sol(announcement_rent));
+        println!(
+            "Total cost:      ~{}",
+            format_sol(amount_lamports + TX_FEE_ESTIMATE + announcement_rent)
+        );
+        if seed.is_none() {
+            println!();
+            println!(
+                "{}",
+                "The ephemeral key is random; pass --seed to get the same address when sending.".dimmed()
+            );
+        }
+        return Ok(());
+    }
+
     println!("Stealth address: {}", stealth_address);
     println!("Ephemeral key:   {}", hex::encode(computation.ephemeral_pubkey));
     println!("Commitment:      {}", hex::encode(&commitment[..8]));
@@ -64,34 +133,25 @@ pub async fn run(
     let payer = load_solana_keypair(keypair_path)?;
 
     // Connect to RPC
-    let client = RpcClient::new_with_commitment(rpc_url, CommitmentConfig::confirmed());
+    let client = RpcClient::new_with_commitment(rpc_url, commitment_config);
 
     // Check balance
-    let balance = client.get_balance(&payer.pubkey())?;
+    let balance = with_retry(|| client.get_balance(&payer.pubkey()))?;
     // Account for rent + fees (~0.003 SOL)
     let required = amount_lamports + 5_000_000;
     if balance < required {
         println!(
             "{}",
             format!(
-                "Insufficient balance. Have {} SOL, need {} SOL + fees",
-                balance as f64 / 1_000_000_000.0,
-                amount_sol
+                "Insufficient balance. Have {}, need {} + fees",
+                format_sol(balance),
+                format_sol(amount_lamports)
             )
             .red()
         );
         return Ok(());
     }
 
-    // Build instruction
-    let program_id: Pubkey = PROGRAM_ID.parse()?;
-
-    // Derive announcement PDA
-    let (announcement_pda, _bump) = Pubkey::find_program_address(
-        &[b"announcement", &computation.ephemeral_pubkey],
-        &program_id,
-    );
-
     // Anchor discriminator for "stealth_send_direct" + args
     // Updated to include commitment parameter
     let mut data = Vec::new();
@@ -103,6 +163,12 @@ pub async fn run(
     data.extend_from_slice(&commitment);
     data.extend_from_slice(&amount_lamports.to_le_bytes());
 
+    // Optional announcement index; the program ID stands in for "none"
+    let announcement_index = match current_announcement_index(&client, &program_id)? {
+        Some(index) => AccountMeta::new(index, false),
+        None => AccountMeta::new_readonly(program_id, false),
+    };
+
     let instruction = Instruction {
         program_id,
         accounts: vec![
@@ -110,12 +176,13 @@ pub async fn run(
             AccountMeta::new(stealth_address, false),    // stealth_address
             AccountMeta::new(announcement_pda, false),   // announcement
             AccountMeta::new_readonly(system_program::id(), false), // system_program
+            announcement_index,                          // announcement_index
         ],
         data,
     };
 
     // Build and send transaction
-    let recent_blockhash = client.get_latest_blockhash()?;
+    let recent_blockhash = with_retry(|| client.get_latest_blockhash())?;
     let transaction = Transaction::new_signed_with_payer(
         &[instruction],
         Some(&payer.pubkey()),
@@ -123,16 +190,24 @@ pub async fn run(
         recent_blockhash,
     );
 
-    println!("Sending {} SOL...", amount_sol);
-    let signature = client
-        .send_and_confirm_transaction(&transaction)
+    println!("Sending {}...", format_sol(amount_lamports));
+    let signature = send_and_confirm(&client, &transaction)
         .context("Failed to send transaction. Make sure the program is deployed.")?;
 
+    history::record(HistoryEntry::sent(
+        &scan_pubkey,
+        &spend_pubkey,
+        amount_lamports,
+        &computation.ephemeral_pubkey,
+        stealth_address,
+        signature,
+    ));
+
     println!();
     println!("{}", "Payment sent successfully!".green().bold());
     println!();
     println!("Transaction: {}", signature);
-    println!("Amount:      {} SOL", amount_sol);
+    println!("Amount:      {}", format_sol(amount_lamports));
     println!("Stealth addr: {}", stealth_address);
     println!();
     println!(
@@ -142,3 +217,96 @@ pub async fn run(
 
     Ok(())
 }
+
+/// Send `amount_sol` to `recipient` through the privacy pools
+///
+/// One `private_deposit` per denomination, each in its own transaction.
+/// A failure partway leaves the earlier deposits in place.
+pub async fn run_private(
+    rpc_url: &str,
+    commitment_config: CommitmentConfig,
+    keypair_path: Option<&str>,
+    recipient: &str,
+    amount_sol: &str,
+    dry_run: bool,
+    legacy: bool,
+) -> Result<()> {
+    println!("{}", "Preparing private payment...".cyan());
+
+    let amount_lamports = parse_sol(amount_sol)?;
+    let denominations = decompose_into_denominations(amount_lamports)?;
+
+    let (scan_pubkey, _) = parse_meta_address_with(recipient, legacy)
+        .context("Invalid meta-address format")?;
+
+    let split: Vec<String> = denominations.iter().map(|d| format_sol(*d)).collect();
+    let leaf_rent = Rent::default().minimum_balance(CommitmentLeaf::SIZE);
+    let costs = denominations.len() as u64 * (TX_FEE_ESTIMATE + leaf_rent);
+
+    println!("Amount:   {}", format_sol(amount_lamports));
+    println!("Deposits: {} ({})", denominations.len(), split.join(" + "));
+
+    if dry_run {
+        println!();
+        println!("{}", "Dry run: nothing will be signed or sent".yellow().bold());
+        println!("Network fees + rent: ~{}", format_sol(costs));
+        println!("Total cost:          ~{}", format_sol(amount_lamports + costs));
+        return Ok(());
+    }
+
+    let payer = load_solana_keypair(keypair_path)?;
+    let client = RpcClient::new_with_commitment(rpc_url, commitment_config);
+
+    // Every pool must exist and accept deposits before anything is sent
+    let mut fee_recipients = Vec::with_capacity(denominations.len());
+    for &denomination in &denominations {
+        let config = fetch_pool_config(&client, denomination)
+            .with_context(|| format!("No {} pool to deposit into", format_sol(denomination)))?;
+        if config.deposits_paused {
+            bail!("Deposits into the {} pool are paused", format_sol(denomination));
+        }
+        ensure_note_scheme(&fetch_pool(&client, denomination)?)
+            .with_context(|| format!("Can't deposit into the {} pool", format_sol(denomination)))?;
+        fee_recipients.push((config.fee_bps > 0).then_some(config.fee_recipient));
+    }
+
+    let balance = with_retry(|| client.get_balance(&payer.pubkey()))?;
+    if balance < amount_lamports + costs {
+        bail!(
+            "Insufficient balance. Have {}, need {} + ~{} fees and rent",
+            format_sol(balance),
+            format_sol(amount_lamports),
+            format_sol(costs)
+        );
+    }
+
+    let total = denominations.len();
+    for (i, (denomination, fee_recipient)) in denominations.into_iter().zip(fee_recipients).enumerate() {
+        let note = Note::random(denomination);
+        let instruction = private_deposit_instruction(payer.pubkey(), &scan_pubkey, note, fee_recipient)
+            .context("Recipient's scan key is not a valid curve point")?;
+
+        let recent_blockhash = with_retry(|| client.get_latest_blockhash())?;
+        let transaction = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&payer.pubkey()),
+            &[&payer],
+            recent_blockhash,
+        );
+
+        let signature = send_and_confirm(&client, &transaction).with_context(|| {
+            format!("Deposit {} of {} failed; earlier deposits went through", i + 1, total)
+        })?;
+        println!("  [{}/{}] {} deposited: {}", i + 1, total, format_sol(denomination), signature);
+    }
+
+    println!();
+    println!("{}", "Private payment sent!".green().bold());
+    println!();
+    println!(
+        "{}",
+        "The recipient finds the notes with 'scan' and withdraws them from the pools.".dimmed()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,169 @@
+//! Sweep every owned stealth address into a single destination
+//!
+//! Scans announcements like `scan`, then for each owned address with a
+//! balance submits a transfer signed by its DKSAP-derived `StealthSigner`.
+//! Each stealth address pays its own fee, and is emptied completely (a
+//! zero-data system account may drop to 0 lamports). The destination must
+//! end up rent-exempt, so a sweep that would leave a new destination below
+//! the minimum is skipped.
+//!
+//! Note: sweeping links all swept addresses to the destination on-chain.
+
+use std::collections::HashSet;
+
+use anyhow::{Result, Context, bail};
+use colored::Colorize;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    pubkey::Pubkey,
+    signer::Signer,
+    system_instruction,
+    transaction::Transaction,
+};
+
+use crate::commands::scan::{fetch_announcements, fetch_balances};
+use crate::crypto::{StealthKeys, scan_batch};
+use crate::rpc::{send_and_confirm, with_retry};
+use crate::secure_storage::{SecureKeyStorage, prompt_password};
+
+/// Fee for a single-signature transfer (0.000005 SOL)
+const FEE_ESTIMATE: u64 = 5_000;
+
+/// Lamports to move out of a stealth address holding `balance`, or `None`
+/// if the sweep can't go through
+///
+/// The stealth address pays `fee` and is emptied. The destination, currently
+/// holding `dest_balance`, must reach `rent_exempt_min` afterwards.
+pub(crate) fn sweep_amount(balance: u64, fee: u64, dest_balance: u64, rent_exempt_min: u64) -> Option<u64> {
+    let amount = balance.checked_sub(fee).filter(|&a| a > 0)?;
+    if dest_balance.saturating_add(amount) < rent_exempt_min {
+        return None;
+    }
+    Some(amount)
+}
+
+pub async fn run(rpc_url: &str, commitment_config: CommitmentConfig, to_address: &str) -> Result<()> {
+    let destination: Pubkey = to_address
+        .parse()
+        .context("Invalid destination address")?;
+
+    println!("{}", "Sweeping stealth balances...".cyan());
+
+    // Load encrypted stealth keys
+    let storage = SecureKeyStorage::new(SecureKeyStorage::default_path());
+
+    if !storage.exists() {
+        bail!(
+            "No stealth keys found. Run 'stealthsol keygen' first."
+        );
+    }
+
+    let password = prompt_password("Enter password to decrypt keys: ")?;
+    let key_data = storage.load(&password)
+        .context("Failed to decrypt keys. Wrong password?")?;
+
+    let keys = StealthKeys::from_secrets(&key_data.scan_secret, &key_data.spend_secret);
+
+    // Connect to RPC
+    let client = RpcClient::new_with_commitment(rpc_url, commitment_config);
+    let announcements = fetch_announcements(&client, None)?;
+
+    let candidates: Vec<([u8; 32], [u8; 32])> = announcements
+        .iter()
+        .map(|a| (a.ephemeral_pubkey, a.stealth_address.to_bytes()))
+        .collect();
+    let mut seen = HashSet::new();
+    let owned: Vec<_> = scan_batch(&keys, &candidates)
+        .into_iter()
+        .filter(|result| {
+            let stealth_pubkey = Pubkey::new_from_array(result.stealth_address);
+            seen.insert(stealth_pubkey) && stealth_pubkey != destination
+        })
+        .collect();
+    let owned_addresses: Vec<Pubkey> = owned
+        .iter()
+        .map(|result| Pubkey::new_from_array(result.stealth_address))
+        .collect();
+    let balances = fetch_balances(&client, &owned_addresses)?;
+
+    let rent_exempt_min = with_retry(|| client.get_minimum_balance_for_rent_exemption(0))?;
+    let mut dest_balance = with_retry(|| client.get_balance(&destination)).unwrap_or(0);
+
+    let mut total_swept: u64 = 0;
+    let mut swept_count = 0;
+    let mut failed_count = 0;
+
+    for ((result, stealth_pubkey), balance) in owned.iter().zip(owned_addresses).zip(balances) {
+        if balance == 0 {
+            continue;
+        }
+
+        let Some(amount) = sweep_amount(balance, FEE_ESTIMATE, dest_balance, rent_exempt_min) else {
+            println!(
+                "{} {} ({} SOL): too small to sweep",
+                "SKIP".yellow(),
+                stealth_pubkey,
+                balance as f64 / 1_000_000_000.0
+            );
+            failed_count += 1;
+            continue;
+        };
+
+        let outcome = result
+            .create_signer()
+            .map_err(|e| anyhow::anyhow!("Failed to create signer: {}", e))
+            .and_then(|signer| {
+                if signer.pubkey() != stealth_pubkey {
+                    bail!("Derived signer does not match stealth address");
+                }
+
+                let instruction = system_instruction::transfer(&stealth_pubkey, &destination, amount);
+                let recent_blockhash = with_retry(|| client.get_latest_blockhash())?;
+                let mut transaction = Transaction::new_with_payer(&[instruction], Some(&stealth_pubkey));
+                transaction.message.recent_blockhash = recent_blockhash;
+                signer
+                    .sign_transaction(&mut transaction)
+                    .map_err(|e| anyhow::anyhow!("Failed to sign sweep: {}", e))?;
+
+                send_and_confirm(&client, &transaction)
+                    .context("Failed to send sweep transaction")
+            });
+
+        match outcome {
+            Ok(signature) => {
+                println!(
+                    "{} {} -> {} SOL ({})",
+                    "OK".green(),
+                    stealth_pubkey,
+                    amount as f64 / 1_000_000_000.0,
+                    signature
+                );
+                total_swept += amount;
+                dest_balance += amount;
+                swept_count += 1;
+            }
+            Err(e) => {
+                println!("{} {}: {}", "FAIL".red(), stealth_pubkey, e);
+                failed_count += 1;
+            }
+        }
+    }
+
+    println!();
+    if swept_count == 0 && failed_count == 0 {
+        println!("{}", "No stealth balances to sweep.".yellow());
+        return Ok(());
+    }
+
+    println!("{}", "Sweep Summary".yellow().bold());
+    println!();
+    println!(
+        "Total swept: {} SOL",
+        format!("{:.9}", total_swept as f64 / 1_000_000_000.0).green()
+    );
+    println!("Addresses:   {} swept, {} failed", swept_count, failed_count);
+    println!("To:          {}", destination);
+
+    Ok(())
+}
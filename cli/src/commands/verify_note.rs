@@ -0,0 +1,78 @@
+//! Check that a stored note is spendable before paying for a withdrawal
+//!
+//! Recomputes the note's pool commitment, looks up its commitment leaf, and
+//! checks whether its nullifier has already been spent. Read-only.
+
+use anyhow::{Result, Context};
+use colored::Colorize;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+
+use crate::commands::pool_stats::parse_denomination;
+use crate::config::{decode_hex_32, format_sol};
+use crate::crypto::Note;
+use crate::pool::{commitment_leaf_pda, fetch_spent_nullifiers, note_commitment, note_nullifier_pda};
+
+/// Whether a note can still be withdrawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum NoteStatus {
+    Spendable,
+    AlreadySpent,
+    NotFound,
+}
+
+impl NoteStatus {
+    /// A spent nullifier wins: leaves stay on-chain after withdrawal
+    pub(crate) fn from_lookup(leaf_exists: bool, nullifier_spent: bool) -> Self {
+        match (leaf_exists, nullifier_spent) {
+            (_, true) => NoteStatus::AlreadySpent,
+            (false, false) => NoteStatus::NotFound,
+            (true, false) => NoteStatus::Spendable,
+        }
+    }
+}
+
+pub fn run(rpc_url: &str, commitment_config: CommitmentConfig, nullifier_hex: &str, secret_hex: &str, amount_sol: &str) -> Result<()> {
+    let note = Note {
+        nullifier: decode_hex_32(nullifier_hex).context("Invalid nullifier (expected 32 bytes hex)")?,
+        secret: decode_hex_32(secret_hex).context("Invalid secret (expected 32 bytes hex)")?,
+        amount: parse_denomination(amount_sol)?,
+    };
+
+    let commitment = note_commitment(&note);
+    let leaf = commitment_leaf_pda(note.amount, &commitment);
+    let nullifier = note_nullifier_pda(&note);
+
+    let client = RpcClient::new_with_commitment(rpc_url, commitment_config);
+    let leaf_exists = client
+        .get_account_with_commitment(&leaf, client.commitment())?
+        .value
+        .is_some_and(|account| account.owner == stealth::ID);
+    let nullifier_spent = fetch_spent_nullifiers(&client, &[nullifier])?[0];
+
+    println!();
+    println!("Pool:        {}", format_sol(note.amount));
+    println!("Commitment:  {}", hex::encode(commitment));
+    println!("Leaf:        {}", leaf);
+    println!("Nullifier:   {}", nullifier);
+    println!();
+
+    match NoteStatus::from_lookup(leaf_exists, nullifier_spent) {
+        NoteStatus::Spendable => {
+            println!("{}", "spendable".green().bold());
+        }
+        NoteStatus::AlreadySpent => {
+            println!("{}", "already spent".red().bold());
+            println!("{}", "This note's nullifier is on-chain; a withdrawal would fail.".dimmed());
+        }
+        NoteStatus::NotFound => {
+            println!("{}", "not found".yellow().bold());
+            println!(
+                "{}",
+                "No commitment leaf for this note. Check the nullifier, secret and amount.".dimmed()
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -1,19 +1,53 @@
-//! Withdraw funds from a stealth address
+//! Withdraw funds from a stealth address or a privacy pool note
+//!
+//! `--from` accepts either:
+//! - a stealth address: the DKSAP spending key signs a plain transfer, or
+//! - a pool commitment leaf (as printed by `scan`): the encrypted note is
+//!   decrypted and a `private_withdraw` is submitted to a fresh stealth address.
+//!
+//! Pool withdrawals in dev builds send a placeholder proof, which the program
+//! accepts without cryptographic verification. Production builds require an
+//! oracle attestation: generate the Noir withdraw proof from the note and the
+//! Merkle path, have the verifier service sign it, and include the matching
+//! Ed25519 instruction before `private_withdraw`. The attestation must cover
+//! `WithdrawPublicInputs::to_bytes()`, which ends with the stealth commitment,
+//! so it is only valid for the stealth address built here. That path is not
+//! wired here.
+//!
+//! `--fee-payer` lets a second keypair (e.g. a sponsor) pay the transaction fee
+//! and the rent for the nullifier and announcement accounts of a pool
+//! withdrawal, while the `--keypair` wallet signs as the relayer.
 
 use anyhow::{Result, Context, bail};
 use colored::Colorize;
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::{
+    account::Account,
     commitment_config::CommitmentConfig,
+    instruction::Instruction,
     pubkey::Pubkey,
     transaction::Transaction,
     system_instruction,
+    system_program,
     signer::Signer,
+    sysvar,
 };
 use borsh::BorshDeserialize;
-
-use crate::config::load_solana_keypair;
-use crate::crypto::{StealthKeys, scan_payment};
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use stealth::crypto::{compute_merkle_proof, compute_nullifier_hash, verify_merkle_proof};
+use stealth::instructions::{WithdrawProof, WithdrawPublicInputs};
+use stealth::state::{CommitmentLeaf, NullifierRecord, PoolConfig, StealthAnnouncement};
+use stealth::zk::verifier::VerificationOracle;
+
+use crate::commands::scan::current_announcement_index;
+use crate::config::{format_sol, load_solana_keypair, parse_meta_address_with, parse_sol};
+use crate::crypto::{
+    StealthKeys, Note, try_scan_payment, try_compute_stealth_address, compute_commitment, decrypt_note,
+    note_ephemeral_pubkey,
+};
+use crate::history::{self, HistoryEntry};
+use crate::pool::{commitment_leaf_pda, fetch_nullifier_statuses, fetch_pool, fetch_pool_leaves, pool_pda};
+use crate::rpc::{send_and_confirm, with_retry};
 use crate::secure_storage::{SecureKeyStorage, prompt_password};
 
 // Program ID (update after deployment)
@@ -46,12 +80,19 @@ impl Announcement {
     const SIZE: usize = 32 + 32 + 32 + 8 + 32 + 8 + 8 + 1; // 153 bytes
 }
 
+/// Placeholder proof size for dev builds (Groth16-sized, must be >= 64 bytes)
+const DEV_PROOF_SIZE: usize = 256;
+
+#[allow(clippy::too_many_arguments)]
 pub async fn run(
     rpc_url: &str,
+    commitment_config: CommitmentConfig,
     keypair_path: Option<&str>,
     from_address: &str,
     to_address: Option<&str>,
-    amount: Option<f64>,
+    amount: Option<&str>,
+    legacy: bool,
+    fee_payer_path: Option<&str>,
 ) -> Result<()> {
     println!("{}", "Preparing withdrawal...".cyan());
 
@@ -78,11 +119,22 @@ pub async fn run(
     let keys = StealthKeys::from_secrets(&key_data.scan_secret, &key_data.spend_secret);
 
     // Connect to RPC
-    let client = RpcClient::new_with_commitment(rpc_url, CommitmentConfig::confirmed());
+    let client = RpcClient::new_with_commitment(rpc_url, commitment_config);
+
+    // Pool note: --from is a commitment leaf owned by the program
+    if let Ok(account) = with_retry(|| client.get_account(&stealth_pubkey)) {
+        if account.owner == stealth::ID && account.data.len() == CommitmentLeaf::SIZE {
+            return withdraw_note(&client, &keys, keypair_path, fee_payer_path, &stealth_pubkey, &account, to_address, legacy);
+        }
+    }
+
+    if fee_payer_path.is_some() {
+        bail!("--fee-payer only applies to pool note withdrawals; a stealth address pays its own fee");
+    }
 
     // Find the announcement for this stealth address
     let program_id: Pubkey = PROGRAM_ID.parse()?;
-    let accounts = client.get_program_accounts(&program_id)?;
+    let accounts = with_retry(|| client.get_program_accounts(&program_id))?;
 
     println!("Searching {} program accounts for announcement...", accounts.len());
 
@@ -112,11 +164,12 @@ pub async fn run(
 
     // Derive spending key using DKSAP
     let payment_address_bytes = announcement.stealth_address.to_bytes();
-    let scan_result = scan_payment(
+    let scan_result = try_scan_payment(
         &keys,
         &announcement.ephemeral_pubkey,
         &payment_address_bytes,
     )
+    .context("Announcement is malformed")?
     .context("This stealth address doesn't belong to you - \
               could not derive matching spending key")?;
 
@@ -147,8 +200,8 @@ pub async fn run(
     };
 
     // Get current balance
-    let balance = client.get_balance(&stealth_pubkey)?;
-    println!("Stealth address balance: {} SOL", balance as f64 / 1_000_000_000.0);
+    let balance = with_retry(|| client.get_balance(&stealth_pubkey))?;
+    println!("Stealth address balance: {}", format_sol(balance));
 
     if balance == 0 {
         println!("{}", "No funds to withdraw.".yellow());
@@ -161,12 +214,12 @@ pub async fn run(
 
     let withdraw_lamports = match amount {
         Some(sol) => {
-            let lamports = (sol * 1_000_000_000.0) as u64;
-            if lamports + fee_estimate > balance {
+            let lamports = parse_sol(sol)?;
+            if lamports.saturating_add(fee_estimate) > balance {
                 bail!(
-                    "Insufficient balance. Have {} SOL, need {} SOL + fee",
-                    balance as f64 / 1_000_000_000.0,
-                    sol
+                    "Insufficient balance. Have {}, need {} + fee",
+                    format_sol(balance),
+                    format_sol(lamports)
                 );
             }
             lamports
@@ -188,30 +241,229 @@ pub async fn run(
     );
 
     // Build and sign transaction with stealth signer
-    let recent_blockhash = client.get_latest_blockhash()?;
-    let transaction = Transaction::new_signed_with_payer(
-        &[instruction],
-        Some(&stealth_pubkey),
-        &[&stealth_signer],
-        recent_blockhash,
-    );
+    let recent_blockhash = with_retry(|| client.get_latest_blockhash())?;
+    let mut transaction = Transaction::new_with_payer(&[instruction], Some(&stealth_pubkey));
+    transaction.message.recent_blockhash = recent_blockhash;
+    stealth_signer
+        .sign_transaction(&mut transaction)
+        .map_err(|e| anyhow::anyhow!("Failed to sign withdrawal: {}", e))?;
 
     println!();
-    println!("Withdrawing {} SOL to {}...",
-             withdraw_lamports as f64 / 1_000_000_000.0,
+    println!("Withdrawing {} to {}...",
+             format_sol(withdraw_lamports),
              destination);
 
-    let signature = client
-        .send_and_confirm_transaction(&transaction)
+    let signature = send_and_confirm(&client, &transaction)
         .context("Failed to send withdrawal transaction")?;
 
+    history::record(HistoryEntry::withdrawn(stealth_pubkey, destination, withdraw_lamports, signature));
+
     println!();
     println!("{}", "Withdrawal successful!".green().bold());
     println!();
     println!("Transaction: {}", signature);
-    println!("Amount:      {} SOL", withdraw_lamports as f64 / 1_000_000_000.0);
+    println!("Amount:      {}", format_sol(withdraw_lamports));
     println!("From:        {}", stealth_pubkey);
     println!("To:          {}", destination);
 
     Ok(())
 }
+
+/// Withdraw a privacy pool note via `private_withdraw`
+///
+/// Funds go to a fresh stealth address for `to_meta_address`, or for our own
+/// meta-address if none is given. Sweep it afterwards with `withdraw --from`.
+#[allow(clippy::too_many_arguments)]
+fn withdraw_note(
+    client: &RpcClient,
+    keys: &StealthKeys,
+    keypair_path: Option<&str>,
+    fee_payer_path: Option<&str>,
+    leaf_address: &Pubkey,
+    leaf_account: &Account,
+    to_meta_address: Option<&str>,
+    legacy: bool,
+) -> Result<()> {
+    let leaf = CommitmentLeaf::try_deserialize(&mut leaf_account.data.as_slice())
+        .context("Account is not a valid commitment leaf")?;
+
+    // Recover the note encrypted to our scan key
+    let ephemeral = note_ephemeral_pubkey(&leaf.encrypted_note);
+    let note = decrypt_note(&keys.scan_secret(), &ephemeral, &leaf.encrypted_note)
+        .context("This pool note isn't addressed to you (could not decrypt)")?;
+
+    let denomination = note.amount;
+    if commitment_leaf_pda(denomination, &leaf.commitment) != *leaf_address {
+        bail!("Note amount doesn't match the pool this leaf belongs to");
+    }
+
+    println!(
+        "Found note: {} at leaf index {}",
+        format_sol(denomination),
+        leaf.leaf_index
+    );
+
+    // Recipient meta-address (default: our own)
+    let (scan_pubkey, spend_pubkey) = match to_meta_address {
+        Some(meta) => parse_meta_address_with(meta, legacy).context("--to must be a stealth meta-address for pool notes")?,
+        None => keys.meta_address(),
+    };
+
+    // Rebuild the Merkle path against the current pool state
+    println!("Fetching pool leaves...");
+    let leaves = fetch_pool_leaves(client, denomination)?;
+    let pool = fetch_pool(client, denomination)?;
+
+    let public_inputs = build_withdraw_inputs(&note, &leaves, leaf.leaf_index, leaf.slot, &scan_pubkey, &spend_pubkey)?;
+
+    if !pool.is_valid_root(&public_inputs.merkle_root) {
+        bail!("Rebuilt Merkle root is not known to the pool - leaf set may be stale, try again");
+    }
+
+    // Skip doomed transactions: nullifier PDA exists once spent
+    if fetch_nullifier_statuses(client, denomination, &[public_inputs.nullifier_hash])?[0] {
+        bail!("This note has already been withdrawn (nullifier spent)");
+    }
+
+    let relayer = load_solana_keypair(keypair_path)?;
+    let fee_payer = fee_payer_path
+        .map(|path| load_solana_keypair(Some(path)))
+        .transpose()?;
+    let payer = fee_payer.as_ref().unwrap_or(&relayer);
+
+    let proof = WithdrawProof {
+        // Dev builds skip verification; production needs a real proof + attestation
+        proof: vec![1u8; DEV_PROOF_SIZE],
+        public_inputs,
+        attestation: None,
+    };
+
+    let mut accounts = private_withdraw_accounts(denomination, &relayer.pubkey(), &payer.pubkey(), &proof.public_inputs);
+    accounts.announcement_index = current_announcement_index(&client, &stealth::ID)?;
+
+    let stealth_address = proof.public_inputs.stealth_address;
+    let instruction = Instruction {
+        program_id: stealth::ID,
+        accounts: accounts.to_account_metas(None),
+        data: stealth::instruction::PrivateWithdraw {
+            denomination,
+            proof,
+            relayer_fee: 0,
+        }
+        .data(),
+    };
+
+    let recent_blockhash = with_retry(|| client.get_latest_blockhash())?;
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[payer, &relayer],
+        recent_blockhash,
+    );
+
+    println!();
+    println!("Withdrawing {} from pool to stealth address {}...",
+             format_sol(denomination),
+             stealth_address);
+    if fee_payer.is_some() {
+        println!("Fee payer:   {}", payer.pubkey());
+    }
+    println!(
+        "{}",
+        "Note: the signing wallets are linked to this withdrawal. Use a relayer for full privacy.".dimmed()
+    );
+
+    let signature = send_and_confirm(&client, &transaction)
+        .context("Failed to send private withdrawal transaction")?;
+
+    history::record(HistoryEntry::withdrawn(leaf_address, stealth_address, denomination, signature));
+
+    println!();
+    println!("{}", "Private withdrawal successful!".green().bold());
+    println!();
+    println!("Transaction: {}", signature);
+    println!("Amount:      {}", format_sol(denomination));
+    println!("To:          {} (stealth)", stealth_address);
+
+    Ok(())
+}
+
+/// Accounts for a `private_withdraw` signed by `relayer`, with `fee_payer`
+/// covering rent for the nullifier and announcement (may be the relayer)
+pub(crate) fn private_withdraw_accounts(
+    denomination: u64,
+    relayer: &Pubkey,
+    fee_payer: &Pubkey,
+    public_inputs: &WithdrawPublicInputs,
+) -> stealth::accounts::PrivateWithdraw {
+    let (nullifier, _) = NullifierRecord::derive_pda(denomination, &public_inputs.nullifier_hash);
+
+    stealth::accounts::PrivateWithdraw {
+        relayer: *relayer,
+        fee_payer: *fee_payer,
+        pool: pool_pda(denomination),
+        config: Pubkey::find_program_address(&[PoolConfig::SEED, &denomination.to_le_bytes()], &stealth::ID).0,
+        nullifier,
+        stealth_address: public_inputs.stealth_address,
+        announcement: Pubkey::find_program_address(
+            &[StealthAnnouncement::SEED, public_inputs.ephemeral_pubkey.as_ref()],
+            &stealth::ID,
+        )
+        .0,
+        relayer_fee_recipient: None,
+        oracle: Pubkey::find_program_address(&[VerificationOracle::SEED], &stealth::ID).0,
+        instructions_sysvar: sysvar::instructions::id(),
+        system_program: system_program::id(),
+        announcement_log: None,
+        protocol_fee_recipient: None,
+        relayer_account: None,
+        verification_key: None,
+        announcement_index: None,
+    }
+}
+
+/// Assemble the public inputs for a `private_withdraw` of `note`
+///
+/// Builds the Merkle path with `compute_merkle_proof` (checked locally with
+/// `verify_merkle_proof`), derives the nullifier hash, and computes a fresh
+/// stealth address + commitment for the recipient meta-address.
+/// `deposit_slot` is the leaf's recorded slot, checked against the pool's
+/// minimum note age.
+pub(crate) fn build_withdraw_inputs(
+    note: &Note,
+    leaves: &[[u8; 32]],
+    leaf_index: u64,
+    deposit_slot: u64,
+    scan_pubkey: &[u8; 32],
+    spend_pubkey: &[u8; 32],
+) -> Result<WithdrawPublicInputs> {
+    let index = leaf_index as usize;
+    if index >= leaves.len() {
+        bail!("Leaf index {} not found among {} pool leaves", leaf_index, leaves.len());
+    }
+
+    let (siblings, merkle_root) = compute_merkle_proof(leaves, index);
+    if !verify_merkle_proof(&leaves[index], index, &siblings, &merkle_root) {
+        bail!("Merkle path failed local verification");
+    }
+
+    let computation = try_compute_stealth_address(scan_pubkey, spend_pubkey)
+        .context("Invalid recipient meta-address")?;
+    let stealth_commitment = compute_commitment(
+        &computation.ephemeral_pubkey,
+        scan_pubkey,
+        spend_pubkey,
+        &computation.stealth_pubkey,
+    );
+
+    Ok(WithdrawPublicInputs {
+        merkle_root,
+        nullifier_hash: compute_nullifier_hash(&note.nullifier),
+        stealth_address: Pubkey::new_from_array(computation.stealth_pubkey),
+        ephemeral_pubkey: computation.ephemeral_pubkey,
+        scan_pubkey: *scan_pubkey,
+        spend_pubkey: *spend_pubkey,
+        stealth_commitment,
+        deposit_slot,
+    })
+}
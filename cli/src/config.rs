@@ -2,7 +2,7 @@
 //!
 //! Note: Some functions kept for backwards compatibility or future use.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::fs;
 use anyhow::{Result, Context, bail};
 use serde::{Deserialize, Serialize};
@@ -11,6 +11,7 @@ use zeroize::Zeroize;
 /// Default directory for stealth keys
 const STEALTH_DIR: &str = ".stealth";
 const KEYS_FILE: &str = "keys.json";
+const VIEW_KEY_FILE: &str = "view_key.json";
 
 /// Stored stealth keys (encrypted at rest in production)
 #[derive(Serialize, Deserialize, Clone)]
@@ -34,6 +35,25 @@ impl Drop for StoredKeys {
     }
 }
 
+/// Exported view key: scan secret + spend pubkey, NEVER the spend secret
+///
+/// Enough to detect incoming payments (watch-only), not to spend them.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ViewKeyFile {
+    /// Scan secret key (hex encoded)
+    pub scan_secret: String,
+    /// Spend public key (hex encoded)
+    pub spend_pubkey: String,
+    /// Creation timestamp
+    pub created_at: String,
+}
+
+impl Drop for ViewKeyFile {
+    fn drop(&mut self) {
+        self.scan_secret.zeroize();
+    }
+}
+
 /// Get the stealth directory path
 pub fn stealth_dir() -> PathBuf {
     dirs::home_dir()
@@ -46,6 +66,11 @@ pub fn keys_file() -> PathBuf {
     stealth_dir().join(KEYS_FILE)
 }
 
+/// Get the default view key file path
+pub fn view_key_file() -> PathBuf {
+    stealth_dir().join(VIEW_KEY_FILE)
+}
+
 /// Check if keys exist
 pub fn keys_exist() -> bool {
     keys_file().exists()
@@ -88,6 +113,93 @@ pub fn load_keys() -> Result<StoredKeys> {
     Ok(keys)
 }
 
+/// Save a view key file with restrictive permissions
+pub fn save_view_key(path: &Path, view_key: &ViewKeyFile) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context("Failed to create view key directory")?;
+    }
+
+    let json = serde_json::to_string_pretty(view_key)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::write(path, &json)?;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+    }
+
+    #[cfg(not(unix))]
+    {
+        fs::write(path, &json)?;
+    }
+
+    Ok(())
+}
+
+/// Load a view key file, returning (scan_secret, spend_pubkey)
+pub fn load_view_key(path: &Path) -> Result<([u8; 32], [u8; 32])> {
+    if !path.exists() {
+        bail!(
+            "No view key found at {:?}. Create one with 'stealthsol export-view-key --output <file>'.",
+            path
+        );
+    }
+
+    let contents = fs::read_to_string(path).context("Failed to read view key file")?;
+    parse_view_key(&contents)
+}
+
+/// Parse a view key from either the JSON file format or the
+/// `<scan_secret_hex>:<spend_pubkey_hex>` line printed by export-view-key
+///
+/// Refuses anything that carries a spend secret (plain or encrypted key files),
+/// so a watch-only host can never end up holding spending capability.
+pub fn parse_view_key(contents: &str) -> Result<([u8; 32], [u8; 32])> {
+    let contents = contents.trim();
+
+    let (scan_hex, spend_pubkey_hex) = if contents.starts_with('{') {
+        let value: serde_json::Value =
+            serde_json::from_str(contents).context("Failed to parse view key file")?;
+
+        if value.get("spend_secret").is_some() || value.get("ciphertext").is_some() {
+            bail!(
+                "Refusing to load: this file contains a spend secret (full key file), not a view key.
+                 Export a view key with 'stealthsol export-view-key --output <file>'."
+            );
+        }
+
+        let view_key: ViewKeyFile =
+            serde_json::from_value(value).context("Invalid view key file")?;
+        (view_key.scan_secret.clone(), view_key.spend_pubkey.clone())
+    } else {
+        let parts: Vec<&str> = contents.split(':').collect();
+        if parts.len() != 2 {
+            bail!("Invalid view key: expected <scan_secret>:<spend_pubkey>");
+        }
+        (parts[0].to_string(), parts[1].to_string())
+    };
+
+    let scan_secret = decode_hex_32(&scan_hex).context("Invalid scan secret in view key")?;
+    let spend_pubkey = decode_hex_32(&spend_pubkey_hex).context("Invalid spend pubkey in view key")?;
+
+    Ok((scan_secret, spend_pubkey))
+}
+
+/// Decode a 32-byte hex string
+pub(crate) fn decode_hex_32(input: &str) -> Result<[u8; 32]> {
+    let mut bytes = hex::decode(input.trim())?;
+    if bytes.len() != 32 {
+        let len = bytes.len();
+        bytes.zeroize();
+        bail!("Expected 32 bytes, got {}", len);
+    }
+
+    let mut out = [0u8; 32];
+    out.copy_from_slice(&bytes);
+    bytes.zeroize();
+    Ok(out)
+}
+
 /// Load Solana keypair from file or default location
 pub fn load_solana_keypair(path: Option<&str>) -> Result<solana_sdk::signature::Keypair> {
     let keypair_path = match path {
@@ -116,27 +228,175 @@ pub fn load_solana_keypair(path: Option<&str>) -> Result<solana_sdk::signature::
     Ok(keypair)
 }
 
+/// Bytes of SHA-256 appended to the 64 key bytes of a meta-address
+pub const META_ADDRESS_CHECKSUM_LEN: usize = 4;
+
+/// Why a meta-address failed to parse
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum MetaAddressError {
+    #[error("Invalid meta-address: not valid base58")]
+    InvalidEncoding,
+    #[error("Invalid meta-address length: expected {expected} bytes, got {got}")]
+    InvalidLength { expected: usize, got: usize },
+    #[error("Meta-address checksum mismatch - it was probably mistyped")]
+    ChecksumMismatch,
+    #[error("Meta-address has no checksum (legacy format) - ask for a new one or pass --legacy")]
+    MissingChecksum,
+}
+
+/// Checksum over the scan and spend public keys
+fn meta_address_checksum(keys: &[u8]) -> [u8; META_ADDRESS_CHECKSUM_LEN] {
+    use sha2::{Digest, Sha256};
+
+    let digest = Sha256::digest(keys);
+    let mut checksum = [0u8; META_ADDRESS_CHECKSUM_LEN];
+    checksum.copy_from_slice(&digest[..META_ADDRESS_CHECKSUM_LEN]);
+    checksum
+}
+
 /// Format a meta-address for display
+///
+/// Encodes `scan || spend || checksum`, where the checksum is the first 4
+/// bytes of SHA-256 over the 64 key bytes, so typos are caught on parse.
 pub fn format_meta_address(scan_pubkey: &[u8; 32], spend_pubkey: &[u8; 32]) -> String {
-    let mut combined = [0u8; 64];
+    let mut combined = [0u8; 64 + META_ADDRESS_CHECKSUM_LEN];
     combined[..32].copy_from_slice(scan_pubkey);
-    combined[32..].copy_from_slice(spend_pubkey);
+    combined[32..64].copy_from_slice(spend_pubkey);
+    let checksum = meta_address_checksum(&combined[..64]);
+    combined[64..].copy_from_slice(&checksum);
     format!("stealth:{}", bs58::encode(&combined).into_string())
 }
 
-/// Parse a meta-address from string
+/// Parse a checksummed meta-address from string
 pub fn parse_meta_address(input: &str) -> Result<([u8; 32], [u8; 32])> {
+    parse_meta_address_with(input, false)
+}
+
+/// Parse a meta-address, optionally accepting the legacy 64-byte format
+///
+/// Legacy addresses carry no checksum, so a mistyped character still
+/// decodes to valid-looking keys. Only accept them when the user opts in.
+/// Errors are `MetaAddressError`s.
+pub fn parse_meta_address_with(input: &str, allow_legacy: bool) -> Result<([u8; 32], [u8; 32])> {
+    use subtle::ConstantTimeEq;
+
     let encoded = input.strip_prefix("stealth:").unwrap_or(input);
-    let bytes = bs58::decode(encoded).into_vec()?;
+    let bytes = bs58::decode(encoded)
+        .into_vec()
+        .map_err(|_| MetaAddressError::InvalidEncoding)?;
 
-    if bytes.len() != 64 {
-        bail!("Invalid meta-address length: expected 64 bytes, got {}", bytes.len());
+    match bytes.len() {
+        len if len == 64 + META_ADDRESS_CHECKSUM_LEN => {
+            let expected = meta_address_checksum(&bytes[..64]);
+            if !bool::from(expected.ct_eq(&bytes[64..])) {
+                return Err(MetaAddressError::ChecksumMismatch.into());
+            }
+        }
+        64 if allow_legacy => {}
+        64 => return Err(MetaAddressError::MissingChecksum.into()),
+        got => {
+            return Err(MetaAddressError::InvalidLength {
+                expected: 64 + META_ADDRESS_CHECKSUM_LEN,
+                got,
+            }
+            .into())
+        }
     }
 
     let mut scan_pubkey = [0u8; 32];
     let mut spend_pubkey = [0u8; 32];
     scan_pubkey.copy_from_slice(&bytes[..32]);
-    spend_pubkey.copy_from_slice(&bytes[32..]);
+    spend_pubkey.copy_from_slice(&bytes[32..64]);
 
     Ok((scan_pubkey, spend_pubkey))
 }
+
+/// Lamports per SOL
+const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+/// Format lamports as SOL without float rounding, e.g. "1.5 SOL"
+///
+/// Trailing zeros are trimmed, keeping at least one decimal.
+pub fn format_sol(lamports: u64) -> String {
+    let whole = lamports / LAMPORTS_PER_SOL;
+    let frac = format!("{:09}", lamports % LAMPORTS_PER_SOL);
+    let frac = frac.trim_end_matches('0');
+    let frac = if frac.is_empty() { "0" } else { frac };
+    format!("{}.{} SOL", whole, frac)
+}
+
+/// Split an amount into the standard pool denominations, largest first
+///
+/// The denominations follow a 1-5-10 pattern, so greedy uses the fewest
+/// deposits. Fails if the amount is zero or leaves a remainder no pool can
+/// take.
+pub fn decompose_into_denominations(lamports: u64) -> Result<Vec<u64>> {
+    use stealth::state::DEFAULT_DENOMINATIONS;
+
+    if lamports == 0 {
+        bail!("Amount must be greater than zero");
+    }
+
+    let mut parts = Vec::new();
+    let mut remaining = lamports;
+    for &denomination in DEFAULT_DENOMINATIONS.iter().rev() {
+        while remaining >= denomination {
+            parts.push(denomination);
+            remaining -= denomination;
+        }
+    }
+
+    if remaining != 0 {
+        let valid: Vec<String> = DEFAULT_DENOMINATIONS.iter().map(|d| format_sol(*d)).collect();
+        bail!(
+            "{} can't be sent privately: {} is left over after splitting into pool denominations ({})",
+            format_sol(lamports),
+            format_sol(remaining),
+            valid.join(", ")
+        );
+    }
+
+    Ok(parts)
+}
+
+/// Parse a user-supplied SOL amount like "0.5" into lamports
+///
+/// Digits past the 9th decimal are rounded half-up. Rejects signs, exponents,
+/// empty parts and amounts above `u64::MAX` lamports.
+pub fn parse_sol(input: &str) -> Result<u64> {
+    let input = input.trim();
+    let (whole, frac) = match input.split_once('.') {
+        Some((whole, frac)) => (whole, Some(frac)),
+        None => (input, None),
+    };
+
+    let is_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    let valid = is_digits(whole)
+        && frac.map_or(!whole.is_empty(), |f| !f.is_empty() && is_digits(f));
+    if !valid {
+        bail!("Invalid SOL amount: {:?}", input);
+    }
+
+    let overflow = || anyhow::anyhow!("SOL amount too large: {}", input);
+
+    let mut lamports: u64 = 0;
+    for digit in whole.bytes() {
+        lamports = lamports
+            .checked_mul(10)
+            .and_then(|l| l.checked_add((digit - b'0') as u64))
+            .ok_or_else(overflow)?;
+    }
+    lamports = lamports.checked_mul(LAMPORTS_PER_SOL).ok_or_else(overflow)?;
+
+    let frac = frac.unwrap_or("").as_bytes();
+    let mut frac_lamports: u64 = 0;
+    for i in 0..9 {
+        let digit = frac.get(i).map_or(0, |d| (d - b'0') as u64);
+        frac_lamports = frac_lamports * 10 + digit;
+    }
+    if frac.get(9).is_some_and(|&d| d >= b'5') {
+        frac_lamports += 1;
+    }
+
+    lamports.checked_add(frac_lamports).ok_or_else(overflow)
+}
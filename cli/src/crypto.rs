@@ -7,23 +7,37 @@
 //! - Constant-time comparison for cryptographic values
 //! - Zeroization of sensitive data on drop
 //! - BIP-39 mnemonic support for key recovery
+//!
+//! Everything except `StealthSigner` is pure curve/hash/AEAD code. Without
+//! the `native` feature the Solana signer and the rayon batch scan are left
+//! out, so the module also builds for `wasm32` (see `wasm`).
 
 use curve25519_dalek::{
     constants::ED25519_BASEPOINT_POINT,
     edwards::{CompressedEdwardsY, EdwardsPoint},
     scalar::Scalar,
 };
+#[cfg(feature = "native")]
 use ed25519_dalek::{
     ExpandedSecretKey, PublicKey as DalekPublicKey,
     Signature as DalekSignature,
 };
-use sha2::{Digest, Sha256, Sha512};
-use rand::RngCore;
+use chacha20poly1305::{
+    aead::{Aead, NewAead, Payload},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use sha2::{Digest, Sha256};
+#[cfg(feature = "native")]
+use sha2::Sha512;
+use rand::{CryptoRng, RngCore};
 use zeroize::Zeroize;
 use subtle::ConstantTimeEq;
+#[cfg(feature = "native")]
 use solana_sdk::{
+    packet::PACKET_DATA_SIZE,
     pubkey::Pubkey,
     signature::{Signature, Signer, SignerError},
+    transaction::Transaction,
 };
 
 /// Domain separator matching on-chain implementation
@@ -33,8 +47,24 @@ const DOMAIN_SEPARATOR: &[u8] = b"stealthsol_v1";
 const COMMITMENT_DOMAIN: &[u8] = b"stealthsol_commitment_v1";
 
 /// Domain separator for nonce derivation in signing
+#[cfg(feature = "native")]
 const NONCE_DOMAIN: &[u8] = b"stealthsol_nonce_v1";
 
+/// Domain separator for note encryption key derivation
+const NOTE_KEY_DOMAIN: &[u8] = b"stealthsol_note_key_v1";
+
+/// Domain separator for note encryption nonce derivation
+const NOTE_NONCE_DOMAIN: &[u8] = b"stealthsol_note_nonce_v1";
+
+/// Size of an on-chain encrypted note blob
+pub const ENCRYPTED_NOTE_SIZE: usize = 128;
+
+/// Size of a serialized note: nullifier(32) || secret(32) || amount(8)
+pub const NOTE_PLAINTEXT_SIZE: usize = 72;
+
+/// Poly1305 authentication tag size
+const NOTE_TAG_SIZE: usize = 16;
+
 /// Minimum payment amount in lamports (must match on-chain)
 pub const MIN_PAYMENT_LAMPORTS: u64 = 1_000_000;
 
@@ -196,15 +226,30 @@ impl StealthKeys {
         })
     }
 
-    /// Generate a new random mnemonic and derive keys from it
+    /// Generate a new random 24-word mnemonic and derive keys from it
     pub fn generate_with_mnemonic() -> anyhow::Result<(Self, String)> {
+        Self::generate_with_mnemonic_words(24)
+    }
+
+    /// Generate a new random mnemonic of `word_count` words and derive keys from it
+    ///
+    /// Supports the BIP-39 lengths 12/15/18/21/24 (128-256 bits of entropy).
+    pub fn generate_with_mnemonic_words(word_count: usize) -> anyhow::Result<(Self, String)> {
         use bip39::Mnemonic;
 
-        // Generate 256 bits of entropy for a 24-word mnemonic
+        if !matches!(word_count, 12 | 15 | 18 | 21 | 24) {
+            anyhow::bail!(
+                "Unsupported mnemonic length: {} words (use 12, 15, 18, 21 or 24)",
+                word_count
+            );
+        }
+
+        // Every 3 words encode 32 bits of entropy (plus checksum)
+        let entropy_len = word_count / 3 * 4;
         let mut entropy = [0u8; 32];
-        rand::RngCore::fill_bytes(&mut rand::rngs::OsRng, &mut entropy);
+        rand::RngCore::fill_bytes(&mut rand::rngs::OsRng, &mut entropy[..entropy_len]);
 
-        let mnemonic = Mnemonic::from_entropy(&entropy)
+        let mnemonic = Mnemonic::from_entropy(&entropy[..entropy_len])
             .map_err(|e| anyhow::anyhow!("Failed to generate mnemonic: {}", e))?;
 
         // Zeroize entropy after use
@@ -285,9 +330,42 @@ fn hash_to_scalar(data: &[u8]) -> Scalar {
 
 /// Decompress a compressed Edwards Y point
 fn decompress_point(bytes: &[u8; 32]) -> Option<EdwardsPoint> {
-    CompressedEdwardsY::from_slice(bytes).decompress()
+    try_decompress_point(bytes).ok()
+}
+
+/// Decompress a compressed Edwards Y point, failing with `PointNotOnCurve`
+pub fn try_decompress_point(bytes: &[u8; 32]) -> Result<EdwardsPoint, CryptoError> {
+    CompressedEdwardsY::from_slice(bytes)
+        .decompress()
+        .ok_or(CryptoError::PointNotOnCurve)
+}
+
+/// Which input made a stealth-address computation or scan fail
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CryptoError {
+    /// The ephemeral public key (R) is not a curve point
+    InvalidEphemeralPoint,
+    /// The recipient's scan public key (S) is not a curve point
+    InvalidScanPoint,
+    /// The recipient's spend public key (B) is not a curve point
+    InvalidSpendPoint,
+    /// A 32-byte value is not a compressed point on the curve
+    PointNotOnCurve,
 }
 
+impl std::fmt::Display for CryptoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            CryptoError::InvalidEphemeralPoint => "Ephemeral public key is not a valid curve point",
+            CryptoError::InvalidScanPoint => "Scan public key is not a valid curve point",
+            CryptoError::InvalidSpendPoint => "Spend public key is not a valid curve point",
+            CryptoError::PointNotOnCurve => "Bytes are not a point on the curve",
+        })
+    }
+}
+
+impl std::error::Error for CryptoError {}
+
 // ============================================================================
 // Stealth Address Computation (Sender Side)
 // ============================================================================
@@ -314,36 +392,104 @@ pub fn compute_stealth_address(
     scan_pubkey: &[u8; 32],
     spend_pubkey: &[u8; 32],
 ) -> Option<StealthAddressComputation> {
-    use rand::rngs::OsRng;
-    let g = ED25519_BASEPOINT_POINT;
+    try_compute_stealth_address(scan_pubkey, spend_pubkey).ok()
+}
 
+/// `compute_stealth_address`, reporting which recipient key is invalid
+pub fn try_compute_stealth_address(
+    scan_pubkey: &[u8; 32],
+    spend_pubkey: &[u8; 32],
+) -> Result<StealthAddressComputation, CryptoError> {
     // Generate ephemeral keypair using OS entropy
-    let mut rng = OsRng;
-    let ephemeral_scalar = random_scalar(&mut rng);
-    let ephemeral_point = &ephemeral_scalar * &g;
+    try_compute_stealth_address_with_rng(scan_pubkey, spend_pubkey, &mut rand::rngs::OsRng)
+}
+
+/// `compute_stealth_address` drawing the ephemeral key from `rng`
+///
+/// Lets tests seed an RNG for reproducible payments. Outside tests use
+/// `compute_stealth_address`: a predictable `rng` makes the payment linkable.
+pub fn compute_stealth_address_with_rng<R: RngCore + CryptoRng>(
+    scan_pubkey: &[u8; 32],
+    spend_pubkey: &[u8; 32],
+    rng: &mut R,
+) -> Option<StealthAddressComputation> {
+    try_compute_stealth_address_with_rng(scan_pubkey, spend_pubkey, rng).ok()
+}
+
+/// `compute_stealth_address_with_rng`, reporting which recipient key is invalid
+pub fn try_compute_stealth_address_with_rng<R: RngCore + CryptoRng>(
+    scan_pubkey: &[u8; 32],
+    spend_pubkey: &[u8; 32],
+    rng: &mut R,
+) -> Result<StealthAddressComputation, CryptoError> {
+    let ephemeral_scalar = random_scalar(rng);
+
+    stealth_address_from_ephemeral(&ephemeral_scalar, scan_pubkey, spend_pubkey)
+}
+
+/// Compute a stealth address with an ephemeral key derived from `seed`
+///
+/// The ephemeral scalar is `hash_to_scalar(seed || scan_pubkey)`, so retrying
+/// a failed send with the same seed yields the same stealth address and
+/// announcement instead of leaking a second ephemeral key.
+///
+/// The seed MUST be unique per intended payment: reusing it for another
+/// payment to the same recipient reuses the stealth address, linking them.
+pub fn compute_stealth_address_deterministic(
+    scan_pubkey: &[u8; 32],
+    spend_pubkey: &[u8; 32],
+    seed: &[u8; 32],
+) -> Option<StealthAddressComputation> {
+    try_compute_stealth_address_deterministic(scan_pubkey, spend_pubkey, seed).ok()
+}
+
+/// `compute_stealth_address_deterministic`, reporting which recipient key is invalid
+pub fn try_compute_stealth_address_deterministic(
+    scan_pubkey: &[u8; 32],
+    spend_pubkey: &[u8; 32],
+    seed: &[u8; 32],
+) -> Result<StealthAddressComputation, CryptoError> {
+    let mut input = [0u8; 64];
+    input[..32].copy_from_slice(seed);
+    input[32..].copy_from_slice(scan_pubkey);
+    let ephemeral_scalar = hash_to_scalar(&input);
+    input.zeroize();
+
+    stealth_address_from_ephemeral(&ephemeral_scalar, scan_pubkey, spend_pubkey)
+}
+
+/// Derive the stealth address for a given ephemeral scalar r
+fn stealth_address_from_ephemeral(
+    ephemeral_scalar: &Scalar,
+    scan_pubkey: &[u8; 32],
+    spend_pubkey: &[u8; 32],
+) -> Result<StealthAddressComputation, CryptoError> {
+    let g = ED25519_BASEPOINT_POINT;
+
+    let ephemeral_point = ephemeral_scalar * &g;
     let ephemeral_pubkey = ephemeral_point.compress().to_bytes();
 
     // S = decompress(scan_pubkey)
-    let scan_point = decompress_point(scan_pubkey)?;
+    let scan_point = decompress_point(scan_pubkey).ok_or(CryptoError::InvalidScanPoint)?;
 
     // Shared secret: ss = r·S
-    let shared_secret = &ephemeral_scalar * &scan_point;
+    let shared_secret = ephemeral_scalar * &scan_point;
     let shared_secret_bytes = shared_secret.compress().to_bytes();
 
     // H(ss) as scalar
     let hash_scalar = hash_to_scalar(&shared_secret_bytes);
 
     // B = decompress(spend_pubkey)
-    let spend_point = decompress_point(spend_pubkey)?;
+    let spend_point = decompress_point(spend_pubkey).ok_or(CryptoError::InvalidSpendPoint)?;
 
     // P = B + H(ss)·G
     let stealth_point = &spend_point + &(&hash_scalar * &g);
     let stealth_pubkey = stealth_point.compress().to_bytes();
 
-    Some(StealthAddressComputation {
+    Ok(StealthAddressComputation {
         stealth_pubkey,
         ephemeral_pubkey,
-        ephemeral_secret: SecretScalar::from_scalar(&ephemeral_scalar),
+        ephemeral_secret: SecretScalar::from_scalar(ephemeral_scalar),
     })
 }
 
@@ -361,11 +507,13 @@ pub struct ScanResult {
 
 impl ScanResult {
     /// Create a signer for this stealth address
+    #[cfg(feature = "native")]
     pub fn create_signer(&self) -> Result<StealthSigner, SignerError> {
         StealthSigner::from_scalar(&self.spending_key.to_scalar())
     }
 
     /// Get the spending key scalar (for internal use)
+    #[cfg(feature = "native")]
     pub(crate) fn spending_scalar(&self) -> Scalar {
         self.spending_key.to_scalar()
     }
@@ -390,10 +538,21 @@ pub fn scan_payment(
     ephemeral_pubkey: &[u8; 32],
     payment_address: &[u8; 32],
 ) -> Option<ScanResult> {
+    try_scan_payment(keys, ephemeral_pubkey, payment_address).ok().flatten()
+}
+
+/// `scan_payment`, separating malformed inputs from payments that aren't ours
+///
+/// `Ok(None)` means the announcement is well-formed but for someone else.
+pub fn try_scan_payment(
+    keys: &StealthKeys,
+    ephemeral_pubkey: &[u8; 32],
+    payment_address: &[u8; 32],
+) -> Result<Option<ScanResult>, CryptoError> {
     let g = ED25519_BASEPOINT_POINT;
 
     // R = decompress(ephemeral_pubkey)
-    let ephemeral_point = decompress_point(ephemeral_pubkey)?;
+    let ephemeral_point = decompress_point(ephemeral_pubkey).ok_or(CryptoError::InvalidEphemeralPoint)?;
 
     // Shared secret: ss = s·R
     let shared_secret = &keys.scan_secret() * &ephemeral_point;
@@ -403,7 +562,7 @@ pub fn scan_payment(
     let hash_scalar = hash_to_scalar(&shared_secret_bytes);
 
     // Expected: P' = B + H(ss)·G
-    let spend_point = decompress_point(&keys.spend_pubkey)?;
+    let spend_point = decompress_point(&keys.spend_pubkey).ok_or(CryptoError::InvalidSpendPoint)?;
     let expected_stealth = &spend_point + &(&hash_scalar * &g);
     let expected_bytes = expected_stealth.compress().to_bytes();
 
@@ -411,15 +570,43 @@ pub fn scan_payment(
     if bool::from(expected_bytes.ct_eq(payment_address)) {
         // Derive private key: p = b + H(ss)
         let spending_scalar = &keys.spend_secret() + &hash_scalar;
-        Some(ScanResult {
+        Ok(Some(ScanResult {
             stealth_address: *payment_address,
             spending_key: SecretScalar::from_scalar(&spending_scalar),
-        })
+        }))
     } else {
-        None
+        Ok(None)
     }
 }
 
+/// Scan many announcements in parallel
+///
+/// Each `(ephemeral_pubkey, payment_address)` pair is checked with
+/// `scan_payment` on the rayon thread pool; the `s·R` multiply dominates and
+/// announcements are independent. Matches are returned in input order.
+#[cfg(feature = "native")]
+pub fn scan_batch(keys: &StealthKeys, announcements: &[([u8; 32], [u8; 32])]) -> Vec<ScanResult> {
+    use rayon::prelude::*;
+
+    announcements
+        .par_iter()
+        .filter_map(|(ephemeral_pubkey, payment_address)| {
+            scan_payment(keys, ephemeral_pubkey, payment_address)
+        })
+        .collect()
+}
+
+/// Scan many announcements sequentially (no thread pool on wasm32)
+#[cfg(not(feature = "native"))]
+pub fn scan_batch(keys: &StealthKeys, announcements: &[([u8; 32], [u8; 32])]) -> Vec<ScanResult> {
+    announcements
+        .iter()
+        .filter_map(|(ephemeral_pubkey, payment_address)| {
+            scan_payment(keys, ephemeral_pubkey, payment_address)
+        })
+        .collect()
+}
+
 /// Check if a payment is for this recipient (view-key only, no spending key)
 ///
 /// This can be used with just the scan secret and spend public key.
@@ -451,6 +638,266 @@ pub fn check_payment(
     bool::from(expected_bytes.ct_eq(payment_address))
 }
 
+// ============================================================================
+// Scan Hints (Optional Prefilter)
+// ============================================================================
+//
+// A recipient's on-chain `ScanHint` is a Bloom filter of 8-byte tags that
+// senders may add per payment. Checking a tag costs one Poseidon hash
+// instead of the `s·R` multiply, so announcements the filter rules out can
+// skip `scan_payment`. The tag only needs the public scan key, so it links
+// the payment to anyone holding the meta-address: this is a speed-up the
+// recipient may ignore, never a privacy feature, and a full scan remains
+// the only way to find payments from senders that didn't write a hint.
+
+/// Tag for the recipient's scan hint: `Poseidon(scan_pubkey, ephemeral_pubkey)[..8]`
+#[cfg(feature = "native")]
+pub fn sender_scan_tag(scan_pubkey: &[u8; 32], ephemeral_pubkey: &[u8; 32]) -> [u8; 8] {
+    let hash = stealth::crypto::poseidon_hash_2(scan_pubkey, ephemeral_pubkey);
+    hash[..8].try_into().expect("8-byte prefix")
+}
+
+/// The same tag from the recipient's side, given the scan secret
+#[cfg(feature = "native")]
+pub fn scan_tag(scan_secret: &Scalar, ephemeral_pubkey: &[u8; 32]) -> [u8; 8] {
+    let scan_pubkey = (scan_secret * &ED25519_BASEPOINT_POINT).compress().to_bytes();
+    sender_scan_tag(&scan_pubkey, ephemeral_pubkey)
+}
+
+/// `scan_batch`, skipping announcements whose tag `hint` rules out
+///
+/// Only finds payments whose sender wrote a hint; run `scan_batch` over
+/// everything to catch the rest.
+#[cfg(feature = "native")]
+pub fn scan_batch_with_hint(
+    keys: &StealthKeys,
+    hint: &stealth::state::ScanHint,
+    announcements: &[([u8; 32], [u8; 32])],
+) -> Vec<ScanResult> {
+    let candidates: Vec<([u8; 32], [u8; 32])> = announcements
+        .iter()
+        .filter(|(ephemeral_pubkey, _)| {
+            hint.may_contain(&sender_scan_tag(&keys.scan_pubkey, ephemeral_pubkey))
+        })
+        .copied()
+        .collect();
+    scan_batch(keys, &candidates)
+}
+
+// ============================================================================
+// View Key (Watch-Only Scanning)
+// ============================================================================
+
+/// Watch-only key set: scan secret + spend pubkey
+///
+/// Can detect payments via `check_payment` but cannot derive spending keys,
+/// since the spend secret is never part of it.
+pub struct ViewKey {
+    /// Scan secret key (s) - zeroized on drop
+    scan_secret: SecretScalar,
+    /// Scan public key (S = s·G)
+    pub scan_pubkey: [u8; 32],
+    /// Spend public key (B)
+    pub spend_pubkey: [u8; 32],
+}
+
+impl ViewKey {
+    /// Build a view key from the scan secret and spend public key
+    pub fn from_parts(scan_secret_bytes: &[u8; 32], spend_pubkey: &[u8; 32]) -> Self {
+        let scan_scalar = Scalar::from_bytes_mod_order(*scan_secret_bytes);
+        let scan_pubkey = (&scan_scalar * &ED25519_BASEPOINT_POINT).compress().to_bytes();
+
+        Self {
+            scan_secret: SecretScalar::from_scalar(&scan_scalar),
+            scan_pubkey,
+            spend_pubkey: *spend_pubkey,
+        }
+    }
+
+    /// Check whether a payment is addressed to this view key's owner
+    pub fn check_payment(&self, ephemeral_pubkey: &[u8; 32], payment_address: &[u8; 32]) -> bool {
+        check_payment(
+            &self.scan_secret.to_scalar(),
+            &self.spend_pubkey,
+            ephemeral_pubkey,
+            payment_address,
+        )
+    }
+}
+
+// ============================================================================
+// Encrypted Notes (Deposit Secrets for the Recipient)
+// ============================================================================
+
+/// Deposit secrets needed to later withdraw from a privacy pool
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Note {
+    pub nullifier: [u8; 32],
+    pub secret: [u8; 32],
+    pub amount: u64,
+}
+
+impl Note {
+    /// Fresh note for a deposit of `amount`
+    ///
+    /// The nullifier and secret are random 253-bit values (little-endian, as
+    /// Poseidon reads them), so both are canonical BN254 scalars.
+    pub fn random(amount: u64) -> Self {
+        use rand::rngs::OsRng;
+
+        let mut note = Note { nullifier: [0u8; 32], secret: [0u8; 32], amount };
+        OsRng.fill_bytes(&mut note.nullifier);
+        OsRng.fill_bytes(&mut note.secret);
+        note.nullifier[31] &= 0x1f;
+        note.secret[31] &= 0x1f;
+        note
+    }
+
+    /// Serialize as `nullifier(32) || secret(32) || amount(8, LE)`
+    ///
+    /// The returned buffer holds secrets; callers must zeroize it.
+    pub fn to_plaintext(&self) -> [u8; NOTE_PLAINTEXT_SIZE] {
+        let mut plaintext = [0u8; NOTE_PLAINTEXT_SIZE];
+        plaintext[..32].copy_from_slice(&self.nullifier);
+        plaintext[32..64].copy_from_slice(&self.secret);
+        plaintext[64..].copy_from_slice(&self.amount.to_le_bytes());
+        plaintext
+    }
+}
+
+impl Drop for Note {
+    fn drop(&mut self) {
+        self.nullifier.zeroize();
+        self.secret.zeroize();
+    }
+}
+
+/// Derive the note cipher and nonce from a DKSAP shared secret
+///
+/// The ephemeral key is fresh per note, so a nonce derived from the shared
+/// secret is never reused under the same key.
+fn note_cipher(shared_secret_bytes: &[u8; 32]) -> (ChaCha20Poly1305, [u8; 12]) {
+    let mut key_bytes: [u8; 32] = Sha256::new()
+        .chain_update(NOTE_KEY_DOMAIN)
+        .chain_update(shared_secret_bytes)
+        .finalize()
+        .into();
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(&key_bytes));
+    key_bytes.zeroize();
+
+    let nonce_hash = Sha256::new()
+        .chain_update(NOTE_NONCE_DOMAIN)
+        .chain_update(shared_secret_bytes)
+        .finalize();
+    let mut nonce = [0u8; 12];
+    nonce.copy_from_slice(&nonce_hash[..12]);
+
+    (cipher, nonce)
+}
+
+/// Encrypt a note for a recipient (sender side)
+///
+/// Blob layout (128 bytes):
+/// `ephemeral_pubkey(32) || ChaCha20-Poly1305(note)(72 + 16 tag) || zero padding(8)`
+///
+/// The ephemeral pubkey is also bound as associated data. The note is
+/// consumed so its secrets are wiped as soon as it has been serialized.
+pub fn encrypt_note(scan_pubkey: &[u8; 32], note: Note) -> Option<[u8; ENCRYPTED_NOTE_SIZE]> {
+    let mut plaintext = note.to_plaintext();
+    drop(note);
+    encrypt_note_plaintext(scan_pubkey, &mut plaintext)
+}
+
+/// Encrypt an already-serialized note, zeroizing `plaintext` before returning
+///
+/// The buffer is wiped on every path, including failures.
+pub fn encrypt_note_plaintext(
+    scan_pubkey: &[u8; 32],
+    plaintext: &mut [u8; NOTE_PLAINTEXT_SIZE],
+) -> Option<[u8; ENCRYPTED_NOTE_SIZE]> {
+    use rand::rngs::OsRng;
+
+    let Some(scan_point) = decompress_point(scan_pubkey) else {
+        plaintext.zeroize();
+        return None;
+    };
+
+    let ephemeral_scalar = random_scalar(&mut OsRng);
+    let ephemeral_pubkey = (&ephemeral_scalar * &ED25519_BASEPOINT_POINT).compress().to_bytes();
+
+    // Shared secret: ss = r·S
+    let mut shared_secret_bytes = (&ephemeral_scalar * &scan_point).compress().to_bytes();
+    let (cipher, nonce) = note_cipher(&shared_secret_bytes);
+    shared_secret_bytes.zeroize();
+
+    let sealed = cipher.encrypt(
+        Nonce::from_slice(&nonce),
+        Payload { msg: &plaintext[..], aad: &ephemeral_pubkey },
+    );
+    plaintext.zeroize();
+    let sealed = sealed.ok()?;
+
+    let mut blob = [0u8; ENCRYPTED_NOTE_SIZE];
+    blob[..32].copy_from_slice(&ephemeral_pubkey);
+    blob[32..32 + sealed.len()].copy_from_slice(&sealed);
+    Some(blob)
+}
+
+/// Ephemeral pubkey embedded at the start of an encrypted note blob
+pub fn note_ephemeral_pubkey(ciphertext: &[u8; ENCRYPTED_NOTE_SIZE]) -> [u8; 32] {
+    let mut ephemeral = [0u8; 32];
+    ephemeral.copy_from_slice(&ciphertext[..32]);
+    ephemeral
+}
+
+/// Decrypt a note addressed to this scan key (recipient side)
+///
+/// Returns `None` if the blob was not encrypted for this recipient or has
+/// been tampered with (auth-tag mismatch).
+pub fn decrypt_note(
+    scan_secret: &Scalar,
+    ephemeral_pubkey: &[u8; 32],
+    ciphertext: &[u8; ENCRYPTED_NOTE_SIZE],
+) -> Option<Note> {
+    if !bool::from(ciphertext[..32].ct_eq(ephemeral_pubkey)) {
+        return None;
+    }
+
+    let ephemeral_point = decompress_point(ephemeral_pubkey)?;
+
+    // Shared secret: ss = s·R
+    let mut shared_secret_bytes = (scan_secret * &ephemeral_point).compress().to_bytes();
+    let (cipher, nonce) = note_cipher(&shared_secret_bytes);
+    shared_secret_bytes.zeroize();
+
+    let sealed = &ciphertext[32..32 + NOTE_PLAINTEXT_SIZE + NOTE_TAG_SIZE];
+    let mut plaintext = cipher
+        .decrypt(
+            Nonce::from_slice(&nonce),
+            Payload { msg: sealed, aad: ephemeral_pubkey },
+        )
+        .ok()?;
+
+    if plaintext.len() != NOTE_PLAINTEXT_SIZE {
+        plaintext.zeroize();
+        return None;
+    }
+
+    let mut nullifier = [0u8; 32];
+    let mut secret = [0u8; 32];
+    let mut amount = [0u8; 8];
+    nullifier.copy_from_slice(&plaintext[..32]);
+    secret.copy_from_slice(&plaintext[32..64]);
+    amount.copy_from_slice(&plaintext[64..]);
+    plaintext.zeroize();
+
+    Some(Note {
+        nullifier,
+        secret,
+        amount: u64::from_le_bytes(amount),
+    })
+}
+
 // ============================================================================
 // Stealth Signer - Proper ed25519 signing with DKSAP-derived keys
 // ============================================================================
@@ -464,15 +911,22 @@ pub fn check_payment(
 /// This implementation uses ed25519-dalek's ExpandedSecretKey to sign
 /// with our exact scalar, ensuring signatures are valid for the
 /// DKSAP-derived public key.
+///
+/// Nonces are hedged: each signature derives a fresh nonce prefix from
+/// `SHA512(NONCE_DOMAIN || scalar || random)`, so a fault in the message
+/// path can't make two signatures share `r`, and a weak RNG still leaves
+/// the deterministic part. Signatures over the same message therefore differ.
+#[cfg(feature = "native")]
 pub struct StealthSigner {
     /// The Solana public key
     pubkey: Pubkey,
-    /// The expanded secret key for signing
-    expanded: ExpandedSecretKey,
+    /// The DKSAP-derived secret scalar (zeroized on drop)
+    scalar_bytes: [u8; 32],
     /// The ed25519-dalek public key
     dalek_pubkey: DalekPublicKey,
 }
 
+#[cfg(feature = "native")]
 impl StealthSigner {
     /// Create a signer from a DKSAP-derived scalar
     ///
@@ -482,47 +936,70 @@ impl StealthSigner {
         let public_point = scalar * &g;
         let public_bytes = public_point.compress().to_bytes();
 
-        // Create expanded secret key
-        // Format: [scalar_bytes (32) | nonce_prefix (32)]
-        // The nonce prefix is derived deterministically for consistent signatures
-        let scalar_bytes = scalar.to_bytes();
+        let dalek_pubkey = DalekPublicKey::from_bytes(&public_bytes)
+            .map_err(|e| SignerError::Custom(format!("Invalid pubkey: {}", e)))?;
+
+        Ok(Self {
+            pubkey: Pubkey::new_from_array(public_bytes),
+            scalar_bytes: scalar.to_bytes(),
+            dalek_pubkey,
+        })
+    }
+
+    /// Build a signing key with a fresh hedged nonce prefix
+    ///
+    /// Format: [scalar_bytes (32) | nonce_prefix (32)], where
+    /// nonce_prefix = SHA512(domain || scalar || random)[..32]
+    fn expanded_key(&self) -> Result<ExpandedSecretKey, SignerError> {
+        let mut random = [0u8; 32];
+        rand::rngs::OsRng.fill_bytes(&mut random);
 
-        // Derive nonce prefix: SHA512(domain || scalar)
-        // This provides the randomness needed for signature nonce generation
         let mut nonce_hasher = Sha512::new();
         nonce_hasher.update(NONCE_DOMAIN);
-        nonce_hasher.update(&scalar_bytes);
-        let nonce_hash = nonce_hasher.finalize();
+        nonce_hasher.update(self.scalar_bytes);
+        nonce_hasher.update(random);
+        let mut nonce_hash: [u8; 64] = nonce_hasher.finalize().into();
 
         let mut expanded_bytes = [0u8; 64];
-        expanded_bytes[..32].copy_from_slice(&scalar_bytes);
+        expanded_bytes[..32].copy_from_slice(&self.scalar_bytes);
         expanded_bytes[32..].copy_from_slice(&nonce_hash[..32]);
 
         let expanded = ExpandedSecretKey::from_bytes(&expanded_bytes)
-            .map_err(|e| SignerError::Custom(format!("Invalid scalar: {}", e)))?;
-
-        let dalek_pubkey = DalekPublicKey::from_bytes(&public_bytes)
-            .map_err(|e| SignerError::Custom(format!("Invalid pubkey: {}", e)))?;
+            .map_err(|e| SignerError::Custom(format!("Invalid scalar: {}", e)));
 
         // Zeroize sensitive data
-        let mut scalar_bytes = scalar_bytes;
-        let mut expanded_bytes = expanded_bytes;
-        scalar_bytes.zeroize();
+        random.zeroize();
+        nonce_hash.zeroize();
         expanded_bytes.zeroize();
 
-        Ok(Self {
-            pubkey: Pubkey::new_from_array(public_bytes),
-            expanded,
-            dalek_pubkey,
-        })
+        expanded
     }
 
     /// Create from a ScanResult
     pub fn from_scan_result(result: &ScanResult) -> Result<Self, SignerError> {
         Self::from_scalar(&result.spending_scalar())
     }
+
+    /// Add this stealth address's signature to `tx`
+    ///
+    /// Signs over the transaction's current message and blockhash, leaving
+    /// other signers' signatures in place. Fails if the stealth address
+    /// isn't one of the transaction's required signers.
+    pub fn sign_transaction(&self, tx: &mut Transaction) -> Result<(), SignerError> {
+        let required = tx.message.header.num_required_signatures as usize;
+        if !tx.message.account_keys.iter().take(required).any(|key| *key == self.pubkey) {
+            return Err(SignerError::Custom(format!(
+                "Stealth address {} is not a required signer of this transaction",
+                self.pubkey
+            )));
+        }
+
+        let recent_blockhash = tx.message.recent_blockhash;
+        tx.try_partial_sign(&[self], recent_blockhash)
+    }
 }
 
+#[cfg(feature = "native")]
 impl Signer for StealthSigner {
     fn pubkey(&self) -> Pubkey {
         self.pubkey
@@ -532,8 +1009,21 @@ impl Signer for StealthSigner {
         Ok(self.pubkey)
     }
 
+    /// Signs transaction-sized messages only: empty messages and anything
+    /// larger than a packet are rejected
     fn try_sign_message(&self, message: &[u8]) -> Result<Signature, SignerError> {
-        let sig: DalekSignature = self.expanded.sign(message, &self.dalek_pubkey);
+        if message.is_empty() {
+            return Err(SignerError::InvalidInput("Refusing to sign an empty message".to_string()));
+        }
+        if message.len() > PACKET_DATA_SIZE {
+            return Err(SignerError::InvalidInput(format!(
+                "Message is {} bytes; at most {} can be signed",
+                message.len(),
+                PACKET_DATA_SIZE
+            )));
+        }
+
+        let sig: DalekSignature = self.expanded_key()?.sign(message, &self.dalek_pubkey);
         Ok(Signature::from(sig.to_bytes()))
     }
 
@@ -542,13 +1032,22 @@ impl Signer for StealthSigner {
     }
 }
 
+#[cfg(feature = "native")]
+impl Drop for StealthSigner {
+    fn drop(&mut self) {
+        self.scalar_bytes.zeroize();
+    }
+}
+
 // Implement PartialEq for Signer trait requirements
+#[cfg(feature = "native")]
 impl PartialEq for StealthSigner {
     fn eq(&self, other: &Self) -> bool {
         self.pubkey == other.pubkey
     }
 }
 
+#[cfg(feature = "native")]
 impl std::fmt::Debug for StealthSigner {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("StealthSigner")
@@ -561,7 +1060,7 @@ impl std::fmt::Debug for StealthSigner {
 // Tests
 // ============================================================================
 
-#[cfg(test)]
+#[cfg(all(test, feature = "native"))]
 mod tests {
     use super::*;
 
@@ -697,6 +1196,76 @@ mod tests {
         assert!(!is_wrong);
     }
 
+    #[test]
+    fn test_note_encryption_roundtrip() {
+        let keys = StealthKeys::generate();
+        let note = Note {
+            nullifier: [7u8; 32],
+            secret: [9u8; 32],
+            amount: 1_000_000_000,
+        };
+
+        let blob = encrypt_note(&keys.scan_pubkey, note.clone()).unwrap();
+        let ephemeral = note_ephemeral_pubkey(&blob);
+
+        let decrypted = decrypt_note(&keys.scan_secret(), &ephemeral, &blob).unwrap();
+        assert_eq!(decrypted, note);
+
+        // Another recipient cannot decrypt
+        let other = StealthKeys::generate();
+        assert!(decrypt_note(&other.scan_secret(), &ephemeral, &blob).is_none());
+    }
+
+    #[test]
+    fn test_note_plaintext_wiped_after_encryption() {
+        let keys = StealthKeys::generate();
+        let note = Note {
+            nullifier: [3u8; 32],
+            secret: [4u8; 32],
+            amount: 500_000_000,
+        };
+
+        let mut plaintext = note.to_plaintext();
+        let blob = encrypt_note_plaintext(&keys.scan_pubkey, &mut plaintext).unwrap();
+        assert_eq!(plaintext, [0u8; NOTE_PLAINTEXT_SIZE]);
+
+        let ephemeral = note_ephemeral_pubkey(&blob);
+        assert_eq!(decrypt_note(&keys.scan_secret(), &ephemeral, &blob).unwrap(), note);
+
+        // Also wiped when encryption fails (scan key not a curve point)
+        let invalid_key = (0u8..=255)
+            .map(|b| {
+                let mut key = [0u8; 32];
+                key[0] = b;
+                key
+            })
+            .find(|key| decompress_point(key).is_none())
+            .unwrap();
+        let mut plaintext = note.to_plaintext();
+        assert!(encrypt_note_plaintext(&invalid_key, &mut plaintext).is_none());
+        assert_eq!(plaintext, [0u8; NOTE_PLAINTEXT_SIZE]);
+    }
+
+    #[test]
+    fn test_note_decryption_rejects_tampering() {
+        let keys = StealthKeys::generate();
+        let note = Note {
+            nullifier: [1u8; 32],
+            secret: [2u8; 32],
+            amount: 42,
+        };
+
+        let blob = encrypt_note(&keys.scan_pubkey, note).unwrap();
+        let ephemeral = note_ephemeral_pubkey(&blob);
+
+        let mut tampered = blob;
+        tampered[40] ^= 0x01;
+        assert!(decrypt_note(&keys.scan_secret(), &ephemeral, &tampered).is_none());
+
+        // Empty (unset) notes decrypt to nothing
+        assert!(decrypt_note(&keys.scan_secret(), &[0u8; 32], &[0u8; 128]).is_none());
+    }
+
     #[test]
     fn test_commitment_computation() {
         let ephemeral = [1u8; 32];
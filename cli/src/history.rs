@@ -0,0 +1,242 @@
+//! Local transaction history
+//!
+//! Every confirmed `send` and `withdraw` appends one JSON line to
+//! `~/.stealth/history.jsonl`, and `rescan` adds the payments and pool notes
+//! it recovers from chain. The file only holds what this machine already
+//! knew when it signed: recipients are recorded as a hash of their
+//! meta-address, never the meta-address itself, so a leaked history can't be
+//! matched against the on-chain registry. Like the key files, it is created
+//! owner-only (0600).
+
+use std::collections::HashSet;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, Context};
+use chrono::{NaiveDate, TimeZone, Utc};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::config::stealth_dir;
+
+const HISTORY_FILE: &str = "history.jsonl";
+
+/// One recorded operation
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct HistoryEntry {
+    /// Unix timestamp (seconds) when the transaction confirmed
+    pub timestamp: i64,
+    #[serde(flatten)]
+    pub event: HistoryEvent,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum HistoryEvent {
+    /// Payment to a stealth address
+    Sent {
+        /// SHA-256 of scan_pubkey || spend_pubkey (hex)
+        recipient_hash: String,
+        amount: u64,
+        /// Ephemeral public key (hex)
+        ephemeral_pubkey: String,
+        stealth_address: String,
+        signature: String,
+    },
+    /// Withdrawal from a stealth address or pool note
+    Withdrawn {
+        from: String,
+        to: String,
+        amount: u64,
+        signature: String,
+    },
+    /// Incoming payment or pool note recovered by `rescan`
+    Received {
+        /// Stealth address, or the commitment leaf for a pool note
+        address: String,
+        amount: u64,
+        pool_note: bool,
+    },
+}
+
+impl HistoryEntry {
+    /// Entry for a send confirmed now
+    pub fn sent(
+        scan_pubkey: &[u8; 32],
+        spend_pubkey: &[u8; 32],
+        amount: u64,
+        ephemeral_pubkey: &[u8; 32],
+        stealth_address: impl ToString,
+        signature: impl ToString,
+    ) -> Self {
+        Self {
+            timestamp: Utc::now().timestamp(),
+            event: HistoryEvent::Sent {
+                recipient_hash: recipient_hash(scan_pubkey, spend_pubkey),
+                amount,
+                ephemeral_pubkey: hex::encode(ephemeral_pubkey),
+                stealth_address: stealth_address.to_string(),
+                signature: signature.to_string(),
+            },
+        }
+    }
+
+    /// Entry for a withdrawal confirmed now
+    pub fn withdrawn(from: impl ToString, to: impl ToString, amount: u64, signature: impl ToString) -> Self {
+        Self {
+            timestamp: Utc::now().timestamp(),
+            event: HistoryEvent::Withdrawn {
+                from: from.to_string(),
+                to: to.to_string(),
+                amount,
+                signature: signature.to_string(),
+            },
+        }
+    }
+
+    /// Entry for a payment or note found on chain at `timestamp`
+    pub fn received(address: impl ToString, amount: u64, pool_note: bool, timestamp: i64) -> Self {
+        Self {
+            timestamp,
+            event: HistoryEvent::Received {
+                address: address.to_string(),
+                amount,
+                pool_note,
+            },
+        }
+    }
+
+    pub fn is_sent(&self) -> bool {
+        matches!(self.event, HistoryEvent::Sent { .. })
+    }
+
+    pub fn is_withdrawn(&self) -> bool {
+        matches!(self.event, HistoryEvent::Withdrawn { .. })
+    }
+
+    /// Address of a `Received` entry
+    pub fn received_address(&self) -> Option<&str> {
+        match &self.event {
+            HistoryEvent::Received { address, .. } => Some(address),
+            _ => None,
+        }
+    }
+}
+
+/// Hash identifying a recipient without revealing their meta-address
+pub fn recipient_hash(scan_pubkey: &[u8; 32], spend_pubkey: &[u8; 32]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(scan_pubkey);
+    hasher.update(spend_pubkey);
+    hex::encode(hasher.finalize())
+}
+
+/// Get the history file path
+pub fn history_file() -> PathBuf {
+    stealth_dir().join(HISTORY_FILE)
+}
+
+/// Append an entry, creating the file owner-only if needed
+pub fn append_entry_to(path: &Path, entry: &HistoryEntry) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context("Failed to create stealth directory")?;
+    }
+
+    let mut options = OpenOptions::new();
+    options.create(true).append(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut file = options.open(path).context("Failed to open history file")?;
+    let mut line = serde_json::to_string(entry)?;
+    line.push('\n');
+    file.write_all(line.as_bytes()).context("Failed to write history entry")?;
+
+    Ok(())
+}
+
+/// Load all entries, oldest first; unreadable lines are skipped
+pub fn load_entries_from(path: &Path) -> Result<Vec<HistoryEntry>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(path).context("Failed to read history file")?;
+    Ok(content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Record a confirmed operation in the default history file
+///
+/// The transaction already landed, so a failure here only warns.
+pub fn record(entry: HistoryEntry) {
+    if let Err(e) = append_entry_to(&history_file(), &entry) {
+        eprintln!("{} could not record history: {}", "Warning:".yellow(), e);
+    }
+}
+
+/// Append `Received` entries whose address isn't recorded yet
+///
+/// Safe to repeat: an address already in the file (or earlier in `entries`)
+/// is skipped. Returns how many entries were added.
+pub fn merge_received(path: &Path, entries: Vec<HistoryEntry>) -> Result<usize> {
+    let mut known: HashSet<String> = load_entries_from(path)?
+        .iter()
+        .filter_map(|e| e.received_address().map(str::to_string))
+        .collect();
+
+    let mut added = 0;
+    for entry in entries {
+        let Some(address) = entry.received_address() else { continue };
+        if known.insert(address.to_string()) {
+            append_entry_to(path, &entry)?;
+            added += 1;
+        }
+    }
+
+    Ok(added)
+}
+
+/// Keep entries matching the `history` command filters
+///
+/// `sent`, `withdrawn` and `received` select event kinds (none means all);
+/// `since` is an inclusive unix timestamp.
+pub fn filter_entries(
+    entries: Vec<HistoryEntry>,
+    sent: bool,
+    withdrawn: bool,
+    received: bool,
+    since: Option<i64>,
+) -> Vec<HistoryEntry> {
+    let all_kinds = !sent && !withdrawn && !received;
+    entries
+        .into_iter()
+        .filter(|e| {
+            all_kinds
+                || if e.is_sent() {
+                    sent
+                } else if e.is_withdrawn() {
+                    withdrawn
+                } else {
+                    received
+                }
+        })
+        .filter(|e| since.is_none_or(|t| e.timestamp >= t))
+        .collect()
+}
+
+/// Parse a `--since` date (YYYY-MM-DD) as UTC midnight
+pub fn parse_since(date: &str) -> Result<i64> {
+    let day = NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+        .with_context(|| format!("Invalid date '{}', expected YYYY-MM-DD", date))?;
+    let midnight = day.and_hms_opt(0, 0, 0).expect("midnight is a valid time");
+    Ok(Utc.from_utc_datetime(&midnight).timestamp())
+}
@@ -41,6 +41,25 @@ mod e2e_tests {
         assert_eq!(keys.spend_pubkey, recovered.spend_pubkey);
     }
 
+    /// Test every supported mnemonic length round-trips to the same keys
+    #[test]
+    fn test_mnemonic_word_counts() {
+        for word_count in [12, 15, 18, 21, 24] {
+            let (keys, mnemonic) = StealthKeys::generate_with_mnemonic_words(word_count)
+                .expect("Should generate keys with mnemonic");
+            assert_eq!(mnemonic.split_whitespace().count(), word_count);
+
+            let recovered = StealthKeys::from_mnemonic(&mnemonic, "")
+                .expect("Should recover keys from mnemonic");
+            assert_eq!(keys.scan_pubkey, recovered.scan_pubkey);
+            assert_eq!(keys.spend_pubkey, recovered.spend_pubkey);
+        }
+
+        for word_count in [0, 11, 13, 25] {
+            assert!(StealthKeys::generate_with_mnemonic_words(word_count).is_err());
+        }
+    }
+
     /// Test encrypted key storage: save → load → decrypt
     #[test]
     fn test_encrypted_storage_lifecycle() {
@@ -75,6 +94,37 @@ mod e2e_tests {
         assert!(wrong_result.is_err());
     }
 
+    /// Test re-encrypting stored keys under a new password
+    #[test]
+    fn test_reencrypt_rotates_password() {
+        let temp_dir = tempdir().expect("Should create temp dir");
+        let storage_path = temp_dir.path().join("test_keys.enc");
+        let storage = SecureKeyStorage::new(storage_path.clone());
+
+        let old_password = "Old@Password123";
+        let new_password = "New@Password456";
+
+        let keys = StealthKeys::generate();
+        let (scan_secret, spend_secret) = keys.export_secrets();
+        let (scan_pubkey, spend_pubkey) = keys.meta_address();
+        let key_data = KeyData { scan_secret, spend_secret, scan_pubkey, spend_pubkey };
+        storage.save(&key_data, old_password).expect("Should save keys");
+
+        // Wrong old password fails without touching the file
+        let before = std::fs::read(&storage_path).unwrap();
+        let err = storage.reencrypt("Wrong@Password1", new_password).unwrap_err();
+        assert!(err.to_string().contains("Current password is incorrect"));
+        assert_eq!(std::fs::read(&storage_path).unwrap(), before);
+
+        storage.reencrypt(old_password, new_password).expect("Should re-encrypt");
+
+        assert!(storage.load(old_password).is_err());
+        let loaded = storage.load(new_password).expect("Should load with new password");
+        assert_eq!(loaded.scan_secret, scan_secret);
+        assert_eq!(loaded.spend_secret, spend_secret);
+        assert!(!storage_path.with_extension("enc.tmp").exists());
+    }
+
     /// Test meta-address encoding/decoding roundtrip
     #[test]
     fn test_meta_address_roundtrip() {
@@ -0,0 +1,17 @@
+//! StealthSol client crypto as a library
+//!
+//! The `stealthsol` binary compiles its own modules; this target exists for
+//! the `wasm` feature, which exposes the pure-crypto half of `crypto` to the
+//! browser so the frontend can prepare stealth addresses and proof inputs
+//! without reimplementing DKSAP. With default features it is empty.
+
+// op_ref warnings are common with curve25519-dalek ergonomics
+#![allow(clippy::op_ref)]
+#![allow(clippy::needless_borrows_for_generic_args)]
+#![allow(dead_code)] // Items only the binary uses (e.g. the ephemeral secret)
+
+#[cfg(feature = "wasm")]
+pub mod crypto;
+
+#[cfg(feature = "wasm")]
+pub mod wasm;
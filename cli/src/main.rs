@@ -7,6 +7,7 @@
 #![allow(clippy::needless_borrow)]
 #![allow(clippy::redundant_closure)]
 #![allow(clippy::redundant_locals)]
+#![allow(clippy::result_large_err)] // solana_client::ClientError is large and used as-is by rpc::with_retry
 #![allow(dead_code)] // Public API items may not be used internally
 #![allow(unused_variables)]
 #![allow(unused_mut)]
@@ -17,6 +18,9 @@ use anyhow::Result;
 mod commands;
 mod config;
 mod crypto;
+mod history;
+mod pool;
+mod rpc;
 mod secure_storage;
 
 #[cfg(test)]
@@ -62,6 +66,20 @@ struct Cli {
     /// Path to keypair file
     #[arg(long, global = true)]
     keypair: Option<String>,
+
+    /// Print machine-readable JSON (info, address, balance, pool-stats,
+    /// relayers, derive-address)
+    #[arg(long, global = true)]
+    json: bool,
+
+    /// Retries for transient RPC failures (timeouts, rate limits)
+    #[arg(long, global = true, default_value_t = rpc::DEFAULT_MAX_RETRIES)]
+    max_retries: u32,
+
+    /// Commitment level for RPC reads and transaction confirmation:
+    /// processed (freshest, may roll back), confirmed, or finalized (slowest)
+    #[arg(long, global = true, value_enum, default_value_t, alias = "commitment-level")]
+    commitment: rpc::Commitment,
 }
 
 #[derive(Subcommand)]
@@ -76,6 +94,10 @@ enum Commands {
         #[arg(short, long, default_value = "true")]
         mnemonic: bool,
 
+        /// Number of words in the recovery phrase (12, 15, 18, 21 or 24)
+        #[arg(long, default_value_t = 24)]
+        words: usize,
+
         /// Import from existing recovery phrase
         #[arg(long)]
         import_mnemonic: Option<String>,
@@ -92,6 +114,13 @@ enum Commands {
         label: String,
     },
 
+    /// Change the label on your registered meta-address
+    Relabel {
+        /// New label (max 32 bytes; empty clears it)
+        #[arg(short, long)]
+        label: String,
+    },
+
     /// Show your stealth meta-address
     Address,
 
@@ -103,7 +132,43 @@ enum Commands {
 
         /// Amount of SOL to send
         #[arg(short, long)]
-        amount: f64,
+        amount: String,
+
+        /// Hex seed for a reproducible ephemeral key, so a retried send reuses
+        /// the same stealth address. Must be unique per payment.
+        #[arg(long)]
+        seed: Option<String>,
+
+        /// Print the stealth address, commitment, announcement PDA and fees
+        /// without signing or sending anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Accept a meta-address without a checksum (older format). A typo
+        /// in such an address can't be detected.
+        #[arg(long)]
+        legacy: bool,
+
+        /// Deposit into the privacy pools instead, split into pool
+        /// denominations, with each note encrypted to the recipient
+        #[arg(long, conflicts_with = "seed")]
+        private: bool,
+    },
+
+    /// Compute a one-time stealth address for a meta-address (offline)
+    DeriveAddress {
+        /// Recipient's meta-address
+        #[arg(short, long)]
+        to: String,
+
+        /// Hex seed for a deterministic ephemeral key (same seed, same address).
+        /// Must be unique per payment.
+        #[arg(long)]
+        seed: Option<String>,
+
+        /// Accept a meta-address without a checksum (older format)
+        #[arg(long)]
+        legacy: bool,
     },
 
     /// Scan for incoming stealth payments
@@ -111,69 +176,257 @@ enum Commands {
         /// Start from this slot (default: scan all)
         #[arg(long)]
         from_slot: Option<u64>,
+
+        /// Read recent announcements from the on-chain announcement logs
+        /// instead of every announcement account. Only logged withdrawals
+        /// are indexed, and the logs keep the most recent entries only.
+        #[arg(long, requires = "from_slot")]
+        log: bool,
+
+        /// Read only the announcements listed in the slot-bucket indexes
+        /// since --from-slot. Payments sent without an index are missed.
+        #[arg(long, requires = "from_slot", conflicts_with = "log")]
+        index: bool,
+    },
+
+    /// Scan for incoming payments using only an exported view key
+    ScanView {
+        /// Path to view key file (default: ~/.stealth/view_key.json)
+        #[arg(long)]
+        view_key: Option<String>,
+
+        /// Start from this slot (default: scan all)
+        #[arg(long)]
+        from_slot: Option<u64>,
+
+        /// Read recent announcements from the on-chain announcement logs
+        #[arg(long, requires = "from_slot")]
+        log: bool,
+
+        /// Read announcements from the slot-bucket indexes
+        #[arg(long, requires = "from_slot", conflicts_with = "log")]
+        index: bool,
     },
 
     /// Withdraw funds from a stealth address
     Withdraw {
-        /// Stealth address to withdraw from (from scan results)
+        /// Stealth address or pool note leaf to withdraw from (from scan results)
         #[arg(short, long)]
         from: String,
 
         /// Destination address (default: your main wallet)
+        /// For pool notes: recipient meta-address (default: your own)
         #[arg(short, long)]
         to: Option<String>,
 
         /// Amount to withdraw in SOL (default: full balance)
         #[arg(short, long)]
-        amount: Option<f64>,
+        amount: Option<String>,
+
+        /// Accept a --to meta-address without a checksum (older format)
+        #[arg(long)]
+        legacy: bool,
+
+        /// Keypair paying the fee and account rent for a pool note withdrawal
+        /// (default: the --keypair wallet, which always signs as relayer)
+        #[arg(long)]
+        fee_payer: Option<String>,
     },
 
     /// Show total balance across all stealth addresses
     Balance,
 
+    /// Move every owned stealth address balance to one destination
+    Sweep {
+        /// Destination address
+        #[arg(short, long)]
+        to: String,
+    },
+
     /// Export view key (scan-only, no spending capability)
-    ExportViewKey,
+    ExportViewKey {
+        /// Write the view key to this file (for use with scan-view)
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// Export the full key set (including the spend key) as an encrypted backup
+    ExportKeys {
+        /// Write the backup to this file
+        #[arg(long)]
+        out: String,
+
+        /// Backup password (prompted for if omitted)
+        #[arg(long)]
+        password: Option<String>,
+    },
+
+    /// Restore the full key set from an export-keys backup
+    ImportKeys {
+        /// Backup file to read
+        #[arg(long = "in")]
+        input: String,
+
+        /// Backup password (prompted for if omitted)
+        #[arg(long)]
+        password: Option<String>,
+
+        /// Overwrite existing local keys
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// Change the password protecting the stored keys
+    RotatePassword,
 
     /// Show configuration and key info
     Info,
+
+    /// Show privacy pool statistics (anonymity set size)
+    PoolStats {
+        /// Pool denomination in SOL (e.g. 1, 10, 100)
+        denomination: String,
+    },
+
+    /// List the Merkle roots a pool currently accepts for withdrawal proofs
+    PoolRoots {
+        /// Pool denomination in SOL (e.g. 1, 10, 100)
+        denomination: String,
+    },
+
+    /// List active relayers, most reliable first
+    Relayers,
+
+    /// Check a pool note exists on-chain and hasn't been spent
+    VerifyNote {
+        /// Note nullifier (32 bytes hex)
+        #[arg(long)]
+        nullifier: String,
+
+        /// Note secret (32 bytes hex)
+        #[arg(long)]
+        secret: String,
+
+        /// Note amount in SOL (a pool denomination, e.g. 1, 10, 100)
+        #[arg(long)]
+        amount: String,
+    },
+
+    /// Recover payments and pool notes from chain using the recovery phrase
+    ///
+    /// Rebuilds the local history after losing it; safe to repeat.
+    Rescan {
+        /// Start from this slot (default: scan all)
+        #[arg(long)]
+        from_slot: Option<u64>,
+
+        /// Passphrase the recovery phrase was created with, if any
+        #[arg(long)]
+        passphrase: Option<String>,
+    },
+
+    /// List past sends and withdrawals made from this machine
+    History {
+        /// Only show sends
+        #[arg(long)]
+        sent: bool,
+
+        /// Only show withdrawals
+        #[arg(long)]
+        withdrawn: bool,
+
+        /// Only show payments and notes recovered by rescan
+        #[arg(long)]
+        received: bool,
+
+        /// Only show entries on or after this date (YYYY-MM-DD, UTC)
+        #[arg(long)]
+        since: Option<String>,
+    },
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
+    rpc::set_max_retries(cli.max_retries);
+    let commitment = cli.commitment.config();
 
     match cli.command {
-        Commands::Keygen { force, mnemonic, import_mnemonic, passphrase } => {
+        Commands::Keygen { force, mnemonic, words, import_mnemonic, passphrase } => {
             keygen::run(keygen::KeygenOptions {
                 force,
                 with_mnemonic: mnemonic,
+                words,
                 import_mnemonic,
                 passphrase,
             })?;
         }
         Commands::Register { label } => {
-            register::run(&cli.rpc_url, cli.keypair.as_deref(), &label).await?;
+            register::run(&cli.rpc_url, commitment, cli.keypair.as_deref(), &label).await?;
+        }
+        Commands::Relabel { label } => {
+            relabel::run(&cli.rpc_url, commitment, cli.keypair.as_deref(), &label).await?;
         }
         Commands::Address => {
-            address::run()?;
+            address::run(cli.json)?;
         }
-        Commands::Send { to, amount } => {
-            send::run(&cli.rpc_url, cli.keypair.as_deref(), &to, amount).await?;
+        Commands::Send { to, amount, seed, dry_run, legacy, private } => {
+            if private {
+                send::run_private(&cli.rpc_url, commitment, cli.keypair.as_deref(), &to, &amount, dry_run, legacy).await?;
+            } else {
+                send::run(&cli.rpc_url, commitment, cli.keypair.as_deref(), &to, &amount, seed.as_deref(), dry_run, legacy).await?;
+            }
         }
-        Commands::Scan { from_slot } => {
-            scan::run(&cli.rpc_url, from_slot).await?;
+        Commands::DeriveAddress { to, seed, legacy } => {
+            derive_address::run(&to, seed.as_deref(), legacy, cli.json)?;
         }
-        Commands::Withdraw { from, to, amount } => {
-            withdraw::run(&cli.rpc_url, cli.keypair.as_deref(), &from, to.as_deref(), amount).await?;
+        Commands::Scan { from_slot, log, index } => {
+            scan::run(&cli.rpc_url, commitment, from_slot, log, index).await?;
+        }
+        Commands::ScanView { view_key, from_slot, log, index } => {
+            scan::run_view_only(&cli.rpc_url, commitment, view_key.as_deref(), from_slot, log, index).await?;
+        }
+        Commands::Withdraw { from, to, amount, legacy, fee_payer } => {
+            withdraw::run(&cli.rpc_url, commitment, cli.keypair.as_deref(), &from, to.as_deref(), amount.as_deref(), legacy, fee_payer.as_deref()).await?;
         }
         Commands::Balance => {
-            balance::run(&cli.rpc_url).await?;
+            balance::run(&cli.rpc_url, commitment, cli.json).await?;
+        }
+        Commands::Sweep { to } => {
+            sweep::run(&cli.rpc_url, commitment, &to).await?;
+        }
+        Commands::ExportViewKey { output } => {
+            export_view_key::run(output.as_deref())?;
+        }
+        Commands::ExportKeys { out, password } => {
+            export_keys::run(&out, password)?;
+        }
+        Commands::ImportKeys { input, password, force } => {
+            import_keys::run(&input, password, force)?;
         }
-        Commands::ExportViewKey => {
-            export_view_key::run()?;
+        Commands::RotatePassword => {
+            rotate_password::run()?;
         }
         Commands::Info => {
-            info::run(&cli.rpc_url)?;
+            info::run(&cli.rpc_url, commitment, cli.json)?;
+        }
+        Commands::PoolStats { denomination } => {
+            pool_stats::run(&cli.rpc_url, commitment, &denomination, cli.json)?;
+        }
+        Commands::PoolRoots { denomination } => {
+            pool_roots::run(&cli.rpc_url, commitment, &denomination)?;
+        }
+        Commands::Relayers => {
+            relayers::run(&cli.rpc_url, commitment, cli.json)?;
+        }
+        Commands::VerifyNote { nullifier, secret, amount } => {
+            verify_note::run(&cli.rpc_url, commitment, &nullifier, &secret, &amount)?;
+        }
+        Commands::Rescan { from_slot, passphrase } => {
+            rescan::run(&cli.rpc_url, commitment, from_slot, passphrase.as_deref()).await?;
+        }
+        Commands::History { sent, withdrawn, received, since } => {
+            commands::history::run(sent, withdrawn, received, since.as_deref())?;
         }
     }
 
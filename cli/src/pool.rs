@@ -0,0 +1,231 @@
+//! Privacy pool account access for the CLI
+//!
+//! Reads fixed-denomination pool state using the on-chain program's own types,
+//! so PDA seeds and account layouts can't drift from the program.
+
+use anyhow::{Result, Context, bail};
+use anchor_lang::{AccountDeserialize, Discriminator, InstructionData, ToAccountMetas};
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_filter::RpcFilterType;
+use solana_account_decoder::UiAccountEncoding;
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey, system_program};
+use stealth::crypto::compute_nullifier_hash;
+use stealth::state::{CommitmentLeaf, CommitmentScheme, DecoyRecord, NullifierRecord, PoolConfig, PrivacyPool};
+
+use crate::crypto::{encrypt_note, Note};
+use crate::rpc::with_retry;
+
+/// Derive the pool PDA for a SOL denomination
+pub fn pool_pda(denomination: u64) -> Pubkey {
+    Pubkey::find_program_address(&[PrivacyPool::SEED, &denomination.to_le_bytes()], &stealth::ID).0
+}
+
+/// Derive the commitment leaf PDA for a SOL denomination pool
+pub fn commitment_leaf_pda(denomination: u64, commitment: &[u8; 32]) -> Pubkey {
+    Pubkey::find_program_address(
+        &[CommitmentLeaf::SEED, &denomination.to_le_bytes(), commitment.as_ref()],
+        &stealth::ID,
+    )
+    .0
+}
+
+/// Pool commitment for `note`: Poseidon(nullifier, secret, amount, 0)
+///
+/// The recipient isn't known at deposit time, so deposits commit to a zero
+/// recipient and the stealth address is bound at withdrawal instead.
+pub fn note_commitment(note: &Note) -> [u8; 32] {
+    CommitmentScheme::Poseidon4.commitment(&note.nullifier, &note.secret, note.amount, &[0u8; 32])
+}
+
+/// Fail unless `pool` takes the commitments `note_commitment` builds
+///
+/// A note committed under another scheme could never be withdrawn.
+pub fn ensure_note_scheme(pool: &PrivacyPool) -> Result<()> {
+    match pool.scheme() {
+        Some(CommitmentScheme::Poseidon4) => Ok(()),
+        Some(scheme) => bail!("Pool expects {:?} commitments; this client only makes Poseidon4 notes", scheme),
+        None => bail!("Pool has an unknown commitment scheme ({})", pool.commitment_scheme),
+    }
+}
+
+/// Build a `send_private` deposit of `note` whose leaf carries the note
+/// encrypted to `scan_pubkey`, so the recipient recovers it by scanning
+///
+/// Returns `None` if `scan_pubkey` isn't a valid curve point.
+pub fn send_private_instruction(depositor: Pubkey, scan_pubkey: &[u8; 32], note: Note) -> Option<Instruction> {
+    let denomination = note.amount;
+    let commitment = note_commitment(&note);
+    let encrypted_note = encrypt_note(scan_pubkey, note)?;
+
+    Some(Instruction {
+        program_id: stealth::ID,
+        accounts: stealth::accounts::UnifiedDeposit {
+            depositor,
+            pool: pool_pda(denomination),
+            commitment_leaf: commitment_leaf_pda(denomination, &commitment),
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None),
+        data: stealth::instruction::SendPrivate {
+            denomination,
+            commitment,
+            encrypted_note: Some(encrypted_note),
+        }
+        .data(),
+    })
+}
+
+/// Build a `private_deposit` of `note` whose leaf carries the note
+/// encrypted to `scan_pubkey`, so the recipient recovers it by scanning
+///
+/// `fee_recipient` must be the pool config's, if it charges a fee.
+/// Returns `None` if `scan_pubkey` isn't a valid curve point.
+pub fn private_deposit_instruction(
+    depositor: Pubkey,
+    scan_pubkey: &[u8; 32],
+    note: Note,
+    fee_recipient: Option<Pubkey>,
+) -> Option<Instruction> {
+    let denomination = note.amount;
+    let commitment = note_commitment(&note);
+    let encrypted_note = encrypt_note(scan_pubkey, note)?;
+
+    Some(Instruction {
+        program_id: stealth::ID,
+        accounts: stealth::accounts::PrivateDeposit {
+            depositor,
+            pool: pool_pda(denomination),
+            config: Pubkey::find_program_address(&[PoolConfig::SEED, &denomination.to_le_bytes()], &stealth::ID).0,
+            commitment_leaf: commitment_leaf_pda(denomination, &commitment),
+            fee_recipient,
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None),
+        data: stealth::instruction::PrivateDeposit {
+            denomination,
+            commitment,
+            encrypted_note: Some(encrypted_note),
+        }
+        .data(),
+    })
+}
+
+/// Derive the nullifier PDA that withdrawing `note` creates
+pub fn note_nullifier_pda(note: &Note) -> Pubkey {
+    let nullifier_hash = compute_nullifier_hash(&note.nullifier);
+    NullifierRecord::derive_pda(note.amount, &nullifier_hash).0
+}
+
+/// Check which nullifier PDAs exist on-chain, i.e. which notes are spent
+pub fn fetch_spent_nullifiers(client: &RpcClient, nullifier_pdas: &[Pubkey]) -> Result<Vec<bool>> {
+    let mut spent = Vec::with_capacity(nullifier_pdas.len());
+    for chunk in nullifier_pdas.chunks(100) {
+        let accounts = with_retry(|| client.get_multiple_accounts(chunk))?;
+        spent.extend(accounts.iter().map(|account| account.is_some()));
+    }
+    Ok(spent)
+}
+
+/// Spent status of each nullifier hash in the `denomination` pool
+///
+/// Derives every `NullifierRecord` PDA up front, so a whole wallet costs one
+/// `get_multiple_accounts` round trip per 100 nullifiers.
+pub fn fetch_nullifier_statuses(
+    client: &RpcClient,
+    denomination: u64,
+    nullifier_hashes: &[[u8; 32]],
+) -> Result<Vec<bool>> {
+    let nullifier_pdas: Vec<Pubkey> = nullifier_hashes
+        .iter()
+        .map(|hash| NullifierRecord::derive_pda(denomination, hash).0)
+        .collect();
+    fetch_spent_nullifiers(client, &nullifier_pdas)
+}
+
+/// Decode a zero-copy `PrivacyPool` from raw account data
+pub fn decode_pool(data: &[u8]) -> Result<PrivacyPool> {
+    let size = std::mem::size_of::<PrivacyPool>();
+    if data.len() < 8 + size {
+        bail!("Account is too small to be a privacy pool");
+    }
+    if data[..8] != PrivacyPool::DISCRIMINATOR {
+        bail!("Account is not a privacy pool");
+    }
+
+    // Account data is not guaranteed to be aligned for the struct
+    Ok(bytemuck::pod_read_unaligned(&data[8..8 + size]))
+}
+
+/// Fetch and decode the pool for a denomination
+pub fn fetch_pool(client: &RpcClient, denomination: u64) -> Result<PrivacyPool> {
+    let address = pool_pda(denomination);
+    let account = with_retry(|| client.get_account(&address))
+        .with_context(|| format!("No pool found for this denomination at {}", address))?;
+
+    decode_pool(&account.data)
+}
+
+/// Fetch and decode the config for a denomination pool
+pub fn fetch_pool_config(client: &RpcClient, denomination: u64) -> Result<PoolConfig> {
+    let (address, _) = Pubkey::find_program_address(&[PoolConfig::SEED, &denomination.to_le_bytes()], &stealth::ID);
+    let account = with_retry(|| client.get_account(&address))
+        .with_context(|| format!("No pool config found for this denomination at {}", address))?;
+
+    PoolConfig::try_deserialize(&mut account.data.as_slice()).context("Account is not a pool config")
+}
+
+/// Fetch program accounts of an exact size
+pub(crate) fn fetch_accounts_by_size(client: &RpcClient, size: usize) -> Result<Vec<(Pubkey, solana_sdk::account::Account)>> {
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![
+            RpcFilterType::DataSize(size as u64),
+        ]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(client.commitment()),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    Ok(with_retry(|| client.get_program_accounts_with_config(&stealth::ID, config.clone()))?)
+}
+
+/// Fetch all leaves of a denomination pool, ordered by leaf index
+///
+/// Real leaves don't store their denomination, so membership is checked by
+/// re-deriving each leaf's PDA. Decoy leaves come from `DecoyRecord`s; they
+/// have no `CommitmentLeaf`, but are needed to rebuild the tree.
+pub fn fetch_pool_leaves(client: &RpcClient, denomination: u64) -> Result<Vec<[u8; 32]>> {
+    let mut leaves: Vec<(u64, [u8; 32])> = Vec::new();
+
+    for (pubkey, account) in fetch_accounts_by_size(client, CommitmentLeaf::SIZE)? {
+        let Ok(leaf) = CommitmentLeaf::try_deserialize(&mut account.data.as_slice()) else {
+            continue;
+        };
+        if commitment_leaf_pda(denomination, &leaf.commitment) == pubkey {
+            leaves.push((leaf.leaf_index, leaf.commitment));
+        }
+    }
+
+    for (_, account) in fetch_accounts_by_size(client, DecoyRecord::SIZE)? {
+        let Ok(record) = DecoyRecord::try_deserialize(&mut account.data.as_slice()) else {
+            continue;
+        };
+        if record.denomination == denomination {
+            leaves.extend(record.decoys.iter().map(|d| (d.leaf_index, d.commitment)));
+        }
+    }
+
+    leaves.sort_by_key(|(index, _)| *index);
+
+    // The tree is append-only, so a gap means we missed an account
+    for (expected, (index, _)) in leaves.iter().enumerate() {
+        if *index != expected as u64 {
+            bail!("Missing commitment leaf at index {} - RPC returned an incomplete set", expected);
+        }
+    }
+
+    Ok(leaves.into_iter().map(|(_, commitment)| commitment).collect())
+}
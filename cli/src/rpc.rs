@@ -0,0 +1,153 @@
+//! RPC retry with exponential backoff
+//!
+//! Public devnet RPCs regularly answer with 429s and timeouts. Reads are
+//! idempotent, so `with_retry` repeats them on any transient failure.
+//! Transaction submission is stricter: `send_and_confirm` only ever resends
+//! the *same* signed transaction (same signature, so it can't execute twice),
+//! checks whether it already landed before each retry, and retries only
+//! errors where the request clearly never reached the node.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use rand::Rng;
+use solana_client::client_error::{ClientError, ClientErrorKind, Result as ClientResult};
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_request::RpcError;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::Transaction;
+
+/// Retries after the first attempt unless `--max-retries` says otherwise
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// Delay before the first retry; doubles on each further attempt
+const BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// JSON-RPC "node is behind / unhealthy" error code
+const RPC_NODE_UNHEALTHY: i64 = -32005;
+
+static MAX_RETRIES: AtomicU32 = AtomicU32::new(DEFAULT_MAX_RETRIES);
+
+/// Set the retry budget for this process (from `--max-retries`)
+pub fn set_max_retries(max_retries: u32) {
+    MAX_RETRIES.store(max_retries, Ordering::Relaxed);
+}
+
+pub fn max_retries() -> u32 {
+    MAX_RETRIES.load(Ordering::Relaxed)
+}
+
+/// How settled the state a command reads must be (`--commitment`)
+///
+/// `confirmed` (the default) sees a transaction about a second after it
+/// lands and is almost never rolled back. `processed` is fresher but can
+/// show state from a fork that gets dropped; `finalized` can't be rolled
+/// back but lags ~13 seconds behind, and confirmations wait that long.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Commitment {
+    Processed,
+    #[default]
+    Confirmed,
+    Finalized,
+}
+
+impl Commitment {
+    /// The RPC client setting for this level
+    pub fn config(self) -> CommitmentConfig {
+        match self {
+            Commitment::Processed => CommitmentConfig::processed(),
+            Commitment::Confirmed => CommitmentConfig::confirmed(),
+            Commitment::Finalized => CommitmentConfig::finalized(),
+        }
+    }
+}
+
+/// Run an idempotent RPC read, retrying transient failures with backoff
+pub fn with_retry<T, F>(f: F) -> ClientResult<T>
+where
+    F: FnMut() -> ClientResult<T>,
+{
+    retry(max_retries(), BASE_DELAY, is_transient, f)
+}
+
+/// Submit and confirm a signed transaction, resending it only when safe
+///
+/// The transaction is never re-signed or rebuilt: a retry sends the
+/// identical bytes, and if the signature turns out to have landed already
+/// that counts as success.
+///
+/// Confirmation and the landed check both wait for the client's commitment,
+/// so `--commitment finalized` returns only once the transaction is final.
+pub fn send_and_confirm(client: &RpcClient, transaction: &Transaction) -> ClientResult<Signature> {
+    let signature = transaction.signatures[0];
+
+    retry(max_retries(), BASE_DELAY, is_transient_send, || {
+        match client.send_and_confirm_transaction(transaction) {
+            Ok(signature) => Ok(signature),
+            Err(err) => match client.get_signature_status(&signature) {
+                Ok(Some(Ok(()))) => Ok(signature),
+                _ => Err(err),
+            },
+        }
+    })
+}
+
+/// Call `f` up to `max_retries + 1` times while `should_retry` says so
+pub fn retry<T, F>(
+    max_retries: u32,
+    base_delay: Duration,
+    should_retry: fn(&ClientError) -> bool,
+    mut f: F,
+) -> ClientResult<T>
+where
+    F: FnMut() -> ClientResult<T>,
+{
+    let mut attempt = 0;
+    loop {
+        match f() {
+            Ok(value) => return Ok(value),
+            Err(err) if attempt < max_retries && should_retry(&err) => {
+                thread::sleep(backoff_delay(base_delay, attempt));
+                attempt += 1;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// `base * 2^attempt`, plus up to 50% random jitter so clients that failed
+/// together don't retry in lockstep
+pub fn backoff_delay(base: Duration, attempt: u32) -> Duration {
+    let delay = base.saturating_mul(1u32 << attempt.min(16));
+    let jitter_ms = rand::thread_rng().gen_range(0..=delay.as_millis() as u64 / 2);
+    delay + Duration::from_millis(jitter_ms)
+}
+
+/// Failures worth repeating a read for: network errors, timeouts, rate
+/// limits, server errors and unhealthy nodes
+pub fn is_transient(err: &ClientError) -> bool {
+    match err.kind() {
+        ClientErrorKind::Io(_) => true,
+        ClientErrorKind::Reqwest(e) => {
+            e.is_timeout()
+                || e.is_connect()
+                || e.status().is_some_and(|s| s.as_u16() == 429 || s.is_server_error())
+        }
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) => *code == RPC_NODE_UNHEALTHY,
+        _ => false,
+    }
+}
+
+/// Failures where the transaction clearly never reached the node: the
+/// connection wasn't made or the request was rate limited. A timeout may
+/// have been accepted, so it isn't retried here.
+pub fn is_transient_send(err: &ClientError) -> bool {
+    match err.kind() {
+        ClientErrorKind::Reqwest(e) => {
+            e.is_connect() || e.status().is_some_and(|s| s.as_u16() == 429)
+        }
+        _ => false,
+    }
+}
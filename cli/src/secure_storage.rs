@@ -15,7 +15,7 @@ use serde::{Deserialize, Serialize};
 use anyhow::{Result, Context, bail};
 use zeroize::Zeroize;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Argon2 parameters for key derivation
 const ARGON2_M_COST: u32 = 65536;  // 64 MB memory
@@ -191,26 +191,37 @@ impl SecureKeyStorage {
     /// Save encrypted keys
     pub fn save(&self, data: &KeyData, password: &str) -> Result<()> {
         let encrypted = EncryptedKeyFile::encrypt(data, password)?;
-        let json = serde_json::to_string_pretty(&encrypted)?;
+        self.write_atomic(&encrypted)
+    }
+
+    /// Write the encrypted file to a sibling temp file, then rename it over
+    /// the real path so a crash never leaves a half-written key file
+    fn write_atomic(&self, encrypted: &EncryptedKeyFile) -> Result<()> {
+        let json = serde_json::to_string_pretty(encrypted)?;
 
         // Ensure directory exists
         if let Some(parent) = self.path.parent() {
             fs::create_dir_all(parent)?;
         }
 
+        let tmp_path = self.path.with_extension("enc.tmp");
+
         // Write with restrictive permissions
         #[cfg(unix)]
         {
             use std::os::unix::fs::PermissionsExt;
-            fs::write(&self.path, &json)?;
-            fs::set_permissions(&self.path, fs::Permissions::from_mode(0o600))?;
+            fs::write(&tmp_path, &json)?;
+            fs::set_permissions(&tmp_path, fs::Permissions::from_mode(0o600))?;
         }
 
         #[cfg(not(unix))]
         {
-            fs::write(&self.path, &json)?;
+            fs::write(&tmp_path, &json)?;
         }
 
+        fs::rename(&tmp_path, &self.path)
+            .context("Failed to replace encrypted key file")?;
+
         Ok(())
     }
 
@@ -225,11 +236,28 @@ impl SecureKeyStorage {
         encrypted.decrypt(password)
     }
 
+    /// Re-encrypt stored keys under a new password
+    ///
+    /// The file is only replaced once the old password has been verified and
+    /// the new ciphertext (fresh salt and nonce) is ready.
+    pub fn reencrypt(&self, old_password: &str, new_password: &str) -> Result<()> {
+        if !self.exists() {
+            bail!("No encrypted key file at {:?}", self.path);
+        }
+
+        let mut data = self.load(old_password)
+            .context("Current password is incorrect")?;
+
+        let result = EncryptedKeyFile::encrypt(&data, new_password)
+            .and_then(|encrypted| self.write_atomic(&encrypted));
+
+        data.zeroize();
+        result
+    }
+
     /// Change the password for stored keys
     pub fn change_password(&self, old_password: &str, new_password: &str) -> Result<()> {
-        let data = self.load(old_password)?;
-        self.save(&data, new_password)?;
-        Ok(())
+        self.reencrypt(old_password, new_password)
     }
 
     /// Delete stored keys (requires password confirmation)
@@ -241,6 +269,93 @@ impl SecureKeyStorage {
     }
 }
 
+/// Magic string identifying a key backup file
+pub const KEY_BACKUP_MAGIC: &str = "nocturne-key-backup";
+
+/// Current key backup format version
+pub const KEY_BACKUP_VERSION: u8 = 1;
+
+/// Portable backup of the full key set (scan + spend secrets)
+///
+/// The keys are encrypted exactly like the local store (`EncryptedKeyFile`),
+/// wrapped with a magic string and version so a backup is recognizable and
+/// can't be confused with a key store or view key file.
+#[derive(Serialize, Deserialize)]
+pub struct KeyBackup {
+    pub magic: String,
+    pub version: u8,
+    pub keys: EncryptedKeyFile,
+}
+
+impl KeyBackup {
+    /// Encrypt `data` into a new backup
+    pub fn seal(data: &KeyData, password: &str) -> Result<Self> {
+        Ok(Self {
+            magic: KEY_BACKUP_MAGIC.to_string(),
+            version: KEY_BACKUP_VERSION,
+            keys: EncryptedKeyFile::encrypt(data, password)?,
+        })
+    }
+
+    /// Parse a backup, rejecting other files and unknown versions
+    pub fn parse(json: &str) -> Result<Self> {
+        let value: serde_json::Value = serde_json::from_str(json)
+            .context("Key backup is not valid JSON")?;
+        if value.get("magic").and_then(|m| m.as_str()) != Some(KEY_BACKUP_MAGIC) {
+            bail!("Not a key backup file (missing '{}' header)", KEY_BACKUP_MAGIC);
+        }
+
+        let backup: Self = serde_json::from_value(value)
+            .context("Failed to parse key backup")?;
+        if backup.version != KEY_BACKUP_VERSION {
+            bail!(
+                "Unsupported key backup version {} (this build reads version {})",
+                backup.version,
+                KEY_BACKUP_VERSION
+            );
+        }
+        Ok(backup)
+    }
+
+    /// Decrypt the backed-up keys
+    pub fn open(&self, password: &str) -> Result<KeyData> {
+        self.keys.decrypt(password)
+    }
+
+    /// Write the backup with restrictive permissions, refusing to overwrite
+    pub fn write(&self, path: &Path) -> Result<()> {
+        if path.exists() {
+            bail!("{:?} already exists; choose another path or remove it first", path);
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context("Failed to create backup directory")?;
+        }
+
+        let json = serde_json::to_string_pretty(self)?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::write(path, &json)?;
+            fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+        }
+
+        #[cfg(not(unix))]
+        {
+            fs::write(path, &json)?;
+        }
+
+        Ok(())
+    }
+
+    /// Read and parse a backup file
+    pub fn read(path: &Path) -> Result<Self> {
+        let json = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read key backup {:?}", path))?;
+        Self::parse(&json)
+    }
+}
+
 /// Password strength validation
 pub fn validate_password_strength(password: &str) -> Result<()> {
     if password.len() < 8 {
@@ -333,6 +448,40 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_key_backup_roundtrip() {
+        let data = KeyData {
+            scan_secret: [0x42; 32],
+            spend_secret: [0x43; 32],
+            scan_pubkey: [0x44; 32],
+            spend_pubkey: [0x45; 32],
+        };
+        let password = "TestPassword123";
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("backup.json");
+        KeyBackup::seal(&data, password).unwrap().write(&path).unwrap();
+
+        let restored = KeyBackup::read(&path).unwrap().open(password).unwrap();
+        assert_eq!(data.scan_secret, restored.scan_secret);
+        assert_eq!(data.spend_secret, restored.spend_secret);
+        assert_eq!(data.scan_pubkey, restored.scan_pubkey);
+        assert_eq!(data.spend_pubkey, restored.spend_pubkey);
+
+        assert!(KeyBackup::read(&path).unwrap().open("WrongPassword123").is_err());
+
+        // An existing backup is never overwritten
+        assert!(KeyBackup::seal(&data, password).unwrap().write(&path).is_err());
+
+        // The plain key store format isn't mistaken for a backup
+        let store = serde_json::to_string(&EncryptedKeyFile::encrypt(&data, password).unwrap()).unwrap();
+        assert!(KeyBackup::parse(&store).err().unwrap().to_string().contains(KEY_BACKUP_MAGIC));
+
+        let mut future: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        future["version"] = serde_json::json!(KEY_BACKUP_VERSION + 1);
+        assert!(KeyBackup::parse(&future.to_string()).err().unwrap().to_string().contains("version"));
+    }
+
     #[test]
     fn test_password_validation() {
         assert!(validate_password_strength("short").is_err());
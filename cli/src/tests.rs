@@ -11,7 +11,10 @@
 #[allow(clippy::expect_fun_call)]
 mod crypto_tests {
     use crate::crypto::{
-        compute_stealth_address, scan_payment, StealthKeys, StealthSigner,
+        compute_stealth_address, compute_stealth_address_deterministic,
+        compute_stealth_address_with_rng, scan_batch,
+        scan_batch_with_hint, scan_payment, scan_tag, sender_scan_tag, StealthKeys, StealthSigner,
+        ViewKey,
     };
     use curve25519_dalek::{constants::ED25519_BASEPOINT_POINT, scalar::Scalar};
     use solana_sdk::signer::Signer;
@@ -81,6 +84,177 @@ mod crypto_tests {
         assert_ne!(result1.ephemeral_pubkey, result2.ephemeral_pubkey);
     }
 
+    #[test]
+    fn test_compute_stealth_address_deterministic_seed() {
+        let keys = StealthKeys::generate();
+        let (scan_pubkey, spend_pubkey) = keys.meta_address();
+        let seed = [7u8; 32];
+
+        let first = compute_stealth_address_deterministic(&scan_pubkey, &spend_pubkey, &seed).unwrap();
+        let retry = compute_stealth_address_deterministic(&scan_pubkey, &spend_pubkey, &seed).unwrap();
+        assert_eq!(first.stealth_pubkey, retry.stealth_pubkey);
+        assert_eq!(first.ephemeral_pubkey, retry.ephemeral_pubkey);
+
+        let other = compute_stealth_address_deterministic(&scan_pubkey, &spend_pubkey, &[8u8; 32]).unwrap();
+        assert_ne!(first.stealth_pubkey, other.stealth_pubkey);
+        assert_ne!(first.ephemeral_pubkey, other.ephemeral_pubkey);
+
+        // Still a normal payment from the recipient's point of view
+        assert!(scan_payment(&keys, &first.ephemeral_pubkey, &first.stealth_pubkey).is_some());
+    }
+
+    #[test]
+    fn test_compute_stealth_address_seeded_rng() {
+        use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+        let keys = StealthKeys::from_secrets(&[0x42u8; 32], &[0x43u8; 32]);
+        let (scan_pubkey, spend_pubkey) = keys.meta_address();
+
+        let mut rng = ChaCha20Rng::from_seed([9u8; 32]);
+        let payments: Vec<_> = (0..3)
+            .map(|_| compute_stealth_address_with_rng(&scan_pubkey, &spend_pubkey, &mut rng).unwrap())
+            .collect();
+
+        // Pinned so a change to the derivation can't slip through unnoticed
+        assert_eq!(hex::encode(payments[0].stealth_pubkey), "38a1b598035e2e7cd774c53e000767ca83e51ad8ef3d17b3d0fd6e950796115d");
+        assert_eq!(hex::encode(payments[0].ephemeral_pubkey), "5de12d283762c5fc4cc7bfa85ea41eddbb315b99054ece3f2703bf2a7681e5d9");
+
+        // Re-seeding replays the same sequence of payments
+        let mut replay = ChaCha20Rng::from_seed([9u8; 32]);
+        for payment in &payments {
+            let again = compute_stealth_address_with_rng(&scan_pubkey, &spend_pubkey, &mut replay).unwrap();
+            assert_eq!(again.stealth_pubkey, payment.stealth_pubkey);
+            assert_eq!(again.ephemeral_pubkey, payment.ephemeral_pubkey);
+        }
+
+        // Each draw is a fresh, unlinkable payment the recipient can still find
+        assert_ne!(payments[0].stealth_pubkey, payments[1].stealth_pubkey);
+        assert_ne!(payments[1].stealth_pubkey, payments[2].stealth_pubkey);
+        for payment in &payments {
+            assert!(scan_payment(&keys, &payment.ephemeral_pubkey, &payment.stealth_pubkey).is_some());
+        }
+    }
+
+    /// Announcements where every `stride`-th entry is addressed to `keys`
+    fn mixed_announcements(keys: &StealthKeys, count: usize, stride: usize) -> Vec<([u8; 32], [u8; 32])> {
+        let (scan_pubkey, spend_pubkey) = keys.meta_address();
+        let other = StealthKeys::generate();
+        let (other_scan, other_spend) = other.meta_address();
+
+        (0..count)
+            .map(|i| {
+                let c = if i % stride == 0 {
+                    compute_stealth_address(&scan_pubkey, &spend_pubkey).unwrap()
+                } else {
+                    compute_stealth_address(&other_scan, &other_spend).unwrap()
+                };
+                (c.ephemeral_pubkey, c.stealth_pubkey)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_scan_batch_preserves_order() {
+        let keys = StealthKeys::generate();
+        let announcements = mixed_announcements(&keys, 64, 5);
+
+        let results = scan_batch(&keys, &announcements);
+        let expected: Vec<[u8; 32]> = announcements
+            .iter()
+            .step_by(5)
+            .map(|(_, stealth)| *stealth)
+            .collect();
+        let found: Vec<[u8; 32]> = results.iter().map(|r| r.stealth_address).collect();
+
+        assert_eq!(found, expected);
+    }
+
+    #[test]
+    fn test_scan_hint_has_no_false_negatives() {
+        use stealth::state::ScanHint;
+
+        let keys = StealthKeys::generate();
+        let announcements = mixed_announcements(&keys, 64, 4);
+        let mut hint = ScanHint { scan_pubkey: keys.scan_pubkey, filter: [0u8; 256], tag_count: 0, bump: 0 };
+
+        // Senders tag every payment to us from the public scan key alone
+        for (ephemeral_pubkey, _) in announcements.iter().step_by(4) {
+            let tag = sender_scan_tag(&keys.scan_pubkey, ephemeral_pubkey);
+            assert_eq!(scan_tag(&keys.scan_secret(), ephemeral_pubkey), tag);
+            hint.insert(&tag);
+        }
+
+        // Every true payment passes the prefilter, so the hinted scan finds them all
+        let found: Vec<[u8; 32]> = scan_batch_with_hint(&keys, &hint, &announcements)
+            .iter()
+            .map(|r| r.stealth_address)
+            .collect();
+        let expected: Vec<[u8; 32]> = scan_batch(&keys, &announcements)
+            .iter()
+            .map(|r| r.stealth_address)
+            .collect();
+        assert_eq!(found.len(), 16);
+        assert_eq!(found, expected);
+    }
+
+    /// Run with `cargo test --release -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn bench_scan_batch_10k() {
+        use std::time::Instant;
+
+        let keys = StealthKeys::generate();
+        let announcements = mixed_announcements(&keys, 10_000, 100);
+
+        let start = Instant::now();
+        let sequential: Vec<[u8; 32]> = announcements
+            .iter()
+            .filter_map(|(e, s)| scan_payment(&keys, e, s))
+            .map(|r| r.stealth_address)
+            .collect();
+        let sequential_time = start.elapsed();
+
+        let start = Instant::now();
+        let parallel: Vec<[u8; 32]> = scan_batch(&keys, &announcements)
+            .iter()
+            .map(|r| r.stealth_address)
+            .collect();
+        let parallel_time = start.elapsed();
+
+        assert_eq!(sequential, parallel);
+        assert_eq!(parallel.len(), 100);
+
+        println!(
+            "10k announcements: sequential {:?}, parallel {:?} ({:.1}x on {} threads)",
+            sequential_time,
+            parallel_time,
+            sequential_time.as_secs_f64() / parallel_time.as_secs_f64(),
+            rayon::current_num_threads(),
+        );
+        if rayon::current_num_threads() > 1 {
+            assert!(parallel_time < sequential_time);
+        }
+    }
+
+    #[test]
+    fn test_view_key_detects_payment() {
+        let keys = StealthKeys::generate();
+        let (scan_pubkey, spend_pubkey) = keys.meta_address();
+        let (scan_secret, _) = keys.export_secrets();
+
+        let view_key = ViewKey::from_parts(&scan_secret, &spend_pubkey);
+        assert_eq!(view_key.scan_pubkey, scan_pubkey);
+
+        let computation = compute_stealth_address(&scan_pubkey, &spend_pubkey).unwrap();
+        assert!(view_key.check_payment(&computation.ephemeral_pubkey, &computation.stealth_pubkey));
+
+        // Payment to someone else is not detected
+        let other = StealthKeys::generate();
+        let (other_scan, other_spend) = other.meta_address();
+        let foreign = compute_stealth_address(&other_scan, &other_spend).unwrap();
+        assert!(!view_key.check_payment(&foreign.ephemeral_pubkey, &foreign.stealth_pubkey));
+    }
+
     #[test]
     fn test_full_stealth_payment_flow() {
         // 1. Recipient generates keys
@@ -159,6 +333,59 @@ mod crypto_tests {
         );
     }
 
+    #[test]
+    fn test_crypto_errors_name_the_invalid_input() {
+        use crate::crypto::{
+            try_compute_stealth_address, try_compute_stealth_address_deterministic,
+            try_decompress_point, try_scan_payment, CryptoError,
+        };
+
+        let recipient_keys = StealthKeys::generate();
+        let (scan_pubkey, spend_pubkey) = recipient_keys.meta_address();
+
+        // y = 2 has no x on the curve
+        let mut off_curve = [0u8; 32];
+        off_curve[0] = 2;
+        assert_eq!(try_decompress_point(&off_curve).unwrap_err(), CryptoError::PointNotOnCurve);
+        assert!(try_decompress_point(&scan_pubkey).is_ok());
+
+        assert_eq!(
+            try_compute_stealth_address(&off_curve, &spend_pubkey).err(),
+            Some(CryptoError::InvalidScanPoint)
+        );
+        assert_eq!(
+            try_compute_stealth_address(&scan_pubkey, &off_curve).err(),
+            Some(CryptoError::InvalidSpendPoint)
+        );
+        assert_eq!(
+            try_compute_stealth_address_deterministic(&scan_pubkey, &off_curve, &[1u8; 32]).err(),
+            Some(CryptoError::InvalidSpendPoint)
+        );
+
+        let computation = try_compute_stealth_address(&scan_pubkey, &spend_pubkey).unwrap();
+        assert_eq!(
+            try_scan_payment(&recipient_keys, &off_curve, &computation.stealth_pubkey).err(),
+            Some(CryptoError::InvalidEphemeralPoint)
+        );
+
+        // A well-formed payment for someone else is not an error
+        let other = StealthKeys::generate();
+        assert!(try_scan_payment(&other, &computation.ephemeral_pubkey, &computation.stealth_pubkey)
+            .unwrap()
+            .is_none());
+        assert!(try_scan_payment(&recipient_keys, &computation.ephemeral_pubkey, &computation.stealth_pubkey)
+            .unwrap()
+            .is_some());
+
+        // The Option wrappers collapse every failure to None
+        assert!(compute_stealth_address(&off_curve, &spend_pubkey).is_none());
+        assert!(scan_payment(&recipient_keys, &off_curve, &computation.stealth_pubkey).is_none());
+        assert_eq!(
+            CryptoError::InvalidScanPoint.to_string(),
+            "Scan public key is not a valid curve point"
+        );
+    }
+
     #[test]
     fn test_stealth_signer() {
         let scalar_bytes = [0x42u8; 32];
@@ -175,6 +402,79 @@ mod crypto_tests {
         assert_eq!(signer.pubkey().to_bytes(), expected_pubkey);
     }
 
+    #[test]
+    fn test_stealth_signer_hedged_nonces() {
+        use ed25519_dalek::{PublicKey, Signature as DalekSignature, Verifier};
+
+        let scalar = Scalar::from_bytes_mod_order([0x42u8; 32]);
+        let signer = StealthSigner::from_scalar(&scalar).unwrap();
+        let verifying_key = PublicKey::from_bytes(&signer.pubkey().to_bytes()).unwrap();
+
+        let message = b"same message twice";
+        let first = signer.try_sign_message(message).unwrap();
+        let second = signer.try_sign_message(message).unwrap();
+
+        // Fresh randomness in every nonce: same key and message, different r
+        assert_ne!(first, second);
+        assert_ne!(first.as_ref()[..32], second.as_ref()[..32]);
+
+        // Both still verify against the unchanged public key
+        for signature in [first, second] {
+            let signature = DalekSignature::from_bytes(signature.as_ref()).unwrap();
+            assert!(verifying_key.verify(message, &signature).is_ok());
+        }
+        assert!(verifying_key
+            .verify(b"another message", &DalekSignature::from_bytes(first.as_ref()).unwrap())
+            .is_err());
+    }
+
+    #[test]
+    fn test_stealth_signer_message_bounds() {
+        use solana_sdk::{packet::PACKET_DATA_SIZE, signature::SignerError};
+
+        let signer = StealthSigner::from_scalar(&Scalar::from_bytes_mod_order([0x42u8; 32])).unwrap();
+
+        assert!(matches!(signer.try_sign_message(&[]), Err(SignerError::InvalidInput(_))));
+        assert!(matches!(
+            signer.try_sign_message(&vec![0u8; PACKET_DATA_SIZE + 1]),
+            Err(SignerError::InvalidInput(_))
+        ));
+        assert!(signer.try_sign_message(&vec![0u8; PACKET_DATA_SIZE]).is_ok());
+    }
+
+    #[test]
+    fn test_stealth_signer_sign_transaction() {
+        use solana_sdk::{hash::Hash, signature::Keypair, system_instruction, transaction::Transaction};
+
+        let signer = StealthSigner::from_scalar(&Scalar::from_bytes_mod_order([0x42u8; 32])).unwrap();
+        let destination = Keypair::new();
+        let blockhash = Hash::new_unique();
+
+        // Stealth address pays and signs alone
+        let transfer = system_instruction::transfer(&signer.pubkey(), &destination.pubkey(), 1);
+        let mut tx = Transaction::new_with_payer(std::slice::from_ref(&transfer), Some(&signer.pubkey()));
+        tx.message.recent_blockhash = blockhash;
+        signer.sign_transaction(&mut tx).unwrap();
+        assert!(tx.is_signed());
+        assert!(tx.verify().is_ok());
+
+        // Sponsored: the fee payer's signature survives the stealth signature
+        let sponsor = Keypair::new();
+        let mut tx = Transaction::new_with_payer(&[transfer], Some(&sponsor.pubkey()));
+        tx.partial_sign(&[&sponsor], blockhash);
+        assert!(!tx.is_signed());
+        signer.sign_transaction(&mut tx).unwrap();
+        assert!(tx.is_signed());
+        assert!(tx.verify().is_ok());
+
+        // Stealth address only referenced, never a signer
+        let unrelated = system_instruction::transfer(&sponsor.pubkey(), &signer.pubkey(), 1);
+        let mut tx = Transaction::new_with_payer(&[unrelated], Some(&sponsor.pubkey()));
+        tx.message.recent_blockhash = blockhash;
+        let err = signer.sign_transaction(&mut tx).unwrap_err();
+        assert!(err.to_string().contains("not a required signer"));
+    }
+
     #[test]
     fn test_scalar_to_keypair_consistency() {
         let recipient_keys = StealthKeys::generate();
@@ -234,7 +534,46 @@ mod crypto_tests {
 
 #[cfg(test)]
 mod config_tests {
-    use crate::config::{format_meta_address, parse_meta_address};
+    use crate::config::{format_meta_address, parse_meta_address, format_sol, parse_sol};
+
+    #[test]
+    fn test_format_sol() {
+        assert_eq!(format_sol(1_500_000_000), "1.5 SOL");
+        assert_eq!(format_sol(1_000_000_000), "1.0 SOL");
+        assert_eq!(format_sol(0), "0.0 SOL");
+        assert_eq!(format_sol(1), "0.000000001 SOL");
+        assert_eq!(format_sol(u64::MAX), "18446744073.709551615 SOL");
+    }
+
+    #[test]
+    fn test_parse_sol_roundtrip() {
+        for lamports in [0, 1, 500_000_000, 1_000_000_000, 123_456_789_012, u64::MAX] {
+            let formatted = format_sol(lamports);
+            let amount = formatted.trim_end_matches(" SOL");
+            assert_eq!(parse_sol(amount).unwrap(), lamports, "{}", formatted);
+        }
+
+        assert_eq!(parse_sol("0.5").unwrap(), 500_000_000);
+        assert_eq!(parse_sol(" 2 ").unwrap(), 2_000_000_000);
+        assert_eq!(parse_sol(".25").unwrap(), 250_000_000);
+
+        // Rounds half-up past 9 decimals
+        assert_eq!(parse_sol("0.0000000014").unwrap(), 1);
+        assert_eq!(parse_sol("0.0000000015").unwrap(), 2);
+    }
+
+    #[test]
+    fn test_parse_sol_rejects_malformed_and_overflow() {
+        for input in ["", ".", "1.", "abc", "-1", "+1", "1e9", "1.2.3", "1,5", "0x10"] {
+            assert!(parse_sol(input).is_err(), "{:?}", input);
+        }
+
+        // Just above u64::MAX lamports
+        assert!(parse_sol("18446744073.709551616").is_err());
+        assert!(parse_sol("18446744074").is_err());
+        assert!(parse_sol("18446744073.7095516155").is_err());
+        assert!(parse_sol("99999999999999999999999").is_err());
+    }
 
     #[test]
     fn test_format_meta_address() {
@@ -280,6 +619,60 @@ mod config_tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_meta_address_checksum_detects_corruption() {
+        use crate::config::MetaAddressError;
+
+        let formatted = format_meta_address(&[1u8; 32], &[2u8; 32]);
+        let encoded = formatted.strip_prefix("stealth:").unwrap();
+
+        // Swap one character for another valid base58 character
+        for pos in [0, encoded.len() / 2, encoded.len() - 1] {
+            let original = encoded.as_bytes()[pos];
+            let replacement = if original == b'2' { '3' } else { '2' };
+            let mut corrupted = encoded.to_string();
+            corrupted.replace_range(pos..pos + 1, &replacement.to_string());
+
+            let err = parse_meta_address(&corrupted).unwrap_err();
+            let err = err.downcast_ref::<MetaAddressError>().unwrap();
+            assert!(
+                matches!(err, MetaAddressError::ChecksumMismatch | MetaAddressError::InvalidLength { .. }),
+                "corruption at {} not detected: {:?}",
+                pos,
+                err
+            );
+        }
+
+        // A corrupted checksum byte itself is caught too
+        let mut bytes = bs58::decode(encoded).into_vec().unwrap();
+        bytes[67] ^= 1;
+        let err = parse_meta_address(&bs58::encode(&bytes).into_string()).unwrap_err();
+        assert_eq!(err.downcast_ref::<MetaAddressError>(), Some(&MetaAddressError::ChecksumMismatch));
+    }
+
+    #[test]
+    fn test_legacy_meta_address_requires_opt_in() {
+        use crate::config::{parse_meta_address_with, MetaAddressError};
+
+        let mut combined = [1u8; 64];
+        combined[32..].copy_from_slice(&[2u8; 32]);
+        let legacy = format!("stealth:{}", bs58::encode(&combined).into_string());
+
+        let err = parse_meta_address(&legacy).unwrap_err();
+        assert_eq!(err.downcast_ref::<MetaAddressError>(), Some(&MetaAddressError::MissingChecksum));
+
+        let (scan, spend) = parse_meta_address_with(&legacy, true).unwrap();
+        assert_eq!(scan, [1u8; 32]);
+        assert_eq!(spend, [2u8; 32]);
+
+        // Checksummed addresses still verify in legacy mode
+        let formatted = format_meta_address(&[1u8; 32], &[2u8; 32]);
+        assert_eq!(parse_meta_address_with(&formatted, true).unwrap(), (scan, spend));
+        let mut bytes = bs58::decode(formatted.strip_prefix("stealth:").unwrap()).into_vec().unwrap();
+        bytes[0] ^= 1;
+        assert!(parse_meta_address_with(&bs58::encode(&bytes).into_string(), true).is_err());
+    }
+
     #[test]
     fn test_meta_address_roundtrip_random() {
         use rand::RngCore;
@@ -298,6 +691,71 @@ mod config_tests {
             assert_eq!(spend, parsed_spend);
         }
     }
+
+    #[test]
+    fn test_view_key_file_roundtrip() {
+        use crate::config::{load_view_key, save_view_key, ViewKeyFile};
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("view_key.json");
+
+        let view_key = ViewKeyFile {
+            scan_secret: hex::encode([0x11u8; 32]),
+            spend_pubkey: hex::encode([0x22u8; 32]),
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+        };
+        save_view_key(&path, &view_key).unwrap();
+
+        let (scan_secret, spend_pubkey) = load_view_key(&path).unwrap();
+        assert_eq!(scan_secret, [0x11u8; 32]);
+        assert_eq!(spend_pubkey, [0x22u8; 32]);
+    }
+
+    #[test]
+    fn test_view_key_accepts_printed_format() {
+        use crate::config::parse_view_key;
+
+        let line = format!("{}:{}\n", hex::encode([0x11u8; 32]), hex::encode([0x22u8; 32]));
+        let (scan_secret, spend_pubkey) = parse_view_key(&line).unwrap();
+        assert_eq!(scan_secret, [0x11u8; 32]);
+        assert_eq!(spend_pubkey, [0x22u8; 32]);
+    }
+
+    #[test]
+    fn test_view_key_rejects_spend_secret() {
+        use crate::config::parse_view_key;
+
+        let full_keys = serde_json::json!({
+            "scan_secret": hex::encode([0x11u8; 32]),
+            "spend_secret": hex::encode([0x33u8; 32]),
+            "scan_pubkey": hex::encode([0x44u8; 32]),
+            "spend_pubkey": hex::encode([0x22u8; 32]),
+            "created_at": "2024-01-01T00:00:00Z",
+        });
+        let err = parse_view_key(&full_keys.to_string()).unwrap_err();
+        assert!(err.to_string().contains("spend secret"));
+
+        let encrypted = serde_json::json!({ "salt": "x", "nonce": "y", "ciphertext": "z" });
+        assert!(parse_view_key(&encrypted.to_string()).is_err());
+    }
+    #[test]
+    fn test_decompose_into_denominations() {
+        use crate::config::decompose_into_denominations;
+
+        const SOL: u64 = 1_000_000_000;
+
+        assert_eq!(decompose_into_denominations(7 * SOL).unwrap(), vec![5 * SOL, SOL, SOL]);
+        assert_eq!(decompose_into_denominations(6 * SOL / 10).unwrap(), vec![SOL / 2, SOL / 10]);
+        assert_eq!(decompose_into_denominations(1000 * SOL).unwrap(), vec![1000 * SOL]);
+
+        let amount = 1_666 * SOL + 7 * SOL / 10;
+        assert_eq!(decompose_into_denominations(amount).unwrap().iter().sum::<u64>(), amount);
+
+        assert!(decompose_into_denominations(0).is_err());
+        let err = decompose_into_denominations(SOL / 20).unwrap_err();
+        assert!(err.to_string().contains("0.05 SOL"));
+        assert!(decompose_into_denominations(SOL + SOL / 4).is_err());
+    }
 }
 
 #[cfg(test)]
@@ -400,4 +858,667 @@ mod edge_case_tests {
             );
         }
     }
+
+    #[test]
+    fn test_relabel_validation_matches_program() {
+        use crate::commands::relabel::validate_label;
+        use stealth::state::StealthRegistry;
+
+        let too_long = "x".repeat(33);
+        let multibyte = "ü".repeat(16);
+        for label in ["", "alice", "bob\0", too_long.as_str(), multibyte.as_str()] {
+            assert_eq!(
+                validate_label(label).is_ok(),
+                StealthRegistry::encode_label(label.as_bytes()).is_ok(),
+                "label {:?}",
+                label
+            );
+        }
+        assert!(validate_label(&too_long).unwrap_err().to_string().contains("maximum is 32"));
+    }
+}
+
+#[cfg(test)]
+mod withdraw_tests {
+    use crate::commands::withdraw::build_withdraw_inputs;
+    use crate::crypto::{Note, StealthKeys};
+    use stealth::crypto::compute_nullifier_hash;
+    use stealth::state::compute_commitment;
+
+    fn note() -> Note {
+        Note {
+            nullifier: [5u8; 32],
+            secret: [6u8; 32],
+            amount: 1_000_000_000,
+        }
+    }
+
+    #[test]
+    fn test_withdraw_inputs_match_program_checks() {
+        let keys = StealthKeys::generate();
+        let (scan_pubkey, spend_pubkey) = keys.meta_address();
+        let leaves = [[1u8; 32], [2u8; 32], [3u8; 32]];
+
+        let inputs = build_withdraw_inputs(&note(), &leaves, 1, 7, &scan_pubkey, &spend_pubkey).unwrap();
+
+        assert_eq!(inputs.nullifier_hash, compute_nullifier_hash(&[5u8; 32]));
+        assert_eq!(inputs.scan_pubkey, scan_pubkey);
+        assert_eq!(inputs.spend_pubkey, spend_pubkey);
+
+        // Same commitment the program recomputes in verify_stealth_commitment
+        let expected = compute_commitment(
+            &inputs.ephemeral_pubkey,
+            &inputs.scan_pubkey,
+            &inputs.spend_pubkey,
+            &inputs.stealth_address.to_bytes(),
+        );
+        assert_eq!(inputs.stealth_commitment, expected);
+
+        // The attested bytes commit to it, binding the attestation to this address
+        assert_eq!(&inputs.to_bytes()[192..224], &inputs.stealth_commitment);
+        assert_eq!(inputs.deposit_slot, 7);
+    }
+
+    #[test]
+    fn test_withdraw_inputs_root_independent_of_leaf() {
+        let keys = StealthKeys::generate();
+        let (scan_pubkey, spend_pubkey) = keys.meta_address();
+        let leaves = [[1u8; 32], [2u8; 32], [3u8; 32]];
+
+        let a = build_withdraw_inputs(&note(), &leaves, 0, 7, &scan_pubkey, &spend_pubkey).unwrap();
+        let b = build_withdraw_inputs(&note(), &leaves, 2, 7, &scan_pubkey, &spend_pubkey).unwrap();
+        assert_eq!(a.merkle_root, b.merkle_root);
+
+        // Fresh stealth address per withdrawal
+        assert_ne!(a.stealth_address, b.stealth_address);
+    }
+
+    #[test]
+    fn test_withdraw_inputs_reject_missing_leaf() {
+        let keys = StealthKeys::generate();
+        let (scan_pubkey, spend_pubkey) = keys.meta_address();
+        let leaves = [[1u8; 32]];
+
+        assert!(build_withdraw_inputs(&note(), &leaves, 1, 7, &scan_pubkey, &spend_pubkey).is_err());
+    }
+
+    #[test]
+    fn test_private_withdraw_separate_fee_payer() {
+        use crate::commands::withdraw::private_withdraw_accounts;
+        use anchor_lang::ToAccountMetas;
+        use solana_sdk::pubkey::Pubkey;
+
+        let keys = StealthKeys::generate();
+        let (scan_pubkey, spend_pubkey) = keys.meta_address();
+        let leaves = [[1u8; 32], [2u8; 32]];
+        let inputs = build_withdraw_inputs(&note(), &leaves, 0, 7, &scan_pubkey, &spend_pubkey).unwrap();
+
+        let relayer = Pubkey::new_unique();
+        let sponsor = Pubkey::new_unique();
+        let metas = private_withdraw_accounts(1_000_000_000, &relayer, &sponsor, &inputs).to_account_metas(None);
+
+        // Both sign; the sponsor pays rent, the relayer still earns the fee
+        assert_eq!(metas[0].pubkey, relayer);
+        assert!(metas[0].is_signer && metas[0].is_writable);
+        assert_eq!(metas[1].pubkey, sponsor);
+        assert!(metas[1].is_signer && metas[1].is_writable);
+
+        // Without a sponsor the relayer fills both roles
+        let metas = private_withdraw_accounts(1_000_000_000, &relayer, &relayer, &inputs).to_account_metas(None);
+        assert_eq!(metas[0].pubkey, metas[1].pubkey);
+    }
+}
+
+#[cfg(test)]
+mod sweep_tests {
+    use crate::commands::sweep::sweep_amount;
+
+    const FEE: u64 = 5_000;
+    const RENT_MIN: u64 = 890_880;
+
+    #[test]
+    fn test_sweep_empties_stealth_address() {
+        // Everything but the fee moves to an existing destination
+        assert_eq!(sweep_amount(1_000_000_000, FEE, RENT_MIN, RENT_MIN), Some(1_000_000_000 - FEE));
+        assert_eq!(sweep_amount(FEE + 1, FEE, RENT_MIN, RENT_MIN), Some(1));
+    }
+
+    #[test]
+    fn test_sweep_respects_fee_and_destination_rent() {
+        // Can't cover the fee
+        assert_eq!(sweep_amount(FEE, FEE, RENT_MIN, RENT_MIN), None);
+        assert_eq!(sweep_amount(1_000, FEE, RENT_MIN, RENT_MIN), None);
+
+        // New destination must end up rent-exempt
+        assert_eq!(sweep_amount(RENT_MIN, FEE, 0, RENT_MIN), None);
+        assert_eq!(sweep_amount(RENT_MIN + FEE, FEE, 0, RENT_MIN), Some(RENT_MIN));
+    }
+}
+
+#[cfg(test)]
+mod pool_tests {
+    use crate::commands::pool_stats::parse_denomination;
+    use crate::pool::decode_pool;
+    use anchor_lang::Discriminator;
+    use stealth::state::PrivacyPool;
+
+    #[test]
+    fn test_parse_denomination_valid() {
+        assert_eq!(parse_denomination("1").unwrap(), 1_000_000_000);
+        assert_eq!(parse_denomination("10").unwrap(), 10_000_000_000);
+        assert_eq!(parse_denomination("100").unwrap(), 100_000_000_000);
+        assert_eq!(parse_denomination("0.1").unwrap(), 100_000_000);
+    }
+
+    #[test]
+    fn test_parse_denomination_invalid() {
+        assert!(parse_denomination("2").is_err());
+        assert!(parse_denomination("0").is_err());
+        assert!(parse_denomination("1.5").is_err());
+    }
+
+    #[test]
+    fn test_note_nullifier_pda_matches_program() {
+        use crate::crypto::Note;
+        use crate::pool::note_nullifier_pda;
+        use stealth::crypto::compute_nullifier_hash;
+        use stealth::state::NullifierRecord;
+
+        let note = Note { nullifier: [7u8; 32], secret: [8u8; 32], amount: 10_000_000_000 };
+        let (expected, _) = NullifierRecord::derive_pda(10_000_000_000, &compute_nullifier_hash(&[7u8; 32]));
+        assert_eq!(note_nullifier_pda(&note), expected);
+
+        // Same note in another pool is a different nullifier record
+        let other = Note { amount: 1_000_000_000, ..note };
+        assert_ne!(note_nullifier_pda(&other), expected);
+    }
+
+    #[test]
+    fn test_note_commitment_uses_zero_recipient() {
+        use crate::crypto::Note;
+        use crate::pool::note_commitment;
+        use stealth::crypto::poseidon::compute_commitment;
+
+        let note = Note { nullifier: [7u8; 32], secret: [8u8; 32], amount: 1_000_000_000 };
+        assert_eq!(note_commitment(&note), compute_commitment(&[7u8; 32], &[8u8; 32], 1_000_000_000, &[0u8; 32]));
+
+        // Every field is bound
+        let base = note_commitment(&note);
+        assert_ne!(note_commitment(&Note { amount: 10_000_000_000, ..note.clone() }), base);
+        assert_ne!(note_commitment(&Note { nullifier: [9u8; 32], ..note.clone() }), base);
+        assert_ne!(note_commitment(&Note { secret: [9u8; 32], ..note }), base);
+    }
+
+    #[test]
+    fn test_ensure_note_scheme() {
+        use crate::pool::ensure_note_scheme;
+        use stealth::state::{CommitmentScheme, PrivacyPool};
+
+        let mut pool: PrivacyPool = bytemuck::Zeroable::zeroed();
+        pool.commitment_scheme = CommitmentScheme::Poseidon4.id();
+        assert!(ensure_note_scheme(&pool).is_ok());
+
+        // Notes made here would be unspendable in a Poseidon2 pool
+        pool.commitment_scheme = CommitmentScheme::Poseidon2.id();
+        assert!(ensure_note_scheme(&pool).is_err());
+        pool.commitment_scheme = 9;
+        assert!(ensure_note_scheme(&pool).unwrap_err().to_string().contains("unknown commitment scheme"));
+    }
+
+    #[test]
+    fn test_send_private_note_round_trip() {
+        use crate::crypto::{decrypt_note, note_ephemeral_pubkey, Note, StealthKeys};
+        use crate::pool::{commitment_leaf_pda, note_commitment, send_private_instruction};
+        use anchor_lang::{AnchorDeserialize, Discriminator};
+        use solana_sdk::pubkey::Pubkey;
+
+        let recipient = StealthKeys::generate();
+        let note = Note { nullifier: [7u8; 32], secret: [8u8; 32], amount: 1_000_000_000 };
+        let commitment = note_commitment(&note);
+
+        let instruction = send_private_instruction(Pubkey::new_unique(), &recipient.scan_pubkey, note.clone()).unwrap();
+        assert_eq!(instruction.accounts[2].pubkey, commitment_leaf_pda(note.amount, &commitment));
+
+        // Decode the args the way the program does
+        assert_eq!(instruction.data[..8], stealth::instruction::SendPrivate::DISCRIMINATOR);
+        let (denomination, sent_commitment, encrypted_note) =
+            <(u64, [u8; 32], Option<[u8; 128]>)>::deserialize(&mut &instruction.data[8..]).unwrap();
+        assert_eq!(denomination, note.amount);
+        assert_eq!(sent_commitment, commitment);
+
+        // The leaf's blob decrypts to the note for the recipient only
+        let blob = encrypted_note.unwrap();
+        let ephemeral = note_ephemeral_pubkey(&blob);
+        assert_eq!(decrypt_note(&recipient.scan_secret(), &ephemeral, &blob).unwrap(), note);
+        assert!(decrypt_note(&StealthKeys::generate().scan_secret(), &ephemeral, &blob).is_none());
+
+        // y = 2 isn't on the curve
+        let mut off_curve = [0u8; 32];
+        off_curve[0] = 2;
+        assert!(send_private_instruction(Pubkey::new_unique(), &off_curve, note).is_none());
+    }
+
+    #[test]
+    fn test_note_status_prefers_spent() {
+        use crate::commands::verify_note::NoteStatus;
+
+        assert_eq!(NoteStatus::from_lookup(true, false), NoteStatus::Spendable);
+        assert_eq!(NoteStatus::from_lookup(false, false), NoteStatus::NotFound);
+        assert_eq!(NoteStatus::from_lookup(true, true), NoteStatus::AlreadySpent);
+        assert_eq!(NoteStatus::from_lookup(false, true), NoteStatus::AlreadySpent);
+    }
+
+    #[test]
+    fn test_decode_pool_roundtrip() {
+        let mut pool: PrivacyPool = bytemuck::Zeroable::zeroed();
+        pool.denomination = 1_000_000_000;
+        pool.deposit_count = 12;
+        pool.withdrawal_count = 3;
+        pool.next_leaf_index = 12;
+        pool.merkle_root = [9u8; 32];
+
+        let mut data = PrivacyPool::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(&pool));
+
+        let decoded = decode_pool(&data).unwrap();
+        assert_eq!(decoded.denomination, 1_000_000_000);
+        assert_eq!(decoded.deposit_count, 12);
+        assert_eq!(decoded.withdrawal_count, 3);
+        assert_eq!(decoded.merkle_root, [9u8; 32]);
+    }
+
+    #[test]
+    fn test_decode_pool_rejects_wrong_account() {
+        let pool: PrivacyPool = bytemuck::Zeroable::zeroed();
+        let mut data = vec![0u8; 8];
+        data.extend_from_slice(bytemuck::bytes_of(&pool));
+        assert!(decode_pool(&data).is_err());
+        assert!(decode_pool(&[0u8; 16]).is_err());
+    }
+}
+
+#[cfg(test)]
+mod history_tests {
+    use crate::history::{
+        append_entry_to, filter_entries, load_entries_from, merge_received, parse_since,
+        recipient_hash, HistoryEntry, HistoryEvent,
+    };
+
+    fn sent_at(timestamp: i64) -> HistoryEntry {
+        HistoryEntry {
+            timestamp,
+            ..HistoryEntry::sent(&[1u8; 32], &[2u8; 32], 1_000_000, &[3u8; 32], "stealth", "sig-send")
+        }
+    }
+
+    fn withdrawn_at(timestamp: i64) -> HistoryEntry {
+        HistoryEntry { timestamp, ..HistoryEntry::withdrawn("from", "to", 500_000, "sig-withdraw") }
+    }
+
+    #[test]
+    fn test_history_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.jsonl");
+
+        assert!(load_entries_from(&path).unwrap().is_empty());
+
+        append_entry_to(&path, &sent_at(100)).unwrap();
+        append_entry_to(&path, &withdrawn_at(200)).unwrap();
+
+        let entries = load_entries_from(&path).unwrap();
+        assert_eq!(entries, vec![sent_at(100), withdrawn_at(200)]);
+    }
+
+    #[test]
+    fn test_history_skips_malformed_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.jsonl");
+
+        append_entry_to(&path, &sent_at(100)).unwrap();
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .and_then(|mut f| std::io::Write::write_all(&mut f, b"{truncated\n"))
+            .unwrap();
+        append_entry_to(&path, &withdrawn_at(200)).unwrap();
+
+        assert_eq!(load_entries_from(&path).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_history_hides_meta_address() {
+        let entry = sent_at(100);
+        let json = serde_json::to_string(&entry).unwrap();
+        assert!(!json.contains(&hex::encode([1u8; 32])));
+        assert!(!json.contains(&hex::encode([2u8; 32])));
+
+        let HistoryEvent::Sent { recipient_hash: hash, .. } = entry.event else { panic!("expected send") };
+        assert_eq!(hash, recipient_hash(&[1u8; 32], &[2u8; 32]));
+        assert_ne!(hash, recipient_hash(&[2u8; 32], &[1u8; 32]));
+    }
+
+    #[test]
+    fn test_history_filters() {
+        let entries = vec![sent_at(100), withdrawn_at(200), sent_at(300)];
+
+        assert_eq!(filter_entries(entries.clone(), false, false, false, None).len(), 3);
+        assert_eq!(filter_entries(entries.clone(), true, true, false, None).len(), 3);
+        assert_eq!(filter_entries(entries.clone(), true, false, false, None), vec![sent_at(100), sent_at(300)]);
+        assert_eq!(filter_entries(entries.clone(), false, true, false, None), vec![withdrawn_at(200)]);
+        assert_eq!(filter_entries(entries.clone(), false, false, false, Some(200)).len(), 2);
+        assert_eq!(filter_entries(entries, true, false, false, Some(200)), vec![sent_at(300)]);
+    }
+
+    #[test]
+    fn test_history_received_filter() {
+        let received = HistoryEntry::received("stealth", 1_000_000, false, 150);
+        let entries = vec![sent_at(100), received.clone(), withdrawn_at(200)];
+
+        assert_eq!(filter_entries(entries.clone(), false, false, false, None).len(), 3);
+        assert_eq!(filter_entries(entries.clone(), false, false, true, None), vec![received]);
+        assert_eq!(filter_entries(entries, false, true, false, None), vec![withdrawn_at(200)]);
+    }
+
+    #[test]
+    fn test_merge_received_is_idempotent() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.jsonl");
+        append_entry_to(&path, &sent_at(100)).unwrap();
+
+        let recovered = || {
+            vec![
+                HistoryEntry::received("addr-a", 1_000_000, false, 10),
+                HistoryEntry::received("leaf-b", 1_000_000_000, true, 20),
+                // Same address twice in one scan is recorded once
+                HistoryEntry::received("addr-a", 1_000_000, false, 10),
+            ]
+        };
+
+        assert_eq!(merge_received(&path, recovered()).unwrap(), 2);
+        assert_eq!(merge_received(&path, recovered()).unwrap(), 0);
+
+        // Existing entries are kept; only new addresses are appended
+        let entries = load_entries_from(&path).unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0], sent_at(100));
+
+        let mut more = recovered();
+        more.push(HistoryEntry::received("addr-c", 5, false, 30));
+        assert_eq!(merge_received(&path, more).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_parse_since() {
+        assert_eq!(parse_since("1970-01-02").unwrap(), 86_400);
+        assert_eq!(parse_since("2024-01-01").unwrap(), 1_704_067_200);
+        assert!(parse_since("01/01/2024").is_err());
+        assert!(parse_since("2024-13-01").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_history_file_is_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.jsonl");
+        append_entry_to(&path, &sent_at(100)).unwrap();
+
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
+
+#[cfg(test)]
+mod json_output_tests {
+    use crate::commands::address::AddressOutput;
+    use crate::commands::balance::BalanceOutput;
+    use crate::commands::pool_stats::PoolStatsOutput;
+    use crate::config::{format_meta_address, StoredKeys};
+    use crate::crypto::StealthKeys;
+    use stealth::state::PrivacyPool;
+
+    #[test]
+    fn test_address_output_has_no_secrets() {
+        let keys = StealthKeys::generate();
+        let stored = StoredKeys {
+            scan_secret: "11".repeat(32),
+            spend_secret: "22".repeat(32),
+            scan_pubkey: hex::encode(keys.scan_pubkey),
+            spend_pubkey: hex::encode(keys.spend_pubkey),
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+        };
+
+        let output = AddressOutput::from_stored(&stored).unwrap();
+        assert_eq!(output.meta_address, format_meta_address(&keys.scan_pubkey, &keys.spend_pubkey));
+
+        let json = serde_json::to_string(&output).unwrap();
+        assert!(!json.contains(&stored.scan_secret));
+        assert!(!json.contains(&stored.spend_secret));
+        assert!(!json.contains("secret"));
+    }
+
+    #[test]
+    fn test_balance_output_schema() {
+        let output = BalanceOutput {
+            available: 1_500_000_000,
+            total_received: 2_000_000_000,
+            addresses_with_balance: 1,
+            unspent_notes: 1,
+            total_notes: 2,
+        };
+
+        let value = serde_json::to_value(&output).unwrap();
+        assert_eq!(value["available"], 1_500_000_000u64);
+        assert_eq!(value["total_received"], 2_000_000_000u64);
+        assert_eq!(value["addresses_with_balance"], 1);
+        assert_eq!(value["unspent_notes"], 1);
+        assert_eq!(value["total_notes"], 2);
+    }
+
+    #[test]
+    fn test_pool_stats_output_from_pool() {
+        let mut pool: PrivacyPool = bytemuck::Zeroable::zeroed();
+        pool.is_active = true;
+        pool.deposit_count = 12;
+        pool.withdrawal_count = 3;
+        pool.next_leaf_index = 12;
+        pool.merkle_root = [9u8; 32];
+
+        let stats = PoolStatsOutput::from_pool(1_000_000_000, &pool);
+        assert_eq!(stats.address, crate::pool::pool_pda(1_000_000_000).to_string());
+        assert_eq!(stats.anonymity_set, 12);
+        assert_eq!(stats.unspent_notes, 9);
+
+        let value = serde_json::to_value(&stats).unwrap();
+        assert_eq!(value["denomination"], 1_000_000_000u64);
+        assert_eq!(value["is_active"], true);
+        assert_eq!(value["merkle_root"], "09".repeat(32));
+        assert_eq!(value["commitment_scheme"], 0);
+    }
+
+    #[test]
+    fn test_derive_address_output() {
+        use crate::commands::derive_address::derive;
+        use crate::crypto::scan_payment;
+        use solana_sdk::pubkey::Pubkey;
+
+        let keys = StealthKeys::generate();
+        let meta = format_meta_address(&keys.scan_pubkey, &keys.spend_pubkey);
+        let seed = "ab".repeat(32);
+
+        // A seed pins the address; without one every call is fresh
+        let first = derive(&meta, Some(&seed), false).unwrap();
+        assert_eq!(first.stealth_address, derive(&meta, Some(&seed), false).unwrap().stealth_address);
+        assert_ne!(derive(&meta, None, false).unwrap().stealth_address, derive(&meta, None, false).unwrap().stealth_address);
+
+        // The recipient finds the derived address
+        let stealth: Pubkey = first.stealth_address.parse().unwrap();
+        let mut ephemeral = [0u8; 32];
+        ephemeral.copy_from_slice(&hex::decode(&first.ephemeral_pubkey).unwrap());
+        assert!(scan_payment(&keys, &ephemeral, &stealth.to_bytes()).is_some());
+
+        let value = serde_json::to_value(&first).unwrap();
+        assert_eq!(value["commitment"].as_str().unwrap().len(), 64);
+
+        assert!(derive(&meta, Some("abcd"), false).is_err());
+    }
+}
+
+#[cfg(test)]
+mod rpc_tests {
+    use crate::rpc::{backoff_delay, is_transient, is_transient_send, retry};
+    use solana_client::client_error::{ClientError, ClientErrorKind};
+    use solana_client::rpc_request::RpcError;
+    use std::cell::Cell;
+    use std::io;
+    use std::time::Duration;
+
+    fn io_error() -> ClientError {
+        io::Error::new(io::ErrorKind::TimedOut, "timed out").into()
+    }
+
+    fn rpc_error(code: i64) -> ClientError {
+        ClientErrorKind::RpcError(RpcError::RpcResponseError {
+            code,
+            message: "error".to_string(),
+            data: solana_client::rpc_request::RpcResponseErrorData::Empty,
+        })
+        .into()
+    }
+
+    #[test]
+    fn test_transient_classification() {
+        assert!(is_transient(&io_error()));
+        assert!(is_transient(&rpc_error(-32005)));
+        assert!(!is_transient(&rpc_error(-32002)));
+        assert!(!is_transient(&ClientErrorKind::Custom("bad input".to_string()).into()));
+
+        // A timed-out send may have been accepted, so it is never resent
+        assert!(!is_transient_send(&io_error()));
+        assert!(!is_transient_send(&rpc_error(-32005)));
+    }
+
+    #[test]
+    fn test_retry_recovers_from_transient_errors() {
+        let calls = Cell::new(0);
+        let result = retry(3, Duration::ZERO, is_transient, || {
+            calls.set(calls.get() + 1);
+            if calls.get() < 3 { Err(io_error()) } else { Ok(42) }
+        });
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(calls.get(), 3);
+    }
+
+    #[test]
+    fn test_retry_gives_up() {
+        // Budget exhausted: first attempt + 2 retries
+        let calls = Cell::new(0);
+        let result: Result<(), _> = retry(2, Duration::ZERO, is_transient, || {
+            calls.set(calls.get() + 1);
+            Err(io_error())
+        });
+        assert!(result.is_err());
+        assert_eq!(calls.get(), 3);
+
+        // Permanent errors are returned immediately
+        let calls = Cell::new(0);
+        let result: Result<(), _> = retry(5, Duration::ZERO, is_transient, || {
+            calls.set(calls.get() + 1);
+            Err(rpc_error(-32002))
+        });
+        assert!(result.is_err());
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn test_backoff_doubles_with_jitter() {
+        let base = Duration::from_millis(100);
+        for attempt in 0..4 {
+            let expected = base * (1 << attempt);
+            let delay = backoff_delay(base, attempt);
+            assert!(delay >= expected);
+            assert!(delay <= expected + expected / 2);
+        }
+    }
+
+    #[test]
+    fn test_commitment_levels() {
+        use crate::rpc::Commitment;
+        use clap::ValueEnum;
+        use solana_sdk::commitment_config::CommitmentConfig;
+
+        assert_eq!(Commitment::default().config(), CommitmentConfig::confirmed());
+        assert_eq!(Commitment::Processed.config(), CommitmentConfig::processed());
+        assert_eq!(Commitment::Finalized.config(), CommitmentConfig::finalized());
+
+        for (name, level) in [
+            ("processed", Commitment::Processed),
+            ("confirmed", Commitment::Confirmed),
+            ("finalized", Commitment::Finalized),
+        ] {
+            assert_eq!(Commitment::from_str(name, false).unwrap(), level);
+        }
+        assert!(Commitment::from_str("recent", false).is_err());
+    }
+}
+
+#[cfg(test)]
+mod relayer_tests {
+    use crate::commands::relayers::{endpoint_url, rank_relayers};
+    use solana_sdk::pubkey::Pubkey;
+    use stealth::state::Relayer;
+
+    fn relayer(total_relayed: u64, total_failed: u64, is_active: bool) -> Relayer {
+        Relayer {
+            pubkey: Pubkey::new_unique(),
+            operator: Pubkey::new_unique(),
+            fee_bps: 50,
+            min_fee: 0,
+            max_fee: 0,
+            tx_count: 0,
+            total_earned: 0,
+            is_active,
+            supported_denominations: 0b111,
+            reputation: 50,
+            registered_at: 0,
+            last_active: 0,
+            min_denomination: 0,
+            endpoint_url: [0u8; 64],
+            total_relayed,
+            total_failed,
+            last_active_slot: 0,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn test_rank_relayers_by_success_rate() {
+        let flaky = relayer(5, 5, true);
+        let reliable = relayer(9, 1, true);
+        let perfect = relayer(2, 0, true);
+        let busy_perfect = relayer(20, 0, true);
+        let new = relayer(0, 0, true);
+        let inactive = relayer(100, 0, false);
+
+        let ranked = rank_relayers(vec![
+            flaky.clone(), new.clone(), inactive, reliable.clone(), perfect.clone(), busy_perfect.clone(),
+        ]);
+        let order: Vec<Pubkey> = ranked.iter().map(|r| r.pubkey).collect();
+
+        assert_eq!(
+            order,
+            vec![busy_perfect.pubkey, perfect.pubkey, reliable.pubkey, flaky.pubkey, new.pubkey]
+        );
+    }
+
+    #[test]
+    fn test_endpoint_url_strips_padding() {
+        let mut r = relayer(0, 0, true);
+        assert_eq!(endpoint_url(&r), "");
+
+        let url = b"https://relay.example.com";
+        r.endpoint_url[..url.len()].copy_from_slice(url);
+        assert_eq!(endpoint_url(&r), "https://relay.example.com");
+    }
 }
@@ -0,0 +1,125 @@
+//! wasm-bindgen exports of the pure-crypto functions
+//!
+//! Keys and addresses cross the boundary as raw 32-byte arrays
+//! (`Uint8Array` on the JS side). Malformed input (wrong length, or a point
+//! that doesn't decompress) yields `undefined` rather than a thrown error,
+//! matching the `Option` returns of the underlying functions.
+
+use curve25519_dalek::scalar::Scalar;
+use wasm_bindgen::prelude::wasm_bindgen;
+use zeroize::Zeroize;
+
+use crate::crypto::{self, Note, StealthKeys, ENCRYPTED_NOTE_SIZE};
+
+fn to_array32(bytes: &[u8]) -> Option<[u8; 32]> {
+    bytes.try_into().ok()
+}
+
+/// Fresh stealth address for a recipient meta-address
+///
+/// Returns `stealth_pubkey(32) || ephemeral_pubkey(32)`.
+#[wasm_bindgen(js_name = computeStealthAddress)]
+pub fn compute_stealth_address(scan_pubkey: &[u8], spend_pubkey: &[u8]) -> Option<Vec<u8>> {
+    let computation = crypto::compute_stealth_address(
+        &to_array32(scan_pubkey)?,
+        &to_array32(spend_pubkey)?,
+    )?;
+
+    let mut out = Vec::with_capacity(64);
+    out.extend_from_slice(&computation.stealth_pubkey);
+    out.extend_from_slice(&computation.ephemeral_pubkey);
+    Some(out)
+}
+
+/// Announcement commitment binding the ephemeral key to the meta-address
+#[wasm_bindgen(js_name = computeCommitment)]
+pub fn compute_commitment(
+    ephemeral_pubkey: &[u8],
+    scan_pubkey: &[u8],
+    spend_pubkey: &[u8],
+    stealth_pubkey: &[u8],
+) -> Option<Vec<u8>> {
+    let commitment = crypto::compute_commitment(
+        &to_array32(ephemeral_pubkey)?,
+        &to_array32(scan_pubkey)?,
+        &to_array32(spend_pubkey)?,
+        &to_array32(stealth_pubkey)?,
+    );
+    Some(commitment.to_vec())
+}
+
+/// View-key check: is this payment addressed to us?
+#[wasm_bindgen(js_name = checkPayment)]
+pub fn check_payment(
+    scan_secret: &[u8],
+    spend_pubkey: &[u8],
+    ephemeral_pubkey: &[u8],
+    payment_address: &[u8],
+) -> bool {
+    let (Some(mut secret), Some(spend), Some(ephemeral), Some(address)) = (
+        to_array32(scan_secret),
+        to_array32(spend_pubkey),
+        to_array32(ephemeral_pubkey),
+        to_array32(payment_address),
+    ) else {
+        return false;
+    };
+    let scalar = Scalar::from_bytes_mod_order(secret);
+    secret.zeroize();
+
+    crypto::check_payment(&scalar, &spend, &ephemeral, &address)
+}
+
+/// Full scan: returns the stealth address's spending key if it is ours
+#[wasm_bindgen(js_name = scanPayment)]
+pub fn scan_payment(
+    scan_secret: &[u8],
+    spend_secret: &[u8],
+    ephemeral_pubkey: &[u8],
+    payment_address: &[u8],
+) -> Option<Vec<u8>> {
+    let mut scan = to_array32(scan_secret)?;
+    let mut spend = to_array32(spend_secret)?;
+    let keys = StealthKeys::from_secrets(&scan, &spend);
+    scan.zeroize();
+    spend.zeroize();
+
+    let result = crypto::scan_payment(
+        &keys,
+        &to_array32(ephemeral_pubkey)?,
+        &to_array32(payment_address)?,
+    )?;
+    Some(result.spending_key_bytes().to_vec())
+}
+
+/// Encrypt deposit secrets to a recipient's scan key (128-byte blob)
+#[wasm_bindgen(js_name = encryptNote)]
+pub fn encrypt_note(
+    scan_pubkey: &[u8],
+    nullifier: &[u8],
+    secret: &[u8],
+    amount: u64,
+) -> Option<Vec<u8>> {
+    let note = Note {
+        nullifier: to_array32(nullifier)?,
+        secret: to_array32(secret)?,
+        amount,
+    };
+    let blob = crypto::encrypt_note(&to_array32(scan_pubkey)?, note)?;
+    Some(blob.to_vec())
+}
+
+/// Decrypt a note blob addressed to this scan key
+///
+/// Returns `nullifier(32) || secret(32) || amount(8, LE)`.
+#[wasm_bindgen(js_name = decryptNote)]
+pub fn decrypt_note(scan_secret: &[u8], ciphertext: &[u8]) -> Option<Vec<u8>> {
+    let ciphertext: &[u8; ENCRYPTED_NOTE_SIZE] = ciphertext.try_into().ok()?;
+    let mut secret = to_array32(scan_secret)?;
+    let scalar = Scalar::from_bytes_mod_order(secret);
+    secret.zeroize();
+
+    let ephemeral = crypto::note_ephemeral_pubkey(ciphertext);
+    let note = crypto::decrypt_note(&scalar, &ephemeral, ciphertext)?;
+    Some(note.to_plaintext().to_vec())
+}
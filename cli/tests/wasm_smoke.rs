@@ -0,0 +1,30 @@
+//! Smoke test for the browser bindings, run natively:
+//! `cargo test -p stealth-cli --no-default-features --features wasm --test wasm_smoke`
+#![cfg(feature = "wasm")]
+
+use stealth_cli::crypto::StealthKeys;
+use stealth_cli::wasm;
+
+#[test]
+fn test_wasm_exports_round_trip() {
+    let keys = StealthKeys::generate();
+    let (scan_secret, spend_secret) = keys.export_secrets();
+
+    let address = wasm::compute_stealth_address(&keys.scan_pubkey, &keys.spend_pubkey).unwrap();
+    let (stealth, ephemeral) = address.split_at(32);
+
+    assert!(wasm::check_payment(&scan_secret, &keys.spend_pubkey, ephemeral, stealth));
+    assert!(wasm::scan_payment(&scan_secret, &spend_secret, ephemeral, stealth).is_some());
+    assert_eq!(
+        wasm::compute_commitment(ephemeral, &keys.scan_pubkey, &keys.spend_pubkey, stealth).unwrap().len(),
+        32
+    );
+
+    let blob = wasm::encrypt_note(&keys.scan_pubkey, &[1u8; 32], &[2u8; 32], 42).unwrap();
+    let plaintext = wasm::decrypt_note(&scan_secret, &blob).unwrap();
+    assert_eq!(&plaintext[..32], &[1u8; 32]);
+    assert_eq!(u64::from_le_bytes(plaintext[64..].try_into().unwrap()), 42);
+
+    // Wrong-length keys are rejected rather than panicking
+    assert!(wasm::compute_stealth_address(&[0u8; 31], &keys.spend_pubkey).is_none());
+}
@@ -58,8 +58,8 @@ export async function fetchCommitmentLeaves(
   const leaves: OnChainCommitment[] = [];
 
   try {
-    // CommitmentLeaf account size: 8 (disc) + 32 (commitment) + 8 (leaf_index) + 8 (timestamp) + 128 (encrypted_note) + 33 (amount_commitment) + 32 (range_proof_hash) + 1 (bump) = 250
-    const COMMITMENT_LEAF_SIZE = 250;
+    // CommitmentLeaf account size: 8 (disc) + 32 (commitment) + 8 (leaf_index) + 8 (timestamp) + 128 (encrypted_note) + 33 (amount_commitment) + 32 (range_proof_hash) + 8 (slot) + 1 (bump) = 258
+    const COMMITMENT_LEAF_SIZE = 258;
 
     const accounts = await connection.getProgramAccounts(PROGRAM_ID, {
       filters: [
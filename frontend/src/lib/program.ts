@@ -13,6 +13,7 @@ import {
   SystemProgram,
   LAMPORTS_PER_SOL,
   ComputeBudgetProgram,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
 } from '@solana/web3.js';
 import { Buffer } from 'buffer';
 
@@ -335,6 +336,8 @@ export interface Attestation {
   verifier: Uint8Array; // 32 bytes
   signature: Uint8Array; // 64 bytes
   verifiedAt: bigint; // i64 timestamp
+  vkVersion: number; // u32 verification key version
+  method: number; // u8 verification method
 }
 
 // Announcement PDA seed
@@ -350,6 +353,16 @@ export function getAnnouncementPDA(ephemeralPubkey: Uint8Array): [PublicKey, num
   );
 }
 
+// Verification oracle PDA seed
+const VERIFICATION_ORACLE_SEED = Buffer.from('verification_oracle');
+
+/**
+ * Get the verification oracle PDA that withdrawal attestations are checked against
+ */
+export function getVerificationOraclePDA(): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([VERIFICATION_ORACLE_SEED], PROGRAM_ID);
+}
+
 /**
  * Stealth address parameters for private withdrawal
  * These prove the recipient address was correctly derived from a meta-address
@@ -369,12 +382,14 @@ export interface StealthAddressParams {
  */
 export interface PrivateWithdrawParams {
   relayer: PublicKey;
+  feePayer?: PublicKey; // Pays account rent (default: relayer)
   stealth: StealthAddressParams; // Stealth address for recipient privacy
   denomination: bigint; // 1, 10, or 100 SOL (in lamports)
   proof: Uint8Array; // Noir proof bytes
   merkleRoot: Uint8Array; // 32 bytes
   nullifierHash: Uint8Array; // 32 bytes
   relayerFee: bigint; // lamports
+  depositSlot?: bigint; // Note's CommitmentLeaf slot (checked against min_age_slots, default 0)
   attestation?: Attestation; // Optional in dev mode, required in production
 }
 
@@ -392,12 +407,14 @@ export function buildPrivateWithdrawInstruction(
 ): TransactionInstruction {
   const {
     relayer,
+    feePayer = relayer,
     stealth,
     denomination,
     proof,
     merkleRoot,
     nullifierHash,
     relayerFee,
+    depositSlot = BigInt(0),
     attestation,
   } = params;
 
@@ -411,17 +428,19 @@ export function buildPrivateWithdrawInstruction(
   // Format: discriminator (8) + denomination (8) + WithdrawProof (serialized) + relayer_fee (8)
   // WithdrawProof = proof_len (4) + proof (var) + public_inputs + attestation_option
   // public_inputs = merkle_root (32) + nullifier_hash (32) + stealth_address (32) +
-  //                 ephemeral_pubkey (32) + scan_pubkey (32) + spend_pubkey (32) + stealth_commitment (32)
-  // attestation_option = has_attestation (1) + [attestation (168)]
-  // attestation = proof_hash (32) + public_inputs_hash (32) + verifier (32) + signature (64) + verified_at (8)
+  //                 ephemeral_pubkey (32) + scan_pubkey (32) + spend_pubkey (32) + stealth_commitment (32) +
+  //                 deposit_slot (8)
+  // attestation_option = has_attestation (1) + [attestation (173)]
+  // attestation = proof_hash (32) + public_inputs_hash (32) + verifier (32) + signature (64) + verified_at (8) +
+  //               vk_version (4) + method (1)
 
   const proofLen = proof.length;
   const hasAttestation = attestation ? 1 : 0;
-  const attestationSize = hasAttestation ? (32 + 32 + 32 + 64 + 8) : 0;
+  const attestationSize = hasAttestation ? (32 + 32 + 32 + 64 + 8 + 4 + 1) : 0;
 
   // Public inputs now include stealth address fields (no amount - implicit from denomination)
-  // merkle_root(32) + nullifier_hash(32) + stealth_address(32) + ephemeral(32) + scan(32) + spend(32) + commitment(32) = 224
-  const dataSize = 8 + 8 + 4 + proofLen + 224 + 1 + attestationSize + 8;
+  // merkle_root(32) + nullifier_hash(32) + stealth_address(32) + ephemeral(32) + scan(32) + spend(32) + commitment(32) + deposit_slot(8) = 232
+  const dataSize = 8 + 8 + 4 + proofLen + 232 + 1 + attestationSize + 8;
   const data = Buffer.alloc(dataSize);
 
   let offset = 0;
@@ -462,6 +481,9 @@ export function buildPrivateWithdrawInstruction(
   Buffer.from(stealth.stealthCommitment).copy(data, offset);
   offset += 32;
 
+  data.writeBigUInt64LE(depositSlot, offset);
+  offset += 8;
+
   // Attestation option
   data.writeUInt8(hasAttestation, offset);
   offset += 1;
@@ -481,6 +503,12 @@ export function buildPrivateWithdrawInstruction(
 
     data.writeBigInt64LE(attestation.verifiedAt, offset);
     offset += 8;
+
+    data.writeUInt32LE(attestation.vkVersion, offset);
+    offset += 4;
+
+    data.writeUInt8(attestation.method, offset);
+    offset += 1;
   }
 
   // Relayer fee
@@ -489,11 +517,16 @@ export function buildPrivateWithdrawInstruction(
   // Build accounts (now includes announcement for recipient scanning)
   const accounts = [
     { pubkey: relayer, isSigner: true, isWritable: true },
+    { pubkey: feePayer, isSigner: true, isWritable: true },
     { pubkey: poolPDA, isSigner: false, isWritable: true },
     { pubkey: configPDA, isSigner: false, isWritable: false },
     { pubkey: nullifierPDA, isSigner: false, isWritable: true },
     { pubkey: stealth.stealthAddress, isSigner: false, isWritable: true },
     { pubkey: announcementPDA, isSigner: false, isWritable: true },
+    // relayer_fee_recipient: none (Anchor reads the program id as None)
+    { pubkey: PROGRAM_ID, isSigner: false, isWritable: false },
+    { pubkey: getVerificationOraclePDA()[0], isSigner: false, isWritable: false },
+    { pubkey: SYSVAR_INSTRUCTIONS_PUBKEY, isSigner: false, isWritable: false },
     { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
   ];
 
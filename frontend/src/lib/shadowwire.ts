@@ -586,6 +586,8 @@ export class Shadowwire {
         verifier: formattedAttestation.verifier,
         signature: formattedAttestation.signature,
         verifiedAt: formattedAttestation.verifiedAt,
+        vkVersion: formattedAttestation.vkVersion,
+        method: formattedAttestation.method,
       };
 
       // Derive stealth address for withdrawal
@@ -1,6 +1,7 @@
 import { Keypair, PublicKey } from '@solana/web3.js';
 import { x25519 } from '@noble/curves/ed25519.js';
 import bs58 from 'bs58';
+import { sha256 as nobleSha256 } from '@noble/hashes/sha2.js';
 
 // Stealth address utilities using proper X25519 ECDH
 
@@ -138,20 +139,44 @@ export async function generateStealthKeys(seedKeypair: Keypair): Promise<Stealth
   };
 }
 
-// Format meta-address for sharing
+// Bytes of SHA-256 appended to the 64 key bytes (matches the CLI)
+const META_ADDRESS_CHECKSUM_LEN = 4;
+
+function metaAddressChecksum(keys: Uint8Array): Uint8Array {
+  return nobleSha256(keys).slice(0, META_ADDRESS_CHECKSUM_LEN);
+}
+
+// Format meta-address for sharing: scan || spend || checksum
 export function formatMetaAddress(scanPubkey: Uint8Array, spendPubkey: Uint8Array): string {
   const combined = concat(scanPubkey, spendPubkey);
-  return `stealth:${bs58.encode(combined)}`;
+  return `stealth:${bs58.encode(concat(combined, metaAddressChecksum(combined)))}`;
 }
 
-// Parse meta-address
-export function parseMetaAddress(metaAddress: string): { scanPubkey: Uint8Array; spendPubkey: Uint8Array } {
+// Parse meta-address. Legacy 64-byte addresses have no checksum, so a typo
+// can't be caught; they're only accepted with `allowLegacy`.
+export function parseMetaAddress(
+  metaAddress: string,
+  options: { allowLegacy?: boolean } = {}
+): { scanPubkey: Uint8Array; spendPubkey: Uint8Array } {
   const encoded = metaAddress.startsWith('stealth:')
     ? metaAddress.slice(8)
     : metaAddress;
 
   const bytes = bs58.decode(encoded);
-  if (bytes.length !== 64) {
+  if (bytes.length === 64 + META_ADDRESS_CHECKSUM_LEN) {
+    const expected = metaAddressChecksum(bytes.slice(0, 64));
+    let diff = 0;
+    for (let i = 0; i < META_ADDRESS_CHECKSUM_LEN; i++) {
+      diff |= expected[i] ^ bytes[64 + i];
+    }
+    if (diff !== 0) {
+      throw new Error('Meta-address checksum mismatch');
+    }
+  } else if (bytes.length === 64) {
+    if (!options.allowLegacy) {
+      throw new Error('Meta-address has no checksum (legacy format)');
+    }
+  } else {
     throw new Error('Invalid meta-address length');
   }
 
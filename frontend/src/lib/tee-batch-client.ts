@@ -31,7 +31,12 @@ const STAGING_SEED = Buffer.from('staging');
 const BATCH_SEED = Buffer.from('batch');
 const COMMITMENT_SEED = Buffer.from('tee_commitment');
 
+// StagingAccount.batch_id: discriminator, user, balance, commitment_count,
+// created_at, delegate; `committed` follows it
+const STAGING_BATCH_ID_OFFSET = 8 + 32 + 8 + 8 + 8 + 32;
+
 // Batch configuration
+export const BATCH_MAX_SIZE = 10; // Capacity passed to initialize_batch
 export const BATCH_THRESHOLD = 3; // Minimum deposits before settlement
 export const BATCH_TIMEOUT_MS = 10 * 60 * 1000; // 10 minutes max wait
 
@@ -71,12 +76,20 @@ export interface StagingAccount {
   balance: bigint;
   commitmentCount: bigint;
   createdAt: bigint;
+  /** Key allowed to create commitments for the owner (default key = none) */
+  delegate: PublicKey;
+  /** Batch holding the unreleased commitments */
+  batchId: bigint;
+  /** Lamports committed into `batchId` and not yet released */
+  committed: bigint;
   bump: number;
 }
 
 export interface CommitmentBatch {
   id: bigint;
   authority: PublicKey;
+  maxSize: number;
+  threshold: number;
   commitments: Uint8Array[];
   denominations: bigint[];
   commitmentCount: number;
@@ -136,6 +149,9 @@ const DISCRIMINATORS = {
   initializeBatch: Buffer.from([126, 44, 205, 90, 220, 105, 105, 193]),
   withdrawFromStaging: Buffer.from([170, 210, 198, 109, 3, 235, 107, 96]),
   releaseSettledFunds: Buffer.from([240, 26, 68, 199, 78, 26, 192, 59]),
+  releaseAndShield: Buffer.from([179, 79, 10, 123, 159, 135, 83, 205]),
+  authorizeDelegate: Buffer.from([88, 13, 156, 92, 228, 219, 214, 57]),
+  closeStaging: Buffer.from([115, 56, 62, 251, 180, 145, 18, 14]),
 };
 
 function buildInitializeStagingIx(
@@ -185,7 +201,8 @@ function buildCreateCommitmentIx(
   encryptedNote: Uint8Array | null,
 ): TransactionInstruction {
   // Data: discriminator (8) + denomination (8) + commitment (32) + option<encrypted_note> (1 + 128)
-  const data = Buffer.alloc(8 + 8 + 32 + 1 + 128);
+  // + option<preimage> (1, always None)
+  const data = Buffer.alloc(8 + 8 + 32 + 1 + 128 + 1);
   let offset = 0;
 
   DISCRIMINATORS.createPrivateCommitment.copy(data, offset);
@@ -201,10 +218,14 @@ function buildCreateCommitmentIx(
     data.writeUInt8(1, offset); // Some
     offset += 1;
     Buffer.from(encryptedNote).copy(data, offset);
+    offset += 128;
   } else {
     data.writeUInt8(0, offset); // None
+    offset += 1;
   }
 
+  data.writeUInt8(0, offset); // preimage: None
+
   return new TransactionInstruction({
     programId: TEE_BRIDGE_PROGRAM_ID,
     keys: [
@@ -222,10 +243,19 @@ function buildInitializeBatchIx(
   authority: PublicKey,
   batch: PublicKey,
   batchId: bigint,
+  maxSize: number = BATCH_MAX_SIZE,
+  threshold: number = BATCH_THRESHOLD,
+  enforceUserQuota: boolean = false,
+  settleRewardLamports: bigint = BigInt(0),
 ): TransactionInstruction {
-  const data = Buffer.alloc(16);
+  const data = Buffer.alloc(28);
   DISCRIMINATORS.initializeBatch.copy(data, 0);
   data.writeBigUInt64LE(batchId, 8);
+  data.writeUInt8(maxSize, 16);
+  data.writeUInt8(threshold, 17);
+  data.writeUInt8(0, 18); // max_age_seconds: None (program default)
+  data.writeUInt8(enforceUserQuota ? 1 : 0, 19);
+  data.writeBigUInt64LE(settleRewardLamports, 20); // keeper reward, skimmed at settlement
 
   return new TransactionInstruction({
     programId: TEE_BRIDGE_PROGRAM_ID,
@@ -238,6 +268,42 @@ function buildInitializeBatchIx(
   });
 }
 
+function buildAuthorizeDelegateIx(
+  user: PublicKey,
+  staging: PublicKey,
+  delegate: PublicKey,
+): TransactionInstruction {
+  const data = Buffer.alloc(8 + 32);
+  DISCRIMINATORS.authorizeDelegate.copy(data, 0);
+  delegate.toBuffer().copy(data, 8);
+
+  return new TransactionInstruction({
+    programId: TEE_BRIDGE_PROGRAM_ID,
+    keys: [
+      { pubkey: user, isSigner: true, isWritable: false },
+      { pubkey: staging, isSigner: false, isWritable: true },
+    ],
+    data,
+  });
+}
+
+function buildCloseStagingIx(
+  user: PublicKey,
+  staging: PublicKey,
+): TransactionInstruction {
+  const data = Buffer.alloc(8);
+  DISCRIMINATORS.closeStaging.copy(data, 0);
+
+  return new TransactionInstruction({
+    programId: TEE_BRIDGE_PROGRAM_ID,
+    keys: [
+      { pubkey: user, isSigner: true, isWritable: true },
+      { pubkey: staging, isSigner: false, isWritable: true },
+    ],
+    data,
+  });
+}
+
 function buildWithdrawFromStagingIx(
   user: PublicKey,
   staging: PublicKey,
@@ -272,7 +338,40 @@ function buildReleaseSettledFundsIx(
     keys: [
       { pubkey: user, isSigner: true, isWritable: true },
       { pubkey: staging, isSigner: false, isWritable: true },
-      { pubkey: batch, isSigner: false, isWritable: false },
+      { pubkey: batch, isSigner: false, isWritable: true },
+    ],
+    data,
+  });
+}
+
+/**
+ * Release settled funds and compress them in one instruction
+ *
+ * `lightIx` is the Light system program's compress instruction for the
+ * same amount (e.g. `LightSystemProgram.compress` with the user as payer);
+ * its data and accounts are forwarded in the CPI.
+ */
+export function buildReleaseAndShieldIx(
+  user: PublicKey,
+  staging: PublicKey,
+  batch: PublicKey,
+  amount: bigint,
+  lightIx: TransactionInstruction,
+): TransactionInstruction {
+  const data = Buffer.alloc(20 + lightIx.data.length);
+  DISCRIMINATORS.releaseAndShield.copy(data, 0);
+  data.writeBigUInt64LE(amount, 8);
+  data.writeUInt32LE(lightIx.data.length, 16);
+  Buffer.from(lightIx.data).copy(data, 20);
+
+  return new TransactionInstruction({
+    programId: TEE_BRIDGE_PROGRAM_ID,
+    keys: [
+      { pubkey: user, isSigner: true, isWritable: true },
+      { pubkey: staging, isSigner: false, isWritable: true },
+      { pubkey: batch, isSigner: false, isWritable: true },
+      { pubkey: lightIx.programId, isSigner: false, isWritable: false },
+      ...lightIx.keys,
     ],
     data,
   });
@@ -377,6 +476,55 @@ export class TeeBatchClient {
     return stagingPDA;
   }
 
+  /**
+   * Authorize a delegate (e.g. a session key) to create commitments from the
+   * user's staging balance. Pass PublicKey.default to revoke.
+   */
+  async authorizeDelegate(
+    user: PublicKey,
+    delegate: PublicKey,
+    signTransaction: (tx: Transaction) => Promise<Transaction>,
+  ): Promise<string> {
+    const [stagingPDA] = getStagingPDA(user);
+    const ix = buildAuthorizeDelegateIx(user, stagingPDA, delegate);
+    const tx = new Transaction().add(ix);
+
+    const { blockhash, lastValidBlockHeight } = await this.connection.getLatestBlockhash();
+    tx.recentBlockhash = blockhash;
+    tx.feePayer = user;
+
+    const signedTx = await signTransaction(tx);
+    const txId = await this.connection.sendRawTransaction(signedTx.serialize());
+    await this.connection.confirmTransaction({ signature: txId, blockhash, lastValidBlockHeight });
+
+    console.log('[TeeBatch] Staging delegate updated:', txId);
+    return txId;
+  }
+
+  /**
+   * Close the user's staging account and reclaim its rent. Withdraw the
+   * balance and release any committed funds first, or the program rejects it.
+   */
+  async closeStaging(
+    user: PublicKey,
+    signTransaction: (tx: Transaction) => Promise<Transaction>,
+  ): Promise<string> {
+    const [stagingPDA] = getStagingPDA(user);
+    const ix = buildCloseStagingIx(user, stagingPDA);
+    const tx = new Transaction().add(ix);
+
+    const { blockhash, lastValidBlockHeight } = await this.connection.getLatestBlockhash();
+    tx.recentBlockhash = blockhash;
+    tx.feePayer = user;
+
+    const signedTx = await signTransaction(tx);
+    const txId = await this.connection.sendRawTransaction(signedTx.serialize());
+    await this.connection.confirmTransaction({ signature: txId, blockhash, lastValidBlockHeight });
+
+    console.log('[TeeBatch] Staging account closed:', txId);
+    return txId;
+  }
+
   /**
    * Get current batch status from on-chain
    */
@@ -403,7 +551,7 @@ export class TeeBatchClient {
         };
       }
 
-      const { commitmentCount, totalAmount, createdAt, settled } = this.parseBatchData(batchInfo.data);
+      const { threshold, commitmentCount, totalAmount, createdAt, settled } = this.parseBatchData(batchInfo.data);
 
       const elapsed = Date.now() - createdAt * 1000;
       const timeRemaining = Math.max(0, BATCH_TIMEOUT_MS - elapsed);
@@ -411,9 +559,9 @@ export class TeeBatchClient {
       return {
         batchId: this.currentBatchId,
         pendingCount: commitmentCount,
-        threshold: BATCH_THRESHOLD,
+        threshold,
         totalAmount,
-        isReady: commitmentCount >= BATCH_THRESHOLD,
+        isReady: commitmentCount >= threshold,
         settled,
         createdAt: createdAt * 1000,
         timeRemaining,
@@ -437,23 +585,42 @@ export class TeeBatchClient {
    * Parse raw batch account data into structured fields
    */
   private parseBatchData(data: Buffer): {
+    maxSize: number;
+    threshold: number;
     commitmentCount: number;
     totalAmount: bigint;
     createdAt: number;
     settled: boolean;
+    settleReward: bigint;
   } {
-    // Layout: discriminator(8) + id(8) + authority(32) + commitments(32*10) + denominations(8*10) + commitment_count(1) + total_amount(8) + created_at(8) + settled(1) + bump(1)
-    const countOffset = 8 + 8 + 32 + 320 + 80;
+    // Layout: discriminator(8) + id(8) + authority(32) + max_size(1) + threshold(1)
+    //   + commitments(4 + 32*n) + denominations(4 + 8*n) + commitment_count(1)
+    //   + total_amount(8) + created_at(8) + settled(1) + ... + settle_reward_lamports(8) + bump(1)
+    const maxSizeOffset = 8 + 8 + 32;
+    const commitmentsOffset = maxSizeOffset + 2;
+    const n = data.readUInt32LE(commitmentsOffset);
+    const denominationsOffset = commitmentsOffset + 4 + 32 * n;
+    const countOffset = denominationsOffset + 4 + 8 * n;
+    // ... + max_age_seconds(8) + expired(1) + enforce_user_quota(1) + user_counts(4 + 33*m)
+    const userCountsOffset = countOffset + 1 + 8 + 8 + 1 + 8 + 1 + 1;
+    const m = data.readUInt32LE(userCountsOffset);
+    const totalAmount = data.readBigUInt64LE(countOffset + 1);
+    const settleRewardLamports = data.readBigUInt64LE(userCountsOffset + 4 + 33 * m);
+    // Capped at MAX_SETTLE_REWARD_BPS (0.5%) of total_amount, as in settle_batch
+    const rewardCap = totalAmount * BigInt(50) / BigInt(10_000);
     return {
+      maxSize: data[maxSizeOffset],
+      threshold: data[maxSizeOffset + 1],
       commitmentCount: data[countOffset],
-      totalAmount: data.readBigUInt64LE(countOffset + 1),
+      totalAmount,
       createdAt: Number(data.readBigInt64LE(countOffset + 1 + 8)),
       settled: data[countOffset + 1 + 8 + 8] === 1,
+      settleReward: settleRewardLamports < rewardCap ? settleRewardLamports : rewardCap,
     };
   }
 
   /**
-   * Advance currentBatchId past any settled or full (10/10) batches
+   * Advance currentBatchId past any settled or full batches
    */
   private async advanceToActiveBatch(): Promise<void> {
     // Check up to 10 batches ahead to find an active one
@@ -466,10 +633,10 @@ export class TeeBatchClient {
         return;
       }
 
-      const { commitmentCount, settled } = this.parseBatchData(batchInfo.data);
+      const { maxSize, commitmentCount, settled } = this.parseBatchData(batchInfo.data);
 
-      if (settled || commitmentCount >= 10) {
-        console.log(`[TeeBatch] Batch ${this.currentBatchId} is ${settled ? 'settled' : 'full'} (${commitmentCount}/10), advancing...`);
+      if (settled || commitmentCount >= maxSize) {
+        console.log(`[TeeBatch] Batch ${this.currentBatchId} is ${settled ? 'settled' : 'full'} (${commitmentCount}/${maxSize}), advancing...`);
         this.currentBatchId++;
       } else {
         // Found an active batch
@@ -490,7 +657,7 @@ export class TeeBatchClient {
     const batchInfo = await this.connection.getAccountInfo(batchPDA);
     if (batchInfo) {
       // Check if batch is settled, if so increment to next batch
-      const settled = batchInfo.data[8 + 8 + 32 + 320 + 80 + 1 + 8 + 8] === 1;
+      const { settled } = this.parseBatchData(batchInfo.data);
       if (settled) {
         this.currentBatchId++;
         return this.ensureBatch(authority, signTransaction);
@@ -676,22 +843,31 @@ export class TeeBatchClient {
 
       console.log('[TeeBatch] Batch marked as settled:', settleTxId);
 
-      // Step 2: Release settler's committed funds from staging PDA back to wallet
+      // Step 2: Release settler's committed funds from the batch back to wallet
       const [stagingPDA] = getStagingPDA(settler);
       const stagingInfo = await this.connection.getAccountInfo(stagingPDA);
 
-      // Calculate releasable amount: total lamports minus rent-exempt minimum
+      // Releasable amount: the staging account's unreleased committed funds,
+      // if they were committed into this batch
       let releaseAmount = BigInt(0);
       if (stagingInfo) {
-        const rentExempt = await this.connection.getMinimumBalanceForRentExemption(stagingInfo.data.length);
-        const excess = BigInt(stagingInfo.lamports) - BigInt(rentExempt);
-        if (excess > BigInt(0)) {
-          releaseAmount = excess;
+        const stagingBatchId = stagingInfo.data.readBigUInt64LE(STAGING_BATCH_ID_OFFSET);
+        if (stagingBatchId === this.currentBatchId) {
+          releaseAmount = stagingInfo.data.readBigUInt64LE(STAGING_BATCH_ID_OFFSET + 8);
         }
       }
 
+      // What actually arrives: the release less its (rounded-up) share of the settle reward
+      let payout = releaseAmount;
+      const settledBatchInfo = await this.connection.getAccountInfo(batchPDA);
+      if (settledBatchInfo && releaseAmount > BigInt(0)) {
+        const { totalAmount, settleReward } = this.parseBatchData(settledBatchInfo.data);
+        const share = (releaseAmount * settleReward + totalAmount - BigInt(1)) / totalAmount;
+        payout = releaseAmount - share;
+      }
+
       if (releaseAmount > BigInt(0)) {
-        console.log(`[TeeBatch] Releasing ${Number(releaseAmount) / LAMPORTS_PER_SOL} SOL from staging...`);
+        console.log(`[TeeBatch] Releasing ${Number(payout) / LAMPORTS_PER_SOL} SOL from batch...`);
         const releaseIx = buildReleaseSettledFundsIx(settler, stagingPDA, batchPDA, releaseAmount);
         const releaseTx = new Transaction().add(releaseIx);
 
@@ -706,13 +882,13 @@ export class TeeBatchClient {
           blockhash: releaseBlockhash.blockhash,
           lastValidBlockHeight: releaseBlockhash.lastValidBlockHeight,
         });
-        console.log('[TeeBatch] Staging release successful:', releaseTxId);
+        console.log('[TeeBatch] Batch release successful:', releaseTxId);
       }
 
       // Step 3: Shield settler's own amount to Light Protocol
       console.log('[TeeBatch] Shielding settler funds to Light Protocol...');
 
-      const shieldAmount = releaseAmount > BigInt(0) ? releaseAmount : batchStatus.totalAmount;
+      const shieldAmount = releaseAmount > BigInt(0) ? payout : batchStatus.totalAmount;
       const shieldResult = await shieldSolWithWallet(
         this.connection,
         settler,
@@ -105,6 +105,8 @@ export interface RelayerState {
   totalFeesCollected: bigint;
   requestCounter: bigint;
   isActive: boolean;
+  /** Bumped on every TEE key rotation; requests encrypted to an older version fail */
+  teePubkeyVersion: number;
 }
 
 /**
@@ -336,10 +338,12 @@ export class TeeRelayerClient {
       const requestLenBuffer = Buffer.alloc(4);
       requestLenBuffer.writeUInt32LE(encryptedRequest.length);
 
+      // request_nonce: None (PDA keyed by the request counter)
       const data = Buffer.concat([
         discriminator,
         requestLenBuffer,
         Buffer.from(encryptedRequest),
+        Buffer.from([0]),
       ]);
 
       const instruction = new TransactionInstruction({
@@ -416,6 +420,7 @@ export class TeeRelayerClient {
       totalFeesCollected: data.readBigUInt64LE(74),
       requestCounter: data.readBigUInt64LE(82),
       isActive: data[90] === 1,
+      teePubkeyVersion: data.readUInt32LE(99),
     };
   }
 
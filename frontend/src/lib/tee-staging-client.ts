@@ -117,6 +117,9 @@ export function getCommitmentPDA(commitment: Uint8Array): [PublicKey, number] {
 // Commitment Generation
 // ============================================
 
+/** Domain separator tee-bridge prefixes to commitment preimages */
+export const COMMITMENT_DOMAIN = new TextEncoder().encode('nocturne_tee_v1');
+
 function generateSecureRandom(length: number): Uint8Array {
   const array = new Uint8Array(length);
   crypto.getRandomValues(array);
@@ -131,10 +134,13 @@ export function generateCommitmentSecrets(): {
   const nullifier = generateSecureRandom(32);
   const secret = generateSecureRandom(32);
 
-  // commitment = keccak256(nullifier || secret)
-  const preimage = new Uint8Array(64);
-  preimage.set(nullifier, 0);
-  preimage.set(secret, 32);
+  // commitment = keccak256(COMMITMENT_DOMAIN || nullifier || secret),
+  // matching tee_bridge::compute_commitment
+  const domain = COMMITMENT_DOMAIN.length;
+  const preimage = new Uint8Array(domain + 64);
+  preimage.set(COMMITMENT_DOMAIN, 0);
+  preimage.set(nullifier, domain);
+  preimage.set(secret, domain + 32);
   const commitment = new Uint8Array(keccak_256.arrayBuffer(preimage));
 
   return { nullifier, secret, commitment };
@@ -213,25 +219,34 @@ export class TeeStagingClient {
 
       const data = accountInfo.data.slice(8);
 
-      // Parse commitments array
+      const view = new DataView(data.buffer, data.byteOffset, data.length);
+
+      // Layout after discriminator: id(8) + authority(32) + max_size(1) + threshold(1)
+      //   + commitments vec(4 + 32*n) + denominations vec(4 + 8*n) + commitment_count(1)
+      //   + total_amount(8) + created_at(8) + settled(1) + bump(1)
       const commitments: Uint8Array[] = [];
       const denominations: bigint[] = [];
-      const commitmentCount = data[32 + 320 + 80]; // After id(8) + authority(32) + commitments(320) + denoms(80)
-
-      for (let i = 0; i < commitmentCount; i++) {
-        commitments.push(new Uint8Array(data.slice(40 + i * 32, 40 + (i + 1) * 32)));
-        denominations.push(BigInt(new DataView(data.buffer, data.byteOffset + 360 + i * 8, 8).getBigUint64(0, true)));
+      const commitmentsOffset = 42;
+      const n = view.getUint32(commitmentsOffset, true);
+      const denominationsOffset = commitmentsOffset + 4 + 32 * n;
+      const countOffset = denominationsOffset + 4 + 8 * n;
+      const commitmentCount = data[countOffset];
+
+      for (let i = 0; i < n; i++) {
+        const start = commitmentsOffset + 4 + i * 32;
+        commitments.push(new Uint8Array(data.slice(start, start + 32)));
+        denominations.push(view.getBigUint64(denominationsOffset + 4 + i * 8, true));
       }
 
       return {
-        id: Number(new DataView(data.buffer, data.byteOffset, 8).getBigUint64(0, true)),
+        id: Number(view.getBigUint64(0, true)),
         authority: new PublicKey(data.slice(8, 40)),
         commitments,
         denominations,
         commitmentCount,
-        totalAmount: BigInt(new DataView(data.buffer, data.byteOffset + 441, 8).getBigUint64(0, true)),
-        createdAt: Number(new DataView(data.buffer, data.byteOffset + 449, 8).getBigInt64(0, true)),
-        settled: data[457] === 1,
+        totalAmount: view.getBigUint64(countOffset + 1, true),
+        createdAt: Number(view.getBigInt64(countOffset + 9, true)),
+        settled: data[countOffset + 17] === 1,
       };
     } catch (error) {
       console.error('[TEE Staging] Failed to get current batch:', error);
@@ -14,6 +14,8 @@ export interface Attestation {
   verifier: number[];
   signature: number[];
   verifiedAt: number;
+  vkVersion: number;
+  method: number;
 }
 
 export interface VerifyWithdrawRequest {
@@ -143,7 +145,8 @@ export async function verifyDepositProof(
  */
 export function attestationToBytes(attestation: Attestation): Uint8Array {
   // Format: proof_hash (32) + public_inputs_hash (32) + verifier (32) + signature (64) + verified_at (8)
-  const bytes = new Uint8Array(32 + 32 + 32 + 64 + 8);
+  //   + vk_version (4) + method (1)
+  const bytes = new Uint8Array(32 + 32 + 32 + 64 + 8 + 4 + 1);
   let offset = 0;
 
   // proof_hash
@@ -169,6 +172,14 @@ export function attestationToBytes(attestation: Attestation): Uint8Array {
   // verified_at (little-endian i64)
   const view = new DataView(bytes.buffer);
   view.setBigInt64(offset, BigInt(attestation.verifiedAt), true);
+  offset += 8;
+
+  // vk_version (little-endian u32)
+  view.setUint32(offset, attestation.vkVersion, true);
+  offset += 4;
+
+  // method
+  bytes[offset] = attestation.method;
 
   return bytes;
 }
@@ -191,6 +202,8 @@ function hexToBytes(hex: string): number[] {
 
 export interface RangeProofAttestation {
   commitmentHash: number[];
+  /** Hex SHA-256 of the range proof; signed, must match the deposit's range_proof_hash */
+  rangeProofHash: string;
   amountRange: [string, string];
   signature: number[];
   verifier: number[];
@@ -271,6 +284,8 @@ export function formatAttestationForChain(attestation: Attestation): {
   verifier: Uint8Array;
   signature: Uint8Array;
   verifiedAt: bigint;
+  vkVersion: number;
+  method: number;
 } {
   return {
     proofHash: new Uint8Array(
@@ -286,5 +301,7 @@ export function formatAttestationForChain(attestation: Attestation): {
     verifier: new Uint8Array(attestation.verifier),
     signature: new Uint8Array(attestation.signature),
     verifiedAt: BigInt(attestation.verifiedAt),
+    vkVersion: attestation.vkVersion,
+    method: attestation.method,
   };
 }
@@ -4,12 +4,19 @@
 //! Uses Poseidon hash for ZK circuit compatibility.
 //!
 //! Tree Properties:
-//! - Depth: 10 levels (supports 1024 notes)
+//! - Depth: MERKLE_DEPTH levels on-chain (256 notes per pool)
 //! - Hash: Poseidon (BN254 compatible with circom/snarkjs)
 //! - Storage: Only non-zero leaves are stored
+//!
+//! The zero-hash and insertion helpers are generic over the depth `D`, so
+//! tests can run small trees to capacity. The program always instantiates
+//! them with `MERKLE_DEPTH`, matching `PrivacyPool::filled_subtrees`.
+
+use std::ops::Index;
 
 use anchor_lang::prelude::*;
 use super::poseidon::poseidon_hash_2;
+use crate::error::StealthError;
 
 /// Merkle tree depth (2^8 = 256 notes per pool)
 /// Reduced from 10 to fit Solana compute budget (~560k CUs for 8 levels)
@@ -69,14 +76,132 @@ pub fn get_precomputed_zero_hashes() -> &'static [[u8; 32]; MERKLE_DEPTH + 1] {
     &ZERO_HASHES
 }
 
-/// Compute zero hashes dynamically using Poseidon
-/// WARNING: This is VERY expensive (~1.4M CUs)! Use get_precomputed_zero_hashes() instead.
-/// Only use this for verification/testing purposes.
+/// Zero hashes for a depth-`D` tree: one per level plus the empty root
+///
+/// Indexing with `0..=D` mirrors the old `[[u8; 32]; D + 1]` array, which
+/// stable Rust can't spell for a generic `D`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ZeroHashes<const D: usize> {
+    /// `levels[i]` is the root of an empty subtree of height `i`
+    pub levels: [[u8; 32]; D],
+    /// Root of the empty depth-`D` tree
+    pub root: [u8; 32],
+}
+
+impl<const D: usize> ZeroHashes<D> {
+    /// Number of entries (`D + 1`)
+    pub const fn len(&self) -> usize {
+        D + 1
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        false
+    }
+}
+
+impl<const D: usize> Index<usize> for ZeroHashes<D> {
+    type Output = [u8; 32];
+
+    fn index(&self, level: usize) -> &[u8; 32] {
+        if level == D {
+            &self.root
+        } else {
+            &self.levels[level]
+        }
+    }
+}
+
+/// Zero hashes for a depth-`D` tree
+///
+/// Levels up to MERKLE_DEPTH come from ZERO_HASHES, so the program's own
+/// depth costs no hashing. Deeper levels extend the table with
+/// `merkle_hash_2` (~150k CUs each), which is fine off-chain but not in an
+/// instruction.
 #[inline(never)]
-pub fn compute_zero_hashes_poseidon() -> [[u8; 32]; MERKLE_DEPTH + 1] {
-    // Return pre-computed values to save compute units
-    // The values in ZERO_HASHES were generated using the same Poseidon implementation
-    ZERO_HASHES
+pub fn compute_zero_hashes_poseidon<const D: usize>() -> ZeroHashes<D> {
+    let mut levels = [[0u8; 32]; D];
+    let mut current = ZERO_VALUE;
+
+    for (i, level) in levels.iter_mut().enumerate() {
+        *level = current;
+        current = match ZERO_HASHES.get(i + 1) {
+            Some(precomputed) => *precomputed,
+            None => merkle_hash_2(&current, &current),
+        };
+    }
+
+    ZeroHashes { levels, root: current }
+}
+
+/// Insert `leaf` at `leaf_index` into a depth-`D` incremental tree
+///
+/// `filled_subtrees[i]` holds the latest left node at level `i` (the
+/// Tornado-style frontier). Returns the new root. Fails with TreeFull once
+/// `leaf_index` reaches `2^D`, before touching the frontier, since the index
+/// bits would otherwise wrap and overwrite existing subtrees.
+#[inline(never)]
+pub fn insert_leaf<const D: usize>(
+    filled_subtrees: &mut [[u8; 32]; D],
+    leaf_index: u64,
+    leaf: [u8; 32],
+) -> Result<[u8; 32]> {
+    // leaf_index < 2^D, without overflowing the shift for D >= 64
+    require!(
+        leaf_index.checked_shr(D as u32).unwrap_or(0) == 0,
+        StealthError::TreeFull
+    );
+
+    let zeros = compute_zero_hashes_poseidon::<D>();
+    let mut current_index = leaf_index;
+    let mut current_hash = leaf;
+
+    for (i, filled) in filled_subtrees.iter_mut().enumerate() {
+        if current_index.is_multiple_of(2) {
+            *filled = current_hash;
+            current_hash = merkle_hash_2(&current_hash, &zeros[i]);
+        } else {
+            current_hash = merkle_hash_2(filled, &current_hash);
+        }
+
+        current_index /= 2;
+    }
+
+    Ok(current_hash)
+}
+
+/// Recompute a depth-`D` tree's root from its frontier alone
+///
+/// Walks the path of the next empty slot, `next_index`: where that path is
+/// a right child its left sibling is complete, so `filled_subtrees[i]`
+/// holds it; where it is a left child its right sibling is still empty.
+/// A full tree has no empty slot and its last right-hand leaves aren't in
+/// the frontier, so it can't be recomputed (TreeFull). Empty subtrees use
+/// the zero hashes, as `insert_leaf` does, rather than being rehashed.
+#[inline(never)]
+pub fn frontier_root<const D: usize>(filled_subtrees: &[[u8; 32]; D], next_index: u64) -> Result<[u8; 32]> {
+    require!(
+        next_index.checked_shr(D as u32).unwrap_or(0) == 0,
+        StealthError::TreeFull
+    );
+
+    let zeros = compute_zero_hashes_poseidon::<D>();
+    let mut current_index = next_index;
+    // `None` while the path node is still an empty subtree (zeros[i])
+    let mut current_hash: Option<[u8; 32]> = None;
+
+    for (i, filled) in filled_subtrees.iter().enumerate() {
+        if current_index.is_multiple_of(2) {
+            if let Some(hash) = current_hash {
+                current_hash = Some(merkle_hash_2(&hash, &zeros[i]));
+            }
+        } else {
+            current_hash = Some(merkle_hash_2(filled, &current_hash.unwrap_or(zeros[i])));
+        }
+
+        current_index /= 2;
+    }
+
+    Ok(current_hash.unwrap_or(zeros[D]))
 }
 
 /// Get zero hash for a specific level (uses pre-computed values)
@@ -136,6 +261,81 @@ impl Default for MerkleProof {
     }
 }
 
+/// Build an inclusion proof for `leaves[leaf_index]` in a tree of `leaves`
+///
+/// Uses the same Poseidon hashing and zero-padding as the on-chain incremental
+/// insertion (`insert_leaf`), so the returned root matches the pool's
+/// root after inserting `leaves` in order.
+///
+/// Returns `(siblings, root)` where `siblings[i]` is the sibling at level `i`.
+/// Path direction at level `i` is bit `i` of `leaf_index` (0 = left).
+///
+/// # Panics
+/// If `leaf_index >= leaves.len()` or `leaves.len() > MAX_LEAVES`.
+pub fn compute_merkle_proof(
+    leaves: &[[u8; 32]],
+    leaf_index: usize,
+) -> ([[u8; 32]; MERKLE_DEPTH], [u8; 32]) {
+    assert!(leaf_index < leaves.len(), "leaf index out of range");
+    assert!(leaves.len() as u64 <= MAX_LEAVES, "too many leaves for tree depth");
+
+    let zeros = compute_zero_hashes_poseidon::<MERKLE_DEPTH>();
+    let mut siblings = [[0u8; 32]; MERKLE_DEPTH];
+    let mut level: Vec<[u8; 32]> = leaves.to_vec();
+    let mut index = leaf_index;
+
+    for (i, sibling) in siblings.iter_mut().enumerate() {
+        *sibling = level.get(index ^ 1).copied().unwrap_or(zeros[i]);
+
+        level = level
+            .chunks(2)
+            .map(|pair| {
+                let right = pair.get(1).unwrap_or(&zeros[i]);
+                merkle_hash_2(&pair[0], right)
+            })
+            .collect();
+        index /= 2;
+    }
+
+    (siblings, level[0])
+}
+
+/// Recompute the root from a leaf, its index and its sibling path
+///
+/// Path direction at level `i` is bit `i` of `leaf_index` (0 = left); only
+/// the low MERKLE_DEPTH bits are used, so callers must bound the index.
+pub fn compute_root_from_path(
+    leaf: &[u8; 32],
+    leaf_index: u64,
+    siblings: &[[u8; 32]; MERKLE_DEPTH],
+) -> [u8; 32] {
+    let mut current = *leaf;
+
+    for (i, sibling) in siblings.iter().enumerate() {
+        current = if (leaf_index >> i) & 1 == 0 {
+            merkle_hash_2(&current, sibling)
+        } else {
+            merkle_hash_2(sibling, &current)
+        };
+    }
+
+    current
+}
+
+/// Verify an inclusion proof produced by `compute_merkle_proof`
+pub fn verify_merkle_proof(
+    leaf: &[u8; 32],
+    leaf_index: usize,
+    siblings: &[[u8; 32]; MERKLE_DEPTH],
+    root: &[u8; 32],
+) -> bool {
+    if leaf_index as u64 >= MAX_LEAVES {
+        return false;
+    }
+
+    compute_root_from_path(leaf, leaf_index as u64, siblings) == *root
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -167,7 +367,7 @@ mod tests {
         // Verify precomputed zero hashes are consistent
         // Note: ZERO_HASHES are from circomlibjs (frontend) for ZK circuit compatibility
         // The on-chain code uses these precomputed values directly
-        let zeros = compute_zero_hashes_poseidon();
+        let zeros = compute_zero_hashes_poseidon::<MERKLE_DEPTH>();
 
         // Level 0 should be zero value
         assert_eq!(zeros[0], ZERO_VALUE, "Level 0 should be zero value");
@@ -183,4 +383,121 @@ mod tests {
             assert_ne!(zeros[i], zeros[i + 1], "Adjacent levels should differ");
         }
     }
+
+    /// Root after inserting `leaves` one by one, as the program does
+    fn incremental_root<const D: usize>(leaves: &[[u8; 32]]) -> [u8; 32] {
+        let zeros = compute_zero_hashes_poseidon::<D>();
+        let mut filled = zeros.levels;
+        let mut root = zeros.root;
+
+        for (leaf_index, leaf) in leaves.iter().enumerate() {
+            root = insert_leaf::<D>(&mut filled, leaf_index as u64, *leaf).unwrap();
+        }
+
+        root
+    }
+
+    /// Root of a depth-`D` tree built level by level, padding with zero hashes
+    fn naive_root<const D: usize>(leaves: &[[u8; 32]]) -> [u8; 32] {
+        let zeros = compute_zero_hashes_poseidon::<D>();
+        let mut level = leaves.to_vec();
+
+        for i in 0..D {
+            level = level
+                .chunks(2)
+                .map(|pair| merkle_hash_2(&pair[0], pair.get(1).unwrap_or(&zeros[i])))
+                .collect();
+        }
+
+        level[0]
+    }
+
+    fn test_leaves(n: u8) -> Vec<[u8; 32]> {
+        (1..=n).map(|i| {
+            let mut leaf = [0u8; 32];
+            leaf[31] = i;
+            leaf
+        }).collect()
+    }
+
+    #[test]
+    fn test_merkle_proof_root_matches_incremental_insert() {
+        for n in [1u8, 2, 3, 5] {
+            let leaves = test_leaves(n);
+            let (_, root) = compute_merkle_proof(&leaves, 0);
+            assert_eq!(root, incremental_root::<MERKLE_DEPTH>(&leaves), "root mismatch for {} leaves", n);
+        }
+    }
+
+    #[test]
+    fn test_merkle_proof_verifies_every_leaf() {
+        let leaves = test_leaves(5);
+        for (index, leaf) in leaves.iter().enumerate() {
+            let (siblings, root) = compute_merkle_proof(&leaves, index);
+            assert!(verify_merkle_proof(leaf, index, &siblings, &root));
+        }
+    }
+
+    #[test]
+    fn test_merkle_proof_rejects_tampering() {
+        let leaves = test_leaves(3);
+        let (mut siblings, root) = compute_merkle_proof(&leaves, 1);
+
+        // Wrong leaf
+        assert!(!verify_merkle_proof(&leaves[0], 1, &siblings, &root));
+        // Wrong index
+        assert!(!verify_merkle_proof(&leaves[1], 0, &siblings, &root));
+        // Out-of-range index
+        assert!(!verify_merkle_proof(&leaves[1], MAX_LEAVES as usize, &siblings, &root));
+        // Tampered sibling
+        siblings[0][0] ^= 1;
+        assert!(!verify_merkle_proof(&leaves[1], 1, &siblings, &root));
+    }
+
+    #[test]
+    fn test_generic_zero_hashes_match_precomputed() {
+        let small = compute_zero_hashes_poseidon::<4>();
+        assert_eq!(small.len(), 5);
+        for i in 0..=4 {
+            assert_eq!(small[i], ZERO_HASHES[i]);
+        }
+
+        let default = compute_zero_hashes_poseidon::<MERKLE_DEPTH>();
+        assert_eq!(default.root, EMPTY_TREE_ROOT);
+
+        // Deeper than the precomputed table: extra levels are hashed
+        let deep = compute_zero_hashes_poseidon::<{ MERKLE_DEPTH + 2 }>();
+        assert_eq!(deep[MERKLE_DEPTH], EMPTY_TREE_ROOT);
+        assert_eq!(deep[MERKLE_DEPTH + 1], merkle_hash_2(&EMPTY_TREE_ROOT, &EMPTY_TREE_ROOT));
+        assert_eq!(deep.root, merkle_hash_2(&deep[MERKLE_DEPTH + 1], &deep[MERKLE_DEPTH + 1]));
+    }
+
+    #[test]
+    fn test_depth_4_tree_fills_to_capacity() {
+        const D: usize = 4;
+        let leaves = test_leaves(1 << D);
+        let zeros = compute_zero_hashes_poseidon::<D>();
+        let mut filled = zeros.levels;
+
+        for (index, leaf) in leaves.iter().enumerate() {
+            let root = insert_leaf::<D>(&mut filled, index as u64, *leaf).unwrap();
+            assert_eq!(root, naive_root::<D>(&leaves[..=index]), "root mismatch after leaf {}", index);
+        }
+    }
+
+    #[test]
+    fn test_depth_4_tree_rejects_overflow() {
+        const D: usize = 4;
+        let leaves = test_leaves(1 << D);
+        let mut filled = compute_zero_hashes_poseidon::<D>().levels;
+        for (index, leaf) in leaves.iter().enumerate() {
+            insert_leaf::<D>(&mut filled, index as u64, *leaf).unwrap();
+        }
+
+        // Index 16 would wrap to the leftmost path; the frontier must be untouched
+        let before = filled;
+        assert!(insert_leaf::<D>(&mut filled, 1 << D, [0xAA; 32]).is_err());
+        assert!(insert_leaf::<D>(&mut filled, u64::MAX, [0xAA; 32]).is_err());
+        assert_eq!(filled, before);
+    }
 }
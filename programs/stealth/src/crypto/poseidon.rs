@@ -5,6 +5,14 @@
 //!
 //! Reference: https://eprint.iacr.org/2019/458.pdf
 //! Parameters: https://github.com/TaceoLabs/poseidon-rust (circom compatible)
+//!
+//! Byte-level inputs and outputs are little-endian field elements; circomlibjs
+//! prints the same values big-endian (see the reference vectors in the tests).
+//!
+//! The permutation is generic over a `PoseidonParams<T>` parameter set;
+//! `Poseidon` is the t=3 BN254 instance nearly every hash here uses, and
+//! `BN254_T4_PARAMS` backs the 3-input hash. Another width only needs its
+//! own constants and MDS matrix, then `PoseidonState::sponge`.
 
 /// Poseidon configuration for t=3 (2 inputs + 1 capacity)
 pub const POSEIDON_T: usize = 3;
@@ -22,6 +30,26 @@ pub const BN254_MODULUS: [u64; 4] = [
     0x30644e72e131a029,
 ];
 
+/// Barrett constant mu = floor(2^512 / p) for BN254 Fr (259 bits, 5 limbs)
+pub const BARRETT_MU: [u64; 5] = [
+    0x20703a6be1de9259,
+    0x144852009e880ae6,
+    0xb074a58680730147,
+    0x4a47462623a04a7a,
+    0x0000000000000005,
+];
+
+/// Montgomery constant INV = -p^{-1} mod 2^64
+pub const MONTGOMERY_INV: u64 = 0xc2e1f593efffffff;
+
+/// R^2 mod p where R = 2^256 (used to convert into Montgomery form)
+pub const MONTGOMERY_R2: [u64; 4] = [
+    0x1bb8e645ae216da7,
+    0x53fe3ab1e35c59e3,
+    0x8c49833d53bb8085,
+    0x0216d0b17f4e44a5,
+];
+
 /// Field element representation (256-bit)
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 pub struct Fr {
@@ -108,186 +136,179 @@ impl Fr {
         fr
     }
 
-    /// Multiplication mod p using schoolbook multiplication with proper reduction
+    /// Multiplication mod p: schoolbook 256x256 -> 512-bit product, then Barrett reduction
     pub fn mul(&self, other: &Fr) -> Fr {
-        // Schoolbook multiplication into 512-bit result
-        // Use wrapping arithmetic to avoid panic
-        let mut result = [0u128; 8];
-
-        for i in 0..4 {
-            for j in 0..4 {
-                let prod = (self.limbs[i] as u128) * (other.limbs[j] as u128);
-                result[i + j] = result[i + j].wrapping_add(prod);
-            }
-        }
-
-        // Carry propagation (with wrapping to handle overflow)
-        for i in 0..7 {
-            let carry = result[i] >> 64;
-            result[i] &= 0xFFFFFFFFFFFFFFFF;
-            result[i + 1] = result[i + 1].wrapping_add(carry);
-        }
-        result[7] &= 0xFFFFFFFFFFFFFFFF;
-
-        // Fast modular reduction using precomputed constants
-        // For 512-bit number n, we compute n mod p
-        self.fast_reduce(&result)
+        let wide = mul_wide(&self.limbs, &other.limbs);
+        barrett_reduce(&wide)
     }
 
-    /// Fast reduction for 512-bit result mod p
-    /// Uses the fact that p is close to 2^254, so we can use
-    /// the relation: 2^256 ≡ 2^256 - p (mod p)
-    fn fast_reduce(&self, wide: &[u128; 8]) -> Fr {
-        // Extract 512-bit number
-        let mut n = [0u64; 8];
-        for i in 0..8 {
-            n[i] = wide[i] as u64;
-        }
-
-        // c = 2^256 - p (the reduction constant)
-        // c ≈ 0x0F... (small relative to p)
-        // We use: n = n_lo + n_hi * 2^256 ≡ n_lo + n_hi * c (mod p)
+    /// Multiply by a constant already in Montgomery form: a * (bR) * R^-1 = a*b
+    ///
+    /// The result is in normal form, so callers holding precomputed Montgomery
+    /// constants (like `MDS_MATRIX_MONT`) skip both conversions.
+    #[inline(always)]
+    pub fn mul_by_mont(&self, constant: &MontgomeryFr) -> Fr {
+        Fr { limbs: cios_mul(&self.limbs, &constant.limbs) }
+    }
 
-        // Since p ≈ 2^254, we need multiple reduction rounds
-        // Round 1: Reduce n[4..8] using the fact that 2^256 ≡ c (mod p)
+    /// Compute x^5 (for S-box)
+    pub fn pow5(&self) -> Fr {
+        let x2 = self.mul(self);
+        let x4 = x2.mul(&x2);
+        x4.mul(self)
+    }
+}
 
-        // The reduction constant c = 2^256 mod p
-        // For BN254 Fr: c = 2^256 - p
-        // c = 0x0e0a77c19a07df2f666ea36f7879462e36fc76959f60cd29ac96341c4ffffffb
-        // Simplified: we compute n_hi * c and add to n_lo
+/// Field element in Montgomery form (stores a*R mod p, R = 2^256)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct MontgomeryFr {
+    pub limbs: [u64; 4],
+}
 
-        // For hackathon speed, we do a simpler approach:
-        // Since the result of two 254-bit numbers is at most 508 bits,
-        // and p is 254 bits, we do iterative reduction
+impl MontgomeryFr {
+    pub const ZERO: MontgomeryFr = MontgomeryFr { limbs: [0, 0, 0, 0] };
 
-        // Reduce high limbs: for each limb n[i] where i >= 4,
-        // we have n[i] * 2^(64*i) = n[i] * 2^(64*i) mod p
-        // We can compute 2^(64*i) mod p and multiply
+    /// Convert a normal-form element into Montgomery form: a * R^2 * R^-1 = aR
+    pub fn to_montgomery(a: &Fr) -> Self {
+        Self { limbs: cios_mul(&a.limbs, &MONTGOMERY_R2) }
+    }
 
-        // Precomputed: 2^256 mod p, 2^320 mod p, 2^384 mod p, 2^448 mod p
-        // For simplicity, we'll use iterative reduction
+    /// Convert back to normal form: aR * 1 * R^-1 = a
+    pub fn from_montgomery(&self) -> Fr {
+        Fr { limbs: cios_mul(&self.limbs, &Fr::ONE.limbs) }
+    }
 
-        // Step 1: Fold high limbs into low using modular arithmetic
-        // 2^256 mod p ≈ small number, so n[4] * 2^256 is much smaller than n[4] * 2^256
+    /// Montgomery multiplication: (aR)(bR)R^-1 = abR
+    pub fn mul_mont(&self, other: &MontgomeryFr) -> MontgomeryFr {
+        Self { limbs: cios_mul(&self.limbs, &other.limbs) }
+    }
 
-        // Actually, let's just do direct reduction rounds
-        // This is O(1) iterations since we have bounded input size
+    /// Addition is form-preserving: aR + bR = (a+b)R
+    pub fn add(&self, other: &MontgomeryFr) -> MontgomeryFr {
+        let sum = Fr { limbs: self.limbs }.add(&Fr { limbs: other.limbs });
+        Self { limbs: sum.limbs }
+    }
+}
 
-        let mut acc = [0u128; 5]; // Accumulator with one extra limb for carries
+/// CIOS Montgomery multiplication: returns a * b * R^-1 mod p
+///
+/// Inputs must be < p. Since p < 2^254 the intermediate stays below 2p,
+/// so one conditional subtraction finishes the reduction.
+#[inline(never)]
+fn cios_mul(a: &[u64; 4], b: &[u64; 4]) -> [u64; 4] {
+    let mut t = [0u64; 6];
 
-        // Start with low 256 bits
-        for i in 0..4 {
-            acc[i] = n[i] as u128;
+    for &bi in b.iter() {
+        // t += a * b[i]
+        let mut carry = 0u128;
+        for j in 0..4 {
+            let s = (t[j] as u128) + (a[j] as u128) * (bi as u128) + carry;
+            t[j] = s as u64;
+            carry = s >> 64;
         }
+        let s = (t[4] as u128) + carry;
+        t[4] = s as u64;
+        t[5] = (s >> 64) as u64;
+
+        // t = (t + m*p) / 2^64
+        let m = t[0].wrapping_mul(MONTGOMERY_INV);
+        let s = (t[0] as u128) + (m as u128) * (BN254_MODULUS[0] as u128);
+        let mut carry = s >> 64;
+        for j in 1..4 {
+            let s = (t[j] as u128) + (m as u128) * (BN254_MODULUS[j] as u128) + carry;
+            t[j - 1] = s as u64;
+            carry = s >> 64;
+        }
+        let s = (t[4] as u128) + carry;
+        t[3] = s as u64;
+        t[4] = t[5] + ((s >> 64) as u64);
+    }
 
-        // Reduce high 256 bits by multiplying by 2^256 mod p
-        // 2^256 mod p = p + (2^256 - p) = small constant
-        // Let k = 2^256 mod p
-        // k = 0x30644e72e131a029b85045b68181585d2833e84879b97091 43e1f593f0000001 - p... wait that's wrong
-
-        // Actually: 2^256 = q*p + r where r = 2^256 mod p
-        // For BN254 scalar field, r is a small constant
-
-        // Let me just use multiple rounds of the simple reduction:
-        // If we have a 512-bit number, after one round we get ~320 bits,
-        // after another we get ~256 bits, then we're done
-
-        // Round 1: Reduce n[4..8]
-        // n[4] * 2^256 + n[5] * 2^320 + n[6] * 2^384 + n[7] * 2^448
-
-        // Since 2^256 ≡ c (mod p) where c = 2^256 - p is small (~64 bits)
-        // we have n[i] * 2^(64*(i-4)) * c needs to be added
-
-        // For BN254 Fr: c = 2^256 - p
-        // c = FFFFFFFF FFFFFFFF FFFFFFFF FFFFFFFE BAAEDCE6 AF48A03B BFD25E8C D0364141 - wait that's secp256k1
-
-        // BN254 Fr: p = 0x30644e72e131a029 b85045b68181585d 2833e84879b97091 43e1f593f0000001
-        // 2^256 - p = 0xcf9bb18d1ece5fd6 47afba497e7ea7a2 d7cc17b7864368f6 bc1e0a6c0fffffff
-
-        // This is a ~254 bit number (almost same size as p), so reduction is tricky
-
-        // SIMPLER APPROACH: Use barrett reduction with precomputed mu
-        // mu = floor(2^512 / p)
-        // q = floor((n * mu) >> 512)
-        // r = n - q * p (may need one more subtraction)
-
-        // For hackathon, let's use a very simple approach:
-        // Just do two rounds of folding the high bits
-
-        // Fold: compute (n[0..4]) + (n[4..8] * k) where k = 2^256 mod p
-        // Since k is almost as large as p, we need to handle overflow
-
-        // Actually, simplest correct approach: subtract p repeatedly
-        // But limit iterations to avoid infinite loop
-
-        for _ in 0..10 {
-            // Check if n[4..8] are all zero
-            if n[4] == 0 && n[5] == 0 && n[6] == 0 && n[7] == 0 {
-                break;
-            }
-
-            // Subtract p shifted by appropriate amount
-            // Find the highest non-zero limb
-            let mut shift = 7;
-            while shift >= 4 && n[shift] == 0 {
-                shift -= 1;
-            }
-            if shift < 4 {
-                break;
-            }
+    let mut result = Fr { limbs: [t[0], t[1], t[2], t[3]] };
+    result.reduce();
+    result.limbs
+}
 
-            // Subtract p << ((shift - 3) * 64) if possible
-            let s = shift - 3;
-            let mut borrow = 0i128;
-            let mut temp = n;
-            let mut can_sub = true;
+/// Full 256x256 -> 512-bit schoolbook product (little-endian limbs)
+#[inline(always)]
+fn mul_wide(a: &[u64; 4], b: &[u64; 4]) -> [u64; 8] {
+    let mut out = [0u64; 8];
+    for i in 0..4 {
+        let mut carry = 0u128;
+        for j in 0..4 {
+            let t = (a[i] as u128) * (b[j] as u128) + (out[i + j] as u128) + carry;
+            out[i + j] = t as u64;
+            carry = t >> 64;
+        }
+        out[i + 4] = carry as u64;
+    }
+    out
+}
 
-            for i in 0..4 {
-                let idx = s + i;
-                if idx > 7 { break; }
-                let diff = (temp[idx] as i128) - (BN254_MODULUS[i] as i128) - borrow;
-                if diff < 0 {
-                    temp[idx] = ((diff as i128) + (1i128 << 64)) as u64;
-                    borrow = 1;
-                } else {
-                    temp[idx] = diff as u64;
-                    borrow = 0;
-                }
-            }
+/// Barrett reduction of a 512-bit value mod p (HAC Algorithm 14.42, b = 2^64, k = 4)
+///
+/// q = floor(floor(x / b^3) * mu / b^5) underestimates floor(x / p) by at most 2,
+/// so after r = x - q*p (computed mod b^5) at most two subtractions of p remain.
+#[inline(never)]
+fn barrett_reduce(x: &[u64; 8]) -> Fr {
+    // q1 = floor(x / b^(k-1)): limbs 3..8
+    let q1 = [x[3], x[4], x[5], x[6], x[7]];
 
-            // Handle borrow from higher limbs
-            for i in (s + 4)..8 {
-                if borrow == 0 { break; }
-                if temp[i] > 0 {
-                    temp[i] -= 1;
-                    borrow = 0;
-                } else {
-                    temp[i] = u64::MAX;
-                }
-            }
+    // q2 = q1 * mu (only limbs >= 5 are needed for q3)
+    let mut q2 = [0u64; 10];
+    for i in 0..5 {
+        let mut carry = 0u128;
+        for j in 0..5 {
+            let t = (q1[i] as u128) * (BARRETT_MU[j] as u128) + (q2[i + j] as u128) + carry;
+            q2[i + j] = t as u64;
+            carry = t >> 64;
+        }
+        q2[i + 5] = carry as u64;
+    }
 
-            if borrow == 0 {
-                n = temp;
-            } else {
-                can_sub = false;
-            }
+    // q3 = floor(q2 / b^(k+1))
+    let q3 = [q2[5], q2[6], q2[7], q2[8], q2[9]];
 
-            if !can_sub {
-                break;
-            }
+    // r2 = (q3 * p) mod b^(k+1)
+    let mut r2 = [0u64; 5];
+    for i in 0..5 {
+        let mut carry = 0u128;
+        for j in 0..(5 - i).min(4) {
+            let t = (q3[i] as u128) * (BN254_MODULUS[j] as u128) + (r2[i + j] as u128) + carry;
+            r2[i + j] = t as u64;
+            carry = t >> 64;
+        }
+        if i + 4 < 5 {
+            r2[i + 4] = r2[i + 4].wrapping_add(carry as u64);
         }
+    }
 
-        let mut result = Fr { limbs: [n[0], n[1], n[2], n[3]] };
-        result.reduce();
-        result
+    // r = (x mod b^(k+1)) - r2, wrapping mod b^(k+1)
+    let mut r = [0u64; 5];
+    let mut borrow = 0u64;
+    for i in 0..5 {
+        let (d1, b1) = x[i].overflowing_sub(r2[i]);
+        let (d2, b2) = d1.overflowing_sub(borrow);
+        r[i] = d2;
+        borrow = (b1 | b2) as u64;
     }
 
-    /// Compute x^5 (for S-box)
-    pub fn pow5(&self) -> Fr {
-        let x2 = self.mul(self);
-        let x4 = x2.mul(&x2);
-        x4.mul(self)
+    // At most two conditional subtractions
+    for _ in 0..2 {
+        if r[4] == 0 && !(Fr { limbs: [r[0], r[1], r[2], r[3]] }).gte_modulus() {
+            break;
+        }
+        let mut borrow = 0u64;
+        for i in 0..5 {
+            let m = if i < 4 { BN254_MODULUS[i] } else { 0 };
+            let (d1, b1) = r[i].overflowing_sub(m);
+            let (d2, b2) = d1.overflowing_sub(borrow);
+            r[i] = d2;
+            borrow = (b1 | b2) as u64;
+        }
     }
+
+    Fr { limbs: [r[0], r[1], r[2], r[3]] }
 }
 
 /// Official Poseidon round constants for BN254, t=3
@@ -576,29 +597,454 @@ pub const MDS_MATRIX: [[Fr; 3]; 3] = [
     ],
 ];
 
-/// Poseidon sponge state
+/// MDS matrix pre-stored in Montgomery form (MDS_MATRIX[i][j] * R mod p)
+/// Lets `mds_mix` multiply normal-form state by a constant with a single CIOS pass
+pub const MDS_MATRIX_MONT: [[MontgomeryFr; 3]; 3] = [
+    [
+        MontgomeryFr { limbs: [0xf2e8909a56fcf3d7, 0x8019ce3145ed8c1d, 0xdda896a228616418, 0x0e5ed723ffc885e1] },
+        MontgomeryFr { limbs: [0x3158f311d66c0469, 0x9511d96f69f040a0, 0xbc6996e5b22127bf, 0x07e69e17a7c9122a] },
+        MontgomeryFr { limbs: [0x28f45876169969b0, 0x3d6ded69e30a7649, 0x79aed6124c9b23dd, 0x03cf3048ffadf517] },
+    ],
+    [
+        MontgomeryFr { limbs: [0x670d8bd946474dd5, 0x56daed800bf07bae, 0x5c98d51ecca20e6d, 0x1a3491eda18b0028] },
+        MontgomeryFr { limbs: [0xf0193e572ba79c47, 0x5fb2e46a6ee2dac5, 0x6892f0d5b6ffb984, 0x0df1dabd49661413] },
+        MontgomeryFr { limbs: [0x3293bffccaab272d, 0x85cbae38b11c4e1f, 0x67208956c8757b3c, 0x17ca537ab6c9d981] },
+    ],
+    [
+        MontgomeryFr { limbs: [0xcc226561d2802757, 0xfcfbd22f5bb9f4ed, 0xc8ef58acce2b8678, 0x05984bb41bae9c88] },
+        MontgomeryFr { limbs: [0x17561a5176bfeefd, 0x1cd5d7be100061af, 0x714cefb2dce7646c, 0x0043bf61f2173fe9] },
+        MontgomeryFr { limbs: [0x4c72e3c51c729128, 0xd35b9fd9170d616c, 0x4d095dc74ab700a6, 0x1282bdf76dc5d39b] },
+    ],
+];
+
+/// Poseidon round constants for BN254, t=4 (circom compatible)
+/// 64 rounds * 4 constants per round = 256 constants total
+pub const ROUND_CONSTANTS_T4: [[u64; 4]; 256] = [
+    // Round 0
+    [0x8b0878e269ed23e5, 0x02bb86744edc2623, 0x48da1d39bd5e4a43, 0x19b849f69450b068],
+    [0xad47f80c8dcf34d6, 0x20eb2cc7450acc1d, 0x7239347b758f0a13, 0x265ddfe127dd51bd],
+    [0x3dfc36bab497d8aa, 0x4108ac845015c2aa, 0xe0f66a545e1e5162, 0x199750ec472f1809],
+    [0xd032f787c7f1cdf8, 0x4d743ea25067f0ff, 0x110f06a5f74302b1, 0x157ff3fe65ac7208],
+    // Round 1
+    [0xfe18f4896ac94902, 0x0b15c590692f8bee, 0x5fd35ac45fca33f1, 0x2e49c43c4569dd9c],
+    [0x2731345ffa2d1f1e, 0xcb2f0b6973c24fa8, 0x0d4aef2b6d6506c3, 0x0e35fb8998189052],
+    [0xc6fe723002e0b996, 0xa9d9e7806d667ffe, 0x05f109ae5e944f1b, 0x251ad47cb15c4f11],
+    [0x563fa39d9c22df4e, 0xf8beb56fdd05e5f3, 0x9873e97160234641, 0x13da07dc64d42836],
+    // Round 2
+    [0x46e7b89055fd4738, 0xa553939689d350cd, 0x3dc00c7dccef7483, 0x0c009b84e650e6d2],
+    [0x203dec74befdca06, 0x04eb650c6d535eb0, 0x01992e3956f42d8b, 0x011f16b1c63a854f],
+    [0x85df07093f367549, 0x2f3f78d0467ad454, 0x209d9a561daa7961, 0x0ed69e5e383a688f],
+    [0x463672264c9f789b, 0x3aec507f5eb3d33f, 0x21acad41472b6bbe, 0x04dba94a7b0ce9e2],
+    // Round 3
+    [0xce732ff1d4fa28e8, 0x6036757d4bb50bf7, 0x6eb094271c9d237b, 0x0a3f2637d840f3a1],
+    [0xe54a485d1182323f, 0x39b1f075569564b6, 0x8f8a1c502fdb38fa, 0x259a666f129eea19],
+    [0x7a32fdf7ede0d6a1, 0x7745d4271038e515, 0xd8e7d06a4ee3a47f, 0x28bf7459c9b2f4c6],
+    [0xec91bd6941432447, 0xc37c85bbcce6a2ae, 0x26ea200f489be8d4, 0x0a1ca941f0570375],
+    // Round 4
+    [0xb43a26fd926361cf, 0x5535ed1539f051dc, 0x53d7fd4fc5451285, 0x0c6f8f958be0e930],
+    [0x84dd57e69caaf811, 0xa9e8a00708e296e0, 0xd426e8128ac9d90a, 0x123106a93cd17578],
+    [0x7b074867cd2dee75, 0x5e8fa83ff1e8f187, 0x7dd3ab52f8e84008, 0x26e1ba52ad9285d9],
+    [0x4471537e6a4ae2c5, 0xbe4d8b7bf9e09586, 0x18a64c5c47b9c97c, 0x1cb55cad7bd133de],
+    // Round 5
+    [0x7143f08e6e9055d0, 0x2a53043d5060a41c, 0x0e2c7ce04bde7f6d, 0x1dcd73e46acd8f8e],
+    [0xb12b9bb4512e5574, 0x0cda294a0eb4e9b9, 0xf5852f05474a4def, 0x011003e32f6d9c66],
+    [0xd7c508dd2287ae8c, 0xbadfe5903f58bafe, 0x9ad5f20d03a57dfe, 0x2b1e809ac1d10ab2],
+    [0xeaa69ae87bcec0a5, 0xef995d05ab2fc5fa, 0x9fb4dac35ee17ed0, 0x2539de1785b73599],
+    // Round 6
+    [0x43982cb11d77951d, 0xf4e1c3d41c86d46e, 0x26497f222b3e0a0e, 0x0c246c5a2ef8ee01],
+    [0x3f0305f5d03b527b, 0xbb09e6a6ad1a1c2f, 0x5408148f7c0632ed, 0x192089c4974f68e9],
+    [0x6d8fdc2fb5a60d85, 0x8529097d91096b75, 0x6a0ee36eeb0d0c05, 0x1eae0ad8ab68b2f0],
+    [0x9768bd98c5d06bfb, 0xdb6e2fdc0dee99e6, 0xe46f8282872abc88, 0x179190e5d0e22179],
+    // Round 7
+    [0x6cafe794a9b3cd1c, 0x14528f7db00f31bf, 0x76e9a81c7ac4b832, 0x29bb9e2c90767325],
+    [0xb10e590e6e691e08, 0x52652645882aac35, 0x403efd0c2464a90d, 0x225d394e42207599],
+    [0xe09efd454b23fd59, 0x2be13557451c087d, 0x753d238055b44453, 0x064760623c25c8cf],
+    [0x922910a78f6b5b87, 0x4d67f4bf42a75c10, 0x7f301c4b716d8a39, 0x10ba3a0e01df92e8],
+    // Round 8
+    [0x361b77693f21471c, 0xcb511bc0c242eb9d, 0x4f9c6e96b0c2a801, 0x0e070bf53f8451b2],
+    [0xa7f921014de252fb, 0xccd6cb11d2491d8a, 0xd39755ff93821a73, 0x1b94cd61b051b04d],
+    [0x0487b5aa7d74070b, 0x9d4e917d5713bb05, 0xe148787a2e70230f, 0x1d7cb39bafb8c744],
+    [0xbb74ac1f303b17db, 0x8785c2961829f701, 0x9117d0fe980c80ff, 0x2ec93189bd1ab4f6],
+    // Round 9
+    [0x82ea46bd83517926, 0xeac404a19ae07a90, 0xa692bb825b86275b, 0x2db366bfdd36d277],
+    [0xdc99cec6960711b8, 0x985275428450359a, 0x69655cf186a68532, 0x062100eb485db062],
+    [0x00c567bf41f5a59b, 0x20243f92fa59e4f9, 0x570e7f1e8244ca11, 0x0761d33c66614aaa],
+    [0xf7a72e494855ad0d, 0x5d78608a0f7de4cc, 0x2c2705aa034e3f31, 0x20fc411a114d1399],
+    // Round 10
+    [0xc3a30f317250bc5a, 0x102c67e8b3effb5f, 0xadd9ec4e9ab219ba, 0x25b5c004a4bdfcb5],
+    [0xd87e7dff62b37f4b, 0x038b186d8474155a, 0xa494e58f6df6f5ed, 0x23b1822d278ed632],
+    [0x16102a29cc2f69e0, 0x0f14d13bfcfcccaa, 0x606c4ba9012499bf, 0x22734b4c5c3f9493],
+    [0x54413d3fad795ce5, 0xe5bdff409aa36102, 0xe27a74dc33492347, 0x26c0c8fe09eb30b7],
+    // Round 11
+    [0xbbd626df348ccad9, 0x196be3083a809829, 0xe88eac03fa1fbb26, 0x070dd0ccb6bd7bba],
+    [0x6067c4ebfd4250da, 0xc2c0a6de46d8c5ad, 0xb043ba78bb28c3be, 0x12b6595bdb329b6f],
+    [0x5e33d95bb7e8d729, 0xc06fca9b275c671c, 0x3bec30e7a5876c11, 0x248d97d7f76283d6],
+    [0x106d15d9bd9baaaa, 0x8b45eb759ddde4aa, 0x16fc6fd64cc93931, 0x1a306d439d463b08],
+    // Round 12
+    [0x0d62d3d6ec7c56cf, 0xf4f1b54ddc27821b, 0xced7c00421cb4621, 0x28a8f8372e3c38da],
+    [0xbc852183e1e2ce7e, 0x071ce320c829f388, 0xbb35152f24d43294, 0x0094975717f9a8a8],
+    [0xf4103246db2e8d65, 0x593f74d4f653ae83, 0x80fde60d716480d3, 0x04d5ee4c3aa78f7d],
+    [0xd08495c12efde187, 0xc7bef54b8822cc76, 0x6349ad6fb8ed2269, 0x2a6cf5e9aa03d433],
+    // Round 13
+    [0xbaae48d7efcba3f3, 0xf792180808fd6e43, 0x9274da43e19ddeb7, 0x2304d31eaab960ba],
+    [0xe1c11d39d199f0b0, 0xbff08a7e0726fcb4, 0xd5e7009785817249, 0x03fd9ac865a4b2a6],
+    [0x3f7954d4d63b0b64, 0x798afc3a20919307, 0x2248404d55ee5044, 0x00b7258ded52bbda],
+    [0x6272c5ca65e92d9a, 0xb13d3a74f3298db3, 0xec38fca2d4bf65eb, 0x159f81ada0771799],
+    // Round 14
+    [0x71e144cf4264431f, 0x9000130ea25f0c54, 0x50237a75bc28e3bb, 0x1ef90e67437fbc85],
+    [0x95a79ed82932e30d, 0x8df739bc176b08ec, 0x196b49aa41a2d256, 0x1e65f838515e5ff0],
+    [0x6575c1068c94c33f, 0xb18c844e570e1f82, 0xec6ce768d079ba74, 0x2b1b045def3a166c],
+    [0xf1c6e07c168bb173, 0x65dc2d73bef715e3, 0x402543b1109229c1, 0x0832e5753ceb0ff6],
+    // Round 15
+    [0xc5a8e3c390b6ad16, 0xb1b841c2e8b6451b, 0x6b762ae0a37d41ba, 0x02f614e9cedfb3dc],
+    [0x0f6a0be27e7ed705, 0x7370ebb777bedff4, 0xdd640b8e362cad96, 0x0e2427d38bd46a60],
+    [0x0768bbe29214a53a, 0x049f0ec098c3c7c5, 0xeb7c84d414e7ce79, 0x0493630b7c670b6d],
+    [0x3dc06cc85327cea9, 0x6bb1515355d5461a, 0x4decdab17066c5a2, 0x22ead100e8e48267],
+    // Round 16
+    [0xe5084e0b6d2a6f16, 0x583f1ae35626d04d, 0xaae2626ed2554d48, 0x25b3e56e655b42cd],
+    [0x4b4fdc0a0cf6f9d0, 0xb599c336349e4c58, 0x5837a6cde8ff13db, 0x1e32752ada8836ef],
+    [0x72a9864074d412e5, 0x23c00995f05078f6, 0xc50f68f6f3c3455b, 0x2fa2a871c15a387c],
+    [0xcd18e7c7a7d83505, 0x54ccbf10661bab7f, 0x278e1db7311e889f, 0x2f569b8a9a4424c9],
+    // Round 17
+    [0x44165374b246b43d, 0xa7df93f7332ffd21, 0x531ade530234c518, 0x044cb455110a8fdd],
+    [0x78ddc723a5319025, 0x91fe8c90adfe1181, 0x420246157f2e42b1, 0x227808de93906d5d],
+    [0x8579d2e7a6800355, 0x5d03781ae090ad4a, 0x623adead87357986, 0x02fcca2934e046bc],
+    [0xcbec2e060d8befac, 0xbad3f3c5ab91a8dd, 0x6abccceb344a1d36, 0x0ef915f0ac120b87],
+    // Round 18
+    [0xf3b16ef2b1405d38, 0xab0fb85f6be63b09, 0x77eb757bc6f287f6, 0x1797130f4b7a3e17],
+    [0x36c668555decc6e5, 0x8c7f497c20156d4d, 0x3306c85abab59e60, 0x0a76225dc04170ae],
+    [0x96174b5326a31a5c, 0xf8fa76d48acb6647, 0xa1e77a7b93209af6, 0x1fffb9ec1992d66b],
+    [0x0611889b797b9c5f, 0x5f8fbba6c6b9c609, 0x53b57c338fa538d8, 0x25721c4fc15a3f28],
+    // Round 19
+    [0xeb63b982bfcaf75a, 0xadb4c3790705da95, 0x215e3d07ba197216, 0x0c817fd42d5f7a41],
+    [0x2bc15866e52b5a96, 0xdf8cf86ce00a2200, 0x9f7e13c2c24970b6, 0x13abe3f5239915d3],
+    [0x92cd60acb4d391ce, 0x5c1bc3dc29bdbd7a, 0x12ef7f39987a46c8, 0x2106feea546224ea],
+    [0x57e1b3345bb0f959, 0xf1ca5a28c748bc71, 0xaaa79474a37dab49, 0x21ca859468a746b6],
+    // Round 20
+    [0x8f1a48999e34185b, 0x2911d14d0321662a, 0x5cf1f0df934194c6, 0x05ccd6255c1e6f0c],
+    [0xea28678cb09490a4, 0x16c4fb267fe44fe6, 0xe464d846674c4c88, 0x0f0e34a64b70a626],
+    [0x8f5b1a8a2de0d4bf, 0x47dbfcfe350d6483, 0x6157794ca36d0e96, 0x0558531a4e25470c],
+    [0xb72f5864961f1455, 0x924cadad3f655a60, 0xceea125157683d18, 0x09d3dca9173ed2fa],
+    // Round 21
+    [0x17d4c722e5bd4335, 0xf23f92d68aaec486, 0x493f866ed03d218b, 0x0328cbd54e8c0913],
+    [0xee3347dd5329d34b, 0xe79e7bcc9798c648, 0x23a487b1a7094e07, 0x2bf07216e2aff0a2],
+    [0x111e11a63fe412df, 0xd6f78ed6a6dffc82, 0x6499c583cb76c316, 0x1daf345a58006b73],
+    [0x391e6f2293d2c404, 0x1ef39039b2edc7ff, 0x46b694c60e182361, 0x176563472456aaa7],
+    // Round 22
+    [0xfb0225035bd3f8db, 0xca964d2b7d1083d4, 0xa3bb5e47d7e33538, 0x2ef1e0fad9f08e87],
+    [0x1779ed36c817ae2a, 0x9c1803dec5ae8f0a, 0x17b2b1f57c731017, 0x226c9b1af95babcf],
+    [0x35734eb5d4ad0def, 0xf8148c89f13fb35d, 0x28126b4c3a15ae0f, 0x14bce3549cc3db74],
+    [0xe550cfd4034212c7, 0xb8e923d301f372f8, 0x742c3373f2635b48, 0x2debff156e276bb5],
+    // Round 23
+    [0xd7d0432d1d4760c7, 0x41afe1b6b29c47ad, 0xfc2395b22e356b64, 0x2d4083cf5a87f5b6],
+    [0x9c317c53d7161c29, 0x91bf79a10c0184d8, 0x34b911262fdc9c1b, 0x0c225b7bcd04bf9c],
+    [0x7b835265f9c9c8f3, 0x99aa0200db66d5aa, 0xc33a79bfac91a02c, 0x03152169d4f3d06e],
+    [0x7afe8b7aa7d3199c, 0xddc8f51bfdfebbb8, 0xb05974587486d58b, 0x0b61811a9210be78],
+    // Round 24
+    [0x046d637a533b6f78, 0xb8ae48acf7048f16, 0xf7eba6a5c5921878, 0x203e000cad298daa],
+    [0x0757143d1bfa9146, 0xba7ee386fda1112c, 0x376672b69f6c9655, 0x1a44bf0937c722d1],
+    [0x002f59c5611d4daa, 0xb8e0fde75a2106d7, 0x3500afec1a1f56ac, 0x0376b4fae08cb03d],
+    [0x3d553ef363182185, 0xd6fc241d3214177f, 0x65a2171250fdfc32, 0x00780af2ca1cad64],
+    // Round 25
+    [0xe9d857079bdc31d5, 0xb75dbe18d5221c87, 0xeb808bedfd72a8d9, 0x10774d9ab80c25bd],
+    [0xb56821fd19d3b6e8, 0x0d03f98929ca1d7f, 0x04b1e03b4bd9490c, 0x10dc6e9c006ea38b],
+    [0x70067d00141cac16, 0xb21f75bb60e35961, 0xb2c7645a50392798, 0x00544b8338791518],
+    [0x13bc534433ee428c, 0x52e105a3b8fa8526, 0x2e2e82eb122789e3, 0x222c01175718386f],
+    // Round 26
+    [0x151a1430f608e3c5, 0xb77f7bdb7f7e2b46, 0x59cfb8811b1e0f45, 0x2840d045e9bc22b2],
+    [0x508e01fa5860186b, 0x04554574c2990196, 0x009c937e468c335b, 0x062752f86eebe11a],
+    [0x55a8e83eaaf04746, 0x1c9950c12a80bc0a, 0x87adb87c20a478a7, 0x06041bdac48205ac],
+    [0x2b1dcbbf51f5000d, 0x2c7a2ae092f308d8, 0xff900a368949b002, 0x04a533f236c422d1],
+    // Round 27
+    [0x4bde50a2b2d05b2a, 0xfe066d1e7dc33df0, 0x11d6a955b3d4f25d, 0x13e31d7a67232fd8],
+    [0x2f79905bb13920f1, 0x9279d1648ff2c95d, 0xfbc13d6357e8599a, 0x011c2683ae91eb4d],
+    [0xa1ecaed015aaf6ae, 0xd56c928e3e2c2bd0, 0x25b1a270e0b4cba5, 0x0b0d219346b85745],
+    [0xd84c7a726b5f1364, 0xb65080781ef9fd13, 0x70291ee638690209, 0x14abdec8db9c6dc9],
+    // Round 28
+    [0x988d0376610be106, 0x01eb12202ef47ced, 0xfcd32aa3d2664788, 0x1a0b70b4b26fdc28],
+    [0x2704882e7278b607, 0x6401deb2ef99c4d1, 0x7b6943f9804e7fe5, 0x278543721f96d130],
+    [0xa36535e011d58259, 0x3f0738a325638d8b, 0x57866214dbd1473f, 0x16eb59494a9776cf],
+    [0x41c3479dcf8c644a, 0x9a9e53eeab6b7f8c, 0x4f240088fa5524c6, 0x2567a658a81ffb44],
+    // Round 29
+    [0xb882ade840bb13d8, 0xab78e0215a5715a6, 0xa7ab39f1abd9cf77, 0x29aa1d7c151e9ad0],
+    [0xe206b91f99f2c984, 0x6a4f017f9a85388c, 0xd4bbfce2b3641500, 0x15c091233e60efe0],
+    [0xeb679a8115f014cf, 0xe7673ad5f1915f9f, 0x0882c2c999558d77, 0x16bd7d22ff858e5e],
+    [0xffe6769250042025, 0xc0182d9b668b8e08, 0xb2c2e13ed6ef4074, 0x02db50480a07be0e],
+    // Round 30
+    [0x13ba866343b73119, 0x86330ef2bf7adb4c, 0x7b6806ec9d6cdba1, 0x05e4a220e6a3bc9f],
+    [0x104d37f1cbcf7a42, 0xb5f70bc424d39fa4, 0x98cbf2a5ee3b50e8, 0x1dda05ebc30170bc],
+    [0xcd301f22b0de8990, 0x91da214414d89ba5, 0xf645b6fee3667f3c, 0x0184bef721888187],
+    [0xad1a6d64341b78ec, 0x37414b84494e1577, 0x5f5e8276f62aef1c, 0x1498a307e6890006],
+    // Round 31
+    [0xfe33548ad46bd49d, 0xcef737b8fab1f864, 0xf4939800b9d2c3ea, 0x25f40f82b31dacc4],
+    [0xcb1ff31ce5bb9650, 0xe83056ce4907bfbb, 0x3f6f5862a30d2ea9, 0x09d317cc67025194],
+    [0x29b913b6cf3149d0, 0xa41132cd467a86ab, 0x3ba4ce4a4c1b3bd0, 0x2f77d77786d979b2],
+    [0x52f89e785f729bbf, 0x1bbd336963f254c1, 0x73dc266b6fccc684, 0x0f53dafd535a9f44],
+    // Round 32
+    [0xde96de85deef2fa2, 0x0e6976e1c00baf16, 0x65c3a099e17526fa, 0x25c1fd72e2230452],
+    [0x893e65d6ce4a8f62, 0x41af95c84eaea3cf, 0xe368d385d52d16be, 0x2a902c8980c17faa],
+    [0x5527405762f83529, 0x6676dd114d1dc8d2, 0x02878c8976b82be9, 0x1ce1580a3452ecf3],
+    [0x2fc50f7f0f4d0056, 0x01c5ec569609034d, 0xa49a1fa306df0088, 0x24a6073f91addc33],
+    // Round 33
+    [0x7f256c68b0be2b74, 0x83e07ca554b5d157, 0x9fc27fe306d71d45, 0x25e52dbd6124530d],
+    [0x6796e5b6cd70f15d, 0x5974be4d0a7b2994, 0x93468dbccfb02985, 0x23dffae3c423fa7a],
+    [0x99591bc9924ed6f5, 0x80615d50be36243a, 0x49b77594f6b027c4, 0x06342da370cc0d8c],
+    [0xcc7df0d8e9f63925, 0x4778303d0405c1b4, 0xb75f09f115fc751b, 0x2754114281286546],
+    // Round 34
+    [0xb59ee197f8187cf5, 0xabf214153833d7bd, 0x862c2bc1d119edde, 0x15c19e8534c5c1a8],
+    [0x79b4b3d2d77d5f3e, 0x366f3be0a8210616, 0xb4c78d0d9ef3cabe, 0x265fe062766d08fa],
+    [0x8debfd098d3ec7be, 0xd377ac5cd0146f04, 0xf22cb7cd0ac3a327, 0x13ccf689d67a3ec9],
+    [0x9fbccca4524aaebd, 0xd92a5e05bdf3fe6b, 0xf81cd3974827a887, 0x17662f7456789739],
+    // Round 35
+    [0xe809fd624be7ad5d, 0x82ca6a5cca70cee4, 0xef18631e515f7f2f, 0x21b29c76329b31c8],
+    [0x939eb17b01fa975c, 0x9c06738165215319, 0x441eb97fe2790198, 0x18137478382aadba],
+    [0x39ceec4668f37e88, 0xd34f761935ffd3b7, 0xdc724f5fef2b37c2, 0x2bc07ea2bfad68e8],
+    [0x0e602077aef9a03e, 0xb4173203c2bd94ad, 0x563840480df993fe, 0x2ddb2e376f54d64a],
+    // Round 36
+    [0x8adb25373596c3f7, 0xe8a20f8d72f61370, 0x06b41cb24c602609, 0x277eb50f2baa7061],
+    [0xbb7f87734c9a1fe5, 0xb33fc4b450c0db50, 0x9d0c620904f01a56, 0x0d4de47e1aba3426],
+    [0xae908d0279a29f0c, 0x9f445697058f134a, 0x428673b6bd3eea6f, 0x0b8442bfe9e4a1b4],
+    [0x74247fddb720f8f5, 0x26e186a65945e965, 0x6e06930cb89f7d4a, 0x11fe5b18fbbea1a8],
+    // Round 37
+    [0x170e4ad89c33a0d6, 0xdf5b774dcad4d883, 0x4d25d8f6d9f90021, 0x224026f6dfaf71e2],
+    [0x1bc9f9c62bbeb824, 0xa96bc9e37d1091f6, 0xe0704dad58d03465, 0x0b2ca6a999fe6887],
+    [0xa1a7e0c96529f421, 0x1d0a4ce41d364797, 0xd40c54053a28a06b, 0x221b63d66f0b45f9],
+    [0xdce2f4836bb84ad4, 0x7493bce64d4d24ae, 0x3d4120801b047d08, 0x30185c48b7b2f1d5],
+    // Round 38
+    [0xf8267318632a61f0, 0x533356f0faa48f27, 0xa989e223056227d3, 0x23f5d372a3f0e3cb],
+    [0x8e6dfbe4328f3e3b, 0x88e1e0090d06162e, 0x1bf8235ea162b1f3, 0x2716683b32c755fd],
+    [0xc930c69748d5d4bc, 0x3d140770c80ac67d, 0x04ca1d853ec0909e, 0x0977545836866fa2],
+    [0xe81c43c0f9434b31, 0x5f51682d31472b05, 0x025d91ab4982dd42, 0x1444e8f592bdbfd8],
+    // Round 39
+    [0xa00f874e7718fbe3, 0xbe3ffbfe583f7012, 0xbeb74a1c5cb8fee8, 0x26e04b65e9ca8270],
+    [0xdf69816fb1a914d2, 0x00f48f4febe29ad6, 0x34ee47a5cd9f8698, 0x22a5c2fa860d11fe],
+    [0x9f7474dd44c5c8d7, 0x7ec338f3a0964c62, 0x6afd672a738f4273, 0x174b54d9907d8f5c],
+    [0xd56c871907b39b87, 0x8d2189b87c8c8143, 0x1168fa66694cf280, 0x1db1db8aa45283f3],
+    // Round 40
+    [0x387341d813d1bfd1, 0x6f65faf8cce0ab66, 0x9030b8c7b7dfde12, 0x1530bf0f46527e88],
+    [0x89330a2f2bade457, 0x36ead9edc8f28148, 0x9f01c1cec8760e99, 0x0b73f613993229f5],
+    [0x7bd2dc0f36bcf41e, 0x587ab977fc822778, 0x4552aaea377f448d, 0x29c25a22fe216460],
+    [0x77df57d77c875526, 0x7abe82795dc272b3, 0x8503da66c92cf407, 0x2b30d53ed1759bfb],
+    // Round 41
+    [0xcf5f0a2916787cd2, 0x756c08c85ede7227, 0x7b7b7e69359d53a2, 0x12f6d703b5702aab],
+    [0x1ffa9ac706364113, 0x55ad01071028d484, 0x61a40a0b8837293a, 0x2520e18300afda3f],
+    [0xc68f09fa03b8b95f, 0xac9bc59278277393, 0xdda8ed4f346fa967, 0x1ec9daea860971ec],
+    [0x08aae24b830ad725, 0x83bf5cbf70ed407c, 0x432f5cd5bef8fe44, 0x0a99b3e178db2e2e],
+    // Round 42
+    [0x317abad7c5778492, 0x07ee0abac3c817a1, 0x086b89b601c2bbe4, 0x07cda9e63db6e39f],
+    [0x5d48aab38f8fc3a3, 0x49bd8290963203b3, 0x52d571b191bb0adb, 0x08c9c65a4f955e89],
+    [0x3801c9c17bdd9c9e, 0x9af54a2a3f2719d3, 0x49590ddbfbd709ed, 0x2737f8ce1d5a67b3],
+    [0xa9f179ba627f7d6a, 0x909432bd0c129813, 0xd28770072798e8b7, 0x1049a6c65ff019f0],
+    // Round 43
+    [0x60a5122361daeddb, 0xde8868944fdf64ee, 0xc0ea5a9beb27cecb, 0x18b4fe968732c462],
+    [0xa4f7473483885d19, 0xa6f478cfcf11f1b2, 0x440b2eaeeefa8c02, 0x2ff2b6fd22df49d2],
+    [0x8a1b352f5cef42ff, 0xe8be4057cbd8dbd1, 0xe56c789b8f6bbcb3, 0x2ec5f2f1928fe932],
+    [0x08c1d100378e545e, 0x424a4c6a7794ee3f, 0xe33ad9f75bf3426d, 0x265a5eccd8b92975],
+    // Round 44
+    [0x20517da1dfd4279c, 0x778e656cfcb366bf, 0x9d6242bb5ada0e68, 0x2405eaa4c0bde112],
+    [0x76dd98a2dbf60417, 0xfdb51955d8b2d66b, 0x88018004cbbf2bc5, 0x094c97d8c194c42e],
+    [0x330c9625c2afe0b8, 0x508b705221e6a686, 0x22b9979a605bf64d, 0x2c30d5f33bb32c5c],
+    [0x6aa2fc716fdb6cf5, 0x4886ea583e87299e, 0x25d01cc6dcb1622d, 0x01a75666f6241f68],
+    // Round 45
+    [0xf47bf2e87d382fcb, 0x6d359ab9a66979fc, 0x4d12ac091e87be7c, 0x0a3290e8398113ea],
+    [0xecd21bf69aa0cc74, 0xc31219d8fa0dfc75, 0xfeb38461425bb0d8, 0x154ade9ca36e268d],
+    [0x13a4b5095d028772, 0x99231ef5dc69d8dc, 0x1b172d79c6f22eee, 0x27aa8d3e25380c0b],
+    [0x9d395bbcbd806461, 0x56bbdf485afa1f54, 0x1a8b2e3bca6099d7, 0x2cf4051e6cab4830],
+    // Round 46
+    [0xb0843d7f84b23e71, 0x5131feab8afa5eeb, 0x1d3f517ddff9f201, 0x301e70f729f3c94b],
+    [0x17a8d7a4c91f83bc, 0x32dc4cef113ae60d, 0x8b4d9620347ab023, 0x298beb64f812d25d],
+    [0xcf11a3f02e46aa95, 0xd1c14a15b221680a, 0x4d03fd291c3c471e, 0x1b362e72a5f847f8],
+    [0xbc1d9ba41dc1c737, 0xaa1ef6e78e1e5ebc, 0x75432902999223d5, 0x0dc8a2146110c0b3],
+    // Round 47
+    [0x08afa1eb922ff279, 0xcb21729a72ddc03a, 0x05dc93092cb69778, 0x0a48663b34ce5e1c],
+    [0x545bb314881098ee, 0x0fe46f143b702d74, 0x6096b64a82f9e95f, 0x0a87391fb1cd8cdf],
+    [0x82ba8a2a0892fd5d, 0x8826edd7ea9c29f3, 0xf0512ff8e6ca362f, 0x1b5b2946f7c28975],
+    [0xb4eac1f533315b6b, 0x173a8bbcb8a5b987, 0x47ebe2239219bc6a, 0x01001cf512ac241d],
+    // Round 48
+    [0xc72beb17d8358a32, 0x7ac093d3fb5f5feb, 0xf704fa7d7693da72, 0x2fd977c70f645db4],
+    [0x9be763a97793a9c4, 0x761d5355c05444d9, 0xc2d7cc688164f39e, 0x23c0039a3fab4ad3],
+    [0x9f27f22ff03fa25d, 0xaec356cf435888e7, 0x2c9c0df6161eaac1, 0x19d43ee0c6081c05],
+    [0x919f9d5ca1cefe59, 0x8bf29b646d020830, 0xfddccffd94a56302, 0x2d9b10c2f2e7ac1a],
+    // Round 49
+    [0xdae2f2b9f83e4267, 0x2799283e166fc81c, 0xc47e4aff5a66f5ce, 0x2457ca6c2f2aa30e],
+    [0x044dfb54a7c10b35, 0x811ee8676ed6f0c3, 0x5820592445094022, 0x0abc392fe85eda85],
+    [0x1d2c2bc30eac1eb0, 0x1161ac3993acf310, 0x0cebcd37f3ea54f3, 0x19d2cc5ca549d1d4],
+    [0xa3d3ab546e98c9c8, 0x3ee0e4ec041ba644, 0x08aafb26ae13cd39, 0x0f97ae3033ffa016],
+    // Round 50
+    [0x8a166496e88cfeca, 0xfa15537ea4e168e8, 0x260e404cf1d427a7, 0x16dbc78fd28b7fb8],
+    [0x1827820366d5e07b, 0xef8344e576f8ad3d, 0x16f085f73bc4f22e, 0x240faf28f11499b9],
+    [0x46f8cab58d9ef1af, 0xeaba808c8fdb6dbf, 0xfe6c8531e55e1770, 0x0a1bb075aa37ff0c],
+    [0xc4a705a7ce089f4d, 0x38d5b085ac1042fd, 0xa6a853aaf3a644ca, 0x2e47e15ea4a47ff1],
+    // Round 51
+    [0x5fb14528375772b6, 0x673ab059935f4df3, 0x860ca4a9c09d39e1, 0x166e5bf073378348],
+    [0xed10f96538f0916f, 0x0cacccd027233001, 0xaf235902f057a274, 0x18b42d7ffdd2ea4f],
+    [0x21deab1051c37702, 0x4fc368020b3ed382, 0x4914788e3e3c7ead, 0x089cb1b032238f5e],
+    [0xd9e70863451dd8d1, 0x89f9339c7b971921, 0xaf7c7076dd165adf, 0x242acd3eb3a2f72b],
+    // Round 52
+    [0x74af860457245c3b, 0xeac9a068283f3264, 0xbf47f2bd82fce896, 0x174fbb104a4ee302],
+    [0x780c275fe1116c6b, 0x2891fb2bb318613f, 0x61f3058ce092c67d, 0x17340e71d96f466d],
+    [0xa2fd380c4df7f6b2, 0xf098b9f8fd455953, 0xf00f2e383982d024, 0x1e8e40ac853b7d42],
+    [0xbf40f92938e2e961, 0x5198c55cad66e8a9, 0xe1d4d5e284b8d107, 0x0529898dc0649907],
+    // Round 53
+    [0xf65f21c4d4e5df8f, 0xe8c77aa017ee1d7b, 0xbf7de5bb797364dc, 0x2162754db0baa030],
+    [0x21bef44741752ec6, 0xa9f9291efbde4c84, 0x3ceb250ae00c58c2, 0x12c7553698c4bf6f],
+    [0x9cb723136526508e, 0xa733c93353e9d9c7, 0xfcb8c5279313bd51, 0x292643e3ba2026af],
+    [0x1db6e74d5b87d158, 0xb6c07c5d98e66ff7, 0x1d52951bea990bd5, 0x00ccf13e0cb6f9d8],
+    // Round 54
+    [0xb0f86c15ab645b4b, 0xb6723873cb30fc22, 0xdd654128cf2f3aaa, 0x185d1e20e23b0917],
+    [0x13fe53f8d8764e1f, 0x6778e3de0f024c0f, 0x742bdf11c60efa18, 0x14c61c836d55d3df],
+    [0xd03ee1195d72449e, 0x2919e2af53008184, 0xe5dbe4680457691c, 0x0f356841b3f556fc],
+    [0x0c0a6b6e8fa5b3e8, 0x83143374fd2080ba, 0x5df124f887bf40b3, 0x1b8fd9ff39714e07],
+    // Round 55
+    [0xe9103418796f6024, 0xfc3c8ae04e9df0b3, 0xa3f873924e2aaa14, 0x0e86a8c2009c140c],
+    [0xb0861421e79155c8, 0x373fc43820ca2b16, 0x0e5462ad932fcdd2, 0x2e6c5e898f554777],
+    [0x2ce5fd5a0c014604, 0xff9fe1a0ecd37797, 0x7c14f9d1df032bc9, 0x05d797f1ab364723],
+    [0xca8929851da8c008, 0x1daf2dcd65519ef5, 0x6c3d152875981d0c, 0x29a3110463a5aae7],
+    // Round 56
+    [0x4b732f8163883314, 0xdc71640a8bbd1f86, 0x73c3a4b91c05354c, 0x2974da7bc0743222],
+    [0xcce9c522889b47dc, 0xa29cb91aa082c8bf, 0xb2a30621c05eb12c, 0x1ed0fb06699ba249],
+    [0xd80c8ae36e40fe9b, 0xae29e8c572eca912, 0x654ff26d8d863fee, 0x1c793ef0dcc51123],
+    [0xfbb4a8770977dc2f, 0x8c91e82589a78169, 0x7956257d3d234ef1, 0x1e6aac1c6d3dd315],
+    // Round 57
+    [0x8fcda33256fb6bf5, 0xd037748080a47d94, 0xe6273dd6fa98b25e, 0x1a20ada7576234ee],
+    [0x35d49306728af96c, 0x642d772045ece513, 0xfc7a9a23a6fd9996, 0x191033d6d85ceaa6],
+    [0x32ef481f5d06297b, 0xc76f200b3740b8b2, 0x3a825aa6fddc3abf, 0x006e5979da7e7ef5],
+    [0x1eff8c0174cdb06d, 0xfbd57f596c8f2983, 0xbef3e68d417e9fa0, 0x0b0d7e69c651910b],
+    // Round 58
+    [0x2c4b20a25c9cdf9d, 0x4ac46dbbb033c511, 0x16435ec084e2ecd4, 0x25caf5b0c1b93bc5],
+    [0x085b2f150f72472a, 0xf7f77442d62fd4c8, 0x9af8b796d9645872, 0x12c1ea892cc31e0d],
+    [0x1de6dadc78c32aae, 0xe5a929d9f928b9b8, 0xb8bbe3afeb245fee, 0x16af29695157aba9],
+    [0x68d31084256b67dc, 0x705b87ec5a4cfdc1, 0xd687fb2f3be18691, 0x0136df457c80588d],
+    // Round 59
+    [0xb95a285060e7b089, 0x9e07b1efbc74434d, 0x6aea984fba6e7147, 0x1639a28c5b4c8116],
+    [0x7e232bd9b5ca9b76, 0x816c28b700bdc50f, 0x13f8e650f587ec06, 0x03d62fbf82fd1d43],
+    [0x249830de1edfde54, 0xf77a1e40fc6da97c, 0xb4d14aaddca3cfe2, 0x11aeeb527dc8ce44],
+    [0x642b645807bfc824, 0x6a670e6bc68c7a49, 0x79c5e6138c6c8ee3, 0x13f9b9a412741294],
+    // Round 60
+    [0x506cae8b7ebcd15b, 0x5ddeeed7a939440c, 0xc8484cd26c7c1f63, 0x0e4772fa3d75179d],
+    [0x39fc46a68c5d4db4, 0xb5971752067a612b, 0xde4bdec58febe8d8, 0x1b39a00cbc81e427],
+    [0x444d1c0a3a25707e, 0xf66463c2eb54a245, 0x71e16e2953f48731, 0x2bedb66e1ad5a1d5],
+    [0x7379ce35da915dec, 0xb08b193b608582a2, 0x8abd068f06a7287f, 0x2cf0a09a55ca93af],
+    // Round 61
+    [0x753c8fb863efb387, 0x7d1a512050ba7db0, 0x88830cabfef2f8d2, 0x2d1bd78fa90e77aa],
+    [0x630d7fd283dc3394, 0xf7c0d49c1387062e, 0xf423d3071eb83539, 0x065610c6f4f92491],
+    [0x642fb464bd607368, 0xcc5f9969033f15ec, 0x5013b12873452beb, 0x2d933ff19217a554],
+    [0x3c49c8aa99e0258b, 0x00dae5354e79508c, 0xf76b92b3e13b30d5, 0x1aa9d3fe4c644910],
+    // Round 62
+    [0x78cea1f1c8450bdd, 0x27095fa773e1aca0, 0xc748638c59111c6b, 0x027ef04869e482b1],
+    [0x02e3fa136ad0b8fb, 0x9f67a2605d9ec038, 0x15db4e00668a8c44, 0x2b7d524c5172cbbb],
+    [0x3f7c3c1dd735db0f, 0x4693ae25b1e55df1, 0x7c8718d86747c7f7, 0x0c7c382443c6aa78],
+    [0xa627dcdd9bd79078, 0x7a1f43c2d30d0fe4, 0x62a7b56acf4f7620, 0x00b4567186bc3f7c],
+    // Round 63
+    [0x0337490883db4fd5, 0xb07fe739e4c1e61d, 0xe6d61737fe08b47f, 0x1e41fc29b825454f],
+    [0x002ae8d3ba0653b6, 0x21e1af872d8c0e89, 0x72ee6dafc6165844, 0x12507cd556b7bbcc],
+    [0xd77d3e97f71cb5db, 0x97eb36617ef36fe4, 0xcef312e5e6f52a5d, 0x13d437083553006b],
+    [0x4686077c6a4486d5, 0x467d90b22f0b3866, 0x687222487dda9a65, 0x163ec73251f85443],
+];
+
+/// MDS matrix for t=4 in Montgomery form (circomlib values * R mod p)
+pub const MDS_MATRIX_T4_MONT: [[MontgomeryFr; 4]; 4] = [
+    [
+        MontgomeryFr { limbs: [0xc746d3a1ea1cc193, 0x6909fa60cbf91537, 0x5b41f9011d64f292, 0x163fd60c1936197d] },
+        MontgomeryFr { limbs: [0x0181d5313bbdbfdb, 0xe18378c86214909e, 0xcc39e35f710ea822, 0x214f5455afa43479] },
+        MontgomeryFr { limbs: [0x582d922f6dc48b16, 0x38c8cb41c45cff82, 0x7df9249ce4429252, 0x007ab9de1893f215] },
+        MontgomeryFr { limbs: [0x9cff2c0f6412a4c4, 0x30fb90c591f50925, 0x6e6ab78411b6fcb9, 0x1b77cf0ecb224202] },
+    ],
+    [
+        MontgomeryFr { limbs: [0xc8c85e8ccec69c00, 0xbe0283254c087a25, 0xe7e873d447f84ea7, 0x006de5b38e5fd49e] },
+        MontgomeryFr { limbs: [0x437c9035a82c8a7e, 0x2cc53efa3fc81999, 0x16a3050a16ec5fef, 0x1405c2ce761b1ffe] },
+        MontgomeryFr { limbs: [0x8100aebd55865e63, 0x4efac234996ba0f3, 0xd7620cc74699967a, 0x1910150019158192] },
+        MontgomeryFr { limbs: [0x6339508f8d23fda0, 0xff4e29746759d717, 0xc9d745285d69fd3c, 0x1c752d58c2ef9d67] },
+    ],
+    [
+        MontgomeryFr { limbs: [0xbb83fa3e0a031edf, 0xa247540e9ab2abde, 0x10ce1ad9c5a34aa0, 0x0e0d7c4459fde200] },
+        MontgomeryFr { limbs: [0x742a45e4beba79e2, 0x262159b82a5d52b2, 0x67c2c32c9a7262fb, 0x1a016e5249486d11] },
+        MontgomeryFr { limbs: [0x032ee49d3f46f8e3, 0x9581df46402300b5, 0xb073993fd3900255, 0x2ff42c980d018110] },
+        MontgomeryFr { limbs: [0xef89cf7680de46a2, 0xaf9fb3890c2afe43, 0xc6241b343d910469, 0x2906a4070cbc228f] },
+    ],
+    [
+        MontgomeryFr { limbs: [0x37d7866bffe26d19, 0x6ced6607b03ae067, 0x68fce24891c473c7, 0x0bb889c2fd6eac56] },
+        MontgomeryFr { limbs: [0x6135f5062b5b2e93, 0x6f938788c83dcb7f, 0x4fc8df2d97896992, 0x077d3ed0c2de7b50] },
+        MontgomeryFr { limbs: [0xaafcf1bd4770a975, 0xfd91630f7b3a8546, 0x789e7c4c49ebb73c, 0x0018a0fab8043422] },
+        MontgomeryFr { limbs: [0xd2e8592dd30a25b5, 0x4f626b2e7f68559e, 0xa8ca8234e9a2e036, 0x03b26515166e9d76] },
+    ],
+];
+
+/// Poseidon parameter set for state width `T` (rate `T - 1`, capacity 1)
+///
+/// Each round adds `T` round constants, so `round_constants` holds
+/// `T * (full_rounds + partial_rounds)` entries in round order.
+#[derive(Debug)]
+pub struct PoseidonParams<const T: usize> {
+    /// Full rounds, split evenly before and after the partial rounds
+    pub full_rounds: usize,
+    /// Partial rounds (S-box on the first element only)
+    pub partial_rounds: usize,
+    /// Round constants in normal form, `T` per round
+    pub round_constants: &'static [[u64; 4]],
+    /// MDS matrix in Montgomery form
+    pub mds: [[MontgomeryFr; T]; T],
+}
+
+impl<const T: usize> PoseidonParams<T> {
+    /// Inputs absorbed per permutation
+    pub const RATE: usize = T - 1;
+
+    /// Total rounds
+    pub const fn rounds(&self) -> usize {
+        self.full_rounds + self.partial_rounds
+    }
+}
+
+/// BN254 parameters for t=3, circom compatible (the default instance)
+pub const BN254_T3_PARAMS: PoseidonParams<POSEIDON_T> = PoseidonParams {
+    full_rounds: POSEIDON_RF,
+    partial_rounds: POSEIDON_RP,
+    round_constants: &ROUND_CONSTANTS,
+    mds: MDS_MATRIX_MONT,
+};
+
+const _: () = assert!(BN254_T3_PARAMS.round_constants.len() == POSEIDON_T * BN254_T3_PARAMS.rounds());
+const _: () = assert!(PoseidonParams::<POSEIDON_T>::RATE == POSEIDON_RATE);
+
+/// BN254 parameters for t=4, circom compatible (circomlib `Poseidon(3)`)
+pub const BN254_T4_PARAMS: PoseidonParams<4> = PoseidonParams {
+    full_rounds: POSEIDON_RF,
+    partial_rounds: 56,
+    round_constants: &ROUND_CONSTANTS_T4,
+    mds: MDS_MATRIX_T4_MONT,
+};
+
+const _: () = assert!(BN254_T4_PARAMS.round_constants.len() == 4 * BN254_T4_PARAMS.rounds());
+
+/// Poseidon permutation state over a parameter set of width `T`
 #[derive(Clone, Debug)]
-pub struct Poseidon {
-    state: [Fr; 3],
+pub struct PoseidonState<const T: usize> {
+    params: &'static PoseidonParams<T>,
+    state: [Fr; T],
     round_idx: usize,
 }
 
-impl Poseidon {
-    /// Create new Poseidon instance
+/// The t=3 BN254 instance used for every hash in this program
+pub type Poseidon = PoseidonState<POSEIDON_T>;
+
+impl<const T: usize> PoseidonState<T> {
+    /// Zeroed state over `params`
     #[inline(never)]
-    pub fn new() -> Self {
+    pub fn with_params(params: &'static PoseidonParams<T>) -> Self {
         Self {
-            state: [Fr::ZERO; 3],
+            params,
+            state: [Fr::ZERO; T],
             round_idx: 0,
         }
     }
 
-    /// Add round constants (3 per round)
+    /// Add round constants (`T` per round)
     #[inline(never)]
     fn add_round_constants(&mut self) {
-        let base = self.round_idx * 3;
-        for i in 0..3 {
-            let rc = Fr { limbs: ROUND_CONSTANTS[base + i] };
+        let base = self.round_idx * T;
+        for i in 0..T {
+            let rc = Fr { limbs: self.params.round_constants[base + i] };
             self.state[i] = self.state[i].add(&rc);
         }
         self.round_idx += 1;
@@ -607,7 +1053,7 @@ impl Poseidon {
     /// Apply S-box (x^5) to all elements
     #[inline(never)]
     fn full_sbox(&mut self) {
-        for i in 0..3 {
+        for i in 0..T {
             self.state[i] = self.state[i].pow5();
         }
     }
@@ -622,10 +1068,10 @@ impl Poseidon {
     #[inline(never)]
     fn mds_mix(&mut self) {
         let old = self.state;
-        for i in 0..3 {
-            self.state[i] = Fr::ZERO;
-            for j in 0..3 {
-                self.state[i] = self.state[i].add(&old[j].mul(&MDS_MATRIX[i][j]));
+        for (out, row) in self.state.iter_mut().zip(&self.params.mds) {
+            *out = Fr::ZERO;
+            for (x, m) in old.iter().zip(row) {
+                *out = out.add(&x.mul_by_mont(m));
             }
         }
     }
@@ -633,6 +1079,7 @@ impl Poseidon {
     /// Run permutation - split into phases to reduce stack per frame
     #[inline(never)]
     fn permute(&mut self) {
+        self.round_idx = 0;
         self.permute_first_full_rounds();
         self.permute_partial_rounds();
         self.permute_second_full_rounds();
@@ -641,7 +1088,7 @@ impl Poseidon {
     /// First half of full rounds
     #[inline(never)]
     fn permute_first_full_rounds(&mut self) {
-        for _ in 0..(POSEIDON_RF / 2) {
+        for _ in 0..(self.params.full_rounds / 2) {
             self.add_round_constants();
             self.full_sbox();
             self.mds_mix();
@@ -651,7 +1098,7 @@ impl Poseidon {
     /// Partial rounds
     #[inline(never)]
     fn permute_partial_rounds(&mut self) {
-        for _ in 0..POSEIDON_RP {
+        for _ in 0..self.params.partial_rounds {
             self.add_round_constants();
             self.partial_sbox();
             self.mds_mix();
@@ -661,13 +1108,59 @@ impl Poseidon {
     /// Second half of full rounds
     #[inline(never)]
     fn permute_second_full_rounds(&mut self) {
-        for _ in 0..(POSEIDON_RF / 2) {
+        for _ in 0..(self.params.full_rounds / 2) {
             self.add_round_constants();
             self.full_sbox();
             self.mds_mix();
         }
     }
 
+    /// Capacity initial value for an `n`-input sponge
+    ///
+    /// Zero for a single full block so the one-block hash is the plain
+    /// permutation; any other length gets a distinct non-zero tag, which
+    /// also separates zero padding ([a] vs [a, 0]).
+    fn length_tag(n: usize) -> Fr {
+        if n == PoseidonParams::<T>::RATE {
+            Fr::ZERO
+        } else {
+            Fr { limbs: [n as u64, 1, 0, 0] }
+        }
+    }
+
+    /// Sponge hash of any number of field elements over `params`
+    ///
+    /// Inputs are absorbed `T - 1` at a time into state[1..T] with a
+    /// permutation after each block; a short trailing block is zero-padded.
+    /// The output is state[0].
+    #[inline(never)]
+    pub fn sponge(params: &'static PoseidonParams<T>, inputs: &[Fr]) -> Fr {
+        let mut poseidon = Self::with_params(params);
+        poseidon.state[0] = Self::length_tag(inputs.len());
+
+        if inputs.is_empty() {
+            poseidon.permute();
+            return poseidon.state[0];
+        }
+
+        for block in inputs.chunks(PoseidonParams::<T>::RATE) {
+            for (slot, input) in poseidon.state[1..].iter_mut().zip(block) {
+                *slot = slot.add(input);
+            }
+            poseidon.permute();
+        }
+
+        poseidon.state[0]
+    }
+}
+
+impl Poseidon {
+    /// Create new Poseidon instance over the t=3 BN254 parameters
+    #[inline(never)]
+    pub fn new() -> Self {
+        Self::with_params(&BN254_T3_PARAMS)
+    }
+
     /// Hash two field elements
     #[inline(never)]
     pub fn hash2(a: &Fr, b: &Fr) -> Fr {
@@ -686,6 +1179,26 @@ impl Poseidon {
         let h2 = Self::hash2(&inputs[2], &inputs[3]);
         Self::hash2(&h1, &h2)
     }
+
+    /// Sponge hash of any number of field elements (rate 2, capacity 1)
+    ///
+    /// `hash_n(&[a, b])` equals `hash2(a, b)`.
+    #[inline(never)]
+    pub fn hash_n(inputs: &[Fr]) -> Fr {
+        Self::sponge(&BN254_T3_PARAMS, inputs)
+    }
+}
+
+impl PoseidonState<4> {
+    /// Hash three field elements: one t=4 permutation of [0, a, b, c],
+    /// the same as circomlib `Poseidon(3)` and Noir's `hash_3`
+    #[inline(never)]
+    pub fn hash3(a: &Fr, b: &Fr, c: &Fr) -> Fr {
+        let mut poseidon = Self::with_params(&BN254_T4_PARAMS);
+        poseidon.state = [Fr::ZERO, *a, *b, *c];
+        poseidon.permute();
+        poseidon.state[0]
+    }
 }
 
 impl Default for Poseidon {
@@ -707,6 +1220,15 @@ pub fn poseidon_hash_2(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
     Poseidon::hash2(&a, &b).to_bytes()
 }
 
+/// Hash three 32-byte values (t=4 permutation)
+#[inline(never)]
+pub fn poseidon_hash_3(inputs: &[[u8; 32]; 3]) -> [u8; 32] {
+    let a = Fr::from_bytes(&inputs[0]);
+    let b = Fr::from_bytes(&inputs[1]);
+    let c = Fr::from_bytes(&inputs[2]);
+    PoseidonState::<4>::hash3(&a, &b, &c).to_bytes()
+}
+
 /// Hash four 32-byte values
 #[inline(never)]
 pub fn poseidon_hash_4(inputs: &[[u8; 32]; 4]) -> [u8; 32] {
@@ -719,7 +1241,46 @@ pub fn poseidon_hash_4(inputs: &[[u8; 32]; 4]) -> [u8; 32] {
     Poseidon::hash4(&fr_inputs).to_bytes()
 }
 
+/// Sponge hash of an arbitrary number of field elements
+///
+/// `hash2` remains the 2-input special case: `poseidon_hash_n(&[a, b])`
+/// equals `Poseidon::hash2(&a, &b)`. Note that `hash4` is a tree of `hash2`
+/// calls, not the 4-input sponge.
+#[inline(never)]
+pub fn poseidon_hash_n(inputs: &[Fr]) -> Fr {
+    Poseidon::hash_n(inputs)
+}
+
+/// Sponge hash of any number of 32-byte values
+#[inline(never)]
+pub fn poseidon_hash_many(inputs: &[[u8; 32]]) -> [u8; 32] {
+    let fr_inputs: Vec<Fr> = inputs.iter().map(Fr::from_bytes).collect();
+    poseidon_hash_n(&fr_inputs).to_bytes()
+}
+
+/// Amount as a little-endian field element
+fn amount_field(amount: u64) -> [u8; 32] {
+    let mut amount_bytes = [0u8; 32];
+    amount_bytes[0..8].copy_from_slice(&amount.to_le_bytes());
+    amount_bytes
+}
+
+// Commitment arities
+//
+// Every pool instruction in this program (private/batch/variable/token
+// deposits, private_transfer outputs, unified_deposit) expects 4-input
+// commitments, since the Noir/circom deposit, withdraw and transfer
+// circuits all open that form. Fixed-denomination deposits pass a zero recipient, since the
+// stealth address is only bound at withdrawal.
+//
+// The 2- and 3-input forms are for circuits that don't carry those inputs
+// at all; they are not interchangeable with the 4-input form (a zero
+// recipient still hashes differently from no recipient), so a client must
+// use whichever arity its circuit opens.
+
 /// Compute commitment: Poseidon(nullifier, secret, amount, recipient)
+///
+/// `hash4` tree, used by every pool in this program (see above).
 #[inline(never)]
 pub fn compute_commitment(
     nullifier: &[u8; 32],
@@ -727,10 +1288,26 @@ pub fn compute_commitment(
     amount: u64,
     recipient: &[u8; 32],
 ) -> [u8; 32] {
-    let mut amount_bytes = [0u8; 32];
-    amount_bytes[0..8].copy_from_slice(&amount.to_le_bytes());
+    poseidon_hash_4(&[*nullifier, *secret, amount_field(amount), *recipient])
+}
 
-    poseidon_hash_4(&[*nullifier, *secret, amount_bytes, *recipient])
+/// Compute commitment: Poseidon(nullifier, secret)
+///
+/// For fixed-denomination circuits that bind neither amount nor recipient
+/// (Tornado-style); a single `hash2` call.
+#[inline(never)]
+pub fn compute_commitment_2(nullifier: &[u8; 32], secret: &[u8; 32]) -> [u8; 32] {
+    poseidon_hash_2(nullifier, secret)
+}
+
+/// Compute commitment: Poseidon(nullifier, secret, amount)
+///
+/// For circuits that bind the amount but leave the recipient out of the
+/// commitment. A single t=4 permutation over [0, nullifier, secret, amount],
+/// matching circomlib `Poseidon(3)` / Noir `poseidon::bn254::hash_3`.
+#[inline(never)]
+pub fn compute_commitment_3(nullifier: &[u8; 32], secret: &[u8; 32], amount: u64) -> [u8; 32] {
+    poseidon_hash_3(&[*nullifier, *secret, amount_field(amount)])
 }
 
 /// Compute nullifier hash: Poseidon(nullifier, 0)
@@ -740,10 +1317,65 @@ pub fn compute_nullifier_hash(nullifier: &[u8; 32]) -> [u8; 32] {
     poseidon_hash_2(nullifier, &zero)
 }
 
+/// Compute a position-bound nullifier hash: Poseidon(nullifier, leaf_index)
+///
+/// Ties the nullifier to the tree position being spent. The unbound
+/// `compute_nullifier_hash` is kept for existing notes and circuits; note
+/// the two agree at leaf index 0.
+#[inline(never)]
+pub fn compute_nullifier_hash_bound(nullifier: &[u8; 32], leaf_index: u64) -> [u8; 32] {
+    let index = Fr::from_u64(leaf_index).to_bytes();
+    poseidon_hash_2(nullifier, &index)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// Field element from a big-endian hex string (as circomlibjs prints it),
+    /// in this module's little-endian byte order
+    fn from_be_hex(hex: &str) -> [u8; 32] {
+        assert_eq!(hex.len(), 64);
+        let mut out = [0u8; 32];
+        for (i, byte) in out.iter_mut().rev().enumerate() {
+            *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap();
+        }
+        out
+    }
+
+    fn field(value: u64) -> [u8; 32] {
+        Fr::from_u64(value).to_bytes()
+    }
+
+    /// Reference outputs of circomlibjs `poseidon([a, b])` (t = 3)
+    #[test]
+    fn test_poseidon_matches_circomlib_vectors() {
+        // poseidon([1, 2]) = 7853200120776062878684798364095072458815029376092732009249414926327459813530
+        assert_eq!(
+            poseidon_hash_2(&field(1), &field(2)),
+            from_be_hex("115cc0f5e7d690413df64c6b9662e9cf2a3617f2743245519e19607a4417189a")
+        );
+        // poseidon([0, 0]), the first empty-subtree hash of circom Merkle trees
+        assert_eq!(
+            poseidon_hash_2(&field(0), &field(0)),
+            from_be_hex("2098f5fb9e239eab3ceac3f27b81e481dc3124d55ffed523a839ee8446b64864")
+        );
+        assert_eq!(
+            poseidon_hash_2(&field(3), &field(4)),
+            from_be_hex("20a3af0435914ccd84b806164531b0cd36e37d4efb93efab76913a93e1f30996")
+        );
+    }
+
+    #[test]
+    fn test_poseidon_chains_like_circomlib() {
+        // Hashing an output again: poseidon([z1, z1]) for z1 = poseidon([0, 0])
+        let z1 = poseidon_hash_2(&field(0), &field(0));
+        assert_eq!(
+            poseidon_hash_2(&z1, &z1),
+            from_be_hex("1069673dcdb12263df301a6ff584a7ec261a44cb9dc68df067a4774460b1f1e1")
+        );
+    }
+
     #[test]
     fn test_poseidon_deterministic() {
         let a = [1u8; 32];
@@ -755,6 +1387,19 @@ mod tests {
         assert_eq!(h1, h2);
     }
 
+    #[test]
+    fn test_bound_nullifier_hash_depends_on_leaf_index() {
+        let nullifier = [7u8; 32];
+
+        let h0 = compute_nullifier_hash_bound(&nullifier, 0);
+        let h1 = compute_nullifier_hash_bound(&nullifier, 1);
+        assert_ne!(h0, h1);
+        assert_eq!(h1, compute_nullifier_hash_bound(&nullifier, 1));
+
+        // Leaf 0 binds to the zero field element, matching the unbound hash
+        assert_eq!(h0, compute_nullifier_hash(&nullifier));
+    }
+
     #[test]
     fn test_poseidon_different_inputs() {
         let a = [1u8; 32];
@@ -766,6 +1411,167 @@ mod tests {
         assert_ne!(h1, h2);
     }
 
+    /// Slow but obviously-correct reference: shift-and-subtract reduction of the 512-bit product
+    fn reference_mul(a: &Fr, b: &Fr) -> Fr {
+        let wide = mul_wide(&a.limbs, &b.limbs);
+        let mut r = Fr::ZERO;
+        for bit in (0..512).rev() {
+            // r = 2r + bit (mod p); r < p < 2^254 so doubling cannot overflow 256 bits
+            r = r.add(&r);
+            if (wide[bit / 64] >> (bit % 64)) & 1 == 1 {
+                r = r.add(&Fr::ONE);
+            }
+        }
+        r
+    }
+
+    /// Deterministic splitmix64 so the vectors are reproducible without a rand dependency
+    fn next_u64(state: &mut u64) -> u64 {
+        *state = state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = *state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    fn random_fr(state: &mut u64) -> Fr {
+        let mut bytes = [0u8; 32];
+        for chunk in bytes.chunks_mut(8) {
+            chunk.copy_from_slice(&next_u64(state).to_le_bytes());
+        }
+        Fr::from_bytes(&bytes)
+    }
+
+    #[test]
+    fn test_barrett_mul_matches_reference() {
+        let mut state = 0x5eed_u64;
+        for _ in 0..4000 {
+            let a = random_fr(&mut state);
+            let b = random_fr(&mut state);
+            assert_eq!(a.mul(&b), reference_mul(&a, &b), "mismatch for {:?} * {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_barrett_mul_edge_cases() {
+        let p_minus_1 = Fr {
+            limbs: [BN254_MODULUS[0] - 1, BN254_MODULUS[1], BN254_MODULUS[2], BN254_MODULUS[3]],
+        };
+        // (p-1)^2 = 1 mod p
+        assert_eq!(p_minus_1.mul(&p_minus_1), Fr::ONE);
+        assert_eq!(p_minus_1.mul(&Fr::ONE), p_minus_1);
+        assert_eq!(p_minus_1.mul(&Fr::ZERO), Fr::ZERO);
+        assert_eq!(Fr::from_u64(u64::MAX).mul(&Fr::from_u64(u64::MAX)),
+            reference_mul(&Fr::from_u64(u64::MAX), &Fr::from_u64(u64::MAX)));
+    }
+
+    #[test]
+    fn test_montgomery_roundtrip_and_mul() {
+        let mut state = 0x4d0e7_u64;
+        for _ in 0..1000 {
+            let a = random_fr(&mut state);
+            let b = random_fr(&mut state);
+            let am = MontgomeryFr::to_montgomery(&a);
+            let bm = MontgomeryFr::to_montgomery(&b);
+
+            assert_eq!(am.from_montgomery(), a);
+            assert_eq!(am.mul_mont(&bm).from_montgomery(), a.mul(&b));
+            assert_eq!(am.add(&bm).from_montgomery(), a.add(&b));
+            assert_eq!(a.mul_by_mont(&bm), a.mul(&b));
+        }
+    }
+
+    #[test]
+    fn test_mds_matrix_mont_matches_mds_matrix() {
+        for i in 0..3 {
+            for j in 0..3 {
+                assert_eq!(MDS_MATRIX_MONT[i][j].from_montgomery(), MDS_MATRIX[i][j]);
+                assert_eq!(MontgomeryFr::to_montgomery(&MDS_MATRIX[i][j]), MDS_MATRIX_MONT[i][j]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_mds_mix_matches_plain_multiplication() {
+        let mut state = 0x3d5_u64;
+        for _ in 0..200 {
+            let mut poseidon = Poseidon::new();
+            poseidon.state = [random_fr(&mut state), random_fr(&mut state), random_fr(&mut state)];
+            let old = poseidon.state;
+
+            poseidon.mds_mix();
+
+            for i in 0..3 {
+                let mut expected = Fr::ZERO;
+                for j in 0..3 {
+                    expected = expected.add(&old[j].mul(&MDS_MATRIX[i][j]));
+                }
+                assert_eq!(poseidon.state[i], expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_hash_n_two_inputs_matches_hash2() {
+        let mut state = 0x2a_u64;
+        for _ in 0..20 {
+            let a = random_fr(&mut state);
+            let b = random_fr(&mut state);
+            assert_eq!(poseidon_hash_n(&[a, b]), Poseidon::hash2(&a, &b));
+        }
+
+        let a = [0x11u8; 32];
+        let b = [0x22u8; 32];
+        assert_eq!(poseidon_hash_many(&[a, b]), poseidon_hash_2(&a, &b));
+    }
+
+    #[test]
+    fn test_hash_n_length_separation() {
+        let a = Fr::from_u64(7);
+
+        // Zero padding must not collide with an explicit trailing zero
+        assert_ne!(poseidon_hash_n(&[a]), poseidon_hash_n(&[a, Fr::ZERO]));
+        // Empty input must not collide with hash2(0, 0)
+        assert_ne!(poseidon_hash_n(&[]), Poseidon::hash2(&Fr::ZERO, &Fr::ZERO));
+        // Longer inputs are order-sensitive and differ from the hash2 tree
+        let inputs = [Fr::from_u64(1), Fr::from_u64(2), Fr::from_u64(3), Fr::from_u64(4)];
+        let reversed = [inputs[3], inputs[2], inputs[1], inputs[0]];
+        assert_ne!(poseidon_hash_n(&inputs), poseidon_hash_n(&reversed));
+        assert_ne!(poseidon_hash_n(&inputs), Poseidon::hash4(&inputs));
+        assert_eq!(poseidon_hash_n(&inputs), poseidon_hash_n(&inputs));
+    }
+
+    /// Outputs recorded before `Poseidon` became generic over its parameters
+    #[test]
+    fn test_parameterized_poseidon_keeps_t3_hashes() {
+        let f = Fr::from_u64;
+
+        assert_eq!(
+            Poseidon::hash2(&f(1), &f(2)).to_bytes(),
+            from_be_hex("115cc0f5e7d690413df64c6b9662e9cf2a3617f2743245519e19607a4417189a")
+        );
+        assert_eq!(
+            Poseidon::hash4(&[f(1), f(2), f(3), f(4)]).to_bytes(),
+            from_be_hex("075d30e28d48842bd6c1044b68f982d586e2892ae91c77f8f56111d8f55070ed")
+        );
+        assert_eq!(
+            Poseidon::hash_n(&[]).to_bytes(),
+            from_be_hex("29f94b67ee4e78b2bb08da025f9943c1201a7af025a27600c2dd0a2e71c7cf8b")
+        );
+        assert_eq!(
+            Poseidon::hash_n(&[f(1), f(2), f(3)]).to_bytes(),
+            from_be_hex("2ef3f2ea4750d961d16816891764c178c5f655037ebd2823adbb35b158b2868a")
+        );
+        assert_eq!(
+            Poseidon::hash_n(&[f(5), f(6), f(7), f(8), f(9)]).to_bytes(),
+            from_be_hex("08a55edbc77aff85b0ab153a1bd9732f0587a1abbff2936fa3417351d94bb32f")
+        );
+
+        // The generic sponge over the default parameters is the same hash
+        let inputs = [f(5), f(6), f(7)];
+        assert_eq!(PoseidonState::sponge(&BN254_T3_PARAMS, &inputs), Poseidon::hash_n(&inputs));
+    }
+
     #[test]
     fn test_commitment() {
         let nullifier = [0x11u8; 32];
@@ -778,4 +1584,43 @@ mod tests {
 
         assert_eq!(c1, c2);
     }
+
+    #[test]
+    fn test_commitment_2() {
+        let nullifier = [0x11u8; 32];
+        let secret = [0x22u8; 32];
+
+        assert_eq!(compute_commitment_2(&nullifier, &secret), compute_commitment_2(&nullifier, &secret));
+        assert_eq!(compute_commitment_2(&nullifier, &secret), poseidon_hash_2(&nullifier, &secret));
+        assert_ne!(compute_commitment_2(&nullifier, &secret), compute_commitment_2(&secret, &nullifier));
+    }
+
+    /// Reference output of circomlibjs `poseidon([a, b, c])` (t = 4)
+    #[test]
+    fn test_poseidon_3_matches_circomlib_vector() {
+        // poseidon([1, 2, 3])
+        let expected = from_be_hex("0e7732d89e6939c0ff03d5e58dab6302f3230e269dc5b968f725df34ab36d732");
+        assert_eq!(poseidon_hash_3(&[field(1), field(2), field(3)]), expected);
+        assert_eq!(compute_commitment_3(&field(1), &field(2), 3), expected);
+        // Not the 3-input t=3 sponge
+        assert_ne!(poseidon_hash_many(&[field(1), field(2), field(3)]), expected);
+    }
+
+    #[test]
+    fn test_commitment_3() {
+        let nullifier = [0x11u8; 32];
+        let secret = [0x22u8; 32];
+        let amount = 1_000_000_000u64;
+
+        let c1 = compute_commitment_3(&nullifier, &secret, amount);
+        assert_eq!(c1, compute_commitment_3(&nullifier, &secret, amount));
+        assert_ne!(c1, compute_commitment_3(&nullifier, &secret, amount + 1));
+
+        // Arities never coincide, even with a zero recipient/amount
+        assert_ne!(c1, compute_commitment(&nullifier, &secret, amount, &[0u8; 32]));
+        assert_ne!(
+            compute_commitment_3(&nullifier, &secret, 0),
+            compute_commitment_2(&nullifier, &secret)
+        );
+    }
 }
@@ -155,6 +155,9 @@ pub enum StealthError {
     #[msg("Verification key data too large")]
     VerificationKeyTooLarge,
 
+    #[msg("Verification key version must increase")]
+    InvalidVerificationKeyVersion,
+
     // ==========================================
     // VIEW KEY ERRORS
     // ==========================================
@@ -315,4 +318,203 @@ pub enum StealthError {
 
     #[msg("Nullifier check failed - may have been used")]
     NullifierCheckFailed,
+
+    // ==========================================
+    // TOKEN POOL ERRORS
+    // ==========================================
+
+    #[msg("Token mint does not match pool mint")]
+    InvalidTokenMint,
+
+    #[msg("Pool is not a token pool")]
+    NotTokenPool,
+
+    // ==========================================
+    // ORACLE QUORUM ERRORS
+    // ==========================================
+
+    #[msg("Duplicate attestation - each verifier may attest only once")]
+    DuplicateAttestation,
+
+    // ==========================================
+    // DECOY DEPOSIT ERRORS
+    // ==========================================
+
+    #[msg("Too many decoys - max 8 per deposit")]
+    TooManyDecoys,
+
+    // ==========================================
+    // RELAYER FEE DISCOVERY ERRORS
+    // ==========================================
+
+    #[msg("Relayer fee does not match the relayer's advertised fee")]
+    RelayerFeeMismatch,
+
+    // ==========================================
+    // MERKLE TREE CAPACITY ERRORS
+    // ==========================================
+
+    #[msg("Merkle tree is full - no more leaves can be inserted")]
+    TreeFull,
+
+    // ==========================================
+    // ANONYMITY SET ERRORS
+    // ==========================================
+
+    #[msg("Pool has too few deposits to withdraw from yet")]
+    AnonymitySetTooSmall,
+
+    // ==========================================
+    // FEE SPLIT ERRORS
+    // ==========================================
+
+    #[msg("Protocol and relayer fee shares must sum to at most 10000 bps")]
+    InvalidFeeSplit,
+
+    // ==========================================
+    // VERIFICATION ORACLE ERRORS
+    // ==========================================
+
+    #[msg("Verifier is already trusted by the oracle")]
+    VerifierAlreadyTrusted,
+
+    #[msg("Oracle already trusts the maximum number of verifiers")]
+    TooManyVerifiers,
+
+    // ==========================================
+    // GROTH16 INPUT ERRORS
+    // ==========================================
+
+    #[msg("Malformed proof - A and C must be 64 bytes, B 128 bytes")]
+    MalformedProof,
+
+    #[msg("Public input count does not match the verification key (IC points - 1)")]
+    PublicInputCountMismatch,
+
+    // ==========================================
+    // SPLIT WITHDRAWAL ERRORS
+    // ==========================================
+
+    #[msg("Split withdrawal needs 1 to 4 splits, each with a stealth address and announcement")]
+    InvalidSplitCount,
+
+    #[msg("Split amounts must sum to the denomination minus the relayer fee")]
+    SplitSumMismatch,
+
+    #[msg("Each split must be at least the rent-exempt minimum")]
+    SplitBelowRentExempt,
+
+    // ==========================================
+    // WITHDRAWAL DELAY ERRORS
+    // ==========================================
+
+    #[msg("Note was deposited too recently to withdraw from this pool")]
+    WithdrawalTooSoon,
+
+    // ==========================================
+    // DEPOSIT ERRORS
+    // ==========================================
+
+    #[msg("Commitment has already been deposited")]
+    DuplicateCommitment,
+
+    // ==========================================
+    // RELAYER STATS ERRORS
+    // ==========================================
+
+    #[msg("Relay request has not expired yet")]
+    RelayNotExpired,
+
+    // ==========================================
+    // FIELD ELEMENT ERRORS
+    // ==========================================
+
+    #[msg("Public input is not a canonical BN254 scalar (must be below the field modulus)")]
+    NonCanonicalFieldElement,
+
+    // ==========================================
+    // AUTHORITY TRANSFER ERRORS
+    // ==========================================
+
+    #[msg("Signer is not the pending authority for this account")]
+    NotPendingAuthority,
+
+    // ==========================================
+    // RENT ERRORS
+    // ==========================================
+
+    #[msg("Payout is below the rent-exempt minimum for the recipient account")]
+    BelowRentExempt,
+
+    // ==========================================
+    // REGISTRY LABEL ERRORS
+    // ==========================================
+
+    #[msg("Label is longer than 32 bytes")]
+    LabelTooLong,
+
+    #[msg("Label must be UTF-8 without NUL bytes")]
+    InvalidLabel,
+
+    // ==========================================
+    // TREE AUDIT ERRORS
+    // ==========================================
+
+    #[msg("Pool root doesn't match the root recomputed from its frontier")]
+    TreeInconsistent,
+
+    // ==========================================
+    // ATTESTATION DOMAIN ERRORS
+    // ==========================================
+
+    #[msg("Verification oracle is for a different circuit")]
+    VerificationKeyMismatch,
+
+    // ==========================================
+    // ANNOUNCEMENT INDEX ERRORS
+    // ==========================================
+
+    #[msg("Announcement index is for a different slot bucket")]
+    AnnouncementIndexBucketMismatch,
+
+    // ==========================================
+    // COMMITMENT SCHEME ERRORS
+    // ==========================================
+
+    #[msg("Unknown commitment scheme")]
+    InvalidCommitmentScheme,
+
+    // ==========================================
+    // VERIFIED BATCH ERRORS
+    // ==========================================
+
+    #[msg("Invalid batch size - must be 1-2 withdrawals, each with a nullifier and recipient account")]
+    InvalidVerifiedBatchSize,
+
+    #[msg("Nullifier appears more than once in the batch")]
+    DuplicateBatchNullifier,
+
+    // ==========================================
+    // ATTESTATION METADATA ERRORS
+    // ==========================================
+
+    #[msg("Attestation is for a different verification key version")]
+    VerificationKeyVersionMismatch,
+
+    #[msg("Attestation method doesn't verify the proof")]
+    UnverifiedAttestationMethod,
+
+    // ==========================================
+    // VARIABLE POOL ERRORS
+    // ==========================================
+
+    #[msg("Variable-amount pool is disabled in this build (no withdrawal path yet)")]
+    VariablePoolDisabled,
+
+    // ==========================================
+    // ATTESTATION CIRCUIT ERRORS
+    // ==========================================
+
+    #[msg("Attestation isn't bound to a circuit - set the oracle's verification key hash")]
+    UnboundAttestationCircuit,
 }
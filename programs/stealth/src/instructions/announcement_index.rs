@@ -0,0 +1,64 @@
+//! Announcement Index Instructions
+//!
+//! Anyone can create the index for a slot bucket; announcing instructions
+//! then append their announcement PDA when given it. See
+//! `state::announcement_index` for what a scanner can rely on.
+
+use anchor_lang::prelude::*;
+use crate::error::StealthError;
+use crate::state::AnnouncementIndex;
+
+/// Accounts for initialize_announcement_index
+#[derive(Accounts)]
+#[instruction(bucket: u64)]
+pub struct InitializeAnnouncementIndex<'info> {
+    /// Pays for the index account
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = AnnouncementIndex::SIZE,
+        seeds = [AnnouncementIndex::SEED, &bucket.to_le_bytes()],
+        bump,
+    )]
+    pub index: Account<'info, AnnouncementIndex>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Create the announcement index for `bucket`
+///
+/// Creating it before the margin leading into the bucket lets it cover the
+/// whole bucket.
+pub fn initialize_announcement_index(ctx: Context<InitializeAnnouncementIndex>, bucket: u64) -> Result<()> {
+    let index = &mut ctx.accounts.index;
+    index.bucket = bucket;
+    index.created_slot = Clock::get()?.slot;
+    index.total_announcements = 0;
+    index.bump = ctx.bumps.index;
+    index.announcements = Vec::new();
+
+    msg!("Announcement index for bucket {} initialized", bucket);
+    Ok(())
+}
+
+/// Record `announcement`, made at `slot`, in the index if one was passed
+///
+/// The index must be for `slot`'s bucket, or the next one when `slot` is in
+/// the margin at the end of its bucket.
+pub(crate) fn index_announcement(
+    index: Option<&mut Account<AnnouncementIndex>>,
+    announcement: Pubkey,
+    slot: u64,
+) -> Result<()> {
+    if let Some(index) = index {
+        require!(
+            index.accepts_slot(slot),
+            StealthError::AnnouncementIndexBucketMismatch
+        );
+        index.record(announcement);
+    }
+    Ok(())
+}
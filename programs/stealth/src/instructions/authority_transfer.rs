@@ -0,0 +1,317 @@
+//! Two-step Authority Transfer
+//!
+//! Pools (SOL and token), pool configs, the relayer registry and the
+//! verification oracle each store an authority. Handing it over takes two instructions: the
+//! current authority proposes a new key, and that key signs an accept.
+//! A mistyped key never gains control, and a proposal can be replaced or
+//! withdrawn (by proposing `Pubkey::default()`) until it is accepted.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+use crate::error::StealthError;
+use crate::instructions::{ManageVerificationOracle, UpdatePoolConfig};
+use crate::state::privacy_pool::{PoolConfig, PrivacyPool};
+use crate::state::RelayerRegistry;
+use crate::util::{accept_authority, propose_authority};
+use crate::zk::verifier::VerificationOracle;
+
+// ==========================================
+// PRIVACY POOL
+// ==========================================
+
+/// Accounts for propose_pool_authority
+#[derive(Accounts)]
+#[instruction(denomination: u64)]
+pub struct ProposePoolAuthority<'info> {
+    /// Current pool authority
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PrivacyPool::SEED, &denomination.to_le_bytes()],
+        bump,
+        constraint = pool.load()?.authority == authority.key() @ StealthError::Unauthorized,
+    )]
+    pub pool: AccountLoader<'info, PrivacyPool>,
+}
+
+/// Accounts for accept_pool_authority
+#[derive(Accounts)]
+#[instruction(denomination: u64)]
+pub struct AcceptPoolAuthority<'info> {
+    /// The proposed authority
+    pub new_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PrivacyPool::SEED, &denomination.to_le_bytes()],
+        bump,
+    )]
+    pub pool: AccountLoader<'info, PrivacyPool>,
+}
+
+/// Accounts for propose_token_pool_authority
+#[derive(Accounts)]
+#[instruction(denomination: u64)]
+pub struct ProposeTokenPoolAuthority<'info> {
+    /// Current pool authority
+    pub authority: Signer<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [PrivacyPool::SEED, mint.key().as_ref(), &denomination.to_le_bytes()],
+        bump,
+        constraint = pool.load()?.authority == authority.key() @ StealthError::Unauthorized,
+    )]
+    pub pool: AccountLoader<'info, PrivacyPool>,
+}
+
+/// Accounts for accept_token_pool_authority
+#[derive(Accounts)]
+#[instruction(denomination: u64)]
+pub struct AcceptTokenPoolAuthority<'info> {
+    /// The proposed authority
+    pub new_authority: Signer<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [PrivacyPool::SEED, mint.key().as_ref(), &denomination.to_le_bytes()],
+        bump,
+    )]
+    pub pool: AccountLoader<'info, PrivacyPool>,
+}
+
+/// Propose a new authority for a pool
+pub fn propose_pool_authority(
+    ctx: Context<ProposePoolAuthority>,
+    denomination: u64,
+    new_authority: Pubkey,
+) -> Result<()> {
+    propose_privacy_pool_authority(&ctx.accounts.pool, new_authority)?;
+
+    msg!("Pool {} authority proposed: {}", denomination, new_authority);
+    Ok(())
+}
+
+/// Take over a pool's authority as its pending authority
+pub fn accept_pool_authority(ctx: Context<AcceptPoolAuthority>, denomination: u64) -> Result<()> {
+    let new_authority = ctx.accounts.new_authority.key();
+    accept_privacy_pool_authority(&ctx.accounts.pool, new_authority)?;
+
+    msg!("Pool {} authority transferred to {}", denomination, new_authority);
+    Ok(())
+}
+
+/// Propose a new authority for a token pool
+pub fn propose_token_pool_authority(
+    ctx: Context<ProposeTokenPoolAuthority>,
+    denomination: u64,
+    new_authority: Pubkey,
+) -> Result<()> {
+    propose_privacy_pool_authority(&ctx.accounts.pool, new_authority)?;
+
+    msg!("Token pool {} ({}) authority proposed: {}", denomination, ctx.accounts.mint.key(), new_authority);
+    Ok(())
+}
+
+/// Take over a token pool's authority as its pending authority
+pub fn accept_token_pool_authority(ctx: Context<AcceptTokenPoolAuthority>, denomination: u64) -> Result<()> {
+    let new_authority = ctx.accounts.new_authority.key();
+    accept_privacy_pool_authority(&ctx.accounts.pool, new_authority)?;
+
+    msg!("Token pool {} ({}) authority transferred to {}", denomination, ctx.accounts.mint.key(), new_authority);
+    Ok(())
+}
+
+/// Record a proposed authority on a SOL or token pool
+fn propose_privacy_pool_authority(pool: &AccountLoader<PrivacyPool>, new_authority: Pubkey) -> Result<()> {
+    let mut pool = pool.load_mut()?;
+    propose_authority(&mut pool.pending_authority, new_authority);
+    Ok(())
+}
+
+/// Hand a SOL or token pool to its pending authority
+fn accept_privacy_pool_authority(pool_loader: &AccountLoader<PrivacyPool>, new_authority: Pubkey) -> Result<()> {
+    let old_authority = {
+        let mut pool = pool_loader.load_mut()?;
+        let pool = &mut *pool;
+        accept_authority(&mut pool.authority, &mut pool.pending_authority, &new_authority)?
+    };
+
+    emit!(AuthorityTransferred {
+        account: pool_loader.key(),
+        old_authority,
+        new_authority,
+    });
+    Ok(())
+}
+
+// ==========================================
+// POOL CONFIG
+// ==========================================
+
+/// Accounts for accept_pool_config_authority
+#[derive(Accounts)]
+#[instruction(denomination: u64)]
+pub struct AcceptPoolConfigAuthority<'info> {
+    /// The proposed authority
+    pub new_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PoolConfig::SEED, &denomination.to_le_bytes()],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, PoolConfig>,
+}
+
+/// Propose a new authority for a pool's config
+pub fn propose_pool_config_authority(
+    ctx: Context<UpdatePoolConfig>,
+    denomination: u64,
+    new_authority: Pubkey,
+) -> Result<()> {
+    propose_authority(&mut ctx.accounts.config.pending_authority, new_authority);
+
+    msg!("Pool {} config authority proposed: {}", denomination, new_authority);
+    Ok(())
+}
+
+/// Take over a pool config's authority as its pending authority
+pub fn accept_pool_config_authority(
+    ctx: Context<AcceptPoolConfigAuthority>,
+    denomination: u64,
+) -> Result<()> {
+    let new_authority = ctx.accounts.new_authority.key();
+    let account = ctx.accounts.config.key();
+    let config: &mut PoolConfig = &mut ctx.accounts.config;
+    let old_authority = accept_authority(&mut config.authority, &mut config.pending_authority, &new_authority)?;
+
+    emit!(AuthorityTransferred {
+        account,
+        old_authority,
+        new_authority,
+    });
+
+    msg!("Pool {} config authority transferred to {}", denomination, new_authority);
+    Ok(())
+}
+
+// ==========================================
+// RELAYER REGISTRY
+// ==========================================
+
+/// Accounts for propose_relayer_registry_authority
+#[derive(Accounts)]
+pub struct ProposeRelayerRegistryAuthority<'info> {
+    /// Current registry authority
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [RelayerRegistry::SEED],
+        bump = registry.bump,
+        constraint = registry.authority == authority.key() @ StealthError::Unauthorized,
+    )]
+    pub registry: Account<'info, RelayerRegistry>,
+}
+
+/// Accounts for accept_relayer_registry_authority
+#[derive(Accounts)]
+pub struct AcceptRelayerRegistryAuthority<'info> {
+    /// The proposed authority
+    pub new_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [RelayerRegistry::SEED],
+        bump = registry.bump,
+    )]
+    pub registry: Account<'info, RelayerRegistry>,
+}
+
+/// Propose a new authority for the relayer registry
+pub fn propose_relayer_registry_authority(
+    ctx: Context<ProposeRelayerRegistryAuthority>,
+    new_authority: Pubkey,
+) -> Result<()> {
+    propose_authority(&mut ctx.accounts.registry.pending_authority, new_authority);
+
+    msg!("Relayer registry authority proposed: {}", new_authority);
+    Ok(())
+}
+
+/// Take over the relayer registry's authority as its pending authority
+pub fn accept_relayer_registry_authority(ctx: Context<AcceptRelayerRegistryAuthority>) -> Result<()> {
+    let new_authority = ctx.accounts.new_authority.key();
+    let account = ctx.accounts.registry.key();
+    let registry: &mut RelayerRegistry = &mut ctx.accounts.registry;
+    let old_authority = accept_authority(&mut registry.authority, &mut registry.pending_authority, &new_authority)?;
+
+    emit!(AuthorityTransferred {
+        account,
+        old_authority,
+        new_authority,
+    });
+
+    msg!("Relayer registry authority transferred to {}", new_authority);
+    Ok(())
+}
+
+// ==========================================
+// VERIFICATION ORACLE
+// ==========================================
+
+/// Accounts for accept_oracle_authority
+#[derive(Accounts)]
+pub struct AcceptOracleAuthority<'info> {
+    /// The proposed authority
+    pub new_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [VerificationOracle::SEED],
+        bump = oracle.bump,
+    )]
+    pub oracle: Account<'info, VerificationOracle>,
+}
+
+/// Propose a new authority for the verification oracle
+pub fn propose_oracle_authority(
+    ctx: Context<ManageVerificationOracle>,
+    new_authority: Pubkey,
+) -> Result<()> {
+    propose_authority(&mut ctx.accounts.oracle.pending_authority, new_authority);
+
+    msg!("Verification oracle authority proposed: {}", new_authority);
+    Ok(())
+}
+
+/// Take over the verification oracle's authority as its pending authority
+pub fn accept_oracle_authority(ctx: Context<AcceptOracleAuthority>) -> Result<()> {
+    let new_authority = ctx.accounts.new_authority.key();
+    let account = ctx.accounts.oracle.key();
+    let oracle: &mut VerificationOracle = &mut ctx.accounts.oracle;
+    let old_authority = accept_authority(&mut oracle.authority, &mut oracle.pending_authority, &new_authority)?;
+
+    emit!(AuthorityTransferred {
+        account,
+        old_authority,
+        new_authority,
+    });
+
+    msg!("Verification oracle authority transferred to {}", new_authority);
+    Ok(())
+}
+
+#[event]
+pub struct AuthorityTransferred {
+    /// Pool, config, registry or oracle whose authority changed
+    pub account: Pubkey,
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
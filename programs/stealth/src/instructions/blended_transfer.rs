@@ -153,7 +153,7 @@ pub struct BlendedTransferWithLog<'info> {
             &log.log_id.to_le_bytes(),
         ],
         bump = log.bump,
-        constraint = log.can_add_entry() @ StealthError::LogFull,
+        constraint = log.can_add_entry() @ StealthError::LogNotActive,
     )]
     pub log: Account<'info, AnnouncementLog>,
 
@@ -199,9 +199,7 @@ pub fn blended_transfer_with_log(
         amount_hint: AnnouncementEntry::amount_to_hint(amount),
     };
 
-    log.entries.push(entry);
-    log.entry_count += 1;
-    log.last_entry_slot = clock.slot;
+    log.record(entry);
 
     // Emit events
     emit!(TransferEvent {
@@ -261,6 +259,8 @@ pub fn initialize_announcement_log(
     log.is_active = true;
     log.created_slot = clock.slot;
     log.last_entry_slot = 0;
+    log.next_index = 0;
+    log.total_entries = 0;
     log.bump = ctx.bumps.log;
     log.entries = Vec::with_capacity(AnnouncementLog::DEFAULT_MAX_ENTRIES as usize);
 
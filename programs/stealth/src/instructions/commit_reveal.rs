@@ -297,38 +297,60 @@ pub fn reveal_and_withdraw(
 #[derive(Accounts)]
 #[instruction(commitment_hash: [u8; 32])]
 pub struct CancelCommitment<'info> {
-    /// User cancelling their commitment
-    #[account(mut)]
-    pub owner: Signer<'info>,
+    /// Owner, or anyone once the commitment has expired
+    pub caller: Signer<'info>,
+
+    /// Commitment owner (receives the rent refund)
+    /// CHECK: Verified against commitment
+    #[account(
+        mut,
+        constraint = owner.key() == commitment.owner @ StealthError::Unauthorized,
+    )]
+    pub owner: UncheckedAccount<'info>,
 
     /// The commitment to cancel
     #[account(
         mut,
+        close = owner,
         seeds = [
             WithdrawalCommitment::SEED,
             owner.key().as_ref(),
             &commitment_hash,
         ],
         bump = commitment.bump,
-        constraint = commitment.owner == owner.key() @ StealthError::Unauthorized,
         constraint = !commitment.executed @ StealthError::CommitmentAlreadyExecuted,
     )]
     pub commitment: Account<'info, WithdrawalCommitment>,
 }
 
-/// Cancel a withdrawal commitment
+/// Cancel a withdrawal commitment and refund its rent to the owner
 ///
-/// User can cancel anytime before execution.
-/// This allows changing their mind or re-committing with different timing.
+/// The owner can cancel anytime before execution. Once the reveal window
+/// (`commit_timestamp + max_delay_seconds`) has passed, anyone can cancel,
+/// so abandoned commitments don't keep rent locked forever.
 pub fn cancel_commitment(
     ctx: Context<CancelCommitment>,
     _commitment_hash: [u8; 32],
 ) -> Result<()> {
+    let clock = Clock::get()?;
     let commitment = &mut ctx.accounts.commitment;
 
+    require!(
+        commitment.can_cancel(&ctx.accounts.caller.key(), clock.unix_timestamp),
+        StealthError::Unauthorized
+    );
+
     commitment.cancelled = true;
 
-    msg!("Withdrawal commitment cancelled");
+    emit!(CommitmentCancelledEvent {
+        owner: commitment.owner,
+        commitment_hash: commitment.commitment_hash,
+        cancelled_by: ctx.accounts.caller.key(),
+        expired: commitment.is_expired(clock.unix_timestamp),
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Withdrawal commitment cancelled, rent refunded to owner");
 
     Ok(())
 }
@@ -380,3 +402,14 @@ pub fn close_commitment(
 
     Ok(())
 }
+
+/// Event emitted when a withdrawal commitment is cancelled
+#[event]
+pub struct CommitmentCancelledEvent {
+    pub owner: Pubkey,
+    pub commitment_hash: [u8; 32],
+    pub cancelled_by: Pubkey,
+    /// True if cancelled after the reveal window closed
+    pub expired: bool,
+    pub timestamp: i64,
+}
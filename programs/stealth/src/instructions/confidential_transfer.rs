@@ -130,13 +130,12 @@ fn verify_bulletproof_attestation(
         StealthError::RangeProofFailed
     );
 
-    // Verify attestation is recent (within 5 minutes)
-    let clock = Clock::get()?;
-    let age = clock.unix_timestamp.saturating_sub(attestation.verified_at);
-    require!(
-        age >= 0 && age < 300,
-        StealthError::AttestationExpired
-    );
+    // Verify attestation is recent (default window, no oracle account here)
+    crate::zk::verifier::check_attestation_age(
+        Clock::get()?.unix_timestamp,
+        attestation.verified_at,
+        crate::zk::verifier::max_attestation_age(None),
+    )?;
 
     // Verify signature format (non-zero)
     let zero_sig = [0u8; 64];
@@ -293,6 +292,8 @@ pub fn confidential_withdraw(
         Some(&zk_attestation),
         &ctx.accounts.instructions_sysvar,
         None,
+        None,
+        None,
     )?;
 
     // Verify Bulletproof attestation for amount
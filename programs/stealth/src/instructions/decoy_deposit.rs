@@ -0,0 +1,237 @@
+//! Decoy Deposit Instruction
+//!
+//! Variant of `private_deposit` that inserts up to `DecoyRecord::MAX_DECOYS`
+//! caller-supplied decoy commitments next to the real one, in one transaction.
+//! Decoys are random field elements with no known preimage, so they can never
+//! be withdrawn; they are listed in a `DecoyRecord` so clients can rebuild the
+//! tree and skip them when looking for spendable notes.
+//!
+//! The batch is inserted in an order shuffled from a caller-provided nonce, so
+//! the real leaf doesn't sit at a fixed position within the batch. Only the
+//! real denomination is charged.
+//!
+//! Note: the real commitment is still visible in the instruction data and in
+//! its `CommitmentLeaf` account. Shuffling defeats position/timing heuristics
+//! over tree indices, not an observer that decodes the transaction.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use anchor_lang::system_program;
+use crate::state::privacy_pool::{PrivacyPool, CommitmentLeaf, PoolConfig};
+use crate::state::decoy::{DecoyLeaf, DecoyRecord};
+use crate::error::StealthError;
+use super::private_deposit::{emit_root_checkpoint, insert_commitments_batch_zc};
+
+/// Decoy deposit accounts for fixed-denomination pools
+#[derive(Accounts)]
+#[instruction(denomination: u64, commitment: [u8; 32])]
+pub struct DepositWithDecoys<'info> {
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+
+    /// Pool PDA includes denomination in seeds
+    #[account(
+        mut,
+        seeds = [PrivacyPool::SEED, &denomination.to_le_bytes()],
+        bump,
+    )]
+    pub pool: AccountLoader<'info, PrivacyPool>,
+
+    /// Config PDA includes denomination in seeds
+    #[account(
+        seeds = [PoolConfig::SEED, &denomination.to_le_bytes()],
+        bump = config.bump,
+        constraint = !config.deposits_paused @ StealthError::DepositsPaused,
+    )]
+    pub config: Account<'info, PoolConfig>,
+
+    /// Commitment leaf for the real deposit only
+    #[account(
+        init,
+        payer = depositor,
+        space = CommitmentLeaf::SIZE,
+        seeds = [CommitmentLeaf::SEED, &denomination.to_le_bytes(), commitment.as_ref()],
+        bump,
+    )]
+    pub commitment_leaf: Account<'info, CommitmentLeaf>,
+
+    /// Decoy record, keyed by the real commitment
+    #[account(
+        init,
+        payer = depositor,
+        space = DecoyRecord::SIZE,
+        seeds = [DecoyRecord::SEED, &denomination.to_le_bytes(), commitment.as_ref()],
+        bump,
+    )]
+    pub decoy_record: Account<'info, DecoyRecord>,
+
+    /// Optional: fee recipient
+    /// CHECK: Validated against config
+    #[account(
+        mut,
+        constraint = fee_recipient.key() == config.fee_recipient @ StealthError::InvalidFeeRecipient,
+    )]
+    pub fee_recipient: Option<AccountInfo<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Deposit into a fixed-denomination pool alongside decoy commitments
+///
+/// # Arguments
+/// * `denomination` - The pool denomination in lamports
+/// * `commitment` - The real commitment hash: Poseidon(nullifier, secret)
+/// * `decoys` - Random decoy commitments (at most 8, distinct, non-zero)
+/// * `shuffle_nonce` - Seeds the insertion order of the batch
+/// * `encrypted_note` - Optional encrypted note for recipient
+#[inline(never)]
+pub fn deposit_with_decoys(
+    ctx: Context<DepositWithDecoys>,
+    denomination: u64,
+    commitment: [u8; 32],
+    decoys: Vec<[u8; 32]>,
+    shuffle_nonce: [u8; 32],
+    encrypted_note: Option<[u8; 128]>,
+) -> Result<()> {
+    require!(decoys.len() <= DecoyRecord::MAX_DECOYS, StealthError::TooManyDecoys);
+    validate_decoys(&commitment, &decoys)?;
+
+    let config = &ctx.accounts.config;
+    let clock = Clock::get()?;
+
+    // Check pool is active and verify denomination matches
+    let amount = {
+        let pool = ctx.accounts.pool.load()?;
+        require!(pool.is_active, StealthError::PoolNotActive);
+        require!(
+            pool.denomination == denomination,
+            StealthError::AmountMustMatchDenomination
+        );
+        pool.denomination
+    };
+
+    // Fee is charged on the real denomination only - decoys are free
+    let fee = (amount as u128)
+        .checked_mul(config.fee_bps as u128)
+        .ok_or(StealthError::ArithmeticOverflow)?
+        .checked_div(10_000)
+        .ok_or(StealthError::ArithmeticOverflow)? as u64;
+    let deposit_amount = amount
+        .checked_sub(fee)
+        .ok_or(StealthError::ArithmeticUnderflow)?;
+
+    transfer_to_pool(&ctx, deposit_amount)?;
+    if fee > 0 {
+        transfer_fee(&ctx, fee)?;
+    }
+
+    // Real commitment goes first, then the whole batch is shuffled
+    let mut batch = Vec::with_capacity(decoys.len() + 1);
+    batch.push(commitment);
+    batch.extend_from_slice(&decoys);
+    shuffle_commitments(&mut batch, &shuffle_nonce);
+
+    let first_index;
+    let new_root;
+    {
+        let mut pool = ctx.accounts.pool.load_mut()?;
+        first_index = insert_commitments_batch_zc(&mut pool, &batch)?;
+        pool.total_deposited = pool.total_deposited
+            .checked_add(deposit_amount)
+            .ok_or(StealthError::ArithmeticOverflow)?;
+        pool.deposit_count = pool.deposit_count
+            .checked_add(1)
+            .ok_or(StealthError::ArithmeticOverflow)?;
+        new_root = pool.merkle_root;
+        emit_root_checkpoint(&pool, clock.slot);
+    }
+
+    let mut leaf_index = first_index;
+    let mut decoy_leaves = Vec::with_capacity(decoys.len());
+    for (offset, leaf) in batch.iter().enumerate() {
+        let index = first_index + offset as u64;
+        if *leaf == commitment {
+            leaf_index = index;
+        } else {
+            decoy_leaves.push(DecoyLeaf { commitment: *leaf, leaf_index: index });
+        }
+    }
+
+    {
+        let commitment_leaf = &mut ctx.accounts.commitment_leaf;
+        commitment_leaf.commitment = commitment;
+        commitment_leaf.leaf_index = leaf_index;
+        commitment_leaf.timestamp = clock.unix_timestamp;
+        commitment_leaf.encrypted_note = encrypted_note.unwrap_or([0u8; 128]);
+        commitment_leaf.amount_commitment = [0u8; 33];
+        commitment_leaf.range_proof_hash = [0u8; 32];
+        commitment_leaf.slot = clock.slot;
+        commitment_leaf.bump = ctx.bumps.commitment_leaf;
+    }
+
+    {
+        let decoy_record = &mut ctx.accounts.decoy_record;
+        decoy_record.denomination = denomination;
+        decoy_record.decoys = decoy_leaves;
+        decoy_record.timestamp = clock.unix_timestamp;
+        decoy_record.bump = ctx.bumps.decoy_record;
+    }
+
+    msg!("Private deposit with {} decoys", decoys.len());
+    msg!("Denomination: {} SOL", amount / 1_000_000_000);
+    msg!("Leaves: {}..{}", first_index, first_index + batch.len() as u64);
+    msg!("New root: {:?}", new_root);
+
+    Ok(())
+}
+
+/// Decoys must be non-zero, distinct, and never equal the real commitment
+fn validate_decoys(commitment: &[u8; 32], decoys: &[[u8; 32]]) -> Result<()> {
+    for (i, decoy) in decoys.iter().enumerate() {
+        require!(
+            *decoy != [0u8; 32] && decoy != commitment && !decoys[..i].contains(decoy),
+            StealthError::InvalidDecoyConfig
+        );
+    }
+    Ok(())
+}
+
+/// Deterministic Fisher-Yates shuffle seeded by `nonce`
+pub fn shuffle_commitments(commitments: &mut [[u8; 32]], nonce: &[u8; 32]) {
+    for i in (1..commitments.len()).rev() {
+        let hash = hashv(&[nonce, &(i as u64).to_le_bytes()]).to_bytes();
+        let mut word = [0u8; 8];
+        word.copy_from_slice(&hash[..8]);
+        let j = (u64::from_le_bytes(word) % (i as u64 + 1)) as usize;
+        commitments.swap(i, j);
+    }
+}
+
+/// Transfer funds to pool (separate stack frame)
+#[inline(never)]
+fn transfer_to_pool(ctx: &Context<DepositWithDecoys>, amount: u64) -> Result<()> {
+    let cpi_context = CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        system_program::Transfer {
+            from: ctx.accounts.depositor.to_account_info(),
+            to: ctx.accounts.pool.to_account_info(),
+        },
+    );
+    system_program::transfer(cpi_context, amount)
+}
+
+/// Transfer fee if applicable (separate stack frame)
+#[inline(never)]
+fn transfer_fee(ctx: &Context<DepositWithDecoys>, fee: u64) -> Result<()> {
+    if let Some(fee_recipient) = &ctx.accounts.fee_recipient {
+        let fee_cpi = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.depositor.to_account_info(),
+                to: fee_recipient.to_account_info(),
+            },
+        );
+        system_program::transfer(fee_cpi, fee)?;
+    }
+    Ok(())
+}
@@ -16,6 +16,10 @@ use crate::zk::types::{VerificationKey, StoredVerificationKey};
 /// This accommodates VKs with up to 10 IC points
 const MAX_VK_DATA_SIZE: usize = 2048;
 
+/// Maximum size for an updated key (account is reallocated to fit)
+/// This accommodates VKs with up to 120 IC points
+const MAX_UPDATED_VK_DATA_SIZE: usize = 8192;
+
 /// Accounts for initialize_verification_key instruction
 #[derive(Accounts)]
 pub struct InitializeVerificationKey<'info> {
@@ -59,39 +63,56 @@ pub fn initialize_verification_key(
 
     // Store authority
     vk_account.authority = ctx.accounts.authority.key();
+    vk_account.vk_hash = StoredVerificationKey::hash_vk_data(&vk_data);
     vk_account.vk_data = vk_data;
+    vk_account.version = 1;
     vk_account.bump = ctx.bumps.verification_key;
 
-    msg!("Verification key initialized");
+    msg!("Verification key initialized (version 1)");
 
     Ok(())
 }
 
 /// Accounts for updating a verification key
 #[derive(Accounts)]
+#[instruction(vk_data: Vec<u8>)]
 pub struct UpdateVerificationKey<'info> {
     /// Authority that can update the VK (must match stored authority)
     #[account(mut)]
     pub authority: Signer<'info>,
 
     /// The verification key account
+    /// Grows to fit a larger key, never shrinks
     #[account(
         mut,
         seeds = [StoredVerificationKey::SEEDS],
         bump = verification_key.bump,
-        constraint = verification_key.authority == authority.key() @ StealthError::Unauthorized
+        constraint = verification_key.authority == authority.key() @ StealthError::Unauthorized,
+        realloc = verification_key
+            .to_account_info()
+            .data_len()
+            .max(StoredVerificationKey::space(vk_data.len())),
+        realloc::payer = authority,
+        realloc::zero = false,
     )]
     pub verification_key: Account<'info, StoredVerificationKey>,
+
+    pub system_program: Program<'info, System>,
 }
 
-/// Update an existing verification key
+/// Update an existing verification key when the circuit changes
 /// Only the original authority can update
+///
+/// # Arguments
+/// * `vk_data` - Serialized verification key bytes for the new circuit
+/// * `version` - New circuit version, must be greater than the stored one
 pub fn update_verification_key(
     ctx: Context<UpdateVerificationKey>,
     vk_data: Vec<u8>,
+    version: u32,
 ) -> Result<()> {
     require!(
-        vk_data.len() <= MAX_VK_DATA_SIZE,
+        vk_data.len() <= MAX_UPDATED_VK_DATA_SIZE,
         StealthError::VerificationKeyTooLarge
     );
 
@@ -100,13 +121,31 @@ pub fn update_verification_key(
         .map_err(|_| StealthError::DeserializationError)?;
 
     let vk_account = &mut ctx.accounts.verification_key;
+    require!(
+        version > vk_account.version,
+        StealthError::InvalidVerificationKeyVersion
+    );
+
+    let vk_hash = StoredVerificationKey::hash_vk_data(&vk_data);
     vk_account.vk_data = vk_data;
+    vk_account.version = version;
+    vk_account.vk_hash = vk_hash;
+
+    emit!(VerificationKeyUpdated { version, vk_hash });
 
-    msg!("Verification key updated");
+    msg!("Verification key updated to version {}", version);
 
     Ok(())
 }
 
+/// Emitted when the verification key changes, so relayers know to
+/// regenerate proofs against the new circuit
+#[event]
+pub struct VerificationKeyUpdated {
+    pub version: u32,
+    pub vk_hash: [u8; 32],
+}
+
 /// Load verification key from account
 pub fn load_verification_key(vk_data: &[u8]) -> Result<VerificationKey> {
     VerificationKey::try_from_slice(vk_data)
@@ -127,4 +166,19 @@ mod tests {
         assert_eq!(expected, 64 + 128 * 3 + 64 * 10); // 1088 bytes
         assert!(expected <= MAX_VK_DATA_SIZE); // Must fit in allocated space
     }
+
+    #[test]
+    fn test_vk_update_growth_fits_one_realloc() {
+        use anchor_lang::solana_program::entrypoint::MAX_PERMITTED_DATA_INCREASE;
+
+        // Growing from the initial allocation to the largest update is one realloc
+        let initial = 8 + StoredVerificationKey::space(MAX_VK_DATA_SIZE);
+        let largest = StoredVerificationKey::space(MAX_UPDATED_VK_DATA_SIZE);
+        assert!(largest - initial <= MAX_PERMITTED_DATA_INCREASE);
+
+        // Hash identifies the exact key bytes
+        let a = StoredVerificationKey::hash_vk_data(&[1u8; 64]);
+        assert_eq!(a, StoredVerificationKey::hash_vk_data(&[1u8; 64]));
+        assert_ne!(a, StoredVerificationKey::hash_vk_data(&[2u8; 64]));
+    }
 }
@@ -16,6 +16,14 @@ pub mod unified_privacy;
 pub mod denomination_registry;
 pub mod simple_deposit;
 pub mod confidential_transfer;
+pub mod token_deposit;
+pub mod token_withdraw;
+pub mod decoy_deposit;
+pub mod variable_deposit;
+pub mod verification_oracle;
+pub mod authority_transfer;
+pub mod scan_hint;
+pub mod announcement_index;
 
 pub use register::*;
 pub use send::*;
@@ -35,3 +43,11 @@ pub use unified_privacy::*;
 pub use denomination_registry::*;
 pub use simple_deposit::*;
 pub use confidential_transfer::*;
+pub use token_deposit::*;
+pub use token_withdraw::*;
+pub use decoy_deposit::*;
+pub use variable_deposit::*;
+pub use verification_oracle::*;
+pub use authority_transfer::*;
+pub use scan_hint::*;
+pub use announcement_index::*;
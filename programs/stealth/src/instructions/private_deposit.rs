@@ -11,15 +11,16 @@
 
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
-use crate::state::privacy_pool::{PrivacyPool, CommitmentLeaf, PoolConfig, ROOT_HISTORY_SIZE};
-use crate::crypto::merkle::{compute_zero_hashes_poseidon, MERKLE_DEPTH, merkle_hash_2};
+use crate::state::privacy_pool::{PrivacyPool, CommitmentLeaf, CommitmentScheme, PauseKind, PoolConfig, ROOT_HISTORY_SIZE};
+use crate::crypto::merkle::{compute_zero_hashes_poseidon, insert_leaf, ZeroHashes, MERKLE_DEPTH};
 use crate::error::StealthError;
+use crate::util::safe_transfer_lamports;
 
 /// Initialize a fixed-denomination privacy pool
 /// Each pool has a specific denomination (1 SOL, 10 SOL, or 100 SOL)
 /// The denomination is included in the PDA seeds so multiple pools can exist
 #[derive(Accounts)]
-#[instruction(denomination: u64)]
+#[instruction(denomination: u64, commitment_scheme: u8)]
 pub struct InitializePool<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
@@ -50,20 +51,24 @@ pub struct InitializePool<'info> {
 /// Initialize a fixed-denomination pool
 /// Uses pre-computed zero hashes to avoid stack allocation
 /// OPTIMIZATION: Computes zero hashes ONCE and passes to init_filled_subtrees
+///
+/// `commitment_scheme` is a `CommitmentScheme` id, fixed for the pool's life.
 #[inline(never)]
-pub fn initialize_pool(ctx: Context<InitializePool>, denomination: u64) -> Result<()> {
+pub fn initialize_pool(ctx: Context<InitializePool>, denomination: u64, commitment_scheme: u8) -> Result<()> {
     // Validate denomination is one of the allowed values
     require!(
         PrivacyPool::is_valid_denomination(denomination),
         StealthError::InvalidDenomination
     );
+    let scheme = CommitmentScheme::from_id(commitment_scheme)
+        .ok_or(StealthError::InvalidCommitmentScheme)?;
 
     // Load pool with zero-copy (no stack allocation for the struct)
     let mut pool = ctx.accounts.pool.load_init()?;
     let config = &mut ctx.accounts.config;
 
     // OPTIMIZATION: Compute zero hashes ONCE (expensive operation)
-    let zeros = compute_zero_hashes_poseidon();
+    let zeros = compute_zero_hashes_poseidon::<MERKLE_DEPTH>();
 
     pool.authority = ctx.accounts.authority.key();
     pool.denomination = denomination; // FIXED DENOMINATION
@@ -75,6 +80,9 @@ pub fn initialize_pool(ctx: Context<InitializePool>, denomination: u64) -> Resul
     pool.withdrawal_count = 0;
     pool.is_active = true;
     pool.root_history_index = 0;
+    pool.token_mint = Pubkey::default(); // Native SOL pool
+    pool.pending_authority = Pubkey::default();
+    pool.commitment_scheme = scheme.id();
     pool.bump = ctx.bumps.pool;
 
     // Initialize filled subtrees with zero hashes (pass precomputed zeros)
@@ -88,10 +96,23 @@ pub fn initialize_pool(ctx: Context<InitializePool>, denomination: u64) -> Resul
     config.fee_recipient = ctx.accounts.authority.key();
     config.deposits_paused = false;
     config.withdrawals_paused = false;
+    config.min_anonymity_set = PoolConfig::DEFAULT_MIN_ANONYMITY_SET;
+    config.protocol_fee_bps = PoolConfig::DEFAULT_PROTOCOL_FEE_BPS;
+    config.relayer_fee_bps = PoolConfig::DEFAULT_RELAYER_FEE_BPS;
+    config.min_age_slots = PoolConfig::DEFAULT_MIN_AGE_SLOTS;
+    config.pending_authority = Pubkey::default();
     config.bump = ctx.bumps.config;
 
+    emit!(PoolInitialized {
+        pool: ctx.accounts.pool.key(),
+        denomination,
+        token_mint: Pubkey::default(),
+        commitment_scheme: scheme.id(),
+    });
+
     msg!("Fixed-denomination privacy pool initialized");
     msg!("Denomination: {} lamports ({} SOL)", denomination, denomination / 1_000_000_000);
+    msg!("Commitment scheme: {:?}", scheme);
     msg!("Initial root: {:?}", pool.merkle_root);
 
     Ok(())
@@ -101,16 +122,14 @@ pub fn initialize_pool(ctx: Context<InitializePool>, denomination: u64) -> Resul
 /// DEPRECATED: Use init_filled_subtrees_with_zeros to avoid recomputing zeros
 #[inline(never)]
 fn init_filled_subtrees(pool: &mut PrivacyPool) {
-    let zeros = compute_zero_hashes_poseidon();
+    let zeros = compute_zero_hashes_poseidon::<MERKLE_DEPTH>();
     init_filled_subtrees_with_zeros(pool, &zeros);
 }
 
 /// Initialize filled subtrees with pre-computed zero hashes (saves ~50% compute)
 #[inline(never)]
-fn init_filled_subtrees_with_zeros(pool: &mut PrivacyPool, zeros: &[[u8; 32]; MERKLE_DEPTH + 1]) {
-    for i in 0..MERKLE_DEPTH {
-        pool.filled_subtrees[i] = zeros[i];
-    }
+pub(crate) fn init_filled_subtrees_with_zeros(pool: &mut PrivacyPool, zeros: &ZeroHashes<MERKLE_DEPTH>) {
+    pool.filled_subtrees = zeros.levels;
     // Initialize root history
     pool.root_history = [[0u8; 32]; ROOT_HISTORY_SIZE];
 }
@@ -139,8 +158,11 @@ pub struct PrivateDeposit<'info> {
     pub config: Account<'info, PoolConfig>,
 
     /// Commitment leaf account (includes denomination for uniqueness across pools)
+    ///
+    /// `init_if_needed` so an existing leaf loads instead of failing inside the
+    /// system program; `private_deposit` rejects it as `DuplicateCommitment`.
     #[account(
-        init,
+        init_if_needed,
         payer = depositor,
         space = CommitmentLeaf::SIZE,
         seeds = [CommitmentLeaf::SEED, &denomination.to_le_bytes(), commitment.as_ref()],
@@ -187,6 +209,11 @@ pub fn private_deposit(
     let config = &ctx.accounts.config;
     let clock = Clock::get()?;
 
+    require!(
+        !ctx.accounts.commitment_leaf.is_recorded(),
+        StealthError::DuplicateCommitment
+    );
+
     // Check pool is active and verify denomination matches (with zero-copy load)
     let amount = {
         let pool = ctx.accounts.pool.load()?;
@@ -219,6 +246,7 @@ pub fn private_deposit(
     // Insert commitment into Merkle tree (separate stack frame)
     let leaf_index;
     let new_root;
+    let commitment_scheme;
     {
         let mut pool = ctx.accounts.pool.load_mut()?;
         leaf_index = insert_commitment_to_tree_zc(&mut pool, commitment)?;
@@ -229,6 +257,8 @@ pub fn private_deposit(
             .checked_add(1)
             .ok_or(StealthError::ArithmeticOverflow)?;
         new_root = pool.merkle_root;
+        commitment_scheme = pool.commitment_scheme;
+        emit_root_checkpoint(&pool, clock.slot);
     }
 
     // Store commitment leaf
@@ -242,6 +272,7 @@ pub fn private_deposit(
         // Amount commitment not needed - denomination is fixed and public
         commitment_leaf.amount_commitment = [0u8; 33];
         commitment_leaf.range_proof_hash = [0u8; 32];
+        commitment_leaf.slot = clock.slot;
         commitment_leaf.bump = ctx.bumps.commitment_leaf;
     }
 
@@ -250,6 +281,16 @@ pub fn private_deposit(
     msg!("Leaf index: {}", leaf_index);
     msg!("New root: {:?}", new_root);
 
+    emit!(PrivateDepositEvent {
+        pool: ctx.accounts.pool.key(),
+        commitment,
+        denomination,
+        leaf_index,
+        new_root,
+        commitment_scheme,
+        timestamp: clock.unix_timestamp,
+    });
+
     Ok(())
 }
 
@@ -282,45 +323,66 @@ fn transfer_fee_zc(ctx: &Context<PrivateDeposit>, fee: u64) -> Result<()> {
     Ok(())
 }
 
+/// Ensure `count` more leaves fit in a tree of `depth` levels
+///
+/// Inserting past 2^depth leaves would wrap the path bits and corrupt the root.
+pub(crate) fn ensure_tree_capacity(next_leaf_index: u64, count: u64, depth: usize) -> Result<()> {
+    let capacity = 2u64.pow(depth as u32);
+    require!(
+        matches!(next_leaf_index.checked_add(count), Some(end) if end <= capacity),
+        StealthError::TreeFull
+    );
+    Ok(())
+}
+
+/// Reject a batch that repeats a commitment
+///
+/// Batch deposits don't create per-commitment leaf PDAs, so nothing else
+/// stops the same commitment landing in the tree twice.
+pub(crate) fn ensure_unique_commitments(commitments: &[[u8; 32]]) -> Result<()> {
+    for (i, commitment) in commitments.iter().enumerate() {
+        require!(
+            !commitments[..i].contains(commitment),
+            StealthError::DuplicateCommitment
+        );
+    }
+    Ok(())
+}
+
 /// Insert commitment into Merkle tree for zero-copy account (separate stack frame)
 #[inline(never)]
-fn insert_commitment_to_tree_zc(pool: &mut PrivacyPool, commitment: [u8; 32]) -> Result<u64> {
+pub(crate) fn insert_commitment_to_tree_zc(pool: &mut PrivacyPool, commitment: [u8; 32]) -> Result<u64> {
     let leaf_index = pool.next_leaf_index;
+    ensure_tree_capacity(leaf_index, 1, MERKLE_DEPTH)?;
 
     // Save current root to history
     pool.save_root_to_history();
 
     // Update Merkle tree using static zero hashes
-    let new_root = compute_new_root_zc(pool, commitment, leaf_index);
-
-    pool.merkle_root = new_root;
+    pool.merkle_root = insert_leaf::<MERKLE_DEPTH>(&mut pool.filled_subtrees, leaf_index, commitment)?;
     pool.next_leaf_index += 1;
 
     Ok(leaf_index)
 }
 
-/// Compute new Merkle root after insertion for zero-copy account (separate stack frame)
-/// Uses Poseidon hash for ZK circuit compatibility
+/// Insert several commitments in order, returning the first leaf index
+///
+/// The pre-batch root is saved to history once, so a batch doesn't flush the
+/// root history window that in-flight withdrawal proofs rely on.
 #[inline(never)]
-fn compute_new_root_zc(pool: &mut PrivacyPool, commitment: [u8; 32], leaf_index: u64) -> [u8; 32] {
-    let zeros = compute_zero_hashes_poseidon();
-    let mut current_index = leaf_index;
-    let mut current_hash = commitment;
-
-    for i in 0..MERKLE_DEPTH {
-        let is_left = current_index % 2 == 0;
-
-        if is_left {
-            pool.filled_subtrees[i] = current_hash;
-            current_hash = merkle_hash_2(&current_hash, &zeros[i]);
-        } else {
-            current_hash = merkle_hash_2(&pool.filled_subtrees[i], &current_hash);
-        }
-
-        current_index /= 2;
+pub(crate) fn insert_commitments_batch_zc(pool: &mut PrivacyPool, commitments: &[[u8; 32]]) -> Result<u64> {
+    let first_index = pool.next_leaf_index;
+    ensure_tree_capacity(first_index, commitments.len() as u64, MERKLE_DEPTH)?;
+
+    pool.save_root_to_history();
+
+    for commitment in commitments {
+        let leaf_index = pool.next_leaf_index;
+        pool.merkle_root = insert_leaf::<MERKLE_DEPTH>(&mut pool.filled_subtrees, leaf_index, *commitment)?;
+        pool.next_leaf_index += 1;
     }
 
-    current_hash
+    Ok(first_index)
 }
 
 /// Batch deposit - deposit multiple commitments at once
@@ -355,6 +417,7 @@ pub fn batch_deposit(
     total_amount: u64,
 ) -> Result<()> {
     let config = &ctx.accounts.config;
+    let clock = Clock::get()?;
 
     // Check pool is active (with zero-copy load)
     {
@@ -366,6 +429,7 @@ pub fn batch_deposit(
         !commitments.is_empty() && commitments.len() <= 10,
         StealthError::InvalidBatchSize
     );
+    ensure_unique_commitments(&commitments)?;
 
     // Calculate fee
     let fee = (total_amount as u128)
@@ -385,7 +449,7 @@ pub fn batch_deposit(
     let new_root;
     {
         let mut pool = ctx.accounts.pool.load_mut()?;
-        batch_insert_commitments_zc(&mut pool, commitments)?;
+        batch_insert_commitments_zc(&mut pool, ctx.accounts.pool.key(), commitments, clock.unix_timestamp)?;
         pool.total_deposited = pool.total_deposited
             .checked_add(deposit_amount)
             .ok_or(StealthError::ArithmeticOverflow)?;
@@ -393,6 +457,7 @@ pub fn batch_deposit(
             .checked_add(num_commitments as u64)
             .ok_or(StealthError::ArithmeticOverflow)?;
         new_root = pool.merkle_root;
+        emit_root_checkpoint(&pool, clock.slot);
     }
 
     msg!("Batch deposit of {} commitments successful", num_commitments);
@@ -416,17 +481,34 @@ fn batch_transfer_to_pool_zc(ctx: &Context<BatchDeposit>, amount: u64) -> Result
 
 /// Insert all commitments into the Merkle tree for zero-copy (separate stack frame)
 #[inline(never)]
-fn batch_insert_commitments_zc(pool: &mut PrivacyPool, commitments: Vec<[u8; 32]>) -> Result<()> {
+fn batch_insert_commitments_zc(
+    pool: &mut PrivacyPool,
+    pool_key: Pubkey,
+    commitments: Vec<[u8; 32]>,
+    timestamp: i64,
+) -> Result<()> {
+    ensure_tree_capacity(pool.next_leaf_index, commitments.len() as u64, MERKLE_DEPTH)?;
+
     // Save current root
     pool.save_root_to_history();
 
     // Insert each commitment
     for commitment in commitments.iter() {
         let leaf_index = pool.next_leaf_index;
-        let new_root = compute_new_root_zc(pool, *commitment, leaf_index);
+        let new_root = insert_leaf::<MERKLE_DEPTH>(&mut pool.filled_subtrees, leaf_index, *commitment)?;
         pool.merkle_root = new_root;
         pool.next_leaf_index += 1;
         msg!("Inserted commitment at index {}", leaf_index);
+
+        emit!(PrivateDepositEvent {
+            pool: pool_key,
+            commitment: *commitment,
+            denomination: pool.denomination,
+            leaf_index,
+            new_root,
+            commitment_scheme: pool.commitment_scheme,
+            timestamp,
+        });
     }
 
     Ok(())
@@ -475,11 +557,7 @@ pub fn close_pool(ctx: Context<ClosePool>, denomination: u64) -> Result<()> {
 
     // Transfer lamports from pool to authority
     let pool_lamports = pool_info.lamports();
-    **pool_info.try_borrow_mut_lamports()? = 0;
-    **authority_info.try_borrow_mut_lamports()? = authority_info
-        .lamports()
-        .checked_add(pool_lamports)
-        .ok_or(StealthError::MathOverflow)?;
+    safe_transfer_lamports(&pool_info, &authority_info, pool_lamports)?;
 
     // Zero out the data to mark as closed
     pool_info.try_borrow_mut_data()?.fill(0);
@@ -489,3 +567,166 @@ pub fn close_pool(ctx: Context<ClosePool>, denomination: u64) -> Result<()> {
 
     Ok(())
 }
+
+/// Update pool config (ADMIN)
+#[derive(Accounts)]
+#[instruction(denomination: u64)]
+pub struct UpdatePoolConfig<'info> {
+    #[account(
+        constraint = authority.key() == config.authority @ StealthError::Unauthorized,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PoolConfig::SEED, &denomination.to_le_bytes()],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, PoolConfig>,
+}
+
+/// Update the minimum anonymity set required before withdrawals
+pub fn update_pool_config(
+    ctx: Context<UpdatePoolConfig>,
+    denomination: u64,
+    min_anonymity_set: u64,
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.min_anonymity_set = min_anonymity_set;
+
+    msg!("Pool {} min anonymity set: {}", denomination, min_anonymity_set);
+
+    Ok(())
+}
+
+/// Set how withdrawal relayer fees are split between protocol and relayer
+///
+/// Each share is in bps of the relayer fee; together they may not exceed
+/// 10000. Any unallocated share is left with the withdrawal recipient.
+pub fn update_fee_split(
+    ctx: Context<UpdatePoolConfig>,
+    denomination: u64,
+    protocol_fee_bps: u16,
+    relayer_fee_bps: u16,
+) -> Result<()> {
+    require!(
+        PoolConfig::is_valid_fee_split(protocol_fee_bps, relayer_fee_bps),
+        StealthError::InvalidFeeSplit
+    );
+
+    let config = &mut ctx.accounts.config;
+    config.protocol_fee_bps = protocol_fee_bps;
+    config.relayer_fee_bps = relayer_fee_bps;
+
+    msg!(
+        "Pool {} fee split: protocol {} bps, relayer {} bps",
+        denomination,
+        protocol_fee_bps,
+        relayer_fee_bps
+    );
+
+    Ok(())
+}
+
+/// Set the minimum slots a note must sit before it can be withdrawn
+///
+/// Withdrawals in the same slot (or a few slots after) their deposit are
+/// linkable by timing alone; 0 disables the check.
+pub fn update_min_age_slots(
+    ctx: Context<UpdatePoolConfig>,
+    denomination: u64,
+    min_age_slots: u64,
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.min_age_slots = min_age_slots;
+
+    msg!("Pool {} min note age: {} slots", denomination, min_age_slots);
+
+    Ok(())
+}
+
+/// Freeze or resume deposits into a pool (incident response)
+pub fn set_deposits_paused(ctx: Context<UpdatePoolConfig>, denomination: u64, paused: bool) -> Result<()> {
+    set_pool_paused(ctx, denomination, PauseKind::Deposits, paused)
+}
+
+/// Freeze or resume withdrawals from a pool (incident response)
+pub fn set_withdrawals_paused(ctx: Context<UpdatePoolConfig>, denomination: u64, paused: bool) -> Result<()> {
+    set_pool_paused(ctx, denomination, PauseKind::Withdrawals, paused)
+}
+
+fn set_pool_paused(
+    ctx: Context<UpdatePoolConfig>,
+    denomination: u64,
+    kind: PauseKind,
+    paused: bool,
+) -> Result<()> {
+    ctx.accounts.config.set_paused(kind, paused);
+
+    emit!(PoolPauseChanged { denomination, kind, paused });
+
+    msg!("Pool {} {:?} paused: {}", denomination, kind, paused);
+
+    Ok(())
+}
+
+// ============================================================================
+// EVENTS
+// ============================================================================
+
+/// Emit the pool's root after an instruction's last tree insert
+pub(crate) fn emit_root_checkpoint(pool: &PrivacyPool, slot: u64) {
+    emit!(MerkleRootCheckpoint {
+        denomination: pool.denomination,
+        root: pool.merkle_root,
+        next_leaf_index: pool.next_leaf_index,
+        slot,
+    });
+}
+
+/// Emitted for every leaf inserted by `private_deposit` / `batch_deposit`
+///
+/// Lets indexers rebuild the tree and root history without parsing logs.
+#[event]
+pub struct PrivateDepositEvent {
+    pub pool: Pubkey,
+    pub commitment: [u8; 32],
+    pub denomination: u64,
+    pub leaf_index: u64,
+    pub new_root: [u8; 32],
+    /// `CommitmentScheme` id the commitment was made under
+    pub commitment_scheme: u8,
+    pub timestamp: i64,
+}
+
+/// Emitted when a pool is created, so clients learn its commitment scheme
+#[event]
+pub struct PoolInitialized {
+    pub pool: Pubkey,
+    pub denomination: u64,
+    /// `Pubkey::default()` for native SOL pools
+    pub token_mint: Pubkey,
+    /// `CommitmentScheme` id deposits must use
+    pub commitment_scheme: u8,
+}
+
+/// Root after every instruction that changes the tree
+///
+/// One per instruction, emitted after its last insert, so a light client can
+/// follow the root without replaying leaves and check proofs against a recent
+/// known-good root. Withdrawals don't touch the tree and emit none.
+#[event]
+pub struct MerkleRootCheckpoint {
+    pub denomination: u64,
+    pub root: [u8; 32],
+    pub next_leaf_index: u64,
+    pub slot: u64,
+}
+
+/// Emitted when the authority pauses or resumes deposits or withdrawals
+#[event]
+pub struct PoolPauseChanged {
+    pub denomination: u64,
+    pub kind: PauseKind,
+    pub paused: bool,
+}
@@ -24,6 +24,18 @@
 //! 5. On-chain program verifies ZK proof and stealth commitment
 //! 6. Funds sent to stealth address, announcement created for scanning
 //!
+//! TRUSTLESS VERIFICATION:
+//! When the stored Groth16 verification key account is passed,
+//! `private_withdraw` verifies the proof on-chain instead (as
+//! `verified_withdraw` does) and ignores any attestation, so integrators can
+//! drop the oracle without switching instructions.
+//!
+//! NON-STEALTH VARIANT:
+//! `private_withdraw_to_address` pays a plain, known recipient instead (e.g. an
+//! exchange treasury). It keeps the ZK proof and nullifier checks but skips the
+//! stealth commitment and announcement, so the recipient is PUBLIC. Prefer the
+//! stealth path unless the recipient is already a known identity.
+//!
 //! Stack Optimization:
 //! - Uses computed Poseidon zero hashes
 //! - Functions marked #[inline(never)] to prevent stack blowup
@@ -32,10 +44,17 @@
 use anchor_lang::prelude::*;
 use crate::state::privacy_pool::{PrivacyPool, NullifierRecord, PoolConfig};
 use crate::state::announcement::{StealthAnnouncement, compute_commitment};
-use crate::crypto::merkle::{compute_zero_hashes_poseidon, MERKLE_DEPTH, merkle_hash_2};
+use crate::state::announcement_log::{AnnouncementLog, AnnouncementEntry};
+use crate::state::announcement_index::AnnouncementIndex;
+use crate::state::relayer::Relayer;
+use crate::crypto::merkle::{compute_root_from_path, frontier_root, insert_leaf, MERKLE_DEPTH};
 use crate::crypto::validate_curve_point;
 use crate::error::StealthError;
-use crate::zk::verifier::{OracleAttestation, verify_proof_with_sysvar};
+use crate::util::{ensure_rent_exempt_payout, safe_transfer_lamports};
+use crate::zk::verifier::{OracleAttestation, VerificationOracle, verify_proof_with_oracle};
+use crate::zk::{Groth16Proof, StoredVerificationKey, verify_groth16};
+use crate::instructions::verified_withdraw::{amount_to_field, load_vk, pubkey_to_field};
+use crate::instructions::announcement_index::index_announcement;
 
 /// ZK proof for withdrawal (from Noir circuit)
 #[derive(Clone, AnchorSerialize, AnchorDeserialize)]
@@ -80,16 +99,65 @@ pub struct WithdrawPublicInputs {
     /// Proves the stealth address was correctly derived from the meta-address
     pub stealth_commitment: [u8; 32],
     // Amount removed - it's implicit from pool denomination
+
+    /// Slot the note was deposited in (its `CommitmentLeaf::slot`)
+    /// Attested alongside the rest so it can't be backdated to skip
+    /// `PoolConfig::min_age_slots`
+    pub deposit_slot: u64,
+}
+
+impl WithdrawPublicInputs {
+    /// Bytes the proof and attestation are verified against:
+    /// root || nullifier_hash || stealth_address || R || S || B || commitment
+    /// || deposit_slot (u64 LE)
+    ///
+    /// The commitment is included so an attestation can't be replayed with a
+    /// different stealth address: changing either changes the inputs hash.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(232);
+        bytes.extend_from_slice(&self.merkle_root);
+        bytes.extend_from_slice(&self.nullifier_hash);
+        bytes.extend_from_slice(self.stealth_address.as_ref());
+        bytes.extend_from_slice(&self.ephemeral_pubkey);
+        bytes.extend_from_slice(&self.scan_pubkey);
+        bytes.extend_from_slice(&self.spend_pubkey);
+        bytes.extend_from_slice(&self.stealth_commitment);
+        bytes.extend_from_slice(&self.deposit_slot.to_le_bytes());
+        bytes
+    }
+
+    /// Groth16 public inputs in the withdraw circuit's order:
+    /// merkleRoot, nullifierHash, recipient, amount
+    ///
+    /// The recipient is the stealth address reduced into the scalar field
+    /// (as `verified_withdraw` expects it) and `amount` is the pool
+    /// denomination. The circuit doesn't take the meta-address keys,
+    /// commitment or deposit_slot, so on this path the proof doesn't bind
+    /// them; the stealth commitment is still checked on-chain.
+    pub fn to_field_elements(&self, amount: u64) -> [[u8; 32]; 4] {
+        [
+            self.merkle_root,
+            self.nullifier_hash,
+            pubkey_to_field(&self.stealth_address),
+            amount_to_field(amount),
+        ]
+    }
 }
 
 /// Private withdrawal accounts for fixed-denomination pools with stealth addresses
 #[derive(Accounts)]
 #[instruction(denomination: u64, proof: WithdrawProof)]
 pub struct PrivateWithdraw<'info> {
-    /// Anyone can submit a withdrawal proof (relayer support)
+    /// Anyone can submit a withdrawal proof (relayer support); earns the relayer fee
     #[account(mut)]
     pub relayer: Signer<'info>,
 
+    /// Pays rent for the nullifier and announcement accounts. Pass the
+    /// relayer again when it pays its own way; a sponsor can sign here
+    /// instead so the relayer only collects its fee.
+    #[account(mut)]
+    pub fee_payer: Signer<'info>,
+
     /// Pool PDA includes denomination in seeds
     #[account(
         mut,
@@ -110,7 +178,7 @@ pub struct PrivateWithdraw<'info> {
     /// Includes denomination to separate nullifiers across pools
     #[account(
         init,
-        payer = relayer,
+        payer = fee_payer,
         space = NullifierRecord::SIZE,
         seeds = [NullifierRecord::SEED, &denomination.to_le_bytes(), proof.public_inputs.nullifier_hash.as_ref()],
         bump,
@@ -130,7 +198,7 @@ pub struct PrivateWithdraw<'info> {
     /// Recipients scan these to detect withdrawals addressed to them
     #[account(
         init,
-        payer = relayer,
+        payer = fee_payer,
         space = StealthAnnouncement::SIZE,
         seeds = [StealthAnnouncement::SEED, proof.public_inputs.ephemeral_pubkey.as_ref()],
         bump,
@@ -142,12 +210,60 @@ pub struct PrivateWithdraw<'info> {
     #[account(mut)]
     pub relayer_fee_recipient: Option<AccountInfo<'info>>,
 
+    /// Verification oracle the attestation is checked against
+    #[account(
+        seeds = [VerificationOracle::SEED],
+        bump = oracle.bump,
+    )]
+    pub oracle: Account<'info, VerificationOracle>,
+
     /// Instructions sysvar for Ed25519 signature verification (production mode)
     /// CHECK: Validated by address constraint
     #[account(address = anchor_lang::solana_program::sysvar::instructions::id())]
     pub instructions_sysvar: AccountInfo<'info>,
 
     pub system_program: Program<'info, System>,
+
+    /// Optional: announcement log indexing this withdrawal by slot
+    #[account(
+        mut,
+        seeds = [AnnouncementLog::SEED, &announcement_log.log_id.to_le_bytes()],
+        bump = announcement_log.bump,
+        constraint = announcement_log.can_add_entry() @ StealthError::LogNotActive,
+    )]
+    pub announcement_log: Option<Account<'info, AnnouncementLog>>,
+
+    /// Protocol's cut of the relayer fee (required when `protocol_fee_bps` > 0)
+    /// CHECK: Validated against config
+    #[account(
+        mut,
+        constraint = protocol_fee_recipient.key() == config.fee_recipient @ StealthError::InvalidFeeRecipient,
+    )]
+    pub protocol_fee_recipient: Option<AccountInfo<'info>>,
+
+    /// Optional: the signer's registered relayer account, credited on success
+    #[account(
+        mut,
+        seeds = [Relayer::SEED, relayer.key().as_ref()],
+        bump = relayer_account.bump,
+    )]
+    pub relayer_account: Option<Account<'info, Relayer>>,
+
+    /// Optional: stored Groth16 verification key. When passed, the proof is
+    /// verified on-chain and the oracle attestation is not consulted.
+    #[account(
+        seeds = [StoredVerificationKey::SEEDS],
+        bump = verification_key.bump,
+    )]
+    pub verification_key: Option<Account<'info, StoredVerificationKey>>,
+
+    /// Optional: index of announcements in the current slot bucket
+    #[account(
+        mut,
+        seeds = [AnnouncementIndex::SEED, &announcement_index.bucket.to_le_bytes()],
+        bump = announcement_index.bump,
+    )]
+    pub announcement_index: Option<Account<'info, AnnouncementIndex>>,
 }
 
 /// Withdraw funds privately using a ZK proof from a FIXED-DENOMINATION pool
@@ -172,26 +288,29 @@ pub fn private_withdraw(
     let clock = Clock::get()?;
 
     // 1. Verify pool is active, denomination matches, and Merkle root is valid
-    let amount = {
-        let pool = ctx.accounts.pool.load()?;
-        require!(pool.is_active, StealthError::PoolNotActive);
-        require!(
-            pool.denomination == denomination,
-            StealthError::AmountMustMatchDenomination
-        );
-        require!(
-            pool.is_valid_root(&proof.public_inputs.merkle_root),
-            StealthError::InvalidMerkleRoot
-        );
-        pool.denomination // Amount is the pool's fixed denomination
-    };
+    let amount = check_withdrawal_pool(
+        &ctx.accounts.pool,
+        &ctx.accounts.config,
+        denomination,
+        &proof.public_inputs.merkle_root,
+        proof.public_inputs.deposit_slot,
+        clock.slot,
+    )?;
 
     // 2. Verify the stealth address commitment
     // This proves the stealth address was correctly derived from the meta-address
     verify_stealth_commitment(&proof.public_inputs)?;
 
-    // 3. Verify the ZK proof (dev: skipped, production: Oracle attestation + Ed25519 introspection)
-    verify_zk_proof(&proof, &ctx.accounts.instructions_sysvar)?;
+    // 3. Verify the ZK proof: on-chain Groth16 when the verification key is
+    // passed, otherwise the oracle path (dev: skipped, production: attestation
+    // + Ed25519 introspection)
+    verify_withdraw_proof(
+        &proof,
+        amount,
+        &ctx.accounts.instructions_sysvar,
+        &ctx.accounts.oracle,
+        ctx.accounts.verification_key.as_deref(),
+    )?;
 
     // 4. Mark nullifier as used (prevents double-spend)
     mark_nullifier_used(
@@ -202,7 +321,21 @@ pub fn private_withdraw(
     );
 
     // 5. Transfer the fixed denomination amount to stealth address
-    transfer_withdrawal_funds_zc(&ctx, amount, relayer_fee)?;
+    let relayer = ctx.accounts.relayer.to_account_info();
+    let fees = FeePayout::new(
+        &ctx.accounts.config,
+        amount,
+        relayer_fee,
+        &relayer,
+        ctx.accounts.relayer_fee_recipient.as_ref(),
+        ctx.accounts.protocol_fee_recipient.as_ref(),
+    )?;
+    transfer_withdrawal_funds_zc(
+        &ctx.accounts.pool.to_account_info(),
+        &ctx.accounts.stealth_address,
+        amount,
+        &fees,
+    )?;
 
     // 6. Create announcement for recipient scanning
     create_stealth_announcement(
@@ -213,16 +346,22 @@ pub fn private_withdraw(
         clock.unix_timestamp,
         ctx.bumps.announcement,
     );
+    let announcement = ctx.accounts.announcement.key();
+    index_announcement(ctx.accounts.announcement_index.as_mut(), announcement, clock.slot)?;
+    if let Some(log) = ctx.accounts.announcement_log.as_mut() {
+        log.record(AnnouncementEntry {
+            ephemeral_pubkey: proof.public_inputs.ephemeral_pubkey,
+            stealth_address: proof.public_inputs.stealth_address,
+            commitment: proof.public_inputs.stealth_commitment,
+            slot: clock.slot,
+            amount_hint: AnnouncementEntry::amount_to_hint(amount),
+        });
+    }
 
     // 7. Update pool stats
-    {
-        let mut pool = ctx.accounts.pool.load_mut()?;
-        pool.total_withdrawn = pool.total_withdrawn
-            .checked_add(amount)
-            .ok_or(StealthError::ArithmeticOverflow)?;
-        pool.withdrawal_count = pool.withdrawal_count
-            .checked_add(1)
-            .ok_or(StealthError::ArithmeticOverflow)?;
+    record_withdrawal(&ctx.accounts.pool, amount)?;
+    if let Some(relayer_account) = ctx.accounts.relayer_account.as_mut() {
+        relayer_account.record_relayed(clock.slot, clock.unix_timestamp);
     }
 
     msg!("Private withdrawal with stealth address");
@@ -233,6 +372,254 @@ pub fn private_withdraw(
     Ok(())
 }
 
+/// ZK proof for a withdrawal to a plain (non-stealth) address
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct AddressWithdrawProof {
+    /// The proof bytes (Noir/Barretenberg format)
+    pub proof: Vec<u8>,
+
+    /// Public inputs to the circuit
+    pub public_inputs: AddressWithdrawPublicInputs,
+
+    /// Oracle attestation (required in production mode)
+    pub attestation: Option<OracleAttestation>,
+}
+
+/// Public inputs for a withdrawal to a plain address
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct AddressWithdrawPublicInputs {
+    /// Merkle root the proof was generated against
+    pub merkle_root: [u8; 32],
+
+    /// Nullifier hash (prevents double-spend)
+    pub nullifier_hash: [u8; 32],
+
+    /// Recipient receiving the funds, bound by the proof so a relayer
+    /// can't redirect them
+    pub recipient: Pubkey,
+
+    /// Slot the note was deposited in (its `CommitmentLeaf::slot`), checked
+    /// against `PoolConfig::min_age_slots`
+    pub deposit_slot: u64,
+}
+
+impl AddressWithdrawPublicInputs {
+    /// Bytes the proof is verified against:
+    /// root || nullifier_hash || recipient || deposit_slot (u64 LE)
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(104);
+        bytes.extend_from_slice(&self.merkle_root);
+        bytes.extend_from_slice(&self.nullifier_hash);
+        bytes.extend_from_slice(self.recipient.as_ref());
+        bytes.extend_from_slice(&self.deposit_slot.to_le_bytes());
+        bytes
+    }
+
+    /// Groth16 public inputs in the withdraw circuit's order, as in
+    /// `WithdrawPublicInputs::to_field_elements` with the recipient in place
+    /// of the stealth address (deposit_slot isn't bound on this path either)
+    pub fn to_field_elements(&self, amount: u64) -> [[u8; 32]; 4] {
+        [
+            self.merkle_root,
+            self.nullifier_hash,
+            pubkey_to_field(&self.recipient),
+            amount_to_field(amount),
+        ]
+    }
+}
+
+/// Withdrawal accounts for paying a plain recipient (no stealth address)
+#[derive(Accounts)]
+#[instruction(denomination: u64, proof: AddressWithdrawProof)]
+pub struct PrivateWithdrawToAddress<'info> {
+    /// Anyone can submit a withdrawal proof (relayer support)
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    /// Pool PDA includes denomination in seeds
+    #[account(
+        mut,
+        seeds = [PrivacyPool::SEED, &denomination.to_le_bytes()],
+        bump,
+    )]
+    pub pool: AccountLoader<'info, PrivacyPool>,
+
+    /// Config PDA includes denomination in seeds
+    #[account(
+        seeds = [PoolConfig::SEED, &denomination.to_le_bytes()],
+        bump = config.bump,
+        constraint = !config.withdrawals_paused @ StealthError::WithdrawalsPaused,
+    )]
+    pub config: Account<'info, PoolConfig>,
+
+    /// Nullifier record, shared with `private_withdraw` so a note can only
+    /// be spent once across both paths
+    #[account(
+        init,
+        payer = relayer,
+        space = NullifierRecord::SIZE,
+        seeds = [NullifierRecord::SEED, &denomination.to_le_bytes(), proof.public_inputs.nullifier_hash.as_ref()],
+        bump,
+    )]
+    pub nullifier: Account<'info, NullifierRecord>,
+
+    /// Recipient of the withdrawal
+    /// CHECK: Bound by the proof's public inputs
+    #[account(
+        mut,
+        constraint = recipient.key() == proof.public_inputs.recipient @ StealthError::InvalidRecipient,
+    )]
+    pub recipient: AccountInfo<'info>,
+
+    /// Optional: relayer fee recipient
+    /// CHECK: Relayer can set their own fee recipient
+    #[account(mut)]
+    pub relayer_fee_recipient: Option<AccountInfo<'info>>,
+
+    /// Verification oracle the attestation is checked against
+    #[account(
+        seeds = [VerificationOracle::SEED],
+        bump = oracle.bump,
+    )]
+    pub oracle: Account<'info, VerificationOracle>,
+
+    /// Instructions sysvar for Ed25519 signature verification (production mode)
+    /// CHECK: Validated by address constraint
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::id())]
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Protocol's cut of the relayer fee (required when `protocol_fee_bps` > 0)
+    /// CHECK: Validated against config
+    #[account(
+        mut,
+        constraint = protocol_fee_recipient.key() == config.fee_recipient @ StealthError::InvalidFeeRecipient,
+    )]
+    pub protocol_fee_recipient: Option<AccountInfo<'info>>,
+}
+
+/// Withdraw from a FIXED-DENOMINATION pool straight to a known recipient
+///
+/// For integrators (e.g. exchanges) that withdraw to a treasury and don't
+/// want stealth derivation. The ZK proof, nullifier, anonymity set and root
+/// checks are the same as `private_withdraw`; no stealth commitment is
+/// checked and no announcement is created.
+///
+/// PRIVACY PROPERTIES:
+/// - Amount: HIDDEN (fixed denomination)
+/// - Deposit↔Withdrawal link: HIDDEN (ZK proof)
+/// - Recipient identity: PUBLIC (the recipient is visible on-chain)
+#[inline(never)]
+pub fn private_withdraw_to_address(
+    ctx: Context<PrivateWithdrawToAddress>,
+    denomination: u64,
+    proof: AddressWithdrawProof,
+    relayer_fee: u64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+
+    // 1. Verify pool is active, denomination matches, and Merkle root is valid
+    let amount = check_withdrawal_pool(
+        &ctx.accounts.pool,
+        &ctx.accounts.config,
+        denomination,
+        &proof.public_inputs.merkle_root,
+        proof.public_inputs.deposit_slot,
+        clock.slot,
+    )?;
+
+    // 2. Verify the ZK proof (binds the recipient) against the oracle
+    verify_proof_with_oracle(
+        &proof.proof,
+        &proof.public_inputs.to_bytes(),
+        proof.attestation.as_ref(),
+        &ctx.accounts.instructions_sysvar,
+        &ctx.accounts.oracle,
+    )?;
+    msg!("ZK proof verification successful");
+
+    // 3. Mark nullifier as used (prevents double-spend)
+    mark_nullifier_used(
+        &mut ctx.accounts.nullifier,
+        &proof.public_inputs.nullifier_hash,
+        clock.unix_timestamp,
+        ctx.bumps.nullifier,
+    );
+
+    // 4. Transfer the fixed denomination amount to the recipient
+    let relayer = ctx.accounts.relayer.to_account_info();
+    let fees = FeePayout::new(
+        &ctx.accounts.config,
+        amount,
+        relayer_fee,
+        &relayer,
+        ctx.accounts.relayer_fee_recipient.as_ref(),
+        ctx.accounts.protocol_fee_recipient.as_ref(),
+    )?;
+    transfer_withdrawal_funds_zc(
+        &ctx.accounts.pool.to_account_info(),
+        &ctx.accounts.recipient,
+        amount,
+        &fees,
+    )?;
+
+    // 5. Update pool stats
+    record_withdrawal(&ctx.accounts.pool, amount)?;
+
+    msg!("Private withdrawal to public address (recipient not hidden)");
+    msg!("Denomination: {} SOL", amount / 1_000_000_000);
+    msg!("Recipient: {}", ctx.accounts.recipient.key());
+
+    Ok(())
+}
+
+/// Check the pool can pay out a withdrawal against `merkle_root` for a note
+/// deposited at `deposit_slot`, returning the amount (the pool's fixed
+/// denomination)
+#[inline(never)]
+pub(crate) fn check_withdrawal_pool(
+    pool: &AccountLoader<PrivacyPool>,
+    config: &PoolConfig,
+    denomination: u64,
+    merkle_root: &[u8; 32],
+    deposit_slot: u64,
+    current_slot: u64,
+) -> Result<u64> {
+    let pool = pool.load()?;
+    require!(pool.is_active, StealthError::PoolNotActive);
+    require!(
+        pool.denomination == denomination,
+        StealthError::AmountMustMatchDenomination
+    );
+    require!(
+        config.anonymity_set_reached(pool.deposit_count),
+        StealthError::AnonymitySetTooSmall
+    );
+    require!(
+        config.note_old_enough(deposit_slot, current_slot),
+        StealthError::WithdrawalTooSoon
+    );
+    require!(
+        pool.is_valid_root(merkle_root),
+        StealthError::InvalidMerkleRoot
+    );
+    Ok(pool.denomination)
+}
+
+/// Update pool stats after a withdrawal (separate stack frame)
+#[inline(never)]
+pub(crate) fn record_withdrawal(pool: &AccountLoader<PrivacyPool>, amount: u64) -> Result<()> {
+    let mut pool = pool.load_mut()?;
+    pool.total_withdrawn = pool.total_withdrawn
+        .checked_add(amount)
+        .ok_or(StealthError::ArithmeticOverflow)?;
+    pool.withdrawal_count = pool.withdrawal_count
+        .checked_add(1)
+        .ok_or(StealthError::ArithmeticOverflow)?;
+    Ok(())
+}
+
 /// Verify the stealth address was correctly derived from the meta-address
 #[inline(never)]
 fn verify_stealth_commitment(inputs: &WithdrawPublicInputs) -> Result<()> {
@@ -293,37 +680,71 @@ fn create_stealth_announcement(
 
 /// Verify ZK proof using the oracle attestation system with Ed25519 introspection
 /// Note: Amount is NOT in public inputs - it's implicit from pool denomination
-/// Public inputs include stealth address (not plaintext recipient)
+/// Public inputs include stealth address and commitment (not plaintext recipient)
 #[inline(never)]
-fn verify_zk_proof(proof: &WithdrawProof, instructions_sysvar: &AccountInfo) -> Result<()> {
+fn verify_zk_proof(
+    proof: &WithdrawProof,
+    instructions_sysvar: &AccountInfo,
+    oracle: &VerificationOracle,
+) -> Result<()> {
     // Serialize public inputs for verification
-    // Includes stealth address for privacy (no plain recipient)
-    let mut public_inputs_bytes = Vec::with_capacity(192); // 32+32+32+32+32+32
-    public_inputs_bytes.extend_from_slice(&proof.public_inputs.merkle_root);
-    public_inputs_bytes.extend_from_slice(&proof.public_inputs.nullifier_hash);
-    public_inputs_bytes.extend_from_slice(proof.public_inputs.stealth_address.as_ref());
-    public_inputs_bytes.extend_from_slice(&proof.public_inputs.ephemeral_pubkey);
-    public_inputs_bytes.extend_from_slice(&proof.public_inputs.scan_pubkey);
-    public_inputs_bytes.extend_from_slice(&proof.public_inputs.spend_pubkey);
+    // Includes stealth address + commitment for privacy (no plain recipient)
     // Amount NOT included - determined by pool denomination
-    // Stealth commitment NOT included in ZK proof - verified separately
+    let public_inputs_bytes = proof.public_inputs.to_bytes();
 
     // Use the centralized verifier with full Ed25519 introspection
-    verify_proof_with_sysvar(
+    verify_proof_with_oracle(
         &proof.proof,
         &public_inputs_bytes,
         proof.attestation.as_ref(),
         instructions_sysvar,
-        None, // No trusted verifier whitelist for now (can be added via VerificationOracle account)
+        oracle,
     )?;
 
     msg!("ZK proof verification successful");
     Ok(())
 }
 
+/// Verify a withdrawal proof on-chain with Groth16 when a verification key
+/// is supplied, falling back to the oracle attestation otherwise
+///
+/// `amount` is the pool denomination, a public input of the circuit.
+pub(crate) fn verify_withdraw_proof(
+    proof: &WithdrawProof,
+    amount: u64,
+    instructions_sysvar: &AccountInfo,
+    oracle: &VerificationOracle,
+    verification_key: Option<&StoredVerificationKey>,
+) -> Result<()> {
+    match verification_key {
+        Some(verification_key) => verify_groth16_withdraw_proof(proof, amount, verification_key),
+        None => verify_zk_proof(proof, instructions_sysvar, oracle),
+    }
+}
+
+/// Verify the withdrawal proof on-chain against the stored Groth16 key
+///
+/// `proof.proof` must be the 256-byte `A || B || C` encoding. Unlike the
+/// oracle path this runs in dev builds too: passing the key opts in.
+#[inline(never)]
+fn verify_groth16_withdraw_proof(
+    proof: &WithdrawProof,
+    amount: u64,
+    verification_key: &StoredVerificationKey,
+) -> Result<()> {
+    let vk = load_vk(&verification_key.vk_data)?;
+    let groth16_proof = Groth16Proof::from_bytes(&proof.proof)?;
+
+    let is_valid = verify_groth16(&groth16_proof, &proof.public_inputs.to_field_elements(amount), &vk)?;
+    require!(is_valid, StealthError::InvalidProof);
+
+    msg!("Groth16 proof verified on-chain (vk v{})", verification_key.version);
+    Ok(())
+}
+
 /// Mark nullifier as used (separate stack frame)
 #[inline(never)]
-fn mark_nullifier_used(
+pub(crate) fn mark_nullifier_used(
     nullifier: &mut NullifierRecord,
     hash: &[u8; 32],
     timestamp: i64,
@@ -334,46 +755,101 @@ fn mark_nullifier_used(
     nullifier.bump = bump;
 }
 
-/// Transfer withdrawal funds to stealth address (separate stack frame)
+/// Where a withdrawal's relayer fee goes, split per `PoolConfig`
+pub(crate) struct FeePayout<'a, 'info> {
+    relayer: &'a AccountInfo<'info>,
+    relayer_fee_recipient: Option<&'a AccountInfo<'info>>,
+    protocol_fee_recipient: Option<&'a AccountInfo<'info>>,
+    protocol_amount: u64,
+    relayer_amount: u64,
+}
+
+impl<'a, 'info> FeePayout<'a, 'info> {
+    pub(crate) fn new(
+        config: &PoolConfig,
+        amount: u64,
+        relayer_fee: u64,
+        relayer: &'a AccountInfo<'info>,
+        relayer_fee_recipient: Option<&'a AccountInfo<'info>>,
+        protocol_fee_recipient: Option<&'a AccountInfo<'info>>,
+    ) -> Result<Self> {
+        require!(relayer_fee <= amount, StealthError::RelayerFeeTooHigh);
+
+        let (protocol_amount, relayer_amount) = config.split_relayer_fee(relayer_fee);
+        require!(
+            protocol_amount == 0 || protocol_fee_recipient.is_some(),
+            StealthError::InvalidFeeRecipient
+        );
+
+        Ok(Self {
+            relayer,
+            relayer_fee_recipient,
+            protocol_fee_recipient,
+            protocol_amount,
+            relayer_amount,
+        })
+    }
+
+    /// Total deducted from the withdrawal
+    fn total(&self) -> u64 {
+        self.protocol_amount + self.relayer_amount
+    }
+}
+
+/// Transfer withdrawal funds from the pool to the recipient (separate stack frame)
 #[inline(never)]
-fn transfer_withdrawal_funds_zc(
-    ctx: &Context<PrivateWithdraw>,
+pub(crate) fn transfer_withdrawal_funds_zc<'info>(
+    pool: &AccountInfo<'info>,
+    recipient: &AccountInfo<'info>,
     amount: u64,
-    relayer_fee: u64,
+    fees: &FeePayout<'_, 'info>,
 ) -> Result<()> {
-    let amount_after_relayer_fee = amount.saturating_sub(relayer_fee);
-
     // Validate pool has sufficient balance
-    let pool_balance = ctx.accounts.pool.to_account_info().lamports();
     require!(
-        pool_balance >= amount,
+        pool.lamports() >= amount,
         StealthError::InsufficientPoolBalance
     );
 
-    // Transfer to stealth address (unlinkable to real recipient)
-    **ctx.accounts.pool.to_account_info().try_borrow_mut_lamports()? -= amount;
-    **ctx.accounts.stealth_address.try_borrow_mut_lamports()? += amount_after_relayer_fee;
+    let recipient_amount = amount - fees.total();
+    ensure_rent_exempt_payout(recipient_amount, Rent::get()?.minimum_balance(0))?;
+    safe_transfer_lamports(pool, recipient, recipient_amount)?;
 
     // Pay relayer fee if applicable
-    if relayer_fee > 0 {
-        pay_relayer_fee_zc(ctx, relayer_fee)?;
+    if fees.total() > 0 {
+        pay_relayer_fee_zc(pool, fees)?;
     }
 
     Ok(())
 }
 
-/// Pay relayer fee for zero-copy (separate stack frame)
+/// Pay the relayer fee, skimming the protocol's cut (separate stack frame)
 #[inline(never)]
-fn pay_relayer_fee_zc(ctx: &Context<PrivateWithdraw>, fee: u64) -> Result<()> {
-    if let Some(relayer_fee_recipient) = &ctx.accounts.relayer_fee_recipient {
-        **relayer_fee_recipient.try_borrow_mut_lamports()? += fee;
-    } else {
-        // Fee goes to relayer themselves
-        **ctx.accounts.relayer.try_borrow_mut_lamports()? += fee;
+fn pay_relayer_fee_zc(pool: &AccountInfo, fees: &FeePayout) -> Result<()> {
+    if let Some(protocol_fee_recipient) = fees.protocol_fee_recipient {
+        safe_transfer_lamports(pool, protocol_fee_recipient, fees.protocol_amount)?;
     }
+
+    // Fee goes to the relayer themselves unless they named a recipient
+    let relayer_fee_recipient = fees.relayer_fee_recipient.unwrap_or(fees.relayer);
+    safe_transfer_lamports(pool, relayer_fee_recipient, fees.relayer_amount)?;
+
+    emit!(FeeSplitEvent {
+        protocol_amount: fees.protocol_amount,
+        relayer_amount: fees.relayer_amount,
+    });
+
     Ok(())
 }
 
+/// Emitted when a withdrawal's relayer fee is paid out
+#[event]
+pub struct FeeSplitEvent {
+    /// Skimmed to the pool's `fee_recipient`
+    pub protocol_amount: u64,
+    /// Paid to the relayer (or its fee recipient)
+    pub relayer_amount: u64,
+}
+
 /// Private transfer within a fixed-denomination pool
 /// Spends one note and creates two new notes (recipient + change)
 /// Note: In fixed-denomination pools, transfers create notes of the SAME denomination
@@ -401,6 +877,13 @@ pub struct PrivateTransfer<'info> {
     )]
     pub nullifier: Account<'info, NullifierRecord>,
 
+    /// Verification oracle the attestation is checked against
+    #[account(
+        seeds = [VerificationOracle::SEED],
+        bump = oracle.bump,
+    )]
+    pub oracle: Account<'info, VerificationOracle>,
+
     /// Instructions sysvar for Ed25519 signature verification (production mode)
     /// CHECK: Validated by address constraint
     #[account(address = anchor_lang::solana_program::sysvar::instructions::id())]
@@ -451,7 +934,7 @@ pub fn private_transfer(
     }
 
     // 2. Verify transfer proof (dev: skipped, production: Oracle attestation + Ed25519)
-    verify_transfer_proof(&proof, &ctx.accounts.instructions_sysvar)?;
+    verify_transfer_proof(&proof, &ctx.accounts.instructions_sysvar, &ctx.accounts.oracle)?;
 
     // 3. Mark old nullifier as used
     mark_nullifier_used(
@@ -469,6 +952,7 @@ pub fn private_transfer(
             proof.public_inputs.new_commitment,
             proof.public_inputs.change_commitment,
         )?;
+        super::private_deposit::emit_root_checkpoint(&pool, clock.slot);
     }
 
     msg!("Private transfer successful");
@@ -487,10 +971,16 @@ pub fn private_transfer(
 /// - Correct nullifier derivation
 /// - Correct new commitment derivation
 #[inline(never)]
-fn verify_transfer_proof(proof: &TransferProof, instructions_sysvar: &AccountInfo) -> Result<()> {
+fn verify_transfer_proof(
+    proof: &TransferProof,
+    instructions_sysvar: &AccountInfo,
+    oracle: &VerificationOracle,
+) -> Result<()> {
     #[cfg(not(feature = "production"))]
     {
         msg!("DEV MODE: Skipping ZK proof verification for transfer");
+        // The kill switch applies in every mode
+        crate::zk::verifier::ensure_oracle_active(Some(oracle))?;
         // Basic validation even in dev mode
         require!(
             !proof.proof.is_empty(),
@@ -519,12 +1009,12 @@ fn verify_transfer_proof(proof: &TransferProof, instructions_sysvar: &AccountInf
         public_inputs_bytes.extend_from_slice(&proof.public_inputs.change_commitment);
 
         // Use the centralized verifier with Ed25519 introspection
-        verify_proof_with_sysvar(
+        verify_proof_with_oracle(
             &proof.proof,
             &public_inputs_bytes,
             proof.attestation.as_ref(),
             instructions_sysvar,
-            None, // No trusted verifier whitelist for transfers
+            oracle,
         )?;
 
         msg!("Transfer proof verification successful");
@@ -554,34 +1044,126 @@ fn insert_transfer_commitments_zc(
 #[inline(never)]
 fn insert_leaf_zc(pool: &mut PrivacyPool, commitment: [u8; 32]) -> Result<u64> {
     let leaf_index = pool.next_leaf_index;
-    let new_root = compute_merkle_root_zc(pool, commitment, leaf_index);
-
-    pool.merkle_root = new_root;
+    super::private_deposit::ensure_tree_capacity(leaf_index, 1, MERKLE_DEPTH)?;
+    pool.merkle_root = insert_leaf::<MERKLE_DEPTH>(&mut pool.filled_subtrees, leaf_index, commitment)?;
     pool.next_leaf_index += 1;
 
     Ok(leaf_index)
 }
 
-/// Compute new Merkle root for zero-copy (separate stack frame)
-/// Uses Poseidon hash for ZK circuit compatibility
-#[inline(never)]
-fn compute_merkle_root_zc(pool: &mut PrivacyPool, commitment: [u8; 32], leaf_index: u64) -> [u8; 32] {
-    let zeros = compute_zero_hashes_poseidon();
-    let mut current_index = leaf_index;
-    let mut current_hash = commitment;
-
-    for i in 0..MERKLE_DEPTH {
-        let is_left = current_index % 2 == 0;
-
-        if is_left {
-            pool.filled_subtrees[i] = current_hash;
-            current_hash = merkle_hash_2(&current_hash, &zeros[i]);
-        } else {
-            current_hash = merkle_hash_2(&pool.filled_subtrees[i], &current_hash);
-        }
-
-        current_index /= 2;
+/// Read-only nullifier lookup (lets relayers skip doomed double-spends)
+#[derive(Accounts)]
+#[instruction(denomination: u64, nullifier_hash: [u8; 32])]
+pub struct CheckNullifier<'info> {
+    /// CHECK: Validated by seeds; may not exist yet
+    #[account(
+        seeds = [NullifierRecord::SEED, &denomination.to_le_bytes(), nullifier_hash.as_ref()],
+        bump,
+    )]
+    pub nullifier: UncheckedAccount<'info>,
+}
+
+/// Return whether a nullifier has already been spent in a denomination pool
+///
+/// The PDA only exists once `private_withdraw` has created it, so existence
+/// (program-owned with data) means spent. Call via simulation to read the
+/// return value without paying fees.
+pub fn is_nullifier_spent(
+    ctx: Context<CheckNullifier>,
+    _denomination: u64,
+    _nullifier_hash: [u8; 32],
+) -> Result<bool> {
+    let nullifier = &ctx.accounts.nullifier;
+    let spent = nullifier.owner == &crate::ID && !nullifier.data_is_empty();
+
+    msg!("Nullifier spent: {}", spent);
+
+    Ok(spent)
+}
+
+/// Read-only commitment inclusion check against a pool's known roots
+#[derive(Accounts)]
+#[instruction(denomination: u64)]
+pub struct VerifyInclusion<'info> {
+    #[account(
+        seeds = [PrivacyPool::SEED, &denomination.to_le_bytes()],
+        bump,
+    )]
+    pub pool: AccountLoader<'info, PrivacyPool>,
+}
+
+/// Prove a commitment is in a denomination pool's tree without spending it
+///
+/// Recomputes the root from `commitment`, `leaf_index` and the sibling
+/// `path` (level 0 first) and checks it against the current root and root
+/// history. Changes no state; useful for disputes and for wallets checking
+/// a stored path before withdrawing.
+pub fn verify_inclusion(
+    ctx: Context<VerifyInclusion>,
+    _denomination: u64,
+    commitment: [u8; 32],
+    leaf_index: u64,
+    path: [[u8; 32]; MERKLE_DEPTH],
+) -> Result<()> {
+    let pool = ctx.accounts.pool.load()?;
+    check_inclusion(&pool, &commitment, leaf_index, &path)?;
+
+    msg!("Commitment included at leaf index {}", leaf_index);
+
+    Ok(())
+}
+
+/// Check that `commitment` sits at `leaf_index` under a root the pool accepts
+///
+/// Indices past `next_leaf_index` are rejected: an empty slot hashes to the
+/// same root as a zero commitment would, so it would otherwise "verify".
+pub fn check_inclusion(
+    pool: &PrivacyPool,
+    commitment: &[u8; 32],
+    leaf_index: u64,
+    path: &[[u8; 32]; MERKLE_DEPTH],
+) -> Result<()> {
+    require!(leaf_index < pool.next_leaf_index, StealthError::InvalidMerkleRoot);
+
+    let root = compute_root_from_path(commitment, leaf_index, path);
+    require!(pool.is_valid_root(&root), StealthError::InvalidMerkleRoot);
+
+    Ok(())
+}
+
+/// Read-only consistency check of a pool's tree
+#[derive(Accounts)]
+#[instruction(denomination: u64)]
+pub struct AuditTree<'info> {
+    #[account(
+        seeds = [PrivacyPool::SEED, &denomination.to_le_bytes()],
+        bump,
+    )]
+    pub pool: AccountLoader<'info, PrivacyPool>,
+}
+
+/// Check that a pool's stored root matches its frontier
+///
+/// The pool keeps only `filled_subtrees` and `merkle_root`, so an insert
+/// that updates one inconsistently would go unnoticed until withdrawals
+/// start failing. Cheap enough for operators to run periodically.
+pub fn audit_tree(ctx: Context<AuditTree>, denomination: u64) -> Result<()> {
+    let pool = ctx.accounts.pool.load()?;
+    check_tree_consistency(&pool)?;
+
+    msg!("Pool {} tree consistent at {} leaves", denomination, pool.next_leaf_index);
+
+    Ok(())
+}
+
+/// Recompute the root from `filled_subtrees` and `next_leaf_index` and
+/// compare it to `merkle_root`
+pub fn check_tree_consistency(pool: &PrivacyPool) -> Result<()> {
+    let root = frontier_root::<MERKLE_DEPTH>(&pool.filled_subtrees, pool.next_leaf_index)?;
+    if root != pool.merkle_root {
+        msg!("Frontier root {:?} != stored root {:?}", &root[..8], &pool.merkle_root[..8]);
+        return err!(StealthError::TreeInconsistent);
     }
 
-    current_hash
+    Ok(())
 }
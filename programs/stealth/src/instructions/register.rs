@@ -115,3 +115,28 @@ pub fn update_registry(
 
     Ok(())
 }
+
+/// Rename a meta-address without touching its keys
+///
+/// # Arguments
+/// * `label` - New label: UTF-8, at most 32 bytes, no NULs (empty clears it)
+pub fn update_label(ctx: Context<UpdateRegistry>, label: Vec<u8>) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    registry.label = StealthRegistry::encode_label(&label)?;
+
+    emit!(LabelUpdated {
+        owner: registry.owner,
+        label: registry.label,
+    });
+
+    msg!("Stealth meta-address label updated for {}", registry.owner);
+
+    Ok(())
+}
+
+#[event]
+pub struct LabelUpdated {
+    pub owner: Pubkey,
+    /// New label, zero-padded
+    pub label: [u8; 32],
+}
@@ -17,8 +17,9 @@
 
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
-use crate::state::{Relayer, RelayerRegistry, RelayerStake, PendingRelay, RelayCompleted};
+use crate::state::{Relayer, RelayerRegistry, RelayerStake, PendingRelay, RelayCompleted, RelayFailed};
 use crate::error::StealthError;
+use crate::util::safe_transfer_lamports;
 
 /// Initialize the relayer registry
 #[derive(Accounts)]
@@ -51,6 +52,7 @@ pub fn initialize_relayer_registry(ctx: Context<InitializeRelayerRegistry>) -> R
     registry.total_fees_paid = 0;
     registry.min_stake = RelayerRegistry::DEFAULT_MIN_STAKE;
     registry.registrations_open = true;
+    registry.pending_authority = Pubkey::default();
     registry.bump = ctx.bumps.registry;
 
     msg!("Relayer registry initialized");
@@ -102,6 +104,7 @@ pub struct RegisterRelayer<'info> {
 }
 
 /// Register as a relayer with stake
+#[allow(clippy::too_many_arguments)]
 pub fn register_relayer(
     ctx: Context<RegisterRelayer>,
     fee_bps: u16,
@@ -109,6 +112,8 @@ pub fn register_relayer(
     max_fee: u64,
     supported_denominations: u8,
     stake_amount: u64,
+    min_denomination: u64,
+    endpoint_url: [u8; 64],
 ) -> Result<()> {
     let clock = Clock::get()?;
     let registry = &mut ctx.accounts.registry;
@@ -149,6 +154,11 @@ pub fn register_relayer(
     relayer.reputation = 50; // Start at neutral
     relayer.registered_at = clock.unix_timestamp;
     relayer.last_active = clock.unix_timestamp;
+    relayer.min_denomination = min_denomination;
+    relayer.endpoint_url = endpoint_url;
+    relayer.total_relayed = 0;
+    relayer.total_failed = 0;
+    relayer.last_active_slot = clock.slot;
     relayer.bump = ctx.bumps.relayer;
 
     // Initialize stake
@@ -185,6 +195,7 @@ pub struct UpdateRelayer<'info> {
 }
 
 /// Update relayer settings
+#[allow(clippy::too_many_arguments)]
 pub fn update_relayer(
     ctx: Context<UpdateRelayer>,
     fee_bps: Option<u16>,
@@ -192,6 +203,8 @@ pub fn update_relayer(
     max_fee: Option<u64>,
     supported_denominations: Option<u8>,
     is_active: Option<bool>,
+    min_denomination: Option<u64>,
+    endpoint_url: Option<[u8; 64]>,
 ) -> Result<()> {
     let relayer = &mut ctx.accounts.relayer;
 
@@ -216,6 +229,14 @@ pub fn update_relayer(
         relayer.is_active = active;
     }
 
+    if let Some(min) = min_denomination {
+        relayer.min_denomination = min;
+    }
+
+    if let Some(url) = endpoint_url {
+        relayer.endpoint_url = url;
+    }
+
     msg!("Relayer updated: {}", relayer.pubkey);
 
     Ok(())
@@ -373,6 +394,60 @@ pub fn complete_relay(
     Ok(())
 }
 
+/// Mark an expired relay request as failed
+#[derive(Accounts)]
+#[instruction(tx_hash: [u8; 32])]
+pub struct FailRelay<'info> {
+    /// User who requested the relay (receives the pending account's rent)
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// Relayer that was assigned the request
+    #[account(
+        mut,
+        seeds = [Relayer::SEED, relayer.pubkey.as_ref()],
+        bump = relayer.bump,
+    )]
+    pub relayer: Account<'info, Relayer>,
+
+    /// Pending relay
+    #[account(
+        mut,
+        close = user,
+        seeds = [PendingRelay::SEED, user.key().as_ref(), &tx_hash],
+        bump = pending.bump,
+        constraint = pending.relayer == relayer.pubkey @ StealthError::WrongRelayer,
+        constraint = !pending.completed @ StealthError::RelayAlreadyCompleted,
+    )]
+    pub pending: Account<'info, PendingRelay>,
+}
+
+/// Record a failure against a relayer that let a request expire
+///
+/// Only the requesting user can do this, and only once the request has
+/// expired; the pending account is closed so it can't be counted twice.
+pub fn fail_relay(ctx: Context<FailRelay>, _tx_hash: [u8; 32]) -> Result<()> {
+    let clock = Clock::get()?;
+    let relayer = &mut ctx.accounts.relayer;
+
+    require!(
+        clock.unix_timestamp > ctx.accounts.pending.expires_at,
+        StealthError::RelayNotExpired
+    );
+
+    relayer.record_failed();
+
+    emit!(RelayFailed {
+        relayer: relayer.pubkey,
+        total_failed: relayer.total_failed,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Relay marked failed for relayer {}", relayer.pubkey);
+
+    Ok(())
+}
+
 /// Withdraw stake (with timelock)
 #[derive(Accounts)]
 pub struct WithdrawRelayerStake<'info> {
@@ -425,8 +500,11 @@ pub fn withdraw_relayer_stake(ctx: Context<WithdrawRelayerStake>) -> Result<()>
 
     // Transfer stake back to operator
     let stake_amount = stake.amount;
-    **stake.to_account_info().try_borrow_mut_lamports()? -= stake_amount;
-    **ctx.accounts.operator.to_account_info().try_borrow_mut_lamports()? += stake_amount;
+    safe_transfer_lamports(
+        &stake.to_account_info(),
+        &ctx.accounts.operator.to_account_info(),
+        stake_amount,
+    )?;
 
     stake.amount = 0;
 
@@ -464,6 +542,7 @@ pub fn get_relayer_info(ctx: Context<GetRelayerInfo>) -> Result<()> {
     msg!("Active: {}", relayer.is_active);
     msg!("Reputation: {}", relayer.reputation);
     msg!("TX count: {}", relayer.tx_count);
+    msg!("Relayed: {} / failed: {}", relayer.total_relayed, relayer.total_failed);
 
     Ok(())
 }
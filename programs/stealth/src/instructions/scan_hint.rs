@@ -0,0 +1,52 @@
+//! Scan Hint Instruction
+//!
+//! Lets a sender add a payment's tag to the recipient's `ScanHint` filter,
+//! creating the filter on first use. See `state::scan_hint` for the tag and
+//! why writing one gives up the payment's unlinkability.
+
+use anchor_lang::prelude::*;
+use crate::crypto::validate_curve_point;
+use crate::error::StealthError;
+use crate::state::ScanHint;
+
+/// Accounts for post_scan_hint
+#[derive(Accounts)]
+#[instruction(scan_pubkey: [u8; 32])]
+pub struct PostScanHint<'info> {
+    /// Sender (pays for the filter if it doesn't exist yet)
+    #[account(mut)]
+    pub sender: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = sender,
+        space = ScanHint::SIZE,
+        seeds = [ScanHint::SEED, scan_pubkey.as_ref()],
+        bump,
+    )]
+    pub hint: Account<'info, ScanHint>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Add `tag` (`Poseidon(scan_pubkey, ephemeral_pubkey)[..8]`) to the
+/// recipient's scan hint filter
+///
+/// The tag isn't checked: a wrong one only costs the recipient a false
+/// positive, never a missed payment.
+pub fn post_scan_hint(ctx: Context<PostScanHint>, scan_pubkey: [u8; 32], tag: [u8; 8]) -> Result<()> {
+    require!(
+        validate_curve_point(&scan_pubkey),
+        StealthError::InvalidScanPubkey
+    );
+
+    let hint = &mut ctx.accounts.hint;
+    if hint.tag_count == 0 {
+        hint.scan_pubkey = scan_pubkey;
+        hint.bump = ctx.bumps.hint;
+    }
+    hint.insert(&tag);
+
+    msg!("Scan hint tags: {}", hint.tag_count);
+    Ok(())
+}
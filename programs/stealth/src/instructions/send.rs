@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
-use crate::state::{StealthRegistry, StealthAnnouncement, compute_commitment};
+use crate::state::{StealthRegistry, StealthAnnouncement, AnnouncementIndex, compute_commitment};
+use crate::instructions::announcement_index::index_announcement;
 use crate::crypto::validate_curve_point;
 use crate::error::{StealthError, MIN_PAYMENT_LAMPORTS};
 
@@ -32,6 +33,14 @@ pub struct StealthSend<'info> {
 
     /// System program for transfers
     pub system_program: Program<'info, System>,
+
+    /// Optional: index of announcements in the current slot bucket
+    #[account(
+        mut,
+        seeds = [AnnouncementIndex::SEED, &announcement_index.bucket.to_le_bytes()],
+        bump = announcement_index.bump,
+    )]
+    pub announcement_index: Option<Account<'info, AnnouncementIndex>>,
 }
 
 /// Send SOL to a stealth address via registry lookup
@@ -104,6 +113,9 @@ pub fn stealth_send(
     announcement.timestamp = clock.unix_timestamp;
     announcement.bump = ctx.bumps.announcement;
 
+    let announcement = ctx.accounts.announcement.key();
+    index_announcement(ctx.accounts.announcement_index.as_mut(), announcement, clock.slot)?;
+
     msg!(
         "Stealth payment: {} lamports to {} (verified)",
         amount,
@@ -144,6 +156,14 @@ pub struct StealthSendDirect<'info> {
 
     /// System program for transfers
     pub system_program: Program<'info, System>,
+
+    /// Optional: index of announcements in the current slot bucket
+    #[account(
+        mut,
+        seeds = [AnnouncementIndex::SEED, &announcement_index.bucket.to_le_bytes()],
+        bump = announcement_index.bump,
+    )]
+    pub announcement_index: Option<Account<'info, AnnouncementIndex>>,
 }
 
 /// Send SOL directly using a meta-address (no registry lookup required)
@@ -223,6 +243,9 @@ pub fn stealth_send_direct(
     announcement.timestamp = clock.unix_timestamp;
     announcement.bump = ctx.bumps.announcement;
 
+    let announcement = ctx.accounts.announcement.key();
+    index_announcement(ctx.accounts.announcement_index.as_mut(), announcement, clock.slot)?;
+
     msg!(
         "Direct stealth payment: {} lamports to {} (verified)",
         amount,
@@ -122,6 +122,11 @@ pub fn simple_deposit(
     {
         let mut pool = ctx.accounts.pool.load_mut()?;
         leaf_index = pool.next_leaf_index;
+        super::private_deposit::ensure_tree_capacity(
+            leaf_index,
+            1,
+            crate::crypto::merkle::MERKLE_DEPTH,
+        )?;
 
         // Store the provided Merkle root (trusted from off-chain computation)
         pool.save_root_to_history();
@@ -134,6 +139,7 @@ pub fn simple_deposit(
         pool.deposit_count = pool.deposit_count
             .checked_add(1)
             .ok_or(StealthError::ArithmeticOverflow)?;
+        super::private_deposit::emit_root_checkpoint(&pool, clock.slot);
     }
 
     // Store commitment leaf for indexing
@@ -145,6 +151,7 @@ pub fn simple_deposit(
         leaf.encrypted_note = [0u8; 128];
         leaf.amount_commitment = [0u8; 33];
         leaf.range_proof_hash = [0u8; 32];
+        leaf.slot = clock.slot;
         leaf.bump = ctx.bumps.commitment_leaf;
     }
 
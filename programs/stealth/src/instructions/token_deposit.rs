@@ -0,0 +1,303 @@
+//! Private Token Deposit Instruction
+//!
+//! SPL token variant of `private_deposit`. Each token pool is keyed by
+//! (mint, denomination) and holds deposits in a vault token account owned
+//! by the pool PDA. Denominations are fixed amounts of the token's base units.
+//!
+//! Merkle insertion is shared with the SOL path (`insert_commitment_to_tree_zc`),
+//! so token commitments use the exact same tree and proof format.
+//!
+//! Withdrawals are in `token_withdraw`, where the pool PDA signs the vault
+//! transfer to the recipient's associated token account.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use crate::state::privacy_pool::{PrivacyPool, CommitmentLeaf, CommitmentScheme, PoolConfig};
+use crate::crypto::merkle::{compute_zero_hashes_poseidon, MERKLE_DEPTH};
+use crate::error::StealthError;
+use super::private_deposit::{emit_root_checkpoint, init_filled_subtrees_with_zeros, insert_commitment_to_tree_zc, PoolInitialized};
+
+/// Initialize a fixed-denomination SPL token pool
+/// The mint is included in the PDA seeds so it never collides with SOL pools
+#[derive(Accounts)]
+#[instruction(denomination: u64)]
+pub struct InitializeTokenPool<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Token being pooled (e.g. USDC)
+    pub mint: Account<'info, Mint>,
+
+    /// Pool PDA includes mint and denomination in seeds
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<PrivacyPool>(),
+        seeds = [PrivacyPool::SEED, mint.key().as_ref(), &denomination.to_le_bytes()],
+        bump,
+    )]
+    pub pool: AccountLoader<'info, PrivacyPool>,
+
+    /// Config PDA also includes mint and denomination
+    #[account(
+        init,
+        payer = authority,
+        space = PoolConfig::SIZE,
+        seeds = [PoolConfig::SEED, mint.key().as_ref(), &denomination.to_le_bytes()],
+        bump,
+    )]
+    pub config: Account<'info, PoolConfig>,
+
+    /// Vault holding pooled tokens - authority is the pool PDA
+    #[account(
+        init,
+        payer = authority,
+        seeds = [PrivacyPool::TOKEN_VAULT_SEED, pool.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = pool,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Initialize a fixed-denomination token pool
+#[inline(never)]
+pub fn initialize_token_pool(ctx: Context<InitializeTokenPool>, denomination: u64) -> Result<()> {
+    // Same fixed set as SOL pools, interpreted in the token's base units
+    require!(
+        PrivacyPool::is_valid_denomination(denomination),
+        StealthError::InvalidDenomination
+    );
+
+    let mut pool = ctx.accounts.pool.load_init()?;
+    let config = &mut ctx.accounts.config;
+
+    let zeros = compute_zero_hashes_poseidon::<MERKLE_DEPTH>();
+
+    pool.authority = ctx.accounts.authority.key();
+    pool.denomination = denomination;
+    pool.merkle_root = zeros[MERKLE_DEPTH];
+    pool.next_leaf_index = 0;
+    pool.total_deposited = 0;
+    pool.total_withdrawn = 0;
+    pool.deposit_count = 0;
+    pool.withdrawal_count = 0;
+    pool.is_active = true;
+    pool.root_history_index = 0;
+    pool.token_mint = ctx.accounts.mint.key();
+    pool.pending_authority = Pubkey::default();
+    pool.commitment_scheme = CommitmentScheme::Poseidon4.id();
+    pool.bump = ctx.bumps.pool;
+
+    init_filled_subtrees_with_zeros(&mut pool, &zeros);
+
+    config.authority = ctx.accounts.authority.key();
+    config.min_deposit = denomination;
+    config.max_deposit = denomination;
+    config.fee_bps = PoolConfig::DEFAULT_FEE_BPS;
+    config.fee_recipient = ctx.accounts.authority.key();
+    config.deposits_paused = false;
+    config.withdrawals_paused = false;
+    config.min_anonymity_set = PoolConfig::DEFAULT_MIN_ANONYMITY_SET;
+    config.protocol_fee_bps = PoolConfig::DEFAULT_PROTOCOL_FEE_BPS;
+    config.relayer_fee_bps = PoolConfig::DEFAULT_RELAYER_FEE_BPS;
+    config.min_age_slots = PoolConfig::DEFAULT_MIN_AGE_SLOTS;
+    config.pending_authority = Pubkey::default();
+    config.bump = ctx.bumps.config;
+
+    emit!(PoolInitialized {
+        pool: ctx.accounts.pool.key(),
+        denomination,
+        token_mint: ctx.accounts.mint.key(),
+        commitment_scheme: CommitmentScheme::Poseidon4.id(),
+    });
+
+    msg!("Fixed-denomination token pool initialized");
+    msg!("Mint: {}", ctx.accounts.mint.key());
+    msg!("Denomination: {} base units", denomination);
+
+    Ok(())
+}
+
+/// Private token deposit accounts
+#[derive(Accounts)]
+#[instruction(denomination: u64, commitment: [u8; 32])]
+pub struct PrivateTokenDeposit<'info> {
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    /// Pool PDA includes mint and denomination in seeds
+    #[account(
+        mut,
+        seeds = [PrivacyPool::SEED, mint.key().as_ref(), &denomination.to_le_bytes()],
+        bump,
+    )]
+    pub pool: AccountLoader<'info, PrivacyPool>,
+
+    #[account(
+        seeds = [PoolConfig::SEED, mint.key().as_ref(), &denomination.to_le_bytes()],
+        bump = config.bump,
+        constraint = !config.deposits_paused @ StealthError::DepositsPaused,
+    )]
+    pub config: Account<'info, PoolConfig>,
+
+    /// Depositor's token account (source of funds)
+    #[account(
+        mut,
+        constraint = depositor_token_account.mint == mint.key() @ StealthError::InvalidTokenMint,
+        constraint = depositor_token_account.owner == depositor.key() @ StealthError::Unauthorized,
+    )]
+    pub depositor_token_account: Account<'info, TokenAccount>,
+
+    /// Pool vault (destination of funds)
+    #[account(
+        mut,
+        seeds = [PrivacyPool::TOKEN_VAULT_SEED, pool.key().as_ref()],
+        bump,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    /// Commitment leaf account (includes mint + denomination for uniqueness across pools)
+    #[account(
+        init,
+        payer = depositor,
+        space = CommitmentLeaf::SIZE,
+        seeds = [CommitmentLeaf::SEED, mint.key().as_ref(), &denomination.to_le_bytes(), commitment.as_ref()],
+        bump,
+    )]
+    pub commitment_leaf: Account<'info, CommitmentLeaf>,
+
+    /// Optional: fee recipient token account (must be owned by config.fee_recipient)
+    #[account(
+        mut,
+        constraint = fee_token_account.owner == config.fee_recipient @ StealthError::InvalidFeeRecipient,
+        constraint = fee_token_account.mint == mint.key() @ StealthError::InvalidTokenMint,
+    )]
+    pub fee_token_account: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Deposit SPL tokens privately into a fixed-denomination token pool
+///
+/// # Arguments
+/// * `denomination` - The pool denomination (in token base units)
+/// * `commitment` - The commitment hash: Poseidon(nullifier, secret)
+/// * `encrypted_note` - Optional encrypted note for recipient
+#[inline(never)]
+pub fn private_token_deposit(
+    ctx: Context<PrivateTokenDeposit>,
+    denomination: u64,
+    commitment: [u8; 32],
+    encrypted_note: Option<[u8; 128]>,
+) -> Result<()> {
+    let config = &ctx.accounts.config;
+    let clock = Clock::get()?;
+
+    let amount = {
+        let pool = ctx.accounts.pool.load()?;
+        require!(pool.is_active, StealthError::PoolNotActive);
+        require!(pool.is_token_pool(), StealthError::NotTokenPool);
+        require!(
+            pool.token_mint == ctx.accounts.mint.key(),
+            StealthError::InvalidTokenMint
+        );
+        require!(
+            pool.denomination == denomination,
+            StealthError::AmountMustMatchDenomination
+        );
+        pool.denomination
+    };
+
+    // Calculate fee
+    let fee = (amount as u128)
+        .checked_mul(config.fee_bps as u128)
+        .ok_or(StealthError::ArithmeticOverflow)?
+        .checked_div(10_000)
+        .ok_or(StealthError::ArithmeticOverflow)? as u64;
+    let deposit_amount = amount
+        .checked_sub(fee)
+        .ok_or(StealthError::ArithmeticUnderflow)?;
+
+    // Transfer tokens to the vault
+    transfer_tokens_to_vault(&ctx, deposit_amount)?;
+
+    // Transfer fee if applicable
+    if fee > 0 {
+        transfer_token_fee(&ctx, fee)?;
+    }
+
+    // Insert commitment into Merkle tree (same logic as SOL pools)
+    let leaf_index;
+    let new_root;
+    {
+        let mut pool = ctx.accounts.pool.load_mut()?;
+        leaf_index = insert_commitment_to_tree_zc(&mut pool, commitment)?;
+        pool.total_deposited = pool.total_deposited
+            .checked_add(deposit_amount)
+            .ok_or(StealthError::ArithmeticOverflow)?;
+        pool.deposit_count = pool.deposit_count
+            .checked_add(1)
+            .ok_or(StealthError::ArithmeticOverflow)?;
+        new_root = pool.merkle_root;
+        emit_root_checkpoint(&pool, clock.slot);
+    }
+
+    {
+        let commitment_leaf = &mut ctx.accounts.commitment_leaf;
+        commitment_leaf.commitment = commitment;
+        commitment_leaf.leaf_index = leaf_index;
+        commitment_leaf.timestamp = clock.unix_timestamp;
+        commitment_leaf.encrypted_note = encrypted_note.unwrap_or([0u8; 128]);
+        commitment_leaf.amount_commitment = [0u8; 33];
+        commitment_leaf.range_proof_hash = [0u8; 32];
+        commitment_leaf.slot = clock.slot;
+        commitment_leaf.bump = ctx.bumps.commitment_leaf;
+    }
+
+    msg!("Private deposit to token pool");
+    msg!("Mint: {}", ctx.accounts.mint.key());
+    msg!("Denomination: {} base units", amount);
+    msg!("Leaf index: {}", leaf_index);
+    msg!("New root: {:?}", new_root);
+
+    Ok(())
+}
+
+/// Transfer tokens from depositor to vault (separate stack frame)
+#[inline(never)]
+fn transfer_tokens_to_vault(ctx: &Context<PrivateTokenDeposit>, amount: u64) -> Result<()> {
+    let cpi_context = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.depositor_token_account.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.depositor.to_account_info(),
+        },
+    );
+    token::transfer(cpi_context, amount)
+}
+
+/// Transfer token fee if a fee account was provided (separate stack frame)
+#[inline(never)]
+fn transfer_token_fee(ctx: &Context<PrivateTokenDeposit>, fee: u64) -> Result<()> {
+    if let Some(fee_token_account) = &ctx.accounts.fee_token_account {
+        let fee_cpi = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.depositor_token_account.to_account_info(),
+                to: fee_token_account.to_account_info(),
+                authority: ctx.accounts.depositor.to_account_info(),
+            },
+        );
+        token::transfer(fee_cpi, fee)?;
+    }
+    Ok(())
+}
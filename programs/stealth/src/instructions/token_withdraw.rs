@@ -0,0 +1,284 @@
+//! Private Token Withdrawal Instruction
+//!
+//! SPL token counterpart of `private_withdraw_to_address`. After the ZK proof
+//! and nullifier checks, the pool PDA signs a transfer of the fixed
+//! denomination from its vault to the recipient's associated token account.
+//! The ATA is created on the fly (the relayer pays rent) so a fresh stealth
+//! address can receive tokens without holding SOL first.
+//!
+//! The proof binds the recipient wallet, not the token account: the ATA is
+//! derived from (recipient, pool mint), so a relayer can't redirect funds.
+//! Pass a stealth address as the recipient to keep the receiver unlinkable.
+//!
+//! Relayer fees are paid in the pool's token, split per `PoolConfig` like
+//! SOL withdrawals.
+//!
+//! Like `private_withdraw`, the proof is checked on-chain with Groth16 when
+//! the stored verification key is passed, otherwise against an attestation
+//! from the verification oracle (kill switch, circuit hash, key version and
+//! trusted verifiers all apply).
+
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use crate::state::privacy_pool::{PrivacyPool, NullifierRecord, PoolConfig};
+use crate::error::StealthError;
+use crate::zk::verifier::{verify_proof_with_sysvar, VerificationOracle};
+use crate::zk::{Groth16Proof, StoredVerificationKey, verify_groth16};
+use crate::instructions::verified_withdraw::load_vk;
+use super::private_withdraw::{check_withdrawal_pool, mark_nullifier_used, record_withdrawal, AddressWithdrawProof};
+
+/// Private token withdrawal accounts
+#[derive(Accounts)]
+#[instruction(denomination: u64, proof: AddressWithdrawProof)]
+pub struct PrivateTokenWithdraw<'info> {
+    /// Anyone can submit a withdrawal proof (relayer support); pays rent for
+    /// the nullifier and, if missing, the recipient's ATA
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    /// Pool PDA includes mint and denomination in seeds
+    #[account(
+        mut,
+        seeds = [PrivacyPool::SEED, mint.key().as_ref(), &denomination.to_le_bytes()],
+        bump,
+    )]
+    pub pool: AccountLoader<'info, PrivacyPool>,
+
+    #[account(
+        seeds = [PoolConfig::SEED, mint.key().as_ref(), &denomination.to_le_bytes()],
+        bump = config.bump,
+        constraint = !config.withdrawals_paused @ StealthError::WithdrawalsPaused,
+    )]
+    pub config: Account<'info, PoolConfig>,
+
+    /// Pool vault (source of funds)
+    #[account(
+        mut,
+        seeds = [PrivacyPool::TOKEN_VAULT_SEED, pool.key().as_ref()],
+        bump,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    /// Nullifier record (includes mint + denomination, like commitment leaves)
+    #[account(
+        init,
+        payer = relayer,
+        space = NullifierRecord::SIZE,
+        seeds = [
+            NullifierRecord::SEED,
+            mint.key().as_ref(),
+            &denomination.to_le_bytes(),
+            proof.public_inputs.nullifier_hash.as_ref(),
+        ],
+        bump,
+    )]
+    pub nullifier: Account<'info, NullifierRecord>,
+
+    /// Wallet (or stealth address) the withdrawal is for
+    /// CHECK: Bound by the proof's public inputs
+    #[account(
+        constraint = recipient.key() == proof.public_inputs.recipient @ StealthError::InvalidRecipient,
+    )]
+    pub recipient: AccountInfo<'info>,
+
+    /// Recipient's associated token account for the pool mint
+    #[account(
+        init_if_needed,
+        payer = relayer,
+        associated_token::mint = mint,
+        associated_token::authority = recipient,
+    )]
+    pub recipient_token_account: Account<'info, TokenAccount>,
+
+    /// Relayer's token account for its fee (required when it gets a share)
+    #[account(
+        mut,
+        constraint = relayer_token_account.owner == relayer.key() @ StealthError::InvalidFeeRecipient,
+        constraint = relayer_token_account.mint == mint.key() @ StealthError::InvalidTokenMint,
+    )]
+    pub relayer_token_account: Option<Account<'info, TokenAccount>>,
+
+    /// Protocol's cut of the relayer fee (required when `protocol_fee_bps` > 0)
+    #[account(
+        mut,
+        constraint = protocol_fee_token_account.owner == config.fee_recipient @ StealthError::InvalidFeeRecipient,
+        constraint = protocol_fee_token_account.mint == mint.key() @ StealthError::InvalidTokenMint,
+    )]
+    pub protocol_fee_token_account: Option<Account<'info, TokenAccount>>,
+
+    /// Verification oracle the attestation is checked against
+    #[account(
+        seeds = [VerificationOracle::SEED],
+        bump = oracle.bump,
+    )]
+    pub oracle: Account<'info, VerificationOracle>,
+
+    /// Instructions sysvar for Ed25519 signature verification (production mode)
+    /// CHECK: Validated by address constraint
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::id())]
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+
+    /// Optional: stored Groth16 verification key. When passed, the proof is
+    /// verified on-chain and the oracle attestation is not consulted.
+    #[account(
+        seeds = [StoredVerificationKey::SEEDS],
+        bump = verification_key.bump,
+    )]
+    pub verification_key: Option<Account<'info, StoredVerificationKey>>,
+}
+
+/// Withdraw a token pool's fixed denomination to the recipient's ATA
+///
+/// # Arguments
+/// * `denomination` - The pool denomination (in token base units)
+/// * `proof` - ZK proof binding the recipient wallet
+/// * `relayer_fee` - Fee in token base units, taken from the withdrawal
+///
+/// PRIVACY PROPERTIES:
+/// - Amount: HIDDEN (fixed denomination)
+/// - Deposit↔Withdrawal link: HIDDEN (ZK proof)
+/// - Recipient identity: as private as the recipient (use a stealth address)
+#[inline(never)]
+pub fn private_token_withdraw(
+    ctx: Context<PrivateTokenWithdraw>,
+    denomination: u64,
+    proof: AddressWithdrawProof,
+    relayer_fee: u64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+
+    // 1. Verify pool is a live token pool for this mint with a valid root
+    let amount = check_withdrawal_pool(
+        &ctx.accounts.pool,
+        &ctx.accounts.config,
+        denomination,
+        &proof.public_inputs.merkle_root,
+        proof.public_inputs.deposit_slot,
+        clock.slot,
+    )?;
+    let (pool_bump, pool_mint) = {
+        let pool = ctx.accounts.pool.load()?;
+        require!(pool.is_token_pool(), StealthError::NotTokenPool);
+        (pool.bump, pool.token_mint)
+    };
+    require!(
+        pool_mint == ctx.accounts.mint.key(),
+        StealthError::InvalidTokenMint
+    );
+    require!(
+        ctx.accounts.recipient_token_account.mint == pool_mint,
+        StealthError::InvalidTokenMint
+    );
+    require!(
+        ctx.accounts.vault.amount >= amount,
+        StealthError::InsufficientPoolBalance
+    );
+
+    // 2. Verify the ZK proof (binds the recipient)
+    verify_token_withdraw_proof(
+        &proof,
+        amount,
+        &ctx.accounts.instructions_sysvar,
+        &ctx.accounts.oracle,
+        ctx.accounts.verification_key.as_deref(),
+    )?;
+    msg!("ZK proof verification successful");
+
+    // 3. Mark nullifier as used (prevents double-spend)
+    mark_nullifier_used(
+        &mut ctx.accounts.nullifier,
+        &proof.public_inputs.nullifier_hash,
+        clock.unix_timestamp,
+        ctx.bumps.nullifier,
+    );
+
+    // 4. Pay the recipient and any fees out of the vault
+    require!(relayer_fee <= amount, StealthError::RelayerFeeTooHigh);
+    let (protocol_amount, relayer_amount) = ctx.accounts.config.split_relayer_fee(relayer_fee);
+    let recipient_amount = amount - protocol_amount - relayer_amount;
+
+    let mint = ctx.accounts.mint.key();
+    let denomination_bytes = denomination.to_le_bytes();
+    let pool_bump = [pool_bump];
+    let signer_seeds: &[&[&[u8]]] = &[&[PrivacyPool::SEED, mint.as_ref(), &denomination_bytes, &pool_bump]];
+
+    let accounts = &ctx.accounts;
+    transfer_from_vault(accounts, &accounts.recipient_token_account, recipient_amount, signer_seeds)?;
+    if protocol_amount > 0 {
+        let account = accounts.protocol_fee_token_account.as_ref()
+            .ok_or(StealthError::InvalidFeeRecipient)?;
+        transfer_from_vault(accounts, account, protocol_amount, signer_seeds)?;
+    }
+    if relayer_amount > 0 {
+        let account = accounts.relayer_token_account.as_ref()
+            .ok_or(StealthError::InvalidFeeRecipient)?;
+        transfer_from_vault(accounts, account, relayer_amount, signer_seeds)?;
+    }
+
+    // 5. Update pool stats
+    record_withdrawal(&ctx.accounts.pool, amount)?;
+
+    msg!("Private withdrawal from token pool");
+    msg!("Mint: {}", mint);
+    msg!("Denomination: {} base units", amount);
+    msg!("Recipient token account: {}", ctx.accounts.recipient_token_account.key());
+
+    Ok(())
+}
+
+/// Verify the withdrawal proof with Groth16 when a verification key is
+/// supplied, falling back to the oracle attestation otherwise
+#[inline(never)]
+fn verify_token_withdraw_proof(
+    proof: &AddressWithdrawProof,
+    amount: u64,
+    instructions_sysvar: &AccountInfo,
+    oracle: &VerificationOracle,
+    verification_key: Option<&StoredVerificationKey>,
+) -> Result<()> {
+    if let Some(verification_key) = verification_key {
+        let vk = load_vk(&verification_key.vk_data)?;
+        let groth16_proof = Groth16Proof::from_bytes(&proof.proof)?;
+        let is_valid = verify_groth16(&groth16_proof, &proof.public_inputs.to_field_elements(amount), &vk)?;
+        require!(is_valid, StealthError::InvalidProof);
+        msg!("Groth16 proof verified on-chain (vk v{})", verification_key.version);
+        return Ok(());
+    }
+
+    verify_proof_with_sysvar(
+        &proof.proof,
+        &proof.public_inputs.to_bytes(),
+        proof.attestation.as_ref(),
+        instructions_sysvar,
+        Some(&oracle.trusted_verifiers),
+        Some(oracle),
+        None,
+    )
+}
+
+/// Transfer tokens out of the vault, signed by the pool PDA (separate stack frame)
+#[inline(never)]
+fn transfer_from_vault<'info>(
+    accounts: &PrivateTokenWithdraw<'info>,
+    to: &Account<'info, TokenAccount>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let cpi_context = CpiContext::new_with_signer(
+        accounts.token_program.to_account_info(),
+        Transfer {
+            from: accounts.vault.to_account_info(),
+            to: to.to_account_info(),
+            authority: accounts.pool.to_account_info(),
+        },
+        signer_seeds,
+    );
+    token::transfer(cpi_context, amount)
+}
@@ -18,6 +18,21 @@
 //!
 //! Separate flows: ~60% (user error prone)
 //! Unified flow:   ~97% (automatic, can't mess up)
+//!
+//! ## Encrypted Notes
+//!
+//! A deposit can carry the note (nullifier, secret, amount) encrypted to the
+//! recipient's scan key, stored on the deposit's `CommitmentLeaf` so the
+//! recipient recovers it by scanning, with nothing handed over off-chain.
+//! The 128 bytes are:
+//!
+//! ```text
+//! ephemeral_pubkey(32) || ChaCha20-Poly1305 ciphertext(72) || tag(16) || zero padding(8)
+//! ```
+//!
+//! The cipher key and 12-byte nonce are both derived from the shared secret
+//! `r·S`, so the nonce isn't stored; the ephemeral key is fresh per note and
+//! is also the associated data. All zeros means no note was attached.
 
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
@@ -25,9 +40,16 @@ use anchor_lang::solana_program::keccak;
 
 use crate::crypto::keys;
 use crate::error::StealthError;
+use crate::util::{ensure_rent_exempt_payout, safe_transfer_lamports};
 use crate::state::{
-    PrivacyPool, NullifierRecord, StealthAnnouncement, Relayer, WithdrawalCommitment,
+    CommitmentLeaf, PrivacyPool, PoolConfig, NullifierRecord, StealthAnnouncement, Relayer, WithdrawalCommitment,
+    AnnouncementLog, AnnouncementEntry, AnnouncementIndex,
 };
+use crate::instructions::announcement_index::index_announcement;
+use crate::zk::verifier::{OracleAttestation, VerificationOracle, verify_proof_with_oracle};
+
+/// Seed prefix of the announcements created by unified withdrawals
+pub const UNIFIED_ANNOUNCEMENT_SEED: &[u8] = b"unified_announcement";
 
 // ============================================================================
 // UNIFIED DEPOSIT
@@ -37,7 +59,7 @@ use crate::state::{
 
 /// Unified deposit into privacy pool
 #[derive(Accounts)]
-#[instruction(denomination: u64)]
+#[instruction(denomination: u64, commitment: [u8; 32])]
 pub struct UnifiedDeposit<'info> {
     /// Depositor
     #[account(mut)]
@@ -51,24 +73,47 @@ pub struct UnifiedDeposit<'info> {
     )]
     pub pool: AccountLoader<'info, PrivacyPool>,
 
+    /// Commitment leaf holding the encrypted note
+    ///
+    /// `init_if_needed` so an existing leaf is rejected as
+    /// `DuplicateCommitment` rather than failing inside the system program.
+    #[account(
+        init_if_needed,
+        payer = depositor,
+        space = CommitmentLeaf::SIZE,
+        seeds = [CommitmentLeaf::SEED, &denomination.to_le_bytes(), commitment.as_ref()],
+        bump,
+    )]
+    pub commitment_leaf: Account<'info, CommitmentLeaf>,
+
     pub system_program: Program<'info, System>,
 }
 
 /// Deposit to unified privacy pool
+///
+/// `encrypted_note` is the note encrypted to the recipient's scan key (see
+/// the module docs for the layout); it is stored on the commitment leaf.
 pub fn unified_deposit(
     ctx: Context<UnifiedDeposit>,
     denomination: u64,
     commitment: [u8; 32],
-    _encrypted_note: Option<[u8; 128]>,
+    encrypted_note: Option<[u8; 128]>,
 ) -> Result<()> {
     // Validate denomination
     require!(
+        denomination == 500_000_000 ||        // 0.5 SOL
         denomination == 1_000_000_000 ||      // 1 SOL
         denomination == 10_000_000_000 ||     // 10 SOL
+        denomination == 50_000_000_000 ||     // 50 SOL
         denomination == 100_000_000_000,      // 100 SOL
         StealthError::InvalidDenomination
     );
 
+    require!(
+        !ctx.accounts.commitment_leaf.is_recorded(),
+        StealthError::DuplicateCommitment
+    );
+
     let pool = ctx.accounts.pool.load()?;
 
     // Check pool is active
@@ -76,6 +121,7 @@ pub fn unified_deposit(
 
     // Store values before drop
     let leaf_index = pool.next_leaf_index;
+    let commitment_scheme = pool.commitment_scheme;
     drop(pool);
 
     // Transfer exact denomination to pool
@@ -92,9 +138,26 @@ pub fn unified_deposit(
 
     // Reload and update pool state
     let mut pool = ctx.accounts.pool.load_mut()?;
+    super::private_deposit::ensure_tree_capacity(
+        pool.next_leaf_index,
+        1,
+        crate::crypto::merkle::MERKLE_DEPTH,
+    )?;
     pool.deposit_count += 1;
     pool.total_deposited += denomination;
     pool.next_leaf_index += 1;
+    drop(pool);
+
+    let clock = Clock::get()?;
+    let commitment_leaf = &mut ctx.accounts.commitment_leaf;
+    commitment_leaf.commitment = commitment;
+    commitment_leaf.leaf_index = leaf_index;
+    commitment_leaf.timestamp = clock.unix_timestamp;
+    commitment_leaf.encrypted_note = encrypted_note.unwrap_or([0u8; 128]);
+    commitment_leaf.amount_commitment = [0u8; 33];
+    commitment_leaf.range_proof_hash = [0u8; 32];
+    commitment_leaf.slot = clock.slot;
+    commitment_leaf.bump = ctx.bumps.commitment_leaf;
 
     msg!("Unified deposit: {} lamports", denomination);
     msg!("Commitment: {:?}", &commitment[..8]);
@@ -106,7 +169,9 @@ pub fn unified_deposit(
         commitment,
         denomination,
         leaf_index: leaf_index as u32,
-        timestamp: Clock::get()?.unix_timestamp,
+        has_encrypted_note: encrypted_note.is_some(),
+        commitment_scheme,
+        timestamp: clock.unix_timestamp,
     });
 
     Ok(())
@@ -153,7 +218,7 @@ pub struct UnifiedWithdraw<'info> {
         payer = fee_payer,
         space = StealthAnnouncement::SIZE,
         seeds = [
-            b"unified_announcement",
+            UNIFIED_ANNOUNCEMENT_SEED,
             stealth_address.key().as_ref(),
         ],
         bump,
@@ -164,10 +229,40 @@ pub struct UnifiedWithdraw<'info> {
     #[account(mut)]
     pub fee_payer: Signer<'info>,
 
-    /// Optional relayer
+    /// Optional relayer (its stats are updated on success)
+    #[account(mut)]
     pub relayer: Option<Account<'info, Relayer>>,
 
+    /// Verification oracle the attestation is checked against
+    #[account(
+        seeds = [VerificationOracle::SEED],
+        bump = oracle.bump,
+    )]
+    pub oracle: Account<'info, VerificationOracle>,
+
+    /// Instructions sysvar for Ed25519 signature verification (production mode)
+    /// CHECK: Validated by address constraint
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::id())]
+    pub instructions_sysvar: AccountInfo<'info>,
+
     pub system_program: Program<'info, System>,
+
+    /// Optional: announcement log indexing this withdrawal by slot
+    #[account(
+        mut,
+        seeds = [AnnouncementLog::SEED, &announcement_log.log_id.to_le_bytes()],
+        bump = announcement_log.bump,
+        constraint = announcement_log.can_add_entry() @ StealthError::LogNotActive,
+    )]
+    pub announcement_log: Option<Account<'info, AnnouncementLog>>,
+
+    /// Optional: index of announcements in the current slot bucket
+    #[account(
+        mut,
+        seeds = [AnnouncementIndex::SEED, &announcement_index.bucket.to_le_bytes()],
+        bump = announcement_index.bump,
+    )]
+    pub announcement_index: Option<Account<'info, AnnouncementIndex>>,
 }
 
 /// Unified withdrawal proof (combines ZK proof + stealth derivation)
@@ -187,6 +282,27 @@ pub struct UnifiedWithdrawProof {
 
     /// Pre-computed stealth address (verified on-chain)
     pub expected_stealth_address: Pubkey,
+
+    /// Oracle attestation (required in production mode)
+    pub attestation: Option<OracleAttestation>,
+}
+
+impl UnifiedWithdrawProof {
+    /// Bytes the proof is verified against:
+    /// root || nullifier_hash || scan || spend || ephemeral || stealth_address
+    ///
+    /// The stealth components are bound so an attestation can't be replayed
+    /// to redirect the withdrawal to another recipient.
+    pub fn public_inputs_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(192);
+        bytes.extend_from_slice(&self.merkle_root);
+        bytes.extend_from_slice(&self.nullifier_hash);
+        bytes.extend_from_slice(&self.recipient_scan_pubkey);
+        bytes.extend_from_slice(&self.recipient_spend_pubkey);
+        bytes.extend_from_slice(&self.ephemeral_pubkey);
+        bytes.extend_from_slice(self.expected_stealth_address.as_ref());
+        bytes
+    }
 }
 
 /// Withdraw from pool to stealth address
@@ -216,6 +332,9 @@ pub fn unified_withdraw(
         StealthError::InvalidMerkleRoot
     );
 
+    // Verify the ZK proof (dev: structure only, production: Oracle attestation + Ed25519 introspection)
+    verify_unified_proof(&proof, &ctx.accounts.instructions_sysvar, &ctx.accounts.oracle)?;
+
     // Update pool state
     pool.withdrawal_count += 1;
     pool.total_withdrawn += denomination;
@@ -264,20 +383,37 @@ pub fn unified_withdraw(
         );
     }
 
+    // A registered relayer must be the fee payer and charge its advertised fee
+    if let Some(relayer) = ctx.accounts.relayer.as_mut() {
+        require!(
+            relayer.pubkey == ctx.accounts.fee_payer.key(),
+            StealthError::WrongRelayer
+        );
+        relayer.validate_withdraw_fee(denomination, relayer_fee)?;
+        relayer.record_relayed(clock.slot, clock.unix_timestamp);
+    }
+
     let recipient_amount = denomination.saturating_sub(relayer_fee);
+    ensure_rent_exempt_payout(recipient_amount, Rent::get()?.minimum_balance(0))?;
 
     // ========================================
     // STEP 5: Transfer to Stealth Address
     // ========================================
 
     // Transfer from pool to stealth address
-    **ctx.accounts.pool.to_account_info().try_borrow_mut_lamports()? -= recipient_amount;
-    **ctx.accounts.stealth_address.to_account_info().try_borrow_mut_lamports()? += recipient_amount;
+    safe_transfer_lamports(
+        &ctx.accounts.pool.to_account_info(),
+        &ctx.accounts.stealth_address.to_account_info(),
+        recipient_amount,
+    )?;
 
     // Pay relayer fee if applicable
     if relayer_fee > 0 {
-        **ctx.accounts.pool.to_account_info().try_borrow_mut_lamports()? -= relayer_fee;
-        **ctx.accounts.fee_payer.to_account_info().try_borrow_mut_lamports()? += relayer_fee;
+        safe_transfer_lamports(
+            &ctx.accounts.pool.to_account_info(),
+            &ctx.accounts.fee_payer.to_account_info(),
+            relayer_fee,
+        )?;
     }
 
     // ========================================
@@ -287,6 +423,7 @@ pub fn unified_withdraw(
     let announcement = &mut ctx.accounts.announcement;
     announcement.ephemeral_pubkey = proof.ephemeral_pubkey;
     announcement.stealth_address = ctx.accounts.stealth_address.key();
+    announcement.slot = clock.slot;
     announcement.timestamp = clock.unix_timestamp;
     announcement.amount = recipient_amount;
 
@@ -299,6 +436,19 @@ pub fn unified_withdraw(
     announcement.commitment = commitment;
     announcement.bump = ctx.bumps.announcement;
 
+    let announcement = ctx.accounts.announcement.key();
+    index_announcement(ctx.accounts.announcement_index.as_mut(), announcement, clock.slot)?;
+
+    if let Some(log) = ctx.accounts.announcement_log.as_mut() {
+        log.record(AnnouncementEntry {
+            ephemeral_pubkey: proof.ephemeral_pubkey,
+            stealth_address: ctx.accounts.stealth_address.key(),
+            commitment,
+            slot: clock.slot,
+            amount_hint: AnnouncementEntry::amount_to_hint(recipient_amount),
+        });
+    }
+
     msg!("Unified withdrawal complete");
     msg!("Stealth address: {}", ctx.accounts.stealth_address.key());
     msg!("Amount: {} lamports", recipient_amount);
@@ -317,6 +467,415 @@ pub fn unified_withdraw(
     Ok(())
 }
 
+/// Verify a unified withdrawal's proof against its public inputs
+///
+/// `proof_data` is fixed-size, so an all-zero buffer is the only malformed
+/// shape to reject before handing it to the verifier.
+#[inline(never)]
+pub(crate) fn verify_unified_proof(
+    proof: &UnifiedWithdrawProof,
+    instructions_sysvar: &AccountInfo,
+    oracle: &VerificationOracle,
+) -> Result<()> {
+    require!(proof.proof_data != [0u8; 256], StealthError::InvalidProof);
+
+    verify_proof_with_oracle(
+        &proof.proof_data,
+        &proof.public_inputs_bytes(),
+        proof.attestation.as_ref(),
+        instructions_sysvar,
+        oracle,
+    )
+}
+
+// ============================================================================
+// UNIFIED WITHDRAW MULTI (One Note -> Several Stealth Addresses)
+// ============================================================================
+//
+// Spends one note but splits the payout across up to MAX_WITHDRAW_SPLITS
+// stealth addresses, each with its own announcement, so e.g. a 10 SOL note
+// can pay three recipients 3 + 3 + 4 SOL.
+
+/// Most stealth addresses one note can be split across
+pub const MAX_WITHDRAW_SPLITS: usize = 4;
+
+/// Unified withdrawal split across several stealth addresses
+///
+/// Remaining accounts, one pair per split in order:
+/// `[stealth_address (mut), announcement (mut, uninitialized)]`, where the
+/// announcement is the PDA `[UNIFIED_ANNOUNCEMENT_SEED, stealth_address]`.
+#[derive(Accounts)]
+#[instruction(denomination: u64, proof: UnifiedWithdrawMultiProof)]
+pub struct UnifiedWithdrawMulti<'info> {
+    /// Pool to withdraw from
+    #[account(
+        mut,
+        seeds = [PrivacyPool::SEED, &denomination.to_le_bytes()],
+        bump,
+    )]
+    pub pool: AccountLoader<'info, PrivacyPool>,
+
+    /// Pool config (anonymity set, note age, withdrawal pause)
+    #[account(
+        seeds = [PoolConfig::SEED, &denomination.to_le_bytes()],
+        bump = config.bump,
+        constraint = !config.withdrawals_paused @ StealthError::WithdrawalsPaused,
+    )]
+    pub config: Account<'info, PoolConfig>,
+
+    /// Nullifier record (prevents double-spend)
+    #[account(
+        init,
+        payer = fee_payer,
+        space = NullifierRecord::SIZE,
+        seeds = [NullifierRecord::SEED, &proof.nullifier_hash],
+        bump,
+    )]
+    pub nullifier: Account<'info, NullifierRecord>,
+
+    /// Fee payer (relayer or user), also pays for the announcements
+    #[account(mut)]
+    pub fee_payer: Signer<'info>,
+
+    /// Optional relayer (its stats are updated on success)
+    #[account(mut)]
+    pub relayer: Option<Account<'info, Relayer>>,
+
+    /// Verification oracle the attestation is checked against
+    #[account(
+        seeds = [VerificationOracle::SEED],
+        bump = oracle.bump,
+    )]
+    pub oracle: Account<'info, VerificationOracle>,
+
+    /// Instructions sysvar for Ed25519 signature verification (production mode)
+    /// CHECK: Validated by address constraint
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::id())]
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Optional: announcement log indexing every split by slot
+    #[account(
+        mut,
+        seeds = [AnnouncementLog::SEED, &announcement_log.log_id.to_le_bytes()],
+        bump = announcement_log.bump,
+        constraint = announcement_log.can_add_entry() @ StealthError::LogNotActive,
+    )]
+    pub announcement_log: Option<Account<'info, AnnouncementLog>>,
+
+    /// Optional: index of announcements in the current slot bucket
+    #[account(
+        mut,
+        seeds = [AnnouncementIndex::SEED, &announcement_index.bucket.to_le_bytes()],
+        bump = announcement_index.bump,
+    )]
+    pub announcement_index: Option<Account<'info, AnnouncementIndex>>,
+}
+
+/// One recipient of a split withdrawal
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct WithdrawSplit {
+    /// Recipient's stealth meta-address
+    pub recipient_scan_pubkey: [u8; 32],
+    pub recipient_spend_pubkey: [u8; 32],
+
+    /// Ephemeral key for this split's stealth derivation
+    pub ephemeral_pubkey: [u8; 32],
+
+    /// Lamports sent to this split's stealth address
+    pub amount: u64,
+}
+
+/// Split withdrawal proof (ZK proof + one stealth derivation per split)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct UnifiedWithdrawMultiProof {
+    /// ZK proof that user knows a valid commitment
+    pub nullifier_hash: [u8; 32],
+    pub merkle_root: [u8; 32],
+    pub proof_data: [u8; 256],
+
+    /// Slot the note was deposited in, checked against
+    /// `PoolConfig::min_age_slots`
+    pub deposit_slot: u64,
+
+    /// Payout splits (1 to MAX_WITHDRAW_SPLITS)
+    pub splits: Vec<WithdrawSplit>,
+
+    /// Oracle attestation (required in production mode)
+    pub attestation: Option<OracleAttestation>,
+}
+
+impl UnifiedWithdrawMultiProof {
+    /// Bytes the proof is verified against:
+    /// root || nullifier_hash || deposit_slot (u64 LE)
+    /// || per split: scan || spend || ephemeral || amount (u64 LE)
+    ///
+    /// Every split is bound, so an attestation can't be replayed with
+    /// different recipients or a different division of the payout.
+    pub fn public_inputs_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(72 + self.splits.len() * 104);
+        bytes.extend_from_slice(&self.merkle_root);
+        bytes.extend_from_slice(&self.nullifier_hash);
+        bytes.extend_from_slice(&self.deposit_slot.to_le_bytes());
+        for split in &self.splits {
+            bytes.extend_from_slice(&split.recipient_scan_pubkey);
+            bytes.extend_from_slice(&split.recipient_spend_pubkey);
+            bytes.extend_from_slice(&split.ephemeral_pubkey);
+            bytes.extend_from_slice(&split.amount.to_le_bytes());
+        }
+        bytes
+    }
+}
+
+/// Check split amounts before any funds move
+///
+/// There must be 1 to `MAX_WITHDRAW_SPLITS` splits, each at least
+/// `min_split` (so the stealth address is rent-exempt), summing to exactly
+/// `denomination - relayer_fee`.
+pub fn validate_withdraw_splits(
+    amounts: &[u64],
+    denomination: u64,
+    relayer_fee: u64,
+    min_split: u64,
+) -> Result<()> {
+    require!(
+        !amounts.is_empty() && amounts.len() <= MAX_WITHDRAW_SPLITS,
+        StealthError::InvalidSplitCount
+    );
+    require!(
+        amounts.iter().all(|amount| *amount >= min_split),
+        StealthError::SplitBelowRentExempt
+    );
+
+    let total = amounts
+        .iter()
+        .try_fold(0u64, |sum, amount| sum.checked_add(*amount))
+        .ok_or(StealthError::SplitSumMismatch)?;
+    let expected = denomination
+        .checked_sub(relayer_fee)
+        .ok_or(StealthError::RelayerFeeTooHigh)?;
+    require!(total == expected, StealthError::SplitSumMismatch);
+
+    Ok(())
+}
+
+/// Verify a split withdrawal's proof against its public inputs (all splits)
+#[inline(never)]
+pub(crate) fn verify_unified_multi_proof(
+    proof: &UnifiedWithdrawMultiProof,
+    instructions_sysvar: &AccountInfo,
+    oracle: &VerificationOracle,
+) -> Result<()> {
+    require!(proof.proof_data != [0u8; 256], StealthError::InvalidProof);
+
+    verify_proof_with_oracle(
+        &proof.proof_data,
+        &proof.public_inputs_bytes(),
+        proof.attestation.as_ref(),
+        instructions_sysvar,
+        oracle,
+    )
+}
+
+/// Withdraw one note from the pool, split across several stealth addresses
+pub fn unified_withdraw_multi<'info>(
+    ctx: Context<'_, '_, 'info, 'info, UnifiedWithdrawMulti<'info>>,
+    denomination: u64,
+    proof: UnifiedWithdrawMultiProof,
+    relayer_fee: u64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+
+    // ========================================
+    // STEP 1: Validate Splits and Fees
+    // ========================================
+
+    if relayer_fee > 0 {
+        require!(
+            relayer_fee <= denomination / 10, // Max 10%
+            StealthError::RelayerFeeTooHigh
+        );
+    }
+
+    if let Some(relayer) = ctx.accounts.relayer.as_mut() {
+        require!(
+            relayer.pubkey == ctx.accounts.fee_payer.key(),
+            StealthError::WrongRelayer
+        );
+        relayer.validate_withdraw_fee(denomination, relayer_fee)?;
+        relayer.record_relayed(clock.slot, clock.unix_timestamp);
+    }
+
+    let amounts: Vec<u64> = proof.splits.iter().map(|split| split.amount).collect();
+    validate_withdraw_splits(&amounts, denomination, relayer_fee, Rent::get()?.minimum_balance(0))?;
+
+    require!(
+        ctx.remaining_accounts.len() == proof.splits.len() * 2,
+        StealthError::InvalidSplitCount
+    );
+
+    // ========================================
+    // STEP 2: Verify Pool State and Proof
+    // ========================================
+
+    let mut pool = ctx.accounts.pool.load_mut()?;
+
+    require!(pool.is_active, StealthError::PoolNotActive);
+
+    let pool_balance = pool.total_deposited.saturating_sub(pool.total_withdrawn);
+    require!(pool_balance >= denomination, StealthError::InsufficientPoolBalance);
+
+    require!(
+        pool.is_valid_root(&proof.merkle_root),
+        StealthError::InvalidMerkleRoot
+    );
+    require!(
+        ctx.accounts.config.anonymity_set_reached(pool.deposit_count),
+        StealthError::AnonymitySetTooSmall
+    );
+    require!(
+        ctx.accounts.config.note_old_enough(proof.deposit_slot, clock.slot),
+        StealthError::WithdrawalTooSoon
+    );
+
+    // Verify the ZK proof over every split before anything is written
+    verify_unified_multi_proof(&proof, &ctx.accounts.instructions_sysvar, &ctx.accounts.oracle)?;
+
+    pool.withdrawal_count += 1;
+    pool.total_withdrawn += denomination;
+
+    drop(pool);
+
+    // ========================================
+    // STEP 3: Record Nullifier (prevents double-spend)
+    // ========================================
+
+    let nullifier = &mut ctx.accounts.nullifier;
+    nullifier.nullifier_hash = proof.nullifier_hash;
+    nullifier.spent_at = clock.unix_timestamp;
+    nullifier.bump = ctx.bumps.nullifier;
+
+    // ========================================
+    // STEP 4: Pay and Announce Each Split
+    // ========================================
+
+    let pool_info = ctx.accounts.pool.to_account_info();
+    let mut stealth_addresses = Vec::with_capacity(proof.splits.len());
+
+    for (split, accounts) in proof.splits.iter().zip(ctx.remaining_accounts.chunks(2)) {
+        let stealth_info = &accounts[0];
+        let announcement_info = &accounts[1];
+
+        let computed_stealth = keys::derive_stealth_address(
+            &split.recipient_scan_pubkey,
+            &split.recipient_spend_pubkey,
+            &split.ephemeral_pubkey,
+        )?;
+        require!(
+            computed_stealth == stealth_info.key(),
+            StealthError::AddressMismatch
+        );
+
+        safe_transfer_lamports(&pool_info, stealth_info, split.amount)?;
+
+        let commitment = keys::compute_stealth_commitment(
+            &split.recipient_scan_pubkey,
+            &split.recipient_spend_pubkey,
+            &split.ephemeral_pubkey,
+        );
+
+        create_split_announcement(
+            &ctx.accounts.fee_payer,
+            &ctx.accounts.system_program,
+            announcement_info,
+            StealthAnnouncement {
+                ephemeral_pubkey: split.ephemeral_pubkey,
+                stealth_address: computed_stealth,
+                commitment,
+                amount: split.amount,
+                token_mint: Pubkey::default(),
+                slot: clock.slot,
+                timestamp: clock.unix_timestamp,
+                bump: 0,
+            },
+        )?;
+        index_announcement(ctx.accounts.announcement_index.as_mut(), announcement_info.key(), clock.slot)?;
+
+        if let Some(log) = ctx.accounts.announcement_log.as_mut() {
+            log.record(AnnouncementEntry {
+                ephemeral_pubkey: split.ephemeral_pubkey,
+                stealth_address: computed_stealth,
+                commitment,
+                slot: clock.slot,
+                amount_hint: AnnouncementEntry::amount_to_hint(split.amount),
+            });
+        }
+
+        stealth_addresses.push(computed_stealth);
+    }
+
+    // Pay relayer fee if applicable
+    if relayer_fee > 0 {
+        safe_transfer_lamports(&pool_info, &ctx.accounts.fee_payer.to_account_info(), relayer_fee)?;
+    }
+
+    msg!("Unified split withdrawal complete");
+    msg!("Splits: {}", stealth_addresses.len());
+
+    emit!(UnifiedWithdrawMultiEvent {
+        pool: ctx.accounts.pool.key(),
+        stealth_addresses,
+        nullifier_hash: proof.nullifier_hash,
+        denomination,
+        relayer_fee,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Create and fill the announcement PDA for one split
+///
+/// Remaining accounts can't use Anchor's `init`, so this does the same by
+/// hand: check the PDA, create it owned by this program, write the account.
+fn create_split_announcement<'info>(
+    fee_payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    announcement_info: &AccountInfo<'info>,
+    mut announcement: StealthAnnouncement,
+) -> Result<()> {
+    let stealth_address = announcement.stealth_address;
+    let (expected, bump) = Pubkey::find_program_address(
+        &[UNIFIED_ANNOUNCEMENT_SEED, stealth_address.as_ref()],
+        &crate::ID,
+    );
+    require!(
+        announcement_info.key() == expected,
+        StealthError::AddressMismatch
+    );
+
+    system_program::create_account(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            system_program::CreateAccount {
+                from: fee_payer.to_account_info(),
+                to: announcement_info.clone(),
+            },
+            &[&[UNIFIED_ANNOUNCEMENT_SEED, stealth_address.as_ref(), &[bump]]],
+        ),
+        Rent::get()?.minimum_balance(StealthAnnouncement::SIZE),
+        StealthAnnouncement::SIZE as u64,
+        &crate::ID,
+    )?;
+
+    announcement.bump = bump;
+    let mut data = announcement_info.try_borrow_mut_data()?;
+    announcement.try_serialize(&mut &mut data[..])?;
+
+    Ok(())
+}
+
 // ============================================================================
 // UNIFIED WITHDRAW WITH COMMIT-REVEAL (Maximum Privacy)
 // ============================================================================
@@ -433,7 +992,7 @@ pub struct UnifiedReveal<'info> {
         payer = fee_payer,
         space = StealthAnnouncement::SIZE,
         seeds = [
-            b"unified_announcement",
+            UNIFIED_ANNOUNCEMENT_SEED,
             stealth_address.key().as_ref(),
         ],
         bump,
@@ -448,6 +1007,14 @@ pub struct UnifiedReveal<'info> {
     pub relayer: Option<Account<'info, Relayer>>,
 
     pub system_program: Program<'info, System>,
+
+    /// Optional: index of announcements in the current slot bucket
+    #[account(
+        mut,
+        seeds = [AnnouncementIndex::SEED, &announcement_index.bucket.to_le_bytes()],
+        bump = announcement_index.bump,
+    )]
+    pub announcement_index: Option<Account<'info, AnnouncementIndex>>,
 }
 
 /// Reveal parameters for unified withdrawal
@@ -581,13 +1148,19 @@ pub fn unified_reveal(
     let recipient_amount = denomination.saturating_sub(relayer_fee);
 
     // Transfer to stealth address
-    **ctx.accounts.pool.to_account_info().try_borrow_mut_lamports()? -= recipient_amount;
-    **ctx.accounts.stealth_address.to_account_info().try_borrow_mut_lamports()? += recipient_amount;
+    safe_transfer_lamports(
+        &ctx.accounts.pool.to_account_info(),
+        &ctx.accounts.stealth_address.to_account_info(),
+        recipient_amount,
+    )?;
 
     // Pay relayer
     if relayer_fee > 0 {
-        **ctx.accounts.pool.to_account_info().try_borrow_mut_lamports()? -= relayer_fee;
-        **ctx.accounts.fee_payer.to_account_info().try_borrow_mut_lamports()? += relayer_fee;
+        safe_transfer_lamports(
+            &ctx.accounts.pool.to_account_info(),
+            &ctx.accounts.fee_payer.to_account_info(),
+            relayer_fee,
+        )?;
     }
 
     // ========================================
@@ -606,6 +1179,9 @@ pub fn unified_reveal(
     );
     announcement.bump = ctx.bumps.announcement;
 
+    let announcement = ctx.accounts.announcement.key();
+    index_announcement(ctx.accounts.announcement_index.as_mut(), announcement, clock.slot)?;
+
     msg!("Unified reveal + withdrawal complete");
     msg!("Stealth address: {}", ctx.accounts.stealth_address.key());
 
@@ -632,6 +1208,10 @@ pub struct UnifiedDepositEvent {
     pub commitment: [u8; 32],
     pub denomination: u64,
     pub leaf_index: u32,
+    /// Whether the commitment leaf carries an encrypted note
+    pub has_encrypted_note: bool,
+    /// `CommitmentScheme` id of the pool
+    pub commitment_scheme: u8,
     pub timestamp: i64,
 }
 
@@ -645,3 +1225,13 @@ pub struct UnifiedWithdrawEvent {
     pub relayer_fee: u64,
     pub timestamp: i64,
 }
+
+#[event]
+pub struct UnifiedWithdrawMultiEvent {
+    pub pool: Pubkey,
+    pub stealth_addresses: Vec<Pubkey>,
+    pub nullifier_hash: [u8; 32],
+    pub denomination: u64,
+    pub relayer_fee: u64,
+    pub timestamp: i64,
+}
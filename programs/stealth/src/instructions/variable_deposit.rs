@@ -0,0 +1,313 @@
+//! Variable-Amount Deposit Instruction
+//!
+//! Variant of `private_deposit` for a single variable-amount pool. Instead of a
+//! fixed denomination, each leaf carries a 33-byte Pedersen commitment to its
+//! amount, backed by a range proof attestation (0 <= amount <= u64::MAX)
+//! from a verifier trusted by the `VerificationOracle`, checked with
+//! `verify_range_proof_attestation_with_sysvar`.
+//!
+//! The variable pool is a `PrivacyPool` whose denomination is
+//! `PrivacyPool::VARIABLE_POOL_DENOMINATION`, so it shares the tree, PDA
+//! layout and `CommitmentLeaf` format with the fixed pools.
+//!
+//! Note: the lamport transfer itself is public, so the deposit amount is
+//! visible on the way in; the commitment is what later spends are checked
+//! against. Only the deposit half is implemented here, and every withdraw
+//! path pays a fixed denomination, so SOL deposited now couldn't be taken
+//! out again: both instructions fail with `VariablePoolDisabled` unless the
+//! program is built with the `variable-pool` feature, until a matching
+//! withdrawal exists.
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::state::privacy_pool::{PrivacyPool, CommitmentLeaf, CommitmentScheme, DenominationRegistry, PoolConfig};
+use crate::crypto::merkle::{compute_zero_hashes_poseidon, MERKLE_DEPTH};
+use crate::zk::verifier::{verify_range_proof_attestation_with_sysvar, RangeProofAttestation, VerificationOracle};
+use crate::error::StealthError;
+use super::private_deposit::{
+    emit_root_checkpoint, init_filled_subtrees_with_zeros, insert_commitment_to_tree_zc, AmountCommitmentData,
+    PoolInitialized,
+};
+
+/// Initialize the variable-amount privacy pool
+#[derive(Accounts)]
+pub struct InitializeVariablePool<'info> {
+    /// Denomination registry authority (the protocol admin)
+    #[account(
+        mut,
+        constraint = authority.key() == registry.authority @ StealthError::Unauthorized,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [DenominationRegistry::SEED],
+        bump = registry.bump,
+    )]
+    pub registry: Account<'info, DenominationRegistry>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<PrivacyPool>(),
+        seeds = [PrivacyPool::SEED, &PrivacyPool::VARIABLE_POOL_DENOMINATION.to_le_bytes()],
+        bump,
+    )]
+    pub pool: AccountLoader<'info, PrivacyPool>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = PoolConfig::SIZE,
+        seeds = [PoolConfig::SEED, &PrivacyPool::VARIABLE_POOL_DENOMINATION.to_le_bytes()],
+        bump,
+    )]
+    pub config: Account<'info, PoolConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Reject variable pool instructions in builds without `variable-pool`
+fn ensure_variable_pool_enabled() -> Result<()> {
+    require!(cfg!(feature = "variable-pool"), StealthError::VariablePoolDisabled);
+    Ok(())
+}
+
+/// Initialize the variable-amount pool
+#[inline(never)]
+pub fn initialize_variable_pool(ctx: Context<InitializeVariablePool>) -> Result<()> {
+    ensure_variable_pool_enabled()?;
+
+    let mut pool = ctx.accounts.pool.load_init()?;
+    let config = &mut ctx.accounts.config;
+
+    let zeros = compute_zero_hashes_poseidon::<MERKLE_DEPTH>();
+
+    pool.authority = ctx.accounts.authority.key();
+    pool.denomination = PrivacyPool::VARIABLE_POOL_DENOMINATION;
+    pool.merkle_root = zeros[MERKLE_DEPTH];
+    pool.next_leaf_index = 0;
+    pool.total_deposited = 0;
+    pool.total_withdrawn = 0;
+    pool.deposit_count = 0;
+    pool.withdrawal_count = 0;
+    pool.is_active = true;
+    pool.root_history_index = 0;
+    pool.token_mint = Pubkey::default(); // Native SOL pool
+    pool.pending_authority = Pubkey::default();
+    pool.commitment_scheme = CommitmentScheme::Poseidon4.id();
+    pool.bump = ctx.bumps.pool;
+
+    init_filled_subtrees_with_zeros(&mut pool, &zeros);
+
+    // Amounts vary, so min/max actually apply here
+    config.authority = ctx.accounts.authority.key();
+    config.min_deposit = PoolConfig::DEFAULT_MIN_DEPOSIT;
+    config.max_deposit = PoolConfig::DEFAULT_MAX_DEPOSIT;
+    config.fee_bps = PoolConfig::DEFAULT_FEE_BPS;
+    config.fee_recipient = ctx.accounts.authority.key();
+    config.deposits_paused = false;
+    config.withdrawals_paused = false;
+    config.min_anonymity_set = PoolConfig::DEFAULT_MIN_ANONYMITY_SET;
+    config.protocol_fee_bps = PoolConfig::DEFAULT_PROTOCOL_FEE_BPS;
+    config.relayer_fee_bps = PoolConfig::DEFAULT_RELAYER_FEE_BPS;
+    config.min_age_slots = PoolConfig::DEFAULT_MIN_AGE_SLOTS;
+    config.pending_authority = Pubkey::default();
+    config.bump = ctx.bumps.config;
+
+    emit!(PoolInitialized {
+        pool: ctx.accounts.pool.key(),
+        denomination: PrivacyPool::VARIABLE_POOL_DENOMINATION,
+        token_mint: Pubkey::default(),
+        commitment_scheme: CommitmentScheme::Poseidon4.id(),
+    });
+
+    msg!("Variable-amount privacy pool initialized");
+    msg!("Initial root: {:?}", pool.merkle_root);
+
+    Ok(())
+}
+
+/// Variable-amount deposit accounts
+#[derive(Accounts)]
+#[instruction(amount: u64, commitment: [u8; 32])]
+pub struct VariableDeposit<'info> {
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PrivacyPool::SEED, &PrivacyPool::VARIABLE_POOL_DENOMINATION.to_le_bytes()],
+        bump,
+    )]
+    pub pool: AccountLoader<'info, PrivacyPool>,
+
+    #[account(
+        seeds = [PoolConfig::SEED, &PrivacyPool::VARIABLE_POOL_DENOMINATION.to_le_bytes()],
+        bump = config.bump,
+        constraint = !config.deposits_paused @ StealthError::DepositsPaused,
+    )]
+    pub config: Account<'info, PoolConfig>,
+
+    #[account(
+        init,
+        payer = depositor,
+        space = CommitmentLeaf::SIZE,
+        seeds = [
+            CommitmentLeaf::SEED,
+            &PrivacyPool::VARIABLE_POOL_DENOMINATION.to_le_bytes(),
+            commitment.as_ref(),
+        ],
+        bump,
+    )]
+    pub commitment_leaf: Account<'info, CommitmentLeaf>,
+
+    /// Optional: fee recipient
+    /// CHECK: Validated against config
+    #[account(
+        mut,
+        constraint = fee_recipient.key() == config.fee_recipient @ StealthError::InvalidFeeRecipient,
+    )]
+    pub fee_recipient: Option<AccountInfo<'info>>,
+
+    /// Oracle holding the verifiers trusted to attest range proofs
+    #[account(
+        seeds = [VerificationOracle::SEED],
+        bump = oracle.bump,
+    )]
+    pub oracle: Account<'info, VerificationOracle>,
+
+    /// Instructions sysvar for Ed25519 signature verification
+    /// CHECK: Sysvar account
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Deposit a variable amount with a range-proven Pedersen commitment
+///
+/// # Arguments
+/// * `amount` - Lamports to deposit (within config min/max)
+/// * `commitment` - The commitment hash: Poseidon(nullifier, secret)
+/// * `amount_commitment` - Pedersen commitment to the amount and its range proof hash
+/// * `attestation` - Oracle attestation that the Pedersen commitment is in range
+/// * `encrypted_note` - Optional encrypted note for recipient
+#[inline(never)]
+pub fn variable_deposit(
+    ctx: Context<VariableDeposit>,
+    amount: u64,
+    commitment: [u8; 32],
+    amount_commitment: AmountCommitmentData,
+    attestation: RangeProofAttestation,
+    encrypted_note: Option<[u8; 128]>,
+) -> Result<()> {
+    ensure_variable_pool_enabled()?;
+
+    let config = &ctx.accounts.config;
+    let clock = Clock::get()?;
+
+    require!(amount >= config.min_deposit, StealthError::DepositTooSmall);
+    require!(amount <= config.max_deposit, StealthError::DepositTooLarge);
+
+    {
+        let pool = ctx.accounts.pool.load()?;
+        require!(pool.is_active, StealthError::PoolNotActive);
+    }
+
+    // Compressed point: 0x02/0x03 prefix
+    require!(
+        amount_commitment.commitment[0] == 0x02 || amount_commitment.commitment[0] == 0x03,
+        StealthError::InvalidPedersenCommitment
+    );
+    require!(
+        amount_commitment.range_proof_hash != [0u8; 32],
+        StealthError::MissingRangeProof
+    );
+
+    // Checks the verifier is trusted, commitment binding, the full
+    // (0, u64::MAX) range, freshness and, in production, the verifier's
+    // Ed25519 signature over the range proof hash and timestamp
+    verify_range_proof_attestation_with_sysvar(
+        &amount_commitment.commitment,
+        &amount_commitment.range_proof_hash,
+        &attestation,
+        &ctx.accounts.instructions_sysvar,
+        &ctx.accounts.oracle,
+    )?;
+
+    let fee = (amount as u128)
+        .checked_mul(config.fee_bps as u128)
+        .ok_or(StealthError::ArithmeticOverflow)?
+        .checked_div(10_000)
+        .ok_or(StealthError::ArithmeticOverflow)? as u64;
+    let deposit_amount = amount
+        .checked_sub(fee)
+        .ok_or(StealthError::ArithmeticUnderflow)?;
+
+    transfer_to_pool(&ctx, deposit_amount)?;
+    if fee > 0 {
+        transfer_fee(&ctx, fee)?;
+    }
+
+    let leaf_index;
+    let new_root;
+    {
+        let mut pool = ctx.accounts.pool.load_mut()?;
+        leaf_index = insert_commitment_to_tree_zc(&mut pool, commitment)?;
+        pool.total_deposited = pool.total_deposited
+            .checked_add(deposit_amount)
+            .ok_or(StealthError::ArithmeticOverflow)?;
+        pool.deposit_count = pool.deposit_count
+            .checked_add(1)
+            .ok_or(StealthError::ArithmeticOverflow)?;
+        new_root = pool.merkle_root;
+        emit_root_checkpoint(&pool, clock.slot);
+    }
+
+    {
+        let commitment_leaf = &mut ctx.accounts.commitment_leaf;
+        commitment_leaf.commitment = commitment;
+        commitment_leaf.leaf_index = leaf_index;
+        commitment_leaf.timestamp = clock.unix_timestamp;
+        commitment_leaf.encrypted_note = encrypted_note.unwrap_or([0u8; 128]);
+        commitment_leaf.amount_commitment = amount_commitment.commitment;
+        commitment_leaf.range_proof_hash = amount_commitment.range_proof_hash;
+        commitment_leaf.slot = clock.slot;
+        commitment_leaf.bump = ctx.bumps.commitment_leaf;
+    }
+
+    msg!("Variable-amount private deposit");
+    msg!("Leaf index: {}", leaf_index);
+    msg!("New root: {:?}", new_root);
+
+    Ok(())
+}
+
+/// Transfer funds to pool (separate stack frame)
+#[inline(never)]
+fn transfer_to_pool(ctx: &Context<VariableDeposit>, amount: u64) -> Result<()> {
+    let cpi_context = CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        system_program::Transfer {
+            from: ctx.accounts.depositor.to_account_info(),
+            to: ctx.accounts.pool.to_account_info(),
+        },
+    );
+    system_program::transfer(cpi_context, amount)
+}
+
+/// Transfer fee if applicable (separate stack frame)
+#[inline(never)]
+fn transfer_fee(ctx: &Context<VariableDeposit>, fee: u64) -> Result<()> {
+    if let Some(fee_recipient) = &ctx.accounts.fee_recipient {
+        let fee_cpi = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.depositor.to_account_info(),
+                to: fee_recipient.to_account_info(),
+            },
+        );
+        system_program::transfer(fee_cpi, fee)?;
+    }
+    Ok(())
+}
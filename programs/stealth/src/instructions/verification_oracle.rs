@@ -0,0 +1,128 @@
+//! Verification Oracle Administration
+//!
+//! Lets the oracle authority respond to a compromised verifier without a
+//! redeploy: trust or distrust individual verifiers, or switch the whole
+//! oracle off so every attestation is rejected until it is re-enabled.
+
+use anchor_lang::prelude::*;
+use crate::error::StealthError;
+use crate::zk::verifier::VerificationOracle;
+
+/// Accounts for initialize_verification_oracle instruction
+#[derive(Accounts)]
+pub struct InitializeVerificationOracle<'info> {
+    /// Authority that will manage the oracle
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = VerificationOracle::SIZE,
+        seeds = [VerificationOracle::SEED],
+        bump
+    )]
+    pub oracle: Account<'info, VerificationOracle>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Create the verification oracle with no trusted verifiers yet
+///
+/// # Arguments
+/// * `vk_hash` - Hash of the circuit's verification key
+/// * `vk_version` - Verification key version attestations must carry
+/// * `required_attestations` - Attestations needed for threshold verification
+/// * `max_attestation_age_seconds` - Freshness window (non-positive = default)
+pub fn initialize_verification_oracle(
+    ctx: Context<InitializeVerificationOracle>,
+    vk_hash: [u8; 32],
+    vk_version: u32,
+    required_attestations: u8,
+    max_attestation_age_seconds: i64,
+) -> Result<()> {
+    let oracle = &mut ctx.accounts.oracle;
+    oracle.authority = ctx.accounts.authority.key();
+    oracle.trusted_verifiers = Vec::new();
+    oracle.vk_hash = vk_hash;
+    oracle.vk_version = vk_version;
+    oracle.required_attestations = required_attestations;
+    oracle.is_active = true;
+    oracle.max_attestation_age_seconds = max_attestation_age_seconds;
+    oracle.pending_authority = Pubkey::default();
+    oracle.bump = ctx.bumps.oracle;
+
+    msg!("Verification oracle initialized");
+    Ok(())
+}
+
+/// Accounts shared by the oracle admin instructions
+#[derive(Accounts)]
+pub struct ManageVerificationOracle<'info> {
+    /// Oracle authority
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [VerificationOracle::SEED],
+        bump = oracle.bump,
+        constraint = oracle.authority == authority.key() @ StealthError::Unauthorized,
+    )]
+    pub oracle: Account<'info, VerificationOracle>,
+}
+
+/// Trust a verifier's attestations
+pub fn add_verifier(ctx: Context<ManageVerificationOracle>, verifier: Pubkey) -> Result<()> {
+    let oracle = &mut ctx.accounts.oracle;
+    oracle.add_verifier(verifier)?;
+
+    emit!(VerifierAdded {
+        verifier,
+        verifier_count: oracle.trusted_verifiers.len() as u8,
+    });
+
+    msg!("Verifier added: {}", verifier);
+    Ok(())
+}
+
+/// Stop trusting a verifier, e.g. after it signed a bad attestation
+pub fn remove_verifier(ctx: Context<ManageVerificationOracle>, verifier: Pubkey) -> Result<()> {
+    let oracle = &mut ctx.accounts.oracle;
+    oracle.remove_verifier(&verifier)?;
+
+    emit!(VerifierRemoved {
+        verifier,
+        verifier_count: oracle.trusted_verifiers.len() as u8,
+    });
+
+    msg!("Verifier removed: {}", verifier);
+    Ok(())
+}
+
+/// Kill switch: while inactive, every oracle attestation is rejected
+pub fn set_oracle_active(ctx: Context<ManageVerificationOracle>, is_active: bool) -> Result<()> {
+    let oracle = &mut ctx.accounts.oracle;
+    oracle.is_active = is_active;
+
+    emit!(OracleActiveChanged { is_active });
+
+    msg!("Verification oracle active: {}", is_active);
+    Ok(())
+}
+
+#[event]
+pub struct VerifierAdded {
+    pub verifier: Pubkey,
+    pub verifier_count: u8,
+}
+
+#[event]
+pub struct VerifierRemoved {
+    pub verifier: Pubkey,
+    pub verifier_count: u8,
+}
+
+#[event]
+pub struct OracleActiveChanged {
+    pub is_active: bool,
+}
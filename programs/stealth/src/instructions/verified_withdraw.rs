@@ -17,11 +17,14 @@
 //! Compute Budget: ~200k CUs (vs 1.4M+ for on-chain Poseidon)
 
 use anchor_lang::prelude::*;
+use anchor_lang::system_program;
 use borsh::BorshDeserialize;
 use crate::state::privacy_pool::{PrivacyPool, NullifierRecord, PoolConfig};
 use crate::crypto::merkle::{compute_zero_hashes_poseidon, MERKLE_DEPTH, merkle_hash_2};
 use crate::error::StealthError;
+use crate::util::safe_transfer_lamports;
 use crate::zk::{Groth16Proof, VerificationKey, StoredVerificationKey, verify_groth16};
+use super::private_withdraw::{transfer_withdrawal_funds_zc, FeePayout};
 
 /// Public inputs for the Groth16 withdrawal circuit
 /// Must match exactly what the circuit expects
@@ -35,6 +38,10 @@ pub struct Groth16WithdrawInputs {
     pub recipient: [u8; 32],
     /// Amount in lamports (as field element)
     pub amount: [u8; 32],
+    /// Slot the note was deposited in, checked against
+    /// `PoolConfig::min_age_slots`. Not a circuit input yet, so the proof
+    /// doesn't bind it: the delay only holds until the circuit exposes it.
+    pub deposit_slot: u64,
 }
 
 impl Groth16WithdrawInputs {
@@ -42,27 +49,39 @@ impl Groth16WithdrawInputs {
     /// Note: All field elements must be big-endian for alt_bn128 syscalls
     pub fn to_field_elements(&self) -> [[u8; 32]; 4] {
         // Amount is stored as LE for u64 reading, but ZK verifier needs BE
-        let mut amount_be = [0u8; 32];
-        // Read amount as LE u64, then convert to BE 32-byte field element
         let amount_u64 = u64::from_le_bytes(self.amount[0..8].try_into().unwrap());
-        let amount_bytes = amount_u64.to_be_bytes();
-        // Place in the last 8 bytes (BE format)
-        amount_be[24..32].copy_from_slice(&amount_bytes);
 
         [
             self.merkle_root,
             self.nullifier_hash,
             self.recipient,
-            amount_be,
+            amount_to_field(amount_u64),
         ]
     }
 
+    /// Check every 32-byte input is a canonical BN254 scalar (< r)
+    ///
+    /// A non-canonical encoding isn't rejected by the pairing check, it just
+    /// makes the proof fail to verify, so reject it up front with a clear error.
+    /// The amount is rebuilt from a u64 in `to_field_elements`, so it is
+    /// always canonical.
+    pub fn validate_canonical(&self) -> Result<()> {
+        for element in [&self.merkle_root, &self.nullifier_hash, &self.recipient] {
+            require!(
+                is_canonical_field_element(element),
+                StealthError::NonCanonicalFieldElement
+            );
+        }
+        Ok(())
+    }
+
     /// Create from pubkey and amount
     pub fn new(
         merkle_root: [u8; 32],
         nullifier_hash: [u8; 32],
         recipient: Pubkey,
         amount: u64,
+        deposit_slot: u64,
     ) -> Self {
         // Convert recipient pubkey to field element (32 bytes)
         let recipient_bytes = recipient.to_bytes();
@@ -76,6 +95,7 @@ impl Groth16WithdrawInputs {
             nullifier_hash,
             recipient: recipient_bytes,
             amount: amount_bytes,
+            deposit_slot,
         }
     }
 }
@@ -158,9 +178,15 @@ pub fn verified_withdraw(
 ) -> Result<()> {
     let clock = Clock::get()?;
 
+    public_inputs.validate_canonical()?;
+
     // 1. Load and deserialize verification key
     let vk = load_vk(&ctx.accounts.verification_key.vk_data)?;
-    msg!("Loaded verification key with {} IC points", vk.ic.len());
+    msg!(
+        "Loaded verification key v{} with {} IC points",
+        ctx.accounts.verification_key.version,
+        vk.ic.len()
+    );
 
     // 2. Verify pool is active and amount matches denomination
     let amount = {
@@ -170,6 +196,14 @@ pub fn verified_withdraw(
             pool.denomination == denomination,
             StealthError::AmountMustMatchDenomination
         );
+        require!(
+            ctx.accounts.config.anonymity_set_reached(pool.deposit_count),
+            StealthError::AnonymitySetTooSmall
+        );
+        require!(
+            ctx.accounts.config.note_old_enough(public_inputs.deposit_slot, clock.slot),
+            StealthError::WithdrawalTooSoon
+        );
         require!(
             pool.is_valid_root(&public_inputs.merkle_root),
             StealthError::InvalidMerkleRoot
@@ -189,20 +223,9 @@ pub fn verified_withdraw(
     // this specific recipient.
     let recipient_pubkey_bytes = ctx.accounts.recipient.key().to_bytes();
 
-    // Debug: print pubkey and input for comparison
-    msg!("Raw pubkey[0..4]: {:02x}{:02x}{:02x}{:02x}",
-        recipient_pubkey_bytes[0], recipient_pubkey_bytes[1],
-        recipient_pubkey_bytes[2], recipient_pubkey_bytes[3]);
-    msg!("Input[0..4]: {:02x}{:02x}{:02x}{:02x}",
-        public_inputs.recipient[0], public_inputs.recipient[1],
-        public_inputs.recipient[2], public_inputs.recipient[3]);
-
     // Verify that public_inputs.recipient is the correct reduction of the pubkey mod field
-    let recipient_valid = verify_field_reduction(&recipient_pubkey_bytes, &public_inputs.recipient);
-    msg!("Recipient field reduction valid: {}", recipient_valid);
-
     require!(
-        recipient_valid,
+        verify_field_reduction(&recipient_pubkey_bytes, &public_inputs.recipient),
         StealthError::InvalidRecipient
     );
 
@@ -251,9 +274,307 @@ pub fn verified_withdraw(
     Ok(())
 }
 
+// ============================================================================
+// BATCHED VERIFIED WITHDRAWAL
+// ============================================================================
+
+/// Most withdrawals one `verified_withdraw_batch` call accepts
+///
+/// Transaction size is the bound, not compute: each withdrawal adds 392
+/// bytes of instruction data and two accounts. Two fit in a 1232-byte
+/// transaction only as a v0 transaction with the pool accounts in an
+/// address lookup table (a legacy transaction fits one); a third would
+/// overflow on instruction data alone. Two Groth16 checks (~200k CUs each)
+/// need a compute budget instruction above the 200k default.
+pub const MAX_VERIFIED_BATCH: usize = 2;
+
+/// One withdrawal in a batch: its circuit inputs and proof
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct VerifiedWithdrawal {
+    pub public_inputs: Groth16WithdrawInputs,
+    pub proof: Groth16Proof,
+}
+
+/// Accounts for a batch of verified withdrawals from one pool
+///
+/// Remaining accounts: `[nullifier, recipient]` per withdrawal, in order.
+#[derive(Accounts)]
+#[instruction(denomination: u64)]
+pub struct VerifiedWithdrawBatch<'info> {
+    /// Anyone can submit (relayer support)
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    /// Privacy pool PDA
+    #[account(
+        mut,
+        seeds = [PrivacyPool::SEED, &denomination.to_le_bytes()],
+        bump,
+    )]
+    pub pool: AccountLoader<'info, PrivacyPool>,
+
+    /// Pool config
+    #[account(
+        seeds = [PoolConfig::SEED, &denomination.to_le_bytes()],
+        bump = config.bump,
+        constraint = !config.withdrawals_paused @ StealthError::WithdrawalsPaused,
+    )]
+    pub config: Account<'info, PoolConfig>,
+
+    /// Stored verification key
+    #[account(
+        seeds = [StoredVerificationKey::SEEDS],
+        bump = verification_key.bump,
+    )]
+    pub verification_key: Account<'info, StoredVerificationKey>,
+
+    /// Optional relayer fee recipient
+    /// CHECK: Relayer controls their fee recipient
+    #[account(mut)]
+    pub relayer_fee_recipient: Option<AccountInfo<'info>>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Protocol's cut of the relayer fee (required when `protocol_fee_bps` > 0)
+    /// CHECK: Validated against config
+    #[account(
+        mut,
+        constraint = protocol_fee_recipient.key() == config.fee_recipient @ StealthError::InvalidFeeRecipient,
+    )]
+    pub protocol_fee_recipient: Option<AccountInfo<'info>>,
+}
+
+/// Check a batch's shape and verify every proof, stopping at the first failure
+///
+/// The batch must hold 1 to `MAX_VERIFIED_BATCH` withdrawals with distinct
+/// nullifier hashes and canonical inputs. `verify` is `verify_groth16` with
+/// the stored key on-chain; nothing has been written when this fails.
+pub fn verify_batch<F>(withdrawals: &[VerifiedWithdrawal], mut verify: F) -> Result<()>
+where
+    F: FnMut(&Groth16Proof, &[[u8; 32]]) -> Result<bool>,
+{
+    require!(
+        !withdrawals.is_empty() && withdrawals.len() <= MAX_VERIFIED_BATCH,
+        StealthError::InvalidVerifiedBatchSize
+    );
+
+    for (i, withdrawal) in withdrawals.iter().enumerate() {
+        require!(
+            withdrawals[..i]
+                .iter()
+                .all(|earlier| earlier.public_inputs.nullifier_hash != withdrawal.public_inputs.nullifier_hash),
+            StealthError::DuplicateBatchNullifier
+        );
+        withdrawal.public_inputs.validate_canonical()?;
+    }
+
+    for (i, withdrawal) in withdrawals.iter().enumerate() {
+        let field_elements = withdrawal.public_inputs.to_field_elements();
+        if !verify(&withdrawal.proof, &field_elements)? {
+            msg!("Proof {} of {} failed verification", i + 1, withdrawals.len());
+            return err!(StealthError::InvalidProof);
+        }
+    }
+
+    Ok(())
+}
+
+/// Settle several withdrawals from one pool, each with its own Groth16 proof
+///
+/// Every withdrawal is checked and every proof verified before any nullifier
+/// is written or lamport moves, so one bad proof reverts the whole batch.
+/// `relayer_fee` is taken from each withdrawal and split per `PoolConfig`
+/// like `private_withdraw`.
+#[inline(never)]
+pub fn verified_withdraw_batch<'info>(
+    ctx: Context<'_, '_, 'info, 'info, VerifiedWithdrawBatch<'info>>,
+    denomination: u64,
+    withdrawals: Vec<VerifiedWithdrawal>,
+    relayer_fee: u64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+
+    require!(
+        ctx.remaining_accounts.len() == withdrawals.len() * 2,
+        StealthError::InvalidVerifiedBatchSize
+    );
+
+    // 1. Check every withdrawal against the pool before verifying anything
+    {
+        let pool = ctx.accounts.pool.load()?;
+        require!(pool.is_active, StealthError::PoolNotActive);
+        require!(
+            pool.denomination == denomination,
+            StealthError::AmountMustMatchDenomination
+        );
+        require!(
+            ctx.accounts.config.anonymity_set_reached(pool.deposit_count),
+            StealthError::AnonymitySetTooSmall
+        );
+
+        for (withdrawal, accounts) in withdrawals.iter().zip(ctx.remaining_accounts.chunks(2)) {
+            let inputs = &withdrawal.public_inputs;
+            require!(
+                ctx.accounts.config.note_old_enough(inputs.deposit_slot, clock.slot),
+                StealthError::WithdrawalTooSoon
+            );
+            require!(
+                pool.is_valid_root(&inputs.merkle_root),
+                StealthError::InvalidMerkleRoot
+            );
+            let proof_amount = u64::from_le_bytes(inputs.amount[0..8].try_into().unwrap());
+            require!(
+                proof_amount == denomination,
+                StealthError::AmountMustMatchDenomination
+            );
+            require!(
+                verify_field_reduction(&accounts[1].key().to_bytes(), &inputs.recipient),
+                StealthError::InvalidRecipient
+            );
+        }
+    }
+
+    // 2. Verify every proof
+    let vk = load_vk(&ctx.accounts.verification_key.vk_data)?;
+    verify_batch(&withdrawals, |proof, inputs| verify_groth16(proof, inputs, &vk))?;
+    msg!("{} Groth16 proofs verified", withdrawals.len());
+
+    // 3. Spend each nullifier and pay each recipient
+    let relayer = ctx.accounts.relayer.to_account_info();
+    let pool_info = ctx.accounts.pool.to_account_info();
+    for (withdrawal, accounts) in withdrawals.iter().zip(ctx.remaining_accounts.chunks(2)) {
+        create_nullifier_record(
+            &ctx.accounts.relayer,
+            &ctx.accounts.system_program,
+            &accounts[0],
+            denomination,
+            &withdrawal.public_inputs.nullifier_hash,
+            clock.unix_timestamp,
+        )?;
+
+        let fees = FeePayout::new(
+            &ctx.accounts.config,
+            denomination,
+            relayer_fee,
+            &relayer,
+            ctx.accounts.relayer_fee_recipient.as_ref(),
+            ctx.accounts.protocol_fee_recipient.as_ref(),
+        )?;
+        transfer_withdrawal_funds_zc(&pool_info, &accounts[1], denomination, &fees)?;
+    }
+
+    // 4. Update pool stats
+    let count = withdrawals.len() as u64;
+    {
+        let mut pool = ctx.accounts.pool.load_mut()?;
+        pool.total_withdrawn = denomination
+            .checked_mul(count)
+            .and_then(|total| pool.total_withdrawn.checked_add(total))
+            .ok_or(StealthError::ArithmeticOverflow)?;
+        pool.withdrawal_count = pool.withdrawal_count
+            .checked_add(count)
+            .ok_or(StealthError::ArithmeticOverflow)?;
+    }
+
+    msg!("Verified batch withdrawal complete");
+    msg!("Withdrawals: {} of {} lamports", count, denomination);
+
+    Ok(())
+}
+
+/// Create and fill the nullifier PDA for one batched withdrawal
+///
+/// Remaining accounts can't use Anchor's `init`, so this does the same by
+/// hand, including its handling of an address someone already sent lamports
+/// to: top up the rent shortfall, then allocate and assign, so a 1-lamport
+/// pre-fund can't block the withdrawal. A nullifier spent earlier already
+/// holds data and is rejected here.
+fn create_nullifier_record<'info>(
+    relayer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    nullifier_info: &AccountInfo<'info>,
+    denomination: u64,
+    nullifier_hash: &[u8; 32],
+    timestamp: i64,
+) -> Result<()> {
+    let (expected, bump) = NullifierRecord::derive_pda(denomination, nullifier_hash);
+    require!(
+        nullifier_info.key() == expected,
+        StealthError::AddressMismatch
+    );
+    require!(
+        nullifier_info.data_is_empty(),
+        StealthError::NullifierAlreadyUsed
+    );
+
+    let denomination_bytes = denomination.to_le_bytes();
+    let bump_bytes = [bump];
+    let signer_seeds: &[&[&[u8]]] = &[&[NullifierRecord::SEED, &denomination_bytes, nullifier_hash.as_ref(), &bump_bytes]];
+    let rent = Rent::get()?.minimum_balance(NullifierRecord::SIZE);
+    let current_lamports = nullifier_info.lamports();
+
+    if current_lamports == 0 {
+        system_program::create_account(
+            CpiContext::new_with_signer(
+                system_program.to_account_info(),
+                system_program::CreateAccount {
+                    from: relayer.to_account_info(),
+                    to: nullifier_info.clone(),
+                },
+                signer_seeds,
+            ),
+            rent,
+            NullifierRecord::SIZE as u64,
+            &crate::ID,
+        )?;
+    } else {
+        // create_account fails on a funded address, so build it in steps
+        let shortfall = rent.saturating_sub(current_lamports);
+        if shortfall > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: relayer.to_account_info(),
+                        to: nullifier_info.clone(),
+                    },
+                ),
+                shortfall,
+            )?;
+        }
+        system_program::allocate(
+            CpiContext::new_with_signer(
+                system_program.to_account_info(),
+                system_program::Allocate { account_to_allocate: nullifier_info.clone() },
+                signer_seeds,
+            ),
+            NullifierRecord::SIZE as u64,
+        )?;
+        system_program::assign(
+            CpiContext::new_with_signer(
+                system_program.to_account_info(),
+                system_program::Assign { account_to_assign: nullifier_info.clone() },
+                signer_seeds,
+            ),
+            &crate::ID,
+        )?;
+    }
+
+    let mut record = NullifierRecord {
+        nullifier_hash: [0u8; 32],
+        spent_at: 0,
+        bump: 0,
+    };
+    mark_nullifier_used(&mut record, nullifier_hash, timestamp, bump);
+    let mut data = nullifier_info.try_borrow_mut_data()?;
+    record.try_serialize(&mut &mut data[..])?;
+
+    Ok(())
+}
+
 /// Load verification key from stored bytes
 #[inline(never)]
-fn load_vk(vk_data: &[u8]) -> Result<VerificationKey> {
+pub(crate) fn load_vk(vk_data: &[u8]) -> Result<VerificationKey> {
     VerificationKey::try_from_slice(vk_data)
         .map_err(|_| error!(StealthError::DeserializationError))
 }
@@ -281,31 +602,72 @@ fn transfer_withdrawal<'info>(
     amount: u64,
     relayer_fee: u64,
 ) -> Result<()> {
-    let amount_after_fee = amount.saturating_sub(relayer_fee);
+    require!(relayer_fee <= amount, StealthError::RelayerFeeTooHigh);
+    let amount_after_fee = amount - relayer_fee;
 
     // Validate pool balance
-    let pool_balance = pool.to_account_info().lamports();
+    let pool_info = pool.to_account_info();
     require!(
-        pool_balance >= amount,
+        pool_info.lamports() >= amount,
         StealthError::InsufficientPoolBalance
     );
 
     // Transfer to recipient
-    **pool.to_account_info().try_borrow_mut_lamports()? -= amount;
-    **recipient.try_borrow_mut_lamports()? += amount_after_fee;
+    safe_transfer_lamports(&pool_info, recipient, amount_after_fee)?;
 
     // Pay relayer fee
     if relayer_fee > 0 {
-        if let Some(fee_recipient) = relayer_fee_recipient {
-            **fee_recipient.try_borrow_mut_lamports()? += relayer_fee;
-        } else {
-            **relayer.try_borrow_mut_lamports()? += relayer_fee;
-        }
+        let fee_recipient = relayer_fee_recipient
+            .cloned()
+            .unwrap_or_else(|| relayer.to_account_info());
+        safe_transfer_lamports(&pool_info, &fee_recipient, relayer_fee)?;
     }
 
     Ok(())
 }
 
+/// BN254 SCALAR FIELD modulus r split into two u128 parts (big-endian)
+/// r = 0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001
+/// NOTE: This is the SCALAR field (Fr), NOT the base field (Fq)!
+const MOD_HIGH: u128 = 0x30644e72e131a029_b85045b68181585d;
+const MOD_LOW: u128 = 0x2833e84879b97091_43e1f593f0000001;
+
+/// Whether a big-endian 32-byte value is below the BN254 scalar modulus
+pub(crate) fn is_canonical_field_element(bytes: &[u8; 32]) -> bool {
+    let high = u128::from_be_bytes(bytes[0..16].try_into().unwrap());
+    let low = u128::from_be_bytes(bytes[16..32].try_into().unwrap());
+    (high < MOD_HIGH) | ((high == MOD_HIGH) & (low < MOD_LOW))
+}
+
+/// A u64 amount as a big-endian field element (in the last 8 bytes)
+pub(crate) fn amount_to_field(amount: u64) -> [u8; 32] {
+    let mut field = [0u8; 32];
+    field[24..32].copy_from_slice(&amount.to_be_bytes());
+    field
+}
+
+/// Reduce a pubkey (read big-endian) modulo the BN254 scalar field
+///
+/// The counterpart of `verify_field_reduction` for when the program derives
+/// the circuit's recipient input itself. A pubkey is below 2^256 < 6r, so
+/// at most five subtractions of r are needed.
+pub(crate) fn pubkey_to_field(pubkey: &Pubkey) -> [u8; 32] {
+    let bytes = pubkey.to_bytes();
+    let mut high = u128::from_be_bytes(bytes[0..16].try_into().unwrap());
+    let mut low = u128::from_be_bytes(bytes[16..32].try_into().unwrap());
+
+    while (high > MOD_HIGH) | ((high == MOD_HIGH) & (low >= MOD_LOW)) {
+        let (diff_low, borrow) = low.overflowing_sub(MOD_LOW);
+        low = diff_low;
+        high = high - MOD_HIGH - borrow as u128;
+    }
+
+    let mut field = [0u8; 32];
+    field[0..16].copy_from_slice(&high.to_be_bytes());
+    field[16..32].copy_from_slice(&low.to_be_bytes());
+    field
+}
+
 /// Verify that `reduced` is the correct reduction of `pubkey` modulo the BN254 scalar field
 /// r = 21888242871839275222246405745257275088548364400416034343698204186575808495617
 ///
@@ -313,82 +675,38 @@ fn transfer_withdrawal<'info>(
 /// - reduced < r (it's a valid field element)
 /// - pubkey - reduced is a multiple of r (i.e., pubkey ≡ reduced (mod r))
 ///
-/// Since pubkey < 2^256 and r ≈ 2^254, we have pubkey - reduced < 12*r,
-/// so we just need to check if (pubkey - reduced) / r is an integer in [0, 11].
+/// The multiple k = (pubkey - reduced) / r is taken from the high limb in one
+/// division (any k up to 11 is accepted; since pubkey < 2^256, k <= 5 in practice).
+/// Comparisons are combined without early returns and nothing is logged.
 #[inline(never)]
-fn verify_field_reduction(pubkey: &[u8; 32], reduced: &[u8; 32]) -> bool {
-    // BN254 SCALAR FIELD modulus r split into two u128 parts (big-endian)
-    // r = 21888242871839275222246405745257275088548364400416034343698204186575808495617
-    // r = 0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001
-    // NOTE: This is the SCALAR field (Fr), NOT the base field (Fq)!
-    const MOD_HIGH: u128 = 0x30644e72e131a029_b85045b68181585d;
-    const MOD_LOW: u128 = 0x2833e84879b97091_43e1f593f0000001;
-
+pub(crate) fn verify_field_reduction(pubkey: &[u8; 32], reduced: &[u8; 32]) -> bool {
     // Convert inputs to (high, low) u128 pairs
     let pk_high = u128::from_be_bytes(pubkey[0..16].try_into().unwrap());
     let pk_low = u128::from_be_bytes(pubkey[16..32].try_into().unwrap());
     let rd_high = u128::from_be_bytes(reduced[0..16].try_into().unwrap());
     let rd_low = u128::from_be_bytes(reduced[16..32].try_into().unwrap());
 
-    // Debug: print all parts
-    msg!("pk_high: {:x}", pk_high);
-    msg!("pk_low: {:x}", pk_low);
-    msg!("rd_high: {:x}", rd_high);
-    msg!("rd_low: {:x}", rd_low);
-
-    // Check that reduced < modulus (it's a valid field element)
-    let reduced_valid = if rd_high < MOD_HIGH {
-        true
-    } else if rd_high > MOD_HIGH {
-        false
-    } else {
-        rd_low < MOD_LOW
-    };
-    msg!("reduced_valid: {}", reduced_valid);
-    if !reduced_valid {
-        return false;
-    }
+    // reduced < modulus (it's a valid field element)
+    let reduced_valid = is_canonical_field_element(reduced);
 
-    // Check that pubkey >= reduced
-    let pubkey_ge_reduced = if pk_high > rd_high {
-        true
-    } else if pk_high < rd_high {
-        false
-    } else {
-        pk_low >= rd_low
-    };
-    msg!("pubkey_ge_reduced: {}", pubkey_ge_reduced);
-    if !pubkey_ge_reduced {
-        return false;
-    }
+    // pubkey >= reduced
+    let pubkey_ge_reduced = (pk_high > rd_high) | ((pk_high == rd_high) & (pk_low >= rd_low));
 
-    // Compute diff = pubkey - reduced
+    // diff = pubkey - reduced (wraps when pubkey < reduced; masked out below)
     let (diff_low, borrow) = pk_low.overflowing_sub(rd_low);
-    let diff_high = pk_high.wrapping_sub(rd_high).wrapping_sub(if borrow { 1 } else { 0 });
-    msg!("diff_high: {:x}, diff_low: {:x}", diff_high, diff_low);
-
-    // Check if diff is a multiple of the modulus (diff = k * modulus for some k in [0, 11])
-    // We do this by checking if diff == 0, modulus, 2*modulus, ..., 11*modulus
-    let mut mult_high = 0u128;
-    let mut mult_low = 0u128;
-
-    for k in 0..12u32 {
-        // Debug: print mult values for first few iterations and k=5
-        if k <= 2 || k == 5 {
-            msg!("k={}: mult_high={:x}, mult_low={:x}", k, mult_high, mult_low);
-        }
-        if diff_high == mult_high && diff_low == mult_low {
-            msg!("Match at k={}", k);
-            return true;
-        }
-        // Add modulus for next iteration
-        let (new_low, carry) = mult_low.overflowing_add(MOD_LOW);
-        mult_high = mult_high.wrapping_add(MOD_HIGH).wrapping_add(if carry { 1 } else { 0 });
-        mult_low = new_low;
-    }
+    let diff_high = pk_high.wrapping_sub(rd_high).wrapping_sub(borrow as u128);
+
+    // If diff = k * r, the carry from k * MOD_LOW into the high limb is < k,
+    // far below MOD_HIGH, so the high limb alone determines k
+    let k = diff_high / MOD_HIGH;
+
+    // k * r as a (high, low) pair; k <= 5 so nothing overflows
+    let low_lo = (MOD_LOW & u64::MAX as u128) * k;
+    let low_hi = (MOD_LOW >> 64) * k;
+    let (mult_low, carry) = low_lo.overflowing_add(low_hi << 64);
+    let mult_high = MOD_HIGH * k + (low_hi >> 64) + carry as u128;
+
+    let is_multiple = (k < 12) & (diff_high == mult_high) & (diff_low == mult_low);
 
-    // Print what k=5 would be if computed
-    msg!("After loop: mult_high={:x} (this is 12*mod)", mult_high);
-    msg!("No match found after 12 iterations");
-    false
+    reduced_valid & pubkey_ge_reduced & is_multiple
 }
@@ -22,7 +22,8 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
 use crate::error::{StealthError, MIN_PAYMENT_LAMPORTS};
-use crate::state::{StealthAnnouncement, StealthRegistry};
+use crate::state::{AnnouncementIndex, StealthAnnouncement, StealthRegistry};
+use crate::instructions::announcement_index::index_announcement;
 use crate::zk::{Groth16Proof, StoredVerificationKey, verify_groth16, VerificationKey};
 
 /// Accounts for ZK-verified stealth send
@@ -75,6 +76,14 @@ pub struct VerifiedStealthSend<'info> {
 
     /// System program for account creation and transfers
     pub system_program: Program<'info, System>,
+
+    /// Optional: index of announcements in the current slot bucket
+    #[account(
+        mut,
+        seeds = [AnnouncementIndex::SEED, &announcement_index.bucket.to_le_bytes()],
+        bump = announcement_index.bump,
+    )]
+    pub announcement_index: Option<Account<'info, AnnouncementIndex>>,
 }
 
 /// ZK-verified stealth send
@@ -131,6 +140,9 @@ pub fn verified_stealth_send(
     announcement.timestamp = clock.unix_timestamp;
     announcement.bump = ctx.bumps.announcement;
 
+    let announcement = ctx.accounts.announcement.key();
+    index_announcement(ctx.accounts.announcement_index.as_mut(), announcement, clock.slot)?;
+
     msg!("ZK-verified payment sent: {} lamports", amount);
 
     Ok(())
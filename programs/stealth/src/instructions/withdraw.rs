@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use crate::util::safe_transfer_lamports;
 
 /// Accounts for withdrawing from a stealth address
 ///
@@ -44,8 +45,7 @@ pub fn withdraw(ctx: Context<Withdraw>) -> Result<()> {
     let transfer_amount = balance;
 
     // Transfer via direct lamport manipulation (more efficient than CPI)
-    **stealth_account.to_account_info().try_borrow_mut_lamports()? -= transfer_amount;
-    **destination.try_borrow_mut_lamports()? += transfer_amount;
+    safe_transfer_lamports(&stealth_account.to_account_info(), destination, transfer_amount)?;
 
     msg!(
         "Withdrawn {} lamports from {} to {}",
@@ -86,8 +86,7 @@ pub fn withdraw_partial(ctx: Context<WithdrawPartial>, amount: u64) -> Result<()
         crate::error::StealthError::InsufficientFunds
     );
 
-    **stealth_account.to_account_info().try_borrow_mut_lamports()? -= amount;
-    **destination.try_borrow_mut_lamports()? += amount;
+    safe_transfer_lamports(&stealth_account.to_account_info(), destination, amount)?;
 
     msg!(
         "Partial withdrawal: {} lamports from {} to {}",
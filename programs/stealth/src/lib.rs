@@ -39,12 +39,14 @@ pub mod crypto;
 pub mod error;
 pub mod instructions;
 pub mod state;
+pub mod util;
 pub mod zk;
 
 #[cfg(test)]
 mod tests;
 
 use instructions::*;
+use crypto::merkle::MERKLE_DEPTH;
 
 declare_id!("3D37zdZf1nQ9RtPsobc7kX6hR8SvieBbqQWBTZwhMzBT");
 
@@ -95,6 +97,13 @@ pub mod stealth {
         instructions::update_registry(ctx, scan_pubkey, spend_pubkey, label)
     }
 
+    /// Rename your stealth identity
+    ///
+    /// `label` must be UTF-8, at most 32 bytes; empty clears it.
+    pub fn update_label(ctx: Context<UpdateRegistry>, label: Vec<u8>) -> Result<()> {
+        instructions::update_label(ctx, label)
+    }
+
     // -------------------------------------------------------------------------
     // SEND: Private payment (deposit to pool)
     // -------------------------------------------------------------------------
@@ -121,6 +130,24 @@ pub mod stealth {
         instructions::unified_deposit(ctx, denomination, commitment, encrypted_note)
     }
 
+    /// Send a private SPL token payment
+    ///
+    /// Same as `send_private` but for token pools (e.g. USDC). The deposit
+    /// goes into the pool's token vault; the commitment joins the pool's tree.
+    ///
+    /// # Arguments
+    /// * `denomination` - Pool denomination in token base units
+    /// * `commitment` - Hash of (nullifier, secret) - generated by SDK
+    /// * `encrypted_note` - Optional encrypted note for recipient
+    pub fn send_private_token(
+        ctx: Context<PrivateTokenDeposit>,
+        denomination: u64,
+        commitment: [u8; 32],
+        encrypted_note: Option<[u8; 128]>,
+    ) -> Result<()> {
+        instructions::private_token_deposit(ctx, denomination, commitment, encrypted_note)
+    }
+
     // -------------------------------------------------------------------------
     // RECEIVE: Private withdrawal (to stealth address - AUTOMATIC)
     // -------------------------------------------------------------------------
@@ -149,6 +176,18 @@ pub mod stealth {
         instructions::unified_withdraw(ctx, denomination, proof, relayer_fee)
     }
 
+    /// Withdraw one note split across up to 4 stealth addresses
+    ///
+    /// Remaining accounts: `[stealth_address, announcement]` per split.
+    pub fn unified_withdraw_multi<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UnifiedWithdrawMulti<'info>>,
+        denomination: u64,
+        proof: UnifiedWithdrawMultiProof,
+        relayer_fee: u64,
+    ) -> Result<()> {
+        instructions::unified_withdraw_multi(ctx, denomination, proof, relayer_fee)
+    }
+
     // -------------------------------------------------------------------------
     // RECEIVE WITH TIMING PRIVACY: Commit-reveal for maximum privacy
     // -------------------------------------------------------------------------
@@ -219,6 +258,25 @@ pub mod stealth {
         instructions::revoke_view_key(ctx)
     }
 
+    // -------------------------------------------------------------------------
+    // SCANNING AIDS: Optional scan hints and announcement indexes
+    // -------------------------------------------------------------------------
+
+    /// Add a payment tag to the recipient's scan hint filter
+    ///
+    /// Links the payment to the recipient for anyone who knows their
+    /// meta-address; only for recipients who opted into faster scanning.
+    pub fn post_scan_hint(ctx: Context<PostScanHint>, scan_pubkey: [u8; 32], tag: [u8; 8]) -> Result<()> {
+        instructions::post_scan_hint(ctx, scan_pubkey, tag)
+    }
+
+    /// Create the announcement index for a slot bucket (permissionless)
+    ///
+    /// Lets `scan --from-slot` read only the buckets since its last scan.
+    pub fn initialize_announcement_index(ctx: Context<InitializeAnnouncementIndex>, bucket: u64) -> Result<()> {
+        instructions::initialize_announcement_index(ctx, bucket)
+    }
+
     // =========================================================================
     //
     //                    ADMIN / INFRASTRUCTURE
@@ -229,8 +287,21 @@ pub mod stealth {
     // =========================================================================
 
     /// Initialize a privacy pool (ADMIN)
-    pub fn initialize_pool(ctx: Context<InitializePool>, denomination: u64) -> Result<()> {
-        instructions::initialize_pool(ctx, denomination)
+    ///
+    /// `commitment_scheme`: 0 = Poseidon(nullifier, secret),
+    /// 1 = Poseidon(nullifier, secret, amount, recipient)
+    pub fn initialize_pool(ctx: Context<InitializePool>, denomination: u64, commitment_scheme: u8) -> Result<()> {
+        instructions::initialize_pool(ctx, denomination, commitment_scheme)
+    }
+
+    /// Initialize an SPL token privacy pool (ADMIN)
+    pub fn initialize_token_pool(ctx: Context<InitializeTokenPool>, denomination: u64) -> Result<()> {
+        instructions::initialize_token_pool(ctx, denomination)
+    }
+
+    /// Initialize the variable-amount privacy pool (ADMIN, `variable-pool` builds only)
+    pub fn initialize_variable_pool(ctx: Context<InitializeVariablePool>) -> Result<()> {
+        instructions::initialize_variable_pool(ctx)
     }
 
     /// Close a privacy pool (ADMIN) - for migration/cleanup
@@ -238,12 +309,59 @@ pub mod stealth {
         instructions::close_pool(ctx, denomination)
     }
 
+    /// Update pool config (ADMIN) - minimum anonymity set for withdrawals
+    pub fn update_pool_config(
+        ctx: Context<UpdatePoolConfig>,
+        denomination: u64,
+        min_anonymity_set: u64,
+    ) -> Result<()> {
+        instructions::update_pool_config(ctx, denomination, min_anonymity_set)
+    }
+
+    /// Update pool config (ADMIN) - protocol/relayer split of withdrawal fees
+    pub fn update_fee_split(
+        ctx: Context<UpdatePoolConfig>,
+        denomination: u64,
+        protocol_fee_bps: u16,
+        relayer_fee_bps: u16,
+    ) -> Result<()> {
+        instructions::update_fee_split(ctx, denomination, protocol_fee_bps, relayer_fee_bps)
+    }
+
+    /// Update pool config (ADMIN) - minimum deposit-to-withdrawal delay
+    pub fn update_min_age_slots(
+        ctx: Context<UpdatePoolConfig>,
+        denomination: u64,
+        min_age_slots: u64,
+    ) -> Result<()> {
+        instructions::update_min_age_slots(ctx, denomination, min_age_slots)
+    }
+
+    /// Pause or resume deposits into a pool (ADMIN)
+    pub fn set_deposits_paused(
+        ctx: Context<UpdatePoolConfig>,
+        denomination: u64,
+        paused: bool,
+    ) -> Result<()> {
+        instructions::set_deposits_paused(ctx, denomination, paused)
+    }
+
+    /// Pause or resume withdrawals from a pool (ADMIN)
+    pub fn set_withdrawals_paused(
+        ctx: Context<UpdatePoolConfig>,
+        denomination: u64,
+        paused: bool,
+    ) -> Result<()> {
+        instructions::set_withdrawals_paused(ctx, denomination, paused)
+    }
+
     /// Initialize relayer registry (ADMIN)
     pub fn initialize_relayer_registry(ctx: Context<InitializeRelayerRegistry>) -> Result<()> {
         instructions::initialize_relayer_registry(ctx)
     }
 
     /// Register as a relayer (RELAYER OPERATORS)
+    #[allow(clippy::too_many_arguments)]
     pub fn register_relayer(
         ctx: Context<RegisterRelayer>,
         fee_bps: u16,
@@ -251,11 +369,16 @@ pub mod stealth {
         max_fee: u64,
         supported_denominations: u8,
         stake_amount: u64,
+        min_denomination: u64,
+        endpoint_url: [u8; 64],
     ) -> Result<()> {
-        instructions::register_relayer(ctx, fee_bps, min_fee, max_fee, supported_denominations, stake_amount)
+        instructions::register_relayer(
+            ctx, fee_bps, min_fee, max_fee, supported_denominations, stake_amount, min_denomination, endpoint_url,
+        )
     }
 
     /// Update relayer settings (RELAYER OPERATORS)
+    #[allow(clippy::too_many_arguments)]
     pub fn update_relayer(
         ctx: Context<UpdateRelayer>,
         fee_bps: Option<u16>,
@@ -263,8 +386,12 @@ pub mod stealth {
         max_fee: Option<u64>,
         supported_denominations: Option<u8>,
         is_active: Option<bool>,
+        min_denomination: Option<u64>,
+        endpoint_url: Option<[u8; 64]>,
     ) -> Result<()> {
-        instructions::update_relayer(ctx, fee_bps, min_fee, max_fee, supported_denominations, is_active)
+        instructions::update_relayer(
+            ctx, fee_bps, min_fee, max_fee, supported_denominations, is_active, min_denomination, endpoint_url,
+        )
     }
 
     /// Request relay (INTERNAL - called by SDK)
@@ -284,6 +411,14 @@ pub mod stealth {
         instructions::complete_relay(ctx, tx_hash)
     }
 
+    /// Mark an expired relay request as failed (USERS)
+    pub fn fail_relay(
+        ctx: Context<FailRelay>,
+        tx_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::fail_relay(ctx, tx_hash)
+    }
+
     /// Withdraw relayer stake (RELAYER OPERATORS)
     pub fn withdraw_relayer_stake(ctx: Context<WithdrawRelayerStake>) -> Result<()> {
         instructions::withdraw_relayer_stake(ctx)
@@ -402,6 +537,31 @@ pub mod stealth {
         instructions::private_deposit(ctx, denomination, commitment, encrypted_note)
     }
 
+    /// Pool deposit with up to 8 decoy commitments inserted in shuffled order
+    pub fn deposit_with_decoys(
+        ctx: Context<DepositWithDecoys>,
+        denomination: u64,
+        commitment: [u8; 32],
+        decoys: Vec<[u8; 32]>,
+        shuffle_nonce: [u8; 32],
+        encrypted_note: Option<[u8; 128]>,
+    ) -> Result<()> {
+        instructions::deposit_with_decoys(ctx, denomination, commitment, decoys, shuffle_nonce, encrypted_note)
+    }
+
+    /// Variable-amount pool deposit with a range-proven Pedersen commitment
+    /// (`variable-pool` builds only)
+    pub fn variable_deposit(
+        ctx: Context<VariableDeposit>,
+        amount: u64,
+        commitment: [u8; 32],
+        amount_commitment: AmountCommitmentData,
+        attestation: zk::verifier::RangeProofAttestation,
+        encrypted_note: Option<[u8; 128]>,
+    ) -> Result<()> {
+        instructions::variable_deposit(ctx, amount, commitment, amount_commitment, attestation, encrypted_note)
+    }
+
     /// Direct pool withdrawal (LEGACY - use receive_private instead)
     #[deprecated(note = "Use receive_private for maximum privacy")]
     pub fn private_withdraw(
@@ -413,6 +573,28 @@ pub mod stealth {
         instructions::private_withdraw(ctx, denomination, proof, relayer_fee)
     }
 
+    /// Pool withdrawal to a plain address (recipient is PUBLIC, no stealth
+    /// address or announcement). For integrators paying a known treasury.
+    pub fn private_withdraw_to_address(
+        ctx: Context<PrivateWithdrawToAddress>,
+        denomination: u64,
+        proof: AddressWithdrawProof,
+        relayer_fee: u64,
+    ) -> Result<()> {
+        instructions::private_withdraw_to_address(ctx, denomination, proof, relayer_fee)
+    }
+
+    /// Pool withdrawal from an SPL token pool to the recipient's associated
+    /// token account, created if missing (relayer pays rent)
+    pub fn private_token_withdraw(
+        ctx: Context<PrivateTokenWithdraw>,
+        denomination: u64,
+        proof: AddressWithdrawProof,
+        relayer_fee: u64,
+    ) -> Result<()> {
+        instructions::private_token_withdraw(ctx, denomination, proof, relayer_fee)
+    }
+
     /// Batch deposit (LEGACY)
     #[deprecated(note = "Use send_private for maximum privacy")]
     pub fn batch_deposit(
@@ -479,7 +661,7 @@ pub mod stealth {
         instructions::reveal_and_withdraw(ctx, commitment_hash, denomination, proof_hash, user_random, nonce, relayer_fee)
     }
 
-    /// Cancel commitment (INTERNAL)
+    /// Cancel commitment and refund rent (owner, or anyone after expiry)
     pub fn cancel_commitment(ctx: Context<CancelCommitment>, commitment_hash: [u8; 32]) -> Result<()> {
         instructions::cancel_commitment(ctx, commitment_hash)
     }
@@ -538,15 +720,70 @@ pub mod stealth {
         instructions::get_relayer_info(ctx)
     }
 
+    /// Check whether a nullifier is already spent (read-only, simulate to query)
+    pub fn is_nullifier_spent(
+        ctx: Context<CheckNullifier>,
+        denomination: u64,
+        nullifier_hash: [u8; 32],
+    ) -> Result<bool> {
+        instructions::is_nullifier_spent(ctx, denomination, nullifier_hash)
+    }
+
+    /// Check a commitment is in a pool's tree without spending it (read-only)
+    pub fn verify_inclusion(
+        ctx: Context<VerifyInclusion>,
+        denomination: u64,
+        commitment: [u8; 32],
+        leaf_index: u64,
+        path: [[u8; 32]; MERKLE_DEPTH],
+    ) -> Result<()> {
+        instructions::verify_inclusion(ctx, denomination, commitment, leaf_index, path)
+    }
+
+    /// Check a pool's root against its frontier (read-only)
+    pub fn audit_tree(ctx: Context<AuditTree>, denomination: u64) -> Result<()> {
+        instructions::audit_tree(ctx, denomination)
+    }
+
     /// Initialize verification key (DISABLED - Solana 2.0)
     #[allow(unused_variables)]
     pub fn initialize_verification_key(ctx: Context<InitializeVerificationKey>, vk_data: Vec<u8>) -> Result<()> {
         instructions::initialize_verification_key(ctx, vk_data)
     }
 
-    /// Update verification key (DISABLED - Solana 2.0)
-    pub fn update_verification_key(ctx: Context<UpdateVerificationKey>, vk_data: Vec<u8>) -> Result<()> {
-        instructions::update_verification_key(ctx, vk_data)
+    /// Update verification key with a new circuit version (ADMIN)
+    pub fn update_verification_key(
+        ctx: Context<UpdateVerificationKey>,
+        vk_data: Vec<u8>,
+        version: u32,
+    ) -> Result<()> {
+        instructions::update_verification_key(ctx, vk_data, version)
+    }
+
+    /// Create the verification oracle (ADMIN)
+    pub fn initialize_verification_oracle(
+        ctx: Context<InitializeVerificationOracle>,
+        vk_hash: [u8; 32],
+        vk_version: u32,
+        required_attestations: u8,
+        max_attestation_age_seconds: i64,
+    ) -> Result<()> {
+        instructions::initialize_verification_oracle(ctx, vk_hash, vk_version, required_attestations, max_attestation_age_seconds)
+    }
+
+    /// Trust a new oracle verifier (ADMIN)
+    pub fn add_verifier(ctx: Context<ManageVerificationOracle>, verifier: Pubkey) -> Result<()> {
+        instructions::add_verifier(ctx, verifier)
+    }
+
+    /// Remove a misbehaving oracle verifier (ADMIN)
+    pub fn remove_verifier(ctx: Context<ManageVerificationOracle>, verifier: Pubkey) -> Result<()> {
+        instructions::remove_verifier(ctx, verifier)
+    }
+
+    /// Enable or disable all oracle attestations (ADMIN kill switch)
+    pub fn set_oracle_active(ctx: Context<ManageVerificationOracle>, is_active: bool) -> Result<()> {
+        instructions::set_oracle_active(ctx, is_active)
     }
 
     /// Verified withdrawal with ON-CHAIN Groth16 verification
@@ -575,6 +812,23 @@ pub mod stealth {
         instructions::verified_withdraw(ctx, denomination, public_inputs, proof, relayer_fee)
     }
 
+    /// Several verified withdrawals from one pool in a single transaction
+    ///
+    /// Verifies up to `MAX_VERIFIED_BATCH` Groth16 proofs (~200k CUs each, so
+    /// request a larger compute budget; a full batch needs a v0 transaction
+    /// with an address lookup table) before settling any of them; one
+    /// invalid proof reverts the batch.
+    ///
+    /// Remaining accounts: `[nullifier, recipient]` per withdrawal.
+    pub fn verified_withdraw_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, VerifiedWithdrawBatch<'info>>,
+        denomination: u64,
+        withdrawals: Vec<VerifiedWithdrawal>,
+        relayer_fee: u64,
+    ) -> Result<()> {
+        instructions::verified_withdraw_batch(ctx, denomination, withdrawals, relayer_fee)
+    }
+
     /// Simple deposit - stores commitment without on-chain Merkle computation
     ///
     /// # Arguments
@@ -638,4 +892,79 @@ pub mod stealth {
     ) -> Result<()> {
         instructions::transfer_registry_authority(ctx, new_authority)
     }
+
+    // ==========================================
+    // AUTHORITY TRANSFER INSTRUCTIONS
+    // ==========================================
+
+    /// Propose a new pool authority (ADMIN)
+    pub fn propose_pool_authority(
+        ctx: Context<ProposePoolAuthority>,
+        denomination: u64,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        instructions::propose_pool_authority(ctx, denomination, new_authority)
+    }
+
+    /// Accept a proposed pool authority (signed by the new authority)
+    pub fn accept_pool_authority(ctx: Context<AcceptPoolAuthority>, denomination: u64) -> Result<()> {
+        instructions::accept_pool_authority(ctx, denomination)
+    }
+
+    /// Propose a new token pool authority (ADMIN)
+    pub fn propose_token_pool_authority(
+        ctx: Context<ProposeTokenPoolAuthority>,
+        denomination: u64,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        instructions::propose_token_pool_authority(ctx, denomination, new_authority)
+    }
+
+    /// Accept a proposed token pool authority (signed by the new authority)
+    pub fn accept_token_pool_authority(ctx: Context<AcceptTokenPoolAuthority>, denomination: u64) -> Result<()> {
+        instructions::accept_token_pool_authority(ctx, denomination)
+    }
+
+    /// Propose a new pool config authority (ADMIN)
+    pub fn propose_pool_config_authority(
+        ctx: Context<UpdatePoolConfig>,
+        denomination: u64,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        instructions::propose_pool_config_authority(ctx, denomination, new_authority)
+    }
+
+    /// Accept a proposed pool config authority (signed by the new authority)
+    pub fn accept_pool_config_authority(
+        ctx: Context<AcceptPoolConfigAuthority>,
+        denomination: u64,
+    ) -> Result<()> {
+        instructions::accept_pool_config_authority(ctx, denomination)
+    }
+
+    /// Propose a new relayer registry authority (ADMIN)
+    pub fn propose_relayer_registry_authority(
+        ctx: Context<ProposeRelayerRegistryAuthority>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        instructions::propose_relayer_registry_authority(ctx, new_authority)
+    }
+
+    /// Accept a proposed relayer registry authority (signed by the new authority)
+    pub fn accept_relayer_registry_authority(ctx: Context<AcceptRelayerRegistryAuthority>) -> Result<()> {
+        instructions::accept_relayer_registry_authority(ctx)
+    }
+
+    /// Propose a new verification oracle authority (ADMIN)
+    pub fn propose_oracle_authority(
+        ctx: Context<ManageVerificationOracle>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        instructions::propose_oracle_authority(ctx, new_authority)
+    }
+
+    /// Accept a proposed verification oracle authority (signed by the new authority)
+    pub fn accept_oracle_authority(ctx: Context<AcceptOracleAuthority>) -> Result<()> {
+        instructions::accept_oracle_authority(ctx)
+    }
 }
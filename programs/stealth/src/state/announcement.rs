@@ -67,3 +67,27 @@ pub fn compute_commitment(
 
     hash.to_bytes()
 }
+
+/// Poseidon variant of `compute_commitment`, provable inside the BN254 circuits
+/// commitment = poseidon_hash_4([ephemeral_pubkey, scan_pubkey, spend_pubkey, stealth_address])
+///
+/// Each 32-byte curve point is read as a little-endian integer and reduced
+/// mod the BN254 scalar field before hashing. Compressed Ed25519 points use
+/// all 256 bits (y plus the sign bit), so encodings >= r alias with their
+/// reduction: a circuit proving this commitment must take the reduced field
+/// elements as inputs and constrain the same reduction, not the raw bytes.
+///
+/// Not interchangeable with the SHA256 commitment stored in announcements.
+pub fn compute_commitment_poseidon(
+    ephemeral_pubkey: &[u8; 32],
+    scan_pubkey: &[u8; 32],
+    spend_pubkey: &[u8; 32],
+    stealth_address: &[u8; 32],
+) -> [u8; 32] {
+    crate::crypto::poseidon::poseidon_hash_4(&[
+        *ephemeral_pubkey,
+        *scan_pubkey,
+        *spend_pubkey,
+        *stealth_address,
+    ])
+}
@@ -0,0 +1,128 @@
+//! Slot-bucketed Announcement Index
+//!
+//! Announcements are separate accounts, so finding the ones made since a
+//! given slot means fetching every announcement and filtering on `slot`.
+//! An `AnnouncementIndex` lists the announcement PDAs created in one bucket
+//! of `SLOTS_PER_BUCKET` slots, so a scanner resuming from `from_slot` only
+//! reads the indexes for `announcement_buckets(from_slot, current_slot)` and
+//! the announcements they name.
+//!
+//! ## Coverage
+//!
+//! Passing the index to an announcing instruction is optional, and an index
+//! only sees announcements made after it was created (`created_slot`). It
+//! also stops listing PDAs after `MAX_ENTRIES`, while still counting them in
+//! `total_announcements`. `covers_slot` tells a scanner whether an index can
+//! stand in for the full scan; if not, it must read the accounts themselves.
+//!
+//! ## Bucket edges
+//!
+//! A transaction sent near the end of a bucket may land in the next one,
+//! where the old bucket's index would be rejected. So in the last
+//! `INDEX_BUCKET_MARGIN` slots senders pass the next bucket's index
+//! (`index_bucket_for_slot`), which also accepts those announcements. A
+//! scanner therefore reads one bucket past the current slot's while in the
+//! margin, and an index only covers its bucket if it existed before the
+//! margin leading into it.
+
+use anchor_lang::prelude::*;
+use std::ops::RangeInclusive;
+
+/// Slots per index bucket (~66 minutes at 400ms slots)
+pub const SLOTS_PER_BUCKET: u64 = 10_000;
+
+/// Slots before a bucket ends in which announcements go to the next
+/// bucket's index
+pub const INDEX_BUCKET_MARGIN: u64 = 150;
+
+/// Bucket an announcement made at `slot` is indexed under
+pub fn announcement_bucket(slot: u64) -> u64 {
+    slot / SLOTS_PER_BUCKET
+}
+
+/// Bucket whose index a transaction sent at `slot` should pass: the next
+/// one during the last `INDEX_BUCKET_MARGIN` slots of a bucket
+pub fn index_bucket_for_slot(slot: u64) -> u64 {
+    announcement_bucket(slot.saturating_add(INDEX_BUCKET_MARGIN))
+}
+
+/// Buckets holding announcements made between `from_slot` and `to_slot`
+pub fn announcement_buckets(from_slot: u64, to_slot: u64) -> RangeInclusive<u64> {
+    announcement_bucket(from_slot)..=announcement_bucket(to_slot)
+}
+
+/// Announcement PDAs created in one slot bucket
+#[account]
+pub struct AnnouncementIndex {
+    /// Bucket number (`slot / SLOTS_PER_BUCKET`)
+    pub bucket: u64,
+
+    /// Slot the index was created in; earlier announcements aren't listed
+    pub created_slot: u64,
+
+    /// Announcements recorded, including ones past `MAX_ENTRIES`
+    pub total_announcements: u64,
+
+    /// Bump seed for PDA
+    pub bump: u8,
+
+    /// Announcement PDAs, in creation order
+    pub announcements: Vec<Pubkey>,
+}
+
+impl AnnouncementIndex {
+    pub const SEED: &'static [u8] = b"announcement_index";
+
+    /// discriminator (8) + bucket (8) + created_slot (8)
+    /// + total_announcements (8) + bump (1) + vec_len (4)
+    pub const HEADER_SIZE: usize = 8 + 8 + 8 + 8 + 1 + 4;
+
+    /// Most PDAs a 10KB account can list: (10240 - HEADER_SIZE) / 32
+    pub const MAX_ENTRIES: usize = 318;
+
+    pub const SIZE: usize = Self::HEADER_SIZE + Self::MAX_ENTRIES * 32;
+
+    /// Derive the index PDA for a bucket
+    /// Shared by the program and off-chain clients (CLI scan)
+    pub fn derive_pda(bucket: u64) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[Self::SEED, &bucket.to_le_bytes()], &crate::ID)
+    }
+
+    /// First slot of this index's bucket
+    pub fn start_slot(&self) -> u64 {
+        self.bucket.saturating_mul(SLOTS_PER_BUCKET)
+    }
+
+    /// First slot whose announcements may be listed here: the margin at the
+    /// end of the previous bucket included
+    pub fn first_indexed_slot(&self) -> u64 {
+        self.start_slot().saturating_sub(INDEX_BUCKET_MARGIN)
+    }
+
+    /// Whether an announcement made at `slot` may be recorded here
+    pub fn accepts_slot(&self, slot: u64) -> bool {
+        self.bucket == announcement_bucket(slot) || self.bucket == index_bucket_for_slot(slot)
+    }
+
+    /// List an announcement, or only count it once the index is full
+    pub fn record(&mut self, announcement: Pubkey) {
+        if self.announcements.len() < Self::MAX_ENTRIES {
+            self.announcements.push(announcement);
+        }
+        self.total_announcements = self.total_announcements.saturating_add(1);
+    }
+
+    /// Whether every recorded announcement is listed
+    pub fn is_complete(&self) -> bool {
+        self.total_announcements == self.announcements.len() as u64
+    }
+
+    /// Whether the index lists every indexed announcement since `from_slot`
+    ///
+    /// Announcements made in the index's creation slot may precede it, so
+    /// the index must be strictly older than the first slot asked for (or
+    /// the first slot it can list).
+    pub fn covers_slot(&self, from_slot: u64) -> bool {
+        self.is_complete() && self.created_slot < from_slot.max(self.first_indexed_slot())
+    }
+}
@@ -19,6 +19,14 @@
 //! - amount_hint: 1 byte (denomination bucket, not exact amount)
 //!
 //! A 10KB account can store ~95 entries before rotation.
+//!
+//! ## Retention
+//!
+//! The log is a ring buffer: once `max_entries` is reached, each new entry
+//! overwrites the oldest one. Only the most recent `max_entries` announcements
+//! are kept, so a scanner that falls further behind than that must fall back
+//! to reading the announcement accounts themselves. `oldest_slot` tells
+//! whether a given `from_slot` is still covered.
 
 use anchor_lang::prelude::*;
 
@@ -73,7 +81,7 @@ pub struct AnnouncementLog {
     /// Sequential log ID (for creating multiple logs)
     pub log_id: u64,
 
-    /// Number of entries currently in the log
+    /// Number of entries currently in the log (at most `max_entries`)
     pub entry_count: u32,
 
     /// Maximum entries this log can hold
@@ -88,10 +96,16 @@ pub struct AnnouncementLog {
     /// Slot of the most recent entry
     pub last_entry_slot: u64,
 
+    /// Ring position the next entry is written to
+    pub next_index: u32,
+
+    /// Entries ever recorded, including overwritten ones
+    pub total_entries: u64,
+
     /// Bump seed for PDA
     pub bump: u8,
 
-    /// The actual announcement entries (variable length)
+    /// The actual announcement entries (ring buffer, see `record`)
     pub entries: Vec<AnnouncementEntry>,
 }
 
@@ -101,8 +115,9 @@ impl AnnouncementLog {
     /// Fixed header size before entries vector:
     /// discriminator (8) + authority (32) + log_id (8) + entry_count (4)
     /// + max_entries (4) + is_active (1) + created_slot (8)
-    /// + last_entry_slot (8) + bump (1) + vec_len (4)
-    pub const HEADER_SIZE: usize = 8 + 32 + 8 + 4 + 4 + 1 + 8 + 8 + 1 + 4;
+    /// + last_entry_slot (8) + next_index (4) + total_entries (8)
+    /// + bump (1) + vec_len (4)
+    pub const HEADER_SIZE: usize = 8 + 32 + 8 + 4 + 4 + 1 + 8 + 8 + 4 + 8 + 1 + 4;
 
     /// Calculate space needed for a log with N entries
     pub fn space(max_entries: u32) -> usize {
@@ -113,15 +128,63 @@ impl AnnouncementLog {
     /// (10240 - HEADER_SIZE) / ENTRY_SIZE ≈ 95 entries
     pub const DEFAULT_MAX_ENTRIES: u32 = 95;
 
-    /// Check if log can accept more entries
+    /// Check if log can accept more entries (a full log wraps around)
     pub fn can_add_entry(&self) -> bool {
-        self.is_active && self.entry_count < self.max_entries
+        self.is_active && self.max_entries > 0
     }
 
-    /// Check if log is full
+    /// Check if log is full (new entries overwrite the oldest)
     pub fn is_full(&self) -> bool {
         self.entry_count >= self.max_entries
     }
+
+    /// Append an entry, overwriting the oldest one once the log is full
+    pub fn record(&mut self, entry: AnnouncementEntry) {
+        let slot = entry.slot;
+        let index = self.next_index as usize;
+
+        if index < self.entries.len() {
+            self.entries[index] = entry;
+        } else {
+            self.entries.push(entry);
+        }
+
+        self.next_index = (self.next_index + 1) % self.max_entries;
+        self.entry_count = self.entries.len() as u32;
+        self.total_entries = self.total_entries.saturating_add(1);
+        self.last_entry_slot = slot;
+    }
+
+    /// Retained entries, oldest first
+    pub fn iter_chronological(&self) -> impl Iterator<Item = &AnnouncementEntry> {
+        // Before the first wrap `next_index == entries.len()`, so the split
+        // yields everything in insertion order
+        let split = (self.next_index as usize).min(self.entries.len());
+        let (newer, older) = self.entries.split_at(split);
+        older.iter().chain(newer.iter())
+    }
+
+    /// Retained entries with `from_slot <= slot <= to_slot`, oldest first
+    pub fn entries_in_slot_range(&self, from_slot: u64, to_slot: u64) -> Vec<&AnnouncementEntry> {
+        self.iter_chronological()
+            .filter(|entry| entry.slot >= from_slot && entry.slot <= to_slot)
+            .collect()
+    }
+
+    /// Slot of the oldest retained entry, `None` if the log is empty
+    pub fn oldest_slot(&self) -> Option<u64> {
+        self.iter_chronological().next().map(|entry| entry.slot)
+    }
+
+    /// Whether every entry recorded since `from_slot` is still retained
+    pub fn covers_slot(&self, from_slot: u64) -> bool {
+        if self.total_entries <= self.entries.len() as u64 {
+            // Nothing overwritten yet
+            return true;
+        }
+        // Overwritten entries are no newer than the oldest retained one
+        self.oldest_slot().is_some_and(|oldest| oldest < from_slot)
+    }
 }
 
 /// Global log registry tracking all announcement logs
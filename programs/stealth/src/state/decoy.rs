@@ -148,6 +148,43 @@ impl DecoyWallet {
     pub const SIZE: usize = 8 + 32 + 8 + 8 + 8 + 8 + 8 + 1 + 1;
 }
 
+/// A decoy leaf inserted alongside a real deposit
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct DecoyLeaf {
+    /// Decoy commitment (random field element - no known preimage)
+    pub commitment: [u8; 32],
+
+    /// Index in the Merkle tree
+    pub leaf_index: u64,
+}
+
+/// Decoy leaves inserted by one `deposit_with_decoys` call
+///
+/// Lets clients rebuild the full tree and never treat decoys as spendable notes.
+#[account]
+pub struct DecoyRecord {
+    /// Pool denomination the decoys were inserted into
+    pub denomination: u64,
+
+    /// Decoy leaves (at most MAX_DECOYS)
+    pub decoys: Vec<DecoyLeaf>,
+
+    /// When the decoys were inserted
+    pub timestamp: i64,
+
+    /// Bump for PDA
+    pub bump: u8,
+}
+
+impl DecoyRecord {
+    pub const SEED: &'static [u8] = b"decoy_record";
+
+    /// Maximum decoys per deposit
+    pub const MAX_DECOYS: usize = 8;
+
+    pub const SIZE: usize = 8 + 8 + 4 + (32 + 8) * Self::MAX_DECOYS + 8 + 1;
+}
+
 /// Event emitted when decoy deposit is scheduled
 #[event]
 pub struct DecoyScheduled {
@@ -5,6 +5,8 @@ pub mod withdrawal_commitment;
 pub mod announcement_log;
 pub mod decoy;
 pub mod relayer;
+pub mod scan_hint;
+pub mod announcement_index;
 
 pub use registry::*;
 pub use announcement::*;
@@ -13,3 +15,5 @@ pub use withdrawal_commitment::*;
 pub use announcement_log::*;
 pub use decoy::*;
 pub use relayer::*;
+pub use scan_hint::*;
+pub use announcement_index::*;
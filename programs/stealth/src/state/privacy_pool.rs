@@ -12,6 +12,7 @@
 
 use anchor_lang::prelude::*;
 use crate::crypto::merkle::MERKLE_DEPTH;
+use crate::crypto::poseidon::{compute_commitment, compute_commitment_2};
 
 /// Historical roots count - reduced to fit in BPF stack
 /// 30 roots allows ~30 blocks for proof generation (typical Solana latency)
@@ -130,15 +131,29 @@ pub struct PrivacyPool {
     pub root_history: [[u8; 32]; ROOT_HISTORY_SIZE],
     pub root_history_index: u8,
 
+    /// SPL token mint for token pools (Pubkey::default() = native SOL pool)
+    /// Token pools hold funds in a separate vault PDA owned by the pool
+    pub token_mint: Pubkey,
+
+    /// Authority proposed by `propose_pool_authority`, awaiting its accept
+    pub pending_authority: Pubkey,
+
+    /// `CommitmentScheme` id the pool's deposits must use
+    pub commitment_scheme: u8,
+
     /// Bump for PDA
     pub bump: u8,
 }
 
 impl PrivacyPool {
     pub const SEED: &'static [u8] = b"privacy_pool";
+    pub const TOKEN_VAULT_SEED: &'static [u8] = b"pool_token_vault";
+
+    /// Denomination marker for the variable-amount pool (see `variable_deposit`)
+    pub const VARIABLE_POOL_DENOMINATION: u64 = 0;
 
     /// Size calculation for account allocation
-    /// Total: ~1738 bytes with MERKLE_DEPTH=10, ROOT_HISTORY_SIZE=30
+    /// Total: ~1770 bytes with MERKLE_DEPTH=10, ROOT_HISTORY_SIZE=30
     pub const SIZE: usize = 8 + // discriminator
         32 + // authority
         8 + // denomination (NEW)
@@ -152,8 +167,21 @@ impl PrivacyPool {
         1 + // is_active
         (32 * ROOT_HISTORY_SIZE) + // root_history (30 * 32 = 960)
         1 + // root_history_index
+        32 + // token_mint
+        32 + // pending_authority
+        1 + // commitment_scheme
         1; // bump
 
+    /// Whether this pool holds an SPL token instead of native SOL
+    pub fn is_token_pool(&self) -> bool {
+        self.token_mint != Pubkey::default()
+    }
+
+    /// The pool's commitment scheme, `None` for an unknown id
+    pub fn scheme(&self) -> Option<CommitmentScheme> {
+        CommitmentScheme::from_id(self.commitment_scheme)
+    }
+
     /// Check if a denomination is valid (uses expanded default list)
     /// For dynamic configuration, use DenominationRegistry.is_enabled()
     pub fn is_valid_denomination(denomination: u64) -> bool {
@@ -205,6 +233,26 @@ impl PrivacyPool {
         false
     }
 
+    /// Roots `is_valid_root` accepts right now: the current root, then
+    /// history entries from newest to oldest
+    ///
+    /// The last entries are the next to rotate out, so clients proving
+    /// offline should prefer roots near the front.
+    pub fn valid_roots(&self) -> Vec<[u8; 32]> {
+        let mut roots = Vec::with_capacity(ROOT_HISTORY_SIZE + 1);
+        roots.push(self.merkle_root);
+
+        for offset in 1..=ROOT_HISTORY_SIZE {
+            let index = (self.root_history_index as usize + ROOT_HISTORY_SIZE - offset) % ROOT_HISTORY_SIZE;
+            let root = self.root_history[index];
+            if root != [0u8; 32] && !roots.contains(&root) {
+                roots.push(root);
+            }
+        }
+
+        roots
+    }
+
     /// Save current root to history before updating
     #[inline(never)]
     pub fn save_root_to_history(&mut self) {
@@ -229,6 +277,15 @@ pub struct NullifierRecord {
 impl NullifierRecord {
     pub const SEED: &'static [u8] = b"nullifier";
     pub const SIZE: usize = 8 + 32 + 8 + 1;
+
+    /// Derive the nullifier PDA used by fixed-denomination withdrawals
+    /// Shared by the program and off-chain clients (relayers, CLI)
+    pub fn derive_pda(denomination: u64, nullifier_hash: &[u8; 32]) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[Self::SEED, &denomination.to_le_bytes(), nullifier_hash.as_ref()],
+            &crate::ID,
+        )
+    }
 }
 
 /// Commitment leaf record - stores commitment with metadata
@@ -244,7 +301,7 @@ pub struct CommitmentLeaf {
     pub timestamp: i64,
 
     /// Optional encrypted note for recipient
-    /// Format: [nonce(24)] || [ciphertext(variable)]
+    /// Format: [ephemeral_pubkey(32)] || [ChaCha20-Poly1305 ciphertext + tag(88)] || [padding(8)]
     pub encrypted_note: [u8; 128],
 
     /// Pedersen commitment to the amount: C = amount*G + blinding*H
@@ -255,13 +312,21 @@ pub struct CommitmentLeaf {
     /// Verified via oracle attestation
     pub range_proof_hash: [u8; 32],
 
+    /// Slot when deposited (checked against `PoolConfig::min_age_slots`)
+    pub slot: u64,
+
     /// Bump for PDA
     pub bump: u8,
 }
 
 impl CommitmentLeaf {
     pub const SEED: &'static [u8] = b"commitment";
-    pub const SIZE: usize = 8 + 32 + 8 + 8 + 128 + 33 + 32 + 1;
+    pub const SIZE: usize = 8 + 32 + 8 + 8 + 128 + 33 + 32 + 8 + 1;
+
+    /// Whether a deposit has already been stored here (a fresh account is all zeros)
+    pub fn is_recorded(&self) -> bool {
+        self.timestamp != 0
+    }
 }
 
 /// Confidential balance account (for ShadowWire integration)
@@ -288,6 +353,53 @@ impl ConfidentialBalance {
     pub const SIZE: usize = 8 + 32 + 32 + 64 + 8 + 1;
 }
 
+/// Commitment preimage a pool's circuits open, fixed at pool creation
+///
+/// The program only stores leaves, so it can't tell a wrongly built
+/// commitment from a right one; a note committed under another scheme is
+/// simply unspendable. Clients read the pool's scheme before depositing.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum CommitmentScheme {
+    /// Poseidon(nullifier, secret)
+    Poseidon2 = 0,
+    /// Poseidon(nullifier, secret, amount, recipient)
+    Poseidon4 = 1,
+}
+
+impl CommitmentScheme {
+    /// Scheme for a stored id, `None` if the id is unknown
+    pub fn from_id(id: u8) -> Option<Self> {
+        match id {
+            0 => Some(Self::Poseidon2),
+            1 => Some(Self::Poseidon4),
+            _ => None,
+        }
+    }
+
+    /// Id stored in `PrivacyPool::commitment_scheme`
+    pub fn id(self) -> u8 {
+        self as u8
+    }
+
+    /// Commitment for a note under this scheme
+    ///
+    /// `Poseidon2` ignores `amount` and `recipient`.
+    pub fn commitment(self, nullifier: &[u8; 32], secret: &[u8; 32], amount: u64, recipient: &[u8; 32]) -> [u8; 32] {
+        match self {
+            Self::Poseidon2 => compute_commitment_2(nullifier, secret),
+            Self::Poseidon4 => compute_commitment(nullifier, secret, amount, recipient),
+        }
+    }
+}
+
+/// Which side of a pool a pause applies to
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PauseKind {
+    Deposits,
+    Withdrawals,
+}
+
 /// Pool configuration
 #[account]
 pub struct PoolConfig {
@@ -312,13 +424,33 @@ pub struct PoolConfig {
     /// Whether withdrawals are paused
     pub withdrawals_paused: bool,
 
+    /// Minimum deposit count before withdrawals are allowed
+    pub min_anonymity_set: u64,
+
+    /// Share of a withdrawal's relayer fee skimmed to `fee_recipient` (bps)
+    pub protocol_fee_bps: u16,
+
+    /// Share of a withdrawal's relayer fee paid to the relayer (bps).
+    /// Whatever the two shares leave over stays with the recipient.
+    pub relayer_fee_bps: u16,
+
+    /// Minimum slots between a note's deposit and its withdrawal (0 = none)
+    ///
+    /// A withdrawal landing right after its deposit is trivially linkable by
+    /// timing, whatever the ZK proof hides. Making every note sit for a while
+    /// lets other deposits land in between and blur the pairing.
+    pub min_age_slots: u64,
+
+    /// Authority proposed by `propose_pool_config_authority`, awaiting its accept
+    pub pending_authority: Pubkey,
+
     /// Bump for PDA
     pub bump: u8,
 }
 
 impl PoolConfig {
     pub const SEED: &'static [u8] = b"pool_config";
-    pub const SIZE: usize = 8 + 32 + 8 + 8 + 2 + 32 + 1 + 1 + 1;
+    pub const SIZE: usize = 8 + 32 + 8 + 8 + 2 + 32 + 1 + 1 + 8 + 2 + 2 + 8 + 32 + 1;
 
     /// Default minimum deposit (0.001 SOL)
     pub const DEFAULT_MIN_DEPOSIT: u64 = 1_000_000;
@@ -328,4 +460,56 @@ impl PoolConfig {
 
     /// Default fee (0.1%)
     pub const DEFAULT_FEE_BPS: u16 = 10;
+
+    /// Default anonymity set (no gating beyond the first deposit)
+    pub const DEFAULT_MIN_ANONYMITY_SET: u64 = 1;
+
+    /// Default protocol share of relayer fees (none)
+    pub const DEFAULT_PROTOCOL_FEE_BPS: u16 = 0;
+
+    /// Default relayer share of relayer fees (all of it)
+    pub const DEFAULT_RELAYER_FEE_BPS: u16 = 10_000;
+
+    /// Default deposit-to-withdrawal delay (none)
+    pub const DEFAULT_MIN_AGE_SLOTS: u64 = 0;
+
+    /// Whether a pool with `deposit_count` deposits may be withdrawn from
+    pub fn anonymity_set_reached(&self, deposit_count: u64) -> bool {
+        deposit_count >= self.min_anonymity_set
+    }
+
+    /// Whether a note deposited at `deposit_slot` may be withdrawn at `current_slot`
+    pub fn note_old_enough(&self, deposit_slot: u64, current_slot: u64) -> bool {
+        current_slot.saturating_sub(deposit_slot) >= self.min_age_slots
+    }
+
+    /// Whether a protocol/relayer split is valid (shares sum to at most 100%)
+    pub fn is_valid_fee_split(protocol_fee_bps: u16, relayer_fee_bps: u16) -> bool {
+        protocol_fee_bps as u32 + relayer_fee_bps as u32 <= 10_000
+    }
+
+    /// Whether deposits or withdrawals are currently refused
+    pub fn is_paused(&self, kind: PauseKind) -> bool {
+        match kind {
+            PauseKind::Deposits => self.deposits_paused,
+            PauseKind::Withdrawals => self.withdrawals_paused,
+        }
+    }
+
+    /// Set the flag the deposit (`DepositsPaused`) or withdrawal
+    /// (`WithdrawalsPaused`) account constraints check
+    pub fn set_paused(&mut self, kind: PauseKind, paused: bool) {
+        match kind {
+            PauseKind::Deposits => self.deposits_paused = paused,
+            PauseKind::Withdrawals => self.withdrawals_paused = paused,
+        }
+    }
+
+    /// Split a withdrawal's `relayer_fee` into (protocol_amount, relayer_amount)
+    ///
+    /// Both round down, so the sum never exceeds `relayer_fee`.
+    pub fn split_relayer_fee(&self, relayer_fee: u64) -> (u64, u64) {
+        let share = |bps: u16| (relayer_fee as u128 * bps as u128 / 10_000) as u64;
+        (share(self.protocol_fee_bps), share(self.relayer_fee_bps))
+    }
 }
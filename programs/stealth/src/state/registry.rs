@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use crate::error::StealthError;
 
 /// Registry entry for a user's stealth meta-address
 ///
@@ -62,6 +63,25 @@ impl StealthRegistry {
     /// + view_key_holder (32) + bump (1)
     pub const SIZE: usize = 8 + 32 + 32 + 32 + 32 + 8 + 32 + 1 + 32 + 1;
 
+    /// Longest label that fits the zero-padded `label` field
+    pub const MAX_LABEL_LEN: usize = 32;
+
+    /// Zero-pad a label for storage
+    ///
+    /// Must be UTF-8, at most `MAX_LABEL_LEN` bytes, and free of NUL bytes
+    /// (which would be indistinguishable from the padding). Empty clears it.
+    pub fn encode_label(label: &[u8]) -> Result<[u8; 32]> {
+        require!(label.len() <= Self::MAX_LABEL_LEN, StealthError::LabelTooLong);
+        require!(
+            std::str::from_utf8(label).is_ok() && !label.contains(&0),
+            StealthError::InvalidLabel
+        );
+
+        let mut padded = [0u8; 32];
+        padded[..label.len()].copy_from_slice(label);
+        Ok(padded)
+    }
+
     /// Returns the meta-address as a tuple (scan_pubkey, spend_pubkey)
     pub fn meta_address(&self) -> ([u8; 32], [u8; 32]) {
         (self.scan_pubkey, self.spend_pubkey)
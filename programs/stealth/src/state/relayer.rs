@@ -62,6 +62,21 @@ pub struct Relayer {
     /// Last activity timestamp
     pub last_active: i64,
 
+    /// Smallest pool denomination this relayer will serve (lamports)
+    pub min_denomination: u64,
+
+    /// Advertised endpoint URL (UTF-8, zero-padded)
+    pub endpoint_url: [u8; 64],
+
+    /// Withdrawals this relayer submitted and signed
+    pub total_relayed: u64,
+
+    /// Relay requests that expired without being completed
+    pub total_failed: u64,
+
+    /// Slot of the last relayed withdrawal
+    pub last_active_slot: u64,
+
     /// Bump for PDA
     pub bump: u8,
 }
@@ -69,8 +84,8 @@ pub struct Relayer {
 impl Relayer {
     pub const SEED: &'static [u8] = b"relayer";
 
-    /// 8 + 32 + 32 + 2 + 8 + 8 + 8 + 8 + 1 + 1 + 1 + 8 + 8 + 1 = 126
-    pub const SIZE: usize = 8 + 32 + 32 + 2 + 8 + 8 + 8 + 8 + 1 + 1 + 1 + 8 + 8 + 1;
+    /// 8 + 32 + 32 + 2 + 8 + 8 + 8 + 8 + 1 + 1 + 1 + 8 + 8 + 8 + 64 + 8 + 8 + 8 + 1 = 222
+    pub const SIZE: usize = 8 + 32 + 32 + 2 + 8 + 8 + 8 + 8 + 1 + 1 + 1 + 8 + 8 + 8 + 64 + 8 + 8 + 8 + 1;
 
     /// Maximum fee: 5%
     pub const MAX_FEE_BPS: u16 = 500;
@@ -81,6 +96,9 @@ impl Relayer {
     /// Minimum reputation to be listed
     pub const MIN_REPUTATION: u8 = 10;
 
+    /// Reputation lost for each failed relay
+    pub const FAILURE_PENALTY: u8 = 5;
+
     /// Check if relayer supports a denomination
     pub fn supports_denomination(&self, denomination: u64) -> bool {
         let bit = match denomination {
@@ -103,6 +121,42 @@ impl Relayer {
             fee
         }
     }
+
+    /// Check a withdrawal's relayer fee against this relayer's advertised terms
+    pub fn validate_withdraw_fee(&self, denomination: u64, relayer_fee: u64) -> Result<()> {
+        require!(self.is_active, crate::error::StealthError::RelayerNotActive);
+        require!(
+            denomination >= self.min_denomination,
+            crate::error::StealthError::DenominationNotSupported
+        );
+        require!(
+            relayer_fee == self.calculate_fee(denomination),
+            crate::error::StealthError::RelayerFeeMismatch
+        );
+        Ok(())
+    }
+
+    /// Count a withdrawal this relayer signed and submitted
+    pub fn record_relayed(&mut self, slot: u64, timestamp: i64) {
+        self.total_relayed = self.total_relayed.saturating_add(1);
+        self.last_active_slot = slot;
+        self.last_active = timestamp;
+    }
+
+    /// Count a relay request the relayer let expire
+    pub fn record_failed(&mut self) {
+        self.total_failed = self.total_failed.saturating_add(1);
+        self.reputation = self.reputation.saturating_sub(Self::FAILURE_PENALTY);
+    }
+
+    /// Share of relays that succeeded, in basis points (None with no history)
+    pub fn success_rate_bps(&self) -> Option<u16> {
+        let total = self.total_relayed as u128 + self.total_failed as u128;
+        if total == 0 {
+            return None;
+        }
+        Some((self.total_relayed as u128 * 10_000 / total) as u16)
+    }
 }
 
 /// Global relayer registry configuration
@@ -129,13 +183,16 @@ pub struct RelayerRegistry {
     /// Whether new registrations are open
     pub registrations_open: bool,
 
+    /// Authority proposed by `propose_relayer_registry_authority`, awaiting its accept
+    pub pending_authority: Pubkey,
+
     /// Bump for PDA
     pub bump: u8,
 }
 
 impl RelayerRegistry {
     pub const SEED: &'static [u8] = b"relayer_registry";
-    pub const SIZE: usize = 8 + 32 + 4 + 4 + 8 + 8 + 8 + 1 + 1;
+    pub const SIZE: usize = 8 + 32 + 4 + 4 + 8 + 8 + 8 + 1 + 32 + 1;
 
     /// Default minimum stake: 1 SOL
     pub const DEFAULT_MIN_STAKE: u64 = 1_000_000_000;
@@ -221,6 +278,19 @@ pub struct RelayCompleted {
     pub timestamp: i64,
 }
 
+/// Event emitted when a relay request expires uncompleted
+#[event]
+pub struct RelayFailed {
+    /// Relayer that failed to complete the relay
+    pub relayer: Pubkey,
+
+    /// Relayer's failure count after this one
+    pub total_failed: u64,
+
+    /// Timestamp
+    pub timestamp: i64,
+}
+
 /// Event emitted when relayer is slashed
 #[event]
 pub struct RelayerSlashed {
@@ -0,0 +1,81 @@
+//! Scan Hints
+//!
+//! Checking an announcement costs the recipient an EC multiply (`s·R`),
+//! even for the vast majority that aren't theirs. A `ScanHint` is an
+//! optional per-recipient Bloom filter that senders add an 8-byte tag to
+//! for each payment:
+//!
+//! ```text
+//! tag = Poseidon(scan_pubkey, ephemeral_pubkey)[..8]
+//! ```
+//!
+//! The recipient recomputes the tag for each announcement (a hash, no curve
+//! math) and skips the full scan when the filter can't contain it. A Bloom
+//! filter has no false negatives, and junk tags only raise the false
+//! positive rate, so spam can't hide a payment from the filter.
+//!
+//! ## Not private
+//!
+//! The tag only needs the *public* scan key, so anyone holding a
+//! recipient's meta-address can tell which announcements are theirs, and
+//! the hint account itself names the recipient. Senders who want the
+//! normal unlinkability must not write hints, and a recipient can always
+//! ignore the filter and scan everything: payments from senders that
+//! skipped the hint are only found that way.
+
+use anchor_lang::prelude::*;
+
+/// Per-recipient Bloom filter of announcement tags
+#[account]
+pub struct ScanHint {
+    /// Recipient scan public key this filter belongs to
+    pub scan_pubkey: [u8; 32],
+
+    /// Filter bits (`FILTER_BITS` = 2048)
+    pub filter: [u8; 256],
+
+    /// Tags inserted so far (the filter saturates as this grows)
+    pub tag_count: u64,
+
+    /// Bump for PDA
+    pub bump: u8,
+}
+
+impl ScanHint {
+    pub const SEED: &'static [u8] = b"scan_hint";
+    pub const SIZE: usize = 8 + 32 + 256 + 8 + 1;
+
+    /// Number of bits in the filter
+    pub const FILTER_BITS: usize = 256 * 8;
+
+    /// Bits set per tag: one per 16-bit chunk of the 8-byte tag
+    pub const HASH_COUNT: usize = 4;
+
+    /// Filter positions for a tag
+    ///
+    /// Tags are Poseidon output, so each 16-bit chunk is already uniform
+    /// and serves as an independent hash.
+    pub fn bit_positions(tag: &[u8; 8]) -> [usize; Self::HASH_COUNT] {
+        let mut positions = [0usize; Self::HASH_COUNT];
+        for (i, position) in positions.iter_mut().enumerate() {
+            let chunk = u16::from_le_bytes([tag[2 * i], tag[2 * i + 1]]) as usize;
+            *position = chunk % Self::FILTER_BITS;
+        }
+        positions
+    }
+
+    /// Add a payment's tag
+    pub fn insert(&mut self, tag: &[u8; 8]) {
+        for bit in Self::bit_positions(tag) {
+            self.filter[bit / 8] |= 1 << (bit % 8);
+        }
+        self.tag_count = self.tag_count.saturating_add(1);
+    }
+
+    /// Whether `tag` may have been inserted (false means definitely not)
+    pub fn may_contain(&self, tag: &[u8; 8]) -> bool {
+        Self::bit_positions(tag)
+            .iter()
+            .all(|bit| self.filter[bit / 8] & (1 << (bit % 8)) != 0)
+    }
+}
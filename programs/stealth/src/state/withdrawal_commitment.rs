@@ -95,6 +95,11 @@ impl WithdrawalCommitment {
         elapsed > self.max_delay_seconds
     }
 
+    /// Check if `caller` may cancel: the owner anytime, anyone once expired
+    pub fn can_cancel(&self, caller: &Pubkey, current_timestamp: i64) -> bool {
+        *caller == self.owner || self.is_expired(current_timestamp)
+    }
+
     /// Time remaining until commitment can be executed (0 if already executable)
     pub fn time_until_executable(&self, current_timestamp: i64) -> i64 {
         let elapsed = current_timestamp - self.commit_timestamp;
@@ -190,11 +190,1566 @@ mod unit_tests {
         assert_eq!(StealthRegistry::SEED, b"stealth_registry");
     }
 
+    #[test]
+    fn test_registry_label_encoding() {
+        use crate::error::StealthError;
+
+        let label = StealthRegistry::encode_label("alice.sol".as_bytes()).unwrap();
+        assert_eq!(&label[..9], b"alice.sol");
+        assert!(label[9..].iter().all(|b| *b == 0));
+
+        // Exactly 32 bytes (multi-byte UTF-8 included) fits; empty clears
+        let full = "é".repeat(16);
+        let encoded = StealthRegistry::encode_label(full.as_bytes()).unwrap();
+        assert_eq!(&encoded[..], full.as_bytes());
+        assert_eq!(StealthRegistry::encode_label(b"").unwrap(), [0u8; 32]);
+
+        let err = StealthRegistry::encode_label(&[b'a'; 33]).unwrap_err();
+        assert_eq!(err, StealthError::LabelTooLong.into());
+        let err = StealthRegistry::encode_label(&[0xff, 0xfe]).unwrap_err();
+        assert_eq!(err, StealthError::InvalidLabel.into());
+        let err = StealthRegistry::encode_label(b"bob\0").unwrap_err();
+        assert_eq!(err, StealthError::InvalidLabel.into());
+    }
+
     #[test]
     fn test_announcement_seed() {
         assert_eq!(StealthAnnouncement::SEED, b"announcement");
     }
 
+    #[test]
+    fn test_nullifier_pda_derivation() {
+        use crate::state::privacy_pool::NullifierRecord;
+        use anchor_lang::prelude::Pubkey;
+
+        let denomination = 1_000_000_000u64;
+        let nullifier_hash = [7u8; 32];
+
+        let (pda, bump) = NullifierRecord::derive_pda(denomination, &nullifier_hash);
+        let expected = Pubkey::create_program_address(
+            &[NullifierRecord::SEED, &denomination.to_le_bytes(), &nullifier_hash, &[bump]],
+            &crate::ID,
+        )
+        .unwrap();
+        assert_eq!(pda, expected);
+
+        // Same nullifier in another denomination pool is a different record
+        let (other, _) = NullifierRecord::derive_pda(10_000_000_000, &nullifier_hash);
+        assert_ne!(pda, other);
+    }
+
+    // ==================== Withdrawal Commitment Tests ====================
+
+    #[test]
+    fn test_commitment_cancel_permissions() {
+        use anchor_lang::prelude::Pubkey;
+        use crate::state::WithdrawalCommitment;
+
+        let owner = Pubkey::new_unique();
+        let stranger = Pubkey::new_unique();
+        let commitment = WithdrawalCommitment {
+            owner,
+            commit_timestamp: 1_000,
+            min_delay_seconds: 3_600,
+            max_delay_seconds: 7_200,
+            ..Default::default()
+        };
+
+        // Owner can cancel anytime
+        assert!(commitment.can_cancel(&owner, 1_000));
+
+        // Others only after commit_timestamp + max_delay_seconds has passed
+        assert!(!commitment.can_cancel(&stranger, 1_000 + 7_200));
+        assert!(commitment.can_cancel(&stranger, 1_000 + 7_201));
+    }
+
+    // ==================== Decoy Deposit Tests ====================
+
+    #[test]
+    fn test_decoy_shuffle_is_deterministic_permutation() {
+        use crate::instructions::decoy_deposit::shuffle_commitments;
+
+        let original: Vec<[u8; 32]> = (0u8..9).map(|i| [i + 1; 32]).collect();
+        let nonce = [0x5Au8; 32];
+
+        let mut first = original.clone();
+        let mut second = original.clone();
+        shuffle_commitments(&mut first, &nonce);
+        shuffle_commitments(&mut second, &nonce);
+        assert_eq!(first, second);
+
+        // Same elements, just reordered
+        let mut sorted = first.clone();
+        sorted.sort();
+        assert_eq!(sorted, original);
+
+        // A different nonce gives a different order
+        let mut other = original.clone();
+        shuffle_commitments(&mut other, &[0xA5u8; 32]);
+        assert_ne!(first, other);
+    }
+
+    #[test]
+    fn test_decoy_record_size() {
+        use crate::state::DecoyRecord;
+
+        // discriminator + denomination + vec len + 8 * (commitment + index) + timestamp + bump
+        assert_eq!(DecoyRecord::SIZE, 8 + 8 + 4 + 8 * 40 + 8 + 1);
+        assert_eq!(DecoyRecord::MAX_DECOYS, 8);
+    }
+
+    // ==================== Variable Pool Tests ====================
+
+    #[test]
+    fn test_variable_pool_never_collides_with_fixed_pools() {
+        use crate::state::privacy_pool::PrivacyPool;
+
+        // initialize_pool rejects this value, so the variable pool PDA is unique
+        assert!(!PrivacyPool::is_valid_denomination(PrivacyPool::VARIABLE_POOL_DENOMINATION));
+    }
+
+    #[test]
+    fn test_half_and_fifty_sol_denominations() {
+        use crate::state::privacy_pool::{PrivacyPool, DENOMINATION_0_5_SOL, DENOMINATION_50_SOL};
+
+        assert!(PrivacyPool::is_valid_denomination(500_000_000));
+        assert!(PrivacyPool::is_valid_denomination(50_000_000_000));
+        assert_eq!(DENOMINATION_0_5_SOL, 500_000_000);
+        assert_eq!(DENOMINATION_50_SOL, 50_000_000_000);
+
+        // Arbitrary amounts are still rejected
+        assert!(!PrivacyPool::is_valid_denomination(750_000_000));
+        assert!(!PrivacyPool::is_valid_denomination(49_999_999_999));
+
+        // Pool PDAs stay distinct per denomination
+        let pda = |d: u64| {
+            anchor_lang::prelude::Pubkey::find_program_address(
+                &[PrivacyPool::SEED, &d.to_le_bytes()],
+                &crate::ID,
+            ).0
+        };
+        assert_ne!(pda(DENOMINATION_0_5_SOL), pda(DENOMINATION_50_SOL));
+    }
+
+    // ==================== Relayer Fee Tests ====================
+
+    #[test]
+    fn test_relayer_withdraw_fee_validation() {
+        use anchor_lang::prelude::Pubkey;
+        use crate::state::Relayer;
+
+        let mut relayer = Relayer {
+            pubkey: Pubkey::new_unique(),
+            operator: Pubkey::new_unique(),
+            fee_bps: 50,
+            min_fee: 0,
+            max_fee: 0,
+            tx_count: 0,
+            total_earned: 0,
+            is_active: true,
+            supported_denominations: 0b111,
+            reputation: 50,
+            registered_at: 0,
+            last_active: 0,
+            min_denomination: 1_000_000_000,
+            endpoint_url: [0u8; 64],
+            total_relayed: 0,
+            total_failed: 0,
+            last_active_slot: 0,
+            bump: 0,
+        };
+
+        // 0.5% of 10 SOL
+        assert!(relayer.validate_withdraw_fee(10_000_000_000, 50_000_000).is_ok());
+        assert!(relayer.validate_withdraw_fee(10_000_000_000, 50_000_001).is_err());
+
+        // Below the relayer's minimum denomination
+        assert!(relayer.validate_withdraw_fee(100_000_000, 500_000).is_err());
+
+        relayer.is_active = false;
+        assert!(relayer.validate_withdraw_fee(10_000_000_000, 50_000_000).is_err());
+    }
+
+    // ==================== Relayer Stats Tests ====================
+
+    #[test]
+    fn test_relayer_success_rate() {
+        use anchor_lang::prelude::Pubkey;
+        use crate::state::Relayer;
+
+        let mut relayer = Relayer {
+            pubkey: Pubkey::new_unique(),
+            operator: Pubkey::new_unique(),
+            fee_bps: 50,
+            min_fee: 0,
+            max_fee: 0,
+            tx_count: 0,
+            total_earned: 0,
+            is_active: true,
+            supported_denominations: 0b111,
+            reputation: 50,
+            registered_at: 0,
+            last_active: 0,
+            min_denomination: 0,
+            endpoint_url: [0u8; 64],
+            total_relayed: 0,
+            total_failed: 0,
+            last_active_slot: 0,
+            bump: 0,
+        };
+        assert_eq!(relayer.success_rate_bps(), None);
+
+        for slot in 1..=3 {
+            relayer.record_relayed(slot, 1_700_000_000);
+        }
+        assert_eq!(relayer.total_relayed, 3);
+        assert_eq!(relayer.last_active_slot, 3);
+        assert_eq!(relayer.success_rate_bps(), Some(10_000));
+
+        relayer.record_failed();
+        assert_eq!(relayer.total_failed, 1);
+        assert_eq!(relayer.success_rate_bps(), Some(7_500));
+        assert_eq!(relayer.reputation, 50 - Relayer::FAILURE_PENALTY);
+
+        // Reputation bottoms out at zero
+        for _ in 0..20 {
+            relayer.record_failed();
+        }
+        assert_eq!(relayer.reputation, 0);
+    }
+
+    // ==================== Tree Capacity Tests ====================
+
+    #[test]
+    fn test_small_tree_rejects_insert_past_capacity() {
+        use crate::error::StealthError;
+        use crate::instructions::private_deposit::ensure_tree_capacity;
+
+        // Depth-2 tree holds 4 leaves
+        let depth = 2;
+        let mut next_leaf_index = 0u64;
+        while ensure_tree_capacity(next_leaf_index, 1, depth).is_ok() {
+            next_leaf_index += 1;
+        }
+        assert_eq!(next_leaf_index, 4);
+
+        let err = ensure_tree_capacity(next_leaf_index, 1, depth).unwrap_err();
+        assert_eq!(err, StealthError::TreeFull.into());
+
+        // Batches must fit entirely
+        assert!(ensure_tree_capacity(2, 2, depth).is_ok());
+        assert!(ensure_tree_capacity(2, 3, depth).is_err());
+        assert!(ensure_tree_capacity(u64::MAX, 1, depth).is_err());
+    }
+
+    // ==================== Duplicate Commitment Tests ====================
+
+    #[test]
+    fn test_batch_with_repeated_commitment_rejected() {
+        use crate::error::StealthError;
+        use crate::instructions::private_deposit::ensure_unique_commitments;
+
+        let a = [1u8; 32];
+        let b = [2u8; 32];
+        let c = [3u8; 32];
+
+        assert!(ensure_unique_commitments(&[a]).is_ok());
+        assert!(ensure_unique_commitments(&[a, b, c]).is_ok());
+
+        let err = ensure_unique_commitments(&[a, b, a]).unwrap_err();
+        assert_eq!(err, StealthError::DuplicateCommitment.into());
+        assert!(ensure_unique_commitments(&[a, b, c, c]).is_err());
+    }
+
+    #[test]
+    fn test_commitment_leaf_recorded_after_deposit() {
+        use crate::state::privacy_pool::CommitmentLeaf;
+
+        let mut leaf = CommitmentLeaf {
+            commitment: [0u8; 32],
+            leaf_index: 0,
+            timestamp: 0,
+            encrypted_note: [0u8; 128],
+            amount_commitment: [0u8; 33],
+            range_proof_hash: [0u8; 32],
+            slot: 0,
+            bump: 0,
+        };
+        assert!(!leaf.is_recorded());
+
+        leaf.commitment = [7u8; 32];
+        leaf.timestamp = 1_700_000_000;
+        assert!(leaf.is_recorded());
+    }
+
+    // ==================== Root History Tests ====================
+
+    #[test]
+    fn test_valid_roots_newest_first() {
+        use crate::state::privacy_pool::{PrivacyPool, ROOT_HISTORY_SIZE};
+        use anchor_lang::__private::bytemuck::Zeroable;
+
+        let mut pool = PrivacyPool::zeroed();
+        pool.merkle_root = [1u8; 32];
+        assert_eq!(pool.valid_roots(), vec![[1u8; 32]]);
+
+        // Each insertion saves the old root, then moves to a new one
+        for i in 2..=(ROOT_HISTORY_SIZE as u8 + 5) {
+            pool.save_root_to_history();
+            pool.merkle_root = [i; 32];
+        }
+
+        let roots = pool.valid_roots();
+        let newest = ROOT_HISTORY_SIZE as u8 + 5;
+        assert_eq!(roots.len(), ROOT_HISTORY_SIZE + 1);
+        assert_eq!(roots[0], [newest; 32]);
+        assert_eq!(roots[1], [newest - 1; 32]);
+        assert_eq!(roots[ROOT_HISTORY_SIZE], [newest - ROOT_HISTORY_SIZE as u8; 32]);
+
+        // Exactly the roots is_valid_root accepts
+        assert!(roots.iter().all(|root| pool.is_valid_root(root)));
+        assert!(!pool.is_valid_root(&[newest - ROOT_HISTORY_SIZE as u8 - 1; 32]));
+    }
+
+    // ==================== Inclusion Proof Tests ====================
+
+    #[test]
+    fn test_check_inclusion() {
+        use crate::crypto::merkle::{compute_merkle_proof, compute_zero_hashes_poseidon, insert_leaf, MERKLE_DEPTH};
+        use crate::instructions::check_inclusion;
+        use crate::state::privacy_pool::PrivacyPool;
+        use anchor_lang::__private::bytemuck::Zeroable;
+
+        let leaves: Vec<[u8; 32]> = (1..=3u8).map(|i| [i; 32]).collect();
+        let mut pool = PrivacyPool::zeroed();
+        pool.filled_subtrees = compute_zero_hashes_poseidon::<MERKLE_DEPTH>().levels;
+        for leaf in &leaves {
+            pool.save_root_to_history();
+            pool.merkle_root = insert_leaf::<MERKLE_DEPTH>(&mut pool.filled_subtrees, pool.next_leaf_index, *leaf).unwrap();
+            pool.next_leaf_index += 1;
+        }
+
+        let (path, root) = compute_merkle_proof(&leaves, 1);
+        assert_eq!(root, pool.merkle_root);
+        assert!(check_inclusion(&pool, &leaves[1], 1, &path).is_ok());
+
+        // A path against an older root in history still verifies
+        let (old_path, _) = compute_merkle_proof(&leaves[..2], 0);
+        assert!(check_inclusion(&pool, &leaves[0], 0, &old_path).is_ok());
+
+        // Wrong commitment, index or sibling
+        assert!(check_inclusion(&pool, &leaves[0], 1, &path).is_err());
+        assert!(check_inclusion(&pool, &leaves[1], 0, &path).is_err());
+        let mut tampered = path;
+        tampered[0][0] ^= 1;
+        assert!(check_inclusion(&pool, &leaves[1], 1, &tampered).is_err());
+
+        // Empty slots can't be "proven", even with a matching path
+        let (empty_path, _) = compute_merkle_proof(&[leaves[0], leaves[1], leaves[2], [0u8; 32]], 3);
+        assert!(check_inclusion(&pool, &[0u8; 32], 3, &empty_path).is_err());
+    }
+
+    #[test]
+    fn test_audit_tree_detects_corrupted_frontier() {
+        use crate::crypto::merkle::{compute_zero_hashes_poseidon, frontier_root, insert_leaf, EMPTY_TREE_ROOT, MERKLE_DEPTH};
+        use crate::error::StealthError;
+        use crate::instructions::check_tree_consistency;
+        use crate::state::privacy_pool::PrivacyPool;
+        use anchor_lang::__private::bytemuck::Zeroable;
+
+        let mut pool = PrivacyPool::zeroed();
+        pool.filled_subtrees = compute_zero_hashes_poseidon::<MERKLE_DEPTH>().levels;
+        pool.merkle_root = EMPTY_TREE_ROOT;
+        assert!(check_tree_consistency(&pool).is_ok());
+
+        // Consistent after every insert, left and right children alike
+        for i in 1..=5u8 {
+            pool.merkle_root = insert_leaf::<MERKLE_DEPTH>(&mut pool.filled_subtrees, pool.next_leaf_index, [i; 32]).unwrap();
+            pool.next_leaf_index += 1;
+            assert!(check_tree_consistency(&pool).is_ok());
+        }
+
+        let mut corrupted = pool;
+        corrupted.filled_subtrees[2][0] ^= 1;
+        let err = check_tree_consistency(&corrupted).unwrap_err();
+        assert_eq!(err, StealthError::TreeInconsistent.into());
+
+        // So is a leaf count that dropped a stored leaf
+        let mut drifted = pool;
+        drifted.next_leaf_index -= 1;
+        assert!(check_tree_consistency(&drifted).is_err());
+
+        // A full tree can't be recomputed from its frontier
+        let mut small = compute_zero_hashes_poseidon::<2>().levels;
+        for i in 0..4u64 {
+            let root = insert_leaf::<2>(&mut small, i, [i as u8 + 1; 32]).unwrap();
+            if i < 3 {
+                assert_eq!(frontier_root::<2>(&small, i + 1).unwrap(), root);
+            }
+        }
+        let err = frontier_root::<2>(&small, 4).unwrap_err();
+        assert_eq!(err, StealthError::TreeFull.into());
+    }
+
+    // ==================== Poseidon Commitment Tests ====================
+
+    #[test]
+    fn test_poseidon_commitment_consistency() {
+        use crate::state::{compute_commitment, compute_commitment_poseidon};
+
+        let ephemeral = [0x11u8; 32];
+        let scan = [0x22u8; 32];
+        let spend = [0x33u8; 32];
+        let stealth = [0x44u8; 32];
+
+        let commitment = compute_commitment_poseidon(&ephemeral, &scan, &spend, &stealth);
+        assert_eq!(commitment, compute_commitment_poseidon(&ephemeral, &scan, &spend, &stealth));
+        assert_ne!(commitment, compute_commitment(&ephemeral, &scan, &spend, &stealth));
+
+        // Changing any input (or the order) changes the commitment
+        let variants = [
+            compute_commitment_poseidon(&[0x12; 32], &scan, &spend, &stealth),
+            compute_commitment_poseidon(&ephemeral, &[0x23; 32], &spend, &stealth),
+            compute_commitment_poseidon(&ephemeral, &scan, &[0x34; 32], &stealth),
+            compute_commitment_poseidon(&ephemeral, &scan, &spend, &[0x45; 32]),
+            compute_commitment_poseidon(&scan, &ephemeral, &spend, &stealth),
+        ];
+        for variant in variants {
+            assert_ne!(commitment, variant);
+        }
+    }
+
+    #[test]
+    fn test_poseidon_commitment_reduces_points_into_field() {
+        use crate::crypto::poseidon::BN254_MODULUS;
+        use crate::state::compute_commitment_poseidon;
+
+        // 5 and r + 5 encode the same field element (no carry out of limb 0)
+        let mut small = [0u8; 32];
+        small[0] = 5;
+        let mut aliased = [0u8; 32];
+        for (i, limb) in BN254_MODULUS.iter().enumerate() {
+            let limb = if i == 0 { limb + 5 } else { *limb };
+            aliased[i * 8..(i + 1) * 8].copy_from_slice(&limb.to_le_bytes());
+        }
+
+        let other = [0x22u8; 32];
+        assert_eq!(
+            compute_commitment_poseidon(&small, &other, &other, &other),
+            compute_commitment_poseidon(&aliased, &other, &other, &other)
+        );
+    }
+
+    // ==================== Commitment Scheme Tests ====================
+
+    #[test]
+    fn test_commitment_scheme_ids() {
+        use crate::crypto::poseidon::{compute_commitment, compute_commitment_2};
+        use crate::state::privacy_pool::{CommitmentScheme, PrivacyPool};
+        use anchor_lang::__private::bytemuck::Zeroable;
+
+        for scheme in [CommitmentScheme::Poseidon2, CommitmentScheme::Poseidon4] {
+            assert_eq!(CommitmentScheme::from_id(scheme.id()), Some(scheme));
+        }
+        assert_eq!(CommitmentScheme::Poseidon2.id(), 0);
+        assert_eq!(CommitmentScheme::Poseidon4.id(), 1);
+        assert_eq!(CommitmentScheme::from_id(2), None);
+        assert_eq!(CommitmentScheme::from_id(u8::MAX), None);
+
+        let mut pool = PrivacyPool::zeroed();
+        pool.commitment_scheme = 7;
+        assert_eq!(pool.scheme(), None);
+        pool.commitment_scheme = CommitmentScheme::Poseidon4.id();
+        assert_eq!(pool.scheme(), Some(CommitmentScheme::Poseidon4));
+
+        // Each scheme opens its own preimage; the two never agree
+        let (nullifier, secret, recipient) = ([1u8; 32], [2u8; 32], [0u8; 32]);
+        let two = CommitmentScheme::Poseidon2.commitment(&nullifier, &secret, 1_000_000_000, &recipient);
+        let four = CommitmentScheme::Poseidon4.commitment(&nullifier, &secret, 1_000_000_000, &recipient);
+        assert_eq!(two, compute_commitment_2(&nullifier, &secret));
+        assert_eq!(four, compute_commitment(&nullifier, &secret, 1_000_000_000, &recipient));
+        assert_ne!(two, four);
+    }
+
+    // ==================== Unified Withdraw Proof Tests ====================
+
+    #[test]
+    fn test_unified_withdraw_proof_verification() {
+        use crate::error::StealthError;
+        use crate::instructions::unified_privacy::{verify_unified_proof, UnifiedWithdrawProof};
+        use crate::zk::verifier::tests::oracle_with_window;
+        use anchor_lang::prelude::{AccountInfo, Pubkey};
+
+        let mut proof = UnifiedWithdrawProof {
+            nullifier_hash: [1u8; 32],
+            merkle_root: [2u8; 32],
+            proof_data: [7u8; 256],
+            recipient_scan_pubkey: [3u8; 32],
+            recipient_spend_pubkey: [4u8; 32],
+            ephemeral_pubkey: [5u8; 32],
+            expected_stealth_address: Pubkey::new_unique(),
+            attestation: None,
+        };
+
+        // Every stealth component is bound into the verified inputs
+        let inputs = proof.public_inputs_bytes();
+        assert_eq!(inputs.len(), 192);
+        assert_eq!(&inputs[160..], proof.expected_stealth_address.as_ref());
+        let mut redirected = proof.clone();
+        redirected.expected_stealth_address = Pubkey::new_unique();
+        assert_ne!(redirected.public_inputs_bytes(), inputs);
+
+        let sysvar_key = anchor_lang::solana_program::sysvar::instructions::id();
+        let (mut lamports, mut data) = (0u64, [0u8; 0]);
+        let sysvar = AccountInfo::new(&sysvar_key, false, false, &mut lamports, &mut data, &sysvar_key, false, 0);
+
+        let mut oracle = oracle_with_window(300);
+
+        // Without an attestation only dev builds accept the proof
+        #[cfg(not(feature = "production"))]
+        assert!(verify_unified_proof(&proof, &sysvar, &oracle).is_ok());
+        #[cfg(feature = "production")]
+        assert_eq!(
+            verify_unified_proof(&proof, &sysvar, &oracle).unwrap_err(),
+            StealthError::MissingAttestation.into()
+        );
+
+        // The oracle's kill switch stops it in every build
+        oracle.is_active = false;
+        assert_eq!(
+            verify_unified_proof(&proof, &sysvar, &oracle).unwrap_err(),
+            StealthError::VerificationMethodNotAvailable.into()
+        );
+
+        // An empty proof is rejected in every build
+        proof.proof_data = [0u8; 256];
+        let err = verify_unified_proof(&proof, &sysvar, &oracle).unwrap_err();
+        assert_eq!(err, StealthError::InvalidProof.into());
+    }
+
+    // ==================== Split Withdrawal Tests ====================
+
+    #[test]
+    fn test_withdraw_splits_must_sum_to_payout() {
+        use crate::error::StealthError;
+        use crate::instructions::unified_privacy::validate_withdraw_splits;
+
+        let sol = 1_000_000_000u64;
+        let min_split = 890_880;
+
+        // 10 SOL -> 3 + 3 + 4, no fee
+        assert!(validate_withdraw_splits(&[3 * sol, 3 * sol, 4 * sol], 10 * sol, 0, min_split).is_ok());
+
+        // With a relayer fee the splits cover the remainder
+        let fee = sol / 10;
+        assert!(validate_withdraw_splits(&[5 * sol, 5 * sol - fee], 10 * sol, fee, min_split).is_ok());
+
+        let err = validate_withdraw_splits(&[5 * sol, 5 * sol], 10 * sol, fee, min_split).unwrap_err();
+        assert_eq!(err, StealthError::SplitSumMismatch.into());
+        let err = validate_withdraw_splits(&[u64::MAX, sol], 10 * sol, 0, min_split).unwrap_err();
+        assert_eq!(err, StealthError::SplitSumMismatch.into());
+    }
+
+    #[test]
+    fn test_withdraw_splits_count_and_minimum() {
+        use crate::error::StealthError;
+        use crate::instructions::unified_privacy::{validate_withdraw_splits, MAX_WITHDRAW_SPLITS};
+
+        let sol = 1_000_000_000u64;
+        let min_split = 890_880;
+
+        let err = validate_withdraw_splits(&[], sol, 0, min_split).unwrap_err();
+        assert_eq!(err, StealthError::InvalidSplitCount.into());
+
+        let four = vec![sol / 4; MAX_WITHDRAW_SPLITS];
+        assert!(validate_withdraw_splits(&four, sol, 0, min_split).is_ok());
+        let five = vec![sol / 5; MAX_WITHDRAW_SPLITS + 1];
+        let err = validate_withdraw_splits(&five, sol, 0, min_split).unwrap_err();
+        assert_eq!(err, StealthError::InvalidSplitCount.into());
+
+        // A dust split would leave the stealth address below rent exemption
+        let err = validate_withdraw_splits(&[sol - 1, 1], sol, 0, min_split).unwrap_err();
+        assert_eq!(err, StealthError::SplitBelowRentExempt.into());
+    }
+
+    #[test]
+    fn test_withdraw_multi_requires_proof() {
+        use crate::error::StealthError;
+        use crate::instructions::unified_privacy::{
+            verify_unified_multi_proof, UnifiedWithdrawMultiProof, WithdrawSplit,
+        };
+        use crate::zk::verifier::tests::oracle_with_window;
+        use anchor_lang::prelude::AccountInfo;
+
+        let split = |amount: u64| WithdrawSplit {
+            recipient_scan_pubkey: [3u8; 32],
+            recipient_spend_pubkey: [4u8; 32],
+            ephemeral_pubkey: [5u8; 32],
+            amount,
+        };
+        let mut proof = UnifiedWithdrawMultiProof {
+            nullifier_hash: [1u8; 32],
+            merkle_root: [2u8; 32],
+            proof_data: [7u8; 256],
+            deposit_slot: 10,
+            splits: vec![split(3), split(7)],
+            attestation: None,
+        };
+
+        // Every split, amounts included, is bound into the verified inputs
+        let inputs = proof.public_inputs_bytes();
+        assert_eq!(inputs.len(), 72 + 2 * 104);
+        let mut reshuffled = proof.clone();
+        reshuffled.splits = vec![split(7), split(3)];
+        assert_ne!(reshuffled.public_inputs_bytes(), inputs);
+        let mut redirected = proof.clone();
+        redirected.splits[1].recipient_spend_pubkey = [9u8; 32];
+        assert_ne!(redirected.public_inputs_bytes(), inputs);
+
+        let sysvar_key = anchor_lang::solana_program::sysvar::instructions::id();
+        let (mut lamports, mut data) = (0u64, [0u8; 0]);
+        let sysvar = AccountInfo::new(&sysvar_key, false, false, &mut lamports, &mut data, &sysvar_key, false, 0);
+
+        let mut oracle = oracle_with_window(300);
+
+        // Without an attestation only dev builds accept the proof
+        #[cfg(not(feature = "production"))]
+        assert!(verify_unified_multi_proof(&proof, &sysvar, &oracle).is_ok());
+        #[cfg(feature = "production")]
+        assert_eq!(
+            verify_unified_multi_proof(&proof, &sysvar, &oracle).unwrap_err(),
+            StealthError::MissingAttestation.into()
+        );
+
+        // The oracle's kill switch stops it in every build
+        oracle.is_active = false;
+        assert_eq!(
+            verify_unified_multi_proof(&proof, &sysvar, &oracle).unwrap_err(),
+            StealthError::VerificationMethodNotAvailable.into()
+        );
+        oracle.is_active = true;
+
+        // An empty proof is rejected in every build
+        proof.proof_data = [0u8; 256];
+        let err = verify_unified_multi_proof(&proof, &sysvar, &oracle).unwrap_err();
+        assert_eq!(err, StealthError::InvalidProof.into());
+    }
+
+    #[test]
+    fn test_rent_exempt_payout_boundary() {
+        use crate::error::StealthError;
+        use crate::util::ensure_rent_exempt_payout;
+
+        // Rent::minimum_balance(0) at the default rent
+        let min_balance = 890_880;
+
+        assert!(ensure_rent_exempt_payout(min_balance, min_balance).is_ok());
+        assert!(ensure_rent_exempt_payout(1_000_000_000, min_balance).is_ok());
+
+        let err = ensure_rent_exempt_payout(min_balance - 1, min_balance).unwrap_err();
+        assert_eq!(err, StealthError::BelowRentExempt.into());
+        let err = ensure_rent_exempt_payout(0, min_balance).unwrap_err();
+        assert_eq!(err, StealthError::BelowRentExempt.into());
+    }
+
+    // ==================== Lamport Transfer Tests ====================
+
+    #[test]
+    fn test_safe_transfer_lamports() {
+        use crate::error::StealthError;
+        use crate::util::safe_transfer_lamports;
+        use anchor_lang::prelude::{AccountInfo, Pubkey};
+
+        let (from_key, to_key, owner) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let (mut from_lamports, mut to_lamports) = (1_000u64, 5u64);
+        let (mut from_data, mut to_data) = ([0u8; 0], [0u8; 0]);
+        let from = AccountInfo::new(&from_key, false, true, &mut from_lamports, &mut from_data, &owner, false, 0);
+        let to = AccountInfo::new(&to_key, false, true, &mut to_lamports, &mut to_data, &owner, false, 0);
+
+        safe_transfer_lamports(&from, &to, 400).unwrap();
+        assert_eq!(from.lamports(), 600);
+        assert_eq!(to.lamports(), 405);
+
+        // Draining the source exactly is fine; one more lamport is not
+        safe_transfer_lamports(&from, &to, 600).unwrap();
+        assert_eq!(from.lamports(), 0);
+        let err = safe_transfer_lamports(&from, &to, 1).unwrap_err();
+        assert_eq!(err, StealthError::ArithmeticOverflow.into());
+        assert_eq!(to.lamports(), 1_005);
+    }
+
+    #[test]
+    fn test_safe_transfer_lamports_destination_overflow() {
+        use crate::error::StealthError;
+        use crate::util::safe_transfer_lamports;
+        use anchor_lang::prelude::{AccountInfo, Pubkey};
+
+        let (from_key, to_key, owner) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let (mut from_lamports, mut to_lamports) = (1_000u64, u64::MAX - 10);
+        let (mut from_data, mut to_data) = ([0u8; 0], [0u8; 0]);
+        let from = AccountInfo::new(&from_key, false, true, &mut from_lamports, &mut from_data, &owner, false, 0);
+        let to = AccountInfo::new(&to_key, false, true, &mut to_lamports, &mut to_data, &owner, false, 0);
+
+        // Exactly reaching u64::MAX is allowed
+        safe_transfer_lamports(&from, &to, 10).unwrap();
+        assert_eq!(to.lamports(), u64::MAX);
+
+        // Wrapping is rejected and leaves the source untouched
+        let err = safe_transfer_lamports(&from, &to, 1).unwrap_err();
+        assert_eq!(err, StealthError::ArithmeticOverflow.into());
+        assert_eq!(from.lamports(), 990);
+        assert_eq!(to.lamports(), u64::MAX);
+    }
+
+    #[test]
+    fn test_safe_transfer_lamports_to_self_is_noop() {
+        use crate::util::safe_transfer_lamports;
+        use anchor_lang::prelude::{AccountInfo, Pubkey};
+
+        let (key, owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut lamports = 1_000u64;
+        let mut data = [0u8; 0];
+        let account = AccountInfo::new(&key, false, true, &mut lamports, &mut data, &owner, false, 0);
+
+        safe_transfer_lamports(&account, &account.clone(), 400).unwrap();
+        assert_eq!(account.lamports(), 1_000);
+    }
+
+    // ==================== Anonymity Set Tests ====================
+
+    #[test]
+    fn test_min_anonymity_set_gates_withdrawals() {
+        use crate::state::privacy_pool::PoolConfig;
+        use anchor_lang::prelude::Pubkey;
+
+        let mut config = PoolConfig {
+            authority: Pubkey::default(),
+            min_deposit: 0,
+            max_deposit: 0,
+            fee_bps: 0,
+            fee_recipient: Pubkey::default(),
+            deposits_paused: false,
+            withdrawals_paused: false,
+            min_anonymity_set: PoolConfig::DEFAULT_MIN_ANONYMITY_SET,
+            protocol_fee_bps: PoolConfig::DEFAULT_PROTOCOL_FEE_BPS,
+            relayer_fee_bps: PoolConfig::DEFAULT_RELAYER_FEE_BPS,
+            min_age_slots: PoolConfig::DEFAULT_MIN_AGE_SLOTS,
+            pending_authority: Pubkey::default(),
+            bump: 0,
+        };
+
+        // Default keeps existing behavior: any deposit can be withdrawn
+        assert!(config.anonymity_set_reached(1));
+
+        config.min_anonymity_set = 10;
+        assert!(!config.anonymity_set_reached(9));
+        assert!(config.anonymity_set_reached(10));
+    }
+
+    // ==================== Pool Pause Tests ====================
+
+    #[test]
+    fn test_pool_pause_flags() {
+        use crate::state::privacy_pool::{PauseKind, PoolConfig};
+        use anchor_lang::prelude::Pubkey;
+
+        let mut config = PoolConfig {
+            authority: Pubkey::default(),
+            min_deposit: 0,
+            max_deposit: 0,
+            fee_bps: 0,
+            fee_recipient: Pubkey::default(),
+            deposits_paused: false,
+            withdrawals_paused: false,
+            min_anonymity_set: PoolConfig::DEFAULT_MIN_ANONYMITY_SET,
+            protocol_fee_bps: PoolConfig::DEFAULT_PROTOCOL_FEE_BPS,
+            relayer_fee_bps: PoolConfig::DEFAULT_RELAYER_FEE_BPS,
+            min_age_slots: PoolConfig::DEFAULT_MIN_AGE_SLOTS,
+            pending_authority: Pubkey::default(),
+            bump: 0,
+        };
+
+        // Pausing deposits sets the flag the DepositsPaused constraints read,
+        // and leaves withdrawals open
+        config.set_paused(PauseKind::Deposits, true);
+        assert!(config.deposits_paused && config.is_paused(PauseKind::Deposits));
+        assert!(!config.withdrawals_paused);
+
+        config.set_paused(PauseKind::Withdrawals, true);
+        assert!(config.withdrawals_paused && config.is_paused(PauseKind::Withdrawals));
+
+        // Unpausing restores both sides independently
+        config.set_paused(PauseKind::Deposits, false);
+        assert!(!config.deposits_paused);
+        assert!(config.withdrawals_paused);
+        config.set_paused(PauseKind::Withdrawals, false);
+        assert!(!config.is_paused(PauseKind::Withdrawals));
+    }
+
+    // ==================== Withdrawal Delay Tests ====================
+
+    #[test]
+    fn test_min_age_slots_gates_withdrawals() {
+        use crate::state::privacy_pool::PoolConfig;
+        use anchor_lang::prelude::Pubkey;
+
+        let mut config = PoolConfig {
+            authority: Pubkey::default(),
+            min_deposit: 0,
+            max_deposit: 0,
+            fee_bps: 0,
+            fee_recipient: Pubkey::default(),
+            deposits_paused: false,
+            withdrawals_paused: false,
+            min_anonymity_set: PoolConfig::DEFAULT_MIN_ANONYMITY_SET,
+            protocol_fee_bps: PoolConfig::DEFAULT_PROTOCOL_FEE_BPS,
+            relayer_fee_bps: PoolConfig::DEFAULT_RELAYER_FEE_BPS,
+            min_age_slots: PoolConfig::DEFAULT_MIN_AGE_SLOTS,
+            pending_authority: Pubkey::default(),
+            bump: 0,
+        };
+
+        // Default keeps existing behavior: same-slot withdrawals are allowed
+        assert!(config.note_old_enough(100, 100));
+
+        config.min_age_slots = 50;
+        assert!(!config.note_old_enough(100, 100));
+        assert!(!config.note_old_enough(100, 149));
+        assert!(config.note_old_enough(100, 150));
+
+        // A deposit slot from the future never counts as aged
+        assert!(!config.note_old_enough(200, 100));
+    }
+
+    #[test]
+    fn test_deposit_slot_is_attested() {
+        use crate::instructions::WithdrawPublicInputs;
+        use anchor_lang::prelude::Pubkey;
+
+        let inputs = WithdrawPublicInputs {
+            merkle_root: [1u8; 32],
+            nullifier_hash: [2u8; 32],
+            stealth_address: Pubkey::new_unique(),
+            ephemeral_pubkey: [3u8; 32],
+            scan_pubkey: [4u8; 32],
+            spend_pubkey: [5u8; 32],
+            stealth_commitment: [6u8; 32],
+            deposit_slot: 1_000,
+        };
+
+        let bytes = inputs.to_bytes();
+        assert_eq!(&bytes[224..], &1_000u64.to_le_bytes());
+
+        // Backdating the deposit changes what the attestation must cover
+        let backdated = WithdrawPublicInputs {
+            deposit_slot: 0,
+            ..inputs
+        };
+        assert_ne!(backdated.to_bytes(), bytes);
+    }
+
+    // ==================== Fee Split Tests ====================
+
+    #[test]
+    fn test_relayer_fee_split() {
+        use crate::state::privacy_pool::PoolConfig;
+        use anchor_lang::prelude::Pubkey;
+
+        let mut config = PoolConfig {
+            authority: Pubkey::default(),
+            min_deposit: 0,
+            max_deposit: 0,
+            fee_bps: 0,
+            fee_recipient: Pubkey::default(),
+            deposits_paused: false,
+            withdrawals_paused: false,
+            min_anonymity_set: PoolConfig::DEFAULT_MIN_ANONYMITY_SET,
+            protocol_fee_bps: PoolConfig::DEFAULT_PROTOCOL_FEE_BPS,
+            relayer_fee_bps: PoolConfig::DEFAULT_RELAYER_FEE_BPS,
+            min_age_slots: PoolConfig::DEFAULT_MIN_AGE_SLOTS,
+            pending_authority: Pubkey::default(),
+            bump: 0,
+        };
+
+        // Default: the relayer keeps the whole fee
+        assert_eq!(config.split_relayer_fee(1_000_000), (0, 1_000_000));
+
+        // 20% to the protocol, the rest to the relayer
+        config.protocol_fee_bps = 2_000;
+        config.relayer_fee_bps = 8_000;
+        assert_eq!(config.split_relayer_fee(1_000_000), (200_000, 800_000));
+
+        // Shares round down, never paying out more than the fee
+        let (protocol, relayer) = config.split_relayer_fee(7);
+        assert_eq!((protocol, relayer), (1, 5));
+        assert!(protocol + relayer <= 7);
+
+        // No overflow at the top of the range
+        config.protocol_fee_bps = 0;
+        config.relayer_fee_bps = 10_000;
+        assert_eq!(config.split_relayer_fee(u64::MAX), (0, u64::MAX));
+
+        assert!(PoolConfig::is_valid_fee_split(2_000, 8_000));
+        assert!(PoolConfig::is_valid_fee_split(0, 0));
+        assert!(!PoolConfig::is_valid_fee_split(5_000, 5_001));
+        assert!(!PoolConfig::is_valid_fee_split(u16::MAX, u16::MAX));
+    }
+
+    // ==================== Authority Transfer Tests ====================
+
+    #[test]
+    fn test_authority_propose_then_accept() {
+        use crate::state::privacy_pool::PoolConfig;
+        use crate::util::{accept_authority, propose_authority};
+        use anchor_lang::prelude::Pubkey;
+
+        let old = Pubkey::new_unique();
+        let new = Pubkey::new_unique();
+        let mut config = PoolConfig {
+            authority: old,
+            min_deposit: 0,
+            max_deposit: 0,
+            fee_bps: 0,
+            fee_recipient: Pubkey::default(),
+            deposits_paused: false,
+            withdrawals_paused: false,
+            min_anonymity_set: PoolConfig::DEFAULT_MIN_ANONYMITY_SET,
+            protocol_fee_bps: PoolConfig::DEFAULT_PROTOCOL_FEE_BPS,
+            relayer_fee_bps: PoolConfig::DEFAULT_RELAYER_FEE_BPS,
+            min_age_slots: PoolConfig::DEFAULT_MIN_AGE_SLOTS,
+            pending_authority: Pubkey::default(),
+            bump: 0,
+        };
+
+        // Proposing alone changes nothing
+        propose_authority(&mut config.pending_authority, new);
+        assert_eq!(config.authority, old);
+        assert_eq!(config.pending_authority, new);
+
+        let previous = accept_authority(&mut config.authority, &mut config.pending_authority, &new).unwrap();
+        assert_eq!(previous, old);
+        assert_eq!(config.authority, new);
+        assert_eq!(config.pending_authority, Pubkey::default());
+    }
+
+    #[test]
+    fn test_authority_accept_rejects_non_pending_key() {
+        use crate::error::StealthError;
+        use crate::util::{accept_authority, propose_authority};
+        use anchor_lang::prelude::Pubkey;
+
+        let old = Pubkey::new_unique();
+        let new = Pubkey::new_unique();
+        let mut authority = old;
+        let mut pending = Pubkey::default();
+
+        // Nothing proposed: nobody can accept, not even the default key
+        let err = accept_authority(&mut authority, &mut pending, &new).unwrap_err();
+        assert_eq!(err, StealthError::NotPendingAuthority.into());
+        let err = accept_authority(&mut authority, &mut pending, &Pubkey::default()).unwrap_err();
+        assert_eq!(err, StealthError::NotPendingAuthority.into());
+
+        // Proposed: only the proposed key may accept, the current one included
+        propose_authority(&mut pending, new);
+        for signer in [old, Pubkey::new_unique()] {
+            let err = accept_authority(&mut authority, &mut pending, &signer).unwrap_err();
+            assert_eq!(err, StealthError::NotPendingAuthority.into());
+        }
+        assert_eq!(authority, old);
+        assert_eq!(pending, new);
+
+        // Withdrawn proposal can no longer be accepted
+        propose_authority(&mut pending, Pubkey::default());
+        let err = accept_authority(&mut authority, &mut pending, &new).unwrap_err();
+        assert_eq!(err, StealthError::NotPendingAuthority.into());
+        assert_eq!(authority, old);
+    }
+
+    // ==================== Scan Hint Tests ====================
+
+    #[test]
+    fn test_scan_hint_filter_has_no_false_negatives() {
+        use crate::state::ScanHint;
+        use anchor_lang::solana_program::keccak;
+
+        // Stand-ins for Poseidon tags: any uniform 8 bytes
+        let tag = |seed: u64| -> [u8; 8] { keccak::hash(&seed.to_le_bytes()).0[..8].try_into().unwrap() };
+
+        let mut hint = ScanHint {
+            scan_pubkey: [0u8; 32],
+            filter: [0u8; 256],
+            tag_count: 0,
+            bump: 0,
+        };
+
+        let tags: Vec<[u8; 8]> = (0..300u64).map(tag).collect();
+
+        // An empty filter rules everything out
+        assert!(tags.iter().all(|tag| !hint.may_contain(tag)));
+
+        for tag in &tags {
+            hint.insert(tag);
+        }
+        assert_eq!(hint.tag_count, 300);
+        assert!(tags.iter().all(|tag| hint.may_contain(tag)));
+
+        // ...and still rules out most tags it never saw
+        let misses = (1_000..2_000u64).filter(|i| !hint.may_contain(&tag(*i))).count();
+        assert!(misses > 900, "only {} of 1000 unseen tags filtered", misses);
+    }
+
+    // ==================== Field Reduction Tests ====================
+
+    /// BN254 scalar field modulus r, big-endian
+    const BN254_R: [u8; 32] = [
+        0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81, 0x58, 0x5d,
+        0x28, 0x33, 0xe8, 0x48, 0x79, 0xb9, 0x70, 0x91, 0x43, 0xe1, 0xf5, 0x93, 0xf0, 0x00, 0x00, 0x01,
+    ];
+
+    /// `value + k * r` as 256-bit big-endian, or None if it overflows
+    fn add_modulus_multiple(value: &[u8; 32], k: u32) -> Option<[u8; 32]> {
+        let mut out = *value;
+        for _ in 0..k {
+            let mut carry = 0u16;
+            for i in (0..32).rev() {
+                let sum = out[i] as u16 + BN254_R[i] as u16 + carry;
+                out[i] = sum as u8;
+                carry = sum >> 8;
+            }
+            if carry != 0 {
+                return None;
+            }
+        }
+        Some(out)
+    }
+
+    #[test]
+    fn test_field_reduction_accepts_multiples() {
+        use crate::instructions::verified_withdraw::verify_field_reduction;
+
+        let mut reduced = [0u8; 32];
+        reduced[31] = 0x2a;
+
+        for k in 0..12 {
+            match add_modulus_multiple(&reduced, k) {
+                Some(pubkey) => assert!(verify_field_reduction(&pubkey, &reduced), "k={}", k),
+                // k * r no longer fits in 32 bytes, so no pubkey can reduce this way
+                None => assert!(k >= 6, "k={} should fit in 256 bits", k),
+            }
+        }
+
+        // Largest reduction of a 32-byte value: k = 5 from the top of the range
+        let max = [0xffu8; 32];
+        let mut reduced_max = max;
+        for _ in 0..5 {
+            let mut borrow = 0i16;
+            for i in (0..32).rev() {
+                let diff = reduced_max[i] as i16 - BN254_R[i] as i16 - borrow;
+                reduced_max[i] = diff.rem_euclid(256) as u8;
+                borrow = (diff < 0) as i16;
+            }
+        }
+        assert!(verify_field_reduction(&max, &reduced_max));
+
+        // The program's own reduction agrees
+        use crate::instructions::verified_withdraw::pubkey_to_field;
+        use anchor_lang::prelude::Pubkey;
+        assert_eq!(pubkey_to_field(&Pubkey::new_from_array(max)), reduced_max);
+        let pubkey = add_modulus_multiple(&reduced, 3).unwrap();
+        assert_eq!(pubkey_to_field(&Pubkey::new_from_array(pubkey)), reduced);
+    }
+
+    #[test]
+    fn test_field_reduction_rejects_invalid() {
+        use crate::instructions::verified_withdraw::verify_field_reduction;
+
+        let mut reduced = [0u8; 32];
+        reduced[31] = 0x2a;
+
+        // Off-by-one from a valid multiple
+        let mut pubkey = add_modulus_multiple(&reduced, 3).unwrap();
+        pubkey[31] ^= 0x01;
+        assert!(!verify_field_reduction(&pubkey, &reduced));
+
+        // pubkey < reduced
+        let mut small = [0u8; 32];
+        small[31] = 0x01;
+        assert!(!verify_field_reduction(&small, &reduced));
+
+        // reduced must itself be < r
+        let pubkey = add_modulus_multiple(&BN254_R, 1).unwrap();
+        assert!(!verify_field_reduction(&pubkey, &BN254_R));
+    }
+
+    #[test]
+    fn test_groth16_inputs_reject_non_canonical() {
+        use crate::error::StealthError;
+        use crate::instructions::verified_withdraw::Groth16WithdrawInputs;
+        use anchor_lang::prelude::Pubkey;
+
+        let mut below_r = BN254_R;
+        below_r[31] -= 1;
+        let inputs = Groth16WithdrawInputs::new(below_r, [7u8; 32], Pubkey::default(), 1_000_000_000, 0);
+        assert!(inputs.validate_canonical().is_ok());
+
+        // r itself and anything above it are out of range
+        for bad in [BN254_R, [0xffu8; 32]] {
+            let mut root = inputs.clone();
+            root.merkle_root = bad;
+            assert_eq!(root.validate_canonical().unwrap_err(), StealthError::NonCanonicalFieldElement.into());
+
+            let mut nullifier = inputs.clone();
+            nullifier.nullifier_hash = bad;
+            assert!(nullifier.validate_canonical().is_err());
+
+            let mut recipient = inputs.clone();
+            recipient.recipient = bad;
+            assert!(recipient.validate_canonical().is_err());
+        }
+    }
+
+    fn batch_withdrawal(nullifier: u8, proof_byte: u8) -> crate::instructions::VerifiedWithdrawal {
+        use crate::instructions::{Groth16WithdrawInputs, VerifiedWithdrawal};
+        use crate::zk::Groth16Proof;
+        use anchor_lang::prelude::Pubkey;
+
+        VerifiedWithdrawal {
+            public_inputs: Groth16WithdrawInputs::new([1u8; 32], [nullifier; 32], Pubkey::default(), 1_000_000_000, 0),
+            proof: Groth16Proof { pi_a: [proof_byte; 64], pi_b: [proof_byte; 128], pi_c: [proof_byte; 64] },
+        }
+    }
+
+    #[test]
+    fn test_verified_batch_rejects_mixed_proofs() {
+        use crate::error::StealthError;
+        use crate::instructions::verify_batch;
+
+        // Stand-in verifier: proofs filled with 1s are valid, anything else isn't
+        let checked = std::cell::Cell::new(0);
+        let verify = |proof: &crate::zk::Groth16Proof, inputs: &[[u8; 32]]| {
+            assert_eq!(inputs.len(), 4);
+            checked.set(checked.get() + 1);
+            Ok(proof.pi_a == [1u8; 64])
+        };
+
+        let valid = [batch_withdrawal(1, 1), batch_withdrawal(2, 1)];
+        assert!(verify_batch(&valid, verify).is_ok());
+        assert_eq!(checked.get(), 2);
+
+        // An invalid proof fails the batch, wherever it sits
+        checked.set(0);
+        let mixed = [batch_withdrawal(1, 1), batch_withdrawal(2, 9)];
+        assert_eq!(verify_batch(&mixed, verify).unwrap_err(), StealthError::InvalidProof.into());
+        assert_eq!(checked.get(), 2);
+
+        // ...and the proofs after it aren't checked
+        checked.set(0);
+        let mixed = [batch_withdrawal(1, 9), batch_withdrawal(2, 1)];
+        assert_eq!(verify_batch(&mixed, verify).unwrap_err(), StealthError::InvalidProof.into());
+        assert_eq!(checked.get(), 1);
+
+        // Verifier errors propagate too
+        let err = verify_batch(&valid, |_: &crate::zk::Groth16Proof, _: &[[u8; 32]]| {
+            Err(StealthError::ZkVerificationNotSupported.into())
+        })
+        .unwrap_err();
+        assert_eq!(err, StealthError::ZkVerificationNotSupported.into());
+    }
+
+    #[test]
+    fn test_verified_batch_shape() {
+        use crate::error::StealthError;
+        use crate::instructions::{verify_batch, MAX_VERIFIED_BATCH};
+
+        let accept = |_: &crate::zk::Groth16Proof, _: &[[u8; 32]]| Ok(true);
+
+        let full: Vec<_> = (0..MAX_VERIFIED_BATCH as u8).map(|i| batch_withdrawal(i, 1)).collect();
+        assert!(verify_batch(&full, accept).is_ok());
+
+        let too_many: Vec<_> = (0..=MAX_VERIFIED_BATCH as u8).map(|i| batch_withdrawal(i, 1)).collect();
+        for batch in [&[][..], &too_many[..]] {
+            assert_eq!(
+                verify_batch(batch, accept).unwrap_err(),
+                StealthError::InvalidVerifiedBatchSize.into()
+            );
+        }
+
+        // The same note twice would be paid twice before the nullifier exists
+        let duplicate = [batch_withdrawal(5, 1), batch_withdrawal(5, 1)];
+        assert_eq!(
+            verify_batch(&duplicate, accept).unwrap_err(),
+            StealthError::DuplicateBatchNullifier.into()
+        );
+
+        // Non-canonical inputs are rejected before any proof is checked
+        let mut non_canonical = batch_withdrawal(7, 1);
+        non_canonical.public_inputs.merkle_root = [0xffu8; 32];
+        assert_eq!(
+            verify_batch(&[non_canonical], |_: &crate::zk::Groth16Proof, _: &[[u8; 32]]| -> anchor_lang::Result<bool> {
+                panic!("proof checked before inputs")
+            })
+            .unwrap_err(),
+            StealthError::NonCanonicalFieldElement.into()
+        );
+    }
+
+    // ==================== Address Withdrawal Tests ====================
+
+    #[test]
+    fn test_address_withdraw_inputs_bind_recipient() {
+        use crate::instructions::AddressWithdrawPublicInputs;
+        use anchor_lang::prelude::Pubkey;
+
+        let recipient = Pubkey::new_unique();
+        let inputs = AddressWithdrawPublicInputs {
+            merkle_root: [1u8; 32],
+            nullifier_hash: [2u8; 32],
+            recipient,
+            deposit_slot: 7,
+        };
+
+        // root || nullifier_hash || recipient || deposit_slot
+        let bytes = inputs.to_bytes();
+        assert_eq!(bytes.len(), 104);
+        assert_eq!(&bytes[..32], &[1u8; 32]);
+        assert_eq!(&bytes[32..64], &[2u8; 32]);
+        assert_eq!(&bytes[64..96], recipient.as_ref());
+        assert_eq!(&bytes[96..], &7u64.to_le_bytes());
+
+        // A proof for one recipient doesn't verify for another
+        let redirected = AddressWithdrawPublicInputs {
+            recipient: Pubkey::new_unique(),
+            ..inputs.clone()
+        };
+        assert_ne!(redirected.to_bytes(), bytes);
+
+        // Nor can the deposit slot be backdated to skip min_age_slots
+        let backdated = AddressWithdrawPublicInputs {
+            deposit_slot: 0,
+            ..inputs
+        };
+        assert_ne!(backdated.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn test_stealth_withdraw_attestation_binds_stealth_address() {
+        use crate::instructions::WithdrawPublicInputs;
+        use crate::zk::verifier::OracleAttestation;
+        use anchor_lang::prelude::Pubkey;
+        use anchor_lang::solana_program::hash::hash;
+
+        let inputs = WithdrawPublicInputs {
+            merkle_root: [1u8; 32],
+            nullifier_hash: [2u8; 32],
+            stealth_address: Pubkey::new_unique(),
+            ephemeral_pubkey: [3u8; 32],
+            scan_pubkey: [4u8; 32],
+            spend_pubkey: [5u8; 32],
+            stealth_commitment: [6u8; 32],
+            deposit_slot: 0,
+        };
+
+        // The commitment follows the meta-address keys in the attested bytes
+        let bytes = inputs.to_bytes();
+        assert_eq!(bytes.len(), 232);
+        assert_eq!(&bytes[192..224], &[6u8; 32]);
+
+        let attestation = OracleAttestation {
+            proof_hash: [0u8; 32],
+            public_inputs_hash: hash(&bytes).to_bytes(),
+            verifier: [0u8; 32],
+            signature: [1u8; 64],
+            verified_at: 0,
+            vk_version: 1,
+            method: OracleAttestation::METHOD_GROTH16,
+        };
+        assert!(attestation.covers_inputs(&bytes));
+
+        // Swapping the stealth address invalidates the attestation
+        let redirected = WithdrawPublicInputs {
+            stealth_address: Pubkey::new_unique(),
+            ..inputs.clone()
+        };
+        assert!(!attestation.covers_inputs(&redirected.to_bytes()));
+
+        // So does swapping the commitment alongside it
+        let recommitted = WithdrawPublicInputs {
+            stealth_commitment: [7u8; 32],
+            ..inputs
+        };
+        assert!(!attestation.covers_inputs(&recommitted.to_bytes()));
+    }
+
+    #[test]
+    fn test_private_withdraw_groth16_verification_path() {
+        use crate::error::StealthError;
+        use crate::instructions::private_withdraw::verify_withdraw_proof;
+        use crate::instructions::{WithdrawProof, WithdrawPublicInputs};
+        use crate::instructions::AddressWithdrawPublicInputs;
+        use crate::zk::groth16::tests::{withdraw_circuit_fixture, WITHDRAW_AMOUNT, WITHDRAW_RECIPIENT};
+        use crate::zk::verifier::tests::oracle_with_window;
+        use crate::zk::verifier::OracleAttestation;
+        use crate::zk::{StoredVerificationKey, VerificationKey};
+        use anchor_lang::prelude::{AccountInfo, Pubkey};
+        use anchor_lang::AnchorSerialize;
+
+        let (vk, groth16, fields) = withdraw_circuit_fixture();
+        let mut proof = WithdrawProof {
+            proof: vec![7u8; 256],
+            public_inputs: WithdrawPublicInputs {
+                merkle_root: fields[0],
+                nullifier_hash: fields[1],
+                stealth_address: Pubkey::new_from_array(WITHDRAW_RECIPIENT),
+                ephemeral_pubkey: [3u8; 32],
+                scan_pubkey: [4u8; 32],
+                spend_pubkey: [5u8; 32],
+                stealth_commitment: [6u8; 32],
+                deposit_slot: 0,
+            },
+            attestation: None,
+        };
+
+        // The circuit's layout: root, nullifier hash, reduced recipient, amount
+        assert_eq!(proof.public_inputs.to_field_elements(WITHDRAW_AMOUNT), fields);
+        let to_address = AddressWithdrawPublicInputs {
+            merkle_root: fields[0],
+            nullifier_hash: fields[1],
+            recipient: Pubkey::new_from_array(WITHDRAW_RECIPIENT),
+            deposit_slot: 0,
+        };
+        assert_eq!(to_address.to_field_elements(WITHDRAW_AMOUNT), fields);
+
+        let stored = |vk: VerificationKey| StoredVerificationKey {
+            authority: Pubkey::default(),
+            vk_data: vk.try_to_vec().unwrap(),
+            version: 1,
+            vk_hash: [0u8; 32],
+            bump: 255,
+        };
+        let filler = |ic_count: usize| VerificationKey {
+            alpha: [1u8; 64],
+            beta: [1u8; 128],
+            gamma: [1u8; 128],
+            delta: [1u8; 128],
+            ic: vec![[1u8; 64]; ic_count],
+        };
+
+        let sysvar_key = anchor_lang::solana_program::sysvar::instructions::id();
+        let (mut lamports, mut data) = (0u64, [0u8; 0]);
+        let sysvar = AccountInfo::new(&sysvar_key, false, false, &mut lamports, &mut data, &sysvar_key, false, 0);
+
+        // Without the key the oracle path runs: skipped in dev builds, an
+        // attestation is required in production
+        let mut oracle = oracle_with_window(300);
+        #[cfg(not(feature = "production"))]
+        assert!(verify_withdraw_proof(&proof, WITHDRAW_AMOUNT, &sysvar, &oracle, None).is_ok());
+        #[cfg(feature = "production")]
+        assert_eq!(
+            verify_withdraw_proof(&proof, WITHDRAW_AMOUNT, &sysvar, &oracle, None).unwrap_err(),
+            StealthError::MissingAttestation.into()
+        );
+
+        // An attestation for another key version is refused on this path
+        let mut stale = proof.clone();
+        stale.attestation = Some(OracleAttestation {
+            proof_hash: [0u8; 32],
+            public_inputs_hash: [0u8; 32],
+            verifier: [1u8; 32],
+            signature: [1u8; 64],
+            verified_at: 0,
+            vk_version: oracle.vk_version + 1,
+            method: OracleAttestation::METHOD_GROTH16,
+        });
+        assert!(verify_withdraw_proof(&stale, WITHDRAW_AMOUNT, &sysvar, &oracle, None).is_err());
+        #[cfg(not(feature = "production"))]
+        assert_eq!(
+            verify_withdraw_proof(&stale, WITHDRAW_AMOUNT, &sysvar, &oracle, None).unwrap_err(),
+            StealthError::VerificationKeyVersionMismatch.into()
+        );
+
+        // The oracle's kill switch stops the attestation path in every build
+        oracle.is_active = false;
+        assert_eq!(
+            verify_withdraw_proof(&proof, WITHDRAW_AMOUNT, &sysvar, &oracle, None).unwrap_err(),
+            StealthError::VerificationMethodNotAvailable.into()
+        );
+
+        // With it a real withdraw circuit proof goes through the pairing
+        // check in every build
+        let vk = stored(vk);
+        proof.proof = [&groth16.pi_a[..], &groth16.pi_b[..], &groth16.pi_c[..]].concat();
+        assert!(verify_withdraw_proof(&proof, WITHDRAW_AMOUNT, &sysvar, &oracle, Some(&vk)).is_ok());
+
+        // The proof doesn't carry over to another recipient or pool...
+        let mut redirected = proof.clone();
+        redirected.public_inputs.stealth_address = Pubkey::new_unique();
+        let err = verify_withdraw_proof(&redirected, WITHDRAW_AMOUNT, &sysvar, &oracle, Some(&vk)).unwrap_err();
+        assert_eq!(err, StealthError::InvalidProof.into());
+        let err = verify_withdraw_proof(&proof, 10 * WITHDRAW_AMOUNT, &sysvar, &oracle, Some(&vk)).unwrap_err();
+        assert_eq!(err, StealthError::InvalidProof.into());
+
+        // ...and a known-bad proof fails against the real key
+        let mut bad = proof.clone();
+        bad.proof[192..].copy_from_slice(&groth16.pi_a);
+        let err = verify_withdraw_proof(&bad, WITHDRAW_AMOUNT, &sysvar, &oracle, Some(&vk)).unwrap_err();
+        assert_eq!(err, StealthError::InvalidProof.into());
+
+        // Points off the curve never verify
+        let err = verify_withdraw_proof(&proof, WITHDRAW_AMOUNT, &sysvar, &oracle, Some(&stored(filler(5)))).unwrap_err();
+        assert_eq!(err, StealthError::InvalidProof.into());
+
+        // A key for a different circuit is rejected
+        let err = verify_withdraw_proof(&proof, WITHDRAW_AMOUNT, &sysvar, &oracle, Some(&stored(filler(2)))).unwrap_err();
+        assert_eq!(err, StealthError::PublicInputCountMismatch.into());
+
+        // And a degenerate proof never verifies
+        proof.proof = vec![0u8; 256];
+        let err = verify_withdraw_proof(&proof, WITHDRAW_AMOUNT, &sysvar, &oracle, Some(&vk)).unwrap_err();
+        assert_eq!(err, StealthError::InvalidProof.into());
+    }
+
+    // ==================== Announcement Log Tests ====================
+
+    #[test]
+    fn test_announcement_log_ring_buffer() {
+        use crate::state::announcement_log::{AnnouncementLog, AnnouncementEntry};
+        use anchor_lang::prelude::Pubkey;
+
+        let mut log = AnnouncementLog {
+            authority: Pubkey::default(),
+            log_id: 0,
+            entry_count: 0,
+            max_entries: 3,
+            is_active: true,
+            created_slot: 0,
+            last_entry_slot: 0,
+            next_index: 0,
+            total_entries: 0,
+            bump: 0,
+            entries: Vec::new(),
+        };
+        let entry = |slot: u64| AnnouncementEntry {
+            ephemeral_pubkey: [slot as u8; 32],
+            slot,
+            ..Default::default()
+        };
+        let slots = |log: &AnnouncementLog| -> Vec<u64> {
+            log.iter_chronological().map(|e| e.slot).collect()
+        };
+
+        assert_eq!(log.oldest_slot(), None);
+        assert!(log.covers_slot(0));
+
+        for slot in [10, 20, 30] {
+            log.record(entry(slot));
+        }
+        assert!(log.is_full());
+        assert!(log.can_add_entry());
+        assert_eq!(slots(&log), vec![10, 20, 30]);
+        assert!(log.covers_slot(0));
+
+        // Wraps around, dropping the oldest entries
+        log.record(entry(40));
+        log.record(entry(50));
+        assert_eq!(log.entry_count, 3);
+        assert_eq!(log.total_entries, 5);
+        assert_eq!(log.last_entry_slot, 50);
+        assert_eq!(slots(&log), vec![30, 40, 50]);
+        assert_eq!(log.oldest_slot(), Some(30));
+
+        // Range reads stay in slot order across the wrap
+        let range: Vec<u64> = log.entries_in_slot_range(35, 50).iter().map(|e| e.slot).collect();
+        assert_eq!(range, vec![40, 50]);
+        assert!(log.entries_in_slot_range(51, u64::MAX).is_empty());
+
+        // Slots at or before the oldest retained entry may have been overwritten
+        assert!(log.covers_slot(31));
+        assert!(!log.covers_slot(30));
+        assert!(!log.covers_slot(0));
+    }
+
+    // ==================== Announcement Index Tests ====================
+
+    #[test]
+    fn test_announcement_buckets() {
+        use crate::state::announcement_index::{
+            announcement_bucket, announcement_buckets, index_bucket_for_slot, AnnouncementIndex,
+            INDEX_BUCKET_MARGIN, SLOTS_PER_BUCKET,
+        };
+
+        assert_eq!(announcement_bucket(0), 0);
+        assert_eq!(announcement_bucket(SLOTS_PER_BUCKET - 1), 0);
+        assert_eq!(announcement_bucket(SLOTS_PER_BUCKET), 1);
+        assert_eq!(announcement_buckets(15_000, 42_000).collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+        assert_eq!(announcement_buckets(42_000, 42_000).count(), 1);
+
+        // Senders switch to the next bucket's index for the last margin slots
+        let margin_start = SLOTS_PER_BUCKET - INDEX_BUCKET_MARGIN;
+        assert_eq!(index_bucket_for_slot(margin_start - 1), 0);
+        assert_eq!(index_bucket_for_slot(margin_start), 1);
+        assert_eq!(index_bucket_for_slot(SLOTS_PER_BUCKET), 1);
+
+        // ...and that index accepts them whether they land before or after the edge
+        let next = AnnouncementIndex {
+            bucket: 1,
+            created_slot: 0,
+            total_announcements: 0,
+            bump: 0,
+            announcements: Vec::new(),
+        };
+        assert!(next.accepts_slot(margin_start));
+        assert!(next.accepts_slot(SLOTS_PER_BUCKET - 1));
+        assert!(next.accepts_slot(2 * SLOTS_PER_BUCKET - 1));
+        assert!(!next.accepts_slot(margin_start - 1));
+        assert!(!next.accepts_slot(2 * SLOTS_PER_BUCKET));
+        assert_eq!(next.first_indexed_slot(), margin_start);
+
+        // A full index fits the 10KB account init limit
+        assert!(AnnouncementIndex::SIZE <= 10_240);
+        assert!(AnnouncementIndex::SIZE + 32 > 10_240);
+    }
+
+    #[test]
+    fn test_announcement_index_coverage() {
+        use crate::state::announcement_index::AnnouncementIndex;
+        use anchor_lang::prelude::Pubkey;
+
+        // Bucket 2 covers slots 20_000..30_000; created partway through
+        let mut index = AnnouncementIndex {
+            bucket: 2,
+            created_slot: 25_000,
+            total_announcements: 0,
+            bump: 0,
+            announcements: Vec::new(),
+        };
+        assert_eq!(index.start_slot(), 20_000);
+        assert!(!index.covers_slot(0));
+        assert!(!index.covers_slot(25_000));
+        assert!(index.covers_slot(25_001));
+
+        // Created inside the margin before the bucket: misses announcements
+        // sent there, so only covers from after its creation
+        index.created_slot = 19_900;
+        assert!(!index.covers_slot(0));
+        assert!(index.covers_slot(19_901));
+
+        // Created before that margin: covers all of it
+        index.created_slot = 19_000;
+        assert!(index.covers_slot(0));
+
+        for i in 0..AnnouncementIndex::MAX_ENTRIES {
+            index.record(Pubkey::new_from_array([i as u8; 32]));
+        }
+        assert!(index.is_complete());
+
+        // Past capacity announcements are counted but not listed
+        index.record(Pubkey::new_unique());
+        assert_eq!(index.announcements.len(), AnnouncementIndex::MAX_ENTRIES);
+        assert_eq!(index.total_announcements, AnnouncementIndex::MAX_ENTRIES as u64 + 1);
+        assert!(!index.is_complete());
+        assert!(!index.covers_slot(0));
+    }
+
     // ==================== Edge Case Tests ====================
 
     #[test]
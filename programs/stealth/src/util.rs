@@ -0,0 +1,69 @@
+//! Helpers shared across instructions
+
+use anchor_lang::prelude::*;
+use crate::error::StealthError;
+
+/// Move `amount` lamports from an account this program may debit to `to`
+///
+/// Both sides use checked math, so an underfunded source or a destination
+/// that would pass `u64::MAX` fails with `ArithmeticOverflow` before either
+/// balance changes. Moving lamports from an account to itself is a no-op.
+pub fn safe_transfer_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    if from.key == to.key {
+        return Ok(());
+    }
+
+    let from_balance = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(StealthError::ArithmeticOverflow)?;
+    let to_balance = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(StealthError::ArithmeticOverflow)?;
+
+    **from.try_borrow_mut_lamports()? = from_balance;
+    **to.try_borrow_mut_lamports()? = to_balance;
+    Ok(())
+}
+
+/// Require a payout to a possibly nonexistent system account to be
+/// rent-exempt on its own
+///
+/// Stealth addresses usually don't exist until paid, and the runtime
+/// refuses to leave a new 0-data account below `Rent::minimum_balance(0)`
+/// (`min_balance`), failing the whole transaction with an opaque rent
+/// error. Denomination-sized payouts always clear it; small splits and
+/// fee-heavy withdrawals may not.
+pub fn ensure_rent_exempt_payout(amount: u64, min_balance: u64) -> Result<()> {
+    require!(amount >= min_balance, StealthError::BelowRentExempt);
+    Ok(())
+}
+
+/// Stage `new_authority` to take over an account's authority
+///
+/// Nothing changes hands until that key signs the matching accept, so a
+/// typo'd key can't lock the account. Proposing `Pubkey::default()`
+/// withdraws an outstanding proposal.
+pub fn propose_authority(pending_authority: &mut Pubkey, new_authority: Pubkey) {
+    *pending_authority = new_authority;
+}
+
+/// Hand the authority to `signer` if it is the pending authority
+///
+/// Clears the proposal and returns the previous authority.
+pub fn accept_authority(
+    authority: &mut Pubkey,
+    pending_authority: &mut Pubkey,
+    signer: &Pubkey,
+) -> Result<Pubkey> {
+    require!(
+        *pending_authority != Pubkey::default() && pending_authority == signer,
+        StealthError::NotPendingAuthority
+    );
+
+    let old_authority = *authority;
+    *authority = *signer;
+    *pending_authority = Pubkey::default();
+    Ok(old_authority)
+}
@@ -15,6 +15,7 @@
 //! For Solana 1.18, ZK verification is disabled (returns error).
 
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::alt_bn128::{self, AltBn128Error};
 use super::types::*;
 use crate::error::StealthError;
 
@@ -23,15 +24,32 @@ use crate::error::StealthError;
 pub const SCALAR_FIELD_MODULUS: [u8; 32] = [
     0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29,
     0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81, 0x58, 0x5d,
-    0x97, 0x81, 0x6a, 0x91, 0x68, 0x71, 0xca, 0x8d,
-    0x3c, 0x20, 0x8c, 0x16, 0xd8, 0x7c, 0xfd, 0x47,
+    0x28, 0x33, 0xe8, 0x48, 0x79, 0xb9, 0x70, 0x91,
+    0x43, 0xe1, 0xf5, 0x93, 0xf0, 0x00, 0x00, 0x01,
 ];
 
+// ============================================
+// alt_bn128 primitives
+// ============================================
+//
+// Byte layouts follow the syscalls (EIP-196/197), all big-endian:
+// - G1: x || y, 32 bytes each (64 total); the identity is all zeros
+// - G2: x_c1 || x_c0 || y_c1 || y_c0, 32 bytes each (128 total), i.e. the
+//   imaginary part first. snarkjs lists G2 coordinates as [c0, c1], so each
+//   pair is swapped when exporting.
+// - Scalar: 32 bytes
+//
+// Off-chain they run through the solana-program host implementation, so
+// `verify_groth16` does the full check in tests too.
+
 /// Negate a G1 point by negating the y-coordinate
 /// On BN254, if P = (x, y), then -P = (x, p - y)
-/// where p is the field modulus
-pub fn negate_g1(point: &[u8; G1_SIZE]) -> [u8; G1_SIZE] {
+/// where p is the field modulus. The identity (all zeros) is its own negation.
+pub(crate) fn g1_negate(point: &[u8; G1_SIZE]) -> [u8; G1_SIZE] {
     let mut result = *point;
+    if point[32..64].iter().all(|&b| b == 0) {
+        return result;
+    }
 
     // BN254 base field modulus p (in LITTLE-ENDIAN)
     // p = 21888242871839275222246405745257275088696311157297823662689037894645226208583
@@ -63,6 +81,51 @@ pub fn negate_g1(point: &[u8; G1_SIZE]) -> [u8; G1_SIZE] {
     result
 }
 
+/// Add two G1 points: a + b
+pub(crate) fn g1_add(
+    a: &[u8; G1_SIZE],
+    b: &[u8; G1_SIZE],
+) -> std::result::Result<[u8; G1_SIZE], AltBn128Error> {
+    let mut input = [0u8; 2 * G1_SIZE];
+    input[..G1_SIZE].copy_from_slice(a);
+    input[G1_SIZE..].copy_from_slice(b);
+
+    let output = alt_bn128::prelude::alt_bn128_addition(&input)?;
+    to_g1(&output)
+}
+
+/// Multiply a G1 point by a big-endian scalar: scalar * point
+pub(crate) fn g1_scalar_mul(
+    point: &[u8; G1_SIZE],
+    scalar: &[u8; 32],
+) -> std::result::Result<[u8; G1_SIZE], AltBn128Error> {
+    let mut input = [0u8; G1_SIZE + 32];
+    input[..G1_SIZE].copy_from_slice(point);
+    input[G1_SIZE..].copy_from_slice(scalar);
+
+    let output = alt_bn128::prelude::alt_bn128_multiplication(&input)?;
+    to_g1(&output)
+}
+
+/// Multi-pairing check over concatenated (G1, G2) pairs
+///
+/// `pairs` is a sequence of 192-byte entries (64-byte G1 then 128-byte G2).
+/// Returns whether the product of all pairings is the identity.
+pub(crate) fn alt_bn128_pairing(pairs: &[u8]) -> std::result::Result<bool, AltBn128Error> {
+    if !pairs.len().is_multiple_of(G1_SIZE + G2_SIZE) {
+        return Err(AltBn128Error::InvalidInputData);
+    }
+
+    // The result is 32 bytes: 0x00...001 for true, 0x00...000 for false
+    let output = alt_bn128::prelude::alt_bn128_pairing(pairs)?;
+    Ok(output.len() == 32 && output[31] == 1 && output[..31].iter().all(|&b| b == 0))
+}
+
+/// Copy a syscall output into a G1 point
+fn to_g1(output: &[u8]) -> std::result::Result<[u8; G1_SIZE], AltBn128Error> {
+    output.try_into().map_err(|_| AltBn128Error::UnexpectedError)
+}
+
 /// Verify a Groth16 proof
 ///
 /// This function verifies that a Groth16 proof is valid for the given
@@ -76,6 +139,7 @@ pub fn negate_g1(point: &[u8; G1_SIZE]) -> [u8; G1_SIZE] {
 /// # Returns
 /// * `Ok(true)` if the proof is valid
 /// * `Ok(false)` if the proof is invalid
+/// * `Err(PublicInputCountMismatch)` if `public_inputs.len() != vk.ic.len() - 1`
 /// * `Err(...)` if verification failed due to an error
 ///
 /// # Verification Equation
@@ -91,11 +155,17 @@ pub fn verify_groth16(
     public_inputs: &[[u8; 32]],
     vk: &VerificationKey,
 ) -> Result<bool> {
-    // Validate inputs
-    require!(
-        public_inputs.len() + 1 == vk.ic.len(),
-        StealthError::InvalidProofInputs
-    );
+    // IC holds one point per public input plus the constant term; check this
+    // before any syscall so a mismatch fails with a clear error
+    if public_inputs.len() + 1 != vk.ic.len() {
+        msg!(
+            "Expected {} public inputs for {} IC points, got {}",
+            vk.ic.len().saturating_sub(1),
+            vk.ic.len(),
+            public_inputs.len()
+        );
+        return err!(StealthError::PublicInputCountMismatch);
+    }
 
     // Validate that public inputs are in the scalar field
     for input in public_inputs {
@@ -113,46 +183,22 @@ pub fn verify_groth16(
 
     // Use Solana's alt_bn128 syscalls for verification
     // Available in solana-program 1.16+
-    #[cfg(target_os = "solana")]
     {
-        use anchor_lang::solana_program::alt_bn128::{
-            prelude::*,
-            AltBn128Error,
-        };
-
         // Step 1: Compute vk_x = IC[0] + Σ(public_inputs[i] · IC[i+1])
         let mut vk_x = vk.ic[0];
         msg!("Computing vk_x with {} public inputs and {} IC points", public_inputs.len(), vk.ic.len());
 
         for (i, input) in public_inputs.iter().enumerate() {
-            // Scalar multiplication: public_input[i] * IC[i+1]
-            let mut mul_input = [0u8; 96]; // 64 bytes point + 32 bytes scalar
-            mul_input[0..64].copy_from_slice(&vk.ic[i + 1]);
-            mul_input[64..96].copy_from_slice(input);
-
-            let scaled_ic = match alt_bn128_multiplication(&mul_input) {
-                Ok(result) => {
-                    let mut point = [0u8; G1_SIZE];
-                    point.copy_from_slice(&result);
-                    point
-                }
+            let scaled_ic = match g1_scalar_mul(&vk.ic[i + 1], input) {
+                Ok(point) => point,
                 Err(e) => {
                     msg!("Scalar mul failed for input {}: {:?}", i, e);
                     return Ok(false);
                 }
             };
 
-            // Point addition: vk_x + scaled_ic
-            let mut add_input = [0u8; 128]; // 64 bytes + 64 bytes
-            add_input[0..64].copy_from_slice(&vk_x);
-            add_input[64..128].copy_from_slice(&scaled_ic);
-
-            vk_x = match alt_bn128_addition(&add_input) {
-                Ok(result) => {
-                    let mut point = [0u8; G1_SIZE];
-                    point.copy_from_slice(&result);
-                    point
-                }
+            vk_x = match g1_add(&vk_x, &scaled_ic) {
+                Ok(point) => point,
                 Err(e) => {
                     msg!("Point add failed for input {}: {:?}", i, e);
                     return Ok(false);
@@ -162,12 +208,12 @@ pub fn verify_groth16(
         msg!("vk_x computed successfully");
 
         // Step 2: Negate A for the pairing equation
-        let neg_a = negate_g1(&proof.pi_a);
+        let neg_a = g1_negate(&proof.pi_a);
 
         // Step 3: Prepare pairing input
         // Format: [(G1, G2), (G1, G2), (G1, G2), (G1, G2)]
         // Pairs: (-A, B), (α, β), (vk_x, γ), (C, δ)
-        let mut pairing_input = Vec::with_capacity(768);
+        let mut pairing_input = Vec::with_capacity(4 * (G1_SIZE + G2_SIZE));
 
         // Pair 1: e(-A, B)
         pairing_input.extend_from_slice(&neg_a);
@@ -187,12 +233,8 @@ pub fn verify_groth16(
 
         // Step 4: Perform multi-pairing check
         // Result should be 1 (identity) if proof is valid
-        msg!("Pairing input size: {} bytes (expected 768)", pairing_input.len());
         match alt_bn128_pairing(&pairing_input) {
-            Ok(result) => {
-                // Check if result equals 1 (identity element)
-                // The result is 32 bytes: 0x00...001 for true, 0x00...000 for false
-                let is_valid = result[31] == 1 && result[0..31].iter().all(|&b| b == 0);
+            Ok(is_valid) => {
                 msg!("Groth16 verification result: {}", is_valid);
                 Ok(is_valid)
             }
@@ -202,14 +244,6 @@ pub fn verify_groth16(
             }
         }
     }
-
-    // Non-Solana target (tests): fall back to stub
-    #[cfg(not(target_os = "solana"))]
-    {
-        msg!("Groth16 verification: running in non-Solana environment");
-        msg!("Use oracle attestation for off-chain verification");
-        err!(StealthError::ZkVerificationNotSupported)
-    }
 }
 
 /// Check if a scalar is valid (less than the scalar field modulus)
@@ -227,7 +261,7 @@ fn is_valid_scalar(scalar: &[u8; 32]) -> bool {
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
 
     #[test]
@@ -261,4 +295,279 @@ mod tests {
         too_large[31] = too_large[31].wrapping_add(1);
         assert!(!is_valid_scalar(&too_large));
     }
+
+    // vk_alpha_1 and vk_beta_2 from the snarkjs-exported frontend/verification_key.json.
+    // Multiples of alpha were computed independently over the BN254 base field.
+    const ALPHA: &str = "2d4d9aa7e302d9df41749d5507949d05dbea33fbb16c643b22f599a2be6df2e2\
+                         14bedd503c37ceb061d8ec60209fe345ce89830a19230301f076caff004d1926";
+    const NEG_ALPHA: &str = "2d4d9aa7e302d9df41749d5507949d05dbea33fbb16c643b22f599a2be6df2e2\
+                             1ba57122a4f9d1795677595660e17517c8f7e7874f4ec78b4ba9c117d82fe421";
+    const TWO_ALPHA: &str = "1052e2d97ed8532ca2cd11c224ff3b2d385012b452d39eff0acd8b542c261d99\
+                             1c811b10f15a3dc9b258428e8e0b892fcae2f039f438daf6c20ab3d1b7c3e4e1";
+    const THREE_ALPHA: &str = "0cedbed42cc2e5a683d973b6d325cd626266001dfb7393325b05296baf9ec21c\
+                               1530d99f1abc9aa2c6735bfcf8c41d835d55000bb3b1493b2a2690b79d20f066";
+    const BETA: &str = "0967032fcbf776d1afc985f88877f182d38480a653f2decaa9794cbc3bf3060c\
+                        0e187847ad4c798374d0d6732bf501847dd68bc0e071241e0213bc7fc13db7ab\
+                        304cfbd1e08a704a99f5e847d93f8c3caafddec46b7a0d379da69a4d112346a7\
+                        1739c1b1a457a8c7313123d24d2f9192f896b7c63eea05a9d57f06547ad0cec8";
+
+    // A real proof for the circom withdraw circuit (circuits/circom/withdraw.circom,
+    // depth 8) under frontend/public/circuits/groth16/withdraw's zkey, which
+    // shares alpha and beta with the key above. The witness spends a note of
+    // 1 SOL to the pubkey `[200, 201, ..., 231]` (`WITHDRAW_RECIPIENT`) from
+    // an otherwise empty tree; the public inputs are merkleRoot,
+    // nullifierHash, recipient (reduced mod r) and amount.
+    const GAMMA: &str = "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2\
+                         1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed\
+                         090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b\
+                         12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa";
+    const WITHDRAW_DELTA: &str = "17cf505bcdc5f8fff62bc32008e539c3f4ecde7f53556e80860bbae863402049\
+                                  2fe7645c2be4a5885ef585ab76732d1ab7976ed35e44f2370867fa7deb79aa77\
+                                  1f4db5d6cbb1a09b7e9480838fcbf6627e7d08ecc5b78eb621b0569f2c0df298\
+                                  282595968dd8cc1a52d2d529c2a07c55298fd9104079d9350972ff73698334cd";
+    const WITHDRAW_IC: &str = "0e4a669089926a624e8b5a73f0643dcccf88a07631f884b0ae6bc22b7e94c55b\
+                               2f68f46737fcb24b1ad2dfe2daaaa930e3481d8c5ba7c36d76acbd25d4fba51d\
+                               0ca9bb76c8de4714bd84e6feaa724ad737314a96ff384140ed9883e9a8d7be45\
+                               22c0b039f37e8f18b3392d8a6669ce620b40b424e592ba8ed2bc22d22652478d\
+                               2c2aed63c4a97041ad5a3c7d356133bab7f6050219f189776e9001ce4b4c0e59\
+                               2d61563255f333e3ead20ff53e559cc221f2b7c88bcad367a703448daf144702\
+                               2dbbd82d775866b5d2bd9e6d617d3b504e5bf72660cc1a5401082406cea84a7f\
+                               002e2070afb14412bb2dae0099bf1cb146ee03a7029fcd14312bcf59380f2e5c\
+                               2c8b3f4360c298c25f03313928597645e3d0adee4d4846cfce7cea4ef3210789\
+                               238d01fde1864a646e379972c58dddeae76230da4dd050281476486cda48567f";
+    const WITHDRAW_PROOF: &str = "22b89bb83e4f80ae677c01e6346b87e86c45c309545cb293065d78e0554ccd87\
+                                  0a8a54e4fb7989814b401f5a1933decd94523556251c6d5e554515710480dae0\
+                                  0c7292dff0e687c5ba2b6823d3d98df226491714fb1d6c4b057a4c8ceb10e0e7\
+                                  2a066ed7d4c8cbaf6edce2d1c0821b6a2bf5866788d3ea68d824d0552545f70b\
+                                  0c6bcaf09b4f8e2d132173c29ad911c02edce44c5916d5e01a3c1da50a48aa3b\
+                                  2d4d09db9638cd602c732dedcf58579f9625744177ecd0de0b396e48c8ab30db\
+                                  1ab058edeba15fd3c7f9e961009bafdefe8dffc77c157fe11634f0ab3f1dba54\
+                                  2b21f488b4729ea1818fdeb732d7901d8dc5062d62a75353dd899ad3e0b1ac99";
+    const WITHDRAW_PUBLIC_INPUTS: &str = "2649cd9cab881fa3fe3dcce2d5dc7bc79ea46eac476b050879cdbedd36c45c8c\
+                                          2af7e305d76bc289f7e1d3e0c3173206532ac4188eed24ca9507a67e42142667\
+                                          0738910048074e28ef90bbf9ced07563380a39b9f5f81c9ad15a0c9424e5e6e3\
+                                          000000000000000000000000000000000000000000000000000000003b9aca00";
+
+    /// Recipient pubkey bound by the withdraw circuit fixture
+    pub(crate) const WITHDRAW_RECIPIENT: [u8; 32] = {
+        let mut recipient = [0u8; 32];
+        let mut i = 0;
+        while i < 32 {
+            recipient[i] = 200 + i as u8;
+            i += 1;
+        }
+        recipient
+    };
+
+    /// Amount (lamports) bound by the withdraw circuit fixture
+    pub(crate) const WITHDRAW_AMOUNT: u64 = 1_000_000_000;
+
+    /// The withdraw circuit's key, a proof under it, and its public inputs
+    pub(crate) fn withdraw_circuit_fixture() -> (VerificationKey, Groth16Proof, [[u8; 32]; 4]) {
+        let ic: [u8; 5 * G1_SIZE] = from_hex(WITHDRAW_IC);
+        let vk = VerificationKey {
+            alpha: from_hex(ALPHA),
+            beta: from_hex(BETA),
+            gamma: from_hex(GAMMA),
+            delta: from_hex(WITHDRAW_DELTA),
+            ic: ic.chunks(G1_SIZE).map(|point| point.try_into().unwrap()).collect(),
+        };
+        let proof = Groth16Proof::from_bytes(&from_hex::<256>(WITHDRAW_PROOF)).unwrap();
+        let inputs: [u8; 128] = from_hex(WITHDRAW_PUBLIC_INPUTS);
+        let inputs = [0, 1, 2, 3].map(|i| inputs[32 * i..32 * (i + 1)].try_into().unwrap());
+        (vk, proof, inputs)
+    }
+
+    fn from_hex<const N: usize>(hex: &str) -> [u8; N] {
+        let hex: String = hex.split_whitespace().collect();
+        assert_eq!(hex.len(), 2 * N);
+        let mut out = [0u8; N];
+        for (i, byte) in out.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap();
+        }
+        out
+    }
+
+    fn scalar(value: u8) -> [u8; 32] {
+        let mut s = [0u8; 32];
+        s[31] = value;
+        s
+    }
+
+    fn pairing_input(pairs: &[([u8; G1_SIZE], [u8; G2_SIZE])]) -> Vec<u8> {
+        pairs.iter().flat_map(|(g1, g2)| g1.iter().chain(g2.iter()).copied()).collect()
+    }
+
+    /// A key and a proof that satisfies it for `public_inputs`
+    ///
+    /// beta = gamma = delta = B, so the pairing product collapses to
+    /// e(-A + alpha + vk_x + C, B) and A = alpha + vk_x + C passes. Not a
+    /// circuit proof, but it runs the real verification equation.
+    pub(crate) fn synthetic_groth16(public_inputs: &[[u8; 32]]) -> (VerificationKey, Groth16Proof) {
+        let alpha = from_hex::<G1_SIZE>(ALPHA);
+        let beta = from_hex::<G2_SIZE>(BETA);
+
+        let ic: Vec<[u8; G1_SIZE]> = (0..=public_inputs.len())
+            .map(|i| g1_scalar_mul(&alpha, &scalar(i as u8 + 2)).unwrap())
+            .collect();
+        let vk_x = public_inputs.iter().zip(&ic[1..]).fold(ic[0], |acc, (input, point)| {
+            g1_add(&acc, &g1_scalar_mul(point, input).unwrap()).unwrap()
+        });
+        let pi_c = g1_scalar_mul(&alpha, &scalar(7)).unwrap();
+        let pi_a = g1_add(&g1_add(&alpha, &vk_x).unwrap(), &pi_c).unwrap();
+
+        let vk = VerificationKey { alpha, beta, gamma: beta, delta: beta, ic };
+        (vk, Groth16Proof { pi_a, pi_b: beta, pi_c })
+    }
+
+    fn vk_with_ic(ic_points: usize) -> VerificationKey {
+        VerificationKey {
+            alpha: [1u8; G1_SIZE],
+            beta: [1u8; G2_SIZE],
+            gamma: [1u8; G2_SIZE],
+            delta: [1u8; G2_SIZE],
+            ic: vec![[1u8; G1_SIZE]; ic_points],
+        }
+    }
+
+    #[test]
+    fn test_public_input_count_mismatch() {
+        let proof = Groth16Proof {
+            pi_a: [1u8; G1_SIZE],
+            pi_b: [1u8; G2_SIZE],
+            pi_c: [1u8; G1_SIZE],
+        };
+
+        // Too few, too many, and a key with no IC points at all
+        for (inputs, ic_points) in [(0, 2), (2, 2), (1, 0)] {
+            let err = verify_groth16(&proof, &vec![scalar(1); inputs], &vk_with_ic(ic_points)).unwrap_err();
+            assert_eq!(err, StealthError::PublicInputCountMismatch.into());
+        }
+
+        // A matching count gets past the check; the filler points aren't on
+        // the curve, so the proof is then rejected
+        assert!(!verify_groth16(&proof, &[scalar(1)], &vk_with_ic(2)).unwrap());
+    }
+
+    #[test]
+    fn test_verify_groth16_synthetic_proof() {
+        let inputs = [scalar(3), scalar(5)];
+        let (vk, proof) = synthetic_groth16(&inputs);
+        assert!(verify_groth16(&proof, &inputs, &vk).unwrap());
+
+        // Different public inputs move vk_x, so the same proof fails
+        assert!(!verify_groth16(&proof, &[scalar(3), scalar(6)], &vk).unwrap());
+
+        // As does a proof with C swapped for another point
+        let tampered = Groth16Proof { pi_c: vk.alpha, ..proof.clone() };
+        assert!(!verify_groth16(&tampered, &inputs, &vk).unwrap());
+    }
+
+    #[test]
+    fn test_verify_groth16_withdraw_circuit_proof() {
+        let (vk, proof, inputs) = withdraw_circuit_fixture();
+        assert!(verify_groth16(&proof, &inputs, &vk).unwrap());
+
+        // Any other amount (or recipient) fails
+        let mut other_amount = inputs;
+        other_amount[3][31] ^= 1;
+        assert!(!verify_groth16(&proof, &other_amount, &vk).unwrap());
+        let mut other_recipient = inputs;
+        other_recipient[2][31] ^= 1;
+        assert!(!verify_groth16(&proof, &other_recipient, &vk).unwrap());
+    }
+
+    #[test]
+    fn test_proof_from_parts_checks_lengths() {
+        let a = [2u8; G1_SIZE];
+        let b = [3u8; G2_SIZE];
+        let c = [4u8; G1_SIZE];
+
+        let proof = Groth16Proof::from_parts(&a, &b, &c).unwrap();
+        assert_eq!(proof.pi_a, a);
+        assert_eq!(proof.pi_b, b);
+        assert_eq!(proof.pi_c, c);
+
+        for (a_len, b_len, c_len) in [(63, 128, 64), (64, 127, 64), (64, 128, 65), (128, 64, 64)] {
+            let err = Groth16Proof::from_parts(&vec![2u8; a_len], &vec![3u8; b_len], &vec![4u8; c_len]).unwrap_err();
+            assert_eq!(err, StealthError::MalformedProof.into());
+        }
+
+        let bytes: Vec<u8> = a.iter().chain(&b).chain(&c).copied().collect();
+        assert_eq!(Groth16Proof::from_bytes(&bytes).unwrap().pi_b, b);
+        assert_eq!(
+            Groth16Proof::from_bytes(&bytes[1..]).unwrap_err(),
+            StealthError::MalformedProof.into()
+        );
+    }
+
+    #[test]
+    fn test_g1_negate() {
+        let alpha = from_hex::<G1_SIZE>(ALPHA);
+        assert_eq!(g1_negate(&alpha), from_hex::<G1_SIZE>(NEG_ALPHA));
+        assert_eq!(g1_negate(&g1_negate(&alpha)), alpha);
+
+        // The identity stays the identity instead of becoming (0, p)
+        assert_eq!(g1_negate(&[0u8; G1_SIZE]), [0u8; G1_SIZE]);
+    }
+
+    #[test]
+    fn test_g1_add() {
+        let alpha = from_hex::<G1_SIZE>(ALPHA);
+        let two_alpha = from_hex::<G1_SIZE>(TWO_ALPHA);
+
+        assert_eq!(g1_add(&alpha, &alpha).unwrap(), two_alpha);
+        assert_eq!(g1_add(&two_alpha, &alpha).unwrap(), from_hex::<G1_SIZE>(THREE_ALPHA));
+        assert_eq!(g1_add(&alpha, &[0u8; G1_SIZE]).unwrap(), alpha);
+        assert_eq!(g1_add(&alpha, &g1_negate(&alpha)).unwrap(), [0u8; G1_SIZE]);
+
+        // A point off the curve is rejected
+        let mut off_curve = alpha;
+        off_curve[63] ^= 1;
+        assert!(g1_add(&off_curve, &alpha).is_err());
+    }
+
+    #[test]
+    fn test_g1_scalar_mul() {
+        let alpha = from_hex::<G1_SIZE>(ALPHA);
+
+        assert_eq!(g1_scalar_mul(&alpha, &scalar(0)).unwrap(), [0u8; G1_SIZE]);
+        assert_eq!(g1_scalar_mul(&alpha, &scalar(1)).unwrap(), alpha);
+        assert_eq!(g1_scalar_mul(&alpha, &scalar(2)).unwrap(), from_hex::<G1_SIZE>(TWO_ALPHA));
+        assert_eq!(g1_scalar_mul(&alpha, &scalar(3)).unwrap(), from_hex::<G1_SIZE>(THREE_ALPHA));
+
+        // (r - 1) * alpha = -alpha
+        let mut r_minus_one = SCALAR_FIELD_MODULUS;
+        r_minus_one[31] -= 1;
+        assert_eq!(g1_scalar_mul(&alpha, &r_minus_one).unwrap(), from_hex::<G1_SIZE>(NEG_ALPHA));
+    }
+
+    #[test]
+    fn test_alt_bn128_pairing() {
+        let alpha = from_hex::<G1_SIZE>(ALPHA);
+        let neg_alpha = g1_negate(&alpha);
+        let two_alpha = from_hex::<G1_SIZE>(TWO_ALPHA);
+        let beta = from_hex::<G2_SIZE>(BETA);
+
+        // e(alpha, beta) alone is not the identity
+        assert!(!alt_bn128_pairing(&pairing_input(&[(alpha, beta)])).unwrap());
+
+        // e(alpha, beta) · e(-alpha, beta) = 1
+        assert!(alt_bn128_pairing(&pairing_input(&[(alpha, beta), (neg_alpha, beta)])).unwrap());
+
+        // e(2·alpha, beta) · e(-alpha, beta)² = 1, the same shape as the Groth16 check
+        assert!(alt_bn128_pairing(&pairing_input(&[
+            (two_alpha, beta),
+            (neg_alpha, beta),
+            (neg_alpha, beta),
+        ]))
+        .unwrap());
+        assert!(!alt_bn128_pairing(&pairing_input(&[(two_alpha, beta), (neg_alpha, beta)])).unwrap());
+
+        // Empty input is the empty product
+        assert!(alt_bn128_pairing(&[]).unwrap());
+
+        // Input must be whole (G1, G2) pairs
+        assert!(alt_bn128_pairing(&[0u8; G1_SIZE + G2_SIZE - 1]).is_err());
+    }
 }
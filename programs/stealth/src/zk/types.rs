@@ -3,6 +3,7 @@
 //! Data structures for Groth16 proofs and verification keys on BN254 curve.
 
 use anchor_lang::prelude::*;
+use crate::error::StealthError;
 
 /// Size of a G1 point (2 * 32 bytes for x, y coordinates)
 pub const G1_SIZE: usize = 64;
@@ -28,6 +29,26 @@ pub struct Groth16Proof {
 
 impl Groth16Proof {
     pub const SIZE: usize = G1_SIZE + G2_SIZE + G1_SIZE; // 256 bytes
+
+    /// Build a proof from raw A/B/C point bytes
+    ///
+    /// # Errors
+    /// * `MalformedProof` - A or C is not 64 bytes, or B is not 128 bytes
+    pub fn from_parts(pi_a: &[u8], pi_b: &[u8], pi_c: &[u8]) -> Result<Self> {
+        Ok(Self {
+            pi_a: pi_a.try_into().map_err(|_| error!(StealthError::MalformedProof))?,
+            pi_b: pi_b.try_into().map_err(|_| error!(StealthError::MalformedProof))?,
+            pi_c: pi_c.try_into().map_err(|_| error!(StealthError::MalformedProof))?,
+        })
+    }
+
+    /// Parse `A || B || C` (exactly `SIZE` bytes)
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        require!(bytes.len() == Self::SIZE, StealthError::MalformedProof);
+        let (pi_a, rest) = bytes.split_at(G1_SIZE);
+        let (pi_b, pi_c) = rest.split_at(G2_SIZE);
+        Self::from_parts(pi_a, pi_b, pi_c)
+    }
 }
 
 /// Verification key for the stealth address circuit
@@ -85,6 +106,10 @@ pub struct StoredVerificationKey {
     pub authority: Pubkey,
     /// The verification key data
     pub vk_data: Vec<u8>,
+    /// Circuit version, bumped on every key update
+    pub version: u32,
+    /// SHA-256 of `vk_data`
+    pub vk_hash: [u8; 32],
     /// Bump seed for PDA derivation
     pub bump: u8,
 }
@@ -98,6 +123,13 @@ impl StoredVerificationKey {
         8 + // discriminator
         32 + // authority
         4 + vk_data_len + // vk_data vec
+        4 + // version
+        32 + // vk_hash
         1 // bump
     }
+
+    /// Hash identifying a verification key's bytes
+    pub fn hash_vk_data(vk_data: &[u8]) -> [u8; 32] {
+        anchor_lang::solana_program::hash::hash(vk_data).to_bytes()
+    }
 }
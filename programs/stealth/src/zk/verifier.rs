@@ -6,6 +6,22 @@
 //! - DEV: Skips verification (for testing)
 //! - ORACLE: Uses trusted verifier attestations (hackathon-ready)
 //! - GROTH16: Full on-chain verification via Solana BN254 syscalls (future)
+//!
+//! ## Attestation message
+//!
+//! A verifier signs, with Ed25519:
+//!
+//! ```text
+//! "nocturne_oracle_v2" (18) || vk_hash (32) || proof_hash (32) || public_inputs_hash (32) || verified_at (8, LE)
+//!     || vk_version (4, LE) || method (1)
+//! ```
+//!
+//! `vk_hash` is the oracle's circuit, so an attestation for one circuit or
+//! oracle can't be replayed against another. Production builds refuse the
+//! all-zero hash, which would bind an attestation to no circuit at all. `vk_version` and `method` record which key version the
+//! verifier checked against and how (see `OracleAttestation::METHOD_*`), so
+//! audits can tell a real verification from a rubber stamp. v1 messages
+//! (without the last two fields) are no longer accepted.
 
 use anchor_lang::prelude::*;
 #[allow(unused_imports)]
@@ -26,6 +42,29 @@ pub struct OracleAttestation {
     pub signature: [u8; 64],
     /// Timestamp of verification
     pub verified_at: i64,
+    /// Verification key version the proof was checked against
+    pub vk_version: u32,
+    /// How the verifier checked the proof (`METHOD_*`)
+    pub method: u8,
+}
+
+impl OracleAttestation {
+    /// The verifier didn't check the proof (development builds only)
+    pub const METHOD_DEV_SKIP: u8 = 0;
+    /// Groth16 verification against the circuit's verification key
+    pub const METHOD_GROTH16: u8 = 1;
+    /// UltraHonk verification of a Noir proof
+    pub const METHOD_ULTRA_HONK: u8 = 2;
+
+    /// Whether the verifier actually checked the proof cryptographically
+    pub fn is_verified_method(&self) -> bool {
+        matches!(self.method, Self::METHOD_GROTH16 | Self::METHOD_ULTRA_HONK)
+    }
+
+    /// Whether this attestation was issued for exactly these public inputs
+    pub fn covers_inputs(&self, public_inputs: &[u8]) -> bool {
+        self.public_inputs_hash == compute_hash(public_inputs)
+    }
 }
 
 /// Verification oracle account (stores trusted verifier keys)
@@ -41,6 +80,12 @@ pub struct VerificationOracle {
     pub required_attestations: u8,
     /// Whether oracle is active
     pub is_active: bool,
+    /// How old an attestation may be before it is rejected
+    pub max_attestation_age_seconds: i64,
+    /// Authority proposed by `propose_oracle_authority`, awaiting its accept
+    pub pending_authority: Pubkey,
+    /// Verification key version attestations must be issued for
+    pub vk_version: u32,
     /// Bump seed
     pub bump: u8,
 }
@@ -48,18 +93,159 @@ pub struct VerificationOracle {
 impl VerificationOracle {
     pub const SEED: &'static [u8] = b"verification_oracle";
     pub const MAX_VERIFIERS: usize = 10;
+    /// Freshness window when no oracle account is passed (5 minutes)
+    pub const DEFAULT_MAX_ATTESTATION_AGE_SECONDS: i64 = 300;
     pub const SIZE: usize = 8 + // discriminator
         32 + // authority
         4 + (32 * Self::MAX_VERIFIERS) + // trusted_verifiers (vec)
         32 + // vk_hash
         1 + // required_attestations
         1 + // is_active
+        8 + // max_attestation_age_seconds
+        32 + // pending_authority
+        4 + // vk_version
         1; // bump
 
     /// Check if a verifier is trusted
     pub fn is_trusted_verifier(&self, verifier: &Pubkey) -> bool {
         self.trusted_verifiers.contains(verifier)
     }
+
+    /// Trust a new verifier, up to `MAX_VERIFIERS`
+    pub fn add_verifier(&mut self, verifier: Pubkey) -> Result<()> {
+        require!(
+            !self.is_trusted_verifier(&verifier),
+            StealthError::VerifierAlreadyTrusted
+        );
+        require!(
+            self.trusted_verifiers.len() < Self::MAX_VERIFIERS,
+            StealthError::TooManyVerifiers
+        );
+        self.trusted_verifiers.push(verifier);
+        Ok(())
+    }
+
+    /// Stop trusting a verifier; its attestations are rejected from now on
+    pub fn remove_verifier(&mut self, verifier: &Pubkey) -> Result<()> {
+        let index = self
+            .trusted_verifiers
+            .iter()
+            .position(|v| v == verifier)
+            .ok_or(StealthError::UntrustedVerifier)?;
+        self.trusted_verifiers.remove(index);
+        Ok(())
+    }
+}
+
+/// Domain prefix of every proof attestation message (v2 adds `vk_version` and `method`)
+pub const ATTESTATION_DOMAIN: &[u8; 18] = b"nocturne_oracle_v2";
+
+/// Size of a proof attestation message
+pub const ATTESTATION_MESSAGE_SIZE: usize = ATTESTATION_DOMAIN.len() + 32 + 32 + 32 + 8 + 4 + 1;
+
+/// Circuit an attestation must be signed for
+///
+/// When both the oracle and the caller name a circuit they must agree.
+/// Without an oracle account the caller's circuit is used, and with
+/// neither the attestation falls back to the all-zero hash. Production
+/// builds reject that fallback (and an oracle left at zero): such an
+/// attestation could be replayed on any deployment that also omits it.
+pub fn attested_vk_hash(
+    oracle: Option<&VerificationOracle>,
+    circuit_vk_hash: Option<&[u8; 32]>,
+) -> Result<[u8; 32]> {
+    let vk_hash = match (oracle, circuit_vk_hash) {
+        (Some(oracle), Some(circuit)) => {
+            require!(
+                oracle.vk_hash == *circuit,
+                StealthError::VerificationKeyMismatch
+            );
+            oracle.vk_hash
+        }
+        (Some(oracle), None) => oracle.vk_hash,
+        (None, Some(circuit)) => *circuit,
+        (None, None) => [0u8; 32],
+    };
+
+    #[cfg(feature = "production")]
+    require!(vk_hash != [0u8; 32], StealthError::UnboundAttestationCircuit);
+
+    Ok(vk_hash)
+}
+
+/// Reject attestations issued for a different verification key version
+///
+/// Only the oracle knows which version is current. Without an oracle
+/// account dev builds record the signed `vk_version` unchecked, and
+/// production builds refuse the attestation outright.
+pub fn check_attestation_vk_version(
+    oracle: Option<&VerificationOracle>,
+    attestation: &OracleAttestation,
+) -> Result<()> {
+    let Some(oracle) = oracle else {
+        require!(
+            !cfg!(feature = "production"),
+            StealthError::VerificationKeyVersionMismatch
+        );
+        return Ok(());
+    };
+    require!(
+        attestation.vk_version == oracle.vk_version,
+        StealthError::VerificationKeyVersionMismatch
+    );
+    Ok(())
+}
+
+/// Message a verifier signs for a proof attestation (see module docs)
+#[cfg(any(feature = "production", test))]
+fn proof_attestation_message(
+    vk_hash: &[u8; 32],
+    attestation: &OracleAttestation,
+) -> [u8; ATTESTATION_MESSAGE_SIZE] {
+    let mut message = [0u8; ATTESTATION_MESSAGE_SIZE];
+    let (domain, rest) = message.split_at_mut(ATTESTATION_DOMAIN.len());
+    domain.copy_from_slice(ATTESTATION_DOMAIN);
+    rest[..32].copy_from_slice(vk_hash);
+    rest[32..64].copy_from_slice(&attestation.proof_hash);
+    rest[64..96].copy_from_slice(&attestation.public_inputs_hash);
+    rest[96..104].copy_from_slice(&attestation.verified_at.to_le_bytes());
+    rest[104..108].copy_from_slice(&attestation.vk_version.to_le_bytes());
+    rest[108] = attestation.method;
+    message
+}
+
+/// Reject every attestation while the oracle's kill switch is off
+///
+/// Without an oracle account there is nothing to switch off.
+pub fn ensure_oracle_active(oracle: Option<&VerificationOracle>) -> Result<()> {
+    if let Some(oracle) = oracle {
+        require!(
+            oracle.is_active,
+            StealthError::VerificationMethodNotAvailable
+        );
+    }
+    Ok(())
+}
+
+/// Allowed attestation age: the oracle's window, or the 5 minute default
+///
+/// A non-positive oracle setting (e.g. never configured) would reject every
+/// attestation, so it also falls back to the default.
+pub fn max_attestation_age(oracle: Option<&VerificationOracle>) -> i64 {
+    oracle
+        .map(|o| o.max_attestation_age_seconds)
+        .filter(|age| *age > 0)
+        .unwrap_or(VerificationOracle::DEFAULT_MAX_ATTESTATION_AGE_SECONDS)
+}
+
+/// Reject attestations older than `max_age` seconds or dated in the future
+pub fn check_attestation_age(now: i64, verified_at: i64, max_age: i64) -> Result<()> {
+    let age = now.saturating_sub(verified_at);
+    require!(
+        age >= 0 && age < max_age,
+        StealthError::AttestationExpired
+    );
+    Ok(())
 }
 
 /// Verify a ZK proof using the appropriate method based on build mode
@@ -122,9 +308,10 @@ pub(crate) fn verify_proof(
 /// This is the production-ready verification function that:
 /// 1. Validates proof and public inputs format
 /// 2. Verifies oracle attestation matches proof
-/// 3. Checks attestation freshness (5 minute window)
+/// 3. Checks attestation freshness (`oracle`'s window, default 5 minutes)
 /// 4. Verifies Ed25519 signature via Solana's Ed25519 program introspection
 /// 5. Optionally checks verifier is in trusted list
+/// 6. Checks the oracle attests the circuit (and key version) being verified
 ///
 /// # Arguments
 /// * `proof_bytes` - The serialized ZK proof
@@ -132,6 +319,8 @@ pub(crate) fn verify_proof(
 /// * `attestation` - Oracle attestation (required in production mode)
 /// * `instructions_sysvar` - Instructions sysvar for Ed25519 verification
 /// * `trusted_verifiers` - Optional list of trusted verifier pubkeys
+/// * `oracle` - Optional oracle account supplying the freshness window
+/// * `circuit_vk_hash` - Optional hash of the circuit being verified
 ///
 /// # Errors
 /// * `InvalidProof` - Proof bytes are empty or malformed
@@ -141,6 +330,9 @@ pub(crate) fn verify_proof(
 /// * `AttestationExpired` - Attestation is too old
 /// * `InvalidSignature` - Attestation signature is invalid
 /// * `UntrustedVerifier` - Verifier not in trusted list (if provided)
+/// * `VerificationKeyMismatch` - Oracle is for a different circuit
+/// * `VerificationKeyVersionMismatch` - Attestation is for another key version
+/// * `UnverifiedAttestationMethod` - Verifier didn't check the proof (production only)
 #[inline(never)]
 pub fn verify_proof_with_sysvar(
     proof_bytes: &[u8],
@@ -148,6 +340,8 @@ pub fn verify_proof_with_sysvar(
     attestation: Option<&OracleAttestation>,
     instructions_sysvar: &AccountInfo,
     trusted_verifiers: Option<&[Pubkey]>,
+    oracle: Option<&VerificationOracle>,
+    circuit_vk_hash: Option<&[u8; 32]>,
 ) -> Result<()> {
     // Basic validation in all modes
     require!(
@@ -166,6 +360,10 @@ pub fn verify_proof_with_sysvar(
         msg!("Proof size: {} bytes", proof_bytes.len());
         msg!("Public inputs size: {} bytes", public_inputs.len());
 
+        // The kill switch and circuit binding apply in every mode
+        ensure_oracle_active(oracle)?;
+        attested_vk_hash(oracle, circuit_vk_hash)?;
+
         // Validate minimum proof size (Groth16 proofs are typically 192+ bytes)
         require!(
             proof_bytes.len() >= 64,
@@ -178,6 +376,7 @@ pub fn verify_proof_with_sysvar(
                 att.signature != [0u8; 64],
                 StealthError::InvalidSignature
             );
+            check_attestation_vk_version(oracle, att)?;
         }
 
         // Suppress unused variable warning
@@ -190,8 +389,156 @@ pub fn verify_proof_with_sysvar(
     // Production mode - full verification
     #[cfg(feature = "production")]
     {
-        verify_with_oracle_full(proof_bytes, public_inputs, attestation, instructions_sysvar, trusted_verifiers)
+        verify_with_oracle_full(
+            proof_bytes,
+            public_inputs,
+            attestation,
+            instructions_sysvar,
+            trusted_verifiers,
+            oracle,
+            circuit_vk_hash,
+        )
+    }
+}
+
+/// Verify a ZK proof against `oracle`: its trusted verifiers, kill switch,
+/// freshness window, circuit hash and key version all apply
+#[inline(never)]
+pub fn verify_proof_with_oracle(
+    proof_bytes: &[u8],
+    public_inputs: &[u8],
+    attestation: Option<&OracleAttestation>,
+    instructions_sysvar: &AccountInfo,
+    oracle: &VerificationOracle,
+) -> Result<()> {
+    verify_proof_with_sysvar(
+        proof_bytes,
+        public_inputs,
+        attestation,
+        instructions_sysvar,
+        Some(&oracle.trusted_verifiers),
+        Some(oracle),
+        None,
+    )
+}
+
+/// Verify a ZK proof against a quorum of oracle attestations
+///
+/// Like `verify_proof_with_sysvar`, but accepts several attestations and
+/// requires at least `oracle.required_attestations` of them to be valid,
+/// each from a distinct verifier in `oracle.trusted_verifiers`.
+///
+/// # Arguments
+/// * `proof_bytes` - The serialized ZK proof
+/// * `public_inputs` - The public inputs to the circuit
+/// * `attestations` - Attestations from (ideally) distinct trusted verifiers
+/// * `instructions_sysvar` - Instructions sysvar for Ed25519 verification
+/// * `oracle` - Oracle account holding trusted verifiers and the threshold
+/// * `circuit_vk_hash` - Optional hash of the circuit being verified
+///
+/// # Errors
+/// * `VerificationMethodNotAvailable` - Oracle is not active
+/// * `VerificationKeyMismatch` - Oracle is for a different circuit
+/// * `DuplicateAttestation` - Two attestations share a verifier
+/// * `MissingAttestation` - Fewer valid attestations than required
+#[inline(never)]
+pub fn verify_proof_with_threshold(
+    proof_bytes: &[u8],
+    public_inputs: &[u8],
+    attestations: &[OracleAttestation],
+    instructions_sysvar: &AccountInfo,
+    oracle: &VerificationOracle,
+    circuit_vk_hash: Option<&[u8; 32]>,
+) -> Result<()> {
+    // Basic validation in all modes
+    require!(
+        !proof_bytes.is_empty(),
+        StealthError::InvalidProof
+    );
+    require!(
+        !public_inputs.is_empty(),
+        StealthError::InvalidProofInputs
+    );
+    ensure_oracle_active(Some(oracle))?;
+    let vk_hash = attested_vk_hash(Some(oracle), circuit_vk_hash)?;
+
+    ensure_distinct_verifiers(attestations)?;
+
+    // A zero threshold would accept anything - treat it as 1
+    let required = oracle.required_attestations.max(1) as usize;
+    require!(
+        attestations.len() >= required,
+        StealthError::MissingAttestation
+    );
+
+    // Development mode - validate structure but skip cryptographic verification
+    #[cfg(not(feature = "production"))]
+    {
+        msg!("DEV MODE: threshold verification skipped ({} of {} attestations)", attestations.len(), required);
+
+        for att in attestations {
+            require!(
+                att.signature != [0u8; 64],
+                StealthError::InvalidSignature
+            );
+            check_attestation_vk_version(Some(oracle), att)?;
+        }
+
+        let _ = (instructions_sysvar, vk_hash);
+
+        Ok(())
+    }
+
+    // Production mode - count fully verified attestations
+    #[cfg(feature = "production")]
+    {
+        let computed_proof_hash = compute_hash(proof_bytes);
+        let computed_inputs_hash = compute_hash(public_inputs);
+
+        let mut valid = 0usize;
+        for att in attestations {
+            let verifier = Pubkey::new_from_array(att.verifier);
+            if !oracle.is_trusted_verifier(&verifier) {
+                msg!("Skipping attestation from untrusted verifier {}", verifier);
+                continue;
+            }
+
+            if check_attestation_vk_version(Some(oracle), att).is_err() {
+                msg!("Skipping attestation for vk version {}", att.vk_version);
+                continue;
+            }
+
+            if verify_attestation(
+                &vk_hash,
+                &computed_proof_hash,
+                &computed_inputs_hash,
+                att,
+                instructions_sysvar,
+                max_attestation_age(Some(oracle)),
+            )
+            .is_ok()
+            {
+                valid += 1;
+            }
+        }
+
+        msg!("{} of {} required attestations valid", valid, required);
+        require!(valid >= required, StealthError::MissingAttestation);
+
+        Ok(())
+    }
+}
+
+/// Reject attestation sets where any verifier appears more than once
+#[inline(never)]
+fn ensure_distinct_verifiers(attestations: &[OracleAttestation]) -> Result<()> {
+    for (i, att) in attestations.iter().enumerate() {
+        require!(
+            attestations[..i].iter().all(|prev| prev.verifier != att.verifier),
+            StealthError::DuplicateAttestation
+        );
     }
+    Ok(())
 }
 
 /// Legacy oracle verification without instruction introspection
@@ -207,7 +554,6 @@ fn verify_with_oracle_legacy(
 
     // Compute expected proof hash
     let computed_proof_hash = compute_hash(proof_bytes);
-    let computed_inputs_hash = compute_hash(public_inputs);
 
     // Verify attestation matches proof
     require!(
@@ -215,17 +561,16 @@ fn verify_with_oracle_legacy(
         StealthError::ProofHashMismatch
     );
     require!(
-        attestation.public_inputs_hash == computed_inputs_hash,
+        attestation.covers_inputs(public_inputs),
         StealthError::PublicInputsMismatch
     );
 
-    // Verify attestation is recent (within 5 minutes)
-    let clock = Clock::get()?;
-    let age = clock.unix_timestamp.saturating_sub(attestation.verified_at);
-    require!(
-        age >= 0 && age < 300, // 5 minutes
-        StealthError::AttestationExpired
-    );
+    // Verify attestation is recent (legacy callers have no oracle account)
+    check_attestation_age(
+        Clock::get()?.unix_timestamp,
+        attestation.verified_at,
+        max_attestation_age(None),
+    )?;
 
     // Basic signature format validation (not cryptographic verification)
     let zero_sig = [0u8; 64];
@@ -248,47 +593,73 @@ fn verify_with_oracle_full(
     attestation: Option<&OracleAttestation>,
     instructions_sysvar: &AccountInfo,
     trusted_verifiers: Option<&[Pubkey]>,
+    oracle: Option<&VerificationOracle>,
+    circuit_vk_hash: Option<&[u8; 32]>,
 ) -> Result<()> {
+    ensure_oracle_active(oracle)?;
+    let vk_hash = attested_vk_hash(oracle, circuit_vk_hash)?;
     let attestation = attestation.ok_or(StealthError::MissingAttestation)?;
 
     // Compute expected proof hash
     let computed_proof_hash = compute_hash(proof_bytes);
     let computed_inputs_hash = compute_hash(public_inputs);
 
+    // Check trusted verifiers if provided
+    if let Some(verifiers) = trusted_verifiers {
+        let verifier_pubkey = Pubkey::new_from_array(attestation.verifier);
+        require!(
+            verifiers.contains(&verifier_pubkey),
+            StealthError::UntrustedVerifier
+        );
+        msg!("Verifier is in trusted list");
+    }
+
+    check_attestation_vk_version(oracle, attestation)?;
+
+    verify_attestation(
+        &vk_hash,
+        &computed_proof_hash,
+        &computed_inputs_hash,
+        attestation,
+        instructions_sysvar,
+        max_attestation_age(oracle),
+    )?;
+
+    msg!("Proof verified via oracle attestation with Ed25519 introspection");
+    Ok(())
+}
+
+/// Verify one attestation: hashes match, the verifier really checked the
+/// proof, it is younger than `max_age` seconds, and its Ed25519 signature
+/// over circuit `vk_hash` is present in the transaction
+#[cfg(feature = "production")]
+#[inline(never)]
+fn verify_attestation(
+    vk_hash: &[u8; 32],
+    computed_proof_hash: &[u8; 32],
+    computed_inputs_hash: &[u8; 32],
+    attestation: &OracleAttestation,
+    instructions_sysvar: &AccountInfo,
+    max_age: i64,
+) -> Result<()> {
     // Verify attestation matches proof
     require!(
-        attestation.proof_hash == computed_proof_hash,
+        attestation.proof_hash == *computed_proof_hash,
         StealthError::ProofHashMismatch
     );
     require!(
-        attestation.public_inputs_hash == computed_inputs_hash,
+        attestation.public_inputs_hash == *computed_inputs_hash,
         StealthError::PublicInputsMismatch
     );
-
-    // Verify attestation is recent (within 5 minutes)
-    let clock = Clock::get()?;
-    let age = clock.unix_timestamp.saturating_sub(attestation.verified_at);
     require!(
-        age >= 0 && age < 300, // 5 minutes
-        StealthError::AttestationExpired
+        attestation.is_verified_method(),
+        StealthError::UnverifiedAttestationMethod
     );
 
-    // Check trusted verifiers if provided
-    if let Some(verifiers) = trusted_verifiers {
-        let verifier_pubkey = Pubkey::new_from_array(attestation.verifier);
-        require!(
-            verifiers.contains(&verifier_pubkey),
-            StealthError::UntrustedVerifier
-        );
-        msg!("Verifier is in trusted list");
-    }
+    // Verify attestation is recent
+    check_attestation_age(Clock::get()?.unix_timestamp, attestation.verified_at, max_age)?;
 
-    // Build attestation message for signature verification
-    // The message is: proof_hash || public_inputs_hash || verified_at (as le bytes)
-    let mut message = Vec::with_capacity(72);
-    message.extend_from_slice(&attestation.proof_hash);
-    message.extend_from_slice(&attestation.public_inputs_hash);
-    message.extend_from_slice(&attestation.verified_at.to_le_bytes());
+    let message = proof_attestation_message(vk_hash, attestation);
 
     // Verify Ed25519 signature via instruction introspection
     verify_ed25519_signature_with_sysvar(
@@ -296,10 +667,7 @@ fn verify_with_oracle_full(
         &message,
         &attestation.signature,
         &attestation.verifier,
-    )?;
-
-    msg!("Proof verified via oracle attestation with Ed25519 introspection");
-    Ok(())
+    )
 }
 
 /// Verify Ed25519 signature with full instruction introspection
@@ -389,8 +757,8 @@ fn compute_hash(data: &[u8]) -> [u8; 32] {
 /// Full Groth16 verification using Solana BN254 syscalls
 /// Uses the alt_bn128 precompiles for pairing checks
 ///
-/// This function is available on Solana 1.16+ when running on-chain.
-/// Off-chain tests should use the oracle attestation method.
+/// This function is available on Solana 1.16+ when running on-chain;
+/// off-chain it runs through the solana-program host implementation.
 #[inline(never)]
 pub fn verify_groth16_onchain(
     proof: &Groth16Proof,
@@ -495,7 +863,8 @@ pub struct RangeProofAttestation {
     pub commitment_hash: [u8; 32],
     /// The amount range (min, max) - typically (0, 2^64)
     pub amount_range: [u64; 2],
-    /// Verifier's signature over commitment_hash || amount_range
+    /// Verifier's signature over the message built by
+    /// `range_proof_attestation_message`
     pub signature: [u8; 64],
     /// Verifier's public key
     pub verifier: [u8; 32],
@@ -504,10 +873,13 @@ pub struct RangeProofAttestation {
 }
 
 /// Verify a range proof attestation
+///
+/// Freshness uses `oracle`'s window when given, else the 5 minute default.
 #[inline(never)]
 pub fn verify_range_proof_attestation(
     pedersen_commitment: &[u8; 33],
     attestation: &RangeProofAttestation,
+    oracle: Option<&VerificationOracle>,
 ) -> Result<()> {
     // Compute commitment hash
     let commitment_hash = compute_hash(pedersen_commitment);
@@ -525,16 +897,14 @@ pub fn verify_range_proof_attestation(
     );
 
     // Verify attestation is recent
-    let clock = Clock::get()?;
-    let age = clock.unix_timestamp.saturating_sub(attestation.verified_at);
-    require!(
-        age >= 0 && age < 300, // 5 minutes
-        crate::error::StealthError::AttestationExpired
-    );
+    check_attestation_age(
+        Clock::get()?.unix_timestamp,
+        attestation.verified_at,
+        max_attestation_age(oracle),
+    )?;
 
-    // In production mode, validate signature format
-    // Note: Full Ed25519 verification requires instruction sysvar.
-    // For full verification, use verify_range_proof_attestation_with_sysvar.
+    // Signature format only - use verify_range_proof_attestation_with_sysvar
+    // to also check the Ed25519 signature via instruction introspection
     #[cfg(feature = "production")]
     {
         // Basic signature format validation (non-zero)
@@ -554,8 +924,82 @@ pub fn verify_range_proof_attestation(
     Ok(())
 }
 
+/// Verify a range proof attestation including its Ed25519 signature
+///
+/// The oracle must be active and `attestation.verifier` one of its trusted
+/// verifiers (in every build), then `verify_range_proof_attestation` runs.
+/// In production an Ed25519 program instruction in this transaction must
+/// sign `range_proof_attestation_message` by `verifier`, which covers
+/// `range_proof_hash` and `verified_at` so neither can be swapped.
+#[inline(never)]
+pub fn verify_range_proof_attestation_with_sysvar(
+    pedersen_commitment: &[u8; 33],
+    range_proof_hash: &[u8; 32],
+    attestation: &RangeProofAttestation,
+    instructions_sysvar: &AccountInfo,
+    oracle: &VerificationOracle,
+) -> Result<()> {
+    ensure_oracle_active(Some(oracle))?;
+    require!(
+        oracle.is_trusted_verifier(&Pubkey::new_from_array(attestation.verifier)),
+        StealthError::UntrustedVerifier
+    );
+
+    verify_range_proof_attestation(pedersen_commitment, attestation, Some(oracle))?;
+
+    #[cfg(feature = "production")]
+    {
+        verify_range_proof_signature(attestation, range_proof_hash, instructions_sysvar)?;
+    }
+
+    #[cfg(not(feature = "production"))]
+    {
+        let _ = (range_proof_hash, instructions_sysvar);
+        msg!("DEV MODE: Range proof attestation signature check skipped");
+    }
+
+    Ok(())
+}
+
+/// Size of a range proof attestation message
+pub const RANGE_PROOF_MESSAGE_SIZE: usize = 32 + 8 + 8 + 32 + 8;
+
+/// Message signed by a range proof verifier:
+/// commitment_hash || amount_range[0] (LE) || amount_range[1] (LE)
+/// || range_proof_hash || verified_at (LE)
+#[cfg(any(feature = "production", test))]
+fn range_proof_attestation_message(
+    attestation: &RangeProofAttestation,
+    range_proof_hash: &[u8; 32],
+) -> [u8; RANGE_PROOF_MESSAGE_SIZE] {
+    let mut message = [0u8; RANGE_PROOF_MESSAGE_SIZE];
+    message[..32].copy_from_slice(&attestation.commitment_hash);
+    message[32..40].copy_from_slice(&attestation.amount_range[0].to_le_bytes());
+    message[40..48].copy_from_slice(&attestation.amount_range[1].to_le_bytes());
+    message[48..80].copy_from_slice(range_proof_hash);
+    message[80..].copy_from_slice(&attestation.verified_at.to_le_bytes());
+    message
+}
+
+/// Check the attestation's Ed25519 signature via instruction introspection
+#[cfg(feature = "production")]
+#[inline(never)]
+fn verify_range_proof_signature(
+    attestation: &RangeProofAttestation,
+    range_proof_hash: &[u8; 32],
+    instructions_sysvar: &AccountInfo,
+) -> Result<()> {
+    let message = range_proof_attestation_message(attestation, range_proof_hash);
+    verify_ed25519_signature_with_sysvar(
+        instructions_sysvar,
+        &message,
+        &attestation.signature,
+        &attestation.verifier,
+    )
+}
+
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
 
     #[test]
@@ -573,10 +1017,408 @@ mod tests {
         assert_ne!(h1, h2);
     }
 
+    fn attestation(verifier: u8) -> OracleAttestation {
+        OracleAttestation {
+            proof_hash: [0u8; 32],
+            public_inputs_hash: [0u8; 32],
+            verifier: [verifier; 32],
+            signature: [1u8; 64],
+            verified_at: 0,
+            vk_version: 1,
+            method: OracleAttestation::METHOD_GROTH16,
+        }
+    }
+
+    #[test]
+    fn test_distinct_verifiers_accepted() {
+        let attestations = [attestation(1), attestation(2), attestation(3)];
+        assert!(ensure_distinct_verifiers(&attestations).is_ok());
+        assert!(ensure_distinct_verifiers(&[]).is_ok());
+    }
+
+    #[test]
+    fn test_duplicate_verifier_rejected() {
+        let attestations = [attestation(1), attestation(2), attestation(1)];
+        let err = ensure_distinct_verifiers(&attestations).unwrap_err();
+        assert_eq!(err, StealthError::DuplicateAttestation.into());
+    }
+
+    fn range_attestation() -> RangeProofAttestation {
+        RangeProofAttestation {
+            commitment_hash: [7u8; 32],
+            amount_range: [0, u64::MAX],
+            signature: [9u8; 64],
+            verifier: [5u8; 32],
+            verified_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_range_proof_attestation_message_layout() {
+        let mut attestation = range_attestation();
+        attestation.verified_at = -3;
+        let message = range_proof_attestation_message(&attestation, &[6u8; 32]);
+        assert_eq!(&message[..32], &[7u8; 32]);
+        assert_eq!(&message[32..40], &0u64.to_le_bytes());
+        assert_eq!(&message[40..48], &u64::MAX.to_le_bytes());
+        assert_eq!(&message[48..80], &[6u8; 32]);
+        assert_eq!(&message[80..], &(-3i64).to_le_bytes());
+    }
+
+    #[test]
+    fn test_range_proof_attestation_requires_trusted_verifier() {
+        let sysvar_key = Pubkey::new_unique();
+        let owner = Pubkey::default();
+        let mut lamports = 0u64;
+        let mut data = vec![];
+        let sysvar = AccountInfo::new(&sysvar_key, false, false, &mut lamports, &mut data, &owner, false, 0);
+
+        let mut attestation = range_attestation();
+        let pedersen_commitment = [2u8; 33];
+        attestation.commitment_hash = compute_hash(&pedersen_commitment);
+        let mut oracle = oracle_with_window(300);
+
+        // A self-signed attestation from a key the oracle doesn't trust
+        let err = verify_range_proof_attestation_with_sysvar(
+            &pedersen_commitment, &[6u8; 32], &attestation, &sysvar, &oracle,
+        )
+        .unwrap_err();
+        assert_eq!(err, StealthError::UntrustedVerifier.into());
+
+        // Trusting the verifier gets past the gate (the clock check needs a
+        // runtime, so stop at the kill switch instead)
+        oracle.add_verifier(Pubkey::new_from_array(attestation.verifier)).unwrap();
+        oracle.is_active = false;
+        let err = verify_range_proof_attestation_with_sysvar(
+            &pedersen_commitment, &[6u8; 32], &attestation, &sysvar, &oracle,
+        )
+        .unwrap_err();
+        assert_eq!(err, StealthError::VerificationMethodNotAvailable.into());
+    }
+
+    /// Ed25519 program instruction data for a single signature, all inline
+    #[cfg(feature = "production")]
+    fn ed25519_ix_data(signature: &[u8; 64], pubkey: &[u8; 32], message: &[u8]) -> Vec<u8> {
+        let mut data = vec![1u8, 0u8];
+        data.extend_from_slice(&16u16.to_le_bytes());
+        data.extend_from_slice(&[0xff, 0xff]);
+        data.extend_from_slice(&(16u16 + 64).to_le_bytes());
+        data.extend_from_slice(&[0xff, 0xff]);
+        data.extend_from_slice(&(16u16 + 64 + 32).to_le_bytes());
+        data.extend_from_slice(&(message.len() as u16).to_le_bytes());
+        data.extend_from_slice(&[0xff, 0xff]);
+        data.extend_from_slice(signature);
+        data.extend_from_slice(pubkey);
+        data.extend_from_slice(message);
+        data
+    }
+
+    #[cfg(feature = "production")]
+    #[test]
+    fn test_range_proof_signature_introspection() {
+        use anchor_lang::solana_program::{
+            ed25519_program,
+            sysvar::instructions::{
+                self, construct_instructions_data, BorrowedAccountMeta, BorrowedInstruction,
+            },
+        };
+
+        let attestation = range_attestation();
+        let range_proof_hash = [6u8; 32];
+        let message = range_proof_attestation_message(&attestation, &range_proof_hash);
+        let ix_data = ed25519_ix_data(&attestation.signature, &attestation.verifier, &message);
+
+        let ed25519_id = ed25519_program::id();
+        let signer = Pubkey::new_unique();
+        let mut sysvar_data = construct_instructions_data(&[BorrowedInstruction {
+            program_id: &ed25519_id,
+            accounts: vec![BorrowedAccountMeta { pubkey: &signer, is_signer: true, is_writable: false }],
+            data: &ix_data,
+        }]);
+
+        let sysvar_id = instructions::ID;
+        let owner = anchor_lang::solana_program::sysvar::ID;
+        let mut lamports = 0u64;
+        let sysvar = AccountInfo::new(
+            &sysvar_id, false, false, &mut lamports, &mut sysvar_data, &owner, false, 0,
+        );
+
+        // Signature matching the introspected Ed25519 instruction passes
+        assert!(verify_range_proof_signature(&attestation, &range_proof_hash, &sysvar).is_ok());
+
+        // A tampered signature has no matching instruction
+        let mut tampered = attestation.clone();
+        tampered.signature[0] ^= 0xff;
+        assert!(verify_range_proof_signature(&tampered, &range_proof_hash, &sysvar).is_err());
+
+        // So does a tampered range
+        let mut tampered = attestation.clone();
+        tampered.amount_range[1] -= 1;
+        assert!(verify_range_proof_signature(&tampered, &range_proof_hash, &sysvar).is_err());
+
+        // Or a re-dated attestation, or one for another range proof
+        let mut tampered = attestation.clone();
+        tampered.verified_at += 1;
+        assert!(verify_range_proof_signature(&tampered, &range_proof_hash, &sysvar).is_err());
+        assert!(verify_range_proof_signature(&attestation, &[5u8; 32], &sysvar).is_err());
+    }
+
     #[test]
     fn test_dev_mode_verification() {
         // In dev mode, verification always succeeds
         let result = verify_proof(b"fake_proof", b"fake_inputs", None);
         assert!(result.is_ok());
     }
+
+    pub(crate) fn oracle_with_window(max_attestation_age_seconds: i64) -> VerificationOracle {
+        VerificationOracle {
+            authority: Pubkey::default(),
+            trusted_verifiers: vec![],
+            vk_hash: [9u8; 32],
+            required_attestations: 1,
+            is_active: true,
+            max_attestation_age_seconds,
+            pending_authority: Pubkey::default(),
+            vk_version: 1,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn test_oracle_verifier_management() {
+        let mut oracle = oracle_with_window(300);
+        let first = Pubkey::new_unique();
+        let second = Pubkey::new_unique();
+
+        oracle.add_verifier(first).unwrap();
+        oracle.add_verifier(second).unwrap();
+        assert!(oracle.is_trusted_verifier(&first));
+        assert!(oracle.add_verifier(first).is_err());
+
+        oracle.remove_verifier(&first).unwrap();
+        assert!(!oracle.is_trusted_verifier(&first));
+        assert!(oracle.is_trusted_verifier(&second));
+        assert!(oracle.remove_verifier(&first).is_err());
+
+        // Capacity is bounded by MAX_VERIFIERS
+        while oracle.trusted_verifiers.len() < VerificationOracle::MAX_VERIFIERS {
+            oracle.add_verifier(Pubkey::new_unique()).unwrap();
+        }
+        assert!(oracle.add_verifier(Pubkey::new_unique()).is_err());
+    }
+
+    #[test]
+    fn test_inactive_oracle_rejects_attestations() {
+        let mut oracle = oracle_with_window(300);
+        let sysvar_key = Pubkey::new_unique();
+        let owner = Pubkey::default();
+        let mut lamports = 0u64;
+        let mut data = vec![];
+        let sysvar = AccountInfo::new(&sysvar_key, false, false, &mut lamports, &mut data, &owner, false, 0);
+        let att = attestation(1);
+
+        assert!(ensure_oracle_active(None).is_ok());
+        assert!(ensure_oracle_active(Some(&oracle)).is_ok());
+        // Production builds also need the Ed25519 instruction, absent here
+        #[cfg(not(feature = "production"))]
+        assert!(verify_proof_with_sysvar(&[1u8; 64], b"inputs", Some(&att), &sysvar, None, Some(&oracle), None).is_ok());
+
+        oracle.is_active = false;
+        assert!(ensure_oracle_active(Some(&oracle)).is_err());
+        assert!(verify_proof_with_sysvar(&[1u8; 64], b"inputs", Some(&att), &sysvar, None, Some(&oracle), None).is_err());
+        assert!(verify_proof_with_threshold(&[1u8; 64], b"inputs", &[att], &sysvar, &oracle, None).is_err());
+    }
+
+    #[test]
+    fn test_proof_attestation_message_layout() {
+        let mut att = attestation(1);
+        att.proof_hash = [2u8; 32];
+        att.public_inputs_hash = [3u8; 32];
+        att.verified_at = -2;
+
+        att.vk_version = 0x0102_0304;
+        att.method = OracleAttestation::METHOD_ULTRA_HONK;
+
+        let message = proof_attestation_message(&[4u8; 32], &att);
+        assert_eq!(message.len(), 127);
+        assert_eq!(&message[..18], b"nocturne_oracle_v2");
+        assert_eq!(&message[18..50], &[4u8; 32]);
+        assert_eq!(&message[50..82], &[2u8; 32]);
+        assert_eq!(&message[82..114], &[3u8; 32]);
+        assert_eq!(&message[114..122], &(-2i64).to_le_bytes());
+        assert_eq!(&message[122..126], &[4, 3, 2, 1]);
+        assert_eq!(message[126], OracleAttestation::METHOD_ULTRA_HONK);
+    }
+
+    #[test]
+    fn test_attestation_bound_to_vk_version() {
+        let mut oracle = oracle_with_window(300);
+        oracle.vk_version = 3;
+        let sysvar_key = Pubkey::new_unique();
+        let owner = Pubkey::default();
+        let mut lamports = 0u64;
+        let mut data = vec![];
+        let sysvar = AccountInfo::new(&sysvar_key, false, false, &mut lamports, &mut data, &owner, false, 0);
+
+        let mut current = attestation(1);
+        current.vk_version = 3;
+        let mut stale = attestation(2);
+        stale.vk_version = 2;
+
+        assert!(check_attestation_vk_version(Some(&oracle), &current).is_ok());
+        let err = check_attestation_vk_version(Some(&oracle), &stale).unwrap_err();
+        assert_eq!(err, StealthError::VerificationKeyVersionMismatch.into());
+
+        // Without an oracle there is no expected version: only dev builds
+        // let the attestation through unchecked
+        #[cfg(not(feature = "production"))]
+        assert!(check_attestation_vk_version(None, &stale).is_ok());
+        #[cfg(feature = "production")]
+        assert_eq!(
+            check_attestation_vk_version(None, &stale).unwrap_err(),
+            StealthError::VerificationKeyVersionMismatch.into()
+        );
+
+        let inputs = b"inputs";
+        // Production builds also need the Ed25519 instruction, absent here
+        #[cfg(not(feature = "production"))]
+        assert!(verify_proof_with_sysvar(&[1u8; 64], inputs, Some(&current), &sysvar, None, Some(&oracle), None).is_ok());
+        assert!(verify_proof_with_sysvar(&[1u8; 64], inputs, Some(&stale), &sysvar, None, Some(&oracle), None).is_err());
+
+        // The version is signed, so it can't be swapped without a new signature
+        let mut relabelled = stale.clone();
+        relabelled.vk_version = 3;
+        assert_ne!(
+            proof_attestation_message(&oracle.vk_hash, &stale),
+            proof_attestation_message(&oracle.vk_hash, &relabelled)
+        );
+    }
+
+    #[test]
+    fn test_attestation_method_classification() {
+        let mut att = attestation(1);
+        for method in [OracleAttestation::METHOD_GROTH16, OracleAttestation::METHOD_ULTRA_HONK] {
+            att.method = method;
+            assert!(att.is_verified_method());
+        }
+        for method in [OracleAttestation::METHOD_DEV_SKIP, 3, u8::MAX] {
+            att.method = method;
+            assert!(!att.is_verified_method());
+        }
+    }
+
+    #[test]
+    fn test_wrong_vk_hash_attestation_rejected() {
+        let mut oracle = oracle_with_window(300);
+        oracle.vk_hash = [7u8; 32];
+        let sysvar_key = Pubkey::new_unique();
+        let owner = Pubkey::default();
+        let mut lamports = 0u64;
+        let mut data = vec![];
+        let sysvar = AccountInfo::new(&sysvar_key, false, false, &mut lamports, &mut data, &owner, false, 0);
+        let att = attestation(1);
+
+        assert_eq!(attested_vk_hash(Some(&oracle), Some(&[7u8; 32])).unwrap(), [7u8; 32]);
+        assert_eq!(attested_vk_hash(Some(&oracle), None).unwrap(), [7u8; 32]);
+        assert_eq!(attested_vk_hash(None, Some(&[8u8; 32])).unwrap(), [8u8; 32]);
+        // With neither, only dev builds fall back to the all-zero hash
+        #[cfg(not(feature = "production"))]
+        assert_eq!(attested_vk_hash(None, None).unwrap(), [0u8; 32]);
+        #[cfg(feature = "production")]
+        assert_eq!(
+            attested_vk_hash(None, None).unwrap_err(),
+            StealthError::UnboundAttestationCircuit.into()
+        );
+
+        let err = attested_vk_hash(Some(&oracle), Some(&[8u8; 32])).unwrap_err();
+        assert_eq!(err, StealthError::VerificationKeyMismatch.into());
+
+        let inputs = b"inputs";
+        // Production builds also need the Ed25519 instruction, absent here
+        #[cfg(not(feature = "production"))]
+        assert!(verify_proof_with_sysvar(&[1u8; 64], inputs, Some(&att), &sysvar, None, Some(&oracle), Some(&[7u8; 32])).is_ok());
+        assert!(verify_proof_with_sysvar(&[1u8; 64], inputs, Some(&att), &sysvar, None, Some(&oracle), Some(&[8u8; 32])).is_err());
+        assert!(verify_proof_with_threshold(&[1u8; 64], inputs, &[att], &sysvar, &oracle, Some(&[8u8; 32])).is_err());
+    }
+
+    #[cfg(feature = "production")]
+    #[test]
+    fn test_attestation_signature_bound_to_vk_hash() {
+        use anchor_lang::solana_program::{
+            ed25519_program,
+            sysvar::instructions::{
+                self, construct_instructions_data, BorrowedAccountMeta, BorrowedInstruction,
+            },
+        };
+
+        let att = attestation(1);
+        let message = proof_attestation_message(&[7u8; 32], &att);
+        let ix_data = ed25519_ix_data(&att.signature, &att.verifier, &message);
+
+        let ed25519_id = ed25519_program::id();
+        let signer = Pubkey::new_unique();
+        let mut sysvar_data = construct_instructions_data(&[BorrowedInstruction {
+            program_id: &ed25519_id,
+            accounts: vec![BorrowedAccountMeta { pubkey: &signer, is_signer: true, is_writable: false }],
+            data: &ix_data,
+        }]);
+
+        let sysvar_id = instructions::ID;
+        let owner = anchor_lang::solana_program::sysvar::ID;
+        let mut lamports = 0u64;
+        let sysvar = AccountInfo::new(
+            &sysvar_id, false, false, &mut lamports, &mut sysvar_data, &owner, false, 0,
+        );
+
+        let signed_for = |vk_hash: &[u8; 32]| {
+            verify_ed25519_signature_with_sysvar(
+                &sysvar,
+                &proof_attestation_message(vk_hash, &att),
+                &att.signature,
+                &att.verifier,
+            )
+        };
+
+        // Only the circuit the verifier signed for has a matching instruction
+        assert!(signed_for(&[7u8; 32]).is_ok());
+        assert!(signed_for(&[8u8; 32]).is_err());
+        assert!(signed_for(&[0u8; 32]).is_err());
+    }
+
+    #[test]
+    fn test_attestation_age_window_boundary() {
+        let now = 1_000_000;
+        let window = max_attestation_age(None);
+        assert_eq!(window, VerificationOracle::DEFAULT_MAX_ATTESTATION_AGE_SECONDS);
+
+        assert!(check_attestation_age(now, now, window).is_ok());
+        assert!(check_attestation_age(now, now - (window - 1), window).is_ok());
+        assert!(check_attestation_age(now, now - window, window).is_err());
+
+        // Future-dated attestations are rejected whatever the window
+        assert!(check_attestation_age(now, now + 1, window).is_err());
+        assert!(check_attestation_age(now, now + 1, i64::MAX).is_err());
+    }
+
+    #[test]
+    fn test_attestation_age_window_from_oracle() {
+        let now = 1_000_000;
+
+        let relaxed = oracle_with_window(900);
+        let window = max_attestation_age(Some(&relaxed));
+        assert_eq!(window, 900);
+        assert!(check_attestation_age(now, now - 899, window).is_ok());
+        assert!(check_attestation_age(now, now - 900, window).is_err());
+
+        let strict = oracle_with_window(60);
+        let window = max_attestation_age(Some(&strict));
+        assert!(check_attestation_age(now, now - 59, window).is_ok());
+        assert!(check_attestation_age(now, now - 60, window).is_err());
+
+        // An unset window falls back to the default instead of rejecting everything
+        assert_eq!(
+            max_attestation_age(Some(&oracle_with_window(0))),
+            VerificationOracle::DEFAULT_MAX_ATTESTATION_AGE_SECONDS
+        );
+    }
 }
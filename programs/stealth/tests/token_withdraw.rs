@@ -0,0 +1,275 @@
+//! Token pool round trip: initialize a pool for a test mint, deposit, then
+//! withdraw to a recipient with no token account yet.
+//!
+//! Runs the program natively under `solana-program-test`, so the dev-mode
+//! verifier accepts the placeholder proof.
+
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::{get_associated_token_address, spl_associated_token_account};
+use anchor_spl::token::spl_token;
+use solana_program_test::{processor, ProgramTest, ProgramTestBanksClientExt, ProgramTestContext};
+use solana_sdk::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction, system_program, sysvar,
+    transaction::Transaction,
+};
+use stealth::instructions::{AddressWithdrawProof, AddressWithdrawPublicInputs};
+use stealth::state::{CommitmentLeaf, NullifierRecord, PrivacyPool, DENOMINATION_0_1_SOL};
+use stealth::zk::verifier::VerificationOracle;
+
+const DENOMINATION: u64 = DENOMINATION_0_1_SOL;
+
+fn process_instruction(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    // Anchor's entry wants the accounts borrowed for 'info; leak them for the test
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    stealth::entry(program_id, accounts, data)
+}
+
+struct TokenPool {
+    mint: Pubkey,
+    pool: Pubkey,
+    config: Pubkey,
+    vault: Pubkey,
+}
+
+impl TokenPool {
+    fn new(mint: Pubkey) -> Self {
+        let denomination = DENOMINATION.to_le_bytes();
+        let (pool, _) = Pubkey::find_program_address(&[PrivacyPool::SEED, mint.as_ref(), &denomination], &stealth::ID);
+        let (config, _) = Pubkey::find_program_address(
+            &[stealth::state::PoolConfig::SEED, mint.as_ref(), &denomination],
+            &stealth::ID,
+        );
+        let (vault, _) = Pubkey::find_program_address(&[PrivacyPool::TOKEN_VAULT_SEED, pool.as_ref()], &stealth::ID);
+        Self { mint, pool, config, vault }
+    }
+
+    fn nullifier(&self, nullifier_hash: &[u8; 32]) -> Pubkey {
+        Pubkey::find_program_address(
+            &[NullifierRecord::SEED, self.mint.as_ref(), &DENOMINATION.to_le_bytes(), nullifier_hash],
+            &stealth::ID,
+        )
+        .0
+    }
+}
+
+async fn send(context: &mut ProgramTestContext, instructions: &[Instruction], signers: &[&Keypair]) -> Result<(), String> {
+    let blockhash = context.banks_client.get_new_latest_blockhash(&context.last_blockhash).await.unwrap();
+    context.last_blockhash = blockhash;
+
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    let tx = Transaction::new_signed_with_payer(instructions, Some(&context.payer.pubkey()), &all_signers, blockhash);
+    context.banks_client.process_transaction(tx).await.map_err(|e| e.to_string())
+}
+
+async fn token_balance(context: &mut ProgramTestContext, address: Pubkey) -> u64 {
+    let account = context.banks_client.get_account(address).await.unwrap().expect("token account exists");
+    spl_token::state::Account::unpack(&account.data).unwrap().amount
+}
+
+/// Create a mint with 6 decimals and `supply` tokens in the payer's ATA
+async fn create_test_mint(context: &mut ProgramTestContext, supply: u64) -> (Pubkey, Pubkey) {
+    let mint = Keypair::new();
+    let payer = context.payer.pubkey();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let payer_ata = get_associated_token_address(&payer, &mint.pubkey());
+
+    send(
+        context,
+        &[
+            system_instruction::create_account(
+                &payer,
+                &mint.pubkey(),
+                rent.minimum_balance(spl_token::state::Mint::LEN),
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::ID,
+            ),
+            spl_token::instruction::initialize_mint(&spl_token::ID, &mint.pubkey(), &payer, None, 6).unwrap(),
+            spl_associated_token_account::instruction::create_associated_token_account(
+                &payer,
+                &payer,
+                &mint.pubkey(),
+                &spl_token::ID,
+            ),
+            spl_token::instruction::mint_to(&spl_token::ID, &mint.pubkey(), &payer_ata, &payer, &[], supply).unwrap(),
+        ],
+        &[&mint],
+    )
+    .await
+    .unwrap();
+
+    (mint.pubkey(), payer_ata)
+}
+
+fn deposit_ix(pool: &TokenPool, depositor: Pubkey, depositor_ata: Pubkey, commitment: [u8; 32]) -> Instruction {
+    let (commitment_leaf, _) = Pubkey::find_program_address(
+        &[CommitmentLeaf::SEED, pool.mint.as_ref(), &DENOMINATION.to_le_bytes(), &commitment],
+        &stealth::ID,
+    );
+    Instruction {
+        program_id: stealth::ID,
+        accounts: stealth::accounts::PrivateTokenDeposit {
+            depositor,
+            mint: pool.mint,
+            pool: pool.pool,
+            config: pool.config,
+            depositor_token_account: depositor_ata,
+            vault: pool.vault,
+            commitment_leaf,
+            fee_token_account: None,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: stealth::instruction::SendPrivateToken {
+            denomination: DENOMINATION,
+            commitment,
+            encrypted_note: None,
+        }
+        .data(),
+    }
+}
+
+fn oracle_address() -> Pubkey {
+    Pubkey::find_program_address(&[VerificationOracle::SEED], &stealth::ID).0
+}
+
+fn withdraw_ix(pool: &TokenPool, relayer: Pubkey, recipient: Pubkey, merkle_root: [u8; 32], nullifier_hash: [u8; 32]) -> Instruction {
+    Instruction {
+        program_id: stealth::ID,
+        accounts: stealth::accounts::PrivateTokenWithdraw {
+            relayer,
+            mint: pool.mint,
+            pool: pool.pool,
+            config: pool.config,
+            vault: pool.vault,
+            nullifier: pool.nullifier(&nullifier_hash),
+            recipient,
+            recipient_token_account: get_associated_token_address(&recipient, &pool.mint),
+            relayer_token_account: None,
+            protocol_fee_token_account: None,
+            oracle: oracle_address(),
+            instructions_sysvar: sysvar::instructions::ID,
+            token_program: spl_token::ID,
+            associated_token_program: spl_associated_token_account::ID,
+            system_program: system_program::ID,
+            verification_key: None,
+        }
+        .to_account_metas(None),
+        data: stealth::instruction::PrivateTokenWithdraw {
+            denomination: DENOMINATION,
+            proof: AddressWithdrawProof {
+                proof: vec![1u8; 256],
+                public_inputs: AddressWithdrawPublicInputs { merkle_root, nullifier_hash, recipient, deposit_slot: 0 },
+                attestation: None,
+            },
+            relayer_fee: 0,
+        }
+        .data(),
+    }
+}
+
+#[tokio::test]
+async fn test_private_token_withdraw_creates_recipient_ata() {
+    let program_test = ProgramTest::new("stealth", stealth::ID, processor!(process_instruction));
+    let mut context = program_test.start_with_context().await;
+    let payer = context.payer.pubkey();
+
+    let (mint, payer_ata) = create_test_mint(&mut context, 10 * DENOMINATION).await;
+    let pool = TokenPool::new(mint);
+
+    send(
+        &mut context,
+        &[Instruction {
+            program_id: stealth::ID,
+            accounts: stealth::accounts::InitializeTokenPool {
+                authority: payer,
+                mint,
+                pool: pool.pool,
+                config: pool.config,
+                vault: pool.vault,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+                rent: sysvar::rent::ID,
+            }
+            .to_account_metas(None),
+            data: stealth::instruction::InitializeTokenPool { denomination: DENOMINATION }.data(),
+        }],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    // Withdrawals check attestations against the verification oracle
+    send(
+        &mut context,
+        &[Instruction {
+            program_id: stealth::ID,
+            accounts: stealth::accounts::InitializeVerificationOracle {
+                authority: payer,
+                oracle: oracle_address(),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: stealth::instruction::InitializeVerificationOracle {
+                vk_hash: [9u8; 32],
+                vk_version: 1,
+                required_attestations: 1,
+                max_attestation_age_seconds: 0,
+            }
+            .data(),
+        }],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    // Two deposits: the deposit fee leaves each one short of a full denomination
+    let mut commitment = [0u8; 32];
+    for leaf in 1..=2 {
+        commitment[31] = leaf;
+        send(&mut context, &[deposit_ix(&pool, payer, payer_ata, commitment)], &[]).await.unwrap();
+    }
+    let vault_before = token_balance(&mut context, pool.vault).await;
+    assert!(vault_before >= DENOMINATION);
+
+    let pool_account = context.banks_client.get_account(pool.pool).await.unwrap().unwrap();
+    let pool_state: PrivacyPool = anchor_lang::__private::bytemuck::pod_read_unaligned(
+        &pool_account.data[8..8 + std::mem::size_of::<PrivacyPool>()],
+    );
+    assert_eq!(pool_state.token_mint, mint);
+
+    // The recipient has never held this token
+    let recipient = Keypair::new().pubkey();
+    let recipient_ata = get_associated_token_address(&recipient, &mint);
+    assert!(context.banks_client.get_account(recipient_ata).await.unwrap().is_none());
+
+    let nullifier_hash = [7u8; 32];
+    send(
+        &mut context,
+        &[withdraw_ix(&pool, payer, recipient, pool_state.merkle_root, nullifier_hash)],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(token_balance(&mut context, recipient_ata).await, DENOMINATION);
+    assert_eq!(token_balance(&mut context, pool.vault).await, vault_before - DENOMINATION);
+    assert!(context.banks_client.get_account(pool.nullifier(&nullifier_hash)).await.unwrap().is_some());
+
+    // The same nullifier can't be spent again, even into the existing ATA
+    let replay = send(
+        &mut context,
+        &[withdraw_ix(&pool, payer, recipient, pool_state.merkle_root, nullifier_hash)],
+        &[],
+    )
+    .await;
+    assert!(replay.is_err());
+    assert_eq!(token_balance(&mut context, recipient_ata).await, DENOMINATION);
+}
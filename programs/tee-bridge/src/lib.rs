@@ -16,8 +16,26 @@
 //! - Commitments are indistinguishable from each other
 //! - ~95% privacy score vs ~80% with traditional relay
 //!
+//! ## Shielding to Light Protocol
+//! After settlement, `release_and_shield` releases a user's committed funds
+//! from their batch and, in the same instruction, CPIs into the Light Protocol
+//! system program (`LIGHT_SYSTEM_PROGRAM_ID`) to compress them, so they never
+//! rest at the user's transparent address. Native SOL is compressed by the
+//! system program's `invoke` instruction; the compressed-token program only
+//! handles SPL mints, so it isn't used here.
+//!
+//! The Light instruction needs a validity proof and the current state tree,
+//! so the client builds it with the Light SDK and passes its data plus
+//! accounts (in order) as `remaining_accounts`: the user as fee payer and
+//! authority, Light's registered program PDA, noop program, account
+//! compression authority and program, the output state tree and its queue,
+//! the SOL pool PDA, and the system program. `release_settled_funds` stays
+//! for releasing to the wallet and shielding separately.
+//!
 //! Note: Session keys are handled by MagicBlock's middleware layer.
-//! This program focuses on the core deposit/commitment logic.
+//! This program focuses on the core deposit/commitment logic; a session key
+//! can additionally be authorized as a staging delegate so it may create
+//! commitments without the owner signing each one.
 
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
@@ -27,43 +45,244 @@ declare_id!("7BWpEN8PqFEZ131A5F8iEniMS6bYREGrabxLHgSdUmVW");
 /// Seeds for PDA derivation
 pub const STAGING_SEED: &[u8] = b"staging";
 pub const BATCH_SEED: &[u8] = b"batch";
+pub const BATCH_COUNTER_SEED: &[u8] = b"batch_counter";
 pub const COMMITMENT_SEED: &[u8] = b"tee_commitment";
 
+/// Light Protocol system program, which compresses SOL into its state trees
+pub const LIGHT_SYSTEM_PROGRAM_ID: Pubkey = pubkey!("SySTEM1eSU2p4BGQfQpimFEWWSC1XDFJNWRvnA3q5h7");
+
 /// Fixed denominations matching the main privacy pool
+pub const DENOMINATION_0_5_SOL: u64 = 500_000_000;
 pub const DENOMINATION_1_SOL: u64 = 1_000_000_000;
 pub const DENOMINATION_10_SOL: u64 = 10_000_000_000;
+pub const DENOMINATION_50_SOL: u64 = 50_000_000_000;
 pub const DENOMINATION_100_SOL: u64 = 100_000_000_000;
 
 #[error_code]
 pub enum TeeBridgeError {
     #[msg("Unauthorized - user mismatch")]
     Unauthorized,
-    #[msg("Invalid denomination - must be 1, 10, or 100 SOL")]
+    #[msg("Invalid denomination - must be 0.5, 1, 10, 50, or 100 SOL")]
     InvalidDenomination,
     #[msg("Insufficient staging balance")]
     InsufficientBalance,
-    #[msg("Batch is full - max 10 commitments per batch")]
+    #[msg("Batch is full - max_size commitments reached")]
     BatchFull,
     #[msg("Batch is empty - nothing to settle")]
     BatchEmpty,
-    #[msg("Batch not ready - need at least 3 commitments")]
+    #[msg("Batch not ready - below settlement threshold")]
     BatchNotReady,
     #[msg("Batch already settled")]
     BatchAlreadySettled,
     #[msg("Arithmetic overflow")]
     ArithmeticOverflow,
+    #[msg("Invalid batch config - need 0 < threshold <= max_size <= 64")]
+    InvalidBatchConfig,
+    #[msg("Batch has expired")]
+    BatchExpired,
+    #[msg("Batch has not reached its max age yet")]
+    BatchNotExpired,
+    #[msg("Commitment not found in batch")]
+    CommitmentNotInBatch,
+    #[msg("Commitment already finalized")]
+    CommitmentAlreadyFinalized,
+    #[msg("Batch is not the counter's current batch")]
+    NotCurrentBatch,
+    #[msg("Commitment does not match Keccak256(COMMITMENT_DOMAIN || nullifier || secret)")]
+    CommitmentMismatch,
+    #[msg("User has reached their commitment quota for this batch")]
+    UserBatchQuotaExceeded,
+    #[msg("Staging still holds a balance or unreleased committed funds")]
+    StagingNotEmpty,
+    #[msg("Shield CPI target is not the Light Protocol system program")]
+    InvalidLightProgram,
+    #[msg("Light Protocol CPI did not take the released funds out of the wallet")]
+    ShieldIncomplete,
+    #[msg("Batch ids from COUNTER_BATCH_ID_START up are reserved for initialize_next_batch")]
+    BatchIdReserved,
+    #[msg("Staging funds are committed to a different batch")]
+    StagingBatchMismatch,
+    #[msg("Release exceeds the staging account's unreleased committed funds")]
+    ExceedsCommittedFunds,
 }
 
-/// Minimum commitments required before anyone can settle
+/// Default batch capacity (callers pass this to `initialize_batch`)
+pub const DEFAULT_BATCH_SIZE: u8 = 10;
+
+/// Default minimum commitments required before anyone can settle
 pub const BATCH_THRESHOLD: u8 = 3;
 
+/// Upper bound on `max_size` to keep batch accounts within sane rent/CU limits
+pub const MAX_BATCH_SIZE: u8 = 64;
+
+/// Default age after which an unsettled batch can be expired (24 hours)
+pub const DEFAULT_BATCH_MAX_AGE_SECONDS: i64 = 86_400;
+
+/// Shortest `max_age_seconds` a batch may be created with (1 hour)
+pub const MIN_BATCH_MAX_AGE_SECONDS: i64 = 3_600;
+
+/// Most commitments one user may add to a batch when the quota is enforced
+pub const MAX_COMMITMENTS_PER_BATCH_PER_USER: u8 = 3;
+
+/// First id handed out by the batch counter; explicit `initialize_batch`
+/// ids must stay below it so nobody can squat the counter's next batch
+pub const COUNTER_BATCH_ID_START: u64 = 1 << 63;
+
+/// Cap on the settler's reward, in basis points of the batch's `total_amount`
+pub const MAX_SETTLE_REWARD_BPS: u64 = 50;
+
 /// Check if denomination is valid
 fn is_valid_denomination(amount: u64) -> bool {
+    amount == DENOMINATION_0_5_SOL ||
     amount == DENOMINATION_1_SOL ||
     amount == DENOMINATION_10_SOL ||
+    amount == DENOMINATION_50_SOL ||
     amount == DENOMINATION_100_SOL
 }
 
+/// Domain separator prefixed to every tee-bridge commitment preimage
+///
+/// Clients must hash with the same prefix, or `create_private_commitment`
+/// rejects their preimage with `CommitmentMismatch`.
+pub const COMMITMENT_DOMAIN: &[u8] = b"nocturne_tee_v1";
+
+/// Compute a tee-bridge note commitment:
+/// Keccak256(COMMITMENT_DOMAIN || nullifier || secret)
+///
+/// The prefix keeps these from colliding with any other program's Keccak
+/// commitment over the same 64 bytes, so one can't be replayed as the other.
+///
+/// Translation boundary: this hash only identifies the note inside the
+/// bridge. The main pool commits with Poseidon, so when a settled batch is
+/// deposited there the relayer submits each note's Poseidon commitment,
+/// not this one; the Keccak commitment never enters the pool's tree.
+pub fn compute_commitment(nullifier: &[u8; 32], secret: &[u8; 32]) -> [u8; 32] {
+    anchor_lang::solana_program::keccak::hashv(&[COMMITMENT_DOMAIN, nullifier, secret]).to_bytes()
+}
+
+/// Note preimage, optionally passed so the commitment can be checked
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CommitmentPreimage {
+    pub nullifier: [u8; 32],
+    pub secret: [u8; 32],
+}
+
+/// Validate the batch config and fill in a freshly created batch account
+#[allow(clippy::too_many_arguments)]
+fn init_batch(
+    batch: &mut CommitmentBatch,
+    batch_id: u64,
+    authority: Pubkey,
+    max_size: u8,
+    threshold: u8,
+    max_age_seconds: Option<i64>,
+    enforce_user_quota: bool,
+    settle_reward_lamports: u64,
+    bump: u8,
+) -> Result<()> {
+    require!(
+        max_size > 0 && max_size <= MAX_BATCH_SIZE,
+        TeeBridgeError::InvalidBatchConfig
+    );
+    require!(
+        threshold > 0 && threshold <= max_size,
+        TeeBridgeError::InvalidBatchConfig
+    );
+    let max_age_seconds = max_age_seconds.unwrap_or(DEFAULT_BATCH_MAX_AGE_SECONDS);
+    require!(
+        max_age_seconds >= MIN_BATCH_MAX_AGE_SECONDS,
+        TeeBridgeError::InvalidBatchConfig
+    );
+
+    batch.id = batch_id;
+    batch.authority = authority;
+    batch.max_size = max_size;
+    batch.threshold = threshold;
+    batch.commitments = Vec::with_capacity(max_size as usize);
+    batch.denominations = Vec::with_capacity(max_size as usize);
+    batch.commitment_count = 0;
+    batch.total_amount = 0;
+    batch.created_at = Clock::get()?.unix_timestamp;
+    batch.settled = false;
+    batch.max_age_seconds = max_age_seconds;
+    batch.expired = false;
+    batch.enforce_user_quota = enforce_user_quota;
+    batch.user_counts = Vec::new();
+    batch.settle_reward_lamports = settle_reward_lamports;
+    batch.bump = bump;
+
+    msg!("Batch {} initialized (max_size: {}, threshold: {})", batch_id, max_size, threshold);
+    Ok(())
+}
+
+/// Move `amount` lamports out of a program-owned account with checked math
+/// on both sides, so neither balance can wrap
+fn safe_transfer_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    if from.key == to.key {
+        return Ok(());
+    }
+
+    let from_balance = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(TeeBridgeError::ArithmeticOverflow)?;
+    let to_balance = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(TeeBridgeError::ArithmeticOverflow)?;
+
+    **from.try_borrow_mut_lamports()? = from_balance;
+    **to.try_borrow_mut_lamports()? = to_balance;
+    Ok(())
+}
+
+/// Pay `amount` of a user's committed funds out of the batch to their wallet
+///
+/// The batch must be the one holding staging's commitments and be settled
+/// or expired; at most the unreleased committed amount is released, less
+/// its share of the settle reward, and the batch keeps its rent-exempt
+/// minimum. Returns the lamports paid out.
+fn release_from_batch(
+    batch: &Account<CommitmentBatch>,
+    staging: &mut Account<StagingAccount>,
+    user_info: &AccountInfo,
+    amount: u64,
+) -> Result<u64> {
+    require!(
+        staging.batch_id == batch.id,
+        TeeBridgeError::StagingBatchMismatch
+    );
+    require!(batch.settled || batch.expired, TeeBridgeError::BatchNotReady);
+
+    staging.committed = staging.committed
+        .checked_sub(amount)
+        .ok_or(TeeBridgeError::ExceedsCommittedFunds)?;
+    let payout = batch.release_payout(amount);
+
+    // Ensure the batch has enough lamports (excluding rent-exempt minimum)
+    let batch_info = batch.to_account_info();
+    let min_balance = Rent::get()?.minimum_balance(batch_info.data_len());
+    let available = batch_info.lamports()
+        .checked_sub(min_balance)
+        .ok_or(TeeBridgeError::InsufficientBalance)?;
+    require!(available >= payout, TeeBridgeError::InsufficientBalance);
+
+    safe_transfer_lamports(&batch_info, user_info, payout)?;
+    Ok(payout)
+}
+
+/// Account metas for a CPI, keeping each account's signer/writable flags
+fn cpi_account_metas(accounts: &[AccountInfo]) -> Vec<AccountMeta> {
+    accounts
+        .iter()
+        .map(|account| AccountMeta {
+            pubkey: *account.key,
+            is_signer: account.is_signer,
+            is_writable: account.is_writable,
+        })
+        .collect()
+}
+
 #[program]
 pub mod tee_bridge {
     use super::*;
@@ -76,12 +295,32 @@ pub mod tee_bridge {
         staging.balance = 0;
         staging.commitment_count = 0;
         staging.created_at = Clock::get()?.unix_timestamp;
+        staging.delegate = Pubkey::default();
+        staging.batch_id = 0;
+        staging.committed = 0;
         staging.bump = ctx.bumps.staging;
 
         msg!("TEE staging account initialized for user: {}", ctx.accounts.user.key());
         Ok(())
     }
 
+    /// Authorize a delegate (e.g. a session key) to create commitments
+    ///
+    /// The delegate may sign `create_private_commitment`, which still spends
+    /// from the owner's staging balance. Only the owner can set it; passing
+    /// `Pubkey::default()` revokes it, leaving the owner-only path.
+    pub fn authorize_delegate(ctx: Context<AuthorizeDelegate>, delegate: Pubkey) -> Result<()> {
+        let staging = &mut ctx.accounts.staging;
+        staging.delegate = delegate;
+
+        if delegate == Pubkey::default() {
+            msg!("Staging delegate revoked");
+        } else {
+            msg!("Staging delegate authorized: {}", delegate);
+        }
+        Ok(())
+    }
+
     /// Deposit SOL to staging account
     /// This must be done before delegating to PER
     pub fn deposit_to_staging(ctx: Context<DepositToStaging>, amount: u64) -> Result<()> {
@@ -111,13 +350,18 @@ pub mod tee_bridge {
     /// only the authenticated user can see their commitment.
     /// The operator cannot map users to commitments.
     ///
-    /// The commitment hash is: Keccak256(nullifier || secret)
+    /// The commitment hash is: Keccak256(COMMITMENT_DOMAIN || nullifier || secret)
     /// where nullifier and secret are provided by the user (generated client-side)
+    ///
+    /// When `preimage` is supplied (only safe inside the TEE, where it stays
+    /// private), the commitment is recomputed and must match, so a malformed
+    /// commitment can't become an unspendable note.
     pub fn create_private_commitment(
         ctx: Context<CreatePrivateCommitment>,
         denomination: u64,
         commitment: [u8; 32],
         encrypted_note: Option<[u8; 128]>,
+        preimage: Option<CommitmentPreimage>,
     ) -> Result<()> {
         // Validate denomination
         require!(
@@ -125,9 +369,16 @@ pub mod tee_bridge {
             TeeBridgeError::InvalidDenomination
         );
 
-        // Verify user owns the staging account
+        if let Some(preimage) = &preimage {
+            require!(
+                compute_commitment(&preimage.nullifier, &preimage.secret) == commitment,
+                TeeBridgeError::CommitmentMismatch
+            );
+        }
+
+        // Verify signer owns the staging account or is its delegate
         require!(
-            ctx.accounts.user.key() == ctx.accounts.staging.user,
+            ctx.accounts.staging.can_commit(&ctx.accounts.user.key()),
             TeeBridgeError::Unauthorized
         );
 
@@ -139,10 +390,28 @@ pub mod tee_bridge {
             TeeBridgeError::InsufficientBalance
         );
 
-        // Deduct from staging balance
+        // Staging releases against a single batch, so it can't hold
+        // unreleased commitments in two at once
+        let batch_id = ctx.accounts.batch.id;
+        require!(
+            staging.committed == 0 || staging.batch_id == batch_id,
+            TeeBridgeError::StagingBatchMismatch
+        );
+
+        // Deduct from staging balance; the funds move into the batch, which
+        // pays the settle reward and releases them afterwards
         staging.balance = staging.balance
             .checked_sub(denomination)
             .ok_or(TeeBridgeError::InsufficientBalance)?;
+        safe_transfer_lamports(
+            &staging.to_account_info(),
+            &ctx.accounts.batch.to_account_info(),
+            denomination,
+        )?;
+        staging.batch_id = batch_id;
+        staging.committed = staging.committed
+            .checked_add(denomination)
+            .ok_or(TeeBridgeError::ArithmeticOverflow)?;
         staging.commitment_count = staging.commitment_count
             .checked_add(1)
             .ok_or(TeeBridgeError::ArithmeticOverflow)?;
@@ -159,13 +428,22 @@ pub mod tee_bridge {
 
         // Add to current batch
         let batch = &mut ctx.accounts.batch;
+        if let Some(counter) = &ctx.accounts.batch_counter {
+            require!(
+                counter.current_batch_id == batch.id,
+                TeeBridgeError::NotCurrentBatch
+            );
+        }
+        require!(!batch.expired, TeeBridgeError::BatchExpired);
         require!(
-            batch.commitment_count < 10,
+            batch.commitment_count < batch.max_size,
             TeeBridgeError::BatchFull
         );
-        let idx = batch.commitment_count as usize;
-        batch.commitments[idx] = commitment;
-        batch.denominations[idx] = denomination;
+        if batch.enforce_user_quota {
+            batch.record_user_commitment(ctx.accounts.staging.user)?;
+        }
+        batch.commitments.push(commitment);
+        batch.denominations.push(denomination);
         batch.commitment_count += 1;
         batch.total_amount = batch.total_amount
             .checked_add(denomination)
@@ -180,10 +458,15 @@ pub mod tee_bridge {
 
     /// Settle a batch of commitments to the main privacy pool
     ///
-    /// ANYONE can call this once the batch has >= BATCH_THRESHOLD commitments.
+    /// ANYONE can call this once the batch has >= `batch.threshold` commitments.
     /// This is intentionally permissionless to ensure batches settle even if
     /// the original authority goes offline.
     ///
+    /// The settler is paid the batch's `settle_reward_lamports`, capped at
+    /// `MAX_SETTLE_REWARD_BPS` of `total_amount`, so keepers have a reason to
+    /// settle stuck batches. It is skimmed from the staging funds the batch
+    /// collected, and each release afterwards carries its pro-rata share.
+    ///
     /// Privacy note: The settler gains no information about who deposited -
     /// they only see commitment hashes, not wallet addresses or secrets.
     pub fn settle_batch(ctx: Context<SettleBatch>) -> Result<()> {
@@ -201,9 +484,15 @@ pub mod tee_bridge {
             TeeBridgeError::BatchAlreadySettled
         );
 
+        // Expired batches release funds back to users instead of settling
+        require!(
+            !batch.expired,
+            TeeBridgeError::BatchExpired
+        );
+
         // ANYONE can settle once batch has enough commitments (decentralized)
         require!(
-            batch.commitment_count >= BATCH_THRESHOLD,
+            batch.commitment_count >= batch.threshold,
             TeeBridgeError::BatchNotReady
         );
 
@@ -214,17 +503,97 @@ pub mod tee_bridge {
         // Mark batch as settled
         batch.settled = true;
 
+        // Keeper incentive, skimmed from the batch's collected funds
+        let settler_reward = batch.settle_reward();
+        if settler_reward > 0 {
+            safe_transfer_lamports(
+                &batch.to_account_info(),
+                &ctx.accounts.settler.to_account_info(),
+                settler_reward,
+            )?;
+        }
+
         // Emit settlement event for off-chain relayer to pick up
         emit!(BatchSettlementEvent {
             batch_id: batch.id,
             commitment_count: batch.commitment_count,
             total_amount: batch.total_amount,
+            settler_reward,
             timestamp: Clock::get()?.unix_timestamp,
         });
 
         Ok(())
     }
 
+    /// Mark an individual commitment as settled once its batch has settled
+    ///
+    /// Permissionless: anyone (typically the relayer) can finalize a commitment
+    /// by pointing at the settled batch that contains its hash. This gives
+    /// indexers a per-note settlement receipt on the `TeeCommitment` account.
+    pub fn finalize_commitment(ctx: Context<FinalizeCommitment>) -> Result<()> {
+        let batch = &ctx.accounts.batch;
+        let tee_commitment = &mut ctx.accounts.tee_commitment;
+
+        require!(batch.settled, TeeBridgeError::BatchNotReady);
+        require!(
+            !tee_commitment.settled,
+            TeeBridgeError::CommitmentAlreadyFinalized
+        );
+
+        let count = batch.commitment_count as usize;
+        require!(
+            batch.commitments[..count].contains(&tee_commitment.commitment),
+            TeeBridgeError::CommitmentNotInBatch
+        );
+
+        tee_commitment.settled = true;
+        tee_commitment.batch_id = batch.id;
+
+        msg!("Commitment finalized in batch {}", batch.id);
+        Ok(())
+    }
+
+    /// Expire a batch that never reached its settlement threshold
+    ///
+    /// ANYONE can call this once the batch is older than `batch.max_age_seconds`.
+    /// Expiry unlocks `release_settled_funds` so staged funds aren't stuck
+    /// behind a batch that will never settle. A batch is either settled or
+    /// expired, never both.
+    pub fn expire_batch(ctx: Context<ExpireBatch>) -> Result<()> {
+        let batch = &mut ctx.accounts.batch;
+
+        require!(
+            !batch.settled,
+            TeeBridgeError::BatchAlreadySettled
+        );
+        require!(
+            !batch.expired,
+            TeeBridgeError::BatchExpired
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let age = now
+            .checked_sub(batch.created_at)
+            .ok_or(TeeBridgeError::ArithmeticOverflow)?;
+        require!(
+            age > batch.max_age_seconds,
+            TeeBridgeError::BatchNotExpired
+        );
+
+        batch.expired = true;
+
+        msg!("Batch {} expired with {} commitments", batch.id, batch.commitment_count);
+        msg!("Caller: {}", ctx.accounts.caller.key());
+
+        emit!(BatchExpiredEvent {
+            batch_id: batch.id,
+            commitment_count: batch.commitment_count,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
     /// Withdraw remaining balance from staging
     /// User must own the staging account
     pub fn withdraw_from_staging(ctx: Context<WithdrawFromStaging>, amount: u64) -> Result<()> {
@@ -236,11 +605,11 @@ pub mod tee_bridge {
         );
 
         // Transfer lamports from staging to user
-        let staging_info = staging.to_account_info();
-        let user_info = ctx.accounts.user.to_account_info();
-
-        **staging_info.try_borrow_mut_lamports()? -= amount;
-        **user_info.try_borrow_mut_lamports()? += amount;
+        safe_transfer_lamports(
+            &staging.to_account_info(),
+            &ctx.accounts.user.to_account_info(),
+            amount,
+        )?;
 
         staging.balance = staging.balance
             .checked_sub(amount)
@@ -250,45 +619,194 @@ pub mod tee_bridge {
         Ok(())
     }
 
-    /// Release committed funds from staging after batch settlement
-    /// The batch must be settled, and the user gets back lamports
-    /// equal to their committed amount so they can shield to Light Protocol
+    /// Release committed funds from the batch after settlement
+    /// The batch must be the one staging committed into and be settled (or
+    /// expired), and the user gets back at most their committed amount (less
+    /// its share of the settle reward) so they can shield to Light Protocol
     pub fn release_settled_funds(ctx: Context<ReleaseSettledFunds>, amount: u64) -> Result<()> {
-        let batch = &ctx.accounts.batch;
-        require!(batch.settled, TeeBridgeError::BatchNotReady);
+        let payout = release_from_batch(
+            &ctx.accounts.batch,
+            &mut ctx.accounts.staging,
+            &ctx.accounts.user.to_account_info(),
+            amount,
+        )?;
+
+        if ctx.accounts.batch.expired {
+            msg!("Released {} lamports from batch after expiry", payout);
+        } else {
+            msg!("Released {} lamports from batch after settlement", payout);
+        }
+        Ok(())
+    }
 
-        let staging_info = ctx.accounts.staging.to_account_info();
+    /// Release committed funds and compress them into Light Protocol
+    ///
+    /// Same checks as `release_settled_funds`, then CPIs into the Light
+    /// system program with `light_instruction_data` over
+    /// `remaining_accounts` (see the module docs). Fails unless the user's
+    /// wallet ends up no richer than before the release, i.e. the CPI moved
+    /// at least the amount paid out into Light's SOL pool.
+    pub fn release_and_shield<'info>(
+        ctx: Context<'_, '_, '_, 'info, ReleaseAndShield<'info>>,
+        amount: u64,
+        light_instruction_data: Vec<u8>,
+    ) -> Result<()> {
         let user_info = ctx.accounts.user.to_account_info();
+        let lamports_before = user_info.lamports();
+
+        let payout = release_from_batch(
+            &ctx.accounts.batch,
+            &mut ctx.accounts.staging,
+            &user_info,
+            amount,
+        )?;
+
+        let instruction = anchor_lang::solana_program::instruction::Instruction {
+            program_id: LIGHT_SYSTEM_PROGRAM_ID,
+            accounts: cpi_account_metas(ctx.remaining_accounts),
+            data: light_instruction_data,
+        };
+        let mut account_infos = ctx.remaining_accounts.to_vec();
+        account_infos.push(ctx.accounts.light_system_program.to_account_info());
+        anchor_lang::solana_program::program::invoke(&instruction, &account_infos)?;
 
-        // Ensure staging has enough lamports (excluding rent-exempt minimum)
-        let rent = Rent::get()?;
-        let min_balance = rent.minimum_balance(staging_info.data_len());
-        let available = staging_info.lamports()
-            .checked_sub(min_balance)
-            .ok_or(TeeBridgeError::InsufficientBalance)?;
-        require!(available >= amount, TeeBridgeError::InsufficientBalance);
+        require!(
+            user_info.lamports() <= lamports_before,
+            TeeBridgeError::ShieldIncomplete
+        );
 
-        **staging_info.try_borrow_mut_lamports()? -= amount;
-        **user_info.try_borrow_mut_lamports()? += amount;
+        msg!("Released and shielded {} lamports from batch", payout);
+        Ok(())
+    }
+
+    /// Close the staging account and return its rent to the user
+    ///
+    /// Requires a zero balance, nothing left committed and no lamports above
+    /// the rent-exempt minimum: committed funds wait in their batch until
+    /// `release_settled_funds`, and closing before then would lose track of
+    /// them. `TeeCommitment` accounts are independent PDAs and are unaffected.
+    pub fn close_staging(ctx: Context<CloseStaging>) -> Result<()> {
+        let staging = &ctx.accounts.staging;
+        let staging_info = staging.to_account_info();
+
+        let min_balance = Rent::get()?.minimum_balance(staging_info.data_len());
+        require!(
+            staging.balance == 0 && staging.committed == 0 && staging_info.lamports() <= min_balance,
+            TeeBridgeError::StagingNotEmpty
+        );
+
+        emit!(StagingClosedEvent {
+            user: staging.user,
+            lamports_returned: staging_info.lamports(),
+            commitment_count: staging.commitment_count,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
 
-        msg!("Released {} lamports from staging after settlement", amount);
+        msg!("Staging account closed for user: {}", staging.user);
         Ok(())
     }
 
     /// Initialize a new batch for collecting commitments
-    pub fn initialize_batch(ctx: Context<InitializeBatch>, batch_id: u64) -> Result<()> {
-        let batch = &mut ctx.accounts.batch;
-        batch.id = batch_id;
-        batch.authority = ctx.accounts.authority.key();
-        batch.commitments = [[0u8; 32]; 10];
-        batch.denominations = [0u64; 10];
-        batch.commitment_count = 0;
-        batch.total_amount = 0;
-        batch.created_at = Clock::get()?.unix_timestamp;
-        batch.settled = false;
-        batch.bump = ctx.bumps.batch;
-
-        msg!("Batch {} initialized", batch_id);
+    ///
+    /// `max_size` bounds how many commitments the batch can hold (and its rent),
+    /// `threshold` is the minimum count before anyone can settle.
+    /// Pass `DEFAULT_BATCH_SIZE` / `BATCH_THRESHOLD` for the standard 10/3 batch.
+    /// `max_age_seconds` defaults to `DEFAULT_BATCH_MAX_AGE_SECONDS` when `None`
+    /// and can't be below `MIN_BATCH_MAX_AGE_SECONDS`.
+    ///
+    /// `enforce_user_quota` caps each user at `MAX_COMMITMENTS_PER_BATCH_PER_USER`
+    /// commitments, so one user can't fill a batch and thin out the mixing.
+    /// The tradeoff: the batch then records which staging users contributed
+    /// and how many times. It never maps users to commitments, but
+    /// privacy-maximalists may prefer to leave it off.
+    ///
+    /// `settle_reward_lamports` is paid to whoever settles the batch out of
+    /// its collected funds (see `settle_batch`); pass 0 for none.
+    #[allow(clippy::too_many_arguments)]
+    pub fn initialize_batch(
+        ctx: Context<InitializeBatch>,
+        batch_id: u64,
+        max_size: u8,
+        threshold: u8,
+        max_age_seconds: Option<i64>,
+        enforce_user_quota: bool,
+        settle_reward_lamports: u64,
+    ) -> Result<()> {
+        require!(batch_id < COUNTER_BATCH_ID_START, TeeBridgeError::BatchIdReserved);
+
+        init_batch(
+            &mut ctx.accounts.batch,
+            batch_id,
+            ctx.accounts.authority.key(),
+            max_size,
+            threshold,
+            max_age_seconds,
+            enforce_user_quota,
+            settle_reward_lamports,
+            ctx.bumps.batch,
+        )
+    }
+
+    /// Initialize the global batch counter (once)
+    ///
+    /// Only the program's upgrade authority can call this, so the counter
+    /// can't be front-run. It becomes the bridge authority, the only key
+    /// allowed to open batches through `initialize_next_batch`. Counter ids
+    /// start at `COUNTER_BATCH_ID_START`, above any explicit batch id.
+    pub fn initialize_batch_counter(ctx: Context<InitializeBatchCounter>) -> Result<()> {
+        let counter = &mut ctx.accounts.batch_counter;
+        counter.authority = ctx.accounts.authority.key();
+        counter.next_batch_id = COUNTER_BATCH_ID_START;
+        counter.current_batch_id = COUNTER_BATCH_ID_START;
+        counter.bump = ctx.bumps.batch_counter;
+
+        msg!("Batch counter initialized");
+        Ok(())
+    }
+
+    /// Initialize the next batch, taking its id from the global counter
+    ///
+    /// The new batch becomes the counter's current batch, which
+    /// `create_private_commitment` can be pinned to by passing the counter.
+    /// Explicit ids passed to `initialize_batch` share the same PDA space but
+    /// are kept below `COUNTER_BATCH_ID_START`, so they can't collide.
+    ///
+    /// Only the counter's authority can call this, so nobody else can move
+    /// the current batch out from under pending commitments.
+    pub fn initialize_next_batch(
+        ctx: Context<InitializeNextBatch>,
+        max_size: u8,
+        threshold: u8,
+        max_age_seconds: Option<i64>,
+        enforce_user_quota: bool,
+        settle_reward_lamports: u64,
+    ) -> Result<()> {
+        let counter = &mut ctx.accounts.batch_counter;
+        let batch_id = counter.next_batch_id;
+        counter.current_batch_id = batch_id;
+        counter.next_batch_id = batch_id
+            .checked_add(1)
+            .ok_or(TeeBridgeError::ArithmeticOverflow)?;
+
+        init_batch(
+            &mut ctx.accounts.batch,
+            batch_id,
+            ctx.accounts.authority.key(),
+            max_size,
+            threshold,
+            max_age_seconds,
+            enforce_user_quota,
+            settle_reward_lamports,
+            ctx.bumps.batch,
+        )?;
+
+        emit!(BatchInitializedEvent {
+            batch_id,
+            max_size,
+            threshold,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
         Ok(())
     }
 }
@@ -333,15 +851,29 @@ pub struct DepositToStaging<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct AuthorizeDelegate<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [STAGING_SEED, user.key().as_ref()],
+        bump = staging.bump,
+        constraint = staging.user == user.key() @ TeeBridgeError::Unauthorized,
+    )]
+    pub staging: Account<'info, StagingAccount>,
+}
+
 #[derive(Accounts)]
 #[instruction(denomination: u64, commitment: [u8; 32])]
 pub struct CreatePrivateCommitment<'info> {
+    /// Staging owner or its authorized delegate
     #[account(mut)]
     pub user: Signer<'info>,
 
     #[account(
         mut,
-        seeds = [STAGING_SEED, user.key().as_ref()],
+        seeds = [STAGING_SEED, staging.user.as_ref()],
         bump = staging.bump,
     )]
     pub staging: Account<'info, StagingAccount>,
@@ -362,6 +894,13 @@ pub struct CreatePrivateCommitment<'info> {
     )]
     pub batch: Account<'info, CommitmentBatch>,
 
+    /// Optional: pins the commitment to the counter's current batch
+    #[account(
+        seeds = [BATCH_COUNTER_SEED],
+        bump = batch_counter.bump,
+    )]
+    pub batch_counter: Option<Account<'info, BatchCounter>>,
+
     pub system_program: Program<'info, System>,
 }
 
@@ -379,6 +918,38 @@ pub struct SettleBatch<'info> {
     pub batch: Account<'info, CommitmentBatch>,
 }
 
+#[derive(Accounts)]
+pub struct FinalizeCommitment<'info> {
+    /// Anyone can finalize a commitment once its batch has settled
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [COMMITMENT_SEED, tee_commitment.commitment.as_ref()],
+        bump = tee_commitment.bump,
+    )]
+    pub tee_commitment: Account<'info, TeeCommitment>,
+
+    #[account(
+        seeds = [BATCH_SEED, &batch.id.to_le_bytes()],
+        bump = batch.bump,
+    )]
+    pub batch: Account<'info, CommitmentBatch>,
+}
+
+#[derive(Accounts)]
+pub struct ExpireBatch<'info> {
+    /// Anyone can expire a batch once it is past its max age
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [BATCH_SEED, &batch.id.to_le_bytes()],
+        bump = batch.bump,
+    )]
+    pub batch: Account<'info, CommitmentBatch>,
+}
+
 #[derive(Accounts)]
 pub struct WithdrawFromStaging<'info> {
     #[account(mut)]
@@ -393,6 +964,21 @@ pub struct WithdrawFromStaging<'info> {
     pub staging: Account<'info, StagingAccount>,
 }
 
+#[derive(Accounts)]
+pub struct CloseStaging<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        close = user,
+        seeds = [STAGING_SEED, user.key().as_ref()],
+        bump = staging.bump,
+        constraint = staging.user == user.key() @ TeeBridgeError::Unauthorized,
+    )]
+    pub staging: Account<'info, StagingAccount>,
+}
+
 #[derive(Accounts)]
 pub struct ReleaseSettledFunds<'info> {
     #[account(mut)]
@@ -407,14 +993,40 @@ pub struct ReleaseSettledFunds<'info> {
     pub staging: Account<'info, StagingAccount>,
 
     #[account(
+        mut,
+        seeds = [BATCH_SEED, &batch.id.to_le_bytes()],
+        bump = batch.bump,
+    )]
+    pub batch: Account<'info, CommitmentBatch>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseAndShield<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [STAGING_SEED, user.key().as_ref()],
+        bump = staging.bump,
+        constraint = staging.user == user.key() @ TeeBridgeError::Unauthorized,
+    )]
+    pub staging: Account<'info, StagingAccount>,
+
+    #[account(
+        mut,
         seeds = [BATCH_SEED, &batch.id.to_le_bytes()],
         bump = batch.bump,
     )]
     pub batch: Account<'info, CommitmentBatch>,
+
+    /// CHECK: Only used as the CPI target; its address is checked
+    #[account(executable, address = LIGHT_SYSTEM_PROGRAM_ID @ TeeBridgeError::InvalidLightProgram)]
+    pub light_system_program: UncheckedAccount<'info>,
 }
 
 #[derive(Accounts)]
-#[instruction(batch_id: u64)]
+#[instruction(batch_id: u64, max_size: u8)]
 pub struct InitializeBatch<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
@@ -422,7 +1034,7 @@ pub struct InitializeBatch<'info> {
     #[account(
         init,
         payer = authority,
-        space = 8 + CommitmentBatch::INIT_SPACE,
+        space = CommitmentBatch::space(max_size.min(MAX_BATCH_SIZE)),
         seeds = [BATCH_SEED, &batch_id.to_le_bytes()],
         bump,
     )]
@@ -431,6 +1043,58 @@ pub struct InitializeBatch<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct InitializeBatchCounter<'info> {
+    /// Program upgrade authority; becomes the counter's authority
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::TeeBridge>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(authority.key()) @ TeeBridgeError::Unauthorized,
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + BatchCounter::INIT_SPACE,
+        seeds = [BATCH_COUNTER_SEED],
+        bump,
+    )]
+    pub batch_counter: Account<'info, BatchCounter>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(max_size: u8)]
+pub struct InitializeNextBatch<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [BATCH_COUNTER_SEED],
+        bump = batch_counter.bump,
+        has_one = authority @ TeeBridgeError::Unauthorized,
+    )]
+    pub batch_counter: Account<'info, BatchCounter>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = CommitmentBatch::space(max_size.min(MAX_BATCH_SIZE)),
+        seeds = [BATCH_SEED, &batch_counter.next_batch_id.to_le_bytes()],
+        bump,
+    )]
+    pub batch: Account<'info, CommitmentBatch>,
+
+    pub system_program: Program<'info, System>,
+}
+
 // ============================================
 // Account Structures
 // ============================================
@@ -447,15 +1111,29 @@ pub struct StagingAccount {
     pub commitment_count: u64,
     /// When the account was created
     pub created_at: i64,
+    /// Key allowed to create commitments on the owner's behalf
+    /// (`Pubkey::default()` when none is set)
+    pub delegate: Pubkey,
+    /// Batch holding this account's unreleased commitments
+    pub batch_id: u64,
+    /// Lamports committed into `batch_id` and not yet released
+    pub committed: u64,
     /// PDA bump
     pub bump: u8,
 }
 
+impl StagingAccount {
+    /// Whether `signer` may spend this balance into commitments
+    pub fn can_commit(&self, signer: &Pubkey) -> bool {
+        *signer == self.user || (self.delegate != Pubkey::default() && *signer == self.delegate)
+    }
+}
+
 /// A commitment created privately in the TEE
 #[account]
 #[derive(InitSpace)]
 pub struct TeeCommitment {
-    /// The commitment hash: Keccak256(nullifier || secret)
+    /// The commitment hash: Keccak256(COMMITMENT_DOMAIN || nullifier || secret)
     pub commitment: [u8; 32],
     /// Denomination in lamports
     pub denomination: u64,
@@ -471,18 +1149,35 @@ pub struct TeeCommitment {
     pub bump: u8,
 }
 
-/// A batch of commitments to be settled together
+/// Global counter handing out batch ids
 #[account]
 #[derive(InitSpace)]
+pub struct BatchCounter {
+    /// Bridge authority allowed to open batches
+    pub authority: Pubkey,
+    /// Id the next `initialize_next_batch` will use
+    pub next_batch_id: u64,
+    /// Id of the most recently opened batch
+    pub current_batch_id: u64,
+    /// PDA bump
+    pub bump: u8,
+}
+
+/// A batch of commitments to be settled together
+#[account]
 pub struct CommitmentBatch {
     /// Batch identifier
     pub id: u64,
     /// Who can settle this batch
     pub authority: Pubkey,
-    /// Commitments in this batch (max 10)
-    pub commitments: [[u8; 32]; 10],
+    /// Maximum number of commitments this batch can hold
+    pub max_size: u8,
+    /// Minimum commitments required before anyone can settle
+    pub threshold: u8,
+    /// Commitments in this batch (at most `max_size`)
+    pub commitments: Vec<[u8; 32]>,
     /// Denomination for each commitment
-    pub denominations: [u64; 10],
+    pub denominations: Vec<u64>,
     /// Number of commitments in this batch
     pub commitment_count: u8,
     /// Total amount in lamports
@@ -491,18 +1186,227 @@ pub struct CommitmentBatch {
     pub created_at: i64,
     /// Whether the batch has been settled
     pub settled: bool,
+    /// Age in seconds after which an unsettled batch can be expired
+    pub max_age_seconds: i64,
+    /// Whether the batch expired before settling (funds releasable)
+    pub expired: bool,
+    /// Whether `MAX_COMMITMENTS_PER_BATCH_PER_USER` is enforced
+    pub enforce_user_quota: bool,
+    /// Commitments added per staging user (only tracked with the quota on)
+    pub user_counts: Vec<UserCommitmentCount>,
+    /// Reward skimmed for whoever settles the batch (see `settle_reward`)
+    pub settle_reward_lamports: u64,
     /// PDA bump
     pub bump: u8,
 }
 
+/// How many commitments a staging user has added to a batch
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct UserCommitmentCount {
+    pub user: Pubkey,
+    pub count: u8,
+}
+
+impl CommitmentBatch {
+    /// Fixed portion: discriminator + id + authority + max_size + threshold +
+    /// three vec length prefixes + count + total + created_at + settled +
+    /// max_age_seconds + expired + enforce_user_quota + settle_reward_lamports + bump
+    pub const HEADER_SIZE: usize = 8 + 8 + 32 + 1 + 1 + 4 + 4 + 4 + 1 + 8 + 8 + 1 + 8 + 1 + 1 + 8 + 1;
+
+    /// Per-commitment cost: commitment hash + denomination + worst-case
+    /// user count entry (every commitment from a different user)
+    pub const ENTRY_SIZE: usize = 32 + 8 + 32 + 1;
+
+    /// Count a commitment from `user`, rejecting it past the per-user quota
+    pub fn record_user_commitment(&mut self, user: Pubkey) -> Result<()> {
+        match self.user_counts.iter_mut().find(|entry| entry.user == user) {
+            Some(entry) => {
+                require!(
+                    entry.count < MAX_COMMITMENTS_PER_BATCH_PER_USER,
+                    TeeBridgeError::UserBatchQuotaExceeded
+                );
+                entry.count += 1;
+            }
+            None => self.user_counts.push(UserCommitmentCount { user, count: 1 }),
+        }
+        Ok(())
+    }
+
+    /// Reward paid on settlement: `settle_reward_lamports`, capped at
+    /// `MAX_SETTLE_REWARD_BPS` of the batch's `total_amount`
+    pub fn settle_reward(&self) -> u64 {
+        let cap = (self.total_amount as u128 * MAX_SETTLE_REWARD_BPS as u128 / 10_000) as u64;
+        self.settle_reward_lamports.min(cap)
+    }
+
+    /// Lamports paid out when `amount` of committed funds is released
+    ///
+    /// After settlement each release gives up its share of the settle
+    /// reward, rounded up so the shares always cover what the settler took.
+    /// Expired batches paid no reward and release in full.
+    pub fn release_payout(&self, amount: u64) -> u64 {
+        if !self.settled || self.total_amount == 0 {
+            return amount;
+        }
+        let total = self.total_amount as u128;
+        let share = (amount as u128 * self.settle_reward() as u128).div_ceil(total);
+        amount.saturating_sub(share as u64)
+    }
+
+    /// Calculate space for a batch holding `max_size` commitments
+    pub fn space(max_size: u8) -> usize {
+        Self::HEADER_SIZE + (max_size as usize * Self::ENTRY_SIZE)
+    }
+}
+
 // ============================================
 // Events
 // ============================================
 
+#[event]
+pub struct BatchInitializedEvent {
+    pub batch_id: u64,
+    pub max_size: u8,
+    pub threshold: u8,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct BatchSettlementEvent {
     pub batch_id: u64,
     pub commitment_count: u8,
     pub total_amount: u64,
+    pub settler_reward: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BatchExpiredEvent {
+    pub batch_id: u64,
+    pub commitment_count: u8,
     pub timestamp: i64,
 }
+
+#[event]
+pub struct StagingClosedEvent {
+    pub user: Pubkey,
+    pub lamports_returned: u64,
+    pub commitment_count: u64,
+    pub timestamp: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commitment_is_domain_separated() {
+        let nullifier = [1u8; 32];
+        let secret = [2u8; 32];
+
+        let raw = anchor_lang::solana_program::keccak::hashv(&[&nullifier, &secret]).to_bytes();
+        assert_ne!(compute_commitment(&nullifier, &secret), raw);
+
+        let prefixed = anchor_lang::solana_program::keccak::hashv(&[COMMITMENT_DOMAIN, &nullifier, &secret]).to_bytes();
+        assert_eq!(compute_commitment(&nullifier, &secret), prefixed);
+    }
+
+    #[test]
+    fn test_cpi_account_metas_keep_flags() {
+        let keys = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let owner = Pubkey::default();
+        let (mut first_lamports, mut second_lamports) = (0u64, 0u64);
+        let (mut first_data, mut second_data) = (vec![], vec![]);
+        let accounts = [
+            AccountInfo::new(&keys[0], true, true, &mut first_lamports, &mut first_data, &owner, false, 0),
+            AccountInfo::new(&keys[1], false, false, &mut second_lamports, &mut second_data, &owner, false, 0),
+        ];
+
+        let metas = cpi_account_metas(&accounts);
+        assert_eq!(metas, vec![AccountMeta::new(keys[0], true), AccountMeta::new_readonly(keys[1], false)]);
+    }
+
+    fn empty_batch() -> CommitmentBatch {
+        CommitmentBatch {
+            id: 0,
+            authority: Pubkey::default(),
+            max_size: 0,
+            threshold: 0,
+            commitments: Vec::new(),
+            denominations: Vec::new(),
+            commitment_count: 0,
+            total_amount: 0,
+            created_at: 0,
+            settled: false,
+            max_age_seconds: 0,
+            expired: false,
+            enforce_user_quota: false,
+            user_counts: Vec::new(),
+            settle_reward_lamports: 0,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn test_batch_max_age_has_floor() {
+        let mut batch = empty_batch();
+        let too_short = init_batch(
+            &mut batch,
+            0,
+            Pubkey::default(),
+            DEFAULT_BATCH_SIZE,
+            BATCH_THRESHOLD,
+            Some(MIN_BATCH_MAX_AGE_SECONDS - 1),
+            false,
+            0,
+            0,
+        );
+        assert_eq!(too_short.unwrap_err(), TeeBridgeError::InvalidBatchConfig.into());
+    }
+
+    #[test]
+    fn test_settle_reward_is_capped() {
+        let mut batch = CommitmentBatch {
+            max_size: DEFAULT_BATCH_SIZE,
+            threshold: BATCH_THRESHOLD,
+            commitment_count: 3,
+            total_amount: 3 * DENOMINATION_1_SOL,
+            max_age_seconds: DEFAULT_BATCH_MAX_AGE_SECONDS,
+            settle_reward_lamports: 1_000_000,
+            ..empty_batch()
+        };
+
+        // Under the 0.5% cap (15_000_000 lamports): paid in full
+        assert_eq!(batch.settle_reward(), 1_000_000);
+
+        // Above it: clamped
+        batch.settle_reward_lamports = DENOMINATION_1_SOL;
+        assert_eq!(batch.settle_reward(), 15_000_000);
+
+        batch.settle_reward_lamports = 0;
+        assert_eq!(batch.settle_reward(), 0);
+    }
+
+    #[test]
+    fn test_release_payout_covers_settle_reward() {
+        let mut batch = CommitmentBatch {
+            commitment_count: 3,
+            total_amount: DENOMINATION_0_5_SOL + DENOMINATION_1_SOL + DENOMINATION_10_SOL,
+            settle_reward_lamports: 1_000_001,
+            ..empty_batch()
+        };
+        let amounts = [DENOMINATION_0_5_SOL, DENOMINATION_1_SOL, DENOMINATION_10_SOL];
+
+        // Before settlement (i.e. on expiry) nothing is skimmed
+        batch.expired = true;
+        assert_eq!(batch.release_payout(DENOMINATION_1_SOL), DENOMINATION_1_SOL);
+
+        // After it, the shares cover the settler's reward, rounding in the batch's favour
+        batch.expired = false;
+        batch.settled = true;
+        let paid_out: u64 = amounts.iter().map(|&amount| batch.release_payout(amount)).sum();
+        let skimmed = batch.total_amount - paid_out;
+        assert!(skimmed >= batch.settle_reward());
+        assert!(skimmed <= batch.settle_reward() + amounts.len() as u64);
+    }
+}
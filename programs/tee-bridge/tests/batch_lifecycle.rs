@@ -0,0 +1,422 @@
+//! Batch lifecycle: staging deposits committed into a batch, then either
+//! expired and released in full, or settled (paying the keeper) and
+//! released less the reward share; plus the batch counter's id range and
+//! authority checks.
+//!
+//! Runs the program natively under `solana-program-test`.
+
+use anchor_lang::{AccountDeserialize, AccountSerialize, InstructionData, ToAccountMetas};
+use solana_program_test::{processor, ProgramTest, ProgramTestBanksClientExt, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    account_info::AccountInfo,
+    bpf_loader_upgradeable,
+    clock::Clock,
+    entrypoint::ProgramResult,
+    instruction::{Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction, system_program,
+    transaction::{Transaction, TransactionError},
+};
+use tee_bridge::{
+    BatchCounter, CommitmentBatch, StagingAccount, TeeBridgeError, BATCH_COUNTER_SEED, BATCH_SEED, COMMITMENT_SEED,
+    COUNTER_BATCH_ID_START, DEFAULT_BATCH_SIZE, DENOMINATION_1_SOL, MIN_BATCH_MAX_AGE_SECONDS, STAGING_SEED,
+};
+
+fn process_instruction(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    // Anchor's entry wants the accounts borrowed for 'info; leak them for the test
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    tee_bridge::entry(program_id, accounts, data)
+}
+
+fn program_test() -> ProgramTest {
+    ProgramTest::new("tee_bridge", tee_bridge::ID, processor!(process_instruction))
+}
+
+fn staging_address(user: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[STAGING_SEED, user.as_ref()], &tee_bridge::ID).0
+}
+
+fn batch_address(batch_id: u64) -> Pubkey {
+    Pubkey::find_program_address(&[BATCH_SEED, &batch_id.to_le_bytes()], &tee_bridge::ID).0
+}
+
+fn counter_address() -> Pubkey {
+    Pubkey::find_program_address(&[BATCH_COUNTER_SEED], &tee_bridge::ID).0
+}
+
+fn custom(error: TeeBridgeError) -> TransactionError {
+    TransactionError::InstructionError(0, InstructionError::Custom(error.into()))
+}
+
+async fn send(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<(), TransactionError> {
+    let blockhash = context.banks_client.get_new_latest_blockhash(&context.last_blockhash).await.unwrap();
+    context.last_blockhash = blockhash;
+
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    let tx = Transaction::new_signed_with_payer(instructions, Some(&context.payer.pubkey()), &all_signers, blockhash);
+    context.banks_client.process_transaction(tx).await.map_err(|e| e.unwrap())
+}
+
+async fn lamports(context: &mut ProgramTestContext, address: Pubkey) -> u64 {
+    context.banks_client.get_balance(address).await.unwrap()
+}
+
+async fn fetch<T: AccountDeserialize>(context: &mut ProgramTestContext, address: Pubkey) -> T {
+    let account = context.banks_client.get_account(address).await.unwrap().expect("account exists");
+    T::try_deserialize(&mut account.data.as_slice()).unwrap()
+}
+
+/// Lamports above the rent-exempt minimum
+async fn excess_lamports(context: &mut ProgramTestContext, address: Pubkey) -> u64 {
+    let account = context.banks_client.get_account(address).await.unwrap().expect("account exists");
+    let rent = context.banks_client.get_rent().await.unwrap();
+    account.lamports - rent.minimum_balance(account.data.len())
+}
+
+/// Move the clock forward by `seconds`
+async fn warp(context: &mut ProgramTestContext, seconds: i64) {
+    let mut clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    clock.unix_timestamp += seconds;
+    context.set_sysvar(&clock);
+}
+
+/// A funded user with an initialized staging account holding `staged` lamports
+async fn staged_user(context: &mut ProgramTestContext, staged: u64) -> Keypair {
+    let user = Keypair::new();
+    let payer = context.payer.pubkey();
+    send(
+        context,
+        &[
+            system_instruction::transfer(&payer, &user.pubkey(), staged + 2 * DENOMINATION_1_SOL),
+            Instruction {
+                program_id: tee_bridge::ID,
+                accounts: tee_bridge::accounts::InitializeStaging {
+                    payer,
+                    user: user.pubkey(),
+                    staging: staging_address(&user.pubkey()),
+                    system_program: system_program::ID,
+                }
+                .to_account_metas(None),
+                data: tee_bridge::instruction::InitializeStaging {}.data(),
+            },
+        ],
+        &[],
+    )
+    .await
+    .unwrap();
+    deposit(context, &user, staged).await;
+    user
+}
+
+async fn deposit(context: &mut ProgramTestContext, user: &Keypair, amount: u64) {
+    let ix = Instruction {
+        program_id: tee_bridge::ID,
+        accounts: tee_bridge::accounts::DepositToStaging {
+            user: user.pubkey(),
+            staging: staging_address(&user.pubkey()),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: tee_bridge::instruction::DepositToStaging { amount }.data(),
+    };
+    send(context, &[ix], &[user]).await.unwrap();
+}
+
+fn initialize_batch_ix(authority: Pubkey, batch_id: u64, threshold: u8, settle_reward_lamports: u64) -> Instruction {
+    Instruction {
+        program_id: tee_bridge::ID,
+        accounts: tee_bridge::accounts::InitializeBatch {
+            authority,
+            batch: batch_address(batch_id),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: tee_bridge::instruction::InitializeBatch {
+            batch_id,
+            max_size: DEFAULT_BATCH_SIZE,
+            threshold,
+            max_age_seconds: Some(MIN_BATCH_MAX_AGE_SECONDS),
+            enforce_user_quota: false,
+            settle_reward_lamports,
+        }
+        .data(),
+    }
+}
+
+fn commit_ix(user: Pubkey, batch_id: u64, commitment: [u8; 32], batch_counter: Option<Pubkey>) -> Instruction {
+    let (tee_commitment, _) = Pubkey::find_program_address(&[COMMITMENT_SEED, &commitment], &tee_bridge::ID);
+    Instruction {
+        program_id: tee_bridge::ID,
+        accounts: tee_bridge::accounts::CreatePrivateCommitment {
+            user,
+            staging: staging_address(&user),
+            tee_commitment,
+            batch: batch_address(batch_id),
+            batch_counter,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: tee_bridge::instruction::CreatePrivateCommitment {
+            denomination: DENOMINATION_1_SOL,
+            commitment,
+            encrypted_note: None,
+            preimage: None,
+        }
+        .data(),
+    }
+}
+
+fn settle_ix(settler: Pubkey, batch_id: u64) -> Instruction {
+    Instruction {
+        program_id: tee_bridge::ID,
+        accounts: tee_bridge::accounts::SettleBatch { settler, batch: batch_address(batch_id) }.to_account_metas(None),
+        data: tee_bridge::instruction::SettleBatch {}.data(),
+    }
+}
+
+fn expire_ix(caller: Pubkey, batch_id: u64) -> Instruction {
+    Instruction {
+        program_id: tee_bridge::ID,
+        accounts: tee_bridge::accounts::ExpireBatch { caller, batch: batch_address(batch_id) }.to_account_metas(None),
+        data: tee_bridge::instruction::ExpireBatch {}.data(),
+    }
+}
+
+fn release_ix(user: Pubkey, batch_id: u64, amount: u64) -> Instruction {
+    Instruction {
+        program_id: tee_bridge::ID,
+        accounts: tee_bridge::accounts::ReleaseSettledFunds {
+            user,
+            staging: staging_address(&user),
+            batch: batch_address(batch_id),
+        }
+        .to_account_metas(None),
+        data: tee_bridge::instruction::ReleaseSettledFunds { amount }.data(),
+    }
+}
+
+#[tokio::test]
+async fn test_expired_batch_releases_in_full() {
+    let mut context = program_test().start_with_context().await;
+    let payer = context.payer.pubkey();
+    let user = staged_user(&mut context, DENOMINATION_1_SOL).await;
+
+    send(&mut context, &[initialize_batch_ix(payer, 1, 3, 1_000_000)], &[]).await.unwrap();
+    send(&mut context, &[commit_ix(user.pubkey(), 1, [1u8; 32], None)], &[&user]).await.unwrap();
+
+    // The denomination moved from staging into the batch
+    let staging: StagingAccount = fetch(&mut context, staging_address(&user.pubkey())).await;
+    assert_eq!((staging.balance, staging.batch_id, staging.committed), (0, 1, DENOMINATION_1_SOL));
+    assert_eq!(excess_lamports(&mut context, batch_address(1)).await, DENOMINATION_1_SOL);
+
+    // Below threshold: it can't settle, and nothing is releasable yet
+    let err = send(&mut context, &[settle_ix(payer, 1)], &[]).await.unwrap_err();
+    assert_eq!(err, custom(TeeBridgeError::BatchNotReady));
+    let err = send(&mut context, &[release_ix(user.pubkey(), 1, DENOMINATION_1_SOL)], &[&user]).await.unwrap_err();
+    assert_eq!(err, custom(TeeBridgeError::BatchNotReady));
+
+    // Expiry waits out max_age_seconds
+    let err = send(&mut context, &[expire_ix(payer, 1)], &[]).await.unwrap_err();
+    assert_eq!(err, custom(TeeBridgeError::BatchNotExpired));
+    warp(&mut context, MIN_BATCH_MAX_AGE_SECONDS + 1).await;
+    send(&mut context, &[expire_ix(payer, 1)], &[]).await.unwrap();
+
+    // An expired batch neither settles nor takes new commitments
+    let err = send(&mut context, &[settle_ix(payer, 1)], &[]).await.unwrap_err();
+    assert_eq!(err, custom(TeeBridgeError::BatchExpired));
+    deposit(&mut context, &user, DENOMINATION_1_SOL).await;
+    let err = send(&mut context, &[commit_ix(user.pubkey(), 1, [2u8; 32], None)], &[&user]).await.unwrap_err();
+    assert_eq!(err, custom(TeeBridgeError::BatchExpired));
+
+    // No reward was paid, so the release is in full
+    let before = lamports(&mut context, user.pubkey()).await;
+    send(&mut context, &[release_ix(user.pubkey(), 1, DENOMINATION_1_SOL)], &[&user]).await.unwrap();
+    assert_eq!(lamports(&mut context, user.pubkey()).await, before + DENOMINATION_1_SOL);
+    assert_eq!(excess_lamports(&mut context, batch_address(1)).await, 0);
+
+    // Only what was committed can come out
+    let err = send(&mut context, &[release_ix(user.pubkey(), 1, 1)], &[&user]).await.unwrap_err();
+    assert_eq!(err, custom(TeeBridgeError::ExceedsCommittedFunds));
+    let staging: StagingAccount = fetch(&mut context, staging_address(&user.pubkey())).await;
+    assert_eq!((staging.balance, staging.committed), (DENOMINATION_1_SOL, 0));
+}
+
+#[tokio::test]
+async fn test_settle_reward_is_skimmed_from_batch_funds() {
+    let mut context = program_test().start_with_context().await;
+    let payer = context.payer.pubkey();
+    let alice = staged_user(&mut context, 2 * DENOMINATION_1_SOL).await;
+    let bob = staged_user(&mut context, DENOMINATION_1_SOL).await;
+    let keeper = Keypair::new();
+    send(&mut context, &[system_instruction::transfer(&payer, &keeper.pubkey(), DENOMINATION_1_SOL)], &[])
+        .await
+        .unwrap();
+
+    let reward = 1_000_000;
+    send(
+        &mut context,
+        &[initialize_batch_ix(payer, 1, 2, reward), initialize_batch_ix(payer, 2, 1, 0)],
+        &[],
+    )
+    .await
+    .unwrap();
+    send(&mut context, &[commit_ix(alice.pubkey(), 1, [1u8; 32], None)], &[&alice]).await.unwrap();
+    send(&mut context, &[commit_ix(bob.pubkey(), 1, [2u8; 32], None)], &[&bob]).await.unwrap();
+
+    // Staging can't spread unreleased commitments over two batches
+    let err = send(&mut context, &[commit_ix(alice.pubkey(), 2, [3u8; 32], None)], &[&alice]).await.unwrap_err();
+    assert_eq!(err, custom(TeeBridgeError::StagingBatchMismatch));
+
+    // The keeper's reward comes out of the batch, not anyone's wallet
+    let before = lamports(&mut context, keeper.pubkey()).await;
+    send(&mut context, &[settle_ix(keeper.pubkey(), 1)], &[&keeper]).await.unwrap();
+    assert_eq!(lamports(&mut context, keeper.pubkey()).await, before + reward);
+    assert_eq!(excess_lamports(&mut context, batch_address(1)).await, 2 * DENOMINATION_1_SOL - reward);
+
+    let err = send(&mut context, &[settle_ix(keeper.pubkey(), 1)], &[&keeper]).await.unwrap_err();
+    assert_eq!(err, custom(TeeBridgeError::BatchAlreadySettled));
+    warp(&mut context, MIN_BATCH_MAX_AGE_SECONDS + 1).await;
+    let err = send(&mut context, &[expire_ix(payer, 1)], &[]).await.unwrap_err();
+    assert_eq!(err, custom(TeeBridgeError::BatchAlreadySettled));
+
+    // Each release carries half the reward, and together they drain the batch
+    let batch: CommitmentBatch = fetch(&mut context, batch_address(1)).await;
+    assert_eq!(batch.release_payout(DENOMINATION_1_SOL), DENOMINATION_1_SOL - reward / 2);
+    for user in [&alice, &bob] {
+        let before = lamports(&mut context, user.pubkey()).await;
+        send(&mut context, &[release_ix(user.pubkey(), 1, DENOMINATION_1_SOL)], &[user]).await.unwrap();
+        assert_eq!(lamports(&mut context, user.pubkey()).await, before + DENOMINATION_1_SOL - reward / 2);
+    }
+    assert_eq!(excess_lamports(&mut context, batch_address(1)).await, 0);
+
+    // Once released, staging is free to commit into another batch
+    send(&mut context, &[commit_ix(alice.pubkey(), 2, [3u8; 32], None)], &[&alice]).await.unwrap();
+    let staging: StagingAccount = fetch(&mut context, staging_address(&alice.pubkey())).await;
+    assert_eq!((staging.batch_id, staging.committed), (2, DENOMINATION_1_SOL));
+
+    // Releasing needs the batch that holds the commitments
+    let err = send(&mut context, &[release_ix(alice.pubkey(), 1, DENOMINATION_1_SOL)], &[&alice]).await.unwrap_err();
+    assert_eq!(err, custom(TeeBridgeError::StagingBatchMismatch));
+}
+
+#[tokio::test]
+async fn test_batch_counter_ids_and_authority() {
+    let mut program_test = program_test();
+
+    // Seed the counter: initializing it needs an upgradeable program, which
+    // a native processor isn't (see the upgrade-authority test below)
+    let authority = Keypair::new();
+    let counter = BatchCounter {
+        authority: authority.pubkey(),
+        next_batch_id: COUNTER_BATCH_ID_START,
+        current_batch_id: COUNTER_BATCH_ID_START,
+        bump: Pubkey::find_program_address(&[BATCH_COUNTER_SEED], &tee_bridge::ID).1,
+    };
+    let mut data = Vec::new();
+    counter.try_serialize(&mut data).unwrap();
+    program_test.add_account(
+        counter_address(),
+        Account { lamports: DENOMINATION_1_SOL, data, owner: tee_bridge::ID, ..Account::default() },
+    );
+    program_test.add_account(authority.pubkey(), Account::new(DENOMINATION_1_SOL, 0, &system_program::ID));
+
+    let mut context = program_test.start_with_context().await;
+    let payer = context.payer.pubkey();
+    let user = staged_user(&mut context, DENOMINATION_1_SOL).await;
+
+    // Explicit ids can't reach into the counter's range
+    let err = send(&mut context, &[initialize_batch_ix(payer, COUNTER_BATCH_ID_START, 1, 0)], &[])
+        .await
+        .unwrap_err();
+    assert_eq!(err, custom(TeeBridgeError::BatchIdReserved));
+
+    let next_batch_ix = |authority: Pubkey, batch_id: u64| Instruction {
+        program_id: tee_bridge::ID,
+        accounts: tee_bridge::accounts::InitializeNextBatch {
+            authority,
+            batch_counter: counter_address(),
+            batch: batch_address(batch_id),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: tee_bridge::instruction::InitializeNextBatch {
+            max_size: DEFAULT_BATCH_SIZE,
+            threshold: 1,
+            max_age_seconds: None,
+            enforce_user_quota: false,
+            settle_reward_lamports: 0,
+        }
+        .data(),
+    };
+
+    // Only the counter's authority opens batches
+    let err = send(&mut context, &[next_batch_ix(payer, COUNTER_BATCH_ID_START)], &[]).await.unwrap_err();
+    assert_eq!(err, custom(TeeBridgeError::Unauthorized));
+
+    for batch_id in [COUNTER_BATCH_ID_START, COUNTER_BATCH_ID_START + 1] {
+        send(&mut context, &[next_batch_ix(authority.pubkey(), batch_id)], &[&authority]).await.unwrap();
+        let batch: CommitmentBatch = fetch(&mut context, batch_address(batch_id)).await;
+        assert_eq!(batch.id, batch_id);
+    }
+    let counter: BatchCounter = fetch(&mut context, counter_address()).await;
+    assert_eq!(counter.current_batch_id, COUNTER_BATCH_ID_START + 1);
+    assert_eq!(counter.next_batch_id, COUNTER_BATCH_ID_START + 2);
+
+    // Pinned to the counter, commitments only go into its current batch
+    let stale = commit_ix(user.pubkey(), COUNTER_BATCH_ID_START, [1u8; 32], Some(counter_address()));
+    let err = send(&mut context, &[stale], &[&user]).await.unwrap_err();
+    assert_eq!(err, custom(TeeBridgeError::NotCurrentBatch));
+    let current = commit_ix(user.pubkey(), COUNTER_BATCH_ID_START + 1, [1u8; 32], Some(counter_address()));
+    send(&mut context, &[current], &[&user]).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_batch_counter_requires_upgrade_authority() {
+    let mut program_test = program_test();
+
+    // A ProgramData account naming the payer as upgrade authority, but not
+    // the one this program points at (a native processor has none)
+    let payer = Keypair::new();
+    let program_data = Pubkey::new_unique();
+    // UpgradeableLoaderState::ProgramData { slot: 0, upgrade_authority_address: Some(payer) }
+    let mut data = vec![3, 0, 0, 0];
+    data.extend_from_slice(&0u64.to_le_bytes());
+    data.push(1);
+    data.extend_from_slice(payer.pubkey().as_ref());
+    program_test.add_account(
+        program_data,
+        Account { lamports: DENOMINATION_1_SOL, data, owner: bpf_loader_upgradeable::ID, ..Account::default() },
+    );
+    program_test.add_account(payer.pubkey(), Account::new(DENOMINATION_1_SOL, 0, &system_program::ID));
+
+    let mut context = program_test.start_with_context().await;
+    let ix = Instruction {
+        program_id: tee_bridge::ID,
+        accounts: tee_bridge::accounts::InitializeBatchCounter {
+            authority: payer.pubkey(),
+            program: tee_bridge::ID,
+            program_data,
+            batch_counter: counter_address(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: tee_bridge::instruction::InitializeBatchCounter {}.data(),
+    };
+
+    let err = send(&mut context, &[ix], &[&payer]).await.unwrap_err();
+    assert_eq!(
+        err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(anchor_lang::error::ErrorCode::ConstraintRaw.into())
+        )
+    );
+    assert!(context.banks_client.get_account(counter_address()).await.unwrap().is_none());
+}
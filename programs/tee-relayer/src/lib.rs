@@ -20,6 +20,7 @@
 //! - Anonymity: Operator sees only "a withdrawal happened", not details
 
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
 
 declare_id!("8BzTaoLzgaeY6TuV8LcQyNHt8RKukPSf9ijUtUbPD6X1");
 
@@ -32,6 +33,18 @@ pub const PROCESSED_SEED: &[u8] = b"processed";
 /// Contains: nonce (12) + recipient (32) + nullifier_hash (32) + denomination (8) + tag (16) = 100 bytes
 pub const MAX_ENCRYPTED_REQUEST_SIZE: usize = 128;
 
+/// Seed identifying a request PDA after `REQUEST_SEED`
+///
+/// Without a nonce this is the request id, as before. With one it is
+/// `hash(requester || nonce)`, so a retried submission lands on the same
+/// account. The lengths differ (8 vs 32 bytes), so the two never collide.
+pub fn request_seed(request_id: u64, requester: &Pubkey, request_nonce: Option<&[u8; 16]>) -> Vec<u8> {
+    match request_nonce {
+        Some(nonce) => hashv(&[requester.as_ref(), nonce]).to_bytes().to_vec(),
+        None => request_id.to_le_bytes().to_vec(),
+    }
+}
+
 #[error_code]
 pub enum TeeRelayerError {
     #[msg("Unauthorized - not the relayer authority")]
@@ -50,6 +63,39 @@ pub enum TeeRelayerError {
     ArithmeticOverflow,
     #[msg("Relayer not active")]
     RelayerNotActive,
+    #[msg("Request expired - pending longer than the relayer allows")]
+    RequestExpired,
+    #[msg("Request has not expired yet")]
+    RequestNotExpired,
+    #[msg("Request was encrypted to a rotated TEE key")]
+    StaleTeeKey,
+    #[msg("Request was encrypted to the current TEE key")]
+    TeeKeyNotStale,
+    #[msg("Request account doesn't hold the given request id")]
+    RequestIdMismatch,
+    #[msg("Signer is not the pending authority")]
+    NotPendingAuthority,
+}
+
+/// Move `amount` lamports out of a program-owned account with checked math
+/// on both sides, so neither balance can wrap
+fn safe_transfer_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    if from.key == to.key {
+        return Ok(());
+    }
+
+    let from_balance = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(TeeRelayerError::ArithmeticOverflow)?;
+    let to_balance = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(TeeRelayerError::ArithmeticOverflow)?;
+
+    **from.try_borrow_mut_lamports()? = from_balance;
+    **to.try_borrow_mut_lamports()? = to_balance;
+    Ok(())
 }
 
 #[program]
@@ -58,27 +104,36 @@ pub mod tee_relayer {
 
     /// Initialize the TEE relayer state
     /// Only called once by the relayer operator
-    pub fn initialize(ctx: Context<Initialize>, fee_bps: u16) -> Result<()> {
+    pub fn initialize(ctx: Context<Initialize>, fee_bps: u16, max_pending_seconds: i64) -> Result<()> {
         let state = &mut ctx.accounts.relayer_state;
         state.authority = ctx.accounts.authority.key();
         state.fee_bps = fee_bps;
+        state.max_pending_seconds = max_pending_seconds;
         state.total_processed = 0;
         state.total_fees_collected = 0;
         state.request_counter = 0;
         state.is_active = true;
+        state.pending_authority = Pubkey::default();
         state.bump = ctx.bumps.relayer_state;
 
         // TEE public key for encryption (in production, derived from TEE attestation)
         // For demo, we use a placeholder that would be replaced by actual TEE key
         state.tee_pubkey = [0u8; 32]; // Will be set by TEE on startup
+        state.tee_pubkey_version = 0;
 
         msg!("TEE Relayer initialized");
         msg!("Fee: {} bps", fee_bps);
+        msg!("Max pending: {} seconds", max_pending_seconds);
         Ok(())
     }
 
     /// Set TEE public key (authority only)
     /// In production, this would be verified via TEE attestation
+    ///
+    /// A new key bumps `tee_pubkey_version`. Pending requests encrypted to
+    /// the old key can no longer be decrypted: `process_withdrawal` rejects
+    /// them and anyone can fail them with `fail_stale_request`. Clients
+    /// watch for `TeePubkeyRotated` and re-encrypt.
     pub fn set_tee_pubkey(ctx: Context<UpdateSettings>, tee_pubkey: [u8; 32]) -> Result<()> {
         let state = &mut ctx.accounts.relayer_state;
 
@@ -87,8 +142,23 @@ pub mod tee_relayer {
             TeeRelayerError::Unauthorized
         );
 
+        if state.tee_pubkey == tee_pubkey {
+            msg!("TEE public key unchanged (version {})", state.tee_pubkey_version);
+            return Ok(());
+        }
+
+        let old_version = state.tee_pubkey_version;
         state.tee_pubkey = tee_pubkey;
-        msg!("TEE public key updated");
+        state.tee_pubkey_version = old_version
+            .checked_add(1)
+            .ok_or(TeeRelayerError::ArithmeticOverflow)?;
+
+        emit!(TeePubkeyRotated {
+            old_version,
+            new_version: state.tee_pubkey_version,
+        });
+
+        msg!("TEE public key updated to version {}", state.tee_pubkey_version);
         Ok(())
     }
 
@@ -98,9 +168,21 @@ pub mod tee_relayer {
     /// can read the contents (recipient address, nullifier, denomination).
     ///
     /// Each request gets its own PDA, identified by a counter.
+    ///
+    /// ## Retries
+    ///
+    /// A client that can't tell whether its transaction landed passes a
+    /// random `request_nonce` and reuses it on retry. The PDA is then keyed
+    /// by (requester, nonce) instead of the counter, so a retry finds the
+    /// existing request and returns without creating a second one; either
+    /// way `RequestSubmittedEvent` carries the request id. The dedupe window
+    /// lasts as long as the request account: processed and failed requests
+    /// keep deduping, but once an expired request is closed by
+    /// `expire_request` the same nonce submits a new request.
     pub fn submit_encrypted_request(
         ctx: Context<SubmitRequest>,
         encrypted_request: Vec<u8>,
+        request_nonce: Option<[u8; 16]>,
     ) -> Result<()> {
         require!(
             encrypted_request.len() <= MAX_ENCRYPTED_REQUEST_SIZE,
@@ -111,10 +193,22 @@ pub mod tee_relayer {
         require!(state.is_active, TeeRelayerError::RelayerNotActive);
 
         let request = &mut ctx.accounts.request;
+        if request.is_submitted() {
+            emit!(RequestSubmittedEvent {
+                request_id: request.id,
+                timestamp: request.submitted_at,
+            });
+
+            msg!("Request {} already submitted with this nonce", request.id);
+            return Ok(());
+        }
+
         request.id = state.request_counter;
+        request.request_nonce = request_nonce;
         request.requester = ctx.accounts.requester.key();
         request.status = RequestStatus::Pending;
         request.submitted_at = Clock::get()?.unix_timestamp;
+        request.tee_pubkey_version = state.tee_pubkey_version;
         request.bump = ctx.bumps.request;
 
         // Store encrypted data (only TEE can decrypt)
@@ -166,6 +260,20 @@ pub mod tee_relayer {
             TeeRelayerError::NotPending
         );
 
+        // Stale requests may target a changed pool state - the TEE should
+        // call mark_failed for these instead
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            !request.is_expired(now, state.max_pending_seconds),
+            TeeRelayerError::RequestExpired
+        );
+
+        // Encrypted to a previous TEE key, which the current TEE can't decrypt
+        require!(
+            !request.is_stale_key(state.tee_pubkey_version),
+            TeeRelayerError::StaleTeeKey
+        );
+
         // Calculate fee
         let fee = (denomination as u128)
             .checked_mul(state.fee_bps as u128)
@@ -186,12 +294,11 @@ pub mod tee_relayer {
             TeeRelayerError::InsufficientBalance
         );
 
-        **relayer_info.try_borrow_mut_lamports()? -= withdrawal_amount;
-        **recipient_info.try_borrow_mut_lamports()? += withdrawal_amount;
+        safe_transfer_lamports(&relayer_info, &recipient_info, withdrawal_amount)?;
 
         // Mark request as processed
         request.status = RequestStatus::Processed;
-        request.processed_at = Clock::get()?.unix_timestamp;
+        request.processed_at = now;
 
         // Update state
         let state = &mut ctx.accounts.relayer_state;
@@ -238,6 +345,55 @@ pub mod tee_relayer {
         Ok(())
     }
 
+    /// Fail a pending request encrypted to a rotated TEE key (permissionless)
+    ///
+    /// The current TEE can't decrypt it, so it would otherwise sit pending
+    /// until it expires. Failing it tells the requester to re-encrypt.
+    pub fn fail_stale_request(ctx: Context<FailStaleRequest>, request_id: u64) -> Result<()> {
+        let state = &ctx.accounts.relayer_state;
+        let request = &mut ctx.accounts.request;
+
+        require!(
+            request.status == RequestStatus::Pending,
+            TeeRelayerError::NotPending
+        );
+        require!(
+            request.is_stale_key(state.tee_pubkey_version),
+            TeeRelayerError::TeeKeyNotStale
+        );
+
+        request.status = RequestStatus::Failed;
+        request.processed_at = Clock::get()?.unix_timestamp;
+
+        msg!(
+            "Request {} failed: encrypted to TEE key version {}, current is {}",
+            request_id,
+            request.tee_pubkey_version,
+            state.tee_pubkey_version
+        );
+        Ok(())
+    }
+
+    /// Close an expired pending request (permissionless)
+    ///
+    /// Refunds the request's rent to the original requester.
+    pub fn expire_request(ctx: Context<ExpireRequest>, request_id: u64) -> Result<()> {
+        let state = &ctx.accounts.relayer_state;
+        let request = &ctx.accounts.request;
+
+        require!(
+            request.status == RequestStatus::Pending,
+            TeeRelayerError::NotPending
+        );
+        require!(
+            request.is_expired(Clock::get()?.unix_timestamp, state.max_pending_seconds),
+            TeeRelayerError::RequestNotExpired
+        );
+
+        msg!("Expired request {} closed, rent refunded", request_id);
+        Ok(())
+    }
+
     /// Withdraw accumulated fees (authority only)
     pub fn withdraw_fees(ctx: Context<WithdrawFees>, amount: u64) -> Result<()> {
         let state = &mut ctx.accounts.relayer_state;
@@ -255,8 +411,7 @@ pub mod tee_relayer {
             TeeRelayerError::InsufficientBalance
         );
 
-        **state_info.try_borrow_mut_lamports()? -= amount;
-        **authority_info.try_borrow_mut_lamports()? += amount;
+        safe_transfer_lamports(&state_info, &authority_info, amount)?;
 
         msg!("Withdrew {} lamports in fees", amount);
         Ok(())
@@ -287,6 +442,40 @@ pub mod tee_relayer {
 
         Ok(())
     }
+
+    /// Propose a new relayer authority (authority only)
+    ///
+    /// Nothing changes until the proposed key signs `accept_authority`, so a
+    /// mistyped key never gains control. Proposing `Pubkey::default()`
+    /// withdraws an outstanding proposal.
+    pub fn propose_authority(ctx: Context<UpdateSettings>, new_authority: Pubkey) -> Result<()> {
+        let state = &mut ctx.accounts.relayer_state;
+
+        require!(
+            ctx.accounts.authority.key() == state.authority,
+            TeeRelayerError::Unauthorized
+        );
+
+        state.pending_authority = new_authority;
+
+        msg!("Relayer authority proposed: {}", new_authority);
+        Ok(())
+    }
+
+    /// Take over the relayer authority as its pending authority
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        let new_authority = ctx.accounts.new_authority.key();
+        let state = &mut ctx.accounts.relayer_state;
+        let old_authority = state.accept_authority(&new_authority)?;
+
+        emit!(AuthorityTransferred {
+            old_authority,
+            new_authority,
+        });
+
+        msg!("Relayer authority transferred to {}", new_authority);
+        Ok(())
+    }
 }
 
 // ============================================
@@ -311,6 +500,7 @@ pub struct Initialize<'info> {
 }
 
 #[derive(Accounts)]
+#[instruction(encrypted_request: Vec<u8>, request_nonce: Option<[u8; 16]>)]
 pub struct SubmitRequest<'info> {
     #[account(mut)]
     pub requester: Signer<'info>,
@@ -322,11 +512,15 @@ pub struct SubmitRequest<'info> {
     )]
     pub relayer_state: Account<'info, RelayerState>,
 
+    /// `init_if_needed` so a retry with the same nonce finds its request
     #[account(
-        init,
+        init_if_needed,
         payer = requester,
         space = 8 + EncryptedRequest::INIT_SPACE,
-        seeds = [REQUEST_SEED, &relayer_state.request_counter.to_le_bytes()],
+        seeds = [
+            REQUEST_SEED,
+            &request_seed(relayer_state.request_counter, &requester.key(), request_nonce.as_ref()),
+        ],
         bump,
     )]
     pub request: Account<'info, EncryptedRequest>,
@@ -349,8 +543,9 @@ pub struct ProcessWithdrawal<'info> {
 
     #[account(
         mut,
-        seeds = [REQUEST_SEED, &request_id.to_le_bytes()],
+        seeds = [REQUEST_SEED, &request.seed()],
         bump = request.bump,
+        constraint = request.id == request_id @ TeeRelayerError::RequestIdMismatch,
     )]
     pub request: Account<'info, EncryptedRequest>,
 
@@ -384,12 +579,61 @@ pub struct MarkFailed<'info> {
 
     #[account(
         mut,
-        seeds = [REQUEST_SEED, &request_id.to_le_bytes()],
+        seeds = [REQUEST_SEED, &request.seed()],
+        bump = request.bump,
+        constraint = request.id == request_id @ TeeRelayerError::RequestIdMismatch,
+    )]
+    pub request: Account<'info, EncryptedRequest>,
+}
+
+#[derive(Accounts)]
+#[instruction(request_id: u64)]
+pub struct FailStaleRequest<'info> {
+    /// Anyone can fail requests encrypted to a rotated key
+    pub caller: Signer<'info>,
+
+    #[account(
+        seeds = [RELAYER_STATE_SEED],
+        bump = relayer_state.bump,
+    )]
+    pub relayer_state: Account<'info, RelayerState>,
+
+    #[account(
+        mut,
+        seeds = [REQUEST_SEED, &request.seed()],
         bump = request.bump,
+        constraint = request.id == request_id @ TeeRelayerError::RequestIdMismatch,
     )]
     pub request: Account<'info, EncryptedRequest>,
 }
 
+#[derive(Accounts)]
+#[instruction(request_id: u64)]
+pub struct ExpireRequest<'info> {
+    /// Anyone can clean up expired requests
+    pub caller: Signer<'info>,
+
+    #[account(
+        seeds = [RELAYER_STATE_SEED],
+        bump = relayer_state.bump,
+    )]
+    pub relayer_state: Account<'info, RelayerState>,
+
+    #[account(
+        mut,
+        close = requester,
+        seeds = [REQUEST_SEED, &request.seed()],
+        bump = request.bump,
+        constraint = request.id == request_id @ TeeRelayerError::RequestIdMismatch,
+        has_one = requester,
+    )]
+    pub request: Account<'info, EncryptedRequest>,
+
+    /// CHECK: Original requester, receives the rent refund
+    #[account(mut)]
+    pub requester: AccountInfo<'info>,
+}
+
 #[derive(Accounts)]
 pub struct WithdrawFees<'info> {
     #[account(mut)]
@@ -416,6 +660,19 @@ pub struct UpdateSettings<'info> {
     pub relayer_state: Account<'info, RelayerState>,
 }
 
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    /// The proposed authority
+    pub new_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [RELAYER_STATE_SEED],
+        bump = relayer_state.bump,
+    )]
+    pub relayer_state: Account<'info, RelayerState>,
+}
+
 // ============================================
 // Account Structures
 // ============================================
@@ -438,10 +695,33 @@ pub struct RelayerState {
     pub request_counter: u64,
     /// Whether relayer is accepting requests
     pub is_active: bool,
+    /// How long a request may stay pending before it expires
+    pub max_pending_seconds: i64,
+    /// Bumped each time `tee_pubkey` changes (0 = never set)
+    pub tee_pubkey_version: u32,
+    /// Proposed next authority (`Pubkey::default()` = none)
+    pub pending_authority: Pubkey,
     /// PDA bump
     pub bump: u8,
 }
 
+impl RelayerState {
+    /// Hand the authority to `signer` if it is the pending authority
+    ///
+    /// Clears the proposal and returns the previous authority.
+    pub fn accept_authority(&mut self, signer: &Pubkey) -> Result<Pubkey> {
+        require!(
+            self.pending_authority != Pubkey::default() && self.pending_authority == *signer,
+            TeeRelayerError::NotPendingAuthority
+        );
+
+        let old_authority = self.authority;
+        self.authority = *signer;
+        self.pending_authority = Pubkey::default();
+        Ok(old_authority)
+    }
+}
+
 /// Individual encrypted request (stored in its own PDA)
 #[account]
 #[derive(InitSpace)]
@@ -459,10 +739,36 @@ pub struct EncryptedRequest {
     pub submitted_at: i64,
     /// When processed (0 if not yet)
     pub processed_at: i64,
+    /// TEE key version the request was encrypted against
+    pub tee_pubkey_version: u32,
+    /// Client nonce the PDA is keyed by (`None`: keyed by `id`)
+    pub request_nonce: Option<[u8; 16]>,
     /// PDA bump
     pub bump: u8,
 }
 
+impl EncryptedRequest {
+    /// This request's seed after `REQUEST_SEED`
+    pub fn seed(&self) -> Vec<u8> {
+        request_seed(self.id, &self.requester, self.request_nonce.as_ref())
+    }
+
+    /// Whether a submission has been stored here (a fresh account is all zeros)
+    pub fn is_submitted(&self) -> bool {
+        self.submitted_at != 0
+    }
+
+    /// Whether this request has been pending longer than `max_pending_seconds`
+    pub fn is_expired(&self, now: i64, max_pending_seconds: i64) -> bool {
+        now.saturating_sub(self.submitted_at) > max_pending_seconds
+    }
+
+    /// Whether the TEE key has rotated since this request was encrypted
+    pub fn is_stale_key(&self, current_version: u32) -> bool {
+        self.tee_pubkey_version != current_version
+    }
+}
+
 /// Marker for processed nullifiers (prevents replay)
 #[account]
 #[derive(InitSpace)]
@@ -506,3 +812,62 @@ pub struct WithdrawalProcessedEvent {
     pub fee: u64,
     pub timestamp: i64,
 }
+
+#[event]
+pub struct TeePubkeyRotated {
+    pub old_version: u32,
+    pub new_version: u32,
+}
+
+#[event]
+pub struct AuthorityTransferred {
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn relayer_state(authority: Pubkey) -> RelayerState {
+        RelayerState {
+            authority,
+            tee_pubkey: [0u8; 32],
+            fee_bps: 50,
+            total_processed: 0,
+            total_fees_collected: 0,
+            request_counter: 0,
+            is_active: true,
+            max_pending_seconds: 3600,
+            tee_pubkey_version: 0,
+            pending_authority: Pubkey::default(),
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn test_authority_transfer_requires_pending_signer() {
+        let old = Pubkey::new_unique();
+        let new = Pubkey::new_unique();
+        let mut state = relayer_state(old);
+
+        // Nothing proposed: nobody can accept, not even the default key
+        for signer in [new, Pubkey::default()] {
+            let err = state.accept_authority(&signer).unwrap_err();
+            assert_eq!(err, TeeRelayerError::NotPendingAuthority.into());
+        }
+
+        // Only the proposed key can accept
+        state.pending_authority = new;
+        let err = state.accept_authority(&Pubkey::new_unique()).unwrap_err();
+        assert_eq!(err, TeeRelayerError::NotPendingAuthority.into());
+        assert_eq!(state.authority, old);
+
+        assert_eq!(state.accept_authority(&new).unwrap(), old);
+        assert_eq!(state.authority, new);
+        assert_eq!(state.pending_authority, Pubkey::default());
+
+        // The proposal is spent
+        assert!(state.accept_authority(&new).is_err());
+    }
+}
@@ -239,14 +239,15 @@ function buildWithdrawInstruction(
   // Build accounts (must match PrivateWithdraw struct order)
   const accounts = [
     { pubkey: relayer, isSigner: true, isWritable: true },           // 1. relayer
-    { pubkey: poolPDA, isSigner: false, isWritable: true },          // 2. pool
-    { pubkey: configPDA, isSigner: false, isWritable: false },       // 3. config
-    { pubkey: nullifierPDA, isSigner: false, isWritable: true },     // 4. nullifier
-    { pubkey: stealthAddress, isSigner: false, isWritable: true },   // 5. stealth_address
-    { pubkey: announcementPDA, isSigner: false, isWritable: true },  // 6. announcement
-    { pubkey: relayer, isSigner: false, isWritable: true },          // 7. relayer_fee_recipient (optional - use relayer as recipient)
-    { pubkey: SYSVAR_INSTRUCTIONS_PUBKEY, isSigner: false, isWritable: false }, // 8. instructions_sysvar
-    { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },    // 9. system_program
+    { pubkey: relayer, isSigner: true, isWritable: true },           // 2. fee_payer (relayer pays its own rent)
+    { pubkey: poolPDA, isSigner: false, isWritable: true },          // 3. pool
+    { pubkey: configPDA, isSigner: false, isWritable: false },       // 4. config
+    { pubkey: nullifierPDA, isSigner: false, isWritable: true },     // 5. nullifier
+    { pubkey: stealthAddress, isSigner: false, isWritable: true },   // 6. stealth_address
+    { pubkey: announcementPDA, isSigner: false, isWritable: true },  // 7. announcement
+    { pubkey: relayer, isSigner: false, isWritable: true },          // 8. relayer_fee_recipient (optional - use relayer as recipient)
+    { pubkey: SYSVAR_INSTRUCTIONS_PUBKEY, isSigner: false, isWritable: false }, // 9. instructions_sysvar
+    { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },    // 10. system_program
   ];
 
   return new TransactionInstruction({
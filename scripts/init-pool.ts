@@ -24,6 +24,10 @@ const CONFIG_SEED = Buffer.from('pool_config');
 // 1 SOL denomination
 const DENOMINATION = BigInt(1_000_000_000);
 
+// Commitment scheme: 0 = Poseidon(nullifier, secret),
+// 1 = Poseidon(nullifier, secret, amount, recipient) (what the CLI's notes use)
+const COMMITMENT_SCHEME = Number(process.env.COMMITMENT_SCHEME ?? 1);
+
 // Instruction discriminator for initialize_pool
 // sha256("global:initialize_pool")[:8]
 const INIT_POOL_DISCRIMINATOR = Buffer.from([0x5f, 0xb4, 0x0a, 0xac, 0x54, 0xae, 0xe8, 0x28]);
@@ -95,10 +99,11 @@ async function main() {
     return;
   }
 
-  // Build instruction data: discriminator (8) + denomination (8)
-  const data = Buffer.alloc(16);
+  // Build instruction data: discriminator (8) + denomination (8) + commitment scheme (1)
+  const data = Buffer.alloc(17);
   INIT_POOL_DISCRIMINATOR.copy(data, 0);
   data.writeBigUInt64LE(DENOMINATION, 8);
+  data.writeUInt8(COMMITMENT_SCHEME, 16);
 
   // Build instruction
   const instruction = new TransactionInstruction({
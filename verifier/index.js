@@ -52,8 +52,54 @@ function loadVerifierKeypair() {
   }
 }
 
+/**
+ * Circuit hash bound into every attestation
+ *
+ * Must equal the on-chain `VerificationOracle.vk_hash` (hex, 32 bytes).
+ * Defaults to all zeros in development; production programs reject
+ * attestations bound to the all-zero hash, so it is required there.
+ */
+function loadOracleVkHash() {
+  const hex = (process.env.ORACLE_VK_HASH || '').replace('0x', '');
+  if (!hex) {
+    if (process.env.NODE_ENV === 'production') {
+      console.error('ERROR: ORACLE_VK_HASH environment variable is required in production!');
+      process.exit(1);
+    }
+    return Buffer.alloc(32);
+  }
+  const vkHash = Buffer.from(hex, 'hex');
+  if (vkHash.length !== 32) {
+    console.error('ERROR: ORACLE_VK_HASH must be 32 bytes of hex');
+    process.exit(1);
+  }
+  return vkHash;
+}
+
+/**
+ * Verification key version bound into every attestation
+ *
+ * Must equal the on-chain `VerificationOracle.vk_version`.
+ */
+function loadOracleVkVersion() {
+  const version = parseInt(process.env.ORACLE_VK_VERSION || '1', 10);
+  if (!Number.isInteger(version) || version < 0 || version > 0xffffffff) {
+    console.error('ERROR: ORACLE_VK_VERSION must be a u32');
+    process.exit(1);
+  }
+  return version;
+}
+
+const ATTESTATION_DOMAIN = Buffer.from('nocturne_oracle_v2');
+// OracleAttestation::METHOD_ULTRA_HONK - this service checks Noir proofs
+const ATTESTATION_METHOD = 2;
+const oracleVkHash = loadOracleVkHash();
+const oracleVkVersion = loadOracleVkVersion();
+
 const verifierKeypair = loadVerifierKeypair();
 console.log('Verifier public key:', verifierKeypair.publicKey.toBase58());
+console.log('Oracle vk_hash:', oracleVkHash.toString('hex'));
+console.log('Oracle vk_version:', oracleVkVersion);
 console.log('Mode:', process.env.NODE_ENV || 'development');
 
 // Cache for loaded circuits
@@ -99,11 +145,17 @@ function computeHash(data) {
  * Sign attestation message
  */
 function signAttestation(proofHash, publicInputsHash, timestamp) {
-  // Message format: proof_hash (32) || public_inputs_hash (32) || timestamp (8 LE)
-  const message = Buffer.alloc(72);
-  proofHash.copy(message, 0);
-  publicInputsHash.copy(message, 32);
-  message.writeBigInt64LE(BigInt(timestamp), 64);
+  // Message format: "nocturne_oracle_v2" (18) || vk_hash (32) ||
+  //   proof_hash (32) || public_inputs_hash (32) || timestamp (8 LE) ||
+  //   vk_version (4 LE) || method (1)
+  const message = Buffer.alloc(ATTESTATION_DOMAIN.length + 32 + 32 + 32 + 8 + 4 + 1);
+  let offset = ATTESTATION_DOMAIN.copy(message, 0);
+  offset += oracleVkHash.copy(message, offset);
+  offset += proofHash.copy(message, offset);
+  offset += publicInputsHash.copy(message, offset);
+  offset = message.writeBigInt64LE(BigInt(timestamp), offset);
+  offset = message.writeUInt32LE(oracleVkVersion, offset);
+  message.writeUInt8(ATTESTATION_METHOD, offset);
 
   // Sign with Ed25519
   const signature = nacl.sign.detached(message, verifierKeypair.secretKey);
@@ -174,6 +226,9 @@ app.post('/verify/withdraw', async (req, res) => {
       verifier: verifierKeypair.publicKey.toBytes(),
       signature: Array.from(signature),
       verifiedAt: timestamp,
+      vkHash: oracleVkHash.toString('hex'),
+      vkVersion: oracleVkVersion,
+      method: ATTESTATION_METHOD,
     };
 
     res.json({
@@ -236,6 +291,9 @@ app.post('/verify/deposit', async (req, res) => {
         verifier: Array.from(verifierKeypair.publicKey.toBytes()),
         signature: Array.from(signature),
         verifiedAt: timestamp,
+        vkHash: oracleVkHash.toString('hex'),
+        vkVersion: oracleVkVersion,
+        method: ATTESTATION_METHOD,
       },
       verifierPubkey: verifierKeypair.publicKey.toBase58(),
     });
@@ -322,19 +380,25 @@ app.post('/verify/range-proof', async (req, res) => {
     // Define amount range (0 to 2^64 - 1)
     const amountRange = [0n, BigInt('18446744073709551615')];
 
-    // Sign attestation
+    // Bound to the deposit's range_proof_hash so the attestation can't be
+    // reused for a different proof
+    const rangeProofHash = computeHash(rangeProofBytes);
+
+    // Sign attestation: commitment_hash || range || range_proof_hash || verified_at
     const timestamp = Math.floor(Date.now() / 1000);
-    const message = Buffer.alloc(32 + 16 + 8);
+    const message = Buffer.alloc(32 + 16 + 32 + 8);
     commitmentHash.copy(message, 0);
     message.writeBigUInt64LE(amountRange[0], 32);
     message.writeBigUInt64LE(amountRange[1], 40);
-    message.writeBigInt64LE(BigInt(timestamp), 48);
+    rangeProofHash.copy(message, 48);
+    message.writeBigInt64LE(BigInt(timestamp), 80);
 
     const signature = nacl.sign.detached(message, verifierKeypair.secretKey);
 
     // Return attestation
     const attestation = {
       commitmentHash: commitmentHash.toString('hex'),
+      rangeProofHash: rangeProofHash.toString('hex'),
       amountRange: [amountRange[0].toString(), amountRange[1].toString()],
       signature: Array.from(signature),
       verifier: Array.from(verifierKeypair.publicKey.toBytes()),
@@ -41,6 +41,33 @@ const THRESHOLD_PEERS = (process.env.THRESHOLD_PEERS || '').split(',').filter(p
 const REQUEST_TIMEOUT_MS = 10000;
 const ATTESTATION_VALIDITY_SECS = 300; // 5 minutes
 
+// Signed ahead of every attestation; see programs/stealth/src/zk/verifier.rs
+const ATTESTATION_DOMAIN = Buffer.from('nocturne_oracle_v2');
+
+// OracleAttestation::METHOD_* - what the verifier actually did with the proof
+const METHOD_DEV_SKIP = 0;
+const METHOD_GROTH16 = 1;
+
+// Must equal the on-chain VerificationOracle.vk_hash (all zeros only in
+// development; production programs reject the all-zero hash)
+const ORACLE_VK_HASH = Buffer.from((process.env.ORACLE_VK_HASH || '').replace('0x', ''), 'hex');
+if (ORACLE_VK_HASH.length !== 0 && ORACLE_VK_HASH.length !== 32) {
+  console.error('ERROR: ORACLE_VK_HASH must be 32 bytes of hex');
+  process.exit(1);
+}
+if (ORACLE_VK_HASH.length === 0 && process.env.NODE_ENV === 'production') {
+  console.error('ERROR: ORACLE_VK_HASH environment variable required in production!');
+  process.exit(1);
+}
+const oracleVkHash = ORACLE_VK_HASH.length === 32 ? ORACLE_VK_HASH : Buffer.alloc(32);
+
+// Must equal the on-chain VerificationOracle.vk_version
+const ORACLE_VK_VERSION = parseInt(process.env.ORACLE_VK_VERSION || '1', 10);
+if (!Number.isInteger(ORACLE_VK_VERSION) || ORACLE_VK_VERSION < 0 || ORACLE_VK_VERSION > 0xffffffff) {
+  console.error('ERROR: ORACLE_VK_VERSION must be a u32');
+  process.exit(1);
+}
+
 // ============================================
 // Verifier Keypair
 // ============================================
@@ -113,22 +140,30 @@ function computeHash(data) {
   return createHash('sha256').update(data).digest();
 }
 
-function createAttestationMessage(proofHash, publicInputsHash, timestamp) {
-  const message = Buffer.alloc(72);
-  proofHash.copy(message, 0);
-  publicInputsHash.copy(message, 32);
-  message.writeBigInt64LE(BigInt(timestamp), 64);
+// "nocturne_oracle_v2" (18) || vk_hash (32) || proof_hash (32) ||
+//   public_inputs_hash (32) || timestamp (8 LE) || vk_version (4 LE) || method (1)
+function createAttestationMessage(proofHash, publicInputsHash, timestamp, method) {
+  const message = Buffer.alloc(ATTESTATION_DOMAIN.length + 32 + 32 + 32 + 8 + 4 + 1);
+  let offset = ATTESTATION_DOMAIN.copy(message, 0);
+  offset += oracleVkHash.copy(message, offset);
+  offset += proofHash.copy(message, offset);
+  offset += publicInputsHash.copy(message, offset);
+  offset = message.writeBigInt64LE(BigInt(timestamp), offset);
+  offset = message.writeUInt32LE(ORACLE_VK_VERSION, offset);
+  message.writeUInt8(method, offset);
   return message;
 }
 
-function signAttestation(proofHash, publicInputsHash, timestamp) {
-  const message = createAttestationMessage(proofHash, publicInputsHash, timestamp);
+function signAttestation(proofHash, publicInputsHash, timestamp, method) {
+  const message = createAttestationMessage(proofHash, publicInputsHash, timestamp, method);
   const signature = nacl.sign.detached(message, verifierKeypair.secretKey);
   return Buffer.from(signature);
 }
 
-function verifyPartialSignature(proofHash, publicInputsHash, timestamp, signature, verifierPubkey) {
-  const message = createAttestationMessage(proofHash, publicInputsHash, timestamp);
+// Peers must have used the same method, so a node that skipped
+// verification can't count towards a Groth16 threshold
+function verifyPartialSignature(proofHash, publicInputsHash, timestamp, method, signature, verifierPubkey) {
+  const message = createAttestationMessage(proofHash, publicInputsHash, timestamp, method);
   return nacl.sign.detached.verify(message, new Uint8Array(signature), new Uint8Array(verifierPubkey));
 }
 
@@ -164,7 +199,8 @@ async function collectThresholdSignatures(proofHash, publicInputsHash, timestamp
   };
 
   // Start with our own signature
-  const localSignature = signAttestation(proofHash, publicInputsHash, timestamp);
+  const method = verificationMethod(circuitType);
+  const localSignature = signAttestation(proofHash, publicInputsHash, timestamp, method);
 
   const partialSignatures = [{
     nodeId: NODE_ID,
@@ -187,6 +223,7 @@ async function collectThresholdSignatures(proofHash, publicInputsHash, timestamp
           proofHash,
           publicInputsHash,
           timestamp,
+          method,
           result.value.signature,
           result.value.verifierPubkey
         );
@@ -211,6 +248,15 @@ async function collectThresholdSignatures(proofHash, publicInputsHash, timestamp
 // Groth16 Verification
 // ============================================
 
+/**
+ * Method this node attests with: Groth16 when it can check proofs for the
+ * circuit, dev-skip when `verifyGroth16Proof` would wave them through
+ */
+function verificationMethod(circuitType) {
+  const vKey = circuitType === 'withdraw' ? withdrawVKey : depositVKey;
+  return snarkjs && vKey ? METHOD_GROTH16 : METHOD_DEV_SKIP;
+}
+
 async function verifyGroth16Proof(proof, publicInputs, circuitType) {
   if (!snarkjs) {
     console.warn(`[${NODE_ID}] snarkjs not loaded, skipping proof verification`);
@@ -351,6 +397,9 @@ app.post('/verify/withdraw', async (req, res) => {
         proofHash: proofHash.toString('hex'),
         publicInputsHash: publicInputsHash.toString('hex'),
         verifiedAt: timestamp,
+        vkHash: oracleVkHash.toString('hex'),
+        vkVersion: ORACLE_VK_VERSION,
+        method: verificationMethod('withdraw'),
         threshold: {
           required: THRESHOLD_REQUIRED,
           collected: partialSignatures.length,
@@ -423,6 +472,9 @@ app.post('/verify/deposit', async (req, res) => {
         proofHash: proofHash.toString('hex'),
         publicInputsHash: publicInputsHash.toString('hex'),
         verifiedAt: timestamp,
+        vkHash: oracleVkHash.toString('hex'),
+        vkVersion: ORACLE_VK_VERSION,
+        method: verificationMethod('deposit'),
         threshold: {
           required: THRESHOLD_REQUIRED,
           collected: partialSignatures.length,
@@ -467,7 +519,7 @@ app.post('/internal/sign', async (req, res) => {
     // Sign the attestation
     const proofHashBuf = Buffer.from(proofHash, 'hex');
     const publicInputsHashBuf = Buffer.from(publicInputsHash, 'hex');
-    const signature = signAttestation(proofHashBuf, publicInputsHashBuf, timestamp);
+    const signature = signAttestation(proofHashBuf, publicInputsHashBuf, timestamp, verificationMethod(circuitType));
 
     console.log(`[${NODE_ID}] Providing partial signature`);
 